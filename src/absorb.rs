@@ -268,6 +268,7 @@ fn apply_plan(repo: &Repository, workdir: &Path, git_dir: &Path, plan: AbsorbPla
     };
     let state = LoomState {
         command: "absorb".to_string(),
+        refs_before: Default::default(),
         rollback: Rollback {
             // reset_hard_to undoes the fixup commits created before the rebase.
             // git rebase --abort restores HEAD to after the fixup commits, not to
@@ -281,7 +282,7 @@ fn apply_plan(repo: &Repository, workdir: &Path, git_dir: &Path, plan: AbsorbPla
     };
     transaction::save(git_dir, &state)?;
 
-    let todo = graph.to_todo();
+    let todo = graph.checked_todo()?;
     match weave::run_rebase(workdir, Some(&graph.base_oid.to_string()), &todo)? {
         RebaseOutcome::Completed => {
             transaction::delete(git_dir)?;
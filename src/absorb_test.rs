@@ -495,6 +495,7 @@ fn absorb_abort_preserves_working_state() {
     // Inject LoomState as absorb would save it before the rebase.
     let state = crate::core::transaction::LoomState {
         command: "absorb".to_string(),
+        refs_before: Default::default(),
         rollback: crate::core::transaction::Rollback {
             reset_hard_to: pre_absorb_oid.to_string(),
             saved_staged_patch: saved_staged,
@@ -0,0 +1,203 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use colored::Colorize;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+use crate::core::{msg, repo};
+
+/// The log is trimmed back to this many entries once it grows past twice
+/// that size.
+const MAX_ENTRIES: usize = 1000;
+
+/// One loom invocation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Local start time (RFC 3339).
+    pub time: String,
+    /// Subcommand name, with aliases resolved (`status` when none was given).
+    pub command: String,
+    pub duration_ms: u64,
+    pub success: bool,
+}
+
+/// Usage of one command over the whole log.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CommandStats {
+    pub command: String,
+    pub runs: usize,
+    pub failures: usize,
+    pub average_ms: u64,
+    pub total_ms: u64,
+}
+
+/// Summarize the local activity log: how often each command ran, how long
+/// it took on average, and how often it failed.
+///
+/// With `clear`, delete the log instead.
+pub fn run(clear: bool) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let path = log_path(repo.path());
+    if clear {
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to delete '{}'", path.display()))?;
+        }
+        msg::success("Cleared the activity log");
+        return Ok(());
+    }
+
+    let entries = load(repo.path());
+    let Some(first) = entries.first() else {
+        msg::warn("No activity recorded yet");
+        return Ok(());
+    };
+    let since = DateTime::parse_from_rfc3339(&first.time)
+        .map(|t| t.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|_| first.time.clone());
+    println!(
+        "{} {}",
+        "Activity since".bold(),
+        format!(
+            "{} ({} {})",
+            since,
+            entries.len(),
+            plural(entries.len(), "run")
+        )
+        .dimmed()
+    );
+
+    let stats = summarize(&entries);
+    let width = stats.iter().map(|s| s.command.len()).max().unwrap_or(0);
+    for s in &stats {
+        let failures = if s.failures > 0 {
+            format!("{} failed", s.failures).red().to_string()
+        } else {
+            String::new()
+        };
+        println!(
+            "  {}  {:>5} {:<4}  {:>8} avg  {}",
+            format!("{:<width$}", s.command, width = width).green(),
+            s.runs,
+            plural(s.runs, "run"),
+            format_duration(s.average_ms),
+            failures
+        );
+    }
+    Ok(())
+}
+
+/// Append an entry for the command that just ran. Best effort: the log
+/// must never make a command fail. Disabled with `loom.activity = false`.
+pub fn record(
+    repo: &Repository,
+    command: &str,
+    started: DateTime<Local>,
+    duration: Duration,
+    success: bool,
+) {
+    let enabled = repo::config_bool(repo, "loom.activity").unwrap_or(true);
+    if !enabled {
+        return;
+    }
+    let entry = Entry {
+        time: started.to_rfc3339(),
+        command: command.to_string(),
+        duration_ms: duration.as_millis() as u64,
+        success,
+    };
+    let _ = append(repo.path(), &entry);
+}
+
+/// Append `entry` to the log in `git_dir`, trimming old entries when the
+/// log gets too long.
+pub fn append(git_dir: &Path, entry: &Entry) -> Result<()> {
+    let path = log_path(git_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    drop(file);
+
+    let entries = load(git_dir);
+    if entries.len() > 2 * MAX_ENTRIES {
+        let kept: Vec<String> = entries[entries.len() - MAX_ENTRIES..]
+            .iter()
+            .filter_map(|e| serde_json::to_string(e).ok())
+            .collect();
+        std::fs::write(&path, kept.join("\n") + "\n")?;
+    }
+    Ok(())
+}
+
+/// All readable entries of the log in `git_dir`, oldest first. Lines that
+/// fail to parse are skipped.
+pub fn load(git_dir: &Path) -> Vec<Entry> {
+    std::fs::read_to_string(log_path(git_dir))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Per-command statistics, most used first (ties by name).
+pub fn summarize(entries: &[Entry]) -> Vec<CommandStats> {
+    let mut stats: Vec<CommandStats> = Vec::new();
+    for entry in entries {
+        let s = match stats.iter_mut().find(|s| s.command == entry.command) {
+            Some(s) => s,
+            None => {
+                stats.push(CommandStats {
+                    command: entry.command.clone(),
+                    runs: 0,
+                    failures: 0,
+                    average_ms: 0,
+                    total_ms: 0,
+                });
+                stats.last_mut().unwrap()
+            }
+        };
+        s.runs += 1;
+        s.total_ms += entry.duration_ms;
+        if !entry.success {
+            s.failures += 1;
+        }
+    }
+    for s in &mut stats {
+        s.average_ms = s.total_ms / s.runs as u64;
+    }
+    stats.sort_by(|a, b| b.runs.cmp(&a.runs).then_with(|| a.command.cmp(&b.command)));
+    stats
+}
+
+pub(crate) fn log_path(git_dir: &Path) -> PathBuf {
+    git_dir.join("loom").join("activity.jsonl")
+}
+
+fn format_duration(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    }
+}
+
+fn plural(n: usize, word: &str) -> String {
+    if n == 1 {
+        word.to_string()
+    } else {
+        format!("{}s", word)
+    }
+}
+
+#[cfg(test)]
+#[path = "activity_test.rs"]
+mod tests;
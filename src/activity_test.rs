@@ -0,0 +1,105 @@
+use super::{CommandStats, Entry};
+
+fn entry(command: &str, duration_ms: u64, success: bool) -> Entry {
+    Entry {
+        time: "2026-03-04T14:30:00+01:00".to_string(),
+        command: command.to_string(),
+        duration_ms,
+        success,
+    }
+}
+
+#[test]
+fn append_and_load_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    super::append(dir.path(), &entry("fold", 120, true)).unwrap();
+    super::append(dir.path(), &entry("update", 3400, false)).unwrap();
+
+    assert_eq!(
+        super::load(dir.path()),
+        vec![entry("fold", 120, true), entry("update", 3400, false)]
+    );
+}
+
+#[test]
+fn load_skips_unreadable_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    super::append(dir.path(), &entry("fold", 120, true)).unwrap();
+    let path = dir.path().join("loom").join("activity.jsonl");
+    let mut content = std::fs::read_to_string(&path).unwrap();
+    content.push_str("not json\n");
+    std::fs::write(&path, content).unwrap();
+
+    assert_eq!(super::load(dir.path()), vec![entry("fold", 120, true)]);
+}
+
+#[test]
+fn append_trims_old_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("loom").join("activity.jsonl");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let lines: String = (0..2 * super::MAX_ENTRIES)
+        .map(|i| serde_json::to_string(&entry("status", i as u64, true)).unwrap() + "\n")
+        .collect();
+    std::fs::write(&path, lines).unwrap();
+
+    super::append(dir.path(), &entry("fold", 5, true)).unwrap();
+
+    let entries = super::load(dir.path());
+    assert_eq!(entries.len(), super::MAX_ENTRIES);
+    assert_eq!(entries.last().unwrap(), &entry("fold", 5, true));
+}
+
+#[test]
+fn summarize_sorts_by_usage_and_averages_durations() {
+    let entries = vec![
+        entry("update", 3000, true),
+        entry("status", 40, true),
+        entry("update", 5000, false),
+        entry("fold", 200, true),
+        entry("status", 60, true),
+    ];
+
+    assert_eq!(
+        super::summarize(&entries),
+        vec![
+            CommandStats {
+                command: "status".to_string(),
+                runs: 2,
+                failures: 0,
+                average_ms: 50,
+                total_ms: 100,
+            },
+            CommandStats {
+                command: "update".to_string(),
+                runs: 2,
+                failures: 1,
+                average_ms: 4000,
+                total_ms: 8000,
+            },
+            CommandStats {
+                command: "fold".to_string(),
+                runs: 1,
+                failures: 0,
+                average_ms: 200,
+                total_ms: 200,
+            },
+        ]
+    );
+}
+
+#[test]
+fn record_respects_loom_activity_setting() {
+    let test_repo = crate::core::test_helpers::TestRepo::new();
+    let now = chrono::Local::now();
+    let elapsed = std::time::Duration::from_millis(15);
+
+    super::record(&test_repo.repo, "status", now, elapsed, true);
+    test_repo.set_config("loom.activity", "false");
+    super::record(&test_repo.repo, "fold", now, elapsed, true);
+
+    let entries = super::load(test_repo.repo.path());
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].command, "status");
+    assert_eq!(entries[0].duration_ms, 15);
+}
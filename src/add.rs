@@ -17,7 +17,7 @@ pub fn run(files: Vec<String>, patch: bool, theme: &graph::Theme) -> Result<()>
 
     // `zz` stages everything, regardless of other args.
     if files.iter().any(|f| f == "zz") {
-        git::stage_all(&workdir)?;
+        git::stage_all(&workdir, &repo::ignore_path_patterns(&repo))?;
         msg::success("Staged all changes");
         return Ok(());
     }
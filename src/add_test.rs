@@ -152,6 +152,23 @@ fn add_zz_stages_all() {
     );
 }
 
+/// `zz` leaves files matching `loom.ignorePaths` unstaged.
+#[test]
+fn add_zz_skips_ignored_paths() {
+    let test_repo = TestRepo::new();
+    test_repo.set_config("loom.ignorePaths", "*.local");
+
+    test_repo.write_file("one.txt", "1");
+    test_repo.write_file("settings.local", "mine");
+
+    let result = test_repo.in_dir(|| run_add(vec!["zz".to_string()]));
+
+    assert!(result.is_ok(), "add zz failed: {:?}", result);
+    let status = test_repo.status_porcelain();
+    assert!(is_staged(&status, "one.txt"), "status: {}", status);
+    assert!(status.contains("?? settings.local"), "status: {}", status);
+}
+
 /// Negative test: nonexistent file returns an error.
 #[test]
 fn add_nonexistent_file_errors() {
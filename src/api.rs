@@ -0,0 +1,222 @@
+use anyhow::Result;
+use git2::{Oid, Repository};
+use serde_json::{Value, json};
+
+use crate::core::repo::{self, Target, TargetKind};
+use crate::core::shortid::IdAllocator;
+use crate::core::weave::{self, Weave};
+
+/// Version of the JSON schema. Bumped on incompatible changes.
+pub const API_VERSION: u32 = 1;
+
+/// A query answered by `loom api`.
+pub enum Query {
+    /// Resolve a short ID, hash, branch name, or path to a target.
+    Resolve(String),
+    /// List branch sections, loose commits, and working changes.
+    Sections,
+    /// Compute the rebase todo for a hypothetical operation, without running it.
+    Todo(TodoOp),
+    /// Snapshot HEAD and all local branch refs.
+    Refs,
+}
+
+/// A hypothetical operation for `Query::Todo`.
+pub enum TodoOp {
+    Drop { target: String },
+    Fixup { source: String, target: String },
+    Move { commit: String, branch: String },
+    Swap { a: String, b: String },
+}
+
+/// Answer a query and print the result as JSON on stdout.
+///
+/// Every response is an object carrying `"version"`, so plugins can detect
+/// schema changes. Errors are reported like any other command (stderr and a
+/// non-zero exit code).
+pub fn run(query: Query) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let value = answer(&repo, query)?;
+    println!("{}", serde_json::to_string_pretty(&value)?);
+    Ok(())
+}
+
+/// Answer a query as a JSON object carrying `"version"`.
+pub fn answer(repo: &Repository, query: Query) -> Result<Value> {
+    let mut value = match query {
+        Query::Resolve(arg) => resolve(repo, &arg)?,
+        Query::Sections => sections(repo)?,
+        Query::Todo(op) => todo(repo, op)?,
+        Query::Refs => refs(repo)?,
+    };
+    value["version"] = json!(API_VERSION);
+    Ok(value)
+}
+
+/// Resolve `arg` the same way commands do, accepting any target kind.
+fn resolve(repo: &Repository, arg: &str) -> Result<Value> {
+    let target = repo::resolve_arg(
+        repo,
+        arg,
+        &[
+            TargetKind::Branch,
+            TargetKind::Commit,
+            TargetKind::File,
+            TargetKind::CommitFile,
+            TargetKind::Unstaged,
+        ],
+    )?;
+    Ok(match target {
+        Target::Branch(name) => json!({ "kind": "branch", "name": name }),
+        Target::Commit(oid) => json!({ "kind": "commit", "oid": oid }),
+        Target::File(path) => json!({ "kind": "file", "path": path }),
+        Target::CommitFile { commit, path } => {
+            json!({ "kind": "commit_file", "oid": commit, "path": path })
+        }
+        Target::Unstaged => json!({ "kind": "unstaged" }),
+    })
+}
+
+/// Describe the integration topology: woven branch sections (commits oldest
+/// first), loose commits on the integration line, and working changes, all
+/// annotated with the short IDs shown by `loom status`.
+fn sections(repo: &Repository) -> Result<Value> {
+    let info = repo::gather_repo_info(repo, false, 0)?;
+    let ids = IdAllocator::new(info.collect_entities());
+    let graph = Weave::from_repo_with_info(repo, &info)?;
+
+    let commit_json = |entry: &weave::CommitEntry| {
+        json!({
+            "oid": entry.oid.to_string(),
+            "short_id": ids.get_commit(entry.oid),
+            "message": entry.message,
+        })
+    };
+
+    let sections: Vec<Value> = graph
+        .branch_sections
+        .iter()
+        .map(|section| {
+            let branches: Vec<Value> = section
+                .branch_names
+                .iter()
+                .map(|name| json!({ "name": name, "short_id": ids.get_branch(name) }))
+                .collect();
+            json!({
+                "label": section.label,
+                "base": section.reset_target,
+                "branches": branches,
+                "commits": section.commits.iter().map(&commit_json).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+
+    let loose: Vec<Value> = graph
+        .integration_line
+        .iter()
+        .filter_map(|entry| match entry {
+            weave::IntegrationEntry::Pick(commit) => Some(commit_json(commit)),
+            weave::IntegrationEntry::Merge { .. } => None,
+        })
+        .collect();
+
+    let working_changes: Vec<Value> = info
+        .working_changes
+        .iter()
+        .map(|change| {
+            // Editor integrations know every kind of conflict as `!`
+            let (index, worktree) = if change.is_conflicted() {
+                ('!', '!')
+            } else {
+                (change.index, change.worktree)
+            };
+            json!({
+                "path": change.path,
+                "short_id": ids.get_file(&change.path),
+                "index": index.to_string(),
+                "worktree": worktree.to_string(),
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "branch": info.branch_name,
+        "upstream": {
+            "label": info.upstream.label,
+            "base": info.upstream.merge_base_oid.to_string(),
+            "commits_ahead": info.upstream.commits_ahead,
+        },
+        "sections": sections,
+        "loose": loose,
+        "working_changes": working_changes,
+    }))
+}
+
+/// Apply a hypothetical operation to the weave and return the resulting todo.
+fn todo(repo: &Repository, op: TodoOp) -> Result<Value> {
+    let mut graph = Weave::from_repo(repo)?;
+    match op {
+        TodoOp::Drop { target } => {
+            match repo::resolve_arg(repo, &target, &[TargetKind::Branch, TargetKind::Commit])? {
+                Target::Branch(name) => graph.drop_branch(&name),
+                Target::Commit(hash) => graph.drop_commit(Oid::from_str(&hash)?),
+                _ => unreachable!(),
+            }
+        }
+        TodoOp::Fixup { source, target } => {
+            let source = resolve_commit(repo, &source)?;
+            let target = resolve_commit(repo, &target)?;
+            graph.fixup_commit(source, target)?;
+        }
+        TodoOp::Move { commit, branch } => {
+            let commit = resolve_commit(repo, &commit)?;
+            let branch =
+                repo::resolve_arg(repo, &branch, &[TargetKind::Branch])?.expect_branch()?;
+            graph.move_commit(commit, &branch)?;
+        }
+        TodoOp::Swap { a, b } => {
+            let a = resolve_commit(repo, &a)?;
+            let b = resolve_commit(repo, &b)?;
+            graph.swap_commits(a, b)?;
+        }
+    }
+
+    Ok(json!({
+        "onto": graph.base_oid.to_string(),
+        "todo": graph.to_todo(),
+    }))
+}
+
+fn resolve_commit(repo: &Repository, arg: &str) -> Result<Oid> {
+    match repo::resolve_arg(repo, arg, &[TargetKind::Commit])? {
+        Target::Commit(hash) => Ok(Oid::from_str(&hash)?),
+        _ => unreachable!(),
+    }
+}
+
+/// Snapshot HEAD and every local branch, sorted by name.
+fn refs(repo: &Repository) -> Result<Value> {
+    let head = repo.head()?;
+    let head_branch = if head.is_branch() {
+        head.shorthand().map(str::to_string)
+    } else {
+        None
+    };
+    let head_oid = repo::head_oid(repo)?;
+
+    let mut branches: Vec<_> = repo::snapshot_branch_refs(repo)?.into_iter().collect();
+    branches.sort();
+    let branches: serde_json::Map<String, Value> = branches
+        .into_iter()
+        .map(|(name, oid)| (name, json!(oid.to_string())))
+        .collect();
+
+    Ok(json!({
+        "head": { "branch": head_branch, "oid": head_oid.to_string() },
+        "branches": branches,
+    }))
+}
+
+#[cfg(test)]
+#[path = "api_test.rs"]
+mod tests;
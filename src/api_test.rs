@@ -0,0 +1,138 @@
+use crate::core::test_helpers::TestRepo;
+
+/// Integration branch with a woven `feature-a` (A1, A2) and a loose commit.
+fn setup_woven_branch() -> (TestRepo, git2::Oid, git2::Oid) {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    let a1 = test_repo.commit("A1", "a1.txt");
+    let a2 = test_repo.commit("A2", "a2.txt");
+    test_repo.switch_branch("integration");
+    test_repo.commit("Int", "int.txt");
+    test_repo.merge_no_ff("feature-a");
+    (test_repo, a1, a2)
+}
+
+#[test]
+fn resolve_branch_and_commit() {
+    let (test_repo, a1, _) = setup_woven_branch();
+
+    let branch = test_repo
+        .in_dir(|| super::resolve(&test_repo.repo, "feature-a"))
+        .unwrap();
+    assert_eq!(branch["kind"], "branch");
+    assert_eq!(branch["name"], "feature-a");
+
+    let commit = test_repo
+        .in_dir(|| super::resolve(&test_repo.repo, &a1.to_string()))
+        .unwrap();
+    assert_eq!(commit["kind"], "commit");
+    assert_eq!(commit["oid"], a1.to_string());
+}
+
+#[test]
+fn resolve_unstaged_short_id() {
+    let (test_repo, _, _) = setup_woven_branch();
+    let value = test_repo
+        .in_dir(|| super::resolve(&test_repo.repo, "zz"))
+        .unwrap();
+    assert_eq!(value["kind"], "unstaged");
+}
+
+#[test]
+fn resolve_unknown_fails() {
+    let (test_repo, _, _) = setup_woven_branch();
+    let result = test_repo.in_dir(|| super::resolve(&test_repo.repo, "no-such-thing"));
+    assert!(result.is_err());
+}
+
+#[test]
+fn sections_lists_branches_loose_commits_and_changes() {
+    let (test_repo, _, _) = setup_woven_branch();
+    test_repo.write_file("dirty.txt", "dirty");
+
+    let value = super::sections(&test_repo.repo).unwrap();
+    assert_eq!(value["branch"], "integration");
+    assert_eq!(value["upstream"]["label"], "origin/main");
+
+    let sections = value["sections"].as_array().unwrap();
+    assert_eq!(sections.len(), 1);
+    assert_eq!(sections[0]["branches"][0]["name"], "feature-a");
+    let messages: Vec<_> = sections[0]["commits"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c["message"].as_str().unwrap())
+        .collect();
+    assert_eq!(messages, vec!["A1", "A2"]);
+
+    let loose = value["loose"].as_array().unwrap();
+    assert_eq!(loose.len(), 1);
+    assert_eq!(loose[0]["message"], "Int");
+    assert!(!loose[0]["short_id"].as_str().unwrap().is_empty());
+
+    let changes = value["working_changes"].as_array().unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0]["path"], "dirty.txt");
+    assert_eq!(changes[0]["worktree"], "?");
+}
+
+#[test]
+fn todo_drop_commit_omits_it() {
+    let (test_repo, a1, _) = setup_woven_branch();
+    let before = test_repo.head_oid();
+
+    let value = test_repo
+        .in_dir(|| {
+            super::todo(
+                &test_repo.repo,
+                super::TodoOp::Drop {
+                    target: a1.to_string(),
+                },
+            )
+        })
+        .unwrap();
+    let todo = value["todo"].as_str().unwrap();
+    assert!(!todo.contains(&format!("pick {}", git_short(a1))));
+    assert!(todo.contains("A2"));
+
+    // Nothing was executed
+    assert_eq!(test_repo.head_oid(), before);
+}
+
+#[test]
+fn todo_fixup_marks_source_as_fixup() {
+    let (test_repo, a1, a2) = setup_woven_branch();
+
+    let value = test_repo
+        .in_dir(|| {
+            super::todo(
+                &test_repo.repo,
+                super::TodoOp::Fixup {
+                    source: a2.to_string(),
+                    target: a1.to_string(),
+                },
+            )
+        })
+        .unwrap();
+    let todo = value["todo"].as_str().unwrap();
+    assert!(todo.contains(&format!("fixup {}", git_short(a2))));
+}
+
+#[test]
+fn refs_snapshots_head_and_branches() {
+    let (test_repo, _, _) = setup_woven_branch();
+    let value = super::refs(&test_repo.repo).unwrap();
+
+    assert_eq!(value["head"]["branch"], "integration");
+    assert_eq!(value["head"]["oid"], test_repo.head_oid().to_string());
+    assert_eq!(
+        value["branches"]["feature-a"],
+        test_repo.get_branch_target("feature-a").to_string()
+    );
+}
+
+fn git_short(oid: git2::Oid) -> String {
+    crate::git::short_hash(&oid.to_string()).to_string()
+}
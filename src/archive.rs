@@ -0,0 +1,203 @@
+use anyhow::{Context, Result, bail};
+use git2::Repository;
+
+use crate::core::msg;
+use crate::core::repo::{self, TargetKind};
+use crate::core::weave::{self, IntegrationEntry, Weave};
+use crate::git;
+
+/// Namespace holding archived branches: `refs/loom/archived/<branch>`.
+pub const ARCHIVE_PREFIX: &str = "refs/loom/archived/";
+
+/// Archive, list, or restore branches.
+///
+/// With `list`, prints the archived branches. With `restore`, recreates the
+/// branch from its archive. Otherwise archives `branch`: see [`archive`].
+pub fn run(branch: Option<String>, list: bool, restore: bool) -> Result<()> {
+    let repo = repo::open_repo()?;
+
+    if list {
+        return print_archived(&repo);
+    }
+    let Some(branch) = branch else {
+        bail!("Specify the branch to archive\nRun `loom archive --list` to see archived branches");
+    };
+
+    if restore {
+        let oid = restore_branch(&repo, &branch)?;
+        msg::success(&format!(
+            "Restored `{}` at `{}`\nRun `loom branch merge {}` to weave it again",
+            branch,
+            git::short_hash(&oid.to_string()),
+            branch
+        ));
+        return Ok(());
+    }
+
+    let name = repo::resolve_arg(&repo, &branch, &[TargetKind::Branch])?.expect_branch()?;
+    let unwoven = archive(&repo, &name)?;
+    msg::success(&format!(
+        "Archived `{}`{}",
+        name,
+        if unwoven {
+            " and removed it from the integration branch"
+        } else {
+            ""
+        }
+    ));
+    Ok(())
+}
+
+/// Move local branch `name` to `refs/loom/archived/<name>`. A woven branch
+/// is removed from the integration branch first. Returns whether it was woven.
+///
+/// Only a branch with its own section can be unwoven: a branch with others
+/// stacked on it, or sharing its tip with another, would take their commits
+/// along.
+pub fn archive(repo: &Repository, name: &str) -> Result<bool> {
+    let workdir = repo::require_workdir(repo, "archive a branch")?;
+    let archive_ref = format!("{}{}", ARCHIVE_PREFIX, name);
+    if repo.find_reference(&archive_ref).is_ok() {
+        bail!(
+            "Branch `{}` is already archived\nRestore it with `loom archive --restore {}` first",
+            name,
+            name
+        );
+    }
+    if repo.head()?.shorthand() == Some(name) {
+        bail!("Cannot archive the current branch `{}`", name);
+    }
+    let tip = repo
+        .find_branch(name, git2::BranchType::Local)?
+        .get()
+        .target()
+        .with_context(|| format!("Branch `{}` does not point to a commit", name))?;
+
+    let graph = Weave::from_repo(repo)?;
+    let woven = owns_section(&graph, name)?;
+    let graph = woven.then(|| {
+        let mut graph = graph;
+        graph.drop_branch(name);
+        graph
+    });
+
+    repo.reference(&archive_ref, tip, false, &format!("loom: archive {}", name))?;
+    if let Some(graph) = graph
+        && let Err(e) = weave::run_rebase_or_abort(
+            workdir,
+            Some(&graph.base_oid.to_string()),
+            &graph.checked_todo()?,
+        )
+    {
+        let _ = repo
+            .find_reference(&archive_ref)
+            .and_then(|mut r| r.delete());
+        return Err(e);
+    }
+    git::branch_delete(workdir, name)?;
+    Ok(woven)
+}
+
+/// Recreate local branch `name` from its archive and drop the archive ref.
+/// Returns the branch tip.
+pub fn restore_branch(repo: &Repository, name: &str) -> Result<git2::Oid> {
+    let archive_ref = format!("{}{}", ARCHIVE_PREFIX, name);
+    let Ok(mut reference) = repo.find_reference(&archive_ref) else {
+        bail!(
+            "Branch `{}` is not archived\nRun `loom archive --list` to see archived branches",
+            name
+        );
+    };
+    let oid = reference
+        .target()
+        .with_context(|| format!("`{}` does not point to a commit", archive_ref))?;
+    repo::ensure_branch_not_exists(repo, name)?;
+    repo.branch(name, &repo.find_commit(oid)?, false)?;
+    reference.delete()?;
+    Ok(oid)
+}
+
+/// The archived branches and their tips, sorted by name.
+pub fn archived_branches(repo: &Repository) -> Result<Vec<(String, git2::Oid)>> {
+    let mut archived = Vec::new();
+    for reference in repo.references()? {
+        let reference = reference?;
+        if let Some(name) = reference
+            .name()
+            .and_then(|n| n.strip_prefix(ARCHIVE_PREFIX))
+            && let Some(oid) = reference.target()
+        {
+            archived.push((name.to_string(), oid));
+        }
+    }
+    archived.sort();
+    Ok(archived)
+}
+
+fn print_archived(repo: &Repository) -> Result<()> {
+    let archived = archived_branches(repo)?;
+    if archived.is_empty() {
+        println!("No archived branches");
+    }
+    for (name, oid) in archived {
+        let commit = repo.find_commit(oid)?;
+        println!(
+            "{}  {}  {}",
+            name,
+            git::short_hash(&oid.to_string()),
+            repo::commit_subject(&commit)
+        );
+    }
+    Ok(())
+}
+
+/// Whether `name` owns a whole branch section of the weave. Bails when it
+/// is woven but can't be removed alone: stacked under another branch, sharing
+/// its tip, or on the integration line itself.
+fn owns_section(graph: &Weave, name: &str) -> Result<bool> {
+    if let Some(section) = graph
+        .branch_sections
+        .iter()
+        .find(|s| s.branch_names.iter().any(|b| b == name))
+    {
+        if section.branch_names.len() > 1 {
+            let others: Vec<&str> = section
+                .branch_names
+                .iter()
+                .filter(|b| *b != name)
+                .map(|b| b.as_str())
+                .collect();
+            bail!(
+                "Branch `{}` shares its tip with `{}`\nRename or drop the other branch first",
+                name,
+                others.join("`, `")
+            );
+        }
+        return Ok(true);
+    }
+    if graph.branch_sections.iter().any(|s| {
+        s.commits
+            .iter()
+            .any(|c| c.update_refs.iter().any(|r| r == name))
+    }) {
+        bail!(
+            "Branch `{}` has branches stacked on it\nArchive the branches above it first",
+            name
+        );
+    }
+    if graph
+        .integration_line
+        .iter()
+        .any(|e| matches!(e, IntegrationEntry::Pick(c) if c.update_refs.iter().any(|r| r == name)))
+    {
+        bail!(
+            "Branch `{}` points into the integration line\nOnly woven branches can be archived",
+            name
+        );
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+#[path = "archive_test.rs"]
+mod tests;
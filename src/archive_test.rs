@@ -0,0 +1,115 @@
+use crate::core::test_helpers::TestRepo;
+
+#[test]
+fn archive_unweaves_and_moves_ref() {
+    let test_repo = TestRepo::new_with_woven_branches();
+    let tip = test_repo.get_branch_target("feature-a");
+
+    test_repo
+        .in_dir(|| super::run(Some("feature-a".to_string()), false, false))
+        .unwrap();
+
+    assert_eq!(test_repo.branch_names(), vec!["feature-b"]);
+    assert!(!test_repo.branch_exists("feature-a"));
+    assert!(!test_repo.workdir().join("feature-a.txt").exists());
+    assert_eq!(
+        super::archived_branches(&test_repo.repo).unwrap(),
+        vec![("feature-a".to_string(), tip)]
+    );
+}
+
+#[test]
+fn archive_restore_recreates_branch() {
+    let test_repo = TestRepo::new_with_woven_branches();
+    let tip = test_repo.get_branch_target("feature-a");
+    test_repo
+        .in_dir(|| super::run(Some("feature-a".to_string()), false, false))
+        .unwrap();
+
+    test_repo
+        .in_dir(|| super::run(Some("feature-a".to_string()), false, true))
+        .unwrap();
+
+    assert_eq!(test_repo.get_branch_target("feature-a"), tip);
+    assert!(
+        super::archived_branches(&test_repo.repo)
+            .unwrap()
+            .is_empty()
+    );
+    // Restoring doesn't weave the branch again
+    assert_eq!(test_repo.branch_names(), vec!["feature-b"]);
+}
+
+#[test]
+fn archive_unwoven_branch_only_moves_ref() {
+    let test_repo = TestRepo::new_with_woven_branches();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("spike", &base_oid.to_string());
+    let head = test_repo.head_oid();
+
+    test_repo
+        .in_dir(|| super::run(Some("spike".to_string()), false, false))
+        .unwrap();
+
+    assert_eq!(test_repo.head_oid(), head);
+    assert!(!test_repo.branch_exists("spike"));
+    assert_eq!(
+        super::archived_branches(&test_repo.repo).unwrap(),
+        vec![("spike".to_string(), base_oid)]
+    );
+}
+
+#[test]
+fn archive_refuses_branch_with_stack_above() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a1.txt");
+    test_repo.create_branch("feature-b");
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1", "b1.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-b");
+    let head = test_repo.head_oid();
+
+    let err = test_repo
+        .in_dir(|| super::run(Some("feature-a".to_string()), false, false))
+        .unwrap_err();
+
+    assert!(err.to_string().contains("stacked on it"), "{}", err);
+    assert_eq!(test_repo.head_oid(), head);
+    assert!(test_repo.branch_exists("feature-a"));
+    assert!(
+        super::archived_branches(&test_repo.repo)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn archive_refuses_current_branch() {
+    let test_repo = TestRepo::new_with_woven_branches();
+
+    let err = test_repo
+        .in_dir(|| super::run(Some("integration".to_string()), false, false))
+        .unwrap_err();
+
+    assert!(err.to_string().contains("current branch"), "{}", err);
+}
+
+#[test]
+fn archive_restore_refuses_existing_branch() {
+    let test_repo = TestRepo::new_with_woven_branches();
+    test_repo
+        .in_dir(|| super::run(Some("feature-a".to_string()), false, false))
+        .unwrap();
+    test_repo.create_branch("feature-a");
+
+    let err = test_repo
+        .in_dir(|| super::run(Some("feature-a".to_string()), false, true))
+        .unwrap_err();
+
+    assert!(err.to_string().contains("already exists"), "{}", err);
+    assert_eq!(super::archived_branches(&test_repo.repo).unwrap().len(), 1);
+}
@@ -0,0 +1,119 @@
+use anyhow::{Context, Result, bail};
+use git2::{Oid, Repository};
+
+use crate::branch::own_section;
+use crate::core::msg;
+use crate::core::repo::{self, TargetKind};
+use crate::core::weave::{self, CommitEntry, IntegrationEntry, Weave};
+use crate::git;
+
+/// Apply an upstream commit to the weave before the next update.
+///
+/// The commit is cherry-picked, keeping its author, at the bottom of the
+/// integration line so everything woven builds against it — or, with
+/// `branch`, as the first commit of that branch.
+pub fn run(commit: String, branch: Option<String>) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "backport")?;
+    let oid = Oid::from_str(
+        &git::rev_parse(workdir, &format!("{}^{{commit}}", commit))
+            .with_context(|| format!("Unknown commit `{}`", commit))?,
+    )?;
+    let branch = branch
+        .map(|b| repo::resolve_arg(&repo, &b, &[TargetKind::Branch])?.expect_branch())
+        .transpose()?;
+
+    let new_oid = backport(&repo, oid, branch.as_deref())?;
+    msg::success(&format!(
+        "Backported `{}` as `{}` {}",
+        git::short_hash(&oid.to_string()),
+        git::short_hash(&new_oid.to_string()),
+        match &branch {
+            Some(name) => format!("at the bottom of branch `{}`", name),
+            None => "at the bottom of the integration line".to_string(),
+        }
+    ));
+    Ok(())
+}
+
+/// Cherry-pick `oid` under the woven commits and rebase them on it. Returns
+/// the OID of the backported commit.
+pub fn backport(repo: &Repository, oid: Oid, branch: Option<&str>) -> Result<Oid> {
+    let workdir = repo::require_workdir(repo, "backport")?;
+    let info = repo::gather_repo_info(repo, false, 1)?;
+    let commit = repo.find_commit(oid)?;
+    let short = git::short_hash(&oid.to_string()).to_string();
+    if commit.parent_count() > 1 {
+        bail!("Cannot backport merge commit `{}`", short);
+    }
+    let head = repo.head()?.peel_to_commit()?.id();
+    if head == oid || repo.graph_descendant_of(head, oid)? {
+        bail!("Commit `{}` is already in the integration branch", short);
+    }
+
+    let mut graph = Weave::from_repo_with_info(repo, &info)?;
+    // Pick against the commit the backport goes on, so it applies cleanly
+    // when the rebase replays it there
+    let onto = match branch {
+        Some(name) => {
+            let first = own_section(&graph, name)?.commits[0].oid;
+            repo.find_commit(first)?.parent(0)?
+        }
+        None => repo.find_commit(graph.base_oid)?,
+    };
+    let mut index = repo.cherrypick_commit(&commit, &onto, 0, None)?;
+    if index.has_conflicts() {
+        bail!(
+            "Commit `{}` does not apply on {}\nBackport it manually with `git cherry-pick`",
+            short,
+            match branch {
+                Some(name) => format!("the base of `{}`", name),
+                None => "the base of the weave".to_string(),
+            }
+        );
+    }
+    let tree = index.write_tree_to(repo)?;
+    let message = format!(
+        "{}\n\n(cherry picked from commit {})\n",
+        commit.message().unwrap_or("").trim_end(),
+        oid
+    );
+    let new_oid = Oid::from_str(&git::commit_tree(
+        workdir,
+        &tree.to_string(),
+        &onto.id().to_string(),
+        &message,
+        Some(&commit.author()),
+        false,
+    )?)?;
+
+    let entry = CommitEntry {
+        oid: new_oid,
+        short_hash: git::short_hash(&new_oid.to_string()).to_string(),
+        message: repo::commit_subject(&commit),
+        command: weave::Command::Pick,
+        update_refs: Vec::new(),
+    };
+    match branch {
+        Some(name) => graph
+            .branch_sections
+            .iter_mut()
+            .find(|s| s.branch_names.iter().any(|b| b == name))
+            .expect("own_section found it")
+            .commits
+            .insert(0, entry),
+        None => graph
+            .integration_line
+            .insert(0, IntegrationEntry::Pick(entry)),
+    }
+    weave::run_rebase_or_abort(
+        workdir,
+        Some(&graph.base_oid.to_string()),
+        &graph.checked_todo()?,
+    )?;
+    Ok(new_oid)
+}
+
+#[cfg(test)]
+#[path = "backport_test.rs"]
+mod tests;
@@ -0,0 +1,72 @@
+use crate::core::test_helpers::TestRepo;
+
+/// Integration branch weaving `feature-a`, with an upstream-style fix on
+/// the unwoven branch `fix` (next to the base, not yet in the weave).
+fn setup() -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("fix", &base_oid.to_string());
+    test_repo.switch_branch("fix");
+    test_repo.commit("Fix crash", "fix.txt");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a1.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-a");
+    test_repo
+}
+
+#[test]
+fn backport_lands_at_bottom_of_integration_line() {
+    let test_repo = setup();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    let fix = test_repo.get_branch_target("fix");
+
+    test_repo
+        .in_dir(|| super::run("fix".to_string(), None))
+        .unwrap();
+
+    let head = test_repo.find_commit(test_repo.head_oid());
+    assert_eq!(head.parent_count(), 2, "feature-a is still merged");
+    let backported = head.parent(0).unwrap();
+    assert_eq!(backported.summary(), Some("Fix crash"));
+    assert_eq!(backported.parent_id(0).unwrap(), base_oid);
+    assert!(
+        backported
+            .message()
+            .unwrap()
+            .contains(&format!("(cherry picked from commit {})", fix))
+    );
+    assert_eq!(test_repo.read_file("fix.txt"), "Fix crash");
+    assert_eq!(test_repo.read_file("a1.txt"), "A1");
+}
+
+#[test]
+fn backport_into_branch_goes_first() {
+    let test_repo = setup();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+
+    test_repo
+        .in_dir(|| super::run("fix".to_string(), Some("feature-a".to_string())))
+        .unwrap();
+
+    let tip = test_repo.find_commit(test_repo.get_branch_target("feature-a"));
+    assert_eq!(tip.summary(), Some("A1"));
+    let first = tip.parent(0).unwrap();
+    assert_eq!(first.summary(), Some("Fix crash"));
+    assert_eq!(first.parent_id(0).unwrap(), base_oid);
+}
+
+#[test]
+fn backport_of_woven_commit_fails() {
+    let test_repo = setup();
+
+    let err = test_repo
+        .in_dir(|| super::run("feature-a".to_string(), None))
+        .unwrap_err();
+
+    assert!(
+        err.to_string()
+            .contains("already in the integration branch")
+    );
+}
@@ -0,0 +1,296 @@
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+
+use crate::core::msg;
+use crate::core::repo::{self, Target, TargetKind};
+use crate::core::transaction::{self, LoomState, Rollback};
+use crate::core::weave::{self, RebaseOutcome, Weave};
+use crate::git;
+
+/// A single operation in a batch plan, with its arguments as typed by the user
+/// (short IDs, hashes, or branch names).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Op {
+    /// `drop <commit|branch>`
+    Drop(String),
+    /// `fixup <source> <target>`
+    Fixup { source: String, target: String },
+    /// `move <commit> <branch>`
+    Move { commit: String, branch: String },
+    /// `swap <a> <b>`
+    Swap { a: String, b: String },
+}
+
+#[derive(Serialize, Deserialize)]
+struct BatchContext {
+    op_count: usize,
+    delete_branches: Vec<String>,
+}
+
+/// Apply several operations to the integration branch in a single rebase.
+///
+/// Operations are read from `file` (or stdin when `None` or `-`), one per
+/// line. Every argument is resolved against the repository *before* anything
+/// changes, so short IDs refer to `loom status` as it was when the batch
+/// started. All operations are applied to one `Weave` and executed as one
+/// rebase, minimizing conflict windows and working-tree churn.
+///
+/// With `dry_run`, prints the generated rebase todo instead of running it.
+pub fn run(file: Option<String>, dry_run: bool) -> Result<()> {
+    let text = read_plan(file.as_deref())?;
+    let ops = parse(&text)?;
+    if ops.is_empty() {
+        bail!("No operations to run\nWrite one operation per line: drop, fixup, move, or swap");
+    }
+
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "batch")?.to_path_buf();
+    let git_dir = repo.path().to_path_buf();
+
+    let info = repo::gather_repo_info(&repo, false, 1)?;
+    let mut graph = Weave::from_repo_with_info(&repo, &info)?;
+    let delete_branches = apply(&repo, &mut graph, &ops)?;
+
+    let todo = graph.checked_todo()?;
+    if dry_run {
+        print!("{}", todo);
+        return Ok(());
+    }
+
+    let ctx = BatchContext {
+        op_count: ops.len(),
+        delete_branches,
+    };
+    let state = LoomState {
+        command: "batch".to_string(),
+        refs_before: Default::default(),
+        rollback: Rollback::default(),
+        context: serde_json::to_value(&ctx)?,
+    };
+    transaction::save(&git_dir, &state)?;
+
+    match weave::run_rebase(&workdir, Some(&graph.base_oid.to_string()), &todo)? {
+        RebaseOutcome::Completed => {
+            transaction::delete(&git_dir)?;
+            finish(&workdir, &ctx);
+        }
+        RebaseOutcome::Conflicted => {
+            transaction::warn_conflict_paused("batch");
+        }
+    }
+
+    Ok(())
+}
+
+/// Resume a `batch` operation after a conflict has been resolved.
+pub fn after_continue(workdir: &Path, context: &serde_json::Value) -> Result<()> {
+    let ctx: BatchContext =
+        serde_json::from_value(context.clone()).context("Failed to parse batch resume context")?;
+    finish(workdir, &ctx);
+    Ok(())
+}
+
+/// Delete the refs of dropped branches and report.
+fn finish(workdir: &Path, ctx: &BatchContext) {
+    for name in &ctx.delete_branches {
+        if let Err(e) = git::branch_delete(workdir, name) {
+            msg::warn(&format!("Could not delete branch `{}`: {}", name, e));
+        }
+    }
+    msg::success(&format!(
+        "Applied {} {} in one rebase",
+        ctx.op_count,
+        if ctx.op_count == 1 {
+            "operation"
+        } else {
+            "operations"
+        }
+    ));
+}
+
+fn read_plan(file: Option<&str>) -> Result<String> {
+    match file {
+        None | Some("-") => {
+            let mut text = String::new();
+            std::io::stdin()
+                .read_to_string(&mut text)
+                .context("Failed to read operations from stdin")?;
+            Ok(text)
+        }
+        Some(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read operations from '{}'", path)),
+    }
+}
+
+/// Parse a batch plan: one operation per line. Blank lines and lines starting
+/// with `#` are ignored. Returns each operation with its 1-based line number.
+pub fn parse(text: &str) -> Result<Vec<(usize, Op)>> {
+    let mut ops = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let op = match words.as_slice() {
+            ["drop", target] => Op::Drop(target.to_string()),
+            ["fixup", source, target] => Op::Fixup {
+                source: source.to_string(),
+                target: target.to_string(),
+            },
+            ["move", commit, branch] => Op::Move {
+                commit: commit.to_string(),
+                branch: branch.to_string(),
+            },
+            ["swap", a, b] => Op::Swap {
+                a: a.to_string(),
+                b: b.to_string(),
+            },
+            [verb @ ("drop" | "fixup" | "move" | "swap"), ..] => bail!(
+                "Line {}: wrong number of arguments for `{}`\n{}",
+                line_no,
+                verb,
+                USAGE
+            ),
+            [verb, ..] => bail!("Line {}: unknown operation `{}`\n{}", line_no, verb, USAGE),
+            [] => unreachable!(),
+        };
+        ops.push((line_no, op));
+    }
+    Ok(ops)
+}
+
+const USAGE: &str = "Expected `drop <target>`, `fixup <source> <target>`, \
+                     `move <commit> <branch>`, or `swap <a> <b>`";
+
+/// Resolve every operation's arguments, then apply them in order to `graph`.
+///
+/// Resolution happens up front so short IDs are taken from the pre-batch
+/// status. Returns the branches whose refs must be deleted once the rebase
+/// completes.
+fn apply(repo: &Repository, graph: &mut Weave, ops: &[(usize, Op)]) -> Result<Vec<String>> {
+    let resolved: Vec<(usize, Resolved)> = ops
+        .iter()
+        .map(|(line, op)| {
+            resolve(repo, op)
+                .map(|r| (*line, r))
+                .map_err(|e| anyhow!("Line {}: {}", line, e))
+        })
+        .collect::<Result<_>>()?;
+
+    let mut delete_branches = Vec::new();
+    for (line, op) in resolved {
+        apply_one(repo, graph, op, &mut delete_branches)
+            .map_err(|e| anyhow!("Line {}: {}", line, e))?;
+    }
+    Ok(delete_branches)
+}
+
+/// An operation with its arguments resolved to OIDs and branch names.
+enum Resolved {
+    DropCommit(Oid),
+    DropBranch(String),
+    Fixup(Oid, Oid),
+    Move(Oid, String),
+    Swap(Oid, Oid),
+}
+
+fn resolve(repo: &Repository, op: &Op) -> Result<Resolved> {
+    Ok(match op {
+        Op::Drop(target) => {
+            match repo::resolve_arg(repo, target, &[TargetKind::Branch, TargetKind::Commit])? {
+                Target::Branch(name) => Resolved::DropBranch(name),
+                Target::Commit(hash) => Resolved::DropCommit(Oid::from_str(&hash)?),
+                _ => unreachable!(),
+            }
+        }
+        Op::Fixup { source, target } => {
+            let source = resolve_commit(repo, source)?;
+            let target = resolve_commit(repo, target)?;
+            if source == target {
+                bail!("Source and target are the same commit");
+            }
+            if !repo.graph_descendant_of(source, target)? {
+                bail!("Source commit must be newer than target commit");
+            }
+            Resolved::Fixup(source, target)
+        }
+        Op::Move { commit, branch } => Resolved::Move(
+            resolve_commit(repo, commit)?,
+            repo::resolve_arg(repo, branch, &[TargetKind::Branch])?.expect_branch()?,
+        ),
+        Op::Swap { a, b } => Resolved::Swap(resolve_commit(repo, a)?, resolve_commit(repo, b)?),
+    })
+}
+
+fn resolve_commit(repo: &Repository, arg: &str) -> Result<Oid> {
+    match repo::resolve_arg(repo, arg, &[TargetKind::Commit])? {
+        Target::Commit(hash) => Ok(Oid::from_str(&hash)?),
+        _ => unreachable!(),
+    }
+}
+
+fn apply_one(
+    repo: &Repository,
+    graph: &mut Weave,
+    op: Resolved,
+    delete_branches: &mut Vec<String>,
+) -> Result<()> {
+    match op {
+        Resolved::DropCommit(oid) => {
+            // Dropping the last commit of a branch drops the branch, like `loom drop`.
+            if let Some(section) = graph
+                .branch_sections
+                .iter()
+                .find(|s| s.commits.iter().any(|c| c.oid == oid))
+                && section.commits.len() == 1
+            {
+                delete_branches.extend(section.branch_names.iter().cloned());
+            }
+            graph.drop_commit(oid);
+        }
+        Resolved::DropBranch(name) => {
+            let section = graph
+                .branch_sections
+                .iter()
+                .find(|s| s.branch_names.contains(&name));
+            match section {
+                Some(section) => {
+                    let other = section.branch_names.iter().find(|n| **n != name).cloned();
+                    match other {
+                        Some(keep) => graph.reassign_branch(&name, &keep),
+                        None => graph.drop_branch(&name),
+                    }
+                }
+                None => {
+                    let tip = repo.revparse_single(&name)?.id();
+                    if tip != graph.base_oid {
+                        bail!(
+                            "Branch `{}` is not woven into the integration branch\n\
+                             Use `loom drop {}` instead",
+                            name,
+                            name
+                        );
+                    }
+                }
+            }
+            delete_branches.push(name);
+        }
+        Resolved::Fixup(source, target) => graph.fixup_commit(source, target)?,
+        Resolved::Move(oid, branch) => {
+            crate::fold::ensure_branch_section(repo, graph, &branch)?;
+            graph.move_commit(oid, &branch)?;
+        }
+        Resolved::Swap(a, b) => graph.swap_commits(a, b)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "batch_test.rs"]
+mod tests;
@@ -0,0 +1,169 @@
+use crate::core::test_helpers::TestRepo;
+
+use super::Op;
+
+/// Integration branch with woven `feature-a` (A1, A2) and `feature-b` (B1),
+/// plus a loose commit.
+fn setup_two_branches() -> (TestRepo, git2::Oid, git2::Oid, git2::Oid) {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    let a1 = test_repo.commit("A1", "a1.txt");
+    let a2 = test_repo.commit("A2", "a2.txt");
+
+    test_repo.create_branch_at("feature-b", &base_oid.to_string());
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1", "b1.txt");
+
+    test_repo.switch_branch("integration");
+    let int = test_repo.commit("Int", "int.txt");
+    test_repo.merge_no_ff("feature-a");
+    test_repo.merge_no_ff("feature-b");
+    (test_repo, a1, a2, int)
+}
+
+/// Write `plan` to a file outside the repository and run the batch.
+fn run_plan(test_repo: &TestRepo, plan: &str, dry_run: bool) -> anyhow::Result<()> {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("plan.txt");
+    std::fs::write(&path, plan).unwrap();
+    let path = path.to_str().unwrap().to_string();
+    test_repo.in_dir(|| super::run(Some(path), dry_run))
+}
+
+// ── Parsing ─────────────────────────────────────────────────────────────
+
+#[test]
+fn parse_skips_comments_and_blank_lines() {
+    let ops = super::parse("# tidy up\n\ndrop abc\n  fixup ab cd  \nmove ab feature\nswap ab cd\n")
+        .unwrap();
+    assert_eq!(
+        ops,
+        vec![
+            (3, Op::Drop("abc".to_string())),
+            (
+                4,
+                Op::Fixup {
+                    source: "ab".to_string(),
+                    target: "cd".to_string()
+                }
+            ),
+            (
+                5,
+                Op::Move {
+                    commit: "ab".to_string(),
+                    branch: "feature".to_string()
+                }
+            ),
+            (
+                6,
+                Op::Swap {
+                    a: "ab".to_string(),
+                    b: "cd".to_string()
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn parse_rejects_unknown_operation() {
+    let err = super::parse("drop ab\nsquash ab cd\n").unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("Line 2"), "got: {}", message);
+    assert!(message.contains("unknown operation `squash`"));
+}
+
+#[test]
+fn parse_rejects_wrong_argument_count() {
+    let err = super::parse("fixup ab\n").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Line 1: wrong number of arguments for `fixup`")
+    );
+}
+
+// ── Running ─────────────────────────────────────────────────────────────
+
+#[test]
+fn run_applies_all_operations_in_one_rebase() {
+    let (test_repo, a1, a2, int) = setup_two_branches();
+    let plan = format!("fixup {} {}\nmove {} feature-b\n", a2, a1, int);
+
+    run_plan(&test_repo, &plan, false).unwrap();
+
+    let messages = test_repo.commit_messages();
+    assert!(!messages.contains(&"A2".to_string()), "A2 should be folded");
+    assert!(messages.contains(&"A1".to_string()));
+    assert_eq!(test_repo.branch_commit_summary("feature-b"), "Int");
+
+    let a1_tip = test_repo.get_branch_target("feature-a");
+    assert!(
+        test_repo
+            .commit_file_paths(a1_tip)
+            .contains(&"a2.txt".to_string()),
+        "A1 should now contain A2's changes"
+    );
+}
+
+#[test]
+fn run_drop_branch_deletes_ref() {
+    let (test_repo, _, _, _) = setup_two_branches();
+
+    run_plan(&test_repo, "drop feature-b\n", false).unwrap();
+
+    assert!(!test_repo.branch_exists("feature-b"));
+    let messages = test_repo.commit_messages();
+    assert!(!messages.contains(&"B1".to_string()));
+    assert!(messages.contains(&"A1".to_string()));
+}
+
+#[test]
+fn run_drop_last_commit_deletes_branch() {
+    let (test_repo, a1, a2, _) = setup_two_branches();
+    let plan = format!("drop {}\ndrop {}\n", a1, a2);
+
+    run_plan(&test_repo, &plan, false).unwrap();
+
+    assert!(!test_repo.branch_exists("feature-a"));
+    assert!(test_repo.branch_exists("feature-b"));
+}
+
+#[test]
+fn dry_run_leaves_history_untouched() {
+    let (test_repo, a1, _, _) = setup_two_branches();
+    let before = test_repo.head_oid();
+
+    run_plan(&test_repo, &format!("drop {}\n", a1), true).unwrap();
+
+    assert_eq!(test_repo.head_oid(), before);
+    assert!(test_repo.commit_messages().contains(&"A1".to_string()));
+}
+
+#[test]
+fn unresolvable_target_fails_before_any_change() {
+    let (test_repo, a1, _, _) = setup_two_branches();
+    let before = test_repo.head_oid();
+    let plan = format!("drop {}\ndrop no-such-thing\n", a1);
+
+    let err = run_plan(&test_repo, &plan, false).unwrap_err();
+
+    assert!(err.to_string().starts_with("Line 2:"), "got: {}", err);
+    assert_eq!(test_repo.head_oid(), before);
+}
+
+#[test]
+fn fixup_into_newer_commit_fails() {
+    let (test_repo, a1, a2, _) = setup_two_branches();
+    let err = run_plan(&test_repo, &format!("fixup {} {}\n", a1, a2), false).unwrap_err();
+    assert!(err.to_string().contains("must be newer"));
+}
+
+#[test]
+fn empty_plan_fails() {
+    let (test_repo, _, _, _) = setup_two_branches();
+    let err = run_plan(&test_repo, "# nothing\n", false).unwrap_err();
+    assert!(err.to_string().contains("No operations"));
+}
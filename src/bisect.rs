@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+
+use crate::core::msg;
+use crate::core::repo;
+use crate::core::shortid::IdAllocator;
+use crate::core::{graph, weave::Weave};
+use crate::git;
+
+/// What `loom bisect start` remembers while git bisects with a detached HEAD.
+#[derive(Debug, Serialize, Deserialize)]
+struct BisectState {
+    /// Integration branch the bisection runs over: short IDs are resolved
+    /// and culprits attributed against it.
+    branch: String,
+}
+
+/// Return the path to the bisect state file: `<git_dir>/loom/bisect.json`.
+fn state_path(git_dir: &Path) -> PathBuf {
+    git_dir.join("loom").join("bisect.json")
+}
+
+/// Start bisecting the integration branch.
+///
+/// `bad` defaults to the integration tip and `good` to the upstream base.
+/// With `branch`, only that woven branch's commits are bisected: `bad`
+/// defaults to its tip and `good` to the commit it starts from.
+pub fn start(bad: Option<String>, good: Vec<String>, branch: Option<String>) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "bisect")?;
+    if repo.path().join("BISECT_START").exists() {
+        bail!("A bisection is already in progress\nRun `loom bisect reset` to end it");
+    }
+    let info = repo::gather_repo_info(&repo, false, 1)?;
+
+    let (default_bad, default_good) = match &branch {
+        Some(arg) => {
+            let name =
+                repo::resolve_arg(&repo, arg, &[repo::TargetKind::Branch])?.expect_branch()?;
+            branch_range(&repo, &name)?
+        }
+        None => (repo::head_oid(&repo)?, info.upstream.merge_base_oid),
+    };
+    let bad = match bad {
+        Some(arg) => resolve_commit(&repo, &info, &arg)?,
+        None => default_bad,
+    };
+    let good = if good.is_empty() {
+        vec![default_good]
+    } else {
+        good.iter()
+            .map(|arg| resolve_commit(&repo, &info, arg))
+            .collect::<Result<_>>()?
+    };
+
+    save_state(
+        repo.path(),
+        &BisectState {
+            branch: info.branch_name.clone(),
+        },
+    )?;
+    let mut args = vec!["bisect".to_string(), "start".to_string(), bad.to_string()];
+    args.extend(good.iter().map(|oid| oid.to_string()));
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    if let Err(e) = report(&repo, workdir, &info.branch_name, &args) {
+        let _ = std::fs::remove_file(state_path(repo.path()));
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Mark commits (default: the checked-out one) as `good`, `bad`, or `skip`.
+pub fn mark(verdict: &str, commits: Vec<String>) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "bisect")?;
+    let state = load_state(repo.path())?;
+    let info = repo::gather_branch_info(&repo, &state.branch, false, 1)?;
+
+    let mut args = vec!["bisect".to_string(), verdict.to_string()];
+    for arg in &commits {
+        args.push(resolve_commit(&repo, &info, arg)?.to_string());
+    }
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    report(&repo, workdir, &state.branch, &args)
+}
+
+/// End the bisection and return to the integration branch.
+pub fn reset() -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "bisect")?;
+    git::run_git(workdir, &["bisect", "reset"])?;
+    let path = state_path(repo.path());
+    if path.exists() {
+        std::fs::remove_file(&path)
+            .with_context(|| format!("Failed to delete bisect state '{}'", path.display()))?;
+    }
+    msg::success("Bisection ended");
+    Ok(())
+}
+
+/// Run a `git bisect` command, print its progress, and attribute the first
+/// bad commit once git has found it.
+fn report(repo: &Repository, workdir: &Path, branch: &str, args: &[&str]) -> Result<()> {
+    let output = git::run_git_combined(workdir, args)?;
+    let Some(culprit) = first_bad_commit(&output) else {
+        for line in output.lines().filter(|l| l.starts_with("Bisecting:")) {
+            println!("{}", line);
+        }
+        return Ok(());
+    };
+
+    let commit = repo.find_commit(culprit)?;
+    let info = repo::gather_branch_info(repo, branch, false, 1)?;
+    msg::success(&format!(
+        "First bad commit: `{}` {} — {}\nRun `loom bisect reset` to return to `{}`",
+        git::short_hash(&culprit.to_string()),
+        repo::commit_subject(&commit),
+        owner_description(repo, &info, culprit)?,
+        branch
+    ));
+    Ok(())
+}
+
+/// Parse `<hash> is the first bad commit` out of `git bisect` output.
+fn first_bad_commit(output: &str) -> Option<Oid> {
+    output.lines().find_map(|line| {
+        let hash = line.strip_suffix(" is the first bad commit")?;
+        Oid::from_str(hash.trim()).ok()
+    })
+}
+
+/// Where `oid` sits in the weave of `info`: in a branch section, the merge
+/// of one, or on the integration line.
+fn owner_description(repo: &Repository, info: &repo::RepoInfo, oid: Oid) -> Result<String> {
+    let owners = graph::assign_commits_to_branches(info);
+    if let Some(branch) = owners.get(&oid) {
+        return Ok(format!("in branch `{}`", branch));
+    }
+    let commit = repo.find_commit(oid)?;
+    if commit.parent_count() > 1
+        && let Ok(merged) = commit.parent_id(1)
+        && let Some(branch) = info.branches.iter().find(|b| b.tip_oid == merged)
+    {
+        return Ok(format!("the merge of branch `{}`", branch.name));
+    }
+    if info.commits.iter().any(|c| c.oid == oid) {
+        return Ok("on the integration line".to_string());
+    }
+    Ok("outside the integration branch".to_string())
+}
+
+/// The tip of woven branch `name` and the parent of its first own commit,
+/// leaving out the commits of the branches it is stacked on.
+fn branch_range(repo: &Repository, name: &str) -> Result<(Oid, Oid)> {
+    let graph = Weave::from_repo(repo)?;
+    for section in &graph.branch_sections {
+        let mut start = 0;
+        let mut end = None;
+        for (i, commit) in section.commits.iter().enumerate() {
+            if commit.update_refs.iter().any(|r| r == name) {
+                end = Some(i);
+                break;
+            }
+            if !commit.update_refs.is_empty() {
+                start = i + 1;
+            }
+        }
+        if end.is_none()
+            && section.branch_names.iter().any(|b| b == name)
+            && start < section.commits.len()
+        {
+            end = Some(section.commits.len() - 1);
+        }
+        if let Some(end) = end {
+            let first = repo.find_commit(section.commits[start].oid)?;
+            return Ok((section.commits[end].oid, first.parent_id(0)?));
+        }
+    }
+    bail!(
+        "Branch `{}` is not woven into the integration branch\n\
+         Only woven branches can be bisected alone",
+        name
+    );
+}
+
+/// Resolve a commit hash, ref, or short ID of the integration branch `info`.
+fn resolve_commit(repo: &Repository, info: &repo::RepoInfo, arg: &str) -> Result<Oid> {
+    if let Ok(object) = repo.revparse_single(arg)
+        && let Ok(commit) = object.peel_to_commit()
+    {
+        return Ok(commit.id());
+    }
+    let ids = IdAllocator::new(info.collect_entities());
+    let by_id: HashMap<String, Oid> = info
+        .commits
+        .iter()
+        .map(|c| (ids.get_commit(c.oid).to_string(), c.oid))
+        .chain(
+            info.branches
+                .iter()
+                .map(|b| (ids.get_branch(&b.name).to_string(), b.tip_oid)),
+        )
+        .collect();
+    by_id
+        .get(arg)
+        .copied()
+        .with_context(|| format!("'{}' did not resolve to a commit", arg))
+}
+
+fn save_state(git_dir: &Path, state: &BisectState) -> Result<()> {
+    let path = state_path(git_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "Failed to create loom state directory '{}'",
+                parent.display()
+            )
+        })?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(state)?)
+        .with_context(|| format!("Failed to write bisect state '{}'", path.display()))
+}
+
+fn load_state(git_dir: &Path) -> Result<BisectState> {
+    let path = state_path(git_dir);
+    let Ok(json) = std::fs::read_to_string(&path) else {
+        bail!("No bisection is in progress\nStart one with `loom bisect start`");
+    };
+    serde_json::from_str(&json)
+        .with_context(|| format!("Bisect state '{}' is corrupted or invalid", path.display()))
+}
+
+#[cfg(test)]
+#[path = "bisect_test.rs"]
+mod tests;
@@ -0,0 +1,125 @@
+use crate::core::repo;
+use crate::core::test_helpers::TestRepo;
+
+/// Integration branch weaving `feature-a`, `feature-b`, and `feature-c`, two
+/// commits each. The second commit of `feature-b` adds `bug.txt`.
+fn setup() -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    for name in ["feature-a", "feature-b", "feature-c"] {
+        test_repo.create_branch_at(name, &base_oid.to_string());
+        test_repo.switch_branch(name);
+        test_repo.commit(&format!("{} 1", name), &format!("{}-1.txt", name));
+        if name == "feature-b" {
+            test_repo.commit("Introduce the bug", "bug.txt");
+        } else {
+            test_repo.commit(&format!("{} 2", name), &format!("{}-2.txt", name));
+        }
+        test_repo.switch_branch("integration");
+        test_repo.merge_no_ff(name);
+    }
+    test_repo
+}
+
+/// Mark checked-out commits until git names the first bad commit.
+fn bisect_for_bug(test_repo: &TestRepo) -> git2::Oid {
+    let log = test_repo.repo.path().join("BISECT_LOG");
+    for _ in 0..10 {
+        if std::fs::read_to_string(&log)
+            .unwrap()
+            .contains("first bad commit")
+        {
+            let bad = test_repo.repo.find_reference("refs/bisect/bad").unwrap();
+            return bad.target().unwrap();
+        }
+        let verdict = if test_repo.workdir().join("bug.txt").exists() {
+            "bad"
+        } else {
+            "good"
+        };
+        test_repo
+            .in_dir(|| super::mark(verdict, Vec::new()))
+            .unwrap();
+    }
+    panic!("bisection did not converge");
+}
+
+#[test]
+fn bisect_finds_culprit_and_its_branch() {
+    let test_repo = setup();
+    let culprit = test_repo.get_branch_target("feature-b");
+
+    test_repo
+        .in_dir(|| super::start(None, Vec::new(), None))
+        .unwrap();
+    assert_eq!(bisect_for_bug(&test_repo), culprit);
+
+    let info = repo::gather_branch_info(&test_repo.repo, "integration", false, 1).unwrap();
+    assert_eq!(
+        super::owner_description(&test_repo.repo, &info, culprit).unwrap(),
+        "in branch `feature-b`"
+    );
+
+    test_repo.in_dir(super::reset).unwrap();
+    assert_eq!(test_repo.current_branch_name(), "integration");
+    assert!(!test_repo.repo.path().join("loom/bisect.json").exists());
+}
+
+#[test]
+fn bisect_restricted_to_branch() {
+    let test_repo = setup();
+    let culprit = test_repo.get_branch_target("feature-b");
+
+    test_repo
+        .in_dir(|| super::start(None, Vec::new(), Some("feature-b".to_string())))
+        .unwrap();
+
+    // Only the two commits of `feature-b` are in range: the first one is
+    // checked out and marking it good leaves the tip as the culprit.
+    assert!(test_repo.workdir().join("feature-b-1.txt").exists());
+    assert!(!test_repo.workdir().join("feature-a-1.txt").exists());
+    assert_eq!(bisect_for_bug(&test_repo), culprit);
+}
+
+#[test]
+fn bisect_marks_accept_short_ids() {
+    let test_repo = setup();
+    let info = repo::gather_branch_info(&test_repo.repo, "integration", false, 1).unwrap();
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let good = ids.get_branch("feature-a").to_string();
+
+    test_repo
+        .in_dir(|| super::start(None, vec![good], None))
+        .unwrap();
+
+    let info = repo::gather_branch_info(&test_repo.repo, "integration", false, 1).unwrap();
+    let tip_a = test_repo.get_branch_target("feature-a");
+    assert_eq!(
+        super::resolve_commit(&test_repo.repo, &info, ids.get_branch("feature-a")).unwrap(),
+        tip_a
+    );
+    test_repo.in_dir(super::reset).unwrap();
+}
+
+#[test]
+fn owner_of_merge_commit_is_the_merged_branch() {
+    let test_repo = setup();
+    let info = repo::gather_branch_info(&test_repo.repo, "integration", false, 1).unwrap();
+    let merge = test_repo.head_oid();
+
+    assert_eq!(
+        super::owner_description(&test_repo.repo, &info, merge).unwrap(),
+        "the merge of branch `feature-c`"
+    );
+}
+
+#[test]
+fn bisect_mark_without_start_fails() {
+    let test_repo = setup();
+
+    let err = test_repo
+        .in_dir(|| super::mark("good", Vec::new()))
+        .unwrap_err();
+
+    assert!(err.to_string().contains("No bisection"), "{}", err);
+}
@@ -0,0 +1,152 @@
+use anyhow::{Context, Result, bail};
+use git2::{BranchType, Oid, Repository};
+
+use crate::core::msg;
+use crate::core::repo;
+use crate::git;
+
+/// Namespace holding a branch's replayed commits until it is moved there.
+pub(crate) const ATTACH_PREFIX: &str = "refs/loom/attach/";
+
+/// Adopt a branch created outside loom into the weave.
+///
+/// The branch's own commits (those not in the upstream) are replayed onto
+/// the integration branch's merge-base, in memory and into
+/// `refs/loom/attach/<branch>`, so a conflict changes nothing. The branch is
+/// then moved there and woven like `loom branch merge`. A remote branch gets
+/// a local branch tracking it.
+pub fn run(target: String) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let info = repo::gather_repo_info(&repo, false, 1)?;
+
+    let (name, remote) = resolve_external_branch(&repo, &info, &target)?;
+    let tip = repo
+        .revparse_single(remote.as_deref().unwrap_or(&name))?
+        .peel_to_commit()?
+        .id();
+    let (commits, old_base) = own_commits(&repo, &info, &name, tip)?;
+
+    let base = info.upstream.merge_base_oid;
+    let new_tip = if old_base == base {
+        tip
+    } else {
+        let temp_ref = format!("{}{}", ATTACH_PREFIX, name);
+        let new_tip = super::rebase::replay(&repo, &commits, base).with_context(|| {
+            format!(
+                "Could not rebase `{}` onto `{}` — nothing was changed\n\
+                 Rebase it with `git rebase --onto {} {} {}`, then attach it again",
+                name,
+                git::short_hash(&base.to_string()),
+                git::short_hash(&base.to_string()),
+                git::short_hash(&old_base.to_string()),
+                name
+            )
+        })?;
+        repo.reference(&temp_ref, new_tip, true, "loom: attach")?;
+        msg::success(&format!(
+            "Rebased {} {} of `{}` from `{}` onto `{}`",
+            commits.len(),
+            if commits.len() == 1 {
+                "commit"
+            } else {
+                "commits"
+            },
+            name,
+            git::short_hash(&old_base.to_string()),
+            git::short_hash(&base.to_string())
+        ));
+        new_tip
+    };
+
+    let message = format!("loom: attach {}", target);
+    match &remote {
+        Some(remote) => {
+            let mut branch = repo.branch(&name, &repo.find_commit(new_tip)?, false)?;
+            branch.set_upstream(Some(remote))?;
+        }
+        None => {
+            repo.reference(&format!("refs/heads/{}", name), new_tip, true, &message)?;
+        }
+    }
+    if let Ok(mut temp) = repo.find_reference(&format!("{}{}", ATTACH_PREFIX, name)) {
+        temp.delete()?;
+    }
+
+    super::merge::run(Some(name), false, false)
+}
+
+/// Resolve `target` to the local branch to attach, and the remote branch it
+/// comes from when it only exists on a remote.
+fn resolve_external_branch(
+    repo: &Repository,
+    info: &repo::RepoInfo,
+    target: &str,
+) -> Result<(String, Option<String>)> {
+    if info.branches.iter().any(|b| b.name == target) {
+        bail!(
+            "Branch `{}` is already woven into the integration branch",
+            target
+        );
+    }
+    if target == info.branch_name {
+        bail!("Cannot attach the integration branch to itself");
+    }
+    if repo.find_branch(target, BranchType::Local).is_ok() {
+        return Ok((target.to_string(), None));
+    }
+    if repo.find_branch(target, BranchType::Remote).is_ok() {
+        let local = repo::upstream_local_branch(target);
+        repo::ensure_branch_not_exists(repo, &local)
+            .with_context(|| format!("Run `loom attach {}` to attach the local branch", local))?;
+        return Ok((local, Some(target.to_string())));
+    }
+    bail!("Branch `{}` not found", target)
+}
+
+/// The commits of `name` not in the upstream, oldest first, and the upstream
+/// commit they are based on.
+fn own_commits(
+    repo: &Repository,
+    info: &repo::RepoInfo,
+    name: &str,
+    tip: Oid,
+) -> Result<(Vec<Oid>, Oid)> {
+    let upstream = repo
+        .revparse_single(&info.upstream.label)?
+        .peel_to_commit()?
+        .id();
+    let old_base = repo
+        .merge_base(tip, upstream)
+        .with_context(|| format!("Branch `{}` shares no history with the upstream", name))?;
+    let base = info.upstream.merge_base_oid;
+    if old_base != base && repo.graph_descendant_of(old_base, base)? {
+        bail!(
+            "Branch `{}` is based on a newer upstream than the integration branch\n\
+             Run `loom update` first",
+            name
+        );
+    }
+
+    let mut walk = repo.revwalk()?;
+    walk.push(tip)?;
+    walk.hide(old_base)?;
+    walk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)?;
+    let commits = walk.collect::<Result<Vec<Oid>, _>>()?;
+    if commits.is_empty() {
+        bail!(
+            "Branch `{}` has no commits of its own on top of `{}`",
+            name,
+            info.upstream.label
+        );
+    }
+    for &oid in &commits {
+        if repo.find_commit(oid)?.parent_count() > 1 {
+            bail!(
+                "Branch `{}` contains merge commit `{}`\nLinearize it with `git rebase` first",
+                name,
+                git::short_hash(&oid.to_string())
+            );
+        }
+    }
+    Ok((commits, old_base))
+}
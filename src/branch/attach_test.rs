@@ -0,0 +1,108 @@
+use crate::core::test_helpers::TestRepo;
+
+/// Integration branch on an upstream that moved on after `legacy` was
+/// branched off it. `legacy` has two commits of its own.
+fn setup() -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let old_base = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("legacy", &old_base.to_string());
+    test_repo.switch_branch("legacy");
+    test_repo.commit("L1", "l1.txt");
+    test_repo.commit("L2", "l2.txt");
+    test_repo.switch_branch("integration");
+
+    let new_base = test_repo.add_remote_commits(&["U1"]);
+    test_repo.fetch_remote();
+    test_repo.reset_hard(new_base);
+    test_repo.commit("Int", "int.txt");
+    test_repo
+}
+
+#[test]
+fn attach_rebases_old_branch_and_weaves_it() {
+    let test_repo = setup();
+    let new_base = test_repo.find_remote_branch_target("origin/main");
+
+    test_repo
+        .in_dir(|| super::attach::run("legacy".to_string()))
+        .unwrap();
+
+    assert_eq!(test_repo.branch_names(), vec!["legacy"]);
+    let tip = test_repo.find_commit(test_repo.get_branch_target("legacy"));
+    assert_eq!(tip.summary().unwrap(), "L2");
+    let first = tip.parent(0).unwrap();
+    assert_eq!(first.summary().unwrap(), "L1");
+    assert_eq!(first.parent_id(0).unwrap(), new_base);
+    assert!(test_repo.workdir().join("l1.txt").exists());
+    assert!(test_repo.workdir().join("int.txt").exists());
+    assert!(
+        test_repo
+            .repo
+            .find_reference("refs/loom/attach/legacy")
+            .is_err(),
+        "temp ref is cleaned up"
+    );
+}
+
+#[test]
+fn attach_branch_on_current_base_weaves_it_unchanged() {
+    let test_repo = TestRepo::new_with_remote();
+    let base = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature", &base.to_string());
+    test_repo.switch_branch("feature");
+    let tip = test_repo.commit("F1", "f1.txt");
+    test_repo.switch_branch("integration");
+
+    test_repo
+        .in_dir(|| super::attach::run("feature".to_string()))
+        .unwrap();
+
+    assert_eq!(test_repo.branch_names(), vec!["feature"]);
+    assert_eq!(test_repo.get_branch_target("feature"), tip);
+}
+
+#[test]
+fn attach_remote_branch_creates_tracking_branch() {
+    let test_repo = setup();
+    let legacy_tip = test_repo.get_branch_target("legacy");
+    test_repo
+        .repo
+        .reference("refs/remotes/origin/legacy", legacy_tip, false, "test")
+        .unwrap();
+    test_repo.delete_branch("legacy");
+
+    test_repo
+        .in_dir(|| super::attach::run("origin/legacy".to_string()))
+        .unwrap();
+
+    assert_eq!(test_repo.branch_names(), vec!["legacy"]);
+    let branch = test_repo
+        .repo
+        .find_branch("legacy", git2::BranchType::Local)
+        .unwrap();
+    assert_eq!(
+        branch.upstream().unwrap().name().unwrap(),
+        Some("origin/legacy")
+    );
+    assert_eq!(
+        test_repo.find_remote_branch_target("origin/legacy"),
+        legacy_tip,
+        "remote branch is left alone"
+    );
+}
+
+#[test]
+fn attach_refuses_woven_branch() {
+    let test_repo = setup();
+    test_repo
+        .in_dir(|| super::attach::run("legacy".to_string()))
+        .unwrap();
+    let head = test_repo.head_oid();
+
+    let err = test_repo
+        .in_dir(|| super::attach::run("legacy".to_string()))
+        .unwrap_err();
+
+    assert!(err.to_string().contains("already woven"), "{}", err);
+    assert_eq!(test_repo.head_oid(), head);
+}
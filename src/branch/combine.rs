@@ -0,0 +1,206 @@
+use anyhow::{Result, bail};
+
+use crate::core::msg;
+use crate::core::repo::{self, TargetKind};
+use crate::core::weave::{self, BranchSection, IntegrationEntry, RebaseOutcome, Weave};
+use crate::git;
+
+use super::own_section;
+
+/// Combine two woven branches into one: the commits of `other` join
+/// `branch`'s section, in history order, and `other` is deleted — or, with
+/// `keep`, left at the tip of the combined branch.
+///
+/// The branches must be woven next to each other (same base) or stacked one
+/// on the other. Branches stacked on `other` move onto the combined branch.
+/// Everything is rewritten in a single rebase, aborted on conflict.
+pub fn run(branch: String, other: String, keep: bool) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "merge branches")?;
+    let info = repo::gather_repo_info(&repo, false, 1)?;
+
+    let name = repo::resolve_arg(&repo, &branch, &[TargetKind::Branch])?.expect_branch()?;
+    let other = repo::resolve_arg(&repo, &other, &[TargetKind::Branch])?.expect_branch()?;
+    if name == other {
+        bail!("Cannot merge `{}` with itself", name);
+    }
+
+    let mut graph = Weave::from_repo_with_info(&repo, &info)?;
+    let moved = combine_sections(&mut graph, &name, &other, keep)?;
+
+    let outcome = weave::run_rebase(
+        workdir,
+        Some(&graph.base_oid.to_string()),
+        &graph.checked_todo()?,
+    )?;
+    if matches!(outcome, RebaseOutcome::Conflicted) {
+        let _ = git::rebase_abort(workdir);
+        bail!(
+            "The commits of `{}` and `{}` conflict once combined — nothing was changed",
+            name,
+            other
+        );
+    }
+    if !keep {
+        git::branch_delete(workdir, &other)?;
+    }
+
+    msg::success(&format!(
+        "Merged `{}` into `{}` ({} {} moved{})",
+        other,
+        name,
+        moved,
+        if moved == 1 { "commit" } else { "commits" },
+        if keep {
+            format!(", `{}` kept at the tip", other)
+        } else {
+            String::new()
+        }
+    ));
+    Ok(())
+}
+
+/// Fold the section of `other` into the section of `name`, keeping the
+/// commits in history order, and leave a single merge of the result.
+/// Returns the number of commits of `other`.
+///
+/// Branches stacked in a single section already share their commits: only
+/// the lower one's ref moves to the tip of the upper one.
+pub(crate) fn combine_sections(
+    graph: &mut Weave,
+    name: &str,
+    other: &str,
+    keep: bool,
+) -> Result<usize> {
+    if let Some(moved) = combine_in_section(graph, name, other, keep) {
+        return Ok(moved);
+    }
+    let a = own_section(graph, name)?;
+    let b = own_section(graph, other)?;
+    let (a_label, b_label) = (a.label.clone(), b.label.clone());
+    let moved = b.commits.len();
+    // Stacked branches combine bottom first; side by side, `name` first
+    let other_first = a.reset_target == b_label;
+    let reset_target = if other_first {
+        b.reset_target.clone()
+    } else if b.reset_target == a_label || b.reset_target == a.reset_target {
+        a.reset_target.clone()
+    } else {
+        bail!(
+            "`{}` and `{}` do not start from the same base\n\
+             Only branches woven side by side, or stacked one on the other, can be merged",
+            name,
+            other
+        );
+    };
+
+    // The combined section takes the place of the first of the two, so it
+    // still comes before every section stacked on either
+    let position = |graph: &Weave, label: &str| {
+        graph
+            .branch_sections
+            .iter()
+            .position(|s| s.label == label)
+            .expect("section found above")
+    };
+    let first = position(graph, &a_label).min(position(graph, &b_label));
+    let b_section = graph.branch_sections.remove(position(graph, &b_label));
+    let mut combined = graph.branch_sections.remove(position(graph, &a_label));
+    if other_first {
+        let a_commits = std::mem::replace(&mut combined.commits, b_section.commits);
+        combined.commits.extend(a_commits);
+    } else {
+        combined.commits.extend(b_section.commits);
+    }
+    combined.reset_target = reset_target;
+    if keep {
+        combined.branch_names.extend(b_section.branch_names);
+    }
+    graph.branch_sections.insert(first, combined);
+    for section in &mut graph.branch_sections {
+        if section.reset_target == b_label {
+            section.reset_target = a_label.clone();
+        }
+    }
+
+    // One merge of the combined branch, where the later of the two was
+    let merges: Vec<usize> = graph
+        .integration_line
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| {
+            matches!(e, IntegrationEntry::Merge { label, .. } if *label == a_label || *label == b_label)
+        })
+        .map(|(i, _)| i)
+        .collect();
+    if let Some(&last) = merges.last() {
+        graph.integration_line[last] = IntegrationEntry::Merge {
+            original_oid: None,
+            label: a_label,
+        };
+        for &i in merges[..merges.len() - 1].iter().rev() {
+            graph.integration_line.remove(i);
+        }
+    }
+    Ok(moved)
+}
+
+/// Combine `name` and `other` when one is stacked on the other inside a
+/// single section. Returns the number of commits of `other` above `name`,
+/// or `None` when they are not in the same section.
+fn combine_in_section(graph: &mut Weave, name: &str, other: &str, keep: bool) -> Option<usize> {
+    // Where a branch ends in a section: the index of the commit it points
+    // to, or the number of commits for the section tip
+    let end = |section: &BranchSection, branch: &str| {
+        if section.branch_names.iter().any(|b| b == branch) {
+            return Some(section.commits.len());
+        }
+        section
+            .commits
+            .iter()
+            .position(|c| c.update_refs.iter().any(|r| r == branch))
+    };
+    let idx = graph
+        .branch_sections
+        .iter()
+        .position(|s| end(s, name).is_some() && end(s, other).is_some())?;
+    let section = &mut graph.branch_sections[idx];
+    let (name_end, other_end) = (end(section, name)?, end(section, other)?);
+    let moved = other_end.saturating_sub(name_end);
+
+    let (lower, upper_end) = if name_end < other_end {
+        (name, other_end)
+    } else {
+        (other, name_end)
+    };
+    let lower_end = end(section, lower)?;
+    if let Some(commit) = section.commits.get_mut(lower_end)
+        && name_end != other_end
+    {
+        commit.update_refs.retain(|r| r != lower);
+    }
+    // Put the lower branch where the upper one ends, if it must stay
+    if lower == name || keep {
+        match section.commits.get_mut(upper_end) {
+            Some(commit) => commit.update_refs.push(lower.to_string()),
+            None => section.branch_names.push(lower.to_string()),
+        }
+    }
+    if !keep {
+        if section.label == other {
+            // Renames the section and its merge after the kept branch
+            graph.reassign_branch(other, name);
+        } else if let Some(commit) = graph.branch_sections[idx]
+            .commits
+            .iter_mut()
+            .find(|c| c.update_refs.iter().any(|r| r == other))
+        {
+            commit.update_refs.retain(|r| r != other);
+        } else {
+            graph.branch_sections[idx]
+                .branch_names
+                .retain(|b| b != other);
+        }
+    }
+    Some(moved)
+}
@@ -0,0 +1,109 @@
+use crate::core::repo;
+use crate::core::test_helpers::TestRepo;
+
+/// Integration branch weaving `feature-a` (A1, A2) and `feature-b` (B1)
+/// side by side.
+fn setup() -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a1.txt");
+    test_repo.commit("A2", "a2.txt");
+    test_repo.create_branch_at("feature-b", &base_oid.to_string());
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1", "b1.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-a");
+    test_repo.merge_no_ff("feature-b");
+    test_repo
+}
+
+fn summaries(test_repo: &TestRepo, branch: &str, count: usize) -> Vec<String> {
+    let mut commit = test_repo.find_commit(test_repo.get_branch_target(branch));
+    let mut out = Vec::new();
+    for _ in 0..count {
+        out.push(commit.summary().unwrap().to_string());
+        commit = commit.parent(0).unwrap();
+    }
+    out
+}
+
+fn woven(test_repo: &TestRepo) -> Vec<String> {
+    let info = repo::gather_repo_info(&test_repo.repo, false, 1).unwrap();
+    let mut names: Vec<String> = info.branches.into_iter().map(|b| b.name).collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn merge_branches_side_by_side() {
+    let test_repo = setup();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+
+    test_repo
+        .in_dir(|| super::combine::run("feature-a".into(), "feature-b".into(), false))
+        .unwrap();
+
+    assert_eq!(
+        summaries(&test_repo, "feature-a", 3),
+        vec!["B1", "A2", "A1"]
+    );
+    let a1 = test_repo.find_commit(test_repo.get_branch_target("feature-a"));
+    let a1 = a1.parent(0).unwrap().parent(0).unwrap();
+    assert_eq!(a1.parent_id(0).unwrap(), base_oid);
+    assert!(!test_repo.branch_exists("feature-b"));
+    assert_eq!(woven(&test_repo), vec!["feature-a"]);
+    assert_eq!(test_repo.read_file("b1.txt"), "B1");
+}
+
+#[test]
+fn merge_branches_keep_leaves_other_at_tip() {
+    let test_repo = setup();
+
+    test_repo
+        .in_dir(|| super::combine::run("feature-a".into(), "feature-b".into(), true))
+        .unwrap();
+
+    assert_eq!(
+        test_repo.get_branch_target("feature-a"),
+        test_repo.get_branch_target("feature-b")
+    );
+    assert_eq!(
+        summaries(&test_repo, "feature-b", 3),
+        vec!["B1", "A2", "A1"]
+    );
+}
+
+#[test]
+fn merge_branches_stacked_in_one_section() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a1.txt");
+    test_repo.create_branch_at("feature-b", "feature-a");
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1", "b1.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-b");
+
+    test_repo
+        .in_dir(|| super::combine::run("feature-a".into(), "feature-b".into(), false))
+        .unwrap();
+
+    assert_eq!(summaries(&test_repo, "feature-a", 2), vec!["B1", "A1"]);
+    assert!(!test_repo.branch_exists("feature-b"));
+    assert_eq!(woven(&test_repo), vec!["feature-a"]);
+}
+
+#[test]
+fn merge_branches_with_itself_fails() {
+    let test_repo = setup();
+
+    let err = test_repo
+        .in_dir(|| super::combine::run("feature-a".into(), "feature-a".into(), false))
+        .unwrap_err();
+
+    assert!(err.to_string().contains("with itself"));
+}
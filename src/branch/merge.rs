@@ -16,8 +16,10 @@ struct MergeContext {
 ///
 /// If no branch is specified, shows an interactive picker with local branches
 /// not currently woven. With `--all`, also shows remote branches without a
-/// local counterpart.
-pub fn run(branch: Option<String>, all: bool) -> Result<()> {
+/// local counterpart. Before weaving, suggests stacking the branch on the
+/// woven branch it shares the most files with; `explain` prints the overlap
+/// matrix behind the suggestion.
+pub fn run(branch: Option<String>, all: bool, explain: bool) -> Result<()> {
     let repo = repo::open_repo()?;
     let workdir = repo::require_workdir(&repo, "merge")?;
     let git_dir = repo.path().to_path_buf();
@@ -28,6 +30,9 @@ pub fn run(branch: Option<String>, all: bool) -> Result<()> {
         None => pick_branch(&repo, &info, all)?,
     };
 
+    let tip = repo.revparse_single(&branch_name)?.peel_to_commit()?.id();
+    crate::overlap::advise_weave(&repo, &branch_name, tip, explain)?;
+
     // If this is a remote branch (contains '/'), create a local tracking branch
     let local_name = if branch_name.contains('/') {
         let local = repo::upstream_local_branch(&branch_name);
@@ -48,6 +53,7 @@ pub fn run(branch: Option<String>, all: bool) -> Result<()> {
         MergeOutcome::Conflicted => {
             let state = LoomState {
                 command: "merge".to_string(),
+                refs_before: Default::default(),
                 rollback: Rollback::default(),
                 context: serde_json::to_value(MergeContext {
                     branch_name: local_name,
@@ -20,7 +20,7 @@ fn merge_weaves_existing_branch() {
 
     // Merge feature-a into integration
     test_repo
-        .in_dir(|| super::merge::run(Some("feature-a".to_string()), false))
+        .in_dir(|| super::merge::run(Some("feature-a".to_string()), false, false))
         .unwrap();
 
     // HEAD should be a merge commit
@@ -54,7 +54,8 @@ fn merge_already_woven_errors() {
         .unwrap();
 
     // Try to merge it again — should error
-    let result = test_repo.in_dir(|| super::merge::run(Some("feature-a".to_string()), false));
+    let result =
+        test_repo.in_dir(|| super::merge::run(Some("feature-a".to_string()), false, false));
     assert!(result.is_err(), "merging already-woven branch should error");
 }
 
@@ -73,7 +74,7 @@ fn merge_then_unmerge_round_trip() {
 
     // Merge (weave)
     test_repo
-        .in_dir(|| super::merge::run(Some("feature-rt".to_string()), false))
+        .in_dir(|| super::merge::run(Some("feature-rt".to_string()), false, false))
         .unwrap();
 
     let info = repo::gather_repo_info(&test_repo.repo, false, 1).unwrap();
@@ -110,8 +111,8 @@ fn merge_nonexistent_branch_errors() {
     let test_repo = TestRepo::new_with_remote();
     test_repo.commit("A1", "a1.txt");
 
-    let result =
-        test_repo.in_dir(|| super::merge::run(Some("nonexistent-branch".to_string()), false));
+    let result = test_repo
+        .in_dir(|| super::merge::run(Some("nonexistent-branch".to_string()), false, false));
     assert!(result.is_err(), "merging nonexistent branch should error");
 }
 
@@ -139,7 +140,7 @@ fn merge_conflict_continue_weaves_branch() {
 
     // Merge pauses — must not return Err.
     let result =
-        test_repo.in_dir(|| super::merge::run(Some("feature-conflict".to_string()), false));
+        test_repo.in_dir(|| super::merge::run(Some("feature-conflict".to_string()), false, false));
     assert!(
         result.is_ok(),
         "merge should return Ok on conflict: {:?}",
@@ -202,7 +203,8 @@ fn merge_conflict_abort_restores_state() {
 
     let pre_merge_head = test_repo.head_oid();
 
-    let result = test_repo.in_dir(|| super::merge::run(Some("feature-abort".to_string()), false));
+    let result =
+        test_repo.in_dir(|| super::merge::run(Some("feature-abort".to_string()), false, false));
     assert!(
         result.is_ok(),
         "merge should return Ok on conflict: {:?}",
@@ -1,15 +1,21 @@
+pub mod attach;
+pub mod combine;
 pub mod merge;
 pub mod new;
+pub mod rebase;
+pub mod split;
 pub mod unmerge;
 
 use std::collections::HashSet;
 
 use git2::Repository;
+use regex::Regex;
 
-use anyhow::{Result, bail};
+use anyhow::{Context, Result, bail};
 
 use crate::core::msg;
 use crate::core::repo;
+use crate::core::weave::{BranchSection, Weave};
 
 /// Emit a warning if `name` starts with the configured hidden branch prefix.
 /// Despite the config key name `loom.hideBranchPattern`, this performs
@@ -25,6 +31,116 @@ pub(crate) fn warn_if_hidden(repo: &Repository, name: &str) {
     }
 }
 
+/// Turn free text into a branch name: lowercase ASCII letters and digits,
+/// every other run of characters becoming a single `-` ("Fix DB pool" →
+/// `fix-db-pool`). `/` is kept to allow namespaces.
+pub(crate) fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.trim().chars() {
+        if c.is_ascii_alphanumeric() || c == '/' {
+            if c == '/' && slug.ends_with('-') {
+                slug.pop();
+            }
+            if !(c == '/' && slug.ends_with('/')) {
+                slug.push(c.to_ascii_lowercase());
+            }
+        } else if !slug.is_empty() && !slug.ends_with(['-', '/']) {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches(['-', '/']).to_string()
+}
+
+/// The branch name for what the user typed at a prompt. Text with spaces
+/// is slugified, and the result is filled into `loom.branchTemplate`
+/// (`{slug}` for the name, `{user}` for the local part of `user.email`),
+/// unless it already follows it.
+pub(crate) fn name_from_input(repo: &Repository, input: &str) -> Result<String> {
+    let input = input.trim();
+    let slug = if input.contains(char::is_whitespace) {
+        slugify(input)
+    } else {
+        input.to_string()
+    };
+    let Some(template) = repo::branch_template(repo) else {
+        return Ok(slug);
+    };
+    let mut name = template.clone();
+    if name.contains("{user}") {
+        let user = branch_user(repo).context(
+            "loom.branchTemplate uses {user}, but neither user.email nor user.name is set",
+        )?;
+        name = name.replace("{user}", &user);
+    }
+    let (prefix, suffix) = name.split_once("{slug}").unwrap_or((name.as_str(), ""));
+    if slug.starts_with(prefix) && slug.ends_with(suffix) {
+        return Ok(slug);
+    }
+    Ok(name.replace("{slug}", &slug))
+}
+
+/// `{user}` of `loom.branchTemplate`: the local part of `user.email`, or
+/// else the slugified `user.name`.
+fn branch_user(repo: &Repository) -> Option<String> {
+    let config = repo.config().ok()?;
+    let email = config.get_string("user.email").ok();
+    let user = match email.as_deref().and_then(|e| e.split_once('@')) {
+        Some((local, _)) => local.to_string(),
+        None => config.get_string("user.name").ok()?,
+    };
+    Some(slugify(&user)).filter(|u| !u.is_empty())
+}
+
+/// Check a new branch name against the `loom.branchPattern` policy, which
+/// must match the whole name.
+pub(crate) fn check_name_policy(repo: &Repository, name: &str) -> Result<()> {
+    let Some(pattern) = repo::branch_pattern(repo) else {
+        return Ok(());
+    };
+    let policy = Regex::new(&format!("^(?:{})$", pattern))
+        .with_context(|| format!("Invalid loom.branchPattern `{}`", pattern))?;
+    if !policy.is_match(name) {
+        bail!(
+            "Branch name `{}` does not match loom.branchPattern `{}`",
+            name,
+            pattern
+        );
+    }
+    Ok(())
+}
+
+/// The weave section of `name`, which must hold only that branch's commits:
+/// no branch stacked inside it, below or above `name`.
+pub(crate) fn own_section<'a>(graph: &'a Weave, name: &str) -> Result<&'a BranchSection> {
+    let Some(section) = graph
+        .branch_sections
+        .iter()
+        .find(|s| s.branch_names.iter().any(|b| b == name))
+    else {
+        if graph.branch_sections.iter().any(|s| {
+            s.commits
+                .iter()
+                .any(|c| c.update_refs.iter().any(|r| r == name))
+        }) {
+            bail!(
+                "Branch `{}` has branches stacked on it in the same section\n\
+                 Only a branch with a section of its own can be changed this way",
+                name
+            );
+        }
+        bail!("Branch `{}` is not woven into the integration branch", name);
+    };
+    if let Some(below) = section.commits.iter().find(|c| !c.update_refs.is_empty()) {
+        bail!(
+            "Branch `{}` is stacked on `{}` in the same section\n\
+             Only a branch with a section of its own can be changed this way",
+            name,
+            below.update_refs.join("`, `")
+        );
+    }
+    Ok(section)
+}
+
 /// Determine if weaving is needed after branch creation.
 ///
 /// Weaving is needed when the branch target is on the first-parent line
@@ -104,3 +220,19 @@ mod unmerge_tests;
 #[cfg(test)]
 #[path = "merge_test.rs"]
 mod merge_tests;
+
+#[cfg(test)]
+#[path = "rebase_test.rs"]
+mod rebase_tests;
+
+#[cfg(test)]
+#[path = "attach_test.rs"]
+mod attach_tests;
+
+#[cfg(test)]
+#[path = "split_test.rs"]
+mod split_tests;
+
+#[cfg(test)]
+#[path = "combine_test.rs"]
+mod combine_tests;
@@ -1,17 +1,21 @@
 use anyhow::{Context, Result, bail};
 use git2::Repository;
 
+use crate::commit;
+use crate::core::graph;
 use crate::core::msg;
 use crate::core::repo;
 use crate::core::weave::{self, Weave};
 use crate::git;
 
-use super::{should_weave, warn_if_hidden};
+use super::{check_name_policy, name_from_input, should_weave, warn_if_hidden};
 
 /// Create a new branch at a target commit, weaving it into the integration branch
 /// if the target is between the merge-base and HEAD.
 ///
-/// If `name` is `None`, prompts interactively for a branch name.
+/// If `name` is `None`, prompts interactively for a branch name, which is
+/// slugified and filled into `loom.branchTemplate`. The name must follow the
+/// `loom.branchPattern` policy.
 /// If `target` is `None`, defaults to the merge-base (upstream base) commit.
 /// The target can be a commit hash, branch name, or shortID.
 ///
@@ -22,25 +26,7 @@ pub fn run(name: Option<String>, target: Option<String>) -> Result<()> {
     let repo = repo::open_repo()?;
     let workdir = repo::require_workdir(&repo, "create branch")?;
 
-    let name = match name {
-        Some(n) => n,
-        None => msg::input("Branch name", |s| {
-            if s.trim().is_empty() {
-                Err("Branch name cannot be empty")
-            } else {
-                Ok(())
-            }
-        })?,
-    };
-
-    let name = name.trim().to_string();
-    if name.is_empty() {
-        bail!("Branch name cannot be empty");
-    }
-
-    git::branch_validate_name(&name)?;
-
-    repo::ensure_branch_not_exists(&repo, &name)?;
+    let name = new_branch_name(&repo, name)?;
 
     // Gather repo info once (needed for merge-base default and weave check).
     // May fail if not on an integration branch — that's OK for plain branch creation.
@@ -66,7 +52,7 @@ pub fn run(name: Option<String>, target: Option<String>) -> Result<()> {
         let mut graph = Weave::from_repo(&repo)?;
         graph.weave_branch(&name);
 
-        let todo = graph.to_todo();
+        let todo = graph.checked_todo()?;
         if let Err(e) =
             weave::run_rebase_or_abort(workdir, Some(&graph.base_oid.to_string()), &todo)
         {
@@ -80,6 +66,68 @@ pub fn run(name: Option<String>, target: Option<String>) -> Result<()> {
     Ok(())
 }
 
+/// Create a branch at the merge-base and move the working changes into its
+/// first commit, weaving it: the rescue for work started before its branch.
+///
+/// The named `files` are committed (`zz` for all); without any, the staged
+/// changes are, or every change when nothing is staged. The commit itself
+/// is `loom commit` to the new branch, so a conflict pauses the same way.
+pub fn run_from_changes(
+    name: Option<String>,
+    message: Option<String>,
+    files: Vec<String>,
+    theme: &graph::Theme,
+) -> Result<()> {
+    let repo = repo::open_repo()?;
+    repo::require_workdir(&repo, "create branch")?;
+
+    let name = new_branch_name(&repo, name)?;
+    // `loom commit -b` would take a short ID for the branch it names
+    if let Ok(target) = repo::resolve_arg(&repo, &name, &[repo::TargetKind::Branch]) {
+        bail!(
+            "`{}` is the short ID of branch `{}`\nChoose another name",
+            name,
+            target.expect_branch()?
+        );
+    }
+
+    let files = if files.is_empty() && repo::verify_has_staged_changes(&repo).is_err() {
+        vec!["zz".to_string()]
+    } else {
+        files
+    };
+    commit::run(Some(name), message, false, false, vec![], files, theme)
+}
+
+/// The name of a branch to create: `name`, or the one typed at a prompt,
+/// checked against git's rules, the naming policy, and existing branches.
+fn new_branch_name(repo: &Repository, name: Option<String>) -> Result<String> {
+    let name = match name {
+        Some(n) => n,
+        None => {
+            let input = msg::input("Branch name", |s| {
+                if s.trim().is_empty() {
+                    Err("Branch name cannot be empty")
+                } else {
+                    Ok(())
+                }
+            })?;
+            name_from_input(repo, &input)?
+        }
+    };
+
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        bail!("Branch name cannot be empty");
+    }
+
+    git::branch_validate_name(&name)?;
+    check_name_policy(repo, &name)?;
+
+    repo::ensure_branch_not_exists(repo, &name)?;
+    Ok(name)
+}
+
 /// Resolve an optional target to a full commit hash.
 /// If no target, defaults to the merge-base (upstream base).
 fn resolve_commit(
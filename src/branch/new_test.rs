@@ -236,3 +236,120 @@ fn branch_inside_existing_branch_no_weave() {
         "HEAD should be unchanged when branching inside an existing side branch"
     );
 }
+
+// ── Naming policy ───────────────────────────────────────────────────────
+
+#[test]
+fn slugify_turns_text_into_branch_name() {
+    assert_eq!(super::slugify("Fix DB pool"), "fix-db-pool");
+    assert_eq!(super::slugify("  Add: login (v2)! "), "add-login-v2");
+    assert_eq!(super::slugify("team / Fix it"), "team/fix-it");
+}
+
+#[test]
+fn name_from_input_without_template_slugifies_text() {
+    let test_repo = TestRepo::new_with_remote();
+    let name = super::name_from_input(&test_repo.repo, "Fix DB pool").unwrap();
+    assert_eq!(name, "fix-db-pool");
+    // A name without spaces is kept as typed
+    let name = super::name_from_input(&test_repo.repo, "PROJ-42-login").unwrap();
+    assert_eq!(name, "PROJ-42-login");
+}
+
+#[test]
+fn name_from_input_fills_template() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.set_config("loom.branchTemplate", "{user}/{slug}");
+
+    let name = super::name_from_input(&test_repo.repo, "Fix DB pool").unwrap();
+    assert_eq!(name, "test/fix-db-pool");
+    // Input already following the template is kept
+    let name = super::name_from_input(&test_repo.repo, "test/fix-db-pool").unwrap();
+    assert_eq!(name, "test/fix-db-pool");
+}
+
+#[test]
+fn run_rejects_name_outside_policy() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.set_config("loom.branchPattern", "(feature|fix)/[a-z0-9-]+");
+
+    let err = test_repo
+        .in_dir(|| super::new::run(Some("misc-stuff".to_string()), None))
+        .unwrap_err();
+    assert!(err.to_string().contains("loom.branchPattern"), "{}", err);
+    assert!(!test_repo.branch_exists("misc-stuff"));
+
+    let result = test_repo.in_dir(|| super::new::run(Some("fix/db-pool".to_string()), None));
+    assert!(result.is_ok(), "branch::run failed: {:?}", result.err());
+    assert!(test_repo.branch_exists("fix/db-pool"));
+}
+
+#[test]
+fn run_rejects_invalid_policy() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.set_config("loom.branchPattern", "(feature");
+
+    let err = test_repo
+        .in_dir(|| super::new::run(Some("feature-a".to_string()), None))
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("Invalid loom.branchPattern"),
+        "{}",
+        err
+    );
+}
+
+// ── From changes ────────────────────────────────────────────────────────
+
+#[test]
+fn from_changes_commits_all_changes_onto_new_branch() {
+    let test_repo = TestRepo::new_with_remote();
+    let merge_base = test_repo.find_remote_branch_target("origin/main");
+    test_repo.write_file("pool.txt", "size = 8");
+
+    let result = test_repo.in_dir(|| {
+        super::new::run_from_changes(
+            Some("fix-db-pool".to_string()),
+            Some("Fix pool size".to_string()),
+            vec![],
+            &crate::core::graph::Theme::dark(),
+        )
+    });
+    assert!(
+        result.is_ok(),
+        "run_from_changes failed: {:?}",
+        result.err()
+    );
+
+    let tip = test_repo.get_branch_target("fix-db-pool");
+    let commit = test_repo.find_commit(tip);
+    assert_eq!(commit.summary(), Some("Fix pool size"));
+    assert_eq!(commit.parent_id(0).unwrap(), merge_base);
+    assert!(commit.tree().unwrap().get_name("pool.txt").is_some());
+
+    // Woven: HEAD merges the branch
+    let head = test_repo.find_commit(test_repo.head_oid());
+    assert_eq!(head.parent_count(), 2);
+    assert_eq!(head.parent_id(1).unwrap(), tip);
+}
+
+#[test]
+fn from_changes_rejects_existing_branch() {
+    let test_repo = TestRepo::new_with_remote();
+    let base = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base.to_string());
+    test_repo.write_file("pool.txt", "size = 8");
+
+    let err = test_repo
+        .in_dir(|| {
+            super::new::run_from_changes(
+                Some("feature-a".to_string()),
+                Some("msg".to_string()),
+                vec![],
+                &crate::core::graph::Theme::dark(),
+            )
+        })
+        .unwrap_err();
+    assert!(err.to_string().contains("already exists"), "{}", err);
+    assert_eq!(test_repo.get_branch_target("feature-a"), base);
+}
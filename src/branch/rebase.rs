@@ -0,0 +1,154 @@
+use anyhow::{Context, Result, bail};
+use git2::{Oid, Repository};
+
+use crate::core::clock;
+use crate::core::msg;
+use crate::core::repo::{self, TargetKind};
+use crate::core::weave::{self, Weave};
+use crate::git;
+
+/// Move a woven branch onto another base, out of the integration branch.
+///
+/// The branch's own commits are replayed onto `onto` first, in memory, so a
+/// conflict there changes nothing. The section is then removed from the
+/// weave (aborted on conflict, as with `unmerge`), and only when both steps
+/// succeeded is the branch ref moved to the replayed commits.
+pub fn run(branch: String, onto: String) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "rebase a branch")?;
+    let info = repo::gather_repo_info(&repo, false, 1)?;
+
+    let name = repo::resolve_arg(&repo, &branch, &[TargetKind::Branch])?.expect_branch()?;
+    let onto_oid = repo
+        .revparse_single(&onto)
+        .with_context(|| format!("Could not resolve `{}`", onto))?
+        .peel_to_commit()
+        .with_context(|| format!("`{}` does not point to a commit", onto))?
+        .id();
+
+    let mut graph = Weave::from_repo_with_info(&repo, &info)?;
+    let commits = section_commits(&graph, &name)?;
+    if commits.contains(&onto_oid) {
+        bail!("Cannot rebase `{}` onto one of its own commits", name);
+    }
+
+    let new_tip = replay(&repo, &commits, onto_oid).with_context(|| {
+        format!(
+            "Could not move `{}` onto `{}` — nothing was changed",
+            name, onto
+        )
+    })?;
+
+    graph.drop_branch(&name);
+    weave::run_rebase_or_abort(
+        workdir,
+        Some(&graph.base_oid.to_string()),
+        &graph.checked_todo()?,
+    )?;
+
+    repo.reference(
+        &format!("refs/heads/{}", name),
+        new_tip,
+        true,
+        &format!("loom: rebase-branch onto {}", onto),
+    )?;
+
+    msg::success(&format!(
+        "Moved `{}` onto `{}` ({} {}, now `{}`)",
+        name,
+        onto,
+        commits.len(),
+        if commits.len() == 1 {
+            "commit"
+        } else {
+            "commits"
+        },
+        git::short_hash(&new_tip.to_string())
+    ));
+    Ok(())
+}
+
+/// The commits of a woven branch, oldest first.
+///
+/// Only the top of a stack can be moved, and only when it doesn't share its
+/// tip with another branch: the others would lose their commits.
+pub(crate) fn section_commits(graph: &Weave, name: &str) -> Result<Vec<Oid>> {
+    let Some(section) = graph
+        .branch_sections
+        .iter()
+        .find(|s| s.branch_names.iter().any(|b| b == name))
+    else {
+        if graph.branch_sections.iter().any(|s| {
+            s.commits
+                .iter()
+                .any(|c| c.update_refs.iter().any(|r| r == name))
+        }) {
+            bail!(
+                "Branch `{}` has branches stacked on it\nMove the branches above it first",
+                name
+            );
+        }
+        bail!("Branch `{}` is not woven into the integration branch", name);
+    };
+    if section.branch_names.len() > 1 {
+        let others: Vec<&str> = section
+            .branch_names
+            .iter()
+            .filter(|b| *b != name)
+            .map(|b| b.as_str())
+            .collect();
+        bail!(
+            "Branch `{}` shares its tip with `{}`\nRename or drop the other branch first",
+            name,
+            others.join("`, `")
+        );
+    }
+
+    // In a stack, the section also holds the commits of the branches below,
+    // up to the last one tagged with their ref.
+    let start = section
+        .commits
+        .iter()
+        .rposition(|c| !c.update_refs.is_empty())
+        .map_or(0, |i| i + 1);
+    let commits: Vec<Oid> = section.commits[start..].iter().map(|c| c.oid).collect();
+    if commits.is_empty() {
+        bail!("Branch `{}` has no commits to move", name);
+    }
+    Ok(commits)
+}
+
+/// Cherry-pick `commits` onto `onto` without touching the working tree or
+/// any ref. Returns the new tip.
+pub(crate) fn replay(repo: &Repository, commits: &[Oid], onto: Oid) -> Result<Oid> {
+    let committer = clock::committer(repo)?;
+    let mut parent = repo.find_commit(onto)?;
+    for &oid in commits {
+        let commit = repo.find_commit(oid)?;
+        let mut index = repo.cherrypick_commit(&commit, &parent, 0, None)?;
+        if index.has_conflicts() {
+            let paths: Vec<String> = index
+                .conflicts()?
+                .filter_map(|c| c.ok())
+                .filter_map(|c| c.our.or(c.their).or(c.ancestor))
+                .map(|e| String::from_utf8_lossy(&e.path).to_string())
+                .collect();
+            bail!(
+                "`{}` conflicts with the new base in {}",
+                git::short_hash(&oid.to_string()),
+                paths.join(", ")
+            );
+        }
+        let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+        let new_oid = repo.commit(
+            None,
+            &commit.author(),
+            &committer,
+            commit.message_raw().unwrap_or(""),
+            &tree,
+            &[&parent],
+        )?;
+        parent = repo.find_commit(new_oid)?;
+    }
+    Ok(parent.id())
+}
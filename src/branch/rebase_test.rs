@@ -0,0 +1,87 @@
+use crate::core::test_helpers::TestRepo;
+
+/// Integration branch weaving `feature-a` and `feature-b`, plus a `release`
+/// branch off the upstream base with one commit writing `release_file`.
+fn setup(release_file: &str) -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    for name in ["feature-a", "feature-b", "release"] {
+        test_repo.create_branch_at(name, &base_oid.to_string());
+    }
+    test_repo.switch_branch("release");
+    test_repo.commit("R1", release_file);
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a1.txt");
+    test_repo.commit("A2", "a2.txt");
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1", "b1.txt");
+    test_repo.switch_branch("integration");
+    test_repo.commit("Int", "int.txt");
+    test_repo.merge_no_ff("feature-a");
+    test_repo.merge_no_ff("feature-b");
+    test_repo
+}
+
+#[test]
+fn rebase_branch_moves_section_onto_new_base() {
+    let test_repo = setup("r.txt");
+    let release_tip = test_repo.get_branch_target("release");
+
+    test_repo
+        .in_dir(|| super::rebase::run("feature-a".to_string(), "release".to_string()))
+        .unwrap();
+
+    assert_eq!(test_repo.branch_names(), vec!["feature-b"]);
+    assert!(!test_repo.workdir().join("a1.txt").exists());
+    assert!(test_repo.workdir().join("b1.txt").exists());
+    assert_eq!(test_repo.current_branch_name(), "integration");
+
+    let tip = test_repo.find_commit(test_repo.get_branch_target("feature-a"));
+    assert_eq!(tip.summary(), Some("A2"));
+    let first = tip.parent(0).unwrap();
+    assert_eq!(first.summary(), Some("A1"));
+    assert_eq!(first.parent_id(0).unwrap(), release_tip);
+}
+
+#[test]
+fn rebase_branch_conflict_changes_nothing() {
+    let test_repo = setup("a1.txt");
+    let head = test_repo.head_oid();
+    let feature_tip = test_repo.get_branch_target("feature-a");
+
+    let err = test_repo
+        .in_dir(|| super::rebase::run("feature-a".to_string(), "release".to_string()))
+        .unwrap_err();
+
+    assert!(
+        format!("{:#}", err).contains("a1.txt"),
+        "expected the conflicting file in: {:#}",
+        err
+    );
+    assert_eq!(test_repo.head_oid(), head);
+    assert_eq!(test_repo.get_branch_target("feature-a"), feature_tip);
+    assert_eq!(test_repo.branch_names(), vec!["feature-a", "feature-b"]);
+}
+
+#[test]
+fn rebase_branch_refuses_branch_with_stack_above() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a1.txt");
+    test_repo.create_branch("feature-b");
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1", "b1.txt");
+    test_repo.switch_branch("integration");
+    test_repo.commit("Int", "int.txt");
+    test_repo.merge_no_ff("feature-b");
+    let head = test_repo.head_oid();
+
+    let err = test_repo
+        .in_dir(|| super::rebase::run("feature-a".to_string(), "origin/main".to_string()))
+        .unwrap_err();
+
+    assert!(err.to_string().contains("stacked on it"), "{}", err);
+    assert_eq!(test_repo.head_oid(), head);
+}
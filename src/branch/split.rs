@@ -0,0 +1,240 @@
+use std::collections::BTreeSet;
+
+use anyhow::{Result, bail};
+use git2::{Oid, Repository};
+
+use crate::core::msg;
+use crate::core::repo::{self, Target, TargetKind};
+use crate::core::weave::{self, BranchSection, IntegrationEntry, RebaseOutcome, Weave};
+use crate::git;
+
+use super::{check_name_policy, name_from_input, own_section};
+
+/// Divide a woven branch in two: the `commits` picked (all short IDs or
+/// hashes; an interactive checklist when empty) move to the new branch
+/// `new_name`, the others stay on `branch`.
+///
+/// The new branch is woven next to `branch` when the two halves change
+/// different files, and stacked on it otherwise (or with `stack`). Both are
+/// rewritten in a single rebase, aborted on conflict.
+pub fn run(
+    branch: String,
+    new_name: Option<String>,
+    commits: Vec<String>,
+    stack: bool,
+) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "split a branch")?;
+    let info = repo::gather_repo_info(&repo, false, 1)?;
+
+    let name = repo::resolve_arg(&repo, &branch, &[TargetKind::Branch])?.expect_branch()?;
+    let mut graph = Weave::from_repo_with_info(&repo, &info)?;
+    let section = own_section(&graph, &name)?;
+    if section.commits.len() < 2 {
+        bail!("Branch `{}` needs at least two commits to be split", name);
+    }
+
+    let picked = if commits.is_empty() {
+        pick_commits(section)?
+    } else {
+        resolve_commits(&repo, &name, section, &commits)?
+    };
+    if picked.is_empty() {
+        bail!("No commit picked for the new branch");
+    }
+    if picked.len() == section.commits.len() {
+        bail!(
+            "Every commit of `{}` was picked\nUse `loom reword` to rename the branch instead",
+            name
+        );
+    }
+
+    let new_name = match new_name {
+        Some(n) => n.trim().to_string(),
+        None => {
+            let input = msg::input("New branch name", |s| {
+                if s.trim().is_empty() {
+                    Err("Branch name cannot be empty")
+                } else {
+                    Ok(())
+                }
+            })?;
+            name_from_input(&repo, &input)?
+        }
+    };
+    git::branch_validate_name(&new_name)?;
+    check_name_policy(&repo, &new_name)?;
+    repo::ensure_branch_not_exists(&repo, &new_name)?;
+
+    let stacked = stack || !independent(&repo, section, &picked)?;
+    split_section(&mut graph, &name, &new_name, &picked, stacked)?;
+
+    // The ref must exist for the rebase to move it; any commit will do
+    let tip = git::rev_parse(workdir, &name)?;
+    git::branch_create(workdir, &new_name, &tip)?;
+    let outcome = weave::run_rebase(
+        workdir,
+        Some(&graph.base_oid.to_string()),
+        &graph.checked_todo()?,
+    );
+    match outcome {
+        Ok(RebaseOutcome::Completed) => {}
+        Ok(RebaseOutcome::Conflicted) => {
+            let _ = git::rebase_abort(workdir);
+            let _ = git::branch_delete(workdir, &new_name);
+            bail!(
+                "The commits of `{}` depend on each other — nothing was changed\n\
+                 Pick commits that apply without the others",
+                name
+            );
+        }
+        Err(e) => {
+            let _ = git::branch_delete(workdir, &new_name);
+            return Err(e);
+        }
+    }
+
+    msg::success(&format!(
+        "Moved {} {} from `{}` to `{}`, {} it",
+        picked.len(),
+        if picked.len() == 1 {
+            "commit"
+        } else {
+            "commits"
+        },
+        name,
+        new_name,
+        if stacked {
+            "stacked on"
+        } else {
+            "woven next to"
+        }
+    ));
+    Ok(())
+}
+
+/// Resolve the commits given on the command line; each must be on the
+/// branch being split.
+fn resolve_commits(
+    repo: &Repository,
+    name: &str,
+    section: &BranchSection,
+    commits: &[String],
+) -> Result<BTreeSet<Oid>> {
+    let mut picked = BTreeSet::new();
+    for arg in commits {
+        let hash = match repo::resolve_arg(repo, arg, &[TargetKind::Commit])? {
+            Target::Commit(hash) => hash,
+            _ => unreachable!(),
+        };
+        let oid = Oid::from_str(&hash)?;
+        if !section.commits.iter().any(|c| c.oid == oid) {
+            bail!(
+                "Commit `{}` is not on branch `{}`",
+                git::short_hash(&hash),
+                name
+            );
+        }
+        picked.insert(oid);
+    }
+    Ok(picked)
+}
+
+/// Checklist of the section's commits, newest first like `loom status`.
+fn pick_commits(section: &BranchSection) -> Result<BTreeSet<Oid>> {
+    let items: Vec<String> = section
+        .commits
+        .iter()
+        .rev()
+        .map(|c| format!("{} {}", c.short_hash, c.message))
+        .collect();
+    msg::require_input("the commits to move to the new branch")?;
+    let selected = inquire::MultiSelect::new(
+        "Select the commits to move to the new branch:",
+        items.clone(),
+    )
+    .prompt()?;
+    Ok(section
+        .commits
+        .iter()
+        .rev()
+        .zip(&items)
+        .filter(|(_, item)| selected.contains(item))
+        .map(|(c, _)| c.oid)
+        .collect())
+}
+
+/// Whether the picked commits and the others change different files, so
+/// each half applies without the other.
+fn independent(repo: &Repository, section: &BranchSection, picked: &BTreeSet<Oid>) -> Result<bool> {
+    let mut moved = BTreeSet::new();
+    let mut kept = BTreeSet::new();
+    for commit in &section.commits {
+        let files = if picked.contains(&commit.oid) {
+            &mut moved
+        } else {
+            &mut kept
+        };
+        files.extend(repo::commit_file_paths(repo, commit.oid)?);
+    }
+    Ok(moved.is_disjoint(&kept))
+}
+
+/// Move the `picked` commits of `name`'s section into a new section for
+/// `new_name`, stacked on it or next to it, and merge the new section.
+pub(crate) fn split_section(
+    graph: &mut Weave,
+    name: &str,
+    new_name: &str,
+    picked: &BTreeSet<Oid>,
+    stacked: bool,
+) -> Result<()> {
+    let idx = graph
+        .branch_sections
+        .iter()
+        .position(|s| s.branch_names.iter().any(|b| b == name))
+        .ok_or_else(|| anyhow::anyhow!("Branch `{}` is not woven", name))?;
+    let label = graph.branch_sections[idx].label.clone();
+    let merge_pos = graph
+        .integration_line
+        .iter()
+        .position(|e| matches!(e, IntegrationEntry::Merge { label: l, .. } if *l == label));
+    let Some(merge_pos) = merge_pos else {
+        bail!(
+            "Branch `{}` is not merged into the integration branch directly\n\
+             Split the branch at the top of its stack instead",
+            name
+        );
+    };
+
+    let section = &mut graph.branch_sections[idx];
+    let (moved, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut section.commits)
+        .into_iter()
+        .partition(|c| picked.contains(&c.oid));
+    section.commits = kept;
+    let reset_target = if stacked {
+        label.clone()
+    } else {
+        section.reset_target.clone()
+    };
+    graph.branch_sections.insert(
+        idx + 1,
+        BranchSection {
+            reset_target,
+            commits: moved,
+            label: new_name.to_string(),
+            branch_names: vec![new_name.to_string()],
+        },
+    );
+
+    if stacked {
+        // The new branch contains the old one: merge it in its place
+        graph.integration_line[merge_pos] = IntegrationEntry::Merge {
+            original_oid: None,
+            label: new_name.to_string(),
+        };
+    } else {
+        graph.add_merge(new_name.to_string(), None, Some(merge_pos + 1));
+    }
+    Ok(())
+}
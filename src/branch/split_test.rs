@@ -0,0 +1,157 @@
+use crate::core::repo;
+use crate::core::test_helpers::TestRepo;
+
+/// Integration branch weaving `feature-a` with A1 (`shared.txt`), A2
+/// (`a2.txt`) and A3 (`shared.txt` again).
+fn setup() -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "shared.txt");
+    test_repo.commit("A2", "a2.txt");
+    test_repo.commit("A3", "shared.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-a");
+    test_repo
+}
+
+fn commit_by_summary(test_repo: &TestRepo, summary: &str) -> String {
+    let mut walk = test_repo.repo.revwalk().unwrap();
+    walk.push(test_repo.head_oid()).unwrap();
+    walk.map(|oid| oid.unwrap())
+        .find(|oid| test_repo.find_commit(*oid).summary() == Some(summary))
+        .unwrap()
+        .to_string()
+}
+
+fn summaries(test_repo: &TestRepo, branch: &str, count: usize) -> Vec<String> {
+    let mut commit = test_repo.find_commit(test_repo.get_branch_target(branch));
+    let mut out = Vec::new();
+    for _ in 0..count {
+        out.push(commit.summary().unwrap().to_string());
+        commit = commit.parent(0).unwrap();
+    }
+    out
+}
+
+#[test]
+fn split_branch_independent_commits_woven_side_by_side() {
+    let test_repo = setup();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    let a2 = commit_by_summary(&test_repo, "A2");
+
+    test_repo
+        .in_dir(|| {
+            super::split::run(
+                "feature-a".to_string(),
+                Some("feature-b".to_string()),
+                vec![a2],
+                false,
+            )
+        })
+        .unwrap();
+
+    assert_eq!(summaries(&test_repo, "feature-a", 2), vec!["A3", "A1"]);
+    let b_tip = test_repo.find_commit(test_repo.get_branch_target("feature-b"));
+    assert_eq!(b_tip.summary(), Some("A2"));
+    assert_eq!(b_tip.parent_id(0).unwrap(), base_oid);
+
+    let info = repo::gather_repo_info(&test_repo.repo, false, 1).unwrap();
+    let mut woven: Vec<String> = info.branches.into_iter().map(|b| b.name).collect();
+    woven.sort();
+    assert_eq!(woven, vec!["feature-a", "feature-b"]);
+    assert_eq!(test_repo.read_file("shared.txt"), "A3");
+    assert_eq!(test_repo.read_file("a2.txt"), "A2");
+}
+
+#[test]
+fn split_branch_dependent_commits_stacked() {
+    let test_repo = setup();
+    let a3 = commit_by_summary(&test_repo, "A3");
+
+    test_repo
+        .in_dir(|| {
+            super::split::run(
+                "feature-a".to_string(),
+                Some("feature-b".to_string()),
+                vec![a3],
+                false,
+            )
+        })
+        .unwrap();
+
+    assert_eq!(summaries(&test_repo, "feature-a", 2), vec!["A2", "A1"]);
+    let b_tip = test_repo.find_commit(test_repo.get_branch_target("feature-b"));
+    assert_eq!(b_tip.summary(), Some("A3"));
+    assert_eq!(
+        b_tip.parent_id(0).unwrap(),
+        test_repo.get_branch_target("feature-a")
+    );
+
+    // A single merge of the stack
+    let head = test_repo.find_commit(test_repo.head_oid());
+    assert_eq!(head.parent_id(1).unwrap(), b_tip.id());
+}
+
+#[test]
+fn split_branch_conflict_changes_nothing() {
+    let test_repo = setup();
+    let head = test_repo.head_oid();
+    let a1 = commit_by_summary(&test_repo, "A1");
+
+    // A3 edits the file A1 creates: stacked on A2 without A1, it conflicts
+    let err = test_repo
+        .in_dir(|| {
+            super::split::run(
+                "feature-a".to_string(),
+                Some("feature-b".to_string()),
+                vec![a1],
+                false,
+            )
+        })
+        .unwrap_err();
+
+    assert!(err.to_string().contains("depend on each other"), "{}", err);
+    assert_eq!(test_repo.head_oid(), head);
+    assert!(!test_repo.branch_exists("feature-b"));
+}
+
+#[test]
+fn split_branch_rejects_picking_every_commit() {
+    let test_repo = setup();
+    let commits: Vec<String> = ["A1", "A2", "A3"]
+        .iter()
+        .map(|s| commit_by_summary(&test_repo, s))
+        .collect();
+
+    let err = test_repo
+        .in_dir(|| {
+            super::split::run(
+                "feature-a".to_string(),
+                Some("feature-b".to_string()),
+                commits,
+                false,
+            )
+        })
+        .unwrap_err();
+    assert!(err.to_string().contains("Every commit"), "{}", err);
+}
+
+#[test]
+fn split_branch_rejects_commit_of_another_branch() {
+    let test_repo = setup();
+    let int = test_repo.commit("Int", "int.txt");
+
+    let err = test_repo
+        .in_dir(|| {
+            super::split::run(
+                "feature-a".to_string(),
+                Some("feature-b".to_string()),
+                vec![int.to_string()],
+                false,
+            )
+        })
+        .unwrap_err();
+    assert!(err.to_string().contains("is not on branch"), "{}", err);
+}
@@ -43,7 +43,7 @@ pub fn run(branch: Option<String>) -> Result<()> {
     let mut graph = Weave::from_repo_with_info(&repo, &info)?;
     graph.drop_branch(&branch_name);
 
-    let todo = graph.to_todo();
+    let todo = graph.checked_todo()?;
     weave::run_rebase_or_abort(workdir, Some(&graph.base_oid.to_string()), &todo)?;
 
     // Do NOT delete the branch ref — that's the key difference from `drop`
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::Result;
+use git2::Repository;
+
+use crate::core::repo;
+use crate::core::transaction;
+use crate::core::weave::{IntegrationEntry, Weave};
+use crate::git;
+
+/// Settings whose values are shown as-is; every other `loom.*` setting is
+/// only reported as set, since it may hold remote, branch, or path names.
+const PLAIN_SETTINGS: &[&str] = &[
+    "loom.remote-type",
+    "commit.gpgsign",
+    "core.autocrlf",
+    "merge.conflictStyle",
+    "pull.rebase",
+    "rebase.autoSquash",
+    "rebase.autoStash",
+    "rebase.updateRefs",
+    "rerere.enabled",
+];
+
+/// Settings that are only reported as set.
+const REDACTED_SETTINGS: &[&str] = &["core.hooksPath", "core.editor", "sequence.editor"];
+
+/// Global flags that take a value, so the next word is not the subcommand.
+const GLOBAL_VALUE_FLAGS: &[&str] = &["--theme", "--git-dir", "--work-tree"];
+
+/// How many recent command traces the report includes.
+const RECENT_COMMANDS: usize = 5;
+
+/// Print a report to attach to a GitHub issue.
+///
+/// The report holds the loom and git versions, the settings that change
+/// loom's behavior, the shape of the weave, and the latest commands. Commit
+/// messages, file paths, branch names, and command arguments are left out.
+pub fn run() -> Result<()> {
+    let repo = repo::open_repo()?;
+    print!("{}", report(&repo));
+    Ok(())
+}
+
+/// Build the redacted report for `repo`.
+pub fn report(repo: &Repository) -> String {
+    let mut out = String::from("## Environment\n\n");
+    out.push_str(&format!("- loom: {}\n", env!("CARGO_PKG_VERSION")));
+    let git_version = git::git_version().unwrap_or_else(|_| "unknown".to_string());
+    out.push_str(&format!(
+        "- git: {}\n",
+        git_version.trim_start_matches("git version ")
+    ));
+    out.push_str(&format!(
+        "- os: {} {}\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    ));
+    out.push_str(&format!(
+        "- repository: {}\n",
+        if repo.is_bare() { "bare" } else { "non-bare" }
+    ));
+
+    out.push_str("\n## Configuration\n\n");
+    let settings = settings(repo);
+    if settings.is_empty() {
+        out.push_str("No relevant settings\n");
+    }
+    for (key, value) in settings {
+        out.push_str(&format!("- {} = {}\n", key, value));
+    }
+
+    out.push_str("\n## Topology\n\n```\n");
+    match repo::gather_repo_info(repo, false, 0) {
+        Ok(info) => {
+            out.push_str(&format!(
+                "upstream: {} {} ahead of the base\n",
+                info.upstream.commits_ahead,
+                plural(info.upstream.commits_ahead, "commit")
+            ));
+            match Weave::from_repo_with_info(repo, &info) {
+                Ok(graph) => out.push_str(&topology(&graph)),
+                Err(e) => out.push_str(&format!("weave: {}\n", first_line(&e.to_string()))),
+            }
+            out.push_str(&format!(
+                "working tree: {} changed {}\n",
+                info.working_changes.len(),
+                plural(info.working_changes.len(), "file")
+            ));
+        }
+        Err(e) => out.push_str(&format!("status: {}\n", first_line(&e.to_string()))),
+    }
+    if let Ok(Some(state)) = transaction::load(repo.path()) {
+        out.push_str(&format!("paused: {}\n", state.command));
+    }
+    out.push_str("```\n");
+
+    out.push_str("\n## Recent commands\n\n```\n");
+    let commands = recent_commands(repo.path(), RECENT_COMMANDS);
+    if commands.is_empty() {
+        out.push_str("none\n");
+    }
+    for line in commands {
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out.push_str("```\n");
+    out
+}
+
+/// The relevant settings that are set, as `(key, value)` pairs sorted by key.
+fn settings(repo: &Repository) -> Vec<(String, String)> {
+    let Ok(config) = repo.config() else {
+        return Vec::new();
+    };
+    let mut settings: HashMap<String, String> = HashMap::new();
+    for key in PLAIN_SETTINGS {
+        if let Ok(value) = config.get_string(key) {
+            settings.insert(key.to_string(), value);
+        }
+    }
+    for key in REDACTED_SETTINGS {
+        if config.get_string(key).is_ok() {
+            settings.insert(key.to_string(), "<set>".to_string());
+        }
+    }
+    if let Ok(mut entries) = config.entries(Some("loom\\..*")) {
+        while let Some(Ok(entry)) = entries.next() {
+            if let Some(name) = entry.name() {
+                settings
+                    .entry(name.to_string())
+                    .or_insert_with(|| "<set>".to_string());
+            }
+        }
+    }
+    let mut settings: Vec<_> = settings.into_iter().collect();
+    settings.sort();
+    settings
+}
+
+/// Describe the weave with numbered sections instead of branch names:
+/// one line per section and one for the integration line.
+fn topology(graph: &Weave) -> String {
+    let names: HashMap<&str, String> = graph
+        .branch_sections
+        .iter()
+        .enumerate()
+        .map(|(i, section)| (section.label.as_str(), format!("s{}", i + 1)))
+        .collect();
+    let name = |label: &str| match label {
+        "onto" => "base".to_string(),
+        other => names.get(other).cloned().unwrap_or_else(|| "?".to_string()),
+    };
+
+    let mut out = String::new();
+    for section in &graph.branch_sections {
+        out.push_str(&format!(
+            "{}: {} {}, {} {}, on {}\n",
+            name(&section.label),
+            section.commits.len(),
+            plural(section.commits.len(), "commit"),
+            section.branch_names.len(),
+            plural(section.branch_names.len(), "branch"),
+            name(&section.reset_target)
+        ));
+    }
+    let line: Vec<String> = graph
+        .integration_line
+        .iter()
+        .map(|entry| match entry {
+            IntegrationEntry::Pick(_) => "commit".to_string(),
+            IntegrationEntry::Merge { label, .. } => format!("merge {}", name(label)),
+        })
+        .collect();
+    out.push_str(&format!(
+        "integration: {}\n",
+        if line.is_empty() {
+            "empty".to_string()
+        } else {
+            line.join(", ")
+        }
+    ));
+    out
+}
+
+/// One line per recent loom invocation, newest last: its redacted command
+/// line and how many git commands it ran, naming the ones that failed.
+fn recent_commands(git_dir: &Path, count: usize) -> Vec<String> {
+    let logs_dir = git_dir.join("loom").join("logs");
+    let mut paths: Vec<_> = std::fs::read_dir(&logs_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "log"))
+        .collect();
+    paths.sort();
+    let skip = paths.len().saturating_sub(count);
+    paths
+        .iter()
+        .skip(skip)
+        .filter_map(|p| std::fs::read_to_string(p).ok())
+        .flat_map(|content| summarize_log(&content))
+        .collect()
+}
+
+/// Summarize each invocation recorded in a trace log (a log holds several
+/// when `continue` or `abort` appended to it).
+fn summarize_log(content: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut header: Option<String> = None;
+    let mut commands = 0;
+    let mut failed: Vec<String> = Vec::new();
+    let mut flush =
+        |header: &mut Option<String>, commands: &mut usize, failed: &mut Vec<String>| {
+            if let Some(h) = header.take() {
+                let mut line = format!("{}  ({} git {}", h, commands, plural(*commands, "command"));
+                if !failed.is_empty() {
+                    line.push_str(&format!(", failed: {}", failed.join(", ")));
+                }
+                line.push(')');
+                out.push(line);
+            }
+            *commands = 0;
+            failed.clear();
+        };
+
+    for line in content.lines() {
+        if let Some(rest) = line.strip_prefix('[')
+            && let Some((timestamp, command_line)) = rest.split_once("] ")
+        {
+            flush(&mut header, &mut commands, &mut failed);
+            header = Some(format!(
+                "[{}] {}",
+                timestamp,
+                redact_command_line(command_line)
+            ));
+        } else if let Some(rest) = line.strip_prefix("  [")
+            && let Some((program, args)) = rest.split_once("] ")
+        {
+            commands += 1;
+            if line.ends_with(" FAILED") {
+                let sub = args.split_whitespace().next().unwrap_or("");
+                failed.push(format!("{} {}", program, sub).trim().to_string());
+            }
+        }
+    }
+    flush(&mut header, &mut commands, &mut failed);
+    out
+}
+
+/// Keep the subcommand and flags of a loom command line; every other
+/// argument (branch names, paths, messages) becomes `<arg>`.
+pub fn redact_command_line(command_line: &str) -> String {
+    let mut out = vec!["loom".to_string()];
+    let mut subcommand = false;
+    let mut global_value = false;
+    for word in command_line.split_whitespace().skip(1) {
+        if word.starts_with('-') {
+            let flag = word.split_once('=').map_or(word, |(flag, _)| flag);
+            out.push(flag.to_string());
+            global_value = !subcommand && flag == word && GLOBAL_VALUE_FLAGS.contains(&flag);
+            continue;
+        }
+        if !subcommand && !global_value {
+            subcommand = true;
+            out.push(word.to_string());
+        } else {
+            out.push("<arg>".to_string());
+        }
+        global_value = false;
+    }
+    out.join(" ")
+}
+
+fn first_line(s: &str) -> &str {
+    s.lines().next().unwrap_or("")
+}
+
+fn plural(n: usize, word: &str) -> String {
+    match (n, word) {
+        (1, _) => word.to_string(),
+        (_, "branch") => "branches".to_string(),
+        _ => format!("{}s", word),
+    }
+}
+
+#[cfg(test)]
+#[path = "bugreport_test.rs"]
+mod tests;
@@ -0,0 +1,90 @@
+use crate::core::test_helpers::TestRepo;
+
+#[test]
+fn report_describes_topology_without_names() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.create_branch("feature-secret");
+    test_repo.switch_branch("feature-secret");
+    test_repo.commit("Top secret change", "secret.txt");
+    test_repo.switch_branch("integration");
+    test_repo.commit("Int", "int.txt");
+    test_repo.merge_no_ff("feature-secret");
+    test_repo.write_file("private-notes.txt", "draft");
+
+    let report = super::report(&test_repo.repo);
+
+    assert!(
+        report.contains("s1: 1 commit, 1 branch, on base"),
+        "{}",
+        report
+    );
+    assert!(
+        report.contains("integration: commit, merge s1"),
+        "{}",
+        report
+    );
+    assert!(
+        report.contains("working tree: 1 changed file"),
+        "{}",
+        report
+    );
+    assert!(!report.contains("feature-secret"));
+    assert!(!report.contains("Top secret"));
+    assert!(!report.contains("private-notes"));
+}
+
+#[test]
+fn report_redacts_free_form_settings() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.set_config("loom.remote-type", "gerrit");
+    test_repo.set_config("loom.push-remote", "my-fork");
+    test_repo.set_config("core.hooksPath", "/home/someone/hooks");
+
+    let report = super::report(&test_repo.repo);
+
+    assert!(report.contains("- loom.remote-type = gerrit"), "{}", report);
+    assert!(report.contains("- loom.push-remote = <set>"), "{}", report);
+    assert!(report.contains("- core.hooksPath = <set>"), "{}", report);
+    assert!(!report.contains("my-fork"));
+    assert!(!report.contains("someone"));
+}
+
+#[test]
+fn redact_command_line_keeps_subcommand_and_flags() {
+    assert_eq!(
+        super::redact_command_line("/usr/bin/git-loom commit -b feature-a -m Secret file.txt"),
+        "loom commit -b <arg> -m <arg> <arg>"
+    );
+    assert_eq!(
+        super::redact_command_line("git-loom --theme dark fold --create=x a.txt"),
+        "loom --theme <arg> fold --create <arg>"
+    );
+    assert_eq!(super::redact_command_line("git-loom"), "loom");
+}
+
+#[test]
+fn summarize_log_counts_commands_and_failures() {
+    let log = "\
+[2026-01-01 10:00:00.000] /usr/bin/git-loom update
+================================================================================
+
+  [git] fetch --prune  [12ms]
+
+  [git] rebase --autostash origin/main  [30ms] FAILED
+    [stderr]
+CONFLICT in secret.txt
+
+[2026-01-01 10:01:00.000] /usr/bin/git-loom abort
+--------------------------------------------------------------------------------
+
+  [git] rebase --abort  [5ms]
+";
+
+    assert_eq!(
+        super::summarize_log(log),
+        vec![
+            "[2026-01-01 10:00:00.000] loom update  (2 git commands, failed: git rebase)",
+            "[2026-01-01 10:01:00.000] loom abort  (1 git command)",
+        ]
+    );
+}
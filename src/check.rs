@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use colored::Colorize;
+use git2::{Oid, Repository};
+
+use crate::core::graph::CheckState;
+use crate::core::repo::{self, TargetKind};
+use crate::core::{clock, msg};
+use crate::{explain, git};
+
+/// The cache is trimmed back to this many entries once it grows past twice
+/// that size.
+const MAX_ENTRIES: usize = 500;
+
+/// Run the `loom.checks` commands against the tip of a branch, in a
+/// temporary worktree so the working tree of the integration branch is left
+/// alone.
+///
+/// Every check runs, even after one fails, and the outcome of each is
+/// reported. A check that passed on the same tree is not run again: results
+/// are remembered by tree, so an unchanged branch (or one only reworded or
+/// rebased without conflicts) is not rechecked. `force` runs them all.
+/// Failures are remembered too, for `status --checks`, but always rerun.
+pub fn run(branch: String, force: bool) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "check a branch")?.to_path_buf();
+    let checks = repo::checks(&repo);
+    if checks.is_empty() {
+        bail!("No checks configured\nAdd one with: git config --add loom.checks \"cargo test\"");
+    }
+    let name = repo::resolve_arg(&repo, &branch, &[TargetKind::Branch])?.expect_branch()?;
+    let tip = repo
+        .revparse_single(&format!("refs/heads/{}", name))?
+        .peel_to_commit()?;
+    let tree = tip.tree_id();
+
+    println!(
+        "{} {} {}",
+        "Checking".bold(),
+        format!("`{}`", name).yellow(),
+        format!(
+            "at {} ({} {})",
+            git::short_hash(&tip.id().to_string()),
+            checks.len(),
+            if checks.len() == 1 { "check" } else { "checks" }
+        )
+        .dimmed()
+    );
+
+    let cache = Cache::load(&repo);
+    let mut pending = Vec::new();
+    for check in &checks {
+        if !force && cache.latest(tree, check).is_some_and(|(passed, _)| passed) {
+            msg::success(&format!("Check `{}` passed (cached)", check));
+        } else {
+            pending.push(check);
+        }
+    }
+    if pending.is_empty() || explain::in_sandbox() {
+        return Ok(());
+    }
+
+    let failed = in_worktree(&workdir, tip.id(), |dir| {
+        let mut failed = 0;
+        for check in &pending {
+            let result = run_check(dir, check);
+            record(&repo, tree, check, result.is_ok());
+            if let Err(e) = result {
+                msg::error(&e.to_string());
+                failed += 1;
+            }
+        }
+        failed
+    })?;
+    if failed > 0 {
+        bail!("{} of {} checks failed on `{}`", failed, checks.len(), name);
+    }
+    Ok(())
+}
+
+/// Check out `tip` in a temporary detached worktree of `workdir`, call `f`
+/// with its path, and remove the worktree again.
+pub fn in_worktree<T>(workdir: &Path, tip: Oid, f: impl FnOnce(&Path) -> T) -> Result<T> {
+    let dir = tempfile::tempdir()?;
+    let checkout = dir.path().join("check");
+    let checkout_str = checkout.to_string_lossy().to_string();
+    git::run_git(
+        workdir,
+        &[
+            "worktree",
+            "add",
+            "--detach",
+            &checkout_str,
+            &tip.to_string(),
+        ],
+    )?;
+
+    let result = f(&checkout);
+    let _ = git::run_git(workdir, &["worktree", "remove", "--force", &checkout_str]);
+    Ok(result)
+}
+
+/// Run one check command through the shell in `dir`, its output going to
+/// the terminal.
+pub fn run_check(dir: &Path, check: &str) -> Result<()> {
+    msg::notice(&format!("Running `{}`", check));
+    let status = if cfg!(windows) {
+        Command::new("cmd")
+            .args(["/C", check])
+            .current_dir(dir)
+            .status()
+    } else {
+        Command::new("sh")
+            .args(["-c", check])
+            .current_dir(dir)
+            .status()
+    }
+    .with_context(|| format!("Could not run `{}`", check))?;
+    if !status.success() {
+        bail!("Check `{}` failed", check);
+    }
+    msg::success(&format!("Check `{}` passed", check));
+    Ok(())
+}
+
+/// The results of past checks, read from `.git/loom/checks`: one line per
+/// run, `<tree> <time> <pass|fail> <command>`, oldest first.
+#[derive(Debug, Default)]
+pub struct Cache {
+    results: HashMap<(Oid, String), (bool, i64)>,
+}
+
+impl Cache {
+    /// Read the cache of `repo`. A missing or unreadable cache is empty.
+    pub fn load(repo: &Repository) -> Self {
+        let mut cache = Cache::default();
+        let content = std::fs::read_to_string(cache_path(repo)).unwrap_or_default();
+        for line in content.lines() {
+            if let Some((tree, time, passed, check)) = parse_entry(line) {
+                cache
+                    .results
+                    .insert((tree, check.to_string()), (passed, time));
+            }
+        }
+        cache
+    }
+
+    /// Whether `check` passed the last time it ran on `tree`, and when.
+    pub fn latest(&self, tree: Oid, check: &str) -> Option<(bool, i64)> {
+        self.results.get(&(tree, check.to_string())).copied()
+    }
+
+    /// The combined result of `checks` on `tree`, and when the latest of them
+    /// ran: failed when any failed, passed when all passed, unknown when
+    /// some never ran there. With no `checks`, whatever ran on `tree` counts.
+    pub fn summarize(&self, tree: Oid, checks: &[String]) -> (CheckState, Option<i64>) {
+        let results: Vec<Option<(bool, i64)>> = if checks.is_empty() {
+            self.results
+                .iter()
+                .filter(|((t, _), _)| *t == tree)
+                .map(|(_, result)| Some(*result))
+                .collect()
+        } else {
+            checks
+                .iter()
+                .map(|check| self.latest(tree, check))
+                .collect()
+        };
+        let time = results.iter().flatten().map(|(_, time)| *time).max();
+        if results.iter().flatten().any(|(passed, _)| !passed) {
+            (CheckState::Failed, time)
+        } else if !results.is_empty() && results.iter().all(Option::is_some) {
+            (CheckState::Passed, time)
+        } else {
+            (CheckState::Unknown, None)
+        }
+    }
+}
+
+/// Drop the cached results for trees that no longer exist (after a `git gc`
+/// pruned them). Returns how many entries were dropped and the bytes they
+/// took; with `dry_run`, the cache is left as it was.
+pub fn prune(repo: &Repository, dry_run: bool) -> Result<(usize, u64)> {
+    let path = cache_path(repo);
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok((0, 0));
+    };
+    let odb = repo.odb()?;
+    let (kept, dropped): (Vec<&str>, Vec<&str>) = content
+        .lines()
+        .partition(|line| parse_entry(line).is_some_and(|(tree, _, _, _)| odb.exists(tree)));
+    let bytes = dropped.iter().map(|line| line.len() as u64 + 1).sum();
+    if !dry_run && !dropped.is_empty() {
+        let content = kept
+            .iter()
+            .map(|line| format!("{}\n", line))
+            .collect::<String>();
+        std::fs::write(&path, content)?;
+    }
+    Ok((dropped.len(), bytes))
+}
+
+/// Split a cache line into its tree, time, outcome, and command.
+fn parse_entry(line: &str) -> Option<(Oid, i64, bool, &str)> {
+    let mut parts = line.splitn(4, ' ');
+    let tree = Oid::from_str(parts.next()?).ok()?;
+    let time = parts.next()?.parse().ok()?;
+    let passed = match parts.next()? {
+        "pass" => true,
+        "fail" => false,
+        _ => return None,
+    };
+    Some((tree, time, passed, parts.next()?))
+}
+
+/// Remember how `check` did on `tree`. The cache is best-effort: a
+/// repository that can't be written to just isn't cached.
+fn record(repo: &Repository, tree: Oid, check: &str, passed: bool) {
+    let outcome = if passed { "pass" } else { "fail" };
+    let _ = append(
+        &cache_path(repo),
+        &format!("{} {} {} {}", tree, clock::now(), outcome, check),
+    );
+}
+
+fn append(path: &Path, line: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", line)?;
+    drop(file);
+
+    let content = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.len() > 2 * MAX_ENTRIES {
+        std::fs::write(path, lines[lines.len() - MAX_ENTRIES..].join("\n") + "\n")?;
+    }
+    Ok(())
+}
+
+/// The cache lives in the common git directory: linked worktrees share the
+/// trees, and so the results.
+fn cache_path(repo: &Repository) -> PathBuf {
+    repo.commondir().join("loom").join("checks")
+}
+
+#[cfg(test)]
+#[path = "check_test.rs"]
+mod tests;
@@ -0,0 +1,131 @@
+use crate::core::graph::CheckState;
+use crate::core::test_helpers::TestRepo;
+
+/// A `feature-a` branch with one commit adding `f.txt`.
+fn setup_branch() -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.create_branch("feature-a");
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("F1", "f.txt");
+    test_repo.switch_branch("integration");
+    test_repo
+}
+
+fn add_check(test_repo: &TestRepo, check: &str) {
+    crate::git::run_git(
+        test_repo.workdir().as_path(),
+        &["config", "--add", "loom.checks", check],
+    )
+    .unwrap();
+}
+
+#[test]
+fn check_runs_against_the_branch_tip() {
+    let test_repo = setup_branch();
+    add_check(&test_repo, "test -f f.txt");
+
+    let result = test_repo.in_dir(|| super::run("feature-a".to_string(), false));
+    assert!(result.is_ok(), "check failed: {:?}", result.err());
+}
+
+#[test]
+fn check_reports_every_failure() {
+    let test_repo = setup_branch();
+    add_check(&test_repo, "false");
+    add_check(&test_repo, "test -f missing.txt");
+    add_check(&test_repo, "true");
+
+    let err = test_repo
+        .in_dir(|| super::run("feature-a".to_string(), false))
+        .unwrap_err();
+    assert_eq!(err.to_string(), "2 of 3 checks failed on `feature-a`");
+}
+
+#[test]
+fn check_skips_checks_that_passed_on_the_same_tree() {
+    let test_repo = setup_branch();
+    let marker = test_repo.workdir().join("runs.txt");
+    add_check(&test_repo, &format!("echo run >> '{}'", marker.display()));
+
+    for _ in 0..2 {
+        test_repo
+            .in_dir(|| super::run("feature-a".to_string(), false))
+            .unwrap();
+    }
+    assert_eq!(std::fs::read_to_string(&marker).unwrap(), "run\n");
+
+    test_repo
+        .in_dir(|| super::run("feature-a".to_string(), true))
+        .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&marker).unwrap(),
+        "run\nrun\n",
+        "--force should run the check again"
+    );
+}
+
+#[test]
+fn check_does_not_cache_failures() {
+    let test_repo = setup_branch();
+    let marker = test_repo.workdir().join("runs.txt");
+    add_check(
+        &test_repo,
+        &format!("echo run >> '{}'; false", marker.display()),
+    );
+
+    for _ in 0..2 {
+        assert!(
+            test_repo
+                .in_dir(|| super::run("feature-a".to_string(), false))
+                .is_err()
+        );
+    }
+    assert_eq!(std::fs::read_to_string(&marker).unwrap(), "run\nrun\n");
+}
+
+#[test]
+fn check_without_checks_configured_fails() {
+    let test_repo = setup_branch();
+
+    let err = test_repo
+        .in_dir(|| super::run("feature-a".to_string(), false))
+        .unwrap_err();
+    assert!(err.to_string().contains("No checks configured"));
+}
+
+#[test]
+fn cache_summarizes_the_latest_result_of_each_check() {
+    let test_repo = setup_branch();
+    add_check(&test_repo, "true");
+    let tree = test_repo
+        .find_commit(test_repo.get_branch_target("feature-a"))
+        .tree_id();
+    let checks = vec!["true".to_string(), "test -f f.txt".to_string()];
+
+    let summary = super::Cache::load(&test_repo.repo).summarize(tree, &checks);
+    assert_eq!(summary, (CheckState::Unknown, None));
+
+    test_repo
+        .in_dir(|| super::run("feature-a".to_string(), false))
+        .unwrap();
+    let summary = super::Cache::load(&test_repo.repo).summarize(tree, &checks);
+    assert_eq!(summary.0, CheckState::Unknown, "`test -f f.txt` never ran");
+
+    add_check(&test_repo, "test -f f.txt");
+    test_repo
+        .in_dir(|| super::run("feature-a".to_string(), false))
+        .unwrap();
+    let summary = super::Cache::load(&test_repo.repo).summarize(tree, &checks);
+    assert_eq!(summary.0, CheckState::Passed);
+    assert!(summary.1.is_some());
+
+    add_check(&test_repo, "false");
+    let _ = test_repo.in_dir(|| super::run("feature-a".to_string(), false));
+    let cache = super::Cache::load(&test_repo.repo);
+    assert_eq!(cache.summarize(tree, &checks).0, CheckState::Passed);
+    assert_eq!(
+        cache.summarize(tree, &[]).0,
+        CheckState::Failed,
+        "without configured checks, every cached result counts"
+    );
+}
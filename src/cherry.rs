@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result, bail};
+use colored::Colorize;
+use git2::{Oid, Repository, RevparseMode};
+
+use crate::core::repo;
+use crate::core::weave::{IntegrationEntry, Weave};
+use crate::git;
+
+/// One local commit, and its upstream equivalent if it has one.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CherryCommit {
+    pub oid: Oid,
+    pub upstream: Option<Oid>,
+}
+
+/// The commits of one woven branch (or of the integration line itself),
+/// oldest first.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BranchCherry {
+    pub name: String,
+    pub commits: Vec<CherryCommit>,
+}
+
+/// Show which commits of the weave already have an equivalent upstream.
+///
+/// `upstream` is a revision (the commits it has that HEAD doesn't, as with
+/// `git cherry`) or a `<from>..<to>` range; it defaults to the upstream the
+/// integration branch tracks. Commits are matched by patch-id, so a commit
+/// rebased or cherry-picked upstream still counts. Each branch lists its
+/// commits with ✓ (upstream) or ✗ (not yet), which tells what the next
+/// `loom update` will drop.
+pub fn run(upstream: Option<String>) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let info = repo::gather_repo_info(&repo, false, 1)?;
+    let upstream = match upstream {
+        Some(upstream) => upstream,
+        None if info.upstream.local_only => bail!(
+            "Branch `{}` has no upstream tracking branch\n\
+             Pass the upstream to compare with: `loom cherry <upstream>`",
+            info.branch_name
+        ),
+        None => info.upstream.label.clone(),
+    };
+    let (count, branches) = report(&repo, &info, &upstream)?;
+
+    println!(
+        "{} {} {}",
+        "Comparing the weave with".bold(),
+        format!("`{}`", upstream).yellow(),
+        format!("({} upstream {})", count, plural(count, "commit")).dimmed()
+    );
+    let mut total = 0;
+    let mut picked = 0;
+    for branch in &branches {
+        let upstreamed = branch
+            .commits
+            .iter()
+            .filter(|c| c.upstream.is_some())
+            .count();
+        total += branch.commits.len();
+        picked += upstreamed;
+        let note = if upstreamed == branch.commits.len() {
+            "all upstream".green().to_string()
+        } else {
+            format!("{} of {} upstream", upstreamed, branch.commits.len())
+                .dimmed()
+                .to_string()
+        };
+        println!("{} {}", branch.name.cyan().bold(), note);
+        for entry in &branch.commits {
+            let commit = repo.find_commit(entry.oid)?;
+            let short = git::short_hash(&entry.oid.to_string()).to_string();
+            let summary = commit.summary().unwrap_or("");
+            match entry.upstream {
+                Some(oid) => println!(
+                    "  {} {} {} {}",
+                    "✓".green(),
+                    short.dimmed(),
+                    summary,
+                    format!("(as {})", git::short_hash(&oid.to_string())).dimmed()
+                ),
+                None => println!("  {} {} {}", "✗".yellow(), short.dimmed(), summary),
+            }
+        }
+    }
+
+    if total == 0 {
+        println!("No commits on the integration branch yet");
+    } else if picked == 0 {
+        println!("None of the {} local commits are upstream", total);
+    } else {
+        println!(
+            "{} of {} local {} already upstream; the next `loom update` drops {}",
+            picked,
+            total,
+            if total == 1 {
+                "commit is"
+            } else {
+                "commits are"
+            },
+            if picked == 1 { "it" } else { "them" }
+        );
+    }
+    Ok(())
+}
+
+/// Match the commits of the weave against `upstream` by patch-id. Returns
+/// how many upstream commits were compared, and the weave's commits grouped
+/// by branch in weave order, the integration line's own commits last.
+pub fn report(
+    repo: &Repository,
+    info: &repo::RepoInfo,
+    upstream: &str,
+) -> Result<(usize, Vec<BranchCherry>)> {
+    let upstream_ids = upstream_patch_ids(repo, upstream)?;
+    let graph = Weave::from_repo_with_info(repo, info)?;
+    let matched = |oid: Oid| -> Result<CherryCommit> {
+        let upstream = patch_id(repo, oid)?.and_then(|id| upstream_ids.get(&id).copied());
+        Ok(CherryCommit { oid, upstream })
+    };
+
+    let mut branches = Vec::new();
+    for section in &graph.branch_sections {
+        branches.push(BranchCherry {
+            name: section.branch_names.join(", "),
+            commits: section
+                .commits
+                .iter()
+                .map(|c| matched(c.oid))
+                .collect::<Result<_>>()?,
+        });
+    }
+    let loose = graph
+        .integration_line
+        .iter()
+        .filter_map(|entry| match entry {
+            IntegrationEntry::Pick(c) => Some(matched(c.oid)),
+            IntegrationEntry::Merge { .. } => None,
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if !loose.is_empty() {
+        branches.push(BranchCherry {
+            name: info.branch_name.clone(),
+            commits: loose,
+        });
+    }
+    Ok((upstream_ids.len(), branches))
+}
+
+/// The patch-ids of the upstream commits, mapped to the commit carrying
+/// each. A `<from>..<to>` range walks `to` down to `from`; a single
+/// revision walks the commits it has that HEAD doesn't.
+fn upstream_patch_ids(repo: &Repository, upstream: &str) -> Result<HashMap<Oid, Oid>> {
+    let spec = repo
+        .revparse(upstream)
+        .with_context(|| format!("Could not resolve `{}`", upstream))?;
+    if spec.mode().contains(RevparseMode::MERGE_BASE) {
+        bail!(
+            "`{}` is a symmetric range\nUse `<from>..<to>` or a single revision",
+            upstream
+        );
+    }
+    let (to, hide) = if spec.mode().contains(RevparseMode::RANGE) {
+        let (Some(from), Some(to)) = (spec.from(), spec.to()) else {
+            bail!("`{}` needs both ends: `<from>..<to>`", upstream);
+        };
+        (to.peel_to_commit()?.id(), from.peel_to_commit()?.id())
+    } else {
+        let Some(to) = spec.from() else {
+            bail!("Could not resolve `{}`", upstream);
+        };
+        (to.peel_to_commit()?.id(), repo::head_oid(repo)?)
+    };
+
+    let mut walk = repo.revwalk()?;
+    walk.push(to)?;
+    walk.hide(hide)?;
+    let mut ids = HashMap::new();
+    for oid in walk {
+        let oid = oid?;
+        if let Some(id) = patch_id(repo, oid)? {
+            ids.entry(id).or_insert(oid);
+        }
+    }
+    Ok(ids)
+}
+
+/// The patch-id of a commit's changes (as `git patch-id --stable`), or
+/// `None` for merges and commits that change nothing: those never match.
+fn patch_id(repo: &Repository, oid: Oid) -> Result<Option<Oid>> {
+    let commit = repo.find_commit(oid)?;
+    if commit.parent_count() != 1 {
+        return Ok(None);
+    }
+    let diff = repo.diff_tree_to_tree(
+        Some(&commit.parent(0)?.tree()?),
+        Some(&commit.tree()?),
+        None,
+    )?;
+    if diff.deltas().len() == 0 {
+        return Ok(None);
+    }
+    Ok(Some(diff.patchid(None)?))
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        word.to_string()
+    } else {
+        format!("{}s", word)
+    }
+}
+
+#[cfg(test)]
+#[path = "cherry_test.rs"]
+mod tests;
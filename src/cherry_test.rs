@@ -0,0 +1,109 @@
+use crate::core::repo;
+use crate::core::test_helpers::TestRepo;
+
+/// `feature-a` (F1, F2) and `feature-b` (B1) woven, plus a loose commit on
+/// the integration branch. Returns the OIDs of F1, B1, and the loose commit.
+fn setup_weave(test_repo: &TestRepo) -> (git2::Oid, git2::Oid, git2::Oid) {
+    let base = test_repo.head_oid();
+    test_repo.create_branch_at_commit("feature-a", base);
+    test_repo.switch_branch("feature-a");
+    let f1 = test_repo.commit("F1", "f1.txt");
+    test_repo.commit("F2", "f2.txt");
+    test_repo.create_branch_at_commit("feature-b", base);
+    test_repo.switch_branch("feature-b");
+    let b1 = test_repo.commit("B1", "b1.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-a");
+    test_repo.merge_no_ff("feature-b");
+    let loose = test_repo.commit("Loose", "loose.txt");
+    (f1, b1, loose)
+}
+
+fn report(test_repo: &TestRepo, upstream: &str) -> (usize, Vec<super::BranchCherry>) {
+    let info = repo::gather_repo_info(&test_repo.repo, false, 1).unwrap();
+    super::report(&test_repo.repo, &info, upstream).unwrap()
+}
+
+fn marks(branches: &[super::BranchCherry]) -> Vec<(String, Vec<bool>)> {
+    branches
+        .iter()
+        .map(|b| {
+            (
+                b.name.clone(),
+                b.commits.iter().map(|c| c.upstream.is_some()).collect(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn cherry_marks_commits_picked_upstream() {
+    let test_repo = TestRepo::new_with_remote();
+    let (f1, b1, _) = setup_weave(&test_repo);
+    let picked_f1 = test_repo.cherry_pick_to_remote(f1, "F1 (#3)");
+    test_repo.cherry_pick_to_remote(b1, "B1 (#4)");
+    test_repo.fetch_remote();
+
+    let (count, branches) = report(&test_repo, "origin/main");
+
+    assert_eq!(count, 2);
+    assert_eq!(
+        marks(&branches),
+        vec![
+            ("feature-a".to_string(), vec![true, false]),
+            ("feature-b".to_string(), vec![true]),
+            ("integration".to_string(), vec![false]),
+        ]
+    );
+    assert_eq!(branches[0].commits[0].upstream, Some(picked_f1));
+}
+
+#[test]
+fn cherry_with_nothing_upstream_marks_every_commit_local() {
+    let test_repo = TestRepo::new_with_remote();
+    setup_weave(&test_repo);
+
+    let (count, branches) = report(&test_repo, "origin/main");
+
+    assert_eq!(count, 0);
+    assert!(
+        branches
+            .iter()
+            .flat_map(|b| &b.commits)
+            .all(|c| c.upstream.is_none())
+    );
+}
+
+#[test]
+fn cherry_compares_with_a_range() {
+    let test_repo = TestRepo::new_with_remote();
+    let (f1, b1, _) = setup_weave(&test_repo);
+    let picked_f1 = test_repo.cherry_pick_to_remote(f1, "F1 (#3)");
+    test_repo.cherry_pick_to_remote(b1, "B1 (#4)");
+    test_repo.fetch_remote();
+
+    let (count, branches) = report(&test_repo, &format!("{}..origin/main", picked_f1));
+
+    assert_eq!(count, 1, "only B1 is in the range");
+    assert_eq!(marks(&branches)[0].1, vec![false, false]);
+    assert_eq!(marks(&branches)[1].1, vec![true]);
+}
+
+#[test]
+fn cherry_rejects_a_symmetric_range() {
+    let test_repo = TestRepo::new_with_remote();
+    setup_weave(&test_repo);
+    let info = repo::gather_repo_info(&test_repo.repo, false, 1).unwrap();
+
+    let err = super::report(&test_repo.repo, &info, "HEAD...origin/main").unwrap_err();
+    assert!(err.to_string().contains("symmetric range"), "{}", err);
+}
+
+#[test]
+fn cherry_run_defaults_to_the_tracked_upstream() {
+    let test_repo = TestRepo::new_with_remote();
+    setup_weave(&test_repo);
+
+    let result = test_repo.in_dir(|| super::run(None));
+    assert!(result.is_ok(), "cherry failed: {:?}", result.err());
+}
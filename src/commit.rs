@@ -2,15 +2,19 @@ use std::path::Path;
 
 use anyhow::{Context, Result, bail};
 use git2::Repository;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 
+use crate::branch;
 use crate::core::graph;
 use crate::core::msg;
 use crate::core::repo;
 use crate::core::staging;
+use crate::core::trailers::{self, Trailer};
 use crate::core::transaction::{self, LoomState, Rollback};
 use crate::core::weave::{self, RebaseOutcome, Weave};
 use crate::git;
+use crate::size_check;
 
 #[derive(Serialize, Deserialize)]
 struct CommitContext {
@@ -21,16 +25,31 @@ struct CommitContext {
 ///
 /// Stages files, creates the commit at HEAD, then uses Weave to relocate
 /// it to the target feature branch (creating merge topology if needed).
+///
+/// Each entry of `coauthors` (`Name <email>`) becomes a `Co-authored-by`
+/// trailer on the new commit. A commit on a feature branch gets the
+/// `loom.commitTemplate` applied to its message.
+///
+/// With `pick_files`, a checklist of the changed files (diffs viewable)
+/// picks the whole files to commit, like `patch` does hunks.
 pub fn run(
     branch: Option<String>,
     message: Option<String>,
     patch: bool,
+    pick_files: bool,
+    coauthors: Vec<String>,
     files: Vec<String>,
     theme: &graph::Theme,
 ) -> Result<()> {
+    let coauthors: Vec<Trailer> = coauthors
+        .iter()
+        .map(|c| trailers::coauthor(c))
+        .collect::<Result<_>>()?;
+
     let repo = repo::open_repo()?;
     let workdir = repo::require_workdir(&repo, "commit")?.to_path_buf();
     let git_dir = repo.path().to_path_buf();
+    let template = commit_template(&repo)?;
 
     // Gather repo info once — also serves as verification that we're on an
     // integration branch (gather_repo_info requires an upstream).
@@ -38,11 +57,14 @@ pub fn run(
         "Must be on an integration branch to use commit\n\
          Use `git commit` directly on feature branches",
     )?;
+    if message.is_none() {
+        msg::require_editor("the commit message", "Pass it with `-m`")?;
+    }
 
     // Stage files, saving aside any pre-existing staged files not in the
     // target list so they don't accidentally end up in this commit.
-    let saved_staged = if patch {
-        resolve_staging_patch(&repo, &workdir, &files, theme)?
+    let saved_staged = if patch || pick_files {
+        resolve_staging_patch(&repo, &workdir, &files, pick_files, theme)?
     } else {
         resolve_staging(&repo, &workdir, &files)?
     };
@@ -52,13 +74,31 @@ pub fn run(
         git::restore_staged_patch(&workdir, &saved_staged)?;
         return Err(e);
     }
+    size_check::warn_staged(&repo);
+
+    // Undo staging (and a newly created branch, below) if the commit step
+    // fails, e.g. when the editor is closed with an empty message.
+    let saved_head = repo::head_oid(&repo)?.to_string();
+    let mut rollback = Rollback {
+        reset_mixed_to: saved_head.clone(),
+        saved_staged_patch: saved_staged.clone(),
+        ..Default::default()
+    };
 
-    let do_commit = || {
+    let do_commit = |branch: Option<&str>| {
         if let Some(msg) = &message {
-            git::commit(&workdir, msg)
+            git::commit(&workdir, msg)?;
         } else {
-            git::commit_with_editor(&workdir)
+            git::commit_with_editor(&workdir)?;
         }
+        if let (Some(branch), Some((template, pattern))) = (branch, &template) {
+            let head = repo.find_commit(repo::head_oid(&repo)?)?;
+            let message = head.message().unwrap_or("");
+            if let Some(filled) = fill_template(template, pattern, branch, message) {
+                git::commit_amend(&workdir, Some(&filled))?;
+            }
+        }
+        trailers::amend_head(&repo, &workdir, &coauthors)
     };
 
     // Loose commit: when no -b flag and local branch name matches the
@@ -67,9 +107,8 @@ pub fn run(
     // branch. This works regardless of whether local commits or woven
     // branches already exist.
     if branch.is_none() && info.branch_name == repo::upstream_local_branch(&info.upstream.label) {
-        let result = do_commit();
+        transaction::editor_step(&workdir, &rollback, || do_commit(None))?;
         git::restore_staged_patch(&workdir, &saved_staged)?;
-        result?;
         let new_head = repo::head_oid(&repo)?;
         msg::success(&format!(
             "Created commit `{}`",
@@ -78,8 +117,6 @@ pub fn run(
         return Ok(());
     }
 
-    let saved_head = repo::head_oid(&repo)?.to_string();
-
     // Resolve branch target (may create a new branch at merge-base).
     // Returns whether the branch was newly created — only newly-created
     // branches are deleted on rollback (not pre-existing empty ones).
@@ -91,10 +128,10 @@ pub fn run(
     let branch_is_empty =
         is_branch_at_merge_base(&repo, &branch_name, info.upstream.merge_base_oid)?;
 
-    if let Err(e) = do_commit() {
-        git::restore_staged_patch(&workdir, &saved_staged)?;
-        return Err(e);
+    if branch_is_new {
+        rollback.delete_branches.push(branch_name.clone());
     }
+    transaction::editor_step(&workdir, &rollback, || do_commit(Some(&branch_name)))?;
 
     let head_oid = repo::head_oid(&repo)?;
 
@@ -113,24 +150,16 @@ pub fn run(
 
     graph.move_commit(head_oid, &branch_name)?;
 
-    let todo = graph.to_todo();
+    let todo = graph.checked_todo()?;
 
     // Save LoomState before the rebase so we can resume on conflict.
-    let mut delete_branches = vec![];
-    if branch_is_new {
-        delete_branches.push(branch_name.clone());
-    }
     let ctx = CommitContext {
         branch_name: branch_name.clone(),
     };
     let state = LoomState {
         command: "commit".to_string(),
-        rollback: Rollback {
-            reset_mixed_to: saved_head.clone(),
-            delete_branches,
-            saved_staged_patch: saved_staged.clone(),
-            ..Default::default()
-        },
+        refs_before: Default::default(),
+        rollback,
         context: serde_json::to_value(&ctx)?,
     };
     transaction::save(&git_dir, &state)?;
@@ -148,6 +177,54 @@ pub fn run(
     Ok(())
 }
 
+/// Default `loom.ticketPattern`: a tracker key such as `ABC-123`.
+const DEFAULT_TICKET_PATTERN: &str = r"[A-Z][A-Z0-9]+-[0-9]+";
+
+/// The `loom.commitTemplate` and the compiled `loom.ticketPattern`, or
+/// `None` when no template is configured.
+fn commit_template(repo: &Repository) -> Result<Option<(String, Regex)>> {
+    let Some(template) = repo::commit_template(repo) else {
+        return Ok(None);
+    };
+    let pattern = repo::ticket_pattern(repo).unwrap_or_else(|| DEFAULT_TICKET_PATTERN.to_string());
+    let pattern = Regex::new(&pattern)
+        .with_context(|| format!("Invalid loom.ticketPattern `{}`", pattern))?;
+    Ok(Some((template, pattern)))
+}
+
+/// Fill `template` for a commit `message` on `branch`: `{message}` is the
+/// message, `{branch}` the branch name, and `{ticket}` what `pattern`
+/// matches in the branch name (its first group, if it has one). A template
+/// without `{message}` is a prefix.
+///
+/// Returns `None` when the message must stay as is: the template needs a
+/// ticket the branch name has none of, or the message already mentions it.
+pub fn fill_template(
+    template: &str,
+    pattern: &Regex,
+    branch: &str,
+    message: &str,
+) -> Option<String> {
+    let message = message.trim_end();
+    let mut filled = if template.contains("{message}") {
+        template.to_string()
+    } else {
+        format!("{}{{message}}", template)
+    };
+    if filled.contains("{ticket}") {
+        let captures = pattern.captures(branch)?;
+        let ticket = captures.get(1).or_else(|| captures.get(0))?.as_str();
+        if message.contains(ticket) {
+            return None;
+        }
+        filled = filled.replace("{ticket}", ticket);
+    }
+    let filled = filled
+        .replace("{branch}", branch)
+        .replace("{message}", message);
+    (filled != message).then_some(filled)
+}
+
 /// Resume a `commit` operation after a conflict has been resolved.
 pub fn after_continue(
     workdir: &Path,
@@ -185,6 +262,7 @@ fn resolve_staging_patch(
     repo: &Repository,
     workdir: &std::path::Path,
     files: &[String],
+    whole_files: bool,
     theme: &graph::Theme,
 ) -> Result<String> {
     // Save aside other staged files when specific files are targeted.
@@ -196,7 +274,11 @@ fn resolve_staging_patch(
         String::new()
     };
 
-    let confirmed = staging::run_hunk_picker(repo, workdir, files, theme)?;
+    let confirmed = if whole_files {
+        staging::run_file_picker(repo, workdir, files, theme)?
+    } else {
+        staging::run_hunk_picker(repo, workdir, files, theme)?
+    };
     if !confirmed {
         git::restore_staged_patch(workdir, &saved_staged)?;
         anyhow::bail!("Cancelled");
@@ -222,7 +304,7 @@ fn resolve_staging(
     }
 
     if files.iter().any(|f| f == "zz") {
-        git::stage_all(workdir)?;
+        git::stage_all(workdir, &repo::ignore_path_patterns(repo))?;
         return Ok(String::new());
     }
 
@@ -284,6 +366,7 @@ fn resolve_explicit_branch(
                 bail!("Branch name cannot be empty");
             }
             git::branch_validate_name(&name)?;
+            branch::check_name_policy(repo, &name)?;
 
             if repo.find_branch(&name, git2::BranchType::Local).is_ok() {
                 bail!(
@@ -324,7 +407,9 @@ fn pick_branch(
 
     // If user typed a name that isn't an existing woven branch, create it
     if !branch_names.contains(&name) {
+        let name = branch::name_from_input(repo, &name)?;
         git::branch_validate_name(&name)?;
+        branch::check_name_policy(repo, &name)?;
         repo::ensure_branch_not_exists(repo, &name)?;
         create_branch_at_merge_base(workdir, &name, info.upstream.merge_base_oid)?;
         return Ok((name, true));
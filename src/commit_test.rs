@@ -3,7 +3,15 @@ use crate::core::test_helpers::TestRepo;
 
 /// Wrapper so existing tests don't need to pass patch/theme.
 fn run(branch: Option<String>, message: Option<String>, files: Vec<String>) -> anyhow::Result<()> {
-    super::run(branch, message, false, files, &graph::Theme::dark())
+    super::run(
+        branch,
+        message,
+        false,
+        false,
+        vec![],
+        files,
+        &graph::Theme::dark(),
+    )
 }
 
 /// Helper: set up a test repo with an empty feature branch at the merge-base.
@@ -51,6 +59,66 @@ fn setup_with_two_branches() -> TestRepo {
     test_repo
 }
 
+// ── Co-authors ──────────────────────────────────────────────────────────
+
+#[test]
+fn commit_coauthor_adds_trailer() {
+    let test_repo = setup_with_woven_branch();
+    test_repo.write_file("new.txt", "content");
+
+    let result = test_repo.in_dir(|| {
+        super::run(
+            Some("feature-a".to_string()),
+            Some("Pair on new file".to_string()),
+            false,
+            false,
+            vec![
+                "Ada Lovelace <ada@example.com>".to_string(),
+                "Alan Turing <alan@example.com>".to_string(),
+            ],
+            vec!["new.txt".to_string()],
+            &graph::Theme::dark(),
+        )
+    });
+    assert!(result.is_ok(), "commit failed: {:?}", result);
+
+    let tip = test_repo.get_branch_target("feature-a");
+    let message = test_repo.find_commit(tip).message().unwrap().to_string();
+    assert_eq!(
+        message,
+        "Pair on new file\n\n\
+         Co-authored-by: Ada Lovelace <ada@example.com>\n\
+         Co-authored-by: Alan Turing <alan@example.com>\n"
+    );
+}
+
+#[test]
+fn commit_invalid_coauthor_fails_before_committing() {
+    let test_repo = setup_with_woven_branch();
+    test_repo.write_file("new.txt", "content");
+    let head_before = test_repo.head_oid();
+
+    let result = test_repo.in_dir(|| {
+        super::run(
+            Some("feature-a".to_string()),
+            Some("msg".to_string()),
+            false,
+            false,
+            vec!["ada@example.com".to_string()],
+            vec!["new.txt".to_string()],
+            &graph::Theme::dark(),
+        )
+    });
+
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid co-author")
+    );
+    assert_eq!(test_repo.head_oid(), head_before);
+}
+
 // ── Staging resolution ───────────────────────────────────────────────────
 
 #[test]
@@ -196,6 +264,57 @@ fn commit_to_new_branch_creates_and_weaves() {
     assert_eq!(second_parent.summary().unwrap(), "Add file");
 }
 
+#[test]
+fn commit_failure_rolls_back_new_branch_and_staging() {
+    let test_repo = TestRepo::new_with_remote();
+    let head_before = test_repo.head_oid();
+    test_repo.write_file("new.txt", "content");
+    test_repo.reject_commit_messages();
+
+    let result = test_repo.in_dir(|| {
+        run(
+            Some("feature-new".to_string()),
+            Some("Add file".to_string()),
+            vec!["new.txt".to_string()],
+        )
+    });
+
+    assert!(result.is_err());
+    assert_eq!(test_repo.head_oid(), head_before);
+    assert!(!test_repo.branch_exists("feature-new"));
+    assert_eq!(test_repo.status_porcelain().trim(), "?? new.txt");
+}
+
+#[test]
+fn commit_honors_hooks_path_unless_no_verify() {
+    let test_repo = TestRepo::new_with_remote();
+    // Move the rejecting hook to a custom hooks directory
+    test_repo.reject_commit_messages();
+    let custom = test_repo.workdir().join("hooks");
+    std::fs::rename(test_repo.repo.path().join("hooks"), &custom).unwrap();
+    test_repo.set_config("core.hooksPath", custom.to_str().unwrap());
+    test_repo.write_file("new.txt", "content");
+
+    let commit = || {
+        test_repo.in_dir(|| {
+            run(
+                Some("feature-new".to_string()),
+                Some("Add file".to_string()),
+                vec!["new.txt".to_string()],
+            )
+        })
+    };
+
+    assert!(commit().is_err());
+    assert!(!test_repo.branch_exists("feature-new"));
+
+    crate::git::set_no_verify(true);
+    let result = commit();
+    crate::git::set_no_verify(false);
+    result.unwrap();
+    assert_eq!(test_repo.branch_commit_summary("feature-new"), "Add file");
+}
+
 // ── Merge topology ──────────────────────────────────────────────────────
 
 #[test]
@@ -537,8 +656,11 @@ fn commit_with_branch_flag_does_not_create_loose() {
 
 #[test]
 fn commit_not_on_integration_branch_fails() {
-    // No upstream tracking = not an integration branch
-    let test_repo = TestRepo::new();
+    // No upstream tracking in a repo with a remote = not an integration branch
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-x", &base_oid.to_string());
+    test_repo.switch_branch("feature-x");
     test_repo.commit("A1", "a1.txt");
     test_repo.write_file("new.txt", "content");
 
@@ -675,3 +797,142 @@ fn commit_abort_preserves_working_state() {
     );
     assert_eq!(test_repo.read_file("new-file.txt"), "new-content");
 }
+
+#[test]
+fn commit_refused_when_rewrite_orphans_tag_rolls_back() {
+    let test_repo = setup_with_two_branches();
+    let head_before = test_repo.head_oid();
+    let repo = &test_repo.repo;
+    repo.tag_lightweight("v1", &repo.find_object(head_before, None).unwrap(), false)
+        .unwrap();
+    test_repo.write_file("new.txt", "content");
+
+    let err = test_repo
+        .in_dir(|| {
+            run(
+                Some("feature-a".to_string()),
+                Some("msg".to_string()),
+                vec!["new.txt".to_string()],
+            )
+        })
+        .unwrap_err();
+
+    assert!(err.to_string().contains("--retag"), "{}", err);
+    assert_eq!(test_repo.head_oid(), head_before);
+    assert!(test_repo.workdir().join("new.txt").exists());
+    assert!(!repo.path().join("loom/state.json").exists());
+}
+
+// ── Message template ────────────────────────────────────────────────────
+
+fn ticket_pattern() -> regex::Regex {
+    regex::Regex::new(super::DEFAULT_TICKET_PATTERN).unwrap()
+}
+
+#[test]
+fn fill_template_prefix_with_ticket() {
+    let filled = super::fill_template(
+        "[{ticket}] ",
+        &ticket_pattern(),
+        "feature/PROJ-42-login",
+        "Add login form\n",
+    );
+    assert_eq!(filled.as_deref(), Some("[PROJ-42] Add login form"));
+}
+
+#[test]
+fn fill_template_with_message_and_branch_placeholders() {
+    let filled = super::fill_template(
+        "{message}\n\nBranch: {branch}",
+        &ticket_pattern(),
+        "feature-a",
+        "Fix bug",
+    );
+    assert_eq!(filled.as_deref(), Some("Fix bug\n\nBranch: feature-a"));
+}
+
+#[test]
+fn fill_template_uses_first_capture_group() {
+    let pattern = regex::Regex::new(r"issue-(\d+)").unwrap();
+    let filled = super::fill_template("#{ticket}: ", &pattern, "issue-17-crash", "Fix crash");
+    assert_eq!(filled.as_deref(), Some("#17: Fix crash"));
+}
+
+#[test]
+fn fill_template_skips_branch_without_ticket() {
+    let filled = super::fill_template("[{ticket}] ", &ticket_pattern(), "feature-a", "Fix bug");
+    assert_eq!(filled, None);
+}
+
+#[test]
+fn fill_template_skips_message_with_ticket() {
+    let filled = super::fill_template(
+        "[{ticket}] ",
+        &ticket_pattern(),
+        "PROJ-42-login",
+        "PROJ-42: Add login form",
+    );
+    assert_eq!(filled, None);
+}
+
+#[test]
+fn commit_applies_template_from_branch_name() {
+    let test_repo = setup_with_woven_branch();
+    test_repo.set_config("loom.commitTemplate", "[{ticket}] ");
+    test_repo.write_file("login.txt", "form");
+
+    let result = test_repo.in_dir(|| {
+        run(
+            Some("PROJ-42-login".to_string()),
+            Some("Add login form".to_string()),
+            vec!["login.txt".to_string()],
+        )
+    });
+    assert!(result.is_ok(), "commit failed: {:?}", result);
+
+    let tip = test_repo.get_branch_target("PROJ-42-login");
+    let message = test_repo.find_commit(tip).message().unwrap().to_string();
+    assert_eq!(message.trim_end(), "[PROJ-42] Add login form");
+}
+
+#[test]
+fn commit_invalid_ticket_pattern_fails_before_staging() {
+    let test_repo = setup_with_woven_branch();
+    test_repo.set_config("loom.commitTemplate", "[{ticket}] ");
+    test_repo.set_config("loom.ticketPattern", "([A-Z");
+    test_repo.write_file("login.txt", "form");
+    let head_before = test_repo.head_oid();
+
+    let err = test_repo
+        .in_dir(|| {
+            run(
+                Some("feature-a".to_string()),
+                Some("Add login form".to_string()),
+                vec!["login.txt".to_string()],
+            )
+        })
+        .unwrap_err();
+
+    assert!(err.to_string().contains("loom.ticketPattern"), "{}", err);
+    assert_eq!(test_repo.head_oid(), head_before);
+}
+
+#[test]
+fn commit_new_branch_rejected_by_policy() {
+    let test_repo = setup_with_woven_branch();
+    test_repo.set_config("loom.branchPattern", "feature-.*");
+    test_repo.write_file("new.txt", "content");
+
+    let err = test_repo
+        .in_dir(|| {
+            run(
+                Some("misc".to_string()),
+                Some("msg".to_string()),
+                vec!["new.txt".to_string()],
+            )
+        })
+        .unwrap_err();
+
+    assert!(err.to_string().contains("loom.branchPattern"), "{}", err);
+    assert!(!test_repo.branch_exists("misc"));
+}
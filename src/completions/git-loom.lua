@@ -3,13 +3,19 @@
 -- Or load dynamically: load(io.popen('git-loom completions clink'):read("*a"))()
 
 local status_matcher = clink.argmatcher()
-    :addflags("-f", "--files", "-a", "--all", "--help", "-h")
+    :addflags("-f", "--files", "-a", "--all", "--path", "-g", "--group", "--stat", "--checks", "--detached", "--diff-filter", "--html", "--help", "-h")
+
+local update_matcher = clink.argmatcher()
+    :addflags("-y", "--yes", "--only", "--daemon", "--help", "-h")
+
+local init_matcher = clink.argmatcher()
+    :addflags("--remote", "--base", "--help", "-h")
 
 local branch_new_matcher = clink.argmatcher()
-    :addflags("-t", "--target", "--help", "-h")
+    :addflags("-t", "--target", "--from-changes", "-m", "--message", "--help", "-h")
 
 local branch_merge_matcher = clink.argmatcher()
-    :addflags("-a", "--all", "--help", "-h")
+    :addflags("-a", "--all", "--explain", "--help", "-h")
 
 local branch_unmerge_matcher = clink.argmatcher()
     :addflags("--help", "-h")
@@ -21,41 +27,186 @@ local branch_matcher = clink.argmatcher()
         "merge"    .. branch_merge_matcher,
         "unmerge"  .. branch_unmerge_matcher
     )
-    :addflags("-t", "--target", "--help", "-h")
+    :addflags("-t", "--target", "--from-changes", "-m", "--message", "--help", "-h")
 
 local reword_matcher = clink.argmatcher()
     :addflags("-m", "--message", "--help", "-h")
 
 local commit_matcher = clink.argmatcher()
-    :addflags("-b", "--branch", "-m", "--message", "--help", "-h")
+    :addflags("-b", "--branch", "-m", "--message", "-p", "--patch", "-i", "--interactive", "--coauthor", "--help", "-h")
 
 local drop_matcher = clink.argmatcher()
     :addflags("-y", "--yes", "--help", "-h")
 
+local fold_matcher = clink.argmatcher()
+    :addflags("-c", "--create", "-p", "--patch", "--keep-trailers", "--staged", "--preview", "--coauthor", "--help", "-h")
+
+local revert_matcher = clink.argmatcher()
+    :addflags("-l", "--loose", "--help", "-h")
+
+local backport_matcher = clink.argmatcher()
+    :addflags("-b", "--branch", "--help", "-h")
+
 local split_matcher = clink.argmatcher()
     :addflags("-m", "--message", "--help", "-h")
 
 local absorb_matcher = clink.argmatcher()
     :addflags("-n", "--dry-run", "--help", "-h")
 
+local api_todo_matcher = clink.argmatcher()
+    :addarg("drop", "fixup", "move", "swap")
+
+local api_matcher = clink.argmatcher()
+    :addarg(
+        "resolve",
+        "sections",
+        "todo"     .. api_todo_matcher,
+        "refs"
+    )
+    :addflags("--help", "-h")
+
+local batch_matcher = clink.argmatcher()
+    :addflags("-n", "--dry-run", "--help", "-h")
+
+local touch_matcher = clink.argmatcher()
+    :addflags("--reset-author", "-S", "--sign", "--help", "-h")
+
+local ignore_matcher = clink.argmatcher()
+    :addflags("-r", "--remove", "--help", "-h")
+
+local stack_matcher = clink.argmatcher()
+    :addflags("-d", "--detach", "--help", "-h")
+
+local check_matcher = clink.argmatcher()
+    :addflags("-f", "--force", "--help", "-h")
+
+local push_matcher = clink.argmatcher()
+    :addflags("--all", "--no-pr", "--help", "-h")
+
+local promote_matcher = clink.argmatcher()
+    :addflags("--squash", "--no-push", "--help", "-h")
+
+local rebase_branch_matcher = clink.argmatcher()
+    :addflags("--onto", "--help", "-h")
+
+local split_branch_matcher = clink.argmatcher()
+    :addflags("-c", "--commit", "--stack", "--help", "-h")
+
+local merge_branches_matcher = clink.argmatcher()
+    :addflags("--keep", "--help", "-h")
+
+local archive_matcher = clink.argmatcher()
+    :addflags("-l", "--list", "-r", "--restore", "--help", "-h")
+
+local open_matcher = clink.argmatcher()
+    :addflags("--print", "--help", "-h")
+
+local serve_matcher = clink.argmatcher()
+    :addflags("--stdio", "--help", "-h")
+
+local render_fixture_matcher = clink.argmatcher()
+    :addarg(clink.filematches)
+    :addflags("--width", "--help", "-h")
+
+local diff_branch_matcher = clink.argmatcher()
+    :addflags("--stat", "--help", "-h")
+
+local graph_matcher = clink.argmatcher()
+    :addflags("--dot", "--mermaid", "--help", "-h")
+
+local tour_matcher = clink.argmatcher()
+    :addflags("--keep", "--help", "-h")
+
+local activity_matcher = clink.argmatcher()
+    :addflags("--clear", "--help", "-h")
+
+local gc_matcher = clink.argmatcher()
+    :addflags("--days", "--dry-run", "-n", "--help", "-h")
+
+local bisect_start_matcher = clink.argmatcher()
+    :addflags("-b", "--branch", "--help", "-h")
+
+local bisect_matcher = clink.argmatcher()
+    :addarg(
+        "start"    .. bisect_start_matcher,
+        "good",
+        "bad",
+        "skip",
+        "reset"
+    )
+
+local worktree_matcher = clink.argmatcher()
+    :addarg("add", "path", "prune")
+
+local meta_matcher = clink.argmatcher()
+    :addarg("push", "pull")
+
+local resolution_matcher = clink.argmatcher()
+    :addarg("save", "apply", "list", "drop", "push", "fetch")
+
+local help_matcher = clink.argmatcher()
+    :addarg("workflows", "stacking", "conflicts", "shortids")
+
+local self_update_matcher = clink.argmatcher()
+    :addflags("--check", "-y", "--yes", "--help", "-h")
+
 clink.argmatcher("git-loom")
     :addarg(
         "status"       .. status_matcher,
-        "init",
+        "init"         .. init_matcher,
         "branch"       .. branch_matcher,
         "reword"       .. reword_matcher,
         "commit"       .. commit_matcher,
         "drop"         .. drop_matcher,
-        "fold",
+        "revert"       .. revert_matcher,
+        "backport"     .. backport_matcher,
+        "fold"         .. fold_matcher,
         "show",
         "trace",
+        "explain",
         "split"        .. split_matcher,
         "absorb"       .. absorb_matcher,
-        "update",
-        "push",
+        "update"       .. update_matcher,
+        "push"         .. push_matcher,
+        "promote"      .. promote_matcher,
         "continue",
         "abort",
+        "resolution"   .. resolution_matcher,
+        "gc"           .. gc_matcher,
         "swap",
-        "switch"
+        "switch",
+        "tour"         .. tour_matcher,
+        "help"         .. help_matcher,
+        "self-update"  .. self_update_matcher,
+        "api"          .. api_matcher,
+        "serve"        .. serve_matcher,
+        "render-fixture" .. render_fixture_matcher,
+        "batch"        .. batch_matcher,
+        "touch"        .. touch_matcher,
+        "diff-branch"  .. diff_branch_matcher,
+        "cherry",
+        "who",
+        "check"        .. check_matcher,
+        "owns",
+        "overlap",
+        "graph"        .. graph_matcher,
+        "open"         .. open_matcher,
+        "bisect"       .. bisect_matcher,
+        "ignore"       .. ignore_matcher,
+        "size-check",
+        "rebase-branch" .. rebase_branch_matcher,
+        "split-branch"  .. split_branch_matcher,
+        "merge-branches" .. merge_branches_matcher,
+        "attach",
+        "archive"      .. archive_matcher,
+        "next"         .. stack_matcher,
+        "prev"         .. stack_matcher,
+        "top",
+        "worktree"     .. worktree_matcher,
+        "export-state",
+        "import-state",
+        "meta"         .. meta_matcher,
+        "bugreport",
+        "activity"     .. activity_matcher
     )
-    :addflags("--no-color", "--help", "-h")
+    :addflags("--no-color", "--ascii", "--help", "-h")
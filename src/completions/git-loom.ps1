@@ -11,21 +11,61 @@ $_gitLoomCompleter = {
         @{ Name = 'reword'; Description = 'Reword a commit message or rename a branch' },
         @{ Name = 'commit'; Description = 'Create a commit on a feature branch' },
         @{ Name = 'drop'; Description = 'Drop a commit or a branch from history' },
+        @{ Name = 'revert'; Description = 'Undo a commit with a new commit on its branch' },
+        @{ Name = 'backport'; Description = 'Apply an upstream fix under the weave' },
         @{ Name = 'fold'; Description = 'Fold source(s) into a target' },
         @{ Name = 'absorb'; Description = 'Absorb working tree changes into originating commits' },
         @{ Name = 'update'; Description = 'Pull-rebase the integration branch' },
         @{ Name = 'push'; Description = 'Push the integration branch to the remote' },
+        @{ Name = 'promote'; Description = 'Rebase a branch onto upstream, check, and push it' },
         @{ Name = 'show'; Description = 'Show the diff and metadata for a commit' },
         @{ Name = 'trace'; Description = 'Trace loom operations for debugging' },
+        @{ Name = 'explain'; Description = 'Show the git commands a loom command would run' },
         @{ Name = 'split'; Description = 'Split a commit into two sequential commits' },
         @{ Name = 'continue'; Description = 'Continue a paused loom operation after resolving conflicts' },
         @{ Name = 'abort'; Description = 'Abort a paused loom operation and restore original state' },
+        @{ Name = 'resolution'; Description = 'Save conflict resolutions and replay them' },
+        @{ Name = 'gc'; Description = 'Prune old loom logs, caches, and leftover refs' },
         @{ Name = 'swap'; Description = 'Swap two commits' },
-        @{ Name = 'switch'; Description = 'Switch to any branch for testing' }
+        @{ Name = 'switch'; Description = 'Switch to any branch for testing' },
+        @{ Name = 'tour'; Description = 'Learn loom step by step in a scratch repository' },
+        @{ Name = 'help'; Description = 'Show help for a command or read a guide' },
+        @{ Name = 'self-update'; Description = 'Update loom to the latest release' },
+        @{ Name = 'api'; Description = 'Query the repository as JSON' },
+        @{ Name = 'serve'; Description = 'Answer editor requests as a JSON-RPC server' },
+        @{ Name = 'render-fixture'; Description = 'Render a status fixture (JSON) as text' },
+        @{ Name = 'batch'; Description = 'Run several operations in one rebase' },
+        @{ Name = 'touch'; Description = "Recreate a branch's commits with a fresh committer date" },
+        @{ Name = 'diff-branch'; Description = 'Show what a branch adds over its base' },
+        @{ Name = 'cherry'; Description = 'Show which commits of the weave are already upstream' },
+        @{ Name = 'who'; Description = 'Summarize the authors of a branch' },
+        @{ Name = 'check'; Description = 'Run the configured checks on a branch tip' },
+        @{ Name = 'owns'; Description = 'Find the branches and commits that change a file' },
+        @{ Name = 'overlap'; Description = 'Show which woven branches change the same files and lines' },
+        @{ Name = 'graph'; Description = 'Export the weave as Graphviz or Mermaid text' },
+        @{ Name = 'open'; Description = 'Open a branch, commit, or file on the forge' },
+        @{ Name = 'bisect'; Description = 'Find the commit and branch that introduced a bug' },
+        @{ Name = 'ignore'; Description = 'Hide always-dirty files from the working changes' },
+        @{ Name = 'size-check'; Description = 'Flag large or binary staged files (for a pre-commit hook)' },
+        @{ Name = 'rebase-branch'; Description = 'Move a woven branch onto another base, out of the weave' },
+        @{ Name = 'split-branch'; Description = 'Divide a woven branch into two' },
+        @{ Name = 'merge-branches'; Description = 'Combine two woven branches into one' },
+        @{ Name = 'attach'; Description = 'Adopt a branch made outside loom into the weave' },
+        @{ Name = 'archive'; Description = 'Freeze a branch out of the weave' },
+        @{ Name = 'next'; Description = 'Check out the next woven branch up the stack' },
+        @{ Name = 'prev'; Description = 'Check out the previous woven branch down the stack' },
+        @{ Name = 'top'; Description = 'Return to the integration branch' },
+        @{ Name = 'worktree'; Description = 'Check out a branch in its own directory' },
+        @{ Name = 'export-state'; Description = 'Bundle the weave to move it to another machine' },
+        @{ Name = 'import-state'; Description = 'Recreate a weave from an export-state bundle' },
+        @{ Name = 'meta'; Description = 'Share the weave structure with teammates' },
+        @{ Name = 'bugreport'; Description = 'Print a redacted environment report for bug reports' },
+        @{ Name = 'activity'; Description = 'Summarize your local command usage and durations' }
     )
 
     $globalFlags = @(
         @{ Name = '--no-color'; Description = 'Disable colored output' },
+        @{ Name = '--ascii'; Description = 'Draw the graph with ASCII characters only' },
         @{ Name = '--help'; Description = 'Show help information' },
         @{ Name = '-h'; Description = 'Show help information' }
     )
@@ -36,8 +76,8 @@ $_gitLoomCompleter = {
         $subcommand = $tokens[1]
     }
 
-    # Complete subcommands (skip if already on 'branch', which has its own sub-subcommands)
-    if ($tokens.Count -le 2 -and $subcommand -ne 'branch' -and -not ($wordToComplete -match '^-')) {
+    # Complete subcommands (skip if already on 'branch', 'worktree', 'bisect', 'meta', or 'resolution', which have their own sub-subcommands)
+    if ($tokens.Count -le 2 -and $subcommand -notin @('branch', 'worktree', 'wt', 'bisect', 'meta', 'resolution') -and -not ($wordToComplete -match '^-')) {
         $commands | Where-Object { $_.Name -like "$wordToComplete*" } | ForEach-Object {
             [System.Management.Automation.CompletionResult]::new($_.Name, $_.Name, 'ParameterValue', $_.Description)
         }
@@ -52,7 +92,105 @@ $_gitLoomCompleter = {
                 @{ Name = '-f'; Description = 'Show files changed in each commit' },
                 @{ Name = '--files'; Description = 'Show files changed in each commit' },
                 @{ Name = '-a'; Description = 'Show all branches including hidden ones' },
-                @{ Name = '--all'; Description = 'Show all branches including hidden ones' }
+                @{ Name = '--all'; Description = 'Show all branches including hidden ones' },
+                @{ Name = '--path'; Description = 'Only show commits touching matching paths' },
+                @{ Name = '-g'; Description = 'Group working changes by probable branch' },
+                @{ Name = '--group'; Description = 'Group working changes by probable branch' },
+                @{ Name = '--stat'; Description = 'Show added/removed line counts per commit' },
+                @{ Name = '--checks'; Description = 'Show the last check result of each branch' },
+                @{ Name = '--detached'; Description = 'Show the integration branch containing a detached HEAD' },
+                @{ Name = '--diff-filter'; Description = 'Only list files changed these ways (A, D, M, R, U)' },
+                @{ Name = '--html'; Description = 'Write the status as a standalone HTML page' }
+            )
+        }
+        'revert' {
+            $subFlags = @(
+                @{ Name = '-l'; Description = 'Place the revert on the integration line' },
+                @{ Name = '--loose'; Description = 'Place the revert on the integration line' }
+            )
+        }
+        'backport' {
+            $subFlags = @(
+                @{ Name = '-b'; Description = 'Branch to put the commit at the bottom of' },
+                @{ Name = '--branch'; Description = 'Branch to put the commit at the bottom of' }
+            )
+        }
+        'ignore' {
+            $subFlags = @(
+                @{ Name = '-r'; Description = 'Stop ignoring the given patterns' },
+                @{ Name = '--remove'; Description = 'Stop ignoring the given patterns' }
+            )
+        }
+        { $_ -in 'worktree', 'wt' } {
+            if ($tokens.Count -le 3 -and -not ($wordToComplete -match '^-')) {
+                $worktreeSubs = @(
+                    @{ Name = 'add'; Description = 'Create a worktree for a branch, or move it to the branch tip' },
+                    @{ Name = 'path'; Description = 'Print the worktree path of a branch' },
+                    @{ Name = 'prune'; Description = 'Remove the worktrees of deleted branches' }
+                )
+                $worktreeSubs | Where-Object { $_.Name -like "$wordToComplete*" } | ForEach-Object {
+                    [System.Management.Automation.CompletionResult]::new($_.Name, $_.Name, 'ParameterValue', $_.Description)
+                }
+                return
+            }
+        }
+        'resolution' {
+            if ($tokens.Count -le 3 -and -not ($wordToComplete -match '^-')) {
+                $resolutionSubs = @(
+                    @{ Name = 'save'; Description = 'Save the resolved conflicts of the paused operation' },
+                    @{ Name = 'apply'; Description = 'Resolve the current conflicts with saved resolutions' },
+                    @{ Name = 'list'; Description = 'List the saved resolutions' },
+                    @{ Name = 'drop'; Description = 'Delete a saved resolution' },
+                    @{ Name = 'push'; Description = 'Push the saved resolutions' },
+                    @{ Name = 'fetch'; Description = 'Fetch the resolutions teammates shared' }
+                )
+                $resolutionSubs | Where-Object { $_.Name -like "$wordToComplete*" } | ForEach-Object {
+                    [System.Management.Automation.CompletionResult]::new($_.Name, $_.Name, 'ParameterValue', $_.Description)
+                }
+                return
+            }
+        }
+        'meta' {
+            if ($tokens.Count -le 3 -and -not ($wordToComplete -match '^-')) {
+                $metaSubs = @(
+                    @{ Name = 'push'; Description = 'Record the weave structure in refs/loom/meta and push it' },
+                    @{ Name = 'pull'; Description = 'Fetch refs/loom/meta and recreate the branches it records' }
+                )
+                $metaSubs | Where-Object { $_.Name -like "$wordToComplete*" } | ForEach-Object {
+                    [System.Management.Automation.CompletionResult]::new($_.Name, $_.Name, 'ParameterValue', $_.Description)
+                }
+                return
+            }
+        }
+        'bisect' {
+            if ($tokens.Count -le 3 -and -not ($wordToComplete -match '^-')) {
+                $bisectSubs = @(
+                    @{ Name = 'start'; Description = 'Start bisecting the integration branch' },
+                    @{ Name = 'good'; Description = 'Mark commits as good' },
+                    @{ Name = 'bad'; Description = 'Mark commits as bad' },
+                    @{ Name = 'skip'; Description = "Skip commits that can't be tested" },
+                    @{ Name = 'reset'; Description = 'End the bisection' }
+                )
+                $bisectSubs | Where-Object { $_.Name -like "$wordToComplete*" } | ForEach-Object {
+                    [System.Management.Automation.CompletionResult]::new($_.Name, $_.Name, 'ParameterValue', $_.Description)
+                }
+                return
+            }
+            $subFlags = @(
+                @{ Name = '-b'; Description = "Only bisect this woven branch's commits" },
+                @{ Name = '--branch'; Description = "Only bisect this woven branch's commits" }
+            )
+        }
+        { $_ -in 'next', 'prev' } {
+            $subFlags = @(
+                @{ Name = '-d'; Description = 'Detach HEAD at the branch tip' },
+                @{ Name = '--detach'; Description = 'Detach HEAD at the branch tip' }
+            )
+        }
+        'init' {
+            $subFlags = @(
+                @{ Name = '--remote'; Description = 'Track the default branch of this remote' },
+                @{ Name = '--base'; Description = 'Track this branch' }
             )
         }
         'branch' {
@@ -76,13 +214,17 @@ $_gitLoomCompleter = {
                 { $_ -in 'new', 'create' } {
                     $subFlags = @(
                         @{ Name = '-t'; Description = 'Target commit, branch, or shortID' },
-                        @{ Name = '--target'; Description = 'Target commit, branch, or shortID' }
+                        @{ Name = '--target'; Description = 'Target commit, branch, or shortID' },
+                        @{ Name = '--from-changes'; Description = 'Commit the working changes onto the new branch' },
+                        @{ Name = '-m'; Description = 'Commit message (with --from-changes)' },
+                        @{ Name = '--message'; Description = 'Commit message (with --from-changes)' }
                     )
                 }
                 'merge' {
                     $subFlags = @(
                         @{ Name = '-a'; Description = 'Also show remote branches' },
-                        @{ Name = '--all'; Description = 'Also show remote branches' }
+                        @{ Name = '--all'; Description = 'Also show remote branches' },
+                        @{ Name = '--explain'; Description = 'Print the file overlap matrix' }
                     )
                 }
                 'unmerge' {
@@ -101,7 +243,10 @@ $_gitLoomCompleter = {
                 @{ Name = '-b'; Description = 'Target feature branch' },
                 @{ Name = '--branch'; Description = 'Target feature branch' },
                 @{ Name = '-m'; Description = 'Commit message' },
-                @{ Name = '--message'; Description = 'Commit message' }
+                @{ Name = '--message'; Description = 'Commit message' },
+                @{ Name = '-i'; Description = 'Pick files from a checklist' },
+                @{ Name = '--interactive'; Description = 'Pick files from a checklist' },
+                @{ Name = '--coauthor'; Description = 'Add a Co-authored-by trailer' }
             )
         }
         'drop' {
@@ -110,6 +255,18 @@ $_gitLoomCompleter = {
                 @{ Name = '--yes'; Description = 'Skip confirmation prompt' }
             )
         }
+        'fold' {
+            $subFlags = @(
+                @{ Name = '-c'; Description = 'Create a new branch and move the commit(s) into it' },
+                @{ Name = '--create'; Description = 'Create a new branch and move the commit(s) into it' },
+                @{ Name = '-p'; Description = 'Interactively select hunks before folding' },
+                @{ Name = '--patch'; Description = 'Interactively select hunks before folding' },
+                @{ Name = '--keep-trailers'; Description = "Carry the source commit's trailers into the target" },
+                @{ Name = '--staged'; Description = 'Fold exactly what is staged into the target commit' },
+                @{ Name = '--preview'; Description = 'Show the patch that will move and ask for confirmation first' },
+                @{ Name = '--coauthor'; Description = 'Add a Co-authored-by trailer' }
+            )
+        }
         'split' {
             $subFlags = @(
                 @{ Name = '-m'; Description = 'Message for the first commit' },
@@ -122,6 +279,119 @@ $_gitLoomCompleter = {
                 @{ Name = '--dry-run'; Description = 'Show what would be absorbed without making changes' }
             )
         }
+        'batch' {
+            $subFlags = @(
+                @{ Name = '-n'; Description = 'Print the rebase todo without running it' },
+                @{ Name = '--dry-run'; Description = 'Print the rebase todo without running it' }
+            )
+        }
+        'touch' {
+            $subFlags = @(
+                @{ Name = '--reset-author'; Description = 'Make yourself the author, dated now' },
+                @{ Name = '-S'; Description = 'GPG-sign the recreated commits' },
+                @{ Name = '--sign'; Description = 'GPG-sign the recreated commits' }
+            )
+        }
+        'serve' {
+            $subFlags = @(
+                @{ Name = '--stdio'; Description = 'Serve requests on stdin and stdout' }
+            )
+        }
+        'render-fixture' {
+            $subFlags = @(
+                @{ Name = '--width'; Description = 'Shorten messages to fit this many columns' }
+            )
+        }
+        'diff-branch' {
+            $subFlags = @(
+                @{ Name = '--stat'; Description = 'Show a diffstat instead of the patch' }
+            )
+        }
+        'graph' {
+            $subFlags = @(
+                @{ Name = '--dot'; Description = 'Graphviz DOT (the default)' },
+                @{ Name = '--mermaid'; Description = 'Mermaid flowchart for Markdown' }
+            )
+        }
+        'open' {
+            $subFlags = @(
+                @{ Name = '--print'; Description = 'Print the URL instead of opening it' }
+            )
+        }
+        'archive' {
+            $subFlags = @(
+                @{ Name = '-l'; Description = 'List the archived branches' },
+                @{ Name = '--list'; Description = 'List the archived branches' },
+                @{ Name = '-r'; Description = 'Recreate the branch from its archive' },
+                @{ Name = '--restore'; Description = 'Recreate the branch from its archive' }
+            )
+        }
+        { $_ -in 'update', 'up' } {
+            $subFlags = @(
+                @{ Name = '-y'; Description = 'Remove branches with a gone upstream without asking' },
+                @{ Name = '--yes'; Description = 'Remove branches with a gone upstream without asking' },
+                @{ Name = '--only'; Description = 'Only restack this branch onto the new upstream' },
+                @{ Name = '--daemon'; Description = 'Keep updating at an interval when it is safe' }
+            )
+        }
+        'check' {
+            $subFlags = @(
+                @{ Name = '--force'; Description = 'Run every check, even those that already passed' }
+            )
+        }
+        'push' {
+            $subFlags = @(
+                @{ Name = '--all'; Description = 'Push every woven branch whose tip changed' },
+                @{ Name = '--no-pr'; Description = 'Push without creating a PR or review' }
+            )
+        }
+        'promote' {
+            $subFlags = @(
+                @{ Name = '--squash'; Description = 'Squash the branch into a single commit' },
+                @{ Name = '--no-push'; Description = 'Stop before pushing' }
+            )
+        }
+        'rebase-branch' {
+            $subFlags = @(
+                @{ Name = '--onto'; Description = 'New base: a branch, tag, or commit' }
+            )
+        }
+        'split-branch' {
+            $subFlags = @(
+                @{ Name = '-c'; Description = 'Commit moving to the new branch' },
+                @{ Name = '--commit'; Description = 'Commit moving to the new branch' },
+                @{ Name = '--stack'; Description = 'Stack the new branch on the old one' }
+            )
+        }
+        'merge-branches' {
+            $subFlags = @(
+                @{ Name = '--keep'; Description = 'Keep the other branch at the combined tip' }
+            )
+        }
+        'tour' {
+            $subFlags = @(
+                @{ Name = '--keep'; Description = 'Keep the scratch repository at the end' }
+            )
+        }
+        'activity' {
+            $subFlags = @(
+                @{ Name = '--clear'; Description = 'Delete the activity log' }
+            )
+        }
+        'gc' {
+            $subFlags = @(
+                @{ Name = '--days'; Description = 'Prune logs older than this many days' },
+                @{ Name = '--dry-run'; Description = 'Show what would be pruned without removing anything' },
+                @{ Name = '-n'; Description = 'Show what would be pruned without removing anything' }
+            )
+        }
+        'self-update' {
+            $subFlags = @(
+                @{ Name = '--check'; Description = 'Only report whether a newer release is available' },
+                @{ Name = '-y'; Description = 'Skip the confirmation prompt' },
+                @{ Name = '--yes'; Description = 'Skip the confirmation prompt' }
+            )
+        }
     }
 
     $allFlags = $globalFlags + $subFlags
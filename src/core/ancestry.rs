@@ -0,0 +1,152 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use git2::{Oid, Repository};
+
+/// The cache is trimmed back to this many entries once it grows past twice
+/// that size.
+const MAX_ENTRIES: usize = 500;
+
+/// The merge-base of `a` and `b`, like `Repository::merge_base`, remembered
+/// across runs in `.git/loom/ancestry`.
+///
+/// On very large repositories the walk behind a merge-base dominates
+/// `loom status`. Its result never changes for a given pair of commits, so
+/// a cached answer stays valid as long as the commit still exists.
+pub fn merge_base(repo: &Repository, a: Oid, b: Oid) -> Result<Oid> {
+    if a == b {
+        return Ok(a);
+    }
+    let key = format!("m {} {}", a.min(b), a.max(b));
+    if let Some(base) = lookup(repo, &key).and_then(|v| Oid::from_str(&v).ok())
+        && repo.find_commit(base).is_ok()
+    {
+        return Ok(base);
+    }
+    let base = repo.merge_base(a, b)?;
+    store(repo, &key, &base.to_string());
+    Ok(base)
+}
+
+/// The number of commits reachable from `from` but not from `hide`, cached
+/// like [`merge_base`].
+pub fn count_commits(repo: &Repository, from: Oid, hide: Oid) -> Result<usize> {
+    if from == hide {
+        return Ok(0);
+    }
+    let key = format!("c {} {}", from, hide);
+    if let Some(count) = lookup(repo, &key).and_then(|v| v.parse().ok()) {
+        return Ok(count);
+    }
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(from)?;
+    revwalk.hide(hide)?;
+    let mut count = 0usize;
+    for oid_result in revwalk {
+        oid_result?;
+        count += 1;
+    }
+    store(repo, &key, &count.to_string());
+    Ok(count)
+}
+
+/// Write a commit-graph file when the repository has none, so that every
+/// later revwalk and merge-base (git's and loom's, through libgit2) can use
+/// the generation numbers it stores instead of parsing commits. Does nothing
+/// when `core.commitGraph` is off; a failure is ignored, the file is only an
+/// optimization.
+pub fn ensure_commit_graph(repo: &Repository, workdir: &Path) {
+    let info = repo.commondir().join("objects").join("info");
+    if info.join("commit-graph").exists() || info.join("commit-graphs").exists() {
+        return;
+    }
+    let enabled = repo
+        .config()
+        .and_then(|config| config.get_bool("core.commitGraph"))
+        .unwrap_or(true);
+    if enabled {
+        let _ = crate::git::run_git(
+            workdir,
+            &[
+                "commit-graph",
+                "write",
+                "--reachable",
+                "--split",
+                "--no-progress",
+            ],
+        );
+    }
+}
+
+/// Drop the cached entries that name a commit which no longer exists (after
+/// a `git gc` pruned it). Returns how many entries were dropped and the
+/// bytes they took; with `dry_run`, the cache is left as it was.
+pub fn prune(repo: &Repository, dry_run: bool) -> Result<(usize, u64)> {
+    let path = cache_path(repo);
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Ok((0, 0));
+    };
+    let odb = repo.odb()?;
+    let (kept, dropped): (Vec<&str>, Vec<&str>) = content.lines().partition(|line| {
+        line.split(' ')
+            .filter(|word| word.len() >= 40)
+            .filter_map(|word| Oid::from_str(word).ok())
+            .all(|oid| odb.exists(oid))
+    });
+    let bytes = dropped.iter().map(|line| line.len() as u64 + 1).sum();
+    if !dry_run && !dropped.is_empty() {
+        let content = kept
+            .iter()
+            .map(|line| format!("{}\n", line))
+            .collect::<String>();
+        std::fs::write(&path, content)?;
+    }
+    Ok((dropped.len(), bytes))
+}
+
+/// The cached value for `key`, the latest one if it was stored twice.
+fn lookup(repo: &Repository, key: &str) -> Option<String> {
+    let content = std::fs::read_to_string(cache_path(repo)).ok()?;
+    content.lines().rev().find_map(|line| {
+        line.strip_prefix(key)
+            .and_then(|rest| rest.strip_prefix(' '))
+            .map(str::to_string)
+    })
+}
+
+/// Remember `value` for `key`. The cache is best-effort: a repository that
+/// can't be written to just isn't cached.
+fn store(repo: &Repository, key: &str, value: &str) {
+    let path = cache_path(repo);
+    let _ = append(&path, &format!("{} {}", key, value));
+}
+
+fn append(path: &Path, line: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", line)?;
+    drop(file);
+
+    let content = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.len() > 2 * MAX_ENTRIES {
+        std::fs::write(path, lines[lines.len() - MAX_ENTRIES..].join("\n") + "\n")?;
+    }
+    Ok(())
+}
+
+/// The cache lives in the common git directory: linked worktrees share the
+/// commits, and so the answers.
+fn cache_path(repo: &Repository) -> PathBuf {
+    repo.commondir().join("loom").join("ancestry")
+}
+
+#[cfg(test)]
+#[path = "ancestry_test.rs"]
+mod tests;
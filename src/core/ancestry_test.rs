@@ -0,0 +1,121 @@
+use crate::core::test_helpers::TestRepo;
+
+/// Two branches forked from `base`, one commit each.
+fn setup_fork() -> (TestRepo, git2::Oid, git2::Oid, git2::Oid) {
+    let test_repo = TestRepo::new();
+    let base = test_repo.head_oid();
+    let a = test_repo.commit("A", "a.txt");
+    test_repo.reset_hard(base);
+    let b = test_repo.commit("B", "b.txt");
+    (test_repo, base, a, b)
+}
+
+#[test]
+fn merge_base_matches_git2_and_is_cached() {
+    let (test_repo, base, a, b) = setup_fork();
+
+    assert_eq!(super::merge_base(&test_repo.repo, a, b).unwrap(), base);
+
+    let cache = std::fs::read_to_string(super::cache_path(&test_repo.repo)).unwrap();
+    assert_eq!(cache.lines().count(), 1);
+    // Either order hits the same entry
+    assert_eq!(super::merge_base(&test_repo.repo, b, a).unwrap(), base);
+    let cache = std::fs::read_to_string(super::cache_path(&test_repo.repo)).unwrap();
+    assert_eq!(cache.lines().count(), 1);
+}
+
+#[test]
+fn merge_base_answers_from_cache() {
+    let (test_repo, _, a, b) = setup_fork();
+    // A planted answer proves the walk is skipped
+    super::store(
+        &test_repo.repo,
+        &format!("m {} {}", a.min(b), a.max(b)),
+        &a.to_string(),
+    );
+
+    assert_eq!(super::merge_base(&test_repo.repo, a, b).unwrap(), a);
+}
+
+#[test]
+fn merge_base_ignores_cached_commit_that_is_gone() {
+    let (test_repo, base, a, b) = setup_fork();
+    super::store(
+        &test_repo.repo,
+        &format!("m {} {}", a.min(b), a.max(b)),
+        "0123456789012345678901234567890123456789",
+    );
+
+    assert_eq!(super::merge_base(&test_repo.repo, a, b).unwrap(), base);
+}
+
+#[test]
+fn count_commits_is_cached() {
+    let (test_repo, base, a, _) = setup_fork();
+
+    assert_eq!(super::count_commits(&test_repo.repo, a, base).unwrap(), 1);
+    assert_eq!(
+        super::count_commits(&test_repo.repo, base, base).unwrap(),
+        0
+    );
+    assert_eq!(
+        super::lookup(&test_repo.repo, &format!("c {} {}", a, base)).as_deref(),
+        Some("1")
+    );
+}
+
+#[test]
+fn cache_is_trimmed() {
+    let test_repo = TestRepo::new();
+    for i in 0..=2 * super::MAX_ENTRIES {
+        super::store(&test_repo.repo, &format!("c {}", i), "0");
+    }
+
+    let cache = std::fs::read_to_string(super::cache_path(&test_repo.repo)).unwrap();
+    assert_eq!(cache.lines().count(), super::MAX_ENTRIES);
+    assert_eq!(
+        super::lookup(&test_repo.repo, &format!("c {}", 2 * super::MAX_ENTRIES)).as_deref(),
+        Some("0")
+    );
+}
+
+#[test]
+fn ensure_commit_graph_writes_one_when_missing() {
+    let test_repo = TestRepo::new();
+    test_repo.commit("Second", "second.txt");
+    let info = test_repo.repo.path().join("objects").join("info");
+
+    super::ensure_commit_graph(&test_repo.repo, &test_repo.workdir());
+
+    assert!(info.join("commit-graph").exists() || info.join("commit-graphs").exists());
+}
+
+#[test]
+fn ensure_commit_graph_respects_core_commit_graph() {
+    let test_repo = TestRepo::new();
+    test_repo.set_config("core.commitGraph", "false");
+    let info = test_repo.repo.path().join("objects").join("info");
+
+    super::ensure_commit_graph(&test_repo.repo, &test_repo.workdir());
+
+    assert!(!info.join("commit-graph").exists() && !info.join("commit-graphs").exists());
+}
+
+#[test]
+fn prune_drops_entries_of_missing_commits() {
+    let (test_repo, _, a, b) = setup_fork();
+    super::merge_base(&test_repo.repo, a, b).unwrap();
+    let gone = git2::Oid::from_bytes(&[7; 20]).unwrap();
+    super::store(&test_repo.repo, &format!("c {} {}", gone, a), "3");
+
+    assert_eq!(super::prune(&test_repo.repo, true).unwrap().0, 1);
+    let cache = std::fs::read_to_string(super::cache_path(&test_repo.repo)).unwrap();
+    assert_eq!(cache.lines().count(), 2, "a dry run keeps the cache");
+
+    let (count, bytes) = super::prune(&test_repo.repo, false).unwrap();
+    assert_eq!(count, 1);
+    assert_eq!(bytes, format!("c {} {} 3\n", gone, a).len() as u64);
+    let cache = std::fs::read_to_string(super::cache_path(&test_repo.repo)).unwrap();
+    assert!(cache.starts_with("m "), "got:\n{}", cache);
+    assert_eq!(cache.lines().count(), 1);
+}
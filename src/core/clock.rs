@@ -0,0 +1,111 @@
+use anyhow::{Result, bail};
+use git2::{Repository, Signature, Time};
+
+/// Freezes loom's clock, for tests, demos, and reproducible bug reports.
+pub const FAKE_TIME_VAR: &str = "LOOM_FAKE_TIME";
+
+/// The time `LOOM_FAKE_TIME` freezes the clock at, or `None` when it is not
+/// set. Fails on a value [`parse_fake_time`] can't read.
+pub fn fake_time() -> Result<Option<Time>> {
+    let Some(value) = std::env::var_os(FAKE_TIME_VAR) else {
+        return Ok(None);
+    };
+    let value = value.to_string_lossy();
+    match parse_fake_time(&value) {
+        Some(time) => Ok(Some(time)),
+        None => bail!(
+            "`{}` is not a valid `{}`\n\
+             Use seconds since the epoch, optionally with a UTC offset: `1700000000 +0200`",
+            value,
+            FAKE_TIME_VAR
+        ),
+    }
+}
+
+/// Parse a time in git's raw format: seconds since the epoch, optionally
+/// prefixed with `@` and followed by a `+hhmm`/`-hhmm` offset (UTC if
+/// omitted).
+pub fn parse_fake_time(value: &str) -> Option<Time> {
+    let mut parts = value.split_whitespace();
+    let seconds: i64 = parts.next()?.trim_start_matches('@').parse().ok()?;
+    let offset = match parts.next() {
+        Some(offset) => {
+            let (sign, digits) = match offset.split_at_checked(1)? {
+                ("+", digits) => (1, digits),
+                ("-", digits) => (-1, digits),
+                _ => return None,
+            };
+            if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let hours: i32 = digits[..2].parse().ok()?;
+            let minutes: i32 = digits[2..].parse().ok()?;
+            sign * (hours * 60 + minutes)
+        }
+        None => 0,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(Time::new(seconds, offset))
+}
+
+/// `time` in the format git reads from `GIT_AUTHOR_DATE` and
+/// `GIT_COMMITTER_DATE`.
+pub fn git_date(time: Time) -> String {
+    let offset = time.offset_minutes();
+    format!(
+        "@{} {}{:02}{:02}",
+        time.seconds(),
+        if offset < 0 { '-' } else { '+' },
+        offset.abs() / 60,
+        offset.abs() % 60
+    )
+}
+
+/// Seconds since the epoch: the frozen time when `LOOM_FAKE_TIME` is set,
+/// the system clock otherwise.
+pub fn now() -> i64 {
+    match fake_time() {
+        Ok(Some(time)) => time.seconds(),
+        _ => std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64),
+    }
+}
+
+/// The committer of the commits, notes, and tags loom writes through
+/// libgit2. Like git, `GIT_COMMITTER_NAME` and `GIT_COMMITTER_EMAIL` take
+/// precedence over `user.name` and `user.email`; the time is frozen by
+/// `LOOM_FAKE_TIME`.
+pub fn committer(repo: &Repository) -> Result<Signature<'static>> {
+    let env = |var: &str| std::env::var(var).ok().filter(|v| !v.is_empty());
+    signature(repo, env("GIT_COMMITTER_NAME"), env("GIT_COMMITTER_EMAIL"))
+}
+
+/// A signature for `name` and `email`, falling back to the configured
+/// identity, dated now (or at `LOOM_FAKE_TIME`).
+pub fn signature(
+    repo: &Repository,
+    name: Option<String>,
+    email: Option<String>,
+) -> Result<Signature<'static>> {
+    let (name, email) = match (name, email) {
+        (Some(name), Some(email)) => (name, email),
+        (name, email) => {
+            let configured = repo.signature()?;
+            (
+                name.unwrap_or_else(|| configured.name().unwrap_or_default().to_string()),
+                email.unwrap_or_else(|| configured.email().unwrap_or_default().to_string()),
+            )
+        }
+    };
+    Ok(match fake_time()? {
+        Some(time) => Signature::new(&name, &email, &time)?,
+        None => Signature::now(&name, &email)?,
+    })
+}
+
+#[cfg(test)]
+#[path = "clock_test.rs"]
+mod tests;
@@ -0,0 +1,67 @@
+use git2::Time;
+
+use crate::core::test_helpers::TestRepo;
+
+#[test]
+fn parse_fake_time_accepts_git_raw_dates() {
+    assert_eq!(
+        super::parse_fake_time("1700000000"),
+        Some(Time::new(1_700_000_000, 0))
+    );
+    assert_eq!(
+        super::parse_fake_time("@1700000000 +0200"),
+        Some(Time::new(1_700_000_000, 120))
+    );
+    assert_eq!(
+        super::parse_fake_time(" 1700000000 -0530 "),
+        Some(Time::new(1_700_000_000, -330))
+    );
+}
+
+#[test]
+fn parse_fake_time_rejects_other_formats() {
+    for value in [
+        "",
+        "2024-01-01",
+        "1700000000 0200",
+        "1700000000 +2",
+        "1700000000 +0200 extra",
+    ] {
+        assert_eq!(super::parse_fake_time(value), None, "{:?}", value);
+    }
+}
+
+#[test]
+fn git_date_round_trips() {
+    for time in [
+        Time::new(1_700_000_000, 0),
+        Time::new(1_700_000_000, 120),
+        Time::new(1_700_000_000, -330),
+    ] {
+        assert_eq!(super::parse_fake_time(&super::git_date(time)), Some(time));
+    }
+    assert_eq!(
+        super::git_date(Time::new(1_700_000_000, -330)),
+        "@1700000000 -0530"
+    );
+}
+
+#[test]
+fn signature_falls_back_to_configured_identity() {
+    let test_repo = TestRepo::new();
+    test_repo.set_config("user.name", "Configured");
+    test_repo.set_config("user.email", "configured@test.com");
+
+    let sig = super::signature(&test_repo.repo, Some("Override".to_string()), None).unwrap();
+    assert_eq!(sig.name(), Some("Override"));
+    assert_eq!(sig.email(), Some("configured@test.com"));
+
+    let sig = super::signature(
+        &test_repo.repo,
+        Some("Bot".to_string()),
+        Some("bot@test.com".to_string()),
+    )
+    .unwrap();
+    assert_eq!(sig.name(), Some("Bot"));
+    assert_eq!(sig.email(), Some("bot@test.com"));
+}
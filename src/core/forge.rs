@@ -0,0 +1,354 @@
+//! Forges hosting git remotes (GitHub, GitLab, Bitbucket Cloud, Gitea and
+//! Forgejo, Azure DevOps) and the web pages of their commits, files, and
+//! branches.
+//!
+//! Forge APIs are called with the credentials of the git credential helpers,
+//! so whatever credential manager git already uses for the remote works.
+
+use std::io::Write as _;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use git2::Repository;
+
+use crate::core::repo;
+use crate::git::{self, Credential};
+
+/// Kind of forge hosting a remote, which decides the shape of its web URLs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeKind {
+    GitHub,
+    GitLab,
+    Bitbucket,
+    /// Gitea and its fork Forgejo (e.g. Codeberg).
+    Gitea,
+    AzureDevOps,
+}
+
+impl ForgeKind {
+    /// Parse a `loom.remote-type` value naming a forge.
+    pub fn from_config(value: &str) -> Option<ForgeKind> {
+        match value.to_lowercase().as_str() {
+            "github" => Some(ForgeKind::GitHub),
+            "gitlab" => Some(ForgeKind::GitLab),
+            "bitbucket" => Some(ForgeKind::Bitbucket),
+            "gitea" | "forgejo" => Some(ForgeKind::Gitea),
+            "azure" => Some(ForgeKind::AzureDevOps),
+            _ => None,
+        }
+    }
+}
+
+/// The web side of a git remote: where its commits and pull requests are
+/// browsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forge {
+    pub kind: ForgeKind,
+    /// Home page of the repository, e.g. `https://github.com/owner/repo`.
+    pub web_url: String,
+}
+
+impl Forge {
+    /// The forge behind `remote`. The kind comes from `loom.remote-type` when
+    /// set, otherwise from the remote's host. `None` for remotes on no known
+    /// forge, and for local paths.
+    pub fn detect(repo: &Repository, remote: &str) -> Option<Forge> {
+        let remote = repo.find_remote(remote).ok()?;
+        let kind = repo::config_string(repo, "loom.remote-type")
+            .and_then(|value| ForgeKind::from_config(&value));
+        Forge::from_url(remote.url()?, kind)
+    }
+
+    /// The forge of a remote URL, of the given kind or guessed from the host.
+    pub fn from_url(url: &str, kind: Option<ForgeKind>) -> Option<Forge> {
+        let web_url = web_url(url)?;
+        let host = web_url.split('/').nth(2)?.to_lowercase();
+        let kind = kind.or(if host.contains("github") {
+            Some(ForgeKind::GitHub)
+        } else if host.contains("gitlab") {
+            Some(ForgeKind::GitLab)
+        } else if host == "bitbucket.org" {
+            Some(ForgeKind::Bitbucket)
+        } else if host.contains("gitea") || host.contains("forgejo") || host == "codeberg.org" {
+            Some(ForgeKind::Gitea)
+        } else if host == "dev.azure.com" || host.ends_with(".visualstudio.com") {
+            Some(ForgeKind::AzureDevOps)
+        } else {
+            None
+        })?;
+        Some(Forge { kind, web_url })
+    }
+
+    /// Page of a commit.
+    pub fn commit_url(&self, oid: &str) -> String {
+        match self.kind {
+            ForgeKind::GitLab => format!("{}/-/commit/{}", self.web_url, oid),
+            ForgeKind::Bitbucket => format!("{}/commits/{}", self.web_url, oid),
+            ForgeKind::GitHub | ForgeKind::Gitea | ForgeKind::AzureDevOps => {
+                format!("{}/commit/{}", self.web_url, oid)
+            }
+        }
+    }
+
+    /// Page of a file as of a commit.
+    pub fn file_url(&self, oid: &str, path: &str) -> String {
+        match self.kind {
+            ForgeKind::GitHub => format!("{}/blob/{}/{}", self.web_url, oid, path),
+            ForgeKind::GitLab => format!("{}/-/blob/{}/{}", self.web_url, oid, path),
+            ForgeKind::Bitbucket => format!("{}/src/{}/{}", self.web_url, oid, path),
+            ForgeKind::Gitea => format!("{}/src/commit/{}/{}", self.web_url, oid, path),
+            ForgeKind::AzureDevOps => {
+                format!("{}?path=/{}&version=GC{}", self.web_url, path, oid)
+            }
+        }
+    }
+
+    /// Page listing the pull requests opened from `branch`. Bitbucket, Gitea,
+    /// and Azure DevOps cannot filter them by branch in a URL, so their
+    /// branch page is used instead.
+    pub fn branch_url(&self, branch: &str) -> String {
+        match self.kind {
+            ForgeKind::GitHub => format!("{}/pulls?q=is%3Apr+head%3A{}", self.web_url, branch),
+            ForgeKind::GitLab => format!(
+                "{}/-/merge_requests?scope=all&source_branch={}",
+                self.web_url, branch
+            ),
+            ForgeKind::Bitbucket => format!("{}/branch/{}", self.web_url, branch),
+            ForgeKind::Gitea => format!("{}/src/branch/{}", self.web_url, branch),
+            ForgeKind::AzureDevOps => format!("{}?version=GB{}", self.web_url, branch),
+        }
+    }
+
+    /// Page creating a pull request from `branch` into `target`.
+    pub fn new_pull_request_url(&self, branch: &str, target: &str) -> String {
+        match self.kind {
+            ForgeKind::GitHub => {
+                format!("{}/compare/{}...{}?expand=1", self.web_url, target, branch)
+            }
+            ForgeKind::GitLab => format!(
+                "{}/-/merge_requests/new?merge_request%5Bsource_branch%5D={}\
+                 &merge_request%5Btarget_branch%5D={}",
+                self.web_url, branch, target
+            ),
+            ForgeKind::Bitbucket => format!(
+                "{}/pull-requests/new?source={}&dest={}",
+                self.web_url, branch, target
+            ),
+            ForgeKind::Gitea => format!("{}/compare/{}...{}", self.web_url, target, branch),
+            ForgeKind::AzureDevOps => format!(
+                "{}/pullrequestcreate?sourceRef={}&targetRef={}",
+                self.web_url, branch, target
+            ),
+        }
+    }
+
+    /// Scheme and host of the forge, e.g. `https://codeberg.org`: what
+    /// credentials are stored under.
+    pub fn origin(&self) -> &str {
+        let host_end = self.web_url.find("://").map_or(0, |i| i + 3);
+        match self.web_url[host_end..].find('/') {
+            Some(i) => &self.web_url[..host_end + i],
+            None => &self.web_url,
+        }
+    }
+
+    /// The credential git's helpers hold for this forge, if any.
+    pub fn credential(&self, workdir: &Path) -> Option<Credential> {
+        git::credential_fill(workdir, self.origin())
+    }
+
+    /// REST endpoint returning the open pull request from `branch` into
+    /// `target`. Only Bitbucket Cloud and Gitea are queried directly, GitHub
+    /// and Azure DevOps go through their own CLI.
+    pub fn pull_request_api_url(&self, branch: &str, target: &str) -> Option<String> {
+        let (prefix, owner_repo) = self.web_url.rsplit_once('/')?;
+        let (prefix, owner) = prefix.rsplit_once('/')?;
+        match self.kind {
+            ForgeKind::Bitbucket => Some(format!(
+                "https://api.bitbucket.org/2.0/repositories/{}/{}/pullrequests\
+                 ?q=source.branch.name%3D%22{}%22%20AND%20destination.branch.name%3D%22{}%22\
+                 %20AND%20state%3D%22OPEN%22",
+                owner,
+                owner_repo,
+                percent_encode(&query_string(branch)),
+                percent_encode(&query_string(target))
+            )),
+            ForgeKind::Gitea => Some(format!(
+                "{}/api/v1/repos/{}/{}/pulls/{}/{}",
+                prefix,
+                owner,
+                owner_repo,
+                percent_encode(target),
+                percent_encode(branch)
+            )),
+            ForgeKind::GitHub | ForgeKind::GitLab | ForgeKind::AzureDevOps => None,
+        }
+    }
+
+    /// Web page of the pull request in a response of
+    /// [`pull_request_api_url`](Self::pull_request_api_url).
+    pub fn pull_request_from_response(&self, body: &str) -> Option<String> {
+        let json: serde_json::Value = serde_json::from_str(body).ok()?;
+        let url = match self.kind {
+            ForgeKind::Bitbucket => &json["values"][0]["links"]["html"]["href"],
+            ForgeKind::Gitea if json["state"] == "open" => &json["html_url"],
+            _ => return None,
+        };
+        url.as_str().map(str::to_string)
+    }
+
+    /// The open pull request from `branch` into `target`, asked to the
+    /// forge's API with the credential of the git credential helpers.
+    /// Anonymous when there is none, which is enough for public
+    /// repositories. `None` when there is no such pull request, or the forge
+    /// cannot be reached.
+    ///
+    /// A credential the forge accepts is approved to the helpers, one it
+    /// refuses is rejected, so they can store or forget it.
+    pub fn find_open_pull_request(
+        &self,
+        workdir: &Path,
+        branch: &str,
+        target: &str,
+    ) -> Option<String> {
+        let url = self.pull_request_api_url(branch, target)?;
+        let credential = self.credential(workdir);
+        let (status, body) = api_get(&url, credential.as_ref())?;
+        if let Some(credential) = &credential {
+            // Storing is best effort, the lookup does not depend on it
+            let _ = match status {
+                200 => git::credential_approve(workdir, self.origin(), credential),
+                401 | 403 => git::credential_reject(workdir, self.origin(), credential),
+                _ => Ok(()),
+            };
+        }
+        if status != 200 {
+            return None;
+        }
+        self.pull_request_from_response(&body)
+    }
+}
+
+/// GET `url` with curl, authenticated with `credential` when given. Returns
+/// the HTTP status and the body, `None` when the request could not be made.
+///
+/// The credential goes through curl's stdin config, never its command line.
+fn api_get(url: &str, credential: Option<&Credential>) -> Option<(u16, String)> {
+    let mut cmd = Command::new("curl");
+    cmd.args([
+        "-sS",
+        "-H",
+        "User-Agent: git-loom",
+        "-H",
+        "Accept: application/json",
+        "-w",
+        "\n%{http_code}",
+        "-K",
+        "-",
+        url,
+    ])
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::null());
+    let mut child = cmd.spawn().ok()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let config = match credential {
+            // A token without a username is sent as a bearer token
+            Some(c) if c.username.is_empty() => {
+                format!(
+                    "header = \"Authorization: Bearer {}\"\n",
+                    curl_quote(&c.password)
+                )
+            }
+            Some(c) => format!(
+                "user = \"{}:{}\"\n",
+                curl_quote(&c.username),
+                curl_quote(&c.password)
+            ),
+            None => String::new(),
+        };
+        stdin.write_all(config.as_bytes()).ok()?;
+    }
+    let output = child.wait_with_output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.rsplit_once('\n')?;
+    Some((status.trim().parse().ok()?, body.to_string()))
+}
+
+/// Escape a value for a double-quoted curl config string.
+fn curl_quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// The web home page of the repository a remote URL points to.
+///
+/// Handles HTTP(S) URLs (credentials dropped), `ssh://` URLs, SCP-style
+/// `[user@]host:path` URLs, and Azure DevOps SSH URLs
+/// (`git@ssh.dev.azure.com:v3/<org>/<project>/<repo>`). Returns `None` for
+/// local paths.
+pub fn web_url(url: &str) -> Option<String> {
+    let url = url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+
+    if let Some(rest) = url
+        .strip_prefix("git@ssh.dev.azure.com:v3/")
+        .or_else(|| url.strip_prefix("ssh://git@ssh.dev.azure.com/v3/"))
+    {
+        let mut parts = rest.splitn(3, '/');
+        let (org, project, repo) = (parts.next()?, parts.next()?, parts.next()?);
+        return Some(format!(
+            "https://dev.azure.com/{}/{}/_git/{}",
+            org, project, repo
+        ));
+    }
+
+    let (host, path) = if let Some((scheme, rest)) = url.split_once("://") {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        match scheme {
+            // Web URLs keep their port, SSH ports are not the web server's
+            "http" | "https" => return Some(format!("{}://{}/{}", scheme, host, path)),
+            "ssh" | "git" => (host.split(':').next()?, path),
+            _ => return None,
+        }
+    } else {
+        let (authority, path) = url.split_once(':')?;
+        if authority.contains('/') || authority.contains('\\') || authority.len() == 1 {
+            // A local path, or a Windows drive letter
+            return None;
+        }
+        (authority.rsplit('@').next()?, path)
+    };
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some(format!("https://{}/{}", host, path.trim_start_matches('/')))
+}
+
+/// `text` with every byte but the unreserved characters of RFC 3986
+/// (letters, digits, `-`, `.`, `_`, `~`) percent-encoded, so it can stand
+/// as a path segment or a query value.
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// `text` escaped for a double-quoted string of Bitbucket's query language.
+fn query_string(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+#[path = "forge_test.rs"]
+mod tests;
@@ -1,11 +1,14 @@
+use crate::core::forge::Forge;
 use crate::core::repo::{
-    CommitInfo, ContextCommit, FileChange, RemoteStatus, RepoInfo, UpstreamInfo,
+    CommitInfo, ContextCommit, FileChange, HiddenFlag, RemoteStatus, RepoInfo, UpstreamInfo,
 };
 use crate::core::shortid::IdAllocator;
-use colored::{Color, Colorize};
+use colored::{Color, ColoredString, Colorize};
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write;
+use std::io::IsTerminal;
 use terminal_size::{Width, terminal_size};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 // ── Theme ────────────────────────────────────────────────────────────────
 
@@ -37,8 +40,15 @@ pub struct Theme {
     pub remote_gone: Color,
     /// Conflicted file status: bold red, matching git convention.
     pub conflict: Color,
+    /// Commit checked out during a paused `edit` rebase stop.
+    pub editing: Color,
     /// Rotating colors for commit dots on feature branches.
     pub branch_dots: &'static [Color],
+    /// Draw with ASCII characters only and spell out the signals otherwise
+    /// carried by color alone (`--ascii`, or a non-UTF-8 locale).
+    pub ascii: bool,
+    /// Made for a light background: the page color of `status --html`.
+    pub light: bool,
 }
 
 impl Theme {
@@ -58,7 +68,10 @@ impl Theme {
             remote_ahead: Color::Yellow,
             remote_gone: Color::Red,
             conflict: Color::Red,
+            editing: Color::BrightYellow,
             branch_dots: BRANCH_DOTS,
+            ascii: false,
+            light: false,
         }
     }
 
@@ -78,7 +91,10 @@ impl Theme {
             remote_ahead: Color::Yellow,
             remote_gone: Color::Red,
             conflict: Color::Red,
+            editing: Color::BrightYellow,
             branch_dots: BRANCH_DOTS,
+            ascii: false,
+            light: true,
         }
     }
 }
@@ -99,6 +115,10 @@ const UNTRACKED_MULTICOLUMN_THRESHOLD: usize = 5;
 /// Width of the graph prefix ("│   ") in visible columns.
 const GRAPH_PREFIX_WIDTH: usize = 4;
 
+/// Columns left to a subject or path however narrow the terminal, below
+/// which shortening it would leave nothing readable.
+const MIN_FIT_WIDTH: usize = 12;
+
 // ── Data types ──────────────────────────────────────────────────────────
 
 /// Display configuration for the status graph renderer.
@@ -110,8 +130,129 @@ pub struct RenderOpts {
     pub theme: Theme,
     /// CWD prefix relative to repo root (empty string if at root).
     pub cwd_prefix: String,
+    /// Commit file paths to collapse into a single "N generated files" line.
+    pub collapsed_files: HashSet<String>,
+    /// Commits to hide, each run of consecutive hidden commits being replaced
+    /// by a dimmed count (e.g. commits not matching `status --path`).
+    pub hidden_commits: HashSet<git2::Oid>,
+    /// Working-change path → woven branch that last touched it. Grouped
+    /// changes are listed under a sub-header per branch (`status --group`).
+    pub change_groups: HashMap<String, String>,
+    /// Tracked files whose local changes git hides (skip-worktree or
+    /// assume-unchanged), listed dimmed after the working changes with `⊘`.
+    pub hidden_files: Vec<(String, HiddenFlag)>,
+    /// Commit OID → (insertions, deletions), shown as a compact `+N/-M`
+    /// after the message (`status --stat`). Commits not listed show nothing.
+    pub commit_stats: HashMap<git2::Oid, (usize, usize)>,
+    /// Commit a paused rebase stopped at for `edit`, drawn with a distinct
+    /// marker and an `(editing)` label.
+    pub editing_commit: Option<git2::Oid>,
+    /// Commit a detached HEAD points at, labelled `(HEAD)` in the read-only
+    /// view of `status --detached`.
+    pub detached_head: Option<git2::Oid>,
+    /// Branch name → whether its `loom worktree` is at the branch tip.
+    /// Listed branches get a `(worktree)` label, or `(worktree: outdated)`.
+    pub worktrees: HashMap<String, bool>,
+    /// Branches whose every commit already has an equivalent (same patch-id)
+    /// upstream, badged `✔ merged upstream`.
+    pub merged_upstream: HashSet<String>,
+    /// Branches past `loom.branchMaxAgeDays` or `loom.branchMaxLines`,
+    /// marked with `⚠`.
+    pub unhealthy_branches: HashSet<String>,
+    /// Branch name → last `loom check` result on its tip, with its age in
+    /// seconds, badged after the name (`status --checks`).
+    pub branch_checks: HashMap<String, (CheckState, Option<i64>)>,
+    /// Color commit dots by age (`loom.ageColors`). `None` draws every dot
+    /// in its branch color.
+    pub age_colors: Option<AgeColors>,
+    /// Commit OID → tag names, shown as a `(tag: v1.0)` label.
+    pub tags: HashMap<git2::Oid, Vec<String>>,
+    /// How lines wider than `terminal_width` are shortened (`loom.overflow`).
+    pub overflow: Overflow,
+    /// Forge the commit hashes and branch names link to, as OSC 8 terminal
+    /// hyperlinks. `None` prints no links.
+    pub forge: Option<Forge>,
+}
+
+/// What to do with lines wider than the terminal. Only applies when the
+/// terminal width is known.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Cut commit subjects at the end and paths at the start, with an
+    /// ellipsis (default).
+    #[default]
+    Truncate,
+    /// Cut commit subjects, and wrap paths onto continuation lines that keep
+    /// the graph prefix.
+    Wrap,
+    /// Leave long lines to the terminal.
+    Off,
+}
+
+impl Overflow {
+    /// Parse a `loom.overflow` value: `truncate`, `wrap`, or `off`.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "truncate" => Some(Overflow::Truncate),
+            "wrap" => Some(Overflow::Wrap),
+            "off" | "false" => Some(Overflow::Off),
+            _ => None,
+        }
+    }
+}
+
+/// Commit dots authored within the last day are drawn bold, those older
+/// than `stale_after` seconds dimmed.
+#[derive(Debug, Clone, Copy)]
+pub struct AgeColors {
+    /// Current time, in seconds since the epoch.
+    pub now: i64,
+    /// Age in seconds from which a commit is stale.
+    pub stale_after: i64,
+}
+
+impl AgeColors {
+    /// Age coloring at the current time, with commits older than
+    /// `stale_days` dimmed.
+    pub fn new(stale_days: i64) -> Self {
+        AgeColors {
+            now: crate::core::clock::now(),
+            stale_after: stale_days * DAY,
+        }
+    }
+
+    /// How the dot of a commit authored at `time` is drawn.
+    fn classify(&self, time: i64) -> Age {
+        let age = self.now - time;
+        if age < DAY {
+            Age::Fresh
+        } else if age >= self.stale_after {
+            Age::Stale
+        } else {
+            Age::Normal
+        }
+    }
 }
 
+/// Age class of a commit dot under [`AgeColors`].
+#[derive(Debug, PartialEq, Eq)]
+enum Age {
+    Fresh,
+    Normal,
+    Stale,
+}
+
+/// Combined result of the `loom check` runs on a branch tip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    Passed,
+    Failed,
+    /// Some check never ran on the tip's tree.
+    Unknown,
+}
+
+const DAY: i64 = 24 * 60 * 60;
+
 /// A logical section in the rendered status output. Sections are built from
 /// RepoInfo and rendered top-to-bottom with UTF-8 box-drawing characters.
 enum Section {
@@ -134,10 +275,229 @@ enum Section {
 
 // ── Public API ──────────────────────────────────────────────────────────
 
-/// Build sections from repo data and render them as a UTF-8 graph string.
+/// Build sections from repo data and render them as a UTF-8 graph string,
+/// or an ASCII one when the theme asks for it.
 pub fn render(info: RepoInfo, ids: &IdAllocator, opts: &RenderOpts) -> String {
     let sections = build_sections(info);
-    render_sections(&sections, ids, opts)
+    let out = render_sections(&sections, ids, opts);
+    if opts.theme.ascii {
+        to_ascii(&out)
+    } else {
+        out
+    }
+}
+
+/// Like [`render`], but write each section to `out` as soon as it is drawn,
+/// so the top of a large weave shows while the rest is still computed.
+///
+/// `prepare` runs on the commits of each branch and loose section right
+/// before that section is drawn: it can fill in what is costly to compute
+/// (file lists, diffstats) only when it is needed.
+pub fn render_streamed(
+    info: RepoInfo,
+    ids: &IdAllocator,
+    opts: &mut RenderOpts,
+    out: &mut dyn std::io::Write,
+    mut prepare: impl FnMut(&mut [CommitInfo], &mut RenderOpts) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let mut sections = build_sections(info);
+    let mut branch_color_idx: usize = 0;
+    for idx in 0..sections.len() {
+        if let Section::Branch { commits, .. } | Section::Loose(commits) = &mut sections[idx] {
+            prepare(commits, opts)?;
+        }
+        let mut chunk = String::new();
+        render_section(&mut chunk, &sections, idx, &mut branch_color_idx, ids, opts);
+        if opts.theme.ascii {
+            chunk = to_ascii(&chunk);
+        }
+        out.write_all(chunk.as_bytes())?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+/// ASCII replacements for the graph's glyphs. Symbols whose meaning was only
+/// told apart by color are spelled out.
+const ASCII_GLYPHS: &[(char, &str)] = &[
+    ('─', "-"),
+    ('│', "|"),
+    ('├', "|"),
+    ('┊', ":"),
+    ('╭', "\\"),
+    ('╯', "/"),
+    ('●', "*"),
+    ('◉', "@"),
+    ('·', "."),
+    ('⁕', "?"),
+    ('→', "->"),
+    ('↳', "->"),
+    ('✓', "(synced)"),
+    ('↑', "(ahead)"),
+    ('✗', "(gone)"),
+    ('✔', "="),
+    ('⚠', "(!)"),
+    ('\u{23EB}', "^"),
+];
+
+/// Replace the box-drawing characters and symbols of a rendered graph with
+/// their [`ASCII_GLYPHS`] equivalents. Other text (paths, messages) is kept.
+pub fn to_ascii(rendered: &str) -> String {
+    let mut out = String::with_capacity(rendered.len());
+    for c in rendered.chars() {
+        match ASCII_GLYPHS.iter().find(|(glyph, _)| *glyph == c) {
+            Some((_, ascii)) => out.push_str(ascii),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+/// Whether the locale cannot display UTF-8, from the values of `LC_ALL`,
+/// `LC_CTYPE`, and `LANG`, the first one set winning. Without any, the
+/// terminal is assumed to handle UTF-8.
+pub fn locale_is_ascii(lc_all: Option<&str>, lc_ctype: Option<&str>, lang: Option<&str>) -> bool {
+    match [lc_all, lc_ctype, lang]
+        .into_iter()
+        .flatten()
+        .find(|v| !v.is_empty())
+    {
+        Some(locale) => {
+            let locale = locale.to_ascii_lowercase();
+            !locale.contains("utf-8") && !locale.contains("utf8")
+        }
+        None => false,
+    }
+}
+
+/// Version of the `--porcelain` format. Bumped on any incompatible change.
+pub const PORCELAIN_VERSION: &str = "v1";
+
+/// Render the status as stable, tab-separated records for tools.
+///
+/// One record per line, the first field naming the record type. Records
+/// appear in the same order as the pretty graph (top to bottom):
+///
+/// ```text
+/// version   v1
+/// head      <branch>  <upstream>  <merge-base oid>  <upstream commits ahead>
+/// change    <short id>  <XY>  <path>            (working tree change)
+/// loose                                         (start of the loose commits)
+/// section                                       (start of a branch section)
+/// branch    <short id>  <name>  <remote>        (remote: none|synced|ahead|gone)
+/// commit    <short id>  <oid>  <subject>        (belongs to the last section)
+/// file      <short id>  <XY>  <path>            (belongs to the last commit, with -f)
+/// upstream  <label>  <merge-base oid>
+/// ```
+///
+/// Paths are repo-relative. Tabs, newlines, and backslashes in free-text
+/// fields are escaped as `\t`, `\n`, and `\\`.
+pub fn render_porcelain(info: RepoInfo, ids: &IdAllocator) -> String {
+    let mut out = String::new();
+    writeln!(out, "version\t{}", PORCELAIN_VERSION).unwrap();
+    writeln!(
+        out,
+        "head\t{}\t{}\t{}\t{}",
+        porcelain_escape(&info.branch_name),
+        porcelain_escape(&info.upstream.label),
+        info.upstream.merge_base_oid,
+        info.upstream.commits_ahead
+    )
+    .unwrap();
+
+    for section in build_sections(info) {
+        match section {
+            Section::WorkingChanges(changes) => {
+                for change in &changes {
+                    // v1 marks every kind of conflict `!!`
+                    let (index, worktree) = if change.is_conflicted() {
+                        ('!', '!')
+                    } else {
+                        (change.index, change.worktree)
+                    };
+                    writeln!(
+                        out,
+                        "change\t{}\t{}{}\t{}",
+                        ids.get_file(&change.path),
+                        index,
+                        worktree,
+                        porcelain_escape(&change.path)
+                    )
+                    .unwrap();
+                }
+            }
+            Section::Branch { names, commits } => {
+                writeln!(out, "section").unwrap();
+                for (name, remote) in &names {
+                    let remote = match remote {
+                        Some(RemoteStatus::Synced) => "synced",
+                        Some(RemoteStatus::Ahead) => "ahead",
+                        Some(RemoteStatus::Gone) => "gone",
+                        None => "none",
+                    };
+                    writeln!(
+                        out,
+                        "branch\t{}\t{}\t{}",
+                        ids.get_branch(name),
+                        porcelain_escape(name),
+                        remote
+                    )
+                    .unwrap();
+                }
+                render_porcelain_commits(&mut out, &commits, ids);
+            }
+            Section::Loose(commits) => {
+                writeln!(out, "loose").unwrap();
+                render_porcelain_commits(&mut out, &commits, ids);
+            }
+            Section::Upstream(upstream) => {
+                writeln!(
+                    out,
+                    "upstream\t{}\t{}",
+                    porcelain_escape(&upstream.label),
+                    upstream.merge_base_oid
+                )
+                .unwrap();
+            }
+            Section::Context(_) => {}
+        }
+    }
+
+    out
+}
+
+fn render_porcelain_commits(out: &mut String, commits: &[CommitInfo], ids: &IdAllocator) {
+    for commit in commits {
+        let sid = ids.get_commit(commit.oid);
+        writeln!(
+            out,
+            "commit\t{}\t{}\t{}",
+            sid,
+            commit.oid,
+            porcelain_escape(&commit.message)
+        )
+        .unwrap();
+        for (i, file) in commit.files.iter().enumerate() {
+            writeln!(
+                out,
+                "file\t{}:{}\t{}{}\t{}",
+                sid,
+                i,
+                file.index,
+                file.worktree,
+                porcelain_escape(&file.path)
+            )
+            .unwrap();
+        }
+    }
+}
+
+/// Escape characters that would break the tab-separated porcelain format.
+fn porcelain_escape(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
 }
 
 /// Detect terminal width and build render options for the given theme.
@@ -146,9 +506,52 @@ pub fn default_render_opts(theme: Theme, cwd_prefix: String) -> RenderOpts {
         terminal_width: terminal_size().map(|(Width(w), _)| w),
         theme,
         cwd_prefix,
+        collapsed_files: HashSet::new(),
+        hidden_commits: HashSet::new(),
+        change_groups: HashMap::new(),
+        hidden_files: Vec::new(),
+        commit_stats: HashMap::new(),
+        editing_commit: None,
+        detached_head: None,
+        worktrees: HashMap::new(),
+        merged_upstream: HashSet::new(),
+        unhealthy_branches: HashSet::new(),
+        branch_checks: HashMap::new(),
+        age_colors: None,
+        tags: HashMap::new(),
+        overflow: Overflow::default(),
+        forge: None,
     }
 }
 
+/// Whether the terminal on stdout renders OSC 8 hyperlinks.
+pub fn terminal_supports_hyperlinks() -> bool {
+    std::io::stdout().is_terminal()
+        && colored::control::SHOULD_COLORIZE.should_colorize()
+        && hyperlinks_in_env(|name| std::env::var(name).ok())
+}
+
+/// Whether the environment, read through `var`, names a terminal known to
+/// render hyperlinks. `FORCE_HYPERLINK` overrides the guess.
+pub fn hyperlinks_in_env(var: impl Fn(&str) -> Option<String>) -> bool {
+    if let Some(force) = var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+    let term = var("TERM").unwrap_or_default();
+    var("WT_SESSION").is_some()
+        || var("KONSOLE_VERSION").is_some()
+        || var("DOMTERM").is_some()
+        || matches!(
+            var("TERM_PROGRAM").as_deref(),
+            Some("iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper")
+        )
+        || var("VTE_VERSION").and_then(|v| v.parse::<u32>().ok()) >= Some(5000)
+        || matches!(
+            term.as_str(),
+            "xterm-kitty" | "xterm-ghostty" | "alacritty" | "foot"
+        )
+}
+
 /// Convert a repo-relative path to CWD-relative for display.
 fn display_path(repo_path: &str, cwd_prefix: &str) -> String {
     crate::core::repo::cwd_relative_path(repo_path, cwd_prefix)
@@ -160,7 +563,7 @@ fn display_path(repo_path: &str, cwd_prefix: &str) -> String {
 /// parent links from every branch tip. Commits absent from the returned map
 /// are "loose": they sit on the integration line and belong to no feature
 /// branch.
-fn assign_commits_to_branches(info: &RepoInfo) -> HashMap<git2::Oid, String> {
+pub(crate) fn assign_commits_to_branches(info: &RepoInfo) -> HashMap<git2::Oid, String> {
     // Build a set of branch tip OIDs for quick lookup.
     let branch_tip_set: HashSet<git2::Oid> = info.branches.iter().map(|b| b.tip_oid).collect();
 
@@ -344,55 +747,58 @@ fn is_stacked_with_next(sections: &[Section], idx: usize) -> bool {
 /// with `││` and `│├─`, while independent branches get `├╯` then `│╭─`.
 fn render_sections(sections: &[Section], ids: &IdAllocator, opts: &RenderOpts) -> String {
     let mut out = String::new();
-    let last_idx = sections.len() - 1;
     let mut branch_color_idx: usize = 0;
+    for idx in 0..sections.len() {
+        render_section(&mut out, sections, idx, &mut branch_color_idx, ids, opts);
+    }
+    out
+}
 
-    for (idx, section) in sections.iter().enumerate() {
-        match section {
-            Section::WorkingChanges(changes) => {
-                render_working_changes(&mut out, changes, ids, opts);
-            }
-            Section::Branch { names, commits } => {
-                let dot_color =
-                    opts.theme.branch_dots[branch_color_idx % opts.theme.branch_dots.len()];
-                branch_color_idx += 1;
-
-                let prev_stacked = idx > 0 && is_stacked_with_next(sections, idx - 1);
-                let next_stacked = is_stacked_with_next(sections, idx);
-
-                render_branch(
-                    &mut out,
-                    names,
-                    commits,
-                    dot_color,
-                    prev_stacked,
-                    next_stacked,
-                    idx < last_idx,
-                    ids,
-                    &opts.theme,
-                    &opts.cwd_prefix,
-                );
-            }
-            Section::Loose(commits) => {
-                render_loose(
-                    &mut out,
-                    commits,
-                    idx < last_idx,
-                    ids,
-                    &opts.theme,
-                    &opts.cwd_prefix,
-                );
-            }
-            Section::Upstream(info) => {
-                render_upstream(&mut out, info, &opts.theme);
-            }
-            Section::Context(commits) => {
-                render_context(&mut out, commits, &opts.theme);
-            }
+/// Render the section at `idx`. Branch sections take the next dot color
+/// from `branch_color_idx`.
+fn render_section(
+    out: &mut String,
+    sections: &[Section],
+    idx: usize,
+    branch_color_idx: &mut usize,
+    ids: &IdAllocator,
+    opts: &RenderOpts,
+) {
+    let last_idx = sections.len() - 1;
+    match &sections[idx] {
+        Section::WorkingChanges(changes) => {
+            render_working_changes(out, changes, ids, opts);
         }
-    }
+        Section::Branch { names, commits } => {
+            let dot_color =
+                opts.theme.branch_dots[*branch_color_idx % opts.theme.branch_dots.len()];
+            *branch_color_idx += 1;
 
-    out
+            let prev_stacked = idx > 0 && is_stacked_with_next(sections, idx - 1);
+            let next_stacked = is_stacked_with_next(sections, idx);
+
+            render_branch(
+                out,
+                names,
+                commits,
+                dot_color,
+                prev_stacked,
+                next_stacked,
+                idx < last_idx,
+                ids,
+                opts,
+            );
+        }
+        Section::Loose(commits) => {
+            render_loose(out, commits, idx < last_idx, ids, opts);
+        }
+        Section::Upstream(info) => {
+            render_upstream(out, info, opts);
+        }
+        Section::Context(commits) => {
+            render_context(out, commits, opts);
+        }
+    }
 }
 
 fn render_working_changes(
@@ -413,20 +819,22 @@ fn render_working_changes(
     )
     .unwrap();
 
-    let conflicted: Vec<&FileChange> = changes
-        .iter()
-        .filter(|f| f.index == '!' && f.worktree == '!')
-        .collect();
+    let is_untracked = |f: &FileChange| f.index == '?' && f.worktree == '?';
+    let conflicted: Vec<&FileChange> = changes.iter().filter(|f| f.is_conflicted()).collect();
     let tracked: Vec<&FileChange> = changes
         .iter()
-        .filter(|f| f.index != '!' && !(f.index == '?' && f.worktree == '?'))
+        .filter(|f| !f.is_conflicted() && !is_untracked(f))
         .collect();
     let untracked: Vec<&FileChange> = changes
         .iter()
         .filter(|f| f.index == '?' && f.worktree == '?')
         .collect();
 
-    if conflicted.is_empty() && tracked.is_empty() && untracked.is_empty() {
+    if conflicted.is_empty()
+        && tracked.is_empty()
+        && untracked.is_empty()
+        && opts.hidden_files.is_empty()
+    {
         writeln!(
             out,
             "{}   {}",
@@ -435,39 +843,87 @@ fn render_working_changes(
         )
         .unwrap();
     } else {
+        let prefix = format!("{}   ", "│".color(theme.graph));
         for change in &conflicted {
-            writeln!(
-                out,
-                "{}   {} {} {}",
-                "│".color(theme.graph),
+            let head = format!(
+                "{}{} {} ",
+                prefix,
                 ids.get_file(&change.path).color(theme.shortid).underline(),
-                "!!".color(theme.conflict).bold(),
-                display_path(&change.path, &opts.cwd_prefix)
+                format!("{}{}", change.index, change.worktree)
                     .color(theme.conflict)
-                    .bold()
-            )
-            .unwrap();
+                    .bold(),
+            );
+            let path = display_path(&change.path, &opts.cwd_prefix);
+            write_path(out, &head, &prefix, &path, opts, |p| {
+                p.color(theme.conflict).bold()
+            });
         }
+        // Ungrouped changes first, then one sub-header per probable branch
+        // in order of first appearance.
+        let mut groups: Vec<(&String, Vec<&FileChange>)> = Vec::new();
         for change in &tracked {
+            match opts.change_groups.get(&change.path) {
+                Some(branch) => match groups.iter_mut().find(|(b, _)| *b == branch) {
+                    Some((_, files)) => files.push(change),
+                    None => groups.push((branch, vec![change])),
+                },
+                None => render_tracked_change(out, change, "", ids, opts),
+            }
+        }
+        for (branch, files) in &groups {
             writeln!(
                 out,
-                "{}   {} {}{} {}",
+                "{}   {} {}{}{}",
                 "│".color(theme.graph),
-                ids.get_file(&change.path).color(theme.shortid).underline(),
-                change.index.to_string().color(theme.staged),
-                change.worktree.to_string().color(theme.unstaged),
-                display_path(&change.path, &opts.cwd_prefix)
+                "↳".color(theme.graph),
+                "[".color(theme.dim),
+                branch.color(theme.branch),
+                "]".color(theme.dim)
             )
             .unwrap();
+            for change in files {
+                render_tracked_change(out, change, "  ", ids, opts);
+            }
         }
         if !untracked.is_empty() {
             render_untracked(out, &untracked, ids, opts);
         }
+        for (path, flag) in &opts.hidden_files {
+            // No short ID: git shows no change to act on
+            let head = format!("{}   {} ", prefix, " ⊘".color(theme.dim));
+            let path = format!(
+                "{} ({})",
+                display_path(path, &opts.cwd_prefix),
+                flag.as_str()
+            );
+            write_path(out, &head, &prefix, &path, opts, |p| p.color(theme.dim));
+        }
     }
 
     writeln!(out, "{}", "│".color(theme.graph)).unwrap();
 }
 
+fn render_tracked_change(
+    out: &mut String,
+    change: &FileChange,
+    indent: &str,
+    ids: &IdAllocator,
+    opts: &RenderOpts,
+) {
+    let theme = &opts.theme;
+    let prefix = format!("{}   ", "│".color(theme.graph));
+    let head = format!(
+        "{}{}{} {}{} ",
+        prefix,
+        indent,
+        ids.get_file(&change.path).color(theme.shortid).underline(),
+        change.index.to_string().color(theme.staged),
+        change.worktree.to_string().color(theme.unstaged),
+    );
+    let path = display_path(&change.path, &opts.cwd_prefix);
+    write_path(out, &head, &prefix, &path, opts, |p| p.normal());
+}
+
 fn render_untracked(
     out: &mut String,
     untracked: &[&FileChange],
@@ -480,26 +936,26 @@ fn render_untracked(
         render_untracked_multicolumn(out, untracked, ids, width, &opts.theme, &opts.cwd_prefix);
         return;
     }
-    render_untracked_single_column(out, untracked, ids, &opts.theme, &opts.cwd_prefix);
+    render_untracked_single_column(out, untracked, ids, opts);
 }
 
 fn render_untracked_single_column(
     out: &mut String,
     untracked: &[&FileChange],
     ids: &IdAllocator,
-    theme: &Theme,
-    cwd_prefix: &str,
+    opts: &RenderOpts,
 ) {
+    let theme = &opts.theme;
+    let prefix = format!("{}   ", "│".color(theme.graph));
     for change in untracked {
-        writeln!(
-            out,
-            "{}   {} {} {}",
-            "│".color(theme.graph),
+        let head = format!(
+            "{}{} {} ",
+            prefix,
             ids.get_file(&change.path).color(theme.shortid).underline(),
             " ⁕".color(theme.untracked),
-            display_path(&change.path, cwd_prefix)
-        )
-        .unwrap();
+        );
+        let path = display_path(&change.path, &opts.cwd_prefix);
+        write_path(out, &head, &prefix, &path, opts, |p| p.normal());
     }
 }
 
@@ -522,7 +978,7 @@ fn render_untracked_multicolumn(
         .map(|f| {
             let sid = ids.get_file(&f.path);
             let disp = display_path(&f.path, cwd_prefix);
-            sid.len() + 1 + 2 + 1 + disp.len()
+            sid.width() + 1 + 2 + 1 + disp.width()
         })
         .collect();
 
@@ -573,9 +1029,9 @@ fn render_branch(
     next_stacked: bool,
     more_sections: bool,
     ids: &IdAllocator,
-    theme: &Theme,
-    cwd_prefix: &str,
+    opts: &RenderOpts,
 ) {
+    let theme = &opts.theme;
     for (i, (name, remote)) in names.iter().enumerate() {
         let branch_id = ids.get_branch(name);
         let connector = if i == 0 && !prev_stacked {
@@ -589,47 +1045,65 @@ fn render_branch(
             Some(RemoteStatus::Gone) => format!(" {}", "✗".color(theme.remote_gone)),
             None => String::new(),
         };
+        let merged = if opts.merged_upstream.contains(name) {
+            format!(" {}", "✔ merged upstream".color(theme.remote_synced))
+        } else {
+            String::new()
+        };
+        let health = if opts.unhealthy_branches.contains(name) {
+            format!(" {}", "⚠".color(theme.remote_ahead))
+        } else {
+            String::new()
+        };
+        let worktree = match opts.worktrees.get(name) {
+            Some(true) => format!(" {}", "(worktree)".color(theme.dim)),
+            Some(false) => format!(" {}", "(worktree: outdated)".color(theme.dim)),
+            None => String::new(),
+        };
         writeln!(
             out,
-            "{} {} {}{}{}{}",
+            "{} {} {}{}{}{}{}{}{}{}",
             connector.color(theme.graph),
             branch_id.color(theme.shortid).underline(),
             "[".color(theme.dim),
-            name.color(theme.branch).bold(),
+            branch_link(name, remote, opts),
             "]".color(theme.dim),
             remote_indicator,
+            merged,
+            health,
+            check_badge(name, opts),
+            worktree,
         )
         .unwrap();
     }
 
+    let hidden_prefix = format!("{}{}    ", "│".color(theme.graph), "┊".color(dot_color));
+    let mut hidden = 0;
     for commit in commits {
+        if opts.hidden_commits.contains(&commit.oid) {
+            hidden += 1;
+            continue;
+        }
+        render_hidden_commits(out, &mut hidden, &hidden_prefix, theme);
         let sid = ids.get_commit(commit.oid);
         let rest: String = commit.short_id.chars().skip(sid.len()).collect();
-        writeln!(
-            out,
-            "{}{}    {}{} {}",
+        let head = format!(
+            "{}{}    {} ",
             "│".color(theme.graph),
-            "●".color(dot_color),
-            sid.color(theme.shortid).underline(),
-            rest.color(theme.dim),
-            commit.message
-        )
-        .unwrap();
-        for (i, file) in commit.files.iter().enumerate() {
-            let file_sid = format!("{}:{}", sid, i);
-            writeln!(
-                out,
-                "{}{}      {} {}{} {}",
-                "│".color(theme.graph),
-                "┊".color(dot_color),
-                file_sid.color(theme.shortid).underline(),
-                file.index.to_string().color(theme.staged),
-                file.worktree.to_string().color(theme.unstaged),
-                display_path(&file.path, cwd_prefix)
-            )
-            .unwrap();
-        }
+            commit_dot(commit, dot_color, opts),
+            commit_link(sid, &rest, commit.oid, opts),
+        );
+        let tail = format!(
+            "{}{}",
+            commit_stat(commit, opts),
+            commit_label(commit, opts)
+        );
+        let message = fit_subject(&commit.message, &head, &tail, opts);
+        writeln!(out, "{}{}{}", head, message, tail).unwrap();
+        let prefix = format!("{}{}      ", "│".color(theme.graph), "┊".color(dot_color));
+        render_commit_files(out, commit, sid, &prefix, opts);
     }
+    render_hidden_commits(out, &mut hidden, &hidden_prefix, theme);
     if next_stacked {
         writeln!(out, "{}", "││".color(theme.graph)).unwrap();
     } else {
@@ -640,46 +1114,216 @@ fn render_branch(
     }
 }
 
+/// The `status --checks` badge of branch `name`, e.g. `checks passed 2h ago`.
+fn check_badge(name: &str, opts: &RenderOpts) -> String {
+    let theme = &opts.theme;
+    let Some((state, age)) = opts.branch_checks.get(name) else {
+        return String::new();
+    };
+    let age = age
+        .map(|a| format!(" {}", age_label(a)))
+        .unwrap_or_default();
+    let badge = match state {
+        CheckState::Passed => format!("checks passed{}", age).color(theme.remote_synced),
+        CheckState::Failed => format!("checks failed{}", age).color(theme.remote_gone),
+        CheckState::Unknown => "checks unknown".color(theme.dim),
+    };
+    format!(" {}", badge)
+}
+
+/// A compact age, e.g. `5m ago` or `3d ago`.
+fn age_label(seconds: i64) -> String {
+    match seconds {
+        s if s < 60 => "just now".to_string(),
+        s if s < 60 * 60 => format!("{}m ago", s / 60),
+        s if s < DAY => format!("{}h ago", s / (60 * 60)),
+        s => format!("{}d ago", s / DAY),
+    }
+}
+
 fn render_loose(
     out: &mut String,
     commits: &[CommitInfo],
     more_sections: bool,
     ids: &IdAllocator,
-    theme: &Theme,
-    cwd_prefix: &str,
+    opts: &RenderOpts,
 ) {
+    let theme = &opts.theme;
+    let hidden_prefix = format!("{}    ", "┊".color(theme.graph));
+    let mut hidden = 0;
     for commit in commits {
+        if opts.hidden_commits.contains(&commit.oid) {
+            hidden += 1;
+            continue;
+        }
+        render_hidden_commits(out, &mut hidden, &hidden_prefix, theme);
         let sid = ids.get_commit(commit.oid);
         let rest: String = commit.short_id.chars().skip(sid.len()).collect();
-        writeln!(
-            out,
-            "{}    {}{} {}",
-            "●".color(theme.graph),
-            sid.color(theme.shortid).underline(),
-            rest.color(theme.dim),
-            commit.message
-        )
-        .unwrap();
-        for (i, file) in commit.files.iter().enumerate() {
-            let file_sid = format!("{}:{}", sid, i);
-            writeln!(
-                out,
-                "{}       {} {}{} {}",
-                "┊".color(theme.graph),
-                file_sid.color(theme.shortid).underline(),
-                file.index.to_string().color(theme.staged),
-                file.worktree.to_string().color(theme.unstaged),
-                display_path(&file.path, cwd_prefix)
-            )
-            .unwrap();
-        }
+        let head = format!(
+            "{}    {} ",
+            commit_dot(commit, theme.graph, opts),
+            commit_link(sid, &rest, commit.oid, opts),
+        );
+        let tail = format!(
+            "{}{}",
+            commit_stat(commit, opts),
+            commit_label(commit, opts)
+        );
+        let message = fit_subject(&commit.message, &head, &tail, opts);
+        writeln!(out, "{}{}{}", head, message, tail).unwrap();
+        let prefix = format!("{}       ", "┊".color(theme.graph));
+        render_commit_files(out, commit, sid, &prefix, opts);
     }
+    render_hidden_commits(out, &mut hidden, &hidden_prefix, theme);
     if more_sections {
         writeln!(out, "{}", "│".color(theme.graph)).unwrap();
     }
 }
 
-fn render_upstream(out: &mut String, info: &UpstreamInfo, theme: &Theme) {
+/// Render the files of a commit, one per line after `prefix`. Files listed in
+/// `opts.collapsed_files` are summarized on a single trailing line; the
+/// remaining files keep their original `sid:index` short IDs.
+fn render_commit_files(
+    out: &mut String,
+    commit: &CommitInfo,
+    sid: &str,
+    prefix: &str,
+    opts: &RenderOpts,
+) {
+    let theme = &opts.theme;
+    let mut collapsed = 0;
+    for (i, file) in commit.files.iter().enumerate() {
+        if opts.collapsed_files.contains(&file.path) {
+            collapsed += 1;
+            continue;
+        }
+        let file_sid = format!("{}:{}", sid, i);
+        let head = format!(
+            "{}{} {}{} ",
+            prefix,
+            file_sid.color(theme.shortid).underline(),
+            file.index.to_string().color(theme.staged),
+            file.worktree.to_string().color(theme.unstaged),
+        );
+        let path = display_path(&file.path, &opts.cwd_prefix);
+        write_path(out, &head, prefix, &path, opts, |p| p.normal());
+    }
+    if collapsed > 0 {
+        let label = format!(
+            "{} generated file{}",
+            collapsed,
+            if collapsed == 1 { "" } else { "s" }
+        );
+        writeln!(out, "{}{}", prefix, label.color(theme.dim)).unwrap();
+    }
+}
+
+/// The dot drawn for a commit: `●` in `color`, or a bold `◉` for the commit
+/// being edited.
+fn commit_dot(commit: &CommitInfo, color: Color, opts: &RenderOpts) -> ColoredString {
+    if opts.editing_commit == Some(commit.oid) {
+        return "◉".color(opts.theme.editing).bold();
+    }
+    match opts.age_colors.map(|ages| ages.classify(commit.time)) {
+        Some(Age::Fresh) => "●".color(color).bold(),
+        // Dimming alone is lost in ASCII mode: draw stale commits hollow
+        Some(Age::Stale) if opts.theme.ascii => "o".color(opts.theme.dim),
+        Some(Age::Stale) => "●".color(opts.theme.dim),
+        _ => "●".color(color),
+    }
+}
+
+/// Wrap `text` in an OSC 8 hyperlink to `url`, when there is one.
+fn link(text: impl std::fmt::Display, url: Option<String>) -> String {
+    match url {
+        Some(url) => format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text),
+        None => text.to_string(),
+    }
+}
+
+/// A commit's short ID and the rest of its hash, linked to its forge page.
+fn commit_link(sid: &str, rest: &str, oid: git2::Oid, opts: &RenderOpts) -> String {
+    let theme = &opts.theme;
+    link(
+        format!(
+            "{}{}",
+            sid.color(theme.shortid).underline(),
+            rest.color(theme.dim)
+        ),
+        opts.forge.as_ref().map(|f| f.commit_url(&oid.to_string())),
+    )
+}
+
+/// A branch name, linked to its pull requests when it was pushed.
+fn branch_link(name: &str, remote: &Option<RemoteStatus>, opts: &RenderOpts) -> String {
+    let url = match remote {
+        Some(RemoteStatus::Synced | RemoteStatus::Ahead) => {
+            opts.forge.as_ref().map(|f| f.branch_url(name))
+        }
+        _ => None,
+    };
+    link(name.color(opts.theme.branch).bold(), url)
+}
+
+/// The ` (editing)` suffix for the commit being edited, ` (HEAD)` for the
+/// commit a detached HEAD points at, and ` (tag: ...)` for tagged commits.
+fn commit_label(commit: &CommitInfo, opts: &RenderOpts) -> String {
+    let mut label = String::new();
+    if opts.editing_commit == Some(commit.oid) {
+        label.push_str(&format!(
+            " {}",
+            "(editing)".color(opts.theme.editing).bold()
+        ));
+    }
+    if opts.detached_head == Some(commit.oid) {
+        label.push_str(&format!(" {}", "(HEAD)".color(opts.theme.label).bold()));
+    }
+    if let Some(tags) = opts.tags.get(&commit.oid) {
+        label.push_str(&format!(
+            " {}",
+            format!("(tag: {})", tags.join(", ")).color(opts.theme.label)
+        ));
+    }
+    label
+}
+
+/// The ` +N/-M` suffix for a commit line, or an empty string when no stat was
+/// computed for the commit.
+fn commit_stat(commit: &CommitInfo, opts: &RenderOpts) -> String {
+    let Some((insertions, deletions)) = opts.commit_stats.get(&commit.oid) else {
+        return String::new();
+    };
+    format!(
+        " {}{}{}",
+        format!("+{}", insertions).color(opts.theme.staged),
+        "/".color(opts.theme.dim),
+        format!("-{}", deletions).color(opts.theme.unstaged)
+    )
+}
+
+/// Flush a run of hidden commits as a single dimmed count line, then reset
+/// the counter. Does nothing when the run is empty.
+fn render_hidden_commits(out: &mut String, hidden: &mut usize, prefix: &str, theme: &Theme) {
+    if *hidden == 0 {
+        return;
+    }
+    let label = format!(
+        "{} commit{} filtered out",
+        hidden,
+        if *hidden == 1 { "" } else { "s" }
+    );
+    writeln!(out, "{}{}", prefix, label.color(theme.dim)).unwrap();
+    *hidden = 0;
+}
+
+fn render_upstream(out: &mut String, info: &UpstreamInfo, opts: &RenderOpts) {
+    let theme = &opts.theme;
+    let base = link(
+        info.base_short_id.color(theme.dim),
+        opts.forge
+            .as_ref()
+            .map(|f| f.commit_url(&info.merge_base_oid.to_string())),
+    );
     if info.commits_ahead > 0 {
         let count_text = format!(
             "\u{23EB} {} new commit{}",
@@ -698,46 +1342,391 @@ fn render_upstream(out: &mut String, info: &UpstreamInfo, theme: &Theme) {
             count_text
         )
         .unwrap();
-        writeln!(
-            out,
-            "{} {} {} {} {}",
+        let head = format!(
+            "{} {} {} {} ",
             "├╯".color(theme.graph),
-            info.base_short_id.color(theme.dim),
+            base,
             "(common base)".color(theme.label),
             info.base_date.color(theme.dim),
-            info.base_message.color(theme.message)
-        )
-        .unwrap();
+        );
+        let message = fit_subject(&info.base_message, &head, "", opts);
+        writeln!(out, "{}{}", head, message.color(theme.message)).unwrap();
+    } else if info.local_only && info.label.is_empty() {
+        let head = format!(
+            "{} {} {} ",
+            "●".color(theme.graph),
+            base,
+            "(root)".color(theme.label),
+        );
+        let message = fit_subject(&info.base_message, &head, "", opts);
+        writeln!(out, "{}{}", head, message.color(theme.message)).unwrap();
     } else {
-        writeln!(
-            out,
-            "{} {} {} {}{}{} {}",
+        let head = format!(
+            "{} {} {} {}{}{} ",
             "●".color(theme.graph),
-            info.base_short_id.color(theme.dim),
-            "(upstream)".color(theme.label),
+            base,
+            if info.local_only {
+                "(base)"
+            } else {
+                "(upstream)"
+            }
+            .color(theme.label),
             "[".color(theme.dim),
             info.label.color(theme.branch).bold(),
             "]".color(theme.dim),
-            info.base_message.color(theme.message)
-        )
-        .unwrap();
+        );
+        let message = fit_subject(&info.base_message, &head, "", opts);
+        writeln!(out, "{}{}", head, message.color(theme.message)).unwrap();
     }
 }
 
-fn render_context(out: &mut String, commits: &[ContextCommit], theme: &Theme) {
+fn render_context(out: &mut String, commits: &[ContextCommit], opts: &RenderOpts) {
+    let theme = &opts.theme;
     for commit in commits {
-        writeln!(
-            out,
-            "{} {} {} {}",
+        let head = format!(
+            "{} {} {} ",
             "·".color(theme.dim),
             commit.short_hash.color(theme.dim),
             commit.date.color(theme.dim),
-            commit.message.color(theme.message),
-        )
-        .unwrap();
+        );
+        let message = fit_subject(&commit.message, &head, "", opts);
+        writeln!(out, "{}{}", head, message.color(theme.message)).unwrap();
+    }
+}
+
+// ── Overflow ────────────────────────────────────────────────────────────
+
+/// Columns a rendered string takes on screen, ANSI escapes aside. Wide
+/// characters (emoji, CJK) take two columns.
+fn visible_width(rendered: &str) -> usize {
+    let mut width = 0;
+    let mut chars = rendered.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            skip_escape(&mut chars);
+        } else {
+            width += c.width().unwrap_or(0);
+        }
+    }
+    width
+}
+
+/// Skip the rest of an escape sequence: a color (`ESC [ ... m`) or an OSC 8
+/// hyperlink (`ESC ] ... ESC \`).
+pub(crate) fn skip_escape(chars: &mut std::str::Chars) {
+    if chars.clone().next() == Some(']') {
+        while let Some(c) = chars.next() {
+            if c == '\x07' || (c == '\x1b' && chars.next() == Some('\\')) {
+                break;
+            }
+        }
+        return;
+    }
+    for c in chars.by_ref() {
+        if c == 'm' {
+            break;
+        }
+    }
+}
+
+/// The longest start of `text` fitting in `max` columns.
+fn take_width(text: &str, max: usize) -> &str {
+    let mut width = 0;
+    for (i, c) in text.char_indices() {
+        width += c.width().unwrap_or(0);
+        if width > max {
+            return &text[..i];
+        }
+    }
+    text
+}
+
+/// The longest end of `text` fitting in `max` columns.
+fn take_width_end(text: &str, max: usize) -> &str {
+    let mut width = 0;
+    for (i, c) in text.char_indices().rev() {
+        width += c.width().unwrap_or(0);
+        if width > max {
+            return &text[i + c.len_utf8()..];
+        }
+    }
+    text
+}
+
+/// Columns left between `head` and `tail` on a terminal line, or `None`
+/// when long lines are left alone.
+fn available_width(head: &str, tail: &str, opts: &RenderOpts) -> Option<usize> {
+    if opts.overflow == Overflow::Off {
+        return None;
+    }
+    let width = opts.terminal_width? as usize;
+    Some(
+        width
+            .saturating_sub(visible_width(head) + visible_width(tail))
+            .max(MIN_FIT_WIDTH),
+    )
+}
+
+fn ellipsis(opts: &RenderOpts) -> &'static str {
+    if opts.theme.ascii { "..." } else { "…" }
+}
+
+/// A commit subject shortened at the end to fit between `head` and `tail`.
+fn fit_subject(message: &str, head: &str, tail: &str, opts: &RenderOpts) -> String {
+    let Some(available) = available_width(head, tail, opts) else {
+        return message.to_string();
+    };
+    if message.width() <= available {
+        return message.to_string();
+    }
+    let ellipsis = ellipsis(opts);
+    let kept = take_width(message, available.saturating_sub(ellipsis.width()));
+    format!("{}{}", kept.trim_end(), ellipsis)
+}
+
+/// Write `head` then `path`, styled by `style`. A path too long for the
+/// terminal loses its start (the file name matters most), or with
+/// [`Overflow::Wrap`] continues on lines made of `prefix` padded to the
+/// path's column.
+fn write_path(
+    out: &mut String,
+    head: &str,
+    prefix: &str,
+    path: &str,
+    opts: &RenderOpts,
+    style: impl Fn(&str) -> ColoredString,
+) {
+    let available = match available_width(head, "", opts) {
+        Some(available) if path.width() > available => available,
+        _ => {
+            writeln!(out, "{}{}", head, style(path)).unwrap();
+            return;
+        }
+    };
+    if opts.overflow == Overflow::Wrap {
+        let indent = format!(
+            "{}{}",
+            prefix,
+            " ".repeat(visible_width(head).saturating_sub(visible_width(prefix)))
+        );
+        let mut rest = path;
+        let mut lead = head;
+        while !rest.is_empty() {
+            // A character wider than the line still goes on it
+            let mut line = take_width(rest, available);
+            if line.is_empty() {
+                line = &rest[..rest.chars().next().map_or(0, char::len_utf8)];
+            }
+            writeln!(out, "{}{}", lead, style(line)).unwrap();
+            rest = &rest[line.len()..];
+            lead = &indent;
+        }
+        return;
+    }
+    let ellipsis = ellipsis(opts);
+    let kept = take_width_end(path, available.saturating_sub(ellipsis.width()));
+    writeln!(out, "{}{}", head, style(&format!("{}{}", ellipsis, kept))).unwrap();
+}
+
+// ── HTML export ─────────────────────────────────────────────────────────
+
+/// The 16 basic terminal colors, as xterm draws them.
+const HTML_PALETTE: [&str; 16] = [
+    "#000000", "#cd0000", "#00cd00", "#cdcd00", "#0000ee", "#cd00cd", "#00cdcd", "#e5e5e5",
+    "#7f7f7f", "#ff0000", "#00ff00", "#ffff00", "#5c5cff", "#ff00ff", "#00ffff", "#ffffff",
+];
+
+/// Text attributes set by ANSI SGR sequences.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct HtmlStyle {
+    color: Option<String>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl HtmlStyle {
+    fn css(&self) -> String {
+        let mut css = Vec::new();
+        if let Some(color) = &self.color {
+            css.push(format!("color:{}", color));
+        }
+        if self.bold {
+            css.push("font-weight:bold".to_string());
+        }
+        if self.dim {
+            css.push("opacity:0.6".to_string());
+        }
+        if self.italic {
+            css.push("font-style:italic".to_string());
+        }
+        if self.underline {
+            css.push("text-decoration:underline".to_string());
+        }
+        css.join(";")
+    }
+
+    /// Apply the `;`-separated parameters of an SGR (`ESC [ ... m`) sequence.
+    fn apply(&mut self, params: &str) {
+        let mut codes = params.split(';').map(|code| code.parse().unwrap_or(0));
+        while let Some(code) = codes.next() {
+            match code {
+                0 => *self = HtmlStyle::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                22 => (self.bold, self.dim) = (false, false),
+                23 => self.italic = false,
+                24 => self.underline = false,
+                30..=37 => self.color = Some(HTML_PALETTE[code as usize - 30].to_string()),
+                90..=97 => self.color = Some(HTML_PALETTE[code as usize - 82].to_string()),
+                39 => self.color = None,
+                38 => match codes.next() {
+                    Some(5) => self.color = codes.next().map(ansi256_css),
+                    Some(2) => {
+                        let rgb: Vec<u32> = codes.by_ref().take(3).collect();
+                        if let [r, g, b] = rgb[..] {
+                            self.color = Some(format!("#{:02x}{:02x}{:02x}", r, g, b));
+                        }
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+}
+
+/// CSS color of entry `n` of the 256-color terminal palette.
+fn ansi256_css(n: u32) -> String {
+    match n {
+        0..=15 => HTML_PALETTE[n as usize].to_string(),
+        16..=231 => {
+            let level = |v: u32| if v == 0 { 0 } else { 55 + v * 40 };
+            let n = n - 16;
+            format!(
+                "#{:02x}{:02x}{:02x}",
+                level(n / 36),
+                level(n / 6 % 6),
+                level(n % 6)
+            )
+        }
+        _ => {
+            let gray = 8 + (n.min(255) - 232) * 10;
+            format!("#{:02x}{:02x}{:02x}", gray, gray, gray)
+        }
     }
 }
 
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Turn a rendered graph into a standalone HTML page titled `title`: ANSI
+/// colors become styled spans, OSC 8 hyperlinks become links, and the page
+/// background suits the theme.
+pub fn to_html(rendered: &str, title: &str, light: bool) -> String {
+    let (background, foreground) = if light {
+        ("#ffffff", "#1e1e1e")
+    } else {
+        ("#1e1e1e", "#d4d4d4")
+    };
+    let mut body = String::new();
+    let mut style = HtmlStyle::default();
+    let mut span_open = false;
+    let mut link_open = false;
+    let mut chars = rendered.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            if !span_open && style != HtmlStyle::default() {
+                write!(body, "<span style=\"{}\">", style.css()).unwrap();
+                span_open = true;
+            }
+            match c {
+                '&' => body.push_str("&amp;"),
+                '<' => body.push_str("&lt;"),
+                '>' => body.push_str("&gt;"),
+                c => body.push(c),
+            }
+            continue;
+        }
+        if span_open {
+            body.push_str("</span>");
+            span_open = false;
+        }
+        match chars.next() {
+            // SGR: `ESC [ params m` (other CSI sequences are dropped)
+            Some('[') => {
+                let mut params = String::new();
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        if c == 'm' {
+                            style.apply(&params);
+                        }
+                        break;
+                    }
+                    params.push(c);
+                }
+            }
+            // OSC 8 hyperlink: `ESC ] 8 ; ; url ST`, an empty URL closing it
+            Some(']') => {
+                let mut osc = String::new();
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                    osc.push(c);
+                }
+                if let Some(url) = osc.strip_prefix("8;;") {
+                    if link_open {
+                        body.push_str("</a>");
+                        link_open = false;
+                    }
+                    if !url.is_empty() {
+                        write!(body, "<a href=\"{}\">", html_escape(url)).unwrap();
+                        link_open = true;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if span_open {
+        body.push_str("</span>");
+    }
+    if link_open {
+        body.push_str("</a>");
+    }
+    format!(
+        "<!DOCTYPE html>\n\
+         <html>\n\
+         <head>\n\
+         <meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n\
+         <style>\n\
+         body {{ background: {background}; color: {foreground}; }}\n\
+         pre {{ font-family: ui-monospace, Menlo, Consolas, monospace; line-height: 1.3; }}\n\
+         a {{ color: inherit; text-decoration: none; }}\n\
+         a:hover {{ text-decoration: underline; }}\n\
+         </style>\n\
+         </head>\n\
+         <body>\n\
+         <pre>{body}</pre>\n\
+         </body>\n\
+         </html>\n",
+        title = html_escape(title),
+    )
+}
+
 #[cfg(test)]
 #[path = "graph_test.rs"]
 mod tests;
@@ -1,8 +1,10 @@
 use git2::Oid;
+use unicode_width::UnicodeWidthStr;
 
 use crate::core::graph::{self, RenderOpts, Theme};
 use crate::core::repo::{
-    BranchInfo, CommitInfo, ContextCommit, FileChange, RemoteStatus, RepoInfo, UpstreamInfo,
+    BranchInfo, CommitInfo, ContextCommit, FileChange, HiddenFlag, RemoteStatus, RepoInfo,
+    UpstreamInfo,
 };
 
 /// Strip ANSI escape codes so tests can compare plain text.
@@ -11,12 +13,7 @@ fn strip_ansi(s: &str) -> String {
     let mut chars = s.chars();
     while let Some(c) = chars.next() {
         if c == '\x1b' {
-            // Skip until 'm' (end of ANSI escape sequence)
-            for inner in chars.by_ref() {
-                if inner == 'm' {
-                    break;
-                }
-            }
+            graph::skip_escape(&mut chars);
         } else {
             out.push(c);
         }
@@ -29,6 +26,21 @@ fn default_opts() -> RenderOpts {
         terminal_width: None,
         theme: Theme::dark(),
         cwd_prefix: String::new(),
+        collapsed_files: Default::default(),
+        hidden_commits: Default::default(),
+        change_groups: Default::default(),
+        hidden_files: Default::default(),
+        commit_stats: Default::default(),
+        editing_commit: None,
+        detached_head: None,
+        worktrees: Default::default(),
+        merged_upstream: Default::default(),
+        unhealthy_branches: Default::default(),
+        branch_checks: Default::default(),
+        age_colors: None,
+        tags: Default::default(),
+        overflow: Default::default(),
+        forge: None,
     }
 }
 
@@ -44,6 +56,21 @@ fn render_plain_with_width(info: RepoInfo, width: u16) -> String {
         terminal_width: Some(width),
         theme: Theme::dark(),
         cwd_prefix: String::new(),
+        collapsed_files: Default::default(),
+        hidden_commits: Default::default(),
+        change_groups: Default::default(),
+        hidden_files: Default::default(),
+        commit_stats: Default::default(),
+        editing_commit: None,
+        detached_head: None,
+        worktrees: Default::default(),
+        merged_upstream: Default::default(),
+        unhealthy_branches: Default::default(),
+        branch_checks: Default::default(),
+        age_colors: None,
+        tags: Default::default(),
+        overflow: Default::default(),
+        forge: None,
     };
     let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
     strip_ansi(&graph::render(info, &ids, &opts))
@@ -63,6 +90,7 @@ fn commit(byte: u8, message: &str, parent: Option<u8>) -> CommitInfo {
         message: message.to_string(),
         parent_oid: parent.map(oid),
         files: vec![],
+        time: 0,
     }
 }
 
@@ -78,6 +106,7 @@ fn commit_with_files(
         message: message.to_string(),
         parent_oid: parent.map(oid),
         files,
+        time: 0,
     }
 }
 
@@ -91,6 +120,7 @@ fn base_info() -> RepoInfo {
             base_date: "2025-07-06".to_string(),
             commits_ahead: 0,
             merge_base_oid: oid(0xAA),
+            local_only: false,
         },
         commits: vec![],
         branches: vec![],
@@ -113,6 +143,19 @@ fn no_commits_no_changes() {
     );
 }
 
+#[test]
+fn local_only_base_labels() {
+    let mut info = base_info();
+    info.upstream.local_only = true;
+    info.upstream.label = String::new();
+    assert!(render_plain(info).ends_with("● aaa0000 (root) Initial commit\n"));
+
+    let mut info = base_info();
+    info.upstream.local_only = true;
+    info.upstream.label = "trunk".to_string();
+    assert!(render_plain(info).ends_with("● aaa0000 (base) [trunk] Initial commit\n"));
+}
+
 #[test]
 fn working_changes_shown() {
     let mut info = base_info();
@@ -136,6 +179,34 @@ fn working_changes_shown() {
     );
 }
 
+#[test]
+fn conflicted_files_show_their_state_first() {
+    let mut info = base_info();
+    info.working_changes = vec![
+        FileChange {
+            path: "src/main.rs".to_string(),
+            index: ' ',
+            worktree: 'M',
+        },
+        FileChange {
+            path: "src/lib.rs".to_string(),
+            index: 'U',
+            worktree: 'U',
+        },
+        FileChange {
+            path: "old.rs".to_string(),
+            index: 'D',
+            worktree: 'U',
+        },
+    ];
+
+    let output = render_plain(info);
+    let lines: Vec<&str> = output.lines().collect();
+    assert!(lines[1].ends_with("UU src/lib.rs"), "got: {}", lines[1]);
+    assert!(lines[2].ends_with("DU old.rs"), "got: {}", lines[2]);
+    assert!(lines[3].ends_with(" M src/main.rs"), "got: {}", lines[3]);
+}
+
 #[test]
 fn single_branch() {
     let mut info = base_info();
@@ -570,6 +641,37 @@ fn files_shown_under_loose_commits() {
     );
 }
 
+#[test]
+fn collapsed_files_summarized_on_one_line() {
+    let file = |path: &str| FileChange {
+        path: path.to_string(),
+        index: 'M',
+        worktree: ' ',
+    };
+    let mut info = base_info();
+    info.commits = vec![commit_with_files(
+        2,
+        "Regenerate",
+        Some(1),
+        vec![file("Cargo.lock"), file("src/lib.rs"), file("gen/api.rs")],
+    )];
+
+    let mut opts = default_opts();
+    opts.collapsed_files = ["Cargo.lock".to_string(), "gen/api.rs".to_string()].into();
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let output = strip_ansi(&graph::render(info, &ids, &opts));
+
+    // Visible files keep their original index so short IDs stay stable
+    assert!(
+        output.contains(
+            "●    0200002 Regenerate\n┊       02:1 M  src/lib.rs\n┊       2 generated files\n"
+        ),
+        "expected generated files collapsed, got:\n{}",
+        output
+    );
+    assert!(!output.contains("Cargo.lock"));
+}
+
 #[test]
 fn commit_file_ids_use_commit_sid_colon_index() {
     let mut info = base_info();
@@ -999,6 +1101,95 @@ fn remote_synced_shows_checkmark() {
     );
 }
 
+#[test]
+fn worktree_label_after_remote_indicator() {
+    let render = |opts: &RenderOpts| {
+        let mut info = base_info();
+        info.commits = vec![commit(2, "A2", Some(1)), commit(1, "A1", None)];
+        info.branches = vec![BranchInfo {
+            name: "feature-a".to_string(),
+            tip_oid: oid(2),
+            remote: Some(RemoteStatus::Synced),
+        }];
+        let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+        strip_ansi(&graph::render(info, &ids, opts))
+    };
+
+    let mut opts = default_opts();
+    opts.worktrees.insert("feature-a".to_string(), true);
+    let output = render(&opts);
+    assert!(
+        output.contains("[feature-a] ✓ (worktree)\n"),
+        "expected worktree label, got:\n{}",
+        output
+    );
+
+    opts.worktrees.insert("feature-a".to_string(), false);
+    let output = render(&opts);
+    assert!(
+        output.contains("[feature-a] ✓ (worktree: outdated)\n"),
+        "expected outdated worktree label, got:\n{}",
+        output
+    );
+}
+
+#[test]
+fn merged_upstream_badge_after_remote_indicator() {
+    let mut info = base_info();
+    info.commits = vec![commit(2, "A2", Some(1)), commit(1, "A1", None)];
+    info.branches = vec![BranchInfo {
+        name: "feature-a".to_string(),
+        tip_oid: oid(2),
+        remote: Some(RemoteStatus::Synced),
+    }];
+
+    let mut opts = default_opts();
+    opts.merged_upstream.insert("feature-a".to_string());
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let output = strip_ansi(&graph::render(info, &ids, &opts));
+    assert!(
+        output.contains("[feature-a] ✓ ✔ merged upstream\n"),
+        "expected merged badge, got:\n{}",
+        output
+    );
+}
+
+#[test]
+fn check_badge_shows_state_and_age() {
+    let render = |state: (graph::CheckState, Option<i64>)| {
+        let mut info = base_info();
+        info.commits = vec![commit(2, "A2", Some(1)), commit(1, "A1", None)];
+        info.branches = vec![BranchInfo {
+            name: "feature-a".to_string(),
+            tip_oid: oid(2),
+            remote: None,
+        }];
+        let mut opts = default_opts();
+        opts.branch_checks.insert("feature-a".to_string(), state);
+        let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+        strip_ansi(&graph::render(info, &ids, &opts))
+    };
+
+    let output = render((graph::CheckState::Passed, Some(2 * 60 * 60)));
+    assert!(
+        output.contains("[feature-a] checks passed 2h ago\n"),
+        "expected passed badge, got:\n{}",
+        output
+    );
+    let output = render((graph::CheckState::Failed, Some(30)));
+    assert!(
+        output.contains("[feature-a] checks failed just now\n"),
+        "expected failed badge, got:\n{}",
+        output
+    );
+    let output = render((graph::CheckState::Unknown, None));
+    assert!(
+        output.contains("[feature-a] checks unknown\n"),
+        "expected unknown badge, got:\n{}",
+        output
+    );
+}
+
 #[test]
 fn remote_ahead_shows_up_arrow() {
     let mut info = base_info();
@@ -1054,3 +1245,605 @@ fn no_remote_shows_no_indicator() {
         header_line
     );
 }
+
+// ── Porcelain ───────────────────────────────────────────────────────────
+
+fn render_porcelain(info: RepoInfo) -> String {
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    graph::render_porcelain(info, &ids)
+}
+
+#[test]
+fn porcelain_empty() {
+    let output = render_porcelain(base_info());
+    let base = oid(0xAA);
+    assert_eq!(
+        output,
+        format!("version\tv1\nhead\tmain\torigin/main\t{base}\t0\nupstream\torigin/main\t{base}\n")
+    );
+}
+
+#[test]
+fn porcelain_sections_commits_and_changes() {
+    let mut info = base_info();
+    info.working_changes = vec![FileChange {
+        path: "src/main.rs".to_string(),
+        index: ' ',
+        worktree: 'M',
+    }];
+    info.commits = vec![
+        commit(3, "Loose", Some(2)),
+        commit_with_files(
+            2,
+            "A1",
+            None,
+            vec![FileChange {
+                path: "a.txt".to_string(),
+                index: 'A',
+                worktree: ' ',
+            }],
+        ),
+    ];
+    info.branches = vec![BranchInfo {
+        name: "feature-a".to_string(),
+        tip_oid: oid(2),
+        remote: Some(RemoteStatus::Synced),
+    }];
+
+    let output = render_porcelain(info);
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[2], "change\tma\t M\tsrc/main.rs");
+    assert_eq!(lines[3], "loose");
+    assert!(lines[4].starts_with("commit\t"));
+    assert!(lines[4].ends_with(&format!("\t{}\tLoose", oid(3))));
+    assert_eq!(lines[5], "section");
+    assert_eq!(lines[6], "branch\tfa\tfeature-a\tsynced");
+    assert!(lines[7].ends_with(&format!("\t{}\tA1", oid(2))));
+    let commit_sid = lines[7].split('\t').nth(1).unwrap();
+    assert_eq!(lines[8], format!("file\t{}:0\tA \ta.txt", commit_sid));
+    assert!(lines[9].starts_with("upstream\t"));
+}
+
+#[test]
+fn porcelain_escapes_tabs_and_newlines() {
+    let mut info = base_info();
+    info.commits = vec![commit(1, "tab\there\\", None)];
+
+    let output = render_porcelain(info);
+    assert!(output.contains("\ttab\\there\\\\\n"), "got:\n{}", output);
+}
+
+#[test]
+fn hidden_commits_collapsed_to_count() {
+    let mut info = base_info();
+    info.commits = vec![
+        commit(5, "Loose", Some(4)),
+        commit(4, "A4", Some(3)),
+        commit(3, "A3", Some(2)),
+        commit(2, "A2", Some(1)),
+        commit(1, "A1", None),
+    ];
+    info.branches = vec![BranchInfo {
+        name: "feature-a".to_string(),
+        tip_oid: oid(4),
+        remote: None,
+    }];
+
+    let mut opts = default_opts();
+    opts.hidden_commits = [oid(5), oid(4), oid(2), oid(1)].into();
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let output = strip_ansi(&graph::render(info, &ids, &opts));
+
+    assert!(
+        output.contains("┊    1 commit filtered out\n│\n"),
+        "expected loose commit collapsed, got:\n{}",
+        output
+    );
+    assert!(
+        output.contains(
+            "│╭─ fa [feature-a]\n│┊    1 commit filtered out\n│●    0300003 A3\n│┊    2 commits filtered out\n├╯\n"
+        ),
+        "expected runs of hidden commits collapsed, got:\n{}",
+        output
+    );
+}
+
+#[test]
+fn commit_stats_shown_after_message() {
+    let mut info = base_info();
+    info.commits = vec![commit(2, "Loose", Some(1)), commit(1, "A1", None)];
+    info.branches = vec![BranchInfo {
+        name: "feature-a".to_string(),
+        tip_oid: oid(1),
+        remote: None,
+    }];
+
+    let mut opts = default_opts();
+    opts.commit_stats = [(oid(2), (120, 4))].into();
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let output = strip_ansi(&graph::render(info, &ids, &opts));
+
+    assert!(
+        output.contains("●    0200002 Loose +120/-4\n"),
+        "expected stat after loose commit, got:\n{}",
+        output
+    );
+    assert!(
+        output.contains("│●    0100001 A1\n"),
+        "expected no stat without an entry, got:\n{}",
+        output
+    );
+}
+
+#[test]
+fn editing_commit_highlighted() {
+    let mut info = base_info();
+    info.commits = vec![commit(2, "Loose", Some(1)), commit(1, "A1", None)];
+    info.branches = vec![BranchInfo {
+        name: "feature-a".to_string(),
+        tip_oid: oid(1),
+        remote: None,
+    }];
+
+    let mut opts = default_opts();
+    opts.editing_commit = Some(oid(1));
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let output = strip_ansi(&graph::render(info, &ids, &opts));
+
+    assert!(
+        output.contains("│◉    0100001 A1 (editing)\n"),
+        "expected edited commit highlighted, got:\n{}",
+        output
+    );
+    assert!(
+        output.contains("●    0200002 Loose\n"),
+        "expected other commits unchanged, got:\n{}",
+        output
+    );
+}
+
+#[test]
+fn detached_head_commit_labelled() {
+    let mut info = base_info();
+    info.commits = vec![commit(2, "Loose", Some(1)), commit(1, "First", None)];
+
+    let mut opts = default_opts();
+    opts.detached_head = Some(oid(1));
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let output = strip_ansi(&graph::render(info, &ids, &opts));
+
+    assert!(
+        output.contains("●    0100001 First (HEAD)\n"),
+        "expected detached HEAD labelled, got:\n{}",
+        output
+    );
+    assert!(output.contains("●    0200002 Loose\n"), "got:\n{}", output);
+}
+
+#[test]
+fn tagged_commit_labelled() {
+    let mut info = base_info();
+    info.commits = vec![commit(2, "Loose", Some(1)), commit(1, "First", None)];
+
+    let mut opts = default_opts();
+    opts.tags = [(oid(1), vec!["v1.0".to_string(), "v1.0-rc".to_string()])].into();
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let output = strip_ansi(&graph::render(info, &ids, &opts));
+
+    assert!(
+        output.contains("●    0100001 First (tag: v1.0, v1.0-rc)\n"),
+        "expected tags labelled, got:\n{}",
+        output
+    );
+    assert!(output.contains("●    0200002 Loose\n"), "got:\n{}", output);
+}
+
+#[test]
+fn working_changes_grouped_by_branch() {
+    let change = |path: &str| FileChange {
+        path: path.to_string(),
+        index: ' ',
+        worktree: 'M',
+    };
+    let mut info = base_info();
+    info.working_changes = vec![change("a.rs"), change("notes.txt"), change("b.rs")];
+
+    let mut opts = default_opts();
+    opts.change_groups = [
+        ("a.rs".to_string(), "feature-a".to_string()),
+        ("b.rs".to_string(), "feature-a".to_string()),
+    ]
+    .into();
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let output = strip_ansi(&graph::render(info, &ids, &opts));
+
+    let lines: Vec<&str> = output.lines().take(5).collect();
+    assert!(lines[1].ends_with(" M notes.txt"), "got:\n{}", output);
+    assert_eq!(lines[2], "│   ↳ [feature-a]");
+    assert!(lines[3].starts_with("│     ") && lines[3].ends_with(" M a.rs"));
+    assert!(lines[4].starts_with("│     ") && lines[4].ends_with(" M b.rs"));
+}
+
+#[test]
+fn hidden_files_marked_after_changes() {
+    let mut info = base_info();
+    info.working_changes = vec![FileChange {
+        path: "src/main.rs".to_string(),
+        index: ' ',
+        worktree: 'M',
+    }];
+
+    let mut opts = default_opts();
+    opts.hidden_files = vec![("config.local".to_string(), HiddenFlag::SkipWorktree)];
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let output = strip_ansi(&graph::render(info, &ids, &opts));
+
+    let lines: Vec<&str> = output.lines().take(3).collect();
+    assert!(lines[1].ends_with(" M src/main.rs"), "got:\n{}", output);
+    assert_eq!(lines[2], "│       ⊘ config.local (skip-worktree)");
+}
+
+#[test]
+fn age_colors_classify_commits() {
+    let day = 24 * 60 * 60;
+    let ages = graph::AgeColors {
+        now: 100 * day,
+        stale_after: 30 * day,
+    };
+
+    assert_eq!(ages.classify(100 * day - 60), graph::Age::Fresh);
+    assert_eq!(ages.classify(99 * day), graph::Age::Normal);
+    assert_eq!(ages.classify(71 * day), graph::Age::Normal);
+    assert_eq!(ages.classify(70 * day), graph::Age::Stale);
+}
+
+#[test]
+fn ascii_mode_replaces_glyphs() {
+    let mut info = base_info();
+    info.commits = vec![commit(2, "A2", Some(1)), commit(1, "A1", None)];
+    info.branches = vec![BranchInfo {
+        name: "feature-a".to_string(),
+        tip_oid: oid(2),
+        remote: Some(RemoteStatus::Synced),
+    }];
+
+    let mut opts = default_opts();
+    opts.theme.ascii = true;
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let output = strip_ansi(&graph::render(info, &ids, &opts));
+    assert_eq!(
+        output,
+        "\
+\\- zz [local changes]
+|   no changes
+|
+|\\- fa [feature-a] (synced)
+|*    0200002 A2
+|*    0100001 A1
+|/
+|
+* aaa0000 (upstream) [origin/main] Initial commit
+"
+    );
+}
+
+#[test]
+fn streamed_render_matches_render() {
+    let mut info = base_info();
+    info.commits = vec![
+        commit(3, "B1", Some(2)),
+        commit(2, "A2", Some(1)),
+        commit(1, "A1", None),
+    ];
+    info.branches = vec![
+        BranchInfo {
+            name: "feature-a".to_string(),
+            tip_oid: oid(2),
+            remote: None,
+        },
+        BranchInfo {
+            name: "feature-b".to_string(),
+            tip_oid: oid(3),
+            remote: None,
+        },
+    ];
+    info.context_commits = vec![ContextCommit {
+        short_hash: "bbb0001".to_string(),
+        message: "Earlier commit".to_string(),
+        date: "2025-07-05".to_string(),
+    }];
+
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let expected = graph::render(info.clone(), &ids, &default_opts());
+
+    let mut opts = default_opts();
+    let mut out = Vec::new();
+    let mut prepared = Vec::new();
+    graph::render_streamed(info, &ids, &mut opts, &mut out, |commits, _| {
+        prepared.push(
+            commits
+                .iter()
+                .map(|c| c.message.clone())
+                .collect::<Vec<_>>(),
+        );
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(String::from_utf8(out).unwrap(), expected);
+    assert_eq!(prepared, vec![vec!["B1"], vec!["A2", "A1"]]);
+}
+
+#[test]
+fn streamed_render_shows_prepared_files_and_stats() {
+    let mut info = base_info();
+    info.commits = vec![commit(1, "A1", None)];
+    info.branches = vec![BranchInfo {
+        name: "feature-a".to_string(),
+        tip_oid: oid(1),
+        remote: None,
+    }];
+
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let mut opts = default_opts();
+    let mut out = Vec::new();
+    graph::render_streamed(info, &ids, &mut opts, &mut out, |commits, opts| {
+        for commit in commits.iter_mut() {
+            commit.files = vec![FileChange {
+                path: "src/lib.rs".to_string(),
+                index: 'M',
+                worktree: ' ',
+            }];
+            opts.commit_stats.insert(commit.oid, (3, 1));
+        }
+        Ok(())
+    })
+    .unwrap();
+
+    let output = strip_ansi(&String::from_utf8(out).unwrap());
+    assert!(
+        output.contains("│●    0100001 A1 +3/-1\n│┊      01:0 M  src/lib.rs\n"),
+        "expected the prepared file and stats under A1, got:\n{}",
+        output
+    );
+}
+
+#[test]
+fn ascii_locale_detection() {
+    assert!(!graph::locale_is_ascii(None, None, None));
+    assert!(!graph::locale_is_ascii(None, None, Some("en_US.UTF-8")));
+    assert!(!graph::locale_is_ascii(None, None, Some("de_DE.utf8")));
+    assert!(graph::locale_is_ascii(None, None, Some("C")));
+    assert!(graph::locale_is_ascii(
+        Some("POSIX"),
+        None,
+        Some("en_US.UTF-8")
+    ));
+    assert!(!graph::locale_is_ascii(
+        Some(""),
+        Some("fr_FR.UTF-8"),
+        Some("C")
+    ));
+}
+
+/// Render a branch with one long commit and one deep file at `width`.
+fn render_overflow(overflow: graph::Overflow, width: u16) -> String {
+    let mut info = base_info();
+    info.commits = vec![commit_with_files(
+        1,
+        "Refactor the rendering of very long commit subjects",
+        None,
+        vec![FileChange {
+            path: "src/some/deeply/nested/module/file.rs".to_string(),
+            index: 'M',
+            worktree: ' ',
+        }],
+    )];
+    info.branches = vec![BranchInfo {
+        name: "feature-a".to_string(),
+        tip_oid: oid(1),
+        remote: None,
+    }];
+    let mut opts = default_opts();
+    opts.terminal_width = Some(width);
+    opts.overflow = overflow;
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    strip_ansi(&graph::render(info, &ids, &opts))
+}
+
+#[test]
+fn overflow_truncates_subjects_and_paths() {
+    let output = render_overflow(graph::Overflow::Truncate, 50);
+    assert!(
+        output.contains("│●    0100001 Refactor the rendering of very long…\n"),
+        "got:\n{}",
+        output
+    );
+    assert!(
+        output.contains("│┊      01:0 M  …some/deeply/nested/module/file.rs\n"),
+        "got:\n{}",
+        output
+    );
+    assert!(output.lines().all(|l| l.chars().count() <= 50));
+}
+
+#[test]
+fn overflow_wraps_paths_with_graph_prefix() {
+    let output = render_overflow(graph::Overflow::Wrap, 40);
+    assert!(
+        output
+            .contains("│┊      01:0 M  src/some/deeply/nested/m\n│┊              odule/file.rs\n"),
+        "got:\n{}",
+        output
+    );
+}
+
+#[test]
+fn overflow_off_keeps_long_lines() {
+    let output = render_overflow(graph::Overflow::Off, 40);
+    assert!(output.contains("Refactor the rendering of very long commit subjects\n"));
+    assert!(output.contains("src/some/deeply/nested/module/file.rs\n"));
+    assert_eq!(graph::Overflow::parse("Wrap"), Some(graph::Overflow::Wrap));
+    assert_eq!(graph::Overflow::parse("sometimes"), None);
+}
+
+#[test]
+fn wide_characters_fit_the_terminal_width() {
+    let wide_info = || {
+        let mut info = base_info();
+        info.commits = vec![commit_with_files(
+            1,
+            "✨ 新しい機能を追加する 🎉 with more words after",
+            None,
+            vec![FileChange {
+                path: "docs/日本語/ガイド/はじめに.md".to_string(),
+                index: 'A',
+                worktree: ' ',
+            }],
+        )];
+        info.branches = vec![BranchInfo {
+            name: "feature-a".to_string(),
+            tip_oid: oid(1),
+            remote: None,
+        }];
+        info
+    };
+
+    for overflow in [graph::Overflow::Truncate, graph::Overflow::Wrap] {
+        let info = wide_info();
+        let mut opts = default_opts();
+        opts.terminal_width = Some(36);
+        opts.overflow = overflow;
+        let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+        let output = strip_ansi(&graph::render(info, &ids, &opts));
+        let commit_lines: Vec<&str> = output.lines().filter(|l| l.starts_with("│●")).collect();
+        assert!(commit_lines[0].width() <= 36, "got:\n{}", output);
+        assert!(commit_lines[0].ends_with('…'));
+        for line in output.lines().filter(|l| l.starts_with("│┊")) {
+            assert!(line.width() <= 36, "got:\n{}", output);
+        }
+    }
+}
+
+#[test]
+fn multicolumn_aligns_wide_file_names() {
+    let mut info = base_info();
+    info.working_changes = ["日本語.txt", "a.txt", "b.txt", "c.txt", "d.txt", "e.txt"]
+        .iter()
+        .map(|path| FileChange {
+            path: path.to_string(),
+            index: '?',
+            worktree: '?',
+        })
+        .collect();
+
+    let output = render_plain_with_width(info, 50);
+    let rows: Vec<&str> = output.lines().filter(|l| l.contains('⁕')).collect();
+    let separator_columns: Vec<usize> = rows
+        .iter()
+        .filter_map(|row| row.find("   │ ").map(|i| row[..i].width()))
+        .collect();
+    assert!(separator_columns.len() > 1, "got:\n{}", output);
+    assert!(
+        separator_columns.iter().all(|&c| c == separator_columns[0]),
+        "got:\n{}",
+        output
+    );
+}
+
+#[test]
+fn forge_links_commits_and_pushed_branches() {
+    let mut info = base_info();
+    info.commits = vec![commit(2, "B1", None), commit(1, "A1", None)];
+    info.branches = vec![
+        BranchInfo {
+            name: "feature-a".to_string(),
+            tip_oid: oid(1),
+            remote: Some(RemoteStatus::Synced),
+        },
+        BranchInfo {
+            name: "feature-b".to_string(),
+            tip_oid: oid(2),
+            remote: None,
+        },
+    ];
+    let mut opts = default_opts();
+    opts.forge = crate::core::forge::Forge::from_url("git@github.com:owner/repo.git", None);
+    let ids = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let output = graph::render(info, &ids, &opts);
+
+    let commit_url = format!("https://github.com/owner/repo/commit/{}", oid(1));
+    assert!(output.contains(&format!("\x1b]8;;{}\x1b\\", commit_url)));
+    assert!(
+        output.contains(
+            "\x1b]8;;https://github.com/owner/repo/pulls?q=is%3Apr+head%3Afeature-a\x1b\\"
+        )
+    );
+    // Unpushed branches have no pull request to link to
+    assert!(!output.contains("head%3Afeature-b"));
+    // Links take no room on screen
+    assert!(strip_ansi(&output).contains("│╭─ fa [feature-a] ✓\n"));
+}
+
+#[test]
+fn hyperlink_support_from_environment() {
+    let env = |vars: &'static [(&'static str, &'static str)]| {
+        move |name: &str| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    };
+    assert!(!graph::hyperlinks_in_env(env(&[(
+        "TERM",
+        "xterm-256color"
+    )])));
+    assert!(graph::hyperlinks_in_env(env(&[(
+        "TERM_PROGRAM",
+        "WezTerm"
+    )])));
+    assert!(graph::hyperlinks_in_env(env(&[("VTE_VERSION", "7600")])));
+    assert!(!graph::hyperlinks_in_env(env(&[("VTE_VERSION", "4200")])));
+    assert!(!graph::hyperlinks_in_env(env(&[
+        ("TERM_PROGRAM", "WezTerm"),
+        ("FORCE_HYPERLINK", "0")
+    ])));
+    assert!(graph::hyperlinks_in_env(env(&[("FORCE_HYPERLINK", "1")])));
+}
+
+#[test]
+fn html_export_styles_colors_and_links() {
+    let rendered = "\x1b[32mok\x1b[0m \x1b[1;38;5;240m<dim>\x1b[0m \
+                    \x1b]8;;https://example.com/c?a=1&b=2\x1b\\\x1b[34mab\x1b[0mcd\x1b]8;;\x1b\\\n";
+    let html = graph::to_html(rendered, "loom status: <int>", false);
+
+    assert!(html.starts_with("<!DOCTYPE html>\n"));
+    assert!(html.contains("<title>loom status: &lt;int&gt;</title>"));
+    assert!(html.contains("background: #1e1e1e"));
+    assert!(html.contains(
+        "<pre><span style=\"color:#00cd00\">ok</span> \
+         <span style=\"color:#585858;font-weight:bold\">&lt;dim&gt;</span> \
+         <a href=\"https://example.com/c?a=1&amp;b=2\"><span style=\"color:#0000ee\">ab</span>cd</a>\n</pre>"
+    ));
+    assert!(!html.contains('\x1b'));
+}
+
+#[test]
+fn html_export_background_follows_theme() {
+    assert!(graph::to_html("", "t", true).contains("background: #ffffff"));
+    assert!(Theme::light().light);
+    assert!(!Theme::dark().light);
+}
+
+#[test]
+fn porcelain_marks_every_conflict_as_unmerged() {
+    let mut info = base_info();
+    info.working_changes = vec![FileChange {
+        path: "src/lib.rs".to_string(),
+        index: 'A',
+        worktree: 'A',
+    }];
+
+    let output = render_porcelain(info);
+    assert!(output.contains("\t!!\tsrc/lib.rs\n"), "got: {}", output);
+}
@@ -0,0 +1,151 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result, bail};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+use crate::core::msg;
+
+/// A lock file older than this is considered abandoned even if a process
+/// with the recorded PID exists (PIDs get reused).
+const MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A lock file that cannot be parsed is only trusted for this long: it may
+/// still be being written by the process that just created it.
+const WRITE_GRACE: Duration = Duration::from_secs(10);
+
+/// Who holds the lock, as recorded in the lock file.
+#[derive(Debug, Serialize, Deserialize)]
+struct Holder {
+    pid: u32,
+    command: String,
+}
+
+/// Advisory lock held by a loom command that modifies the repository, so
+/// two commands (e.g. one from an editor plugin, one from the shell) never
+/// interleave their rebases. Released when dropped.
+#[derive(Debug)]
+pub struct Lock {
+    path: PathBuf,
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Return the path to the lock file: `<git_dir>/loom/lock`.
+pub fn lock_path(git_dir: &Path) -> PathBuf {
+    git_dir.join("loom").join("lock")
+}
+
+/// Take the lock for `command` in `repo`, shared by all its worktrees: it
+/// lives in the common git directory, since they rewrite the same refs.
+pub fn acquire_repo(repo: &Repository, command: &str) -> Result<Lock> {
+    acquire(repo.commondir(), command)
+}
+
+/// Take the lock for `command`, or fail if another loom command holds it.
+///
+/// A lock left behind by a process that is no longer running (or older than
+/// a day) is stale: it is removed with a warning and the lock is taken.
+pub fn acquire(git_dir: &Path, command: &str) -> Result<Lock> {
+    let path = lock_path(git_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "Failed to create loom state directory '{}'",
+                parent.display()
+            )
+        })?;
+    }
+
+    for _ in 0..2 {
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(mut file) => {
+                let holder = Holder {
+                    pid: std::process::id(),
+                    command: command.to_string(),
+                };
+                file.write_all(serde_json::to_string(&holder)?.as_bytes())
+                    .with_context(|| format!("Failed to write lock file '{}'", path.display()))?;
+                return Ok(Lock { path });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to create lock file '{}'", path.display()));
+            }
+        }
+
+        let holder = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str::<Holder>(&json).ok());
+        let age = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| SystemTime::now().duration_since(t).ok())
+            .unwrap_or_default();
+
+        match holder {
+            Some(holder) if age < MAX_AGE && process_alive(holder.pid) => {
+                bail!(
+                    "Another loom operation is running (`loom {}`, pid {})\n\
+                     Wait for it to finish. If it was interrupted, delete `{}`",
+                    holder.command,
+                    holder.pid,
+                    path.display()
+                );
+            }
+            None if age < WRITE_GRACE => {
+                bail!(
+                    "Another loom operation is starting\n\
+                     Wait for it to finish. If it was interrupted, delete `{}`",
+                    path.display()
+                );
+            }
+            Some(holder) => msg::warn(&format!(
+                "Removed a stale lock left by `loom {}` (pid {})",
+                holder.command, holder.pid
+            )),
+            None => msg::warn("Removed an unreadable stale lock"),
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+    bail!(
+        "Could not take the loom lock\nIf no loom operation is running, delete `{}`",
+        path.display()
+    )
+}
+
+/// Whether a process with `pid` is running on this machine.
+fn process_alive(pid: u32) -> bool {
+    if pid == std::process::id() {
+        return true;
+    }
+    #[cfg(unix)]
+    let alive = std::process::Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success());
+    #[cfg(windows)]
+    let alive = std::process::Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH", "/FO", "CSV"])
+        .output()
+        .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).contains(&format!("\"{}\"", pid)));
+    #[cfg(not(any(unix, windows)))]
+    let alive = true;
+    alive
+}
+
+#[cfg(test)]
+#[path = "lock_test.rs"]
+mod tests;
@@ -0,0 +1,90 @@
+use super::{acquire, acquire_repo, lock_path};
+use crate::core::test_helpers::TestRepo;
+
+#[test]
+fn lock_is_released_when_dropped() {
+    let dir = tempfile::tempdir().unwrap();
+    let lock = acquire(dir.path(), "fold").unwrap();
+    assert!(lock_path(dir.path()).exists());
+
+    drop(lock);
+    assert!(!lock_path(dir.path()).exists());
+    drop(acquire(dir.path(), "fold").unwrap());
+}
+
+#[test]
+fn second_lock_fails_while_held() {
+    let dir = tempfile::tempdir().unwrap();
+    let _lock = acquire(dir.path(), "fold").unwrap();
+
+    let err = acquire(dir.path(), "drop").unwrap_err();
+    let text = err.to_string();
+    assert!(
+        text.contains("Another loom operation is running"),
+        "{}",
+        text
+    );
+    assert!(text.contains("`loom fold`"), "{}", text);
+}
+
+#[test]
+fn lock_of_a_finished_process_is_stale() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut child = std::process::Command::new("git")
+        .arg("--version")
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let pid = child.id();
+    child.wait().unwrap();
+    let path = lock_path(dir.path());
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, format!(r#"{{"pid":{},"command":"fold"}}"#, pid)).unwrap();
+
+    let lock = acquire(dir.path(), "drop").unwrap();
+
+    assert!(std::fs::read_to_string(&path).unwrap().contains("drop"));
+    drop(lock);
+}
+
+#[test]
+fn freshly_created_lock_is_respected_before_it_is_written() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = lock_path(dir.path());
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "").unwrap();
+
+    let err = acquire(dir.path(), "drop").unwrap_err();
+    assert!(err.to_string().contains("is starting"), "{}", err);
+    assert!(path.exists());
+}
+
+#[test]
+fn linked_worktrees_share_the_lock() {
+    let test_repo = TestRepo::new();
+    let workdir = test_repo.workdir();
+    let wt_dir = tempfile::tempdir().unwrap();
+    let wt_path = wt_dir.path().join("linked");
+    crate::git::run_git(
+        &workdir,
+        &[
+            "worktree",
+            "add",
+            "--detach",
+            wt_path.to_str().unwrap(),
+            "HEAD",
+        ],
+    )
+    .unwrap();
+    let linked = git2::Repository::open(&wt_path).unwrap();
+    assert_ne!(linked.path(), test_repo.repo.path());
+
+    let _lock = acquire_repo(&test_repo.repo, "fold").unwrap();
+    let err = acquire_repo(&linked, "drop").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Another loom operation is running"),
+        "{}",
+        err
+    );
+}
@@ -1,9 +1,19 @@
+//! Shared building blocks used by every command: repository inspection,
+//! argument resolution, the weave model, transactions, and output helpers.
+
+pub mod ancestry;
+pub mod clock;
 pub mod diff;
+pub mod forge;
 pub mod graph;
+pub mod lock;
 pub mod msg;
+pub mod oplog;
+pub mod project;
 pub mod repo;
 pub mod shortid;
 pub mod staging;
+pub mod trailers;
 pub mod transaction;
 pub mod weave;
 
@@ -1,4 +1,4 @@
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 use std::sync::{
     Arc, Mutex,
     atomic::{AtomicBool, Ordering},
@@ -113,6 +113,15 @@ pub fn warn(message: &str) {
     }
 }
 
+/// Print a note with a blue arrow to stderr, so it never mixes with the
+/// output of a command (e.g. `loom api`).
+/// Text between backticks is highlighted in yellow.
+pub fn notice(message: &str) {
+    for line in message.lines() {
+        eprintln!("{} {}", "›".blue(), colorize_backticks(line));
+    }
+}
+
 /// Print an error message with a red cross to stderr.
 /// Additional lines are treated as hints and prefixed with a blue arrow.
 /// Text between backticks is highlighted in yellow.
@@ -126,10 +135,95 @@ pub fn error(message: &str) {
     }
 }
 
+/// Format a byte count for messages: `512 B`, `3.4 KB`, `12.0 MB`, `1.2 GB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KB", "MB", "GB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
 // --- Interactive prompts ---
 
+/// Set by `--no-input`, and inherited by the loom processes it starts:
+/// loom fails instead of prompting or opening an editor.
+pub const NO_INPUT_VAR: &str = "LOOM_NO_INPUT";
+
+/// Exit status of a command that stopped because it needed input.
+pub const NO_INPUT_EXIT_CODE: i32 = 3;
+
+/// The error of a command that needed input it may not ask for. `main`
+/// exits with [`NO_INPUT_EXIT_CODE`] when it sees one.
+#[derive(Debug)]
+pub struct InputRequired {
+    what: String,
+    hint: String,
+}
+
+impl std::fmt::Display for InputRequired {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Input required: {}\n{}", self.what, self.hint)
+    }
+}
+
+impl std::error::Error for InputRequired {}
+
+/// Whether `--no-input` (or `LOOM_NO_INPUT`) is in effect.
+pub fn no_input() -> bool {
+    std::env::var_os(NO_INPUT_VAR).is_some_and(|v| !v.is_empty())
+}
+
+/// Whether `err` (or one of its causes) is an [`InputRequired`].
+pub fn is_input_required(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<InputRequired>())
+}
+
+/// Fail with [`InputRequired`] unless loom may prompt for `what`: not with
+/// `--no-input`, nor when stdin is not a terminal.
+pub fn require_input(what: &str) -> Result<()> {
+    if no_input() {
+        return Err(InputRequired {
+            what: what.to_string(),
+            hint: "`--no-input` is set: answer with an option instead, such as `-y` or `-m`"
+                .to_string(),
+        }
+        .into());
+    }
+    if !io::stdin().is_terminal() {
+        return Err(InputRequired {
+            what: what.to_string(),
+            hint: "stdin is not a terminal: answer with an option instead, such as `-y` or `-m`"
+                .to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Fail with [`InputRequired`] under `--no-input` instead of opening an
+/// editor for `what`. Without a terminal the editor still opens: it may be
+/// a script (`GIT_EDITOR`, `core.editor`) rather than a person.
+pub fn require_editor(what: &str, hint: &str) -> Result<()> {
+    if no_input() {
+        return Err(InputRequired {
+            what: what.to_string(),
+            hint: hint.to_string(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
 /// Prompt the user for a yes/no confirmation. Returns `true` if confirmed.
 pub fn confirm(prompt: &str) -> Result<bool> {
+    require_input(prompt)?;
     let answer = inquire::Confirm::new(prompt).with_default(false).prompt()?;
     Ok(answer)
 }
@@ -142,6 +236,7 @@ pub fn input<F>(prompt: &str, validator: F) -> Result<String>
 where
     F: Fn(&str) -> std::result::Result<(), &'static str> + Clone + 'static,
 {
+    require_input(prompt)?;
     let answer = inquire::Text::new(prompt)
         .with_validator(move |input: &str| match validator(input) {
             Ok(()) => Ok(Validation::Valid),
@@ -158,6 +253,7 @@ pub fn input_with_placeholder<F>(prompt: &str, placeholder: &str, validator: F)
 where
     F: Fn(&str) -> std::result::Result<(), &'static str> + Clone + 'static,
 {
+    require_input(prompt)?;
     let answer = inquire::Text::new(prompt)
         .with_default(placeholder)
         .with_validator(move |input: &str| match validator(input) {
@@ -170,6 +266,7 @@ where
 
 /// Prompt the user to select one item from a list.
 pub fn select(prompt: &str, items: Vec<String>) -> Result<String> {
+    require_input(prompt)?;
     let answer = inquire::Select::new(prompt, items).prompt()?;
     Ok(answer)
 }
@@ -183,6 +280,7 @@ pub fn select_or_input<F>(prompt: &str, suggestions: Vec<String>, validator: F)
 where
     F: Fn(&str) -> std::result::Result<(), &'static str> + Clone + 'static,
 {
+    require_input(prompt)?;
     let answer = inquire::Text::new(prompt)
         .with_autocomplete(SuggestionsHelper(suggestions))
         .with_validator(move |input: &str| match validator(input) {
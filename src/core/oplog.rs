@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local};
+use git2::Oid;
+use serde::{Deserialize, Serialize};
+
+/// The log is trimmed back to this many entries once it grows past twice
+/// that size.
+const MAX_ENTRIES: usize = 200;
+
+/// Old hashes are followed to their rewritten commits for this long after
+/// the rewrite: a working session, not the whole history of the log.
+const SESSION_HOURS: i64 = 12;
+
+/// The commits one rebase rewrote.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Local time the rebase completed (RFC 3339).
+    pub time: String,
+    /// `(old, new)` OID pairs, in the order the rebase rewrote them.
+    pub rewritten: Vec<(String, String)>,
+}
+
+/// Record the commits the running rebase has rewritten so far, from its
+/// `rewritten-list`. Runs as the last step of every weave todo (see
+/// `weave::run_rebase`), where the list is complete but still on disk.
+/// Commits the rebase kept as they were are left out.
+pub fn record_rewritten(git_dir: &Path) -> Result<()> {
+    let list = std::fs::read_to_string(git_dir.join("rebase-merge").join("rewritten-list"))
+        .unwrap_or_default();
+    let rewritten: Vec<(String, String)> = list
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .filter(|(old, new)| old != new)
+        .map(|(old, new)| (old.to_string(), new.trim().to_string()))
+        .collect();
+    if rewritten.is_empty() {
+        return Ok(());
+    }
+    append(
+        git_dir,
+        &Entry {
+            time: Local::now().to_rfc3339(),
+            rewritten,
+        },
+    )
+}
+
+/// Append `entry` to the log in `git_dir`, trimming old entries when the
+/// log gets too long.
+pub fn append(git_dir: &Path, entry: &Entry) -> Result<()> {
+    let path = log_path(git_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    drop(file);
+
+    let entries = load(git_dir);
+    if entries.len() > 2 * MAX_ENTRIES {
+        let kept: Vec<String> = entries[entries.len() - MAX_ENTRIES..]
+            .iter()
+            .filter_map(|e| serde_json::to_string(e).ok())
+            .collect();
+        std::fs::write(&path, kept.join("\n") + "\n")?;
+    }
+    Ok(())
+}
+
+/// All readable entries of the log in `git_dir`, oldest first. Lines that
+/// fail to parse are skipped.
+pub fn load(git_dir: &Path) -> Vec<Entry> {
+    std::fs::read_to_string(log_path(git_dir))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// The commit `oid` became through the rewrites of the current session,
+/// following a commit rewritten several times to its latest version, or
+/// `None` when it was not rewritten.
+pub fn follow(git_dir: &Path, oid: Oid) -> Option<Oid> {
+    let since = Local::now() - Duration::hours(SESSION_HOURS);
+    let mut map: HashMap<String, String> = HashMap::new();
+    for entry in load(git_dir) {
+        let recent = DateTime::parse_from_rfc3339(&entry.time).is_ok_and(|t| t >= since);
+        if recent {
+            map.extend(entry.rewritten);
+        }
+    }
+    let mut current = oid.to_string();
+    // Bounded, in case the log maps a commit back to an older one
+    for _ in 0..map.len() {
+        match map.get(&current) {
+            Some(new) => current = new.clone(),
+            None => break,
+        }
+    }
+    let current = Oid::from_str(&current).ok()?;
+    (current != oid).then_some(current)
+}
+
+pub(crate) fn log_path(git_dir: &Path) -> PathBuf {
+    git_dir.join("loom").join("oplog.jsonl")
+}
+
+#[cfg(test)]
+#[path = "oplog_test.rs"]
+mod tests;
@@ -0,0 +1,97 @@
+use super::Entry;
+use crate::core::repo::{self, Target, TargetKind};
+use crate::core::test_helpers::TestRepo;
+use crate::core::weave::{self, Weave};
+
+fn oid(byte: u8) -> git2::Oid {
+    git2::Oid::from_bytes(&[byte; 20]).unwrap()
+}
+
+fn entry(time: &str, rewritten: &[(git2::Oid, git2::Oid)]) -> Entry {
+    Entry {
+        time: time.to_string(),
+        rewritten: rewritten
+            .iter()
+            .map(|(old, new)| (old.to_string(), new.to_string()))
+            .collect(),
+    }
+}
+
+fn now() -> String {
+    chrono::Local::now().to_rfc3339()
+}
+
+#[test]
+fn record_rewritten_skips_kept_commits() {
+    let dir = tempfile::tempdir().unwrap();
+    let rebase_dir = dir.path().join("rebase-merge");
+    std::fs::create_dir_all(&rebase_dir).unwrap();
+    std::fs::write(
+        rebase_dir.join("rewritten-list"),
+        format!("{a} {a}\n{b} {c}\n", a = oid(1), b = oid(2), c = oid(3)),
+    )
+    .unwrap();
+
+    super::record_rewritten(dir.path()).unwrap();
+
+    let entries = super::load(dir.path());
+    assert_eq!(entries.len(), 1);
+    assert_eq!(
+        entries[0].rewritten,
+        vec![(oid(2).to_string(), oid(3).to_string())]
+    );
+}
+
+#[test]
+fn record_rewritten_without_list_records_nothing() {
+    let dir = tempfile::tempdir().unwrap();
+    super::record_rewritten(dir.path()).unwrap();
+    assert!(super::load(dir.path()).is_empty());
+}
+
+#[test]
+fn follow_chains_rewrites() {
+    let dir = tempfile::tempdir().unwrap();
+    super::append(dir.path(), &entry(&now(), &[(oid(1), oid(2))])).unwrap();
+    super::append(dir.path(), &entry(&now(), &[(oid(2), oid(3))])).unwrap();
+
+    assert_eq!(super::follow(dir.path(), oid(1)), Some(oid(3)));
+    assert_eq!(super::follow(dir.path(), oid(2)), Some(oid(3)));
+    assert_eq!(super::follow(dir.path(), oid(3)), None);
+}
+
+#[test]
+fn follow_ignores_earlier_sessions() {
+    let dir = tempfile::tempdir().unwrap();
+    let old = (chrono::Local::now() - chrono::Duration::days(2)).to_rfc3339();
+    super::append(dir.path(), &entry(&old, &[(oid(1), oid(2))])).unwrap();
+
+    assert_eq!(super::follow(dir.path(), oid(1)), None);
+}
+
+#[test]
+fn rebase_records_rewrites_and_old_hash_resolves_to_new_commit() {
+    let test_repo = TestRepo::new_with_remote();
+    let a1 = test_repo.commit_empty("A1");
+    let a2 = test_repo.commit("A2", "a2.txt");
+
+    test_repo.in_dir(|| {
+        let mut graph = Weave::from_repo(&test_repo.repo).unwrap();
+        graph.drop_commit(a1);
+        weave::run_rebase_or_abort(
+            &test_repo.workdir(),
+            Some(&graph.base_oid.to_string()),
+            &graph.checked_todo().unwrap(),
+        )
+        .unwrap();
+    });
+    let new_a2 = test_repo.head_oid();
+    assert_ne!(new_a2, a2);
+    assert_eq!(super::follow(test_repo.repo.path(), a2), Some(new_a2));
+
+    test_repo.in_dir(|| {
+        let target =
+            repo::resolve_arg(&test_repo.repo, &a2.to_string(), &[TargetKind::Commit]).unwrap();
+        assert_eq!(target, Target::Commit(new_a2.to_string()));
+    });
+}
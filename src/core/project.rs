@@ -0,0 +1,278 @@
+//! Project defaults committed in `.loom.toml` at the root of the repository.
+//!
+//! The file holds `loom.*` settings shared by everyone working on the project,
+//! under their git config name without the `loom.` prefix:
+//!
+//! ```toml
+//! integrationBranch = "integration"
+//! hideBranchPattern = "wip-"
+//! ignorePaths = ["*.lock", "generated/**"]
+//! ```
+//!
+//! Git config always wins: a setting from `.loom.toml` only applies when no
+//! git config level (system, global, local) sets the key.
+//!
+//! Only existing `loom.*` settings can be given defaults; there are no
+//! project-only settings (protected paths, merge message templates).
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+
+use anyhow::{Result, bail};
+use git2::Repository;
+
+use crate::core::msg;
+
+/// Name of the project defaults file, at the root of the working tree.
+pub const PROJECT_FILE: &str = ".loom.toml";
+
+/// Settings never read from `.loom.toml`: they run commands, and a committed
+/// file must not make loom run whatever it names.
+const LOCAL_ONLY_KEYS: &[&str] = &["loom.notifycommand"];
+
+/// Whether a broken `.loom.toml` was already reported by this process.
+static WARNED: AtomicBool = AtomicBool::new(false);
+
+/// Where a `.loom.toml` is and the modification time and size it had when
+/// it was read.
+type Fingerprint = (PathBuf, Option<SystemTime>, u64);
+
+thread_local! {
+    /// The last `.loom.toml` read, with its fingerprint: every config lookup
+    /// goes through [`ProjectDefaults::load`], so a command reads the file
+    /// once, and again only when it changes.
+    static LOADED: RefCell<Option<(Fingerprint, ProjectDefaults)>> = const { RefCell::new(None) };
+}
+
+/// The settings of a `.loom.toml`, keyed by their lowercase git config name
+/// (`loom.hidebranchpattern`). Scalars are kept as their text; arrays as one
+/// value per element, like a multi-valued git config key.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProjectDefaults {
+    values: Vec<(String, Vec<String>)>,
+}
+
+impl ProjectDefaults {
+    /// The defaults of the repository's working tree. Empty for bare
+    /// repositories and when there is no `.loom.toml`. A file that does not
+    /// parse is reported once and ignored. The parsed file is reused until
+    /// it changes on disk.
+    pub fn load(repo: &Repository) -> ProjectDefaults {
+        let Some(workdir) = repo.workdir() else {
+            return ProjectDefaults::default();
+        };
+        let path = workdir.join(PROJECT_FILE);
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            return ProjectDefaults::default();
+        };
+        let fingerprint = (path, metadata.modified().ok(), metadata.len());
+        if let Some(defaults) = LOADED.with(|loaded| match &*loaded.borrow() {
+            Some((cached, defaults)) if *cached == fingerprint => Some(defaults.clone()),
+            _ => None,
+        }) {
+            return defaults;
+        }
+        let defaults = ProjectDefaults::read(&fingerprint.0);
+        LOADED.with(|loaded| *loaded.borrow_mut() = Some((fingerprint, defaults.clone())));
+        defaults
+    }
+
+    /// Read and parse the file at `path`; empty when it can't be read or
+    /// parsed.
+    fn read(path: &std::path::Path) -> ProjectDefaults {
+        let Ok(text) = std::fs::read_to_string(path) else {
+            return ProjectDefaults::default();
+        };
+        match ProjectDefaults::parse(&text) {
+            Ok(defaults) => defaults,
+            Err(e) => {
+                if !WARNED.swap(true, Ordering::Relaxed) {
+                    msg::warn(&format!("Ignoring `{}`: {}", PROJECT_FILE, e));
+                }
+                ProjectDefaults::default()
+            }
+        }
+    }
+
+    /// Parse the TOML subset loom settings need: `key = value` pairs, where a
+    /// value is a string, a boolean, an integer, or an array of strings, with
+    /// `#` comments and an optional `[loom]` table header.
+    pub fn parse(text: &str) -> Result<ProjectDefaults> {
+        let mut values: Vec<(String, Vec<String>)> = Vec::new();
+        let mut lines = text.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let line_no = index + 1;
+            let line = strip_comment(line).trim();
+            if line.is_empty() || line.eq_ignore_ascii_case("[loom]") {
+                continue;
+            }
+            if line.starts_with('[') {
+                bail!("line {}: unknown table `{}`", line_no, line);
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                bail!("line {}: expected `key = value`", line_no);
+            };
+            let key = key.trim();
+            if key.is_empty()
+                || !key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+            {
+                bail!("line {}: invalid key `{}`", line_no, key);
+            }
+
+            let mut value = value.trim().to_string();
+            if value.starts_with('[') {
+                // Arrays may span lines until their closing bracket
+                while !array_closed(&value) {
+                    let Some((_, next)) = lines.next() else {
+                        bail!("line {}: unterminated array", line_no);
+                    };
+                    value.push(' ');
+                    value.push_str(strip_comment(next).trim());
+                }
+            }
+            let parsed =
+                parse_value(&value).map_err(|e| anyhow::anyhow!("line {}: {}", line_no, e))?;
+
+            let name = format!("loom.{}", key.to_lowercase());
+            if values.iter().any(|(k, _)| *k == name) {
+                bail!("line {}: `{}` is set twice", line_no, key);
+            }
+            values.push((name, parsed));
+        }
+        Ok(ProjectDefaults { values })
+    }
+
+    /// The values of `key` (a git config name like `loom.ignorePaths`), or
+    /// `None` when the file does not set it.
+    pub fn get(&self, key: &str) -> Option<&[String]> {
+        let key = key.to_lowercase();
+        if LOCAL_ONLY_KEYS.contains(&key.as_str()) {
+            return None;
+        }
+        self.values
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_slice())
+    }
+
+    /// The single value of `key`; for an array, its last element, like git
+    /// config for multi-valued keys.
+    pub fn get_string(&self, key: &str) -> Option<String> {
+        self.get(key)?.last().cloned()
+    }
+}
+
+/// `line` without its `#` comment, if the `#` is outside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return &line[..i],
+            _ => {}
+        }
+        escaped = false;
+    }
+    line
+}
+
+/// Whether an array value has its closing bracket (outside of strings).
+fn array_closed(value: &str) -> bool {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut depth = 0;
+    for c in value.chars() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[') => depth += 1,
+            (None, ']') => depth -= 1,
+            _ => {}
+        }
+        escaped = false;
+    }
+    depth <= 0
+}
+
+/// Parse a value into its text, or the text of each element of an array.
+fn parse_value(value: &str) -> Result<Vec<String>> {
+    if let Some(inner) = value.strip_prefix('[') {
+        let Some(inner) = inner.trim_end().strip_suffix(']') else {
+            bail!("unexpected text after the array");
+        };
+        let mut elements = Vec::new();
+        let mut rest = inner.trim();
+        while !rest.is_empty() {
+            let (element, after) = parse_string(rest)?;
+            elements.push(element);
+            rest = after.trim_start();
+            match rest.strip_prefix(',') {
+                Some(after) => rest = after.trim_start(),
+                None if rest.is_empty() => {}
+                None => bail!("expected `,` between array elements"),
+            }
+        }
+        return Ok(elements);
+    }
+    if value.starts_with('"') || value.starts_with('\'') {
+        let (text, rest) = parse_string(value)?;
+        if !rest.trim().is_empty() {
+            bail!("unexpected text after the string");
+        }
+        return Ok(vec![text]);
+    }
+    if value == "true" || value == "false" {
+        return Ok(vec![value.to_string()]);
+    }
+    let digits = value.replace('_', "");
+    if digits.parse::<i64>().is_ok() {
+        return Ok(vec![digits]);
+    }
+    bail!("unsupported value `{}`", value)
+}
+
+/// Parse the string `text` starts with (`"basic"` or `'literal'`), returning
+/// it and the text after it.
+fn parse_string(text: &str) -> Result<(String, &str)> {
+    let mut chars = text.char_indices();
+    let quote = match chars.next() {
+        Some((_, q @ ('"' | '\''))) => q,
+        _ => bail!("expected a string"),
+    };
+    let mut out = String::new();
+    while let Some((i, c)) = chars.next() {
+        if c == quote {
+            return Ok((out, &text[i + 1..]));
+        }
+        if c == '\\' && quote == '"' {
+            match chars.next().map(|(_, c)| c) {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(c @ ('"' | '\\')) => out.push(c),
+                Some(c) => bail!("unsupported escape `\\{}`", c),
+                None => break,
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    bail!("unterminated string")
+}
+
+#[cfg(test)]
+#[path = "project_test.rs"]
+mod tests;
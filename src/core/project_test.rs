@@ -0,0 +1,116 @@
+use crate::core::project::ProjectDefaults;
+use crate::core::repo;
+use crate::core::test_helpers::TestRepo;
+
+#[test]
+fn parse_scalars_arrays_and_comments() {
+    let defaults = ProjectDefaults::parse(
+        "# Project defaults\n\
+         [loom]\n\
+         integrationBranch = \"weave\" # trailing comment\n\
+         hideBranchPattern = 'wip-#'\n\
+         shareMeta = true\n\
+         branch_max_lines = 2_000\n\
+         ignorePaths = [\n\
+             \"*.lock\", # lockfiles\n\
+             \"gen/**\",\n\
+         ]\n",
+    )
+    .unwrap();
+
+    assert_eq!(
+        defaults.get_string("loom.integrationBranch").as_deref(),
+        Some("weave")
+    );
+    assert_eq!(
+        defaults.get_string("loom.HIDEBRANCHPATTERN").as_deref(),
+        Some("wip-#")
+    );
+    assert_eq!(
+        defaults.get_string("loom.shareMeta").as_deref(),
+        Some("true")
+    );
+    assert_eq!(
+        defaults.get_string("loom.branch_max_lines").as_deref(),
+        Some("2000")
+    );
+    assert_eq!(
+        defaults.get("loom.ignorePaths"),
+        Some(&["*.lock".to_string(), "gen/**".to_string()][..])
+    );
+    assert_eq!(defaults.get("loom.base"), None);
+}
+
+#[test]
+fn parse_rejects_what_it_does_not_understand() {
+    for text in [
+        "base",
+        "[remote]\nbase = \"main\"",
+        "base = main",
+        "base = \"main",
+        "ignorePaths = [\"a\" \"b\"]",
+        "ignorePaths = [\"a\",",
+        "base = \"a\"\nbase = \"b\"",
+    ] {
+        assert!(
+            ProjectDefaults::parse(text).is_err(),
+            "should not parse: {}",
+            text
+        );
+    }
+}
+
+#[test]
+fn git_config_overrides_project_defaults() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.write_file(
+        ".loom.toml",
+        "hideBranchPattern = \"wip-\"\n\
+         overflow = \"wrap\"\n\
+         ageColors = true\n\
+         ignorePaths = [\"*.lock\"]\n",
+    );
+    test_repo.set_config("loom.overflow", "off");
+
+    let repo = &test_repo.repo;
+    assert_eq!(repo::hide_branch_pattern(repo).as_deref(), Some("wip-"));
+    assert_eq!(repo::overflow_mode(repo).as_deref(), Some("off"));
+    assert_eq!(repo::stale_days(repo), Some(30));
+    assert_eq!(repo::ignore_path_patterns(repo), vec!["*.lock"]);
+
+    test_repo.set_config("loom.ageColors", "false");
+    test_repo.set_config("loom.ignorePaths", "*.tmp");
+    assert_eq!(repo::stale_days(repo), None);
+    assert_eq!(repo::ignore_path_patterns(repo), vec!["*.tmp"]);
+}
+
+#[test]
+fn commands_are_never_read_from_the_project_file() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.write_file(".loom.toml", "notifyCommand = \"touch pwned\"\n");
+
+    assert_eq!(repo::notify_command(&test_repo.repo), None);
+}
+
+#[test]
+fn broken_project_file_is_ignored() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.write_file(".loom.toml", "hideBranchPattern = wip-\n");
+
+    assert_eq!(repo::hide_branch_pattern(&test_repo.repo), None);
+}
+
+#[test]
+fn project_file_is_read_again_when_it_changes() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.write_file(".loom.toml", "hideBranchPattern = \"wip-\"\n");
+    let repo = &test_repo.repo;
+    assert_eq!(repo::hide_branch_pattern(repo).as_deref(), Some("wip-"));
+    assert_eq!(repo::hide_branch_pattern(repo).as_deref(), Some("wip-"));
+
+    test_repo.write_file(".loom.toml", "hideBranchPattern = \"draft-\"\n");
+    assert_eq!(repo::hide_branch_pattern(repo).as_deref(), Some("draft-"));
+
+    std::fs::remove_file(test_repo.workdir().join(".loom.toml")).unwrap();
+    assert_eq!(repo::hide_branch_pattern(repo), None);
+}
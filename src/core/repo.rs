@@ -1,17 +1,31 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 use anyhow::{Context, Result, bail};
 use chrono::DateTime;
+use colored::Colorize;
 use git2::{BranchType, Repository, StatusOptions};
 
+use crate::core::ancestry;
 use crate::core::msg;
+use crate::core::project::ProjectDefaults;
 use crate::git;
 
-/// Open a `Repository` by discovering it from the current working directory.
+/// Open a `Repository` by discovering it from the current working directory,
+/// or from `GIT_DIR` / `GIT_WORK_TREE` when set.
 pub fn open_repo() -> Result<Repository> {
-    let cwd = std::env::current_dir()?;
-    Ok(Repository::discover(cwd)?)
+    // `GIT_DIR` / `GIT_WORK_TREE` (also set by `--git-dir` / `--work-tree`)
+    // take precedence over discovery, as they do for git itself.
+    let repo = match std::env::var_os("GIT_DIR") {
+        Some(dir) => Repository::open(&dir)
+            .with_context(|| format!("Not a git repository: {}", Path::new(&dir).display()))?,
+        None => Repository::discover(std::env::current_dir()?)?,
+    };
+    if let Some(work_tree) = std::env::var_os("GIT_WORK_TREE") {
+        repo.set_workdir(Path::new(&work_tree), false)?;
+    }
+    Ok(repo)
 }
 
 /// Return the working directory of the repository, or error if bare.
@@ -22,6 +36,12 @@ pub fn require_workdir<'a>(repo: &'a Repository, operation: &str) -> Result<&'a
         .with_context(|| format!("Cannot {operation} in bare repository"))
 }
 
+/// Directory to run read-only git commands from: the working tree, or the
+/// git directory itself for a bare repository.
+pub fn command_dir(repo: &Repository) -> &Path {
+    repo.workdir().unwrap_or_else(|| repo.path())
+}
+
 /// Return the OID that HEAD points to.
 pub fn head_oid(repo: &Repository) -> Result<git2::Oid> {
     repo.head()?.target().context("HEAD has no target")
@@ -46,6 +66,167 @@ pub fn snapshot_branch_refs(repo: &Repository) -> Result<HashMap<String, git2::O
     Ok(refs)
 }
 
+/// A local branch whose tip changed during an operation.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RefMove {
+    pub name: String,
+    /// Tip before the operation, `None` for a branch it created.
+    pub old: Option<git2::Oid>,
+    /// Tip after the operation, `None` for a branch it deleted.
+    pub new: Option<git2::Oid>,
+    /// Commits the branch gained (or lost, when negative) over its base.
+    pub delta: i64,
+}
+
+impl RefMove {
+    /// `feature-a  ab12345 → cd67890  (+1 commit)`, the name padded to
+    /// `width`.
+    pub fn describe(&self, width: usize) -> String {
+        let tip = |oid: Option<git2::Oid>, missing: &str| match oid {
+            Some(oid) => git::short_hash(&oid.to_string()).to_string(),
+            None => missing.to_string(),
+        };
+        let delta = match self.delta {
+            0 => String::new(),
+            n => format!(
+                "  ({}{} {})",
+                if n > 0 { "+" } else { "−" },
+                n.abs(),
+                if n.abs() == 1 { "commit" } else { "commits" }
+            ),
+        };
+        format!(
+            "{}  {} → {}{}",
+            format!("{:<width$}", self.name, width = width).green(),
+            tip(self.old, "created"),
+            tip(self.new, "deleted"),
+            delta.dimmed()
+        )
+    }
+}
+
+/// The local branches whose tips differ from `before` (a
+/// [`snapshot_branch_refs`]), by name. Commits are counted over the base a
+/// branch shares with the upstream of HEAD and with its old tip, so a branch
+/// only rebased onto a new upstream neither gains nor loses any. Loom's
+/// temporary `_loom-*` branches are left out.
+pub fn ref_moves(repo: &Repository, before: &HashMap<String, git2::Oid>) -> Result<Vec<RefMove>> {
+    let after = snapshot_branch_refs(repo)?;
+    let upstream = repo
+        .head()
+        .ok()
+        .and_then(|head| head.shorthand().map(str::to_string))
+        .and_then(|name| repo.find_branch(&name, BranchType::Local).ok())
+        .and_then(|branch| branch.upstream().ok())
+        .and_then(|upstream| upstream.get().target());
+    let mut names: Vec<&String> = before.keys().chain(after.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    let mut moves = Vec::new();
+    for name in names {
+        let (old, new) = (before.get(name).copied(), after.get(name).copied());
+        if old == new || name.starts_with("_loom") {
+            continue;
+        }
+        let delta = match (old, new) {
+            (Some(old), Some(new)) => {
+                let count = |tip: git2::Oid| -> Result<i64> {
+                    let mut walk = repo.revwalk()?;
+                    walk.push(tip)?;
+                    if let Some(upstream) = upstream {
+                        walk.hide(upstream)?;
+                    }
+                    if let Ok(base) = repo.merge_base(old, new) {
+                        walk.hide(base)?;
+                    }
+                    Ok(walk.count() as i64)
+                };
+                count(new)? - count(old)?
+            }
+            _ => 0,
+        };
+        moves.push(RefMove {
+            name: name.clone(),
+            old,
+            new,
+            delta,
+        });
+    }
+    Ok(moves)
+}
+
+/// Tag names by the commit they point at (annotated tags peeled), each list
+/// sorted.
+pub fn tags_by_commit(repo: &Repository) -> Result<HashMap<git2::Oid, Vec<String>>> {
+    let mut tags: HashMap<git2::Oid, Vec<String>> = HashMap::new();
+    for reference in repo.references_glob("refs/tags/*")? {
+        let reference = reference?;
+        if let Some(name) = reference.shorthand()
+            && let Ok(commit) = reference.peel_to_commit()
+        {
+            tags.entry(commit.id()).or_default().push(name.to_string());
+        }
+    }
+    for names in tags.values_mut() {
+        names.sort();
+    }
+    Ok(tags)
+}
+
+/// Refs recording the upstream commit `loom update --only` restacked a branch
+/// onto, while the rest of the weave stays on the older base.
+pub const BRANCH_BASE_PREFIX: &str = "refs/loom/base/";
+
+/// The bases recorded by `loom update --only`, by branch name. Records of
+/// deleted branches, or of branches no longer built on their base, are
+/// ignored.
+pub fn branch_bases(repo: &Repository) -> Result<HashMap<String, git2::Oid>> {
+    let mut bases = HashMap::new();
+    for reference in repo.references_glob(&format!("{}*", BRANCH_BASE_PREFIX))? {
+        let reference = reference?;
+        let (Some(name), Some(base)) = (
+            reference
+                .name()
+                .and_then(|n| n.strip_prefix(BRANCH_BASE_PREFIX)),
+            reference.target(),
+        ) else {
+            continue;
+        };
+        let Some(tip) = repo
+            .find_branch(name, BranchType::Local)
+            .ok()
+            .and_then(|b| b.get().target())
+        else {
+            continue;
+        };
+        if tip == base || repo.graph_descendant_of(tip, base)? {
+            bases.insert(name.to_string(), base);
+        }
+    }
+    Ok(bases)
+}
+
+/// Record that `branch` was restacked onto `base`.
+pub fn set_branch_base(repo: &Repository, branch: &str, base: git2::Oid) -> Result<()> {
+    repo.reference(
+        &format!("{}{}", BRANCH_BASE_PREFIX, branch),
+        base,
+        true,
+        "loom: update --only",
+    )?;
+    Ok(())
+}
+
+/// Forget every recorded branch base, once the whole weave is on one base
+/// again.
+pub fn clear_branch_bases(repo: &Repository) -> Result<()> {
+    for reference in repo.references_glob(&format!("{}*", BRANCH_BASE_PREFIX))? {
+        reference?.delete()?;
+    }
+    Ok(())
+}
+
 /// Restore branches to snapshot OIDs, deleting any branches not in the snapshot.
 pub fn restore_branch_refs(workdir: &Path, snapshot: &HashMap<String, git2::Oid>) -> Result<()> {
     let repo = Repository::discover(workdir)?;
@@ -108,26 +289,430 @@ pub fn ensure_branch_not_exists(repo: &Repository, name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Read string setting `key` from git config, or else from the project
+/// defaults of `.loom.toml`.
+pub fn config_string(repo: &Repository, key: &str) -> Option<String> {
+    match repo.config().map(|config| config.get_string(key)) {
+        Ok(Ok(value)) => Some(value),
+        _ => ProjectDefaults::load(repo).get_string(key),
+    }
+}
+
+/// Read boolean setting `key` from git config, or else from the project
+/// defaults. An invalid git config value is not replaced by the default.
+pub fn config_bool(repo: &Repository, key: &str) -> Option<bool> {
+    if let Ok(config) = repo.config()
+        && config.get_entry(key).is_ok()
+    {
+        return config.get_bool(key).ok();
+    }
+    ProjectDefaults::load(repo).get_string(key)?.parse().ok()
+}
+
+/// Read integer setting `key` from git config, or else from the project
+/// defaults. An invalid git config value is not replaced by the default.
+pub fn config_i64(repo: &Repository, key: &str) -> Option<i64> {
+    if let Ok(config) = repo.config()
+        && config.get_entry(key).is_ok()
+    {
+        return config.get_i64(key).ok();
+    }
+    ProjectDefaults::load(repo).get_string(key)?.parse().ok()
+}
+
+/// Read git config `loom.integrationBranch`: the name `loom init` gives the
+/// integration branch when none is passed.
+pub fn integration_branch_name(repo: &Repository) -> Option<String> {
+    config_string(repo, "loom.integrationBranch")
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// Read git config `loom.commitTemplate`: the pattern `loom commit` applies
+/// to the message of commits on feature branches (`[{ticket}] {message}`).
+pub fn commit_template(repo: &Repository) -> Option<String> {
+    config_string(repo, "loom.commitTemplate").filter(|t| !t.trim().is_empty())
+}
+
+/// Read git config `loom.ticketPattern`: the regular expression extracting
+/// `{ticket}` from a branch name for `loom.commitTemplate`.
+pub fn ticket_pattern(repo: &Repository) -> Option<String> {
+    config_string(repo, "loom.ticketPattern").filter(|p| !p.trim().is_empty())
+}
+
+/// Read git config `loom.branchTemplate`: the pattern interactive branch
+/// names are filled into (`{user}/{slug}`).
+pub fn branch_template(repo: &Repository) -> Option<String> {
+    config_string(repo, "loom.branchTemplate").filter(|t| !t.trim().is_empty())
+}
+
+/// Read git config `loom.branchPattern`: the regular expression new branch
+/// names must match.
+pub fn branch_pattern(repo: &Repository) -> Option<String> {
+    config_string(repo, "loom.branchPattern").filter(|p| !p.trim().is_empty())
+}
+
 /// Default prefix for branches hidden from status display.
 pub(crate) const DEFAULT_HIDE_PATTERN: &str = "local-";
 
 /// Read the hidden branch prefix from git config `loom.hideBranchPattern`.
 /// Returns `None` if the config key is not set.
 pub fn hide_branch_pattern(repo: &Repository) -> Option<String> {
+    config_string(repo, "loom.hideBranchPattern")
+}
+
+/// Read git config `loom.shareMeta`: whether `loom push` shares the weave
+/// structure in `refs/loom/meta` and `loom update` fetches it.
+pub fn share_meta(repo: &Repository) -> bool {
+    config_bool(repo, "loom.shareMeta").unwrap_or(false)
+}
+
+/// Read git config `loom.hyperlinks`: whether `loom status` links hashes
+/// and branch names to the forge. Unset means auto-detect.
+pub fn hyperlinks(repo: &Repository) -> Option<bool> {
+    config_bool(repo, "loom.hyperlinks")
+}
+
+/// Read git config `loom.overflow`: how `loom status` shortens lines wider
+/// than the terminal (`truncate`, `wrap`, or `off`).
+pub fn overflow_mode(repo: &Repository) -> Option<String> {
+    config_string(repo, "loom.overflow")
+}
+
+/// Read git config `loom.base`: the base of an integration branch that has
+/// no upstream tracking branch (a branch, tag, or commit). Without it, such
+/// a branch is based on its root commit.
+pub fn local_base(repo: &Repository) -> Option<String> {
+    config_string(repo, "loom.base")
+}
+
+/// Read git config `loom.baseRemote`: the remote whose copy of the upstream
+/// branch the weave is based on, when it is not the remote the integration
+/// branch tracks (in a fork workflow, `upstream` while the branch tracks
+/// `origin/main`).
+pub fn base_remote(repo: &Repository) -> Option<String> {
+    config_string(repo, "loom.baseRemote")
+}
+
+/// Read git config `loom.notifyCommand`: a shell command `loom update
+/// --daemon` runs with a message as its last argument, typically a desktop
+/// notifier (`notify-send git-loom`).
+pub fn notify_command(repo: &Repository) -> Option<String> {
     repo.config()
         .ok()?
-        .get_string("loom.hideBranchPattern")
+        .get_string("loom.notifyCommand")
         .ok()
+        .filter(|cmd| !cmd.trim().is_empty())
+}
+
+/// Read git config `loom.pushRemote` (or its older spelling
+/// `loom.push-remote`): the remote `loom push` pushes branches to.
+pub fn push_remote(repo: &Repository) -> Option<String> {
+    config_string(repo, "loom.pushRemote")
+        .or_else(|| config_string(repo, "loom.push-remote"))
+        .filter(|remote| !remote.trim().is_empty())
+}
+
+/// Read git config `branch.<branch>.loomPushRemote`: the remote `loom push`
+/// pushes this branch to, overriding `loom.pushRemote` (e.g. a weave where
+/// some branches go to a fork and others to the canonical repository).
+pub fn branch_push_remote(repo: &Repository, branch: &str) -> Option<String> {
+    repo.config()
+        .ok()?
+        .get_string(&format!("branch.{}.loomPushRemote", branch))
+        .ok()
+        .map(|remote| remote.trim().to_string())
+        .filter(|remote| !remote.is_empty())
+}
+
+/// The upstream the integration branch `branch_name` is based on: its
+/// tracking branch `upstream_name`, or the same branch on `loom.baseRemote`
+/// when that is set (`origin/main` → `upstream/main`). A branch tracking a
+/// local branch keeps it.
+pub fn base_upstream(repo: &Repository, branch_name: &str, upstream_name: &str) -> String {
+    let refname = format!("refs/heads/{}", branch_name);
+    if let Some(remote) = base_remote(repo)
+        && let Ok(tracked) = repo.branch_upstream_remote(&refname)
+        && tracked.as_str() != Some(".")
+        && let Ok(merge) = repo.branch_upstream_merge(&refname)
+        && let Some(branch) = merge.as_str().and_then(|m| m.strip_prefix("refs/heads/"))
+    {
+        return format!("{}/{}", remote, branch);
+    }
+    upstream_name.to_string()
+}
+
+/// Notes refs whose notes follow commits that loom rewrites, when
+/// `loom.rewriteNotes` is set: the `notes.rewriteRef` patterns, like
+/// `git rebase`, or else the default notes ref (`core.notesRef`, falling back
+/// to `refs/notes/commits`).
+pub fn notes_rewrite_refs(repo: &Repository) -> Vec<String> {
+    let Ok(config) = repo.config() else {
+        return Vec::new();
+    };
+    if !config_bool(repo, "loom.rewriteNotes").unwrap_or(false) {
+        return Vec::new();
+    }
+    let mut refs = Vec::new();
+    if let Ok(mut entries) = config.multivar("notes.rewriteRef", None) {
+        while let Some(Ok(entry)) = entries.next() {
+            if let Some(value) = entry.value() {
+                refs.push(value.to_string());
+            }
+        }
+    }
+    if refs.is_empty() {
+        refs.push(
+            config
+                .get_string("core.notesRef")
+                .unwrap_or_else(|_| "refs/notes/commits".to_string()),
+        );
+    }
+    refs
+}
+
+/// Copy the notes of commit `from` to its rewritten version `to`, for every
+/// ref of [`notes_rewrite_refs`]. For commits loom recreates itself rather
+/// than through `git rebase`.
+pub fn copy_notes(repo: &Repository, from: git2::Oid, to: git2::Oid) -> Result<()> {
+    for pattern in notes_rewrite_refs(repo) {
+        let names: Vec<String> = if pattern.contains('*') {
+            repo.references_glob(&pattern)?
+                .filter_map(|r| r.ok()?.name().map(str::to_string))
+                .collect()
+        } else {
+            vec![pattern]
+        };
+        for name in names {
+            if let Ok(note) = repo.find_note(Some(&name), from) {
+                let sig = crate::core::clock::committer(repo)?;
+                repo.note(
+                    &sig,
+                    &sig,
+                    Some(&name),
+                    to,
+                    note.message().unwrap_or(""),
+                    true,
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Default for `loom.staleDays`.
+const DEFAULT_STALE_DAYS: i64 = 30;
+
+/// Read git config `loom.ageColors` and `loom.staleDays`. When age coloring
+/// is enabled, returns the age in days after which a commit is stale.
+pub fn stale_days(repo: &Repository) -> Option<i64> {
+    if !config_bool(repo, "loom.ageColors").unwrap_or(false) {
+        return None;
+    }
+    Some(
+        config_i64(repo, "loom.staleDays")
+            .filter(|days| *days > 0)
+            .unwrap_or(DEFAULT_STALE_DAYS),
+    )
+}
+
+/// Default for `loom.gcDays`.
+const DEFAULT_GC_DAYS: i64 = 30;
+
+/// Read git config `loom.gcDays`: how many days of logs `loom gc` keeps
+/// (default 30).
+pub fn gc_days(repo: &Repository) -> i64 {
+    config_i64(repo, "loom.gcDays")
+        .filter(|days| *days >= 0)
+        .unwrap_or(DEFAULT_GC_DAYS)
+}
+
+/// Limits beyond which `loom status` flags a woven branch with `⚠`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BranchLimits {
+    /// Age in days of a branch's oldest commit (`loom.branchMaxAgeDays`).
+    pub max_age_days: Option<i64>,
+    /// Lines changed by a branch as a whole (`loom.branchMaxLines`).
+    pub max_lines: Option<usize>,
+}
+
+/// Read git config `loom.branchMaxAgeDays` (default 60) and
+/// `loom.branchMaxLines` (default 1000). `0` turns a check off.
+pub fn branch_limits(repo: &Repository) -> BranchLimits {
+    let get = |key: &str, default: i64| {
+        let value = config_i64(repo, key).unwrap_or(default);
+        (value > 0).then_some(value)
+    };
+    BranchLimits {
+        max_age_days: get("loom.branchMaxAgeDays", 60),
+        max_lines: get("loom.branchMaxLines", 1000).map(|lines| lines as usize),
+    }
+}
+
+/// What a history rewrite does with uncommitted changes (`loom.autostash`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AutostashPolicy {
+    /// Stash them for the rebase and restore them after (default).
+    #[default]
+    Always,
+    /// Ask before stashing them.
+    Prompt,
+    /// Refuse to rewrite history until they are committed or stashed.
+    Never,
+}
+
+/// Read git config `loom.autostash`: `always`, `prompt`, or `never`. Unset
+/// or unknown values mean `always`.
+pub fn autostash_policy(repo: &Repository) -> AutostashPolicy {
+    match config_string(repo, "loom.autostash")
+        .as_deref()
+        .map(str::trim)
+    {
+        Some("prompt") => AutostashPolicy::Prompt,
+        Some("never") => AutostashPolicy::Never,
+        _ => AutostashPolicy::Always,
+    }
 }
 
 /// Read git config `loom.pruneGoneBranches`. When `true`, `loom update`
 /// removes local branches whose upstream was pruned without prompting.
 /// Returns `false` if the key is unset or not a boolean.
 pub fn prune_gone_branches(repo: &Repository) -> bool {
-    repo.config()
-        .ok()
-        .and_then(|config| config.get_bool("loom.pruneGoneBranches").ok())
-        .unwrap_or(false)
+    config_bool(repo, "loom.pruneGoneBranches").unwrap_or(false)
+}
+
+/// Read the glob patterns from git config `loom.generatedFiles` (multi-valued,
+/// one pattern per entry). Returns an empty list if the key is not set.
+pub fn generated_file_patterns(repo: &Repository) -> Vec<String> {
+    config_patterns(repo, "loom.generatedFiles")
+}
+
+/// Read the shell commands from git config `loom.promoteChecks` (multi-valued,
+/// one command per entry): the checks `loom promote` runs on a branch before
+/// pushing it. Unlike most settings, they never come from the project
+/// defaults, which would let a checked-in file run commands.
+pub fn promote_checks(repo: &Repository) -> Vec<String> {
+    config_commands(repo, "loom.promoteChecks")
+}
+
+/// Read the shell commands from git config `loom.checks` (multi-valued, one
+/// command per entry): the checks `loom check` runs on a branch tip. Like
+/// `loom.promoteChecks`, they never come from the project defaults.
+pub fn checks(repo: &Repository) -> Vec<String> {
+    config_commands(repo, "loom.checks")
+}
+
+/// The non-empty values of the multi-valued git config `key`, read from git
+/// config only.
+fn config_commands(repo: &Repository, key: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    if let Ok(config) = repo.config()
+        && let Ok(entries) = config.multivar(key, None)
+    {
+        let _ = entries.for_each(|entry| {
+            if let Some(value) = entry.value()
+                && !value.trim().is_empty()
+            {
+                commands.push(value.trim().to_string());
+            }
+        });
+    }
+    commands
+}
+
+/// Default for `loom.maxFileSize`.
+const DEFAULT_MAX_FILE_SIZE: i64 = 5 * 1024 * 1024;
+
+/// Read git config `loom.maxFileSize` (default 5 MiB, `k`/`m`/`g` suffixes
+/// accepted): the size from which a staged file is flagged before it is
+/// committed. `0` turns the check off.
+pub fn max_file_size(repo: &Repository) -> Option<u64> {
+    let size = config_i64(repo, "loom.maxFileSize").unwrap_or(DEFAULT_MAX_FILE_SIZE);
+    (size > 0).then_some(size as u64)
+}
+
+/// Read the glob patterns from git config `loom.binaryPatterns` (multi-valued,
+/// one pattern per entry): files flagged before they are committed, whatever
+/// their size. Returns an empty list if the key is not set.
+pub fn binary_file_patterns(repo: &Repository) -> Vec<String> {
+    config_patterns(repo, "loom.binaryPatterns")
+}
+
+/// Read the glob patterns from git config `loom.ignorePaths` (multi-valued,
+/// one pattern per entry). Returns an empty list if the key is not set.
+pub fn ignore_path_patterns(repo: &Repository) -> Vec<String> {
+    config_patterns(repo, "loom.ignorePaths")
+}
+
+/// Read the non-empty, trimmed values of a multi-valued config key, from git
+/// config or else from the project defaults.
+fn config_patterns(repo: &Repository, key: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    if let Ok(config) = repo.config()
+        && let Ok(entries) = config.multivar(key, None)
+    {
+        let _ = entries.for_each(|entry| {
+            if let Some(value) = entry.value()
+                && !value.trim().is_empty()
+            {
+                patterns.push(value.trim().to_string());
+            }
+        });
+    }
+    if patterns.is_empty()
+        && let Some(values) = ProjectDefaults::load(repo).get(key)
+    {
+        patterns = values
+            .iter()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .collect();
+    }
+    patterns
+}
+
+/// Return the subset of `paths` that are generated files.
+///
+/// A path is generated when `.gitattributes` sets `linguist-generated` on it,
+/// or when it matches a `loom.generatedFiles` pattern. An explicit
+/// `-linguist-generated` (or `linguist-generated=false`) overrides the patterns.
+pub fn generated_paths<'a>(
+    repo: &Repository,
+    paths: impl IntoIterator<Item = &'a str>,
+) -> HashSet<String> {
+    let patterns = generated_file_patterns(repo);
+    let pathspec = if patterns.is_empty() {
+        None
+    } else {
+        git2::Pathspec::new(patterns.iter()).ok()
+    };
+
+    let mut generated = HashSet::new();
+    for path in paths {
+        if generated.contains(path) {
+            continue;
+        }
+        let attr = repo
+            .get_attr(
+                Path::new(path),
+                "linguist-generated",
+                git2::AttrCheckFlags::FILE_THEN_INDEX,
+            )
+            .ok()
+            .flatten();
+        let is_generated = match git2::AttrValue::from_string(attr) {
+            git2::AttrValue::True => true,
+            git2::AttrValue::False => false,
+            git2::AttrValue::String(value) => value != "false",
+            _ => pathspec
+                .as_ref()
+                .is_some_and(|p| p.matches_path(Path::new(path), git2::PathspecFlags::DEFAULT)),
+        };
+        if is_generated {
+            generated.insert(path.to_string());
+        }
+    }
+    generated
 }
 
 /// Extract the local branch name from a remote tracking ref.
@@ -196,7 +781,9 @@ impl std::fmt::Display for TargetKind {
 ///
 /// Only the resolution strategies for the kinds listed in `accept` are
 /// attempted, in the order given.  The first match wins.  If nothing
-/// matches, a generic error lists the accepted types.
+/// matches, a generic error lists the accepted types — unless `arg` is the
+/// short ID of a commit that has since been rewritten or dropped, in which
+/// case the error names it and its current equivalent.
 pub fn resolve_arg(repo: &Repository, arg: &str, accept: &[TargetKind]) -> Result<Target> {
     // Phase 1: direct checks (cheap, no graph building)
     for kind in accept {
@@ -216,10 +803,105 @@ pub fn resolve_arg(repo: &Repository, arg: &str, accept: &[TargetKind]) -> Resul
         return Ok(target);
     }
 
+    if let Some(hint) = stale_shortid_hint(repo, arg, accept)? {
+        bail!(hint);
+    }
     let types: Vec<_> = accept.iter().map(|k| k.to_string()).collect();
     bail!("'{}' did not resolve to a {}", arg, types.join(" or "))
 }
 
+/// Describe the commit a stale short ID used to name, when `arg` looks like
+/// one: a hex prefix of a commit found in the reflogs of HEAD or a local
+/// branch, no longer in the weave nor in history. A weave commit with the
+/// same subject is suggested as its current equivalent.
+fn stale_shortid_hint(
+    repo: &Repository,
+    arg: &str,
+    accept: &[TargetKind],
+) -> Result<Option<String>> {
+    let (prefix, suffix) = match arg.split_once(':') {
+        Some((commit, index)) if accept.contains(&TargetKind::CommitFile) => {
+            (commit, format!(":{}", index))
+        }
+        None if accept.contains(&TargetKind::Commit) => (arg, String::new()),
+        _ => return Ok(None),
+    };
+    if prefix.len() < 2 || !prefix.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f')) {
+        return Ok(None);
+    }
+    let Ok(info) = resolution_info(repo, false) else {
+        return Ok(None);
+    };
+    let head = head_oid(repo)?;
+
+    let mut refs = vec!["HEAD".to_string()];
+    for branch in repo.branches(Some(BranchType::Local))? {
+        if let Some(name) = branch?.0.get().name() {
+            refs.push(name.to_string());
+        }
+    }
+    let mut stale = None;
+    'refs: for name in &refs {
+        let Ok(reflog) = repo.reflog(name) else {
+            continue;
+        };
+        for entry in reflog.iter() {
+            for oid in [entry.id_new(), entry.id_old()] {
+                if oid.is_zero()
+                    || !oid.to_string().starts_with(prefix)
+                    || info.commits.iter().any(|c| c.oid == oid)
+                    || oid == head
+                    || repo.graph_descendant_of(head, oid).unwrap_or(false)
+                {
+                    continue;
+                }
+                if let Ok(commit) = repo.find_commit(oid) {
+                    stale = Some(commit);
+                    break 'refs;
+                }
+            }
+        }
+    }
+    let Some(stale) = stale else {
+        return Ok(None);
+    };
+
+    let subject = commit_subject(&stale);
+    let was = format!(
+        "'{}' is a stale short ID: it named `{}` ({})",
+        arg,
+        crate::git::short_hash(&stale.id().to_string()),
+        subject
+    );
+    // The rebase that rewrote it knows its new version; failing that, guess
+    // from the subject
+    let rewritten = crate::core::oplog::follow(repo.path(), stale.id());
+    let equivalents: Vec<_> = match info.commits.iter().find(|c| Some(c.oid) == rewritten) {
+        Some(current) => vec![current],
+        None => info
+            .commits
+            .iter()
+            .filter(|c| c.message == subject)
+            .collect(),
+    };
+    if equivalents.is_empty() {
+        return Ok(Some(format!(
+            "{}, which is no longer in the weave\nRun `loom status` to see the current short IDs",
+            was
+        )));
+    }
+    let allocator = crate::core::shortid::IdAllocator::new(info.collect_entities());
+    let ids: Vec<_> = equivalents
+        .iter()
+        .map(|c| format!("`{}{}`", allocator.get_commit(c.oid), suffix))
+        .collect();
+    Ok(Some(format!(
+        "{}, which has been rewritten since\nIts current short ID is {}",
+        was,
+        ids.join(" or ")
+    )))
+}
+
 /// Reject a commit if it is a merge commit.
 fn reject_merge_commit(repo: &Repository, oid: git2::Oid) -> Result<()> {
     let commit = repo.find_commit(oid)?;
@@ -272,13 +954,98 @@ fn try_resolve_commit(repo: &Repository, arg: &str) -> Result<Option<Target>> {
     if let Ok(obj) = repo.revparse_single(arg)
         && let Ok(commit) = obj.peel_to_commit()
     {
-        let oid = commit.id();
+        let oid = if arg.chars().all(|c| c.is_ascii_hexdigit()) {
+            follow_rewrite(repo, commit.id())
+        } else {
+            commit.id()
+        };
         reject_merge_commit(repo, oid)?;
         return Ok(Some(Target::Commit(oid.to_string())));
     }
     Ok(None)
 }
 
+/// The commit a rebase of this session rewrote `oid` into, when `oid` is
+/// no longer in the history of HEAD but its rewritten version is — so an
+/// old hash from before a loom command still names the same change.
+fn follow_rewrite(repo: &Repository, oid: git2::Oid) -> git2::Oid {
+    let Ok(head) = head_oid(repo) else {
+        return oid;
+    };
+    let in_history =
+        |oid: git2::Oid| oid == head || repo.graph_descendant_of(head, oid).unwrap_or(false);
+    match crate::core::oplog::follow(repo.path(), oid) {
+        Some(new) if !in_history(oid) && in_history(new) => {
+            crate::core::msg::notice(&format!(
+                "`{}` was rewritten since — using `{}`",
+                crate::git::short_hash(&oid.to_string()),
+                crate::git::short_hash(&new.to_string())
+            ));
+            new
+        }
+        _ => oid,
+    }
+}
+
+thread_local! {
+    /// The last [`RepoInfo`] gathered to resolve arguments, with the
+    /// fingerprint of the refs it was gathered from.
+    static RESOLUTION_INFO: RefCell<Option<(u64, RepoInfo)>> = const { RefCell::new(None) };
+}
+
+/// [`gather_repo_info`] for argument resolution, reused until a ref moves: a
+/// command resolving several arguments walks the history once, not once per
+/// argument. The working changes are always read afresh, as they can change
+/// without any ref moving.
+fn resolution_info(repo: &Repository, show_files: bool) -> Result<RepoInfo> {
+    let key = refs_fingerprint(repo, show_files)?;
+    let cached = RESOLUTION_INFO.with_borrow(|cached| {
+        cached
+            .as_ref()
+            .filter(|(k, _)| *k == key)
+            .map(|(_, info)| info.clone())
+    });
+    if let Some(mut info) = cached {
+        info.working_changes = get_working_changes(repo)?;
+        return Ok(info);
+    }
+    let info = gather_repo_info(repo, show_files, 1)?;
+    RESOLUTION_INFO.set(Some((key, info.clone())));
+    Ok(info)
+}
+
+/// A hash of everything a [`RepoInfo`] (working changes aside) is derived
+/// from: the repository, where HEAD points, the target of every ref, and the
+/// branch and loom settings.
+fn refs_fingerprint(repo: &Repository, show_files: bool) -> Result<u64> {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    repo.path().hash(&mut hasher);
+    show_files.hash(&mut hasher);
+    let head = repo.find_reference("HEAD")?;
+    head.symbolic_target_bytes().hash(&mut hasher);
+    head.target().map(|oid| oid.to_string()).hash(&mut hasher);
+    for reference in repo.references()? {
+        let reference = reference?;
+        reference.name_bytes().hash(&mut hasher);
+        reference
+            .resolve()
+            .ok()
+            .and_then(|r| r.target())
+            .map(|oid| oid.to_string())
+            .hash(&mut hasher);
+    }
+    let config = repo.config()?;
+    let mut entries = config.entries(Some("^(branch|loom)\\."))?;
+    while let Some(entry) = entries.next() {
+        let entry = entry?;
+        entry.name_bytes().hash(&mut hasher);
+        entry.value_bytes().hash(&mut hasher);
+    }
+    Ok(hasher.finish())
+}
+
 /// Try to resolve `arg` via the shortid allocator, but only return
 /// results matching one of the `accept` kinds.
 fn try_resolve_shortid(
@@ -287,7 +1054,7 @@ fn try_resolve_shortid(
     accept: &[TargetKind],
 ) -> Result<Option<Target>> {
     let needs_files = arg.contains(':');
-    let info = gather_repo_info(repo, needs_files, 1)?;
+    let info = resolution_info(repo, needs_files)?;
     let entities = info.collect_entities();
     let allocator = crate::core::shortid::IdAllocator::new(entities);
 
@@ -348,7 +1115,11 @@ fn try_resolve_shortid(
 /// Convert a CWD-relative path to a repo-relative path.
 ///
 /// If CWD is `<repo>/src/` and `arg` is `"git.rs"`, returns `"src/git.rs"`.
-fn cwd_to_repo_path(repo: &Repository, arg: &str) -> Result<String> {
+pub fn cwd_to_repo_path(repo: &Repository, arg: &str) -> Result<String> {
+    // Without a working tree, paths are taken relative to the repository root.
+    if repo.is_bare() {
+        return Ok(arg.to_string());
+    }
     let prefix = cwd_relative_to_repo(repo)?;
     if prefix.is_empty() {
         return Ok(arg.to_string());
@@ -405,7 +1176,7 @@ pub fn cwd_relative_path(repo_path: &str, cwd_prefix: &str) -> String {
 }
 
 /// Info about the upstream tracking branch and the merge-base with HEAD.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct UpstreamInfo {
     /// Full name of the upstream ref (e.g. "origin/main").
     pub label: String,
@@ -419,11 +1190,14 @@ pub struct UpstreamInfo {
     pub base_date: String,
     /// How many commits upstream is ahead of the merge-base (0 = up-to-date).
     pub commits_ahead: usize,
+    /// The branch has no upstream tracking branch: the base is `loom.base`
+    /// (named by `label`) or, when unset, the root commit (empty `label`).
+    pub local_only: bool,
 }
 
 /// All data needed to render the status: commits between HEAD and the upstream
 /// tracking branch, detected feature branches, and working tree status.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RepoInfo {
     /// Name of the current (integration) branch.
     pub branch_name: String,
@@ -464,7 +1238,7 @@ impl RepoInfo {
 }
 
 /// A single non-merge commit in the range upstream..HEAD.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CommitInfo {
     pub oid: git2::Oid,
     /// Abbreviated hash respecting the repo's core.abbrev setting.
@@ -476,6 +1250,8 @@ pub struct CommitInfo {
     pub parent_oid: Option<git2::Oid>,
     /// Files changed in this commit (only populated when `-f` is active).
     pub files: Vec<FileChange>,
+    /// Author date, in seconds since the epoch.
+    pub time: i64,
 }
 
 /// Remote tracking status for a feature branch.
@@ -490,7 +1266,7 @@ pub enum RemoteStatus {
 }
 
 /// A local branch whose tip falls within the upstream..HEAD range.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BranchInfo {
     pub name: String,
     pub tip_oid: git2::Oid,
@@ -499,7 +1275,7 @@ pub struct BranchInfo {
 
 /// A context commit shown below the upstream base for history context.
 /// These are display-only (no short ID, not actionable).
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ContextCommit {
     pub short_hash: String,
     pub message: String,
@@ -507,15 +1283,28 @@ pub struct ContextCommit {
 }
 
 /// A file with staged or unstaged changes in the working tree.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FileChange {
     pub path: String,
     /// Index (staged) status: ' ', 'A', 'M', 'D', 'R', or '?'
     pub index: char,
-    /// Worktree (unstaged) status: ' ', 'M', 'D', 'R', '?', or '!' (conflict)
+    /// Worktree (unstaged) status: ' ', 'M', 'D', 'R', or '?'
     pub worktree: char,
 }
 
+impl FileChange {
+    /// Whether the file has unresolved conflicts. `index` and `worktree` then
+    /// hold its unmerged state, as in `git status --short`: `UU` (both
+    /// modified), `AA` (both added), `DU`/`UD` (deleted by us/them), `AU`/`UA`
+    /// (added by us/them), or `DD` (both deleted).
+    pub fn is_conflicted(&self) -> bool {
+        matches!(
+            (self.index, self.worktree),
+            ('U', _) | (_, 'U') | ('A', 'A') | ('D', 'D')
+        )
+    }
+}
+
 /// Collect all data needed for the status display: walk commits from HEAD to the
 /// upstream tracking branch, detect feature branches, and gather working tree status.
 ///
@@ -524,38 +1313,150 @@ pub fn gather_repo_info(repo: &Repository, show_files: bool, context: usize) ->
     let head = repo.head()?;
 
     if !head.is_branch() {
-        bail!("HEAD is detached\nSwitch to an integration branch");
+        bail!(detached_head_message(repo));
     }
 
-    let head_oid = head.target().context("HEAD does not point to a commit")?;
-
     let branch_name = head.shorthand().unwrap_or("HEAD").to_string();
+    gather_branch_info(repo, &branch_name, show_files, context)
+}
+
+/// Error message for a detached HEAD, pointing at the nearest integration
+/// branch that contains it when there is one.
+fn detached_head_message(repo: &Repository) -> String {
+    let nearest = repo
+        .head()
+        .ok()
+        .and_then(|head| head.target())
+        .and_then(|oid| integration_branches_containing(repo, oid).ok())
+        .and_then(|branches| branches.into_iter().next());
+    match nearest {
+        Some(branch) => format!(
+            "HEAD is detached inside `{}`\n\
+             Switch back with `git switch {}`, or view it with `loom status --detached`",
+            branch, branch
+        ),
+        None => "HEAD is detached\nSwitch to an integration branch".to_string(),
+    }
+}
+
+/// Local branches with an upstream whose history contains `oid`, nearest
+/// first (fewest commits above `oid`, then by name). Branches contained in
+/// another candidate are left out: those are feature branches woven into an
+/// integration branch, not integration branches themselves.
+pub fn integration_branches_containing(repo: &Repository, oid: git2::Oid) -> Result<Vec<String>> {
+    let mut candidates: Vec<(String, git2::Oid)> = Vec::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let (Some(name), Some(tip)) = (branch.name()?, branch.get().target()) else {
+            continue;
+        };
+        if branch.upstream().is_ok() && (tip == oid || repo.graph_descendant_of(tip, oid)?) {
+            candidates.push((name.to_string(), tip));
+        }
+    }
+
+    let mut integration = Vec::new();
+    for (name, tip) in &candidates {
+        let mut woven = false;
+        for (other, other_tip) in &candidates {
+            if other != name && tip != other_tip && repo.graph_descendant_of(*other_tip, *tip)? {
+                woven = true;
+                break;
+            }
+        }
+        if !woven {
+            let (distance, _) = repo.graph_ahead_behind(*tip, oid)?;
+            integration.push((distance, name.clone()));
+        }
+    }
+    integration.sort();
+    Ok(integration.into_iter().map(|(_, name)| name).collect())
+}
+
+/// Same as [`gather_repo_info`], for the integration branch `branch_name`
+/// rather than the checked-out one. The commits are walked from the branch
+/// tip, which is how `status` shows a branch whose rebase is paused.
+pub fn gather_branch_info(
+    repo: &Repository,
+    branch_name: &str,
+    show_files: bool,
+    context: usize,
+) -> Result<RepoInfo> {
+    let branch_name = branch_name.to_string();
 
     let local_branch = repo
         .find_branch(&branch_name, BranchType::Local)
         .with_context(|| format!("Branch '{}' not found — are you on a branch?", branch_name))?;
 
-    let upstream = local_branch.upstream().with_context(|| {
-        format!(
-            "Branch '{}' has no upstream tracking branch\n\
-             Set one with: git branch --set-upstream-to=<upstream> {}",
-            branch_name, branch_name
-        )
-    })?;
-
-    let upstream_name = upstream
-        .name()?
-        .context("Upstream branch name is not valid UTF-8")?
-        .to_string();
-
-    let upstream_oid = upstream
+    let head_oid = local_branch
         .get()
         .target()
-        .context("Upstream does not point to a commit")?;
+        .with_context(|| format!("Branch '{}' does not point to a commit", branch_name))?;
+
+    // Without an upstream, a fresh local-only repository (no remote) falls
+    // back to `loom.base` or the root commit so status and history edits
+    // still work. With a remote, a branch without upstream is a feature
+    // branch, not an integration branch.
+    let (upstream_name, upstream_oid, local_only) = match local_branch.upstream() {
+        Ok(upstream) => {
+            let tracked = upstream
+                .name()?
+                .context("Upstream branch name is not valid UTF-8")?;
+            let name = base_upstream(repo, &branch_name, tracked);
+            let oid = if name == tracked {
+                upstream
+                    .get()
+                    .target()
+                    .context("Upstream does not point to a commit")?
+            } else {
+                repo.find_branch(&name, BranchType::Remote)
+                    .ok()
+                    .and_then(|b| b.get().target())
+                    .with_context(|| {
+                        format!(
+                            "`loom.baseRemote` bases `{}` on `{}`, which has not been fetched\n\
+                             Run `loom update` to fetch it",
+                            branch_name, name
+                        )
+                    })?
+            };
+            (name, oid, false)
+        }
+        Err(_) if local_base(repo).is_none() && !repo.remotes()?.is_empty() => {
+            bail!(
+                "Branch '{}' has no upstream tracking branch\n\
+                 Set one with: git branch --set-upstream-to=<upstream> {}",
+                branch_name,
+                branch_name
+            );
+        }
+        Err(_) => match local_base(repo) {
+            Some(base) => {
+                let oid = repo
+                    .revparse_single(&base)
+                    .and_then(|o| o.peel_to_commit())
+                    .map(|c| c.id())
+                    .with_context(|| {
+                        format!("`loom.base` is set to `{}`, which is not a commit", base)
+                    })?;
+                (base, oid, true)
+            }
+            None => (String::new(), root_commit(repo, head_oid)?, true),
+        },
+    };
 
-    let merge_base_oid = repo.merge_base(head_oid, upstream_oid)?;
+    // A branch restacked by `loom update --only` brings newer upstream
+    // commits into HEAD's history: the base of the rest of the weave is where
+    // the first-parent line meets the upstream, and those commits are not
+    // part of the weave.
+    let bases = branch_bases(repo)?;
+    let merge_base_oid = if bases.is_empty() {
+        ancestry::merge_base(repo, head_oid, upstream_oid)?
+    } else {
+        first_parent_base(repo, head_oid, upstream_oid)?
+    };
 
-    let commits = walk_commits(repo, head_oid, merge_base_oid, show_files)?;
+    let commits = walk_commits(repo, head_oid, merge_base_oid, &bases, show_files)?;
     let commit_set: std::collections::HashSet<git2::Oid> = commits.iter().map(|c| c.oid).collect();
     let branches = find_branches_in_range(
         repo,
@@ -567,7 +1468,7 @@ pub fn gather_repo_info(repo: &Repository, show_files: bool, context: usize) ->
     let working_changes = get_working_changes(repo)?;
 
     // Count how many commits upstream is ahead of the merge-base
-    let commits_ahead = count_commits(repo, upstream_oid, merge_base_oid)?;
+    let commits_ahead = ancestry::count_commits(repo, upstream_oid, merge_base_oid)?;
 
     // Get merge-base commit info
     let base_commit = repo.find_commit(merge_base_oid)?;
@@ -592,6 +1493,7 @@ pub fn gather_repo_info(repo: &Repository, show_files: bool, context: usize) ->
             base_message,
             base_date,
             commits_ahead,
+            local_only,
         },
         commits,
         branches,
@@ -600,6 +1502,59 @@ pub fn gather_repo_info(repo: &Repository, show_files: bool, context: usize) ->
     })
 }
 
+/// The first commit of `head`'s first-parent history that is in `upstream`.
+fn first_parent_base(repo: &Repository, head: git2::Oid, upstream: git2::Oid) -> Result<git2::Oid> {
+    let mut commit = repo.find_commit(head)?;
+    loop {
+        if commit.id() == upstream || repo.graph_descendant_of(upstream, commit.id())? {
+            return Ok(commit.id());
+        }
+        commit = match commit.parent(0) {
+            Ok(parent) => parent,
+            Err(_) => return ancestry::merge_base(repo, head, upstream),
+        };
+    }
+}
+
+/// The first commit of `oid`'s first-parent history.
+fn root_commit(repo: &Repository, oid: git2::Oid) -> Result<git2::Oid> {
+    let mut commit = repo.find_commit(oid)?;
+    while let Ok(parent) = commit.parent(0) {
+        commit = parent;
+    }
+    Ok(commit.id())
+}
+
+/// An interactive rebase stopped at an `edit` command.
+#[derive(Debug, PartialEq, Eq)]
+pub struct EditPause {
+    /// Short name of the branch being rebased.
+    pub branch_name: String,
+    /// Original OID of the commit checked out for amending.
+    pub commit: git2::Oid,
+}
+
+/// Detect a rebase paused at an `edit` stop (as opposed to a conflict).
+/// Git marks these stops with `rebase-merge/amend`; the commit being edited
+/// is recorded in `stopped-sha`. Returns None when no such pause exists or
+/// when the rebase runs on a detached HEAD.
+pub fn edit_pause(repo: &Repository) -> Option<EditPause> {
+    let dir = repo.path().join("rebase-merge");
+    if !dir.join("amend").exists() {
+        return None;
+    }
+    let read = |name: &str| std::fs::read_to_string(dir.join(name)).ok();
+    let branch_name = read("head-name")?
+        .trim()
+        .strip_prefix("refs/heads/")?
+        .to_string();
+    let commit = git2::Oid::from_str(read("stopped-sha")?.trim()).ok()?;
+    Some(EditPause {
+        branch_name,
+        commit,
+    })
+}
+
 /// Check if a path (file or directory) has staged or unstaged changes.
 pub fn path_has_changes(repo: &Repository, path: &str) -> Result<bool> {
     let mut opts = StatusOptions::new();
@@ -648,22 +1603,6 @@ pub fn verify_has_staged_changes(repo: &Repository) -> Result<()> {
     Ok(())
 }
 
-/// Count the number of commits reachable from `from` but not from `hide`.
-fn count_commits(repo: &Repository, from: git2::Oid, hide: git2::Oid) -> Result<usize> {
-    if from == hide {
-        return Ok(0);
-    }
-    let mut revwalk = repo.revwalk()?;
-    revwalk.push(from)?;
-    revwalk.hide(hide)?;
-    let mut count = 0usize;
-    for oid_result in revwalk {
-        oid_result?;
-        count += 1;
-    }
-    Ok(count)
-}
-
 /// Walk N-1 commits before the merge-base to provide history context.
 /// Returns an empty vec when `count` is 0 or 1 (the base itself is already
 /// shown in the upstream section).
@@ -714,11 +1653,15 @@ fn walk_commits(
     repo: &Repository,
     head_oid: git2::Oid,
     stop_oid: git2::Oid,
+    branch_bases: &HashMap<String, git2::Oid>,
     show_files: bool,
 ) -> Result<Vec<CommitInfo>> {
     let mut revwalk = repo.revwalk()?;
     revwalk.push(head_oid)?;
     revwalk.hide(stop_oid)?;
+    for &base in branch_bases.values() {
+        revwalk.hide(base)?;
+    }
     revwalk.set_sorting(git2::Sort::TOPOLOGICAL)?;
 
     let mut commits = Vec::new();
@@ -748,6 +1691,7 @@ fn walk_commits(
             message,
             parent_oid,
             files,
+            time: commit.author().when().seconds(),
         });
     }
 
@@ -756,11 +1700,30 @@ fn walk_commits(
 
 /// Return the file paths changed in a commit.
 pub fn commit_file_paths(repo: &Repository, oid: git2::Oid) -> Result<Vec<String>> {
-    let commit = repo.find_commit(oid)?;
-    let files = get_commit_files(repo, &commit)?;
+    let files = commit_files(repo, oid)?;
     Ok(files.into_iter().map(|f| f.path).collect())
 }
 
+/// Return the files changed in a commit, as listed by `loom status -f`.
+pub fn commit_files(repo: &Repository, oid: git2::Oid) -> Result<Vec<FileChange>> {
+    let commit = repo.find_commit(oid)?;
+    get_commit_files(repo, &commit)
+}
+
+/// Return the (insertions, deletions) line counts of a commit against its
+/// first parent (or the empty tree for a root commit).
+pub fn commit_line_stats(repo: &Repository, oid: git2::Oid) -> Result<(usize, usize)> {
+    let commit = repo.find_commit(oid)?;
+    let parent_tree = if commit.parent_count() > 0 {
+        Some(commit.parent(0)?.tree()?)
+    } else {
+        None
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    let stats = diff.stats()?;
+    Ok((stats.insertions(), stats.deletions()))
+}
+
 /// Get the files changed in a commit by diffing against its parent tree.
 /// For root commits (no parent), diffs against an empty tree.
 fn get_commit_files(repo: &Repository, commit: &git2::Commit) -> Result<Vec<FileChange>> {
@@ -816,8 +1779,8 @@ fn find_branches_in_range(
             continue;
         };
         let name = name.to_string();
-        // Skip the current (integration) branch itself
-        if name == current_branch {
+        // Skip the current (integration) branch itself, and a local upstream
+        if name == current_branch || name == upstream_name {
             continue;
         }
         // Skip branches that track the same upstream (e.g. main tracking origin/main)
@@ -886,7 +1849,74 @@ fn detect_remote_status(
     }
 }
 
+/// Collect the working tree changes, leaving out unstaged and untracked files
+/// that match a `loom.ignorePaths` pattern. Staged changes are always kept.
 pub(crate) fn get_working_changes(repo: &Repository) -> Result<Vec<FileChange>> {
+    let mut changes = get_working_changes_opts(repo, false)?;
+    let patterns = ignore_path_patterns(repo);
+    if !patterns.is_empty() {
+        let pathspec = git2::Pathspec::new(patterns.iter())
+            .context("Invalid pattern in git config loom.ignorePaths")?;
+        changes.retain(|change| {
+            !matches!(change.index, ' ' | '?')
+                || !pathspec.matches_path(Path::new(&change.path), git2::PathspecFlags::DEFAULT)
+        });
+    }
+    Ok(changes)
+}
+
+/// How git was told to ignore the local changes of a tracked file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HiddenFlag {
+    /// `git update-index --skip-worktree`
+    SkipWorktree,
+    /// `git update-index --assume-unchanged`
+    AssumeUnchanged,
+}
+
+impl HiddenFlag {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            HiddenFlag::SkipWorktree => "skip-worktree",
+            HiddenFlag::AssumeUnchanged => "assume-unchanged",
+        }
+    }
+}
+
+/// Tracked files whose local changes git hides, in index order. With a
+/// sparse checkout, skip-worktree only marks the files left out of the
+/// working tree, so it is not reported.
+pub fn hidden_files(repo: &Repository) -> Result<Vec<(String, HiddenFlag)>> {
+    if repo.is_bare() {
+        return Ok(Vec::new());
+    }
+    let sparse = repo
+        .config()
+        .and_then(|c| c.get_bool("core.sparseCheckout"))
+        .unwrap_or(false);
+    let mut index = repo.index()?;
+    // Flags may have changed on disk since the index was loaded
+    index.read(false)?;
+    let mut hidden = Vec::new();
+    for entry in index.iter() {
+        let flag = if !sparse
+            && git2::IndexEntryExtendedFlag::from_bits_truncate(entry.flags_extended)
+                .is_skip_worktree()
+        {
+            HiddenFlag::SkipWorktree
+        } else if git2::IndexEntryFlag::from_bits_truncate(entry.flags).is_valid() {
+            HiddenFlag::AssumeUnchanged
+        } else {
+            continue;
+        };
+        hidden.push((String::from_utf8_lossy(&entry.path).into_owned(), flag));
+    }
+    Ok(hidden)
+}
+
+/// Like `get_working_changes` but without applying `loom.ignorePaths`
+/// (`status --all`).
+pub(crate) fn get_all_working_changes(repo: &Repository) -> Result<Vec<FileChange>> {
     get_working_changes_opts(repo, false)
 }
 
@@ -897,11 +1927,16 @@ pub(crate) fn get_working_changes_recurse(repo: &Repository) -> Result<Vec<FileC
 }
 
 fn get_working_changes_opts(repo: &Repository, recurse_untracked: bool) -> Result<Vec<FileChange>> {
+    // A bare repository has no working tree, hence no changes.
+    if repo.is_bare() {
+        return Ok(Vec::new());
+    }
     let mut opts = StatusOptions::new();
     opts.include_untracked(true)
         .recurse_untracked_dirs(recurse_untracked);
 
     let statuses = repo.statuses(Some(&mut opts))?;
+    let conflicts = unmerged_states(repo)?;
     let mut changes = Vec::new();
 
     for entry in statuses.iter() {
@@ -913,10 +1948,17 @@ fn get_working_changes_opts(repo: &Repository, recurse_untracked: bool) -> Resul
             }
         };
         let status = entry.status();
+        if status.is_conflicted() {
+            let (index, worktree) = conflicts.get(&path).copied().unwrap_or(('U', 'U'));
+            changes.push(FileChange {
+                path,
+                index,
+                worktree,
+            });
+            continue;
+        }
 
-        let index = if status.is_conflicted() {
-            '!'
-        } else if status.is_index_new() {
+        let index = if status.is_index_new() {
             'A'
         } else if status.is_index_modified() {
             'M'
@@ -932,8 +1974,6 @@ fn get_working_changes_opts(repo: &Repository, recurse_untracked: bool) -> Resul
 
         let worktree = if status.is_wt_new() {
             '?'
-        } else if status.is_conflicted() {
-            '!'
         } else if status.is_wt_modified() {
             'M'
         } else if status.is_wt_deleted() {
@@ -954,6 +1994,36 @@ fn get_working_changes_opts(repo: &Repository, recurse_untracked: bool) -> Resul
     Ok(changes)
 }
 
+/// The `git status --short` letters of each conflicted path, from the
+/// stages the index holds for it: the common ancestor, ours, and theirs.
+fn unmerged_states(repo: &Repository) -> Result<HashMap<String, (char, char)>> {
+    let index = repo.index()?;
+    let mut states = HashMap::new();
+    if !index.has_conflicts() {
+        return Ok(states);
+    }
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let state = match (&conflict.ancestor, &conflict.our, &conflict.their) {
+            (Some(_), Some(_), Some(_)) => ('U', 'U'),
+            (None, Some(_), Some(_)) => ('A', 'A'),
+            (Some(_), Some(_), None) => ('U', 'D'),
+            (Some(_), None, Some(_)) => ('D', 'U'),
+            (None, Some(_), None) => ('A', 'U'),
+            (None, None, Some(_)) => ('U', 'A'),
+            (Some(_), None, None) => ('D', 'D'),
+            (None, None, None) => continue,
+        };
+        let entry = [&conflict.our, &conflict.their, &conflict.ancestor]
+            .into_iter()
+            .find_map(|e| e.as_ref());
+        if let Some(entry) = entry {
+            states.insert(String::from_utf8_lossy(&entry.path).into_owned(), state);
+        }
+    }
+    Ok(states)
+}
+
 #[cfg(test)]
 #[path = "repo_test.rs"]
 mod tests;
@@ -1,5 +1,6 @@
 use crate::core::repo::{
-    self, Target, TargetKind, gather_repo_info, get_working_changes, get_working_changes_recurse,
+    self, Target, TargetKind, gather_repo_info, get_all_working_changes, get_working_changes,
+    get_working_changes_recurse,
 };
 use crate::core::test_helpers::TestRepo;
 
@@ -148,15 +149,128 @@ fn detached_head_returns_error() {
     assert!(result.unwrap_err().to_string().contains("detached"));
 }
 
+#[test]
+fn detached_head_points_at_containing_integration_branch() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    let a1 = test_repo.commit("A1", "a.txt");
+    // A pushed feature branch has an upstream too, but is woven
+    test_repo
+        .repo
+        .find_branch("feature-a", git2::BranchType::Local)
+        .unwrap()
+        .set_upstream(Some("origin/main"))
+        .unwrap();
+    test_repo.switch_branch("integration");
+    test_repo.commit("Int", "int.txt");
+    test_repo.merge_no_ff("feature-a");
+
+    test_repo.set_detached_head(a1);
+
+    assert_eq!(
+        repo::integration_branches_containing(&test_repo.repo, a1).unwrap(),
+        vec!["integration"]
+    );
+    let err = gather_repo_info(&test_repo.repo, false, 1).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("HEAD is detached inside `integration`"),
+        "{}",
+        err
+    );
+}
+
 #[test]
 fn no_upstream_returns_error() {
-    let test_repo = TestRepo::new();
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-x", &base_oid.to_string());
+    test_repo.switch_branch("feature-x");
 
     let result = gather_repo_info(&test_repo.repo, false, 1);
     assert!(result.is_err());
     assert!(result.unwrap_err().to_string().contains("upstream"));
 }
 
+#[test]
+fn local_only_repo_is_based_on_root_commit() {
+    let test_repo = TestRepo::new();
+    let root = test_repo.head_oid();
+    test_repo.commit("A1", "a1.txt");
+    test_repo.commit("A2", "a2.txt");
+
+    let info = gather_repo_info(&test_repo.repo, false, 1).unwrap();
+
+    assert!(info.upstream.local_only);
+    assert_eq!(info.upstream.label, "");
+    assert_eq!(info.upstream.merge_base_oid, root);
+    let messages: Vec<&str> = info.commits.iter().map(|c| c.message.as_str()).collect();
+    assert_eq!(messages, vec!["A2", "A1"]);
+}
+
+#[test]
+fn local_only_repo_uses_configured_base() {
+    let test_repo = TestRepo::new();
+    let integration = test_repo.current_branch_name();
+    let a1 = test_repo.commit("A1", "a1.txt");
+    test_repo.create_branch_at("trunk", &a1.to_string());
+    test_repo.commit("B1", "b1.txt");
+    test_repo.set_config("loom.base", "trunk");
+
+    // Move the base ahead on its own: the integration branch is behind it.
+    test_repo.switch_branch("trunk");
+    test_repo.commit("T1", "t1.txt");
+    test_repo.switch_branch(&integration);
+
+    let info = gather_repo_info(&test_repo.repo, false, 1).unwrap();
+
+    assert!(info.upstream.local_only);
+    assert_eq!(info.upstream.label, "trunk");
+    assert_eq!(info.upstream.merge_base_oid, a1);
+    assert_eq!(info.upstream.commits_ahead, 1);
+    assert_eq!(info.commits.len(), 1);
+}
+
+/// Add an `upstream` remote next to `origin` (both serving the same bare
+/// repository), as in a fork workflow.
+fn add_upstream_remote(test_repo: &TestRepo) {
+    let remote_path = test_repo.remote_path().unwrap();
+    test_repo
+        .repo
+        .remote("upstream", remote_path.to_str().unwrap())
+        .unwrap();
+}
+
+#[test]
+fn base_remote_bases_weave_on_its_branch() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit("Int", "int.txt");
+    add_upstream_remote(&test_repo);
+    test_repo.add_remote_commits(&["Remote 1", "Remote 2"]);
+    // Only `upstream` sees the new commits: `origin/main` is stale
+    crate::git::run_git(&test_repo.workdir(), &["fetch", "-q", "upstream"]).unwrap();
+    test_repo.set_config("loom.baseRemote", "upstream");
+
+    let info = gather_repo_info(&test_repo.repo, false, 1).unwrap();
+
+    assert_eq!(info.upstream.label, "upstream/main");
+    assert_eq!(info.upstream.commits_ahead, 2);
+    assert_eq!(info.commits.len(), 1);
+}
+
+#[test]
+fn base_remote_not_fetched_returns_error() {
+    let test_repo = TestRepo::new_with_remote();
+    add_upstream_remote(&test_repo);
+    test_repo.set_config("loom.baseRemote", "upstream");
+
+    let err = gather_repo_info(&test_repo.repo, false, 1).unwrap_err();
+
+    assert!(err.to_string().contains("has not been fetched"), "{}", err);
+}
+
 #[test]
 fn working_tree_changes_detected() {
     let test_repo = TestRepo::new_with_remote();
@@ -239,6 +353,33 @@ fn no_working_changes_when_clean() {
     assert!(info.working_changes.is_empty());
 }
 
+#[test]
+fn hidden_files_lists_flagged_entries() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit("skip", "skip.txt");
+    test_repo.commit("assume", "assume.txt");
+    for (flag, file) in [
+        ("--skip-worktree", "skip.txt"),
+        ("--assume-unchanged", "assume.txt"),
+    ] {
+        crate::git::run_git(test_repo.workdir().as_path(), &["update-index", flag, file]).unwrap();
+    }
+
+    let hidden = repo::hidden_files(&test_repo.repo).unwrap();
+    assert_eq!(
+        hidden,
+        vec![
+            ("assume.txt".to_string(), repo::HiddenFlag::AssumeUnchanged),
+            ("skip.txt".to_string(), repo::HiddenFlag::SkipWorktree),
+        ]
+    );
+
+    // A sparse checkout marks files skip-worktree on its own
+    test_repo.set_config("core.sparseCheckout", "true");
+    let hidden = repo::hidden_files(&test_repo.repo).unwrap();
+    assert_eq!(hidden.len(), 1);
+}
+
 #[test]
 fn upstream_ahead_of_merge_base() {
     let test_repo = TestRepo::new_with_remote();
@@ -550,3 +691,437 @@ fn resolve_arg_unstaged_not_accepted() {
         assert!(result.is_err());
     });
 }
+
+#[test]
+fn generated_paths_from_attributes_and_config() {
+    let test_repo = TestRepo::new();
+    test_repo.write_file(
+        ".gitattributes",
+        "gen/** linguist-generated\nCargo.lock linguist-generated=true\nvendor/keep.rs -linguist-generated\n",
+    );
+    crate::git::run_git(
+        test_repo.workdir().as_path(),
+        &["config", "--add", "loom.generatedFiles", "vendor/**"],
+    )
+    .unwrap();
+    crate::git::run_git(
+        test_repo.workdir().as_path(),
+        &["config", "--add", "loom.generatedFiles", "*.min.js"],
+    )
+    .unwrap();
+
+    let generated = repo::generated_paths(
+        &test_repo.repo,
+        [
+            "gen/api.rs",
+            "Cargo.lock",
+            "vendor/dep.rs",
+            "vendor/keep.rs",
+            "web/app.min.js",
+            "src/main.rs",
+        ],
+    );
+
+    let mut generated: Vec<_> = generated.into_iter().collect();
+    generated.sort();
+    assert_eq!(
+        generated,
+        vec![
+            "Cargo.lock",
+            "gen/api.rs",
+            "vendor/dep.rs",
+            "web/app.min.js"
+        ]
+    );
+}
+
+#[test]
+fn generated_paths_empty_without_configuration() {
+    let test_repo = TestRepo::new();
+    assert!(repo::generated_paths(&test_repo.repo, ["Cargo.lock", "src/main.rs"]).is_empty());
+}
+
+#[test]
+fn ignored_paths_hidden_from_working_changes() {
+    let test_repo = TestRepo::new();
+    test_repo.commit("Add config", "app.toml");
+    test_repo.set_config("loom.ignorePaths", "*.toml");
+    test_repo.write_file("app.toml", "local tweak");
+    test_repo.write_file("other.toml", "untracked");
+    test_repo.write_file("main.rs", "fn main() {}");
+
+    let paths = |changes: Vec<repo::FileChange>| -> Vec<String> {
+        let mut paths: Vec<String> = changes.into_iter().map(|c| c.path).collect();
+        paths.sort();
+        paths
+    };
+
+    assert_eq!(
+        paths(get_working_changes(&test_repo.repo).unwrap()),
+        vec!["main.rs"]
+    );
+    assert_eq!(
+        paths(get_all_working_changes(&test_repo.repo).unwrap()),
+        vec!["app.toml", "main.rs", "other.toml"]
+    );
+
+    // Staged changes stay visible even when ignored
+    test_repo.stage_files(&["app.toml"]);
+    assert_eq!(
+        paths(get_working_changes(&test_repo.repo).unwrap()),
+        vec!["app.toml", "main.rs"]
+    );
+}
+
+#[test]
+fn commit_line_stats_counts_insertions_and_deletions() {
+    let test_repo = TestRepo::new();
+    let root = test_repo.commit("one\ntwo\nthree\n", "a.txt");
+    let edit = test_repo.commit("one\n2\n3\nfour\n", "a.txt");
+
+    assert_eq!(
+        repo::commit_line_stats(&test_repo.repo, root).unwrap(),
+        (3, 0)
+    );
+    assert_eq!(
+        repo::commit_line_stats(&test_repo.repo, edit).unwrap(),
+        (3, 2)
+    );
+}
+
+#[test]
+fn edit_pause_reports_branch_and_commit() {
+    let test_repo = TestRepo::new_with_remote();
+    let first = test_repo.commit("First", "a.txt");
+    test_repo.commit("Second", "b.txt");
+    assert_eq!(repo::edit_pause(&test_repo.repo), None);
+
+    let workdir = test_repo.workdir();
+    crate::core::weave::start_edit_rebase(&test_repo.repo, &workdir, first).unwrap();
+
+    assert_eq!(
+        repo::edit_pause(&test_repo.repo),
+        Some(repo::EditPause {
+            branch_name: "integration".to_string(),
+            commit: first,
+        })
+    );
+    // The paused branch is still gathered from its (unchanged) tip
+    let info = repo::gather_branch_info(&test_repo.repo, "integration", false, 1).unwrap();
+    assert_eq!(info.commits.len(), 2);
+    assert_eq!(info.commits[1].oid, first);
+
+    crate::git::rebase_abort(&workdir).unwrap();
+    assert_eq!(repo::edit_pause(&test_repo.repo), None);
+}
+
+#[test]
+fn bare_repository_can_be_gathered() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a.txt");
+    test_repo.switch_branch("integration");
+    test_repo.commit("Int", "int.txt");
+    test_repo.merge_no_ff("feature-a");
+    test_repo.write_file("dirty.txt", "not in the bare copy");
+
+    // A bare copy whose integration branch tracks a local `main`
+    let bare_path = test_repo.workdir().parent().unwrap().join("bare.git");
+    let bare = git2::Repository::init_bare(&bare_path).unwrap();
+    bare.remote_anonymous(test_repo.workdir().to_str().unwrap())
+        .unwrap()
+        .fetch(&["refs/heads/*:refs/heads/*"], None, None)
+        .unwrap();
+    bare.set_head("refs/heads/integration").unwrap();
+    let mut config = bare.config().unwrap();
+    config.set_str("branch.integration.remote", ".").unwrap();
+    config
+        .set_str("branch.integration.merge", "refs/heads/main")
+        .unwrap();
+
+    assert!(get_working_changes(&bare).unwrap().is_empty());
+    let info = gather_repo_info(&bare, true, 1).unwrap();
+    assert_eq!(info.branch_name, "integration");
+    let names: Vec<&str> = info.branches.iter().map(|b| b.name.as_str()).collect();
+    assert_eq!(names, vec!["feature-a"]);
+    assert_eq!(info.commits.len(), 2);
+    assert_eq!(repo::cwd_to_repo_path(&bare, "a.txt").unwrap(), "a.txt");
+}
+
+#[test]
+fn stale_days_requires_age_colors() {
+    let test_repo = TestRepo::new_with_remote();
+    assert_eq!(repo::stale_days(&test_repo.repo), None);
+
+    test_repo.set_config("loom.ageColors", "true");
+    assert_eq!(repo::stale_days(&test_repo.repo), Some(30));
+
+    test_repo.set_config("loom.staleDays", "90");
+    assert_eq!(repo::stale_days(&test_repo.repo), Some(90));
+}
+
+#[test]
+fn notes_rewrite_refs_follow_git_config() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.set_config("notes.rewriteRef", "refs/notes/review");
+    assert!(repo::notes_rewrite_refs(&test_repo.repo).is_empty());
+
+    test_repo.set_config("loom.rewriteNotes", "true");
+    assert_eq!(
+        repo::notes_rewrite_refs(&test_repo.repo),
+        vec!["refs/notes/review"]
+    );
+
+    crate::git::run_git(
+        test_repo.workdir().as_path(),
+        &["config", "--unset", "notes.rewriteRef"],
+    )
+    .unwrap();
+    assert_eq!(
+        repo::notes_rewrite_refs(&test_repo.repo),
+        vec!["refs/notes/commits"]
+    );
+}
+
+/// The shortest hex prefix of `oid` (2 or 3 characters, as short IDs are)
+/// that no commit of the current weave starts with.
+fn stale_prefix(test_repo: &TestRepo, oid: git2::Oid) -> String {
+    let info = repo::gather_repo_info(&test_repo.repo, false, 1).unwrap();
+    let hex = oid.to_string();
+    (2..=3)
+        .map(|n| hex[..n].to_string())
+        .find(|p| {
+            !info
+                .commits
+                .iter()
+                .any(|c| c.oid.to_string().starts_with(p))
+        })
+        .expect("a prefix unique to the stale commit")
+}
+
+#[test]
+fn resolve_arg_stale_shortid_suggests_rewritten_commit() {
+    let test_repo = TestRepo::new_with_remote();
+    let a1 = test_repo.commit_empty("A1");
+    let old = test_repo.commit("A2", "a2.txt");
+    test_repo.reset_hard(a1);
+    let new = test_repo.commit("A2", "other.txt");
+
+    test_repo.in_dir(|| {
+        let prefix = stale_prefix(&test_repo, old);
+        let err = repo::resolve_arg(&test_repo.repo, &prefix, &[TargetKind::Commit])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("stale short ID"), "{}", err);
+        assert!(err.contains("(A2)"), "{}", err);
+
+        let info = repo::gather_repo_info(&test_repo.repo, false, 1).unwrap();
+        let alloc = crate::core::shortid::IdAllocator::new(info.collect_entities());
+        assert!(
+            err.contains(&format!(
+                "Its current short ID is `{}`",
+                alloc.get_commit(new)
+            )),
+            "{}",
+            err
+        );
+
+        // Commit file IDs keep their index in the suggestion
+        let err = repo::resolve_arg(
+            &test_repo.repo,
+            &format!("{}:0", prefix),
+            &[TargetKind::CommitFile],
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.contains(&format!("`{}:0`", alloc.get_commit(new))),
+            "{}",
+            err
+        );
+    });
+}
+
+#[test]
+fn resolve_arg_stale_shortid_of_dropped_commit() {
+    let test_repo = TestRepo::new_with_remote();
+    let a1 = test_repo.commit_empty("A1");
+    let old = test_repo.commit("A2", "a2.txt");
+    test_repo.reset_hard(a1);
+
+    test_repo.in_dir(|| {
+        let prefix = stale_prefix(&test_repo, old);
+        let err = repo::resolve_arg(&test_repo.repo, &prefix, &[TargetKind::Commit])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("no longer in the weave"), "{}", err);
+    });
+}
+
+#[test]
+fn resolve_arg_unknown_shortid_keeps_generic_error() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit_empty("A1");
+    test_repo.in_dir(|| {
+        let err = repo::resolve_arg(&test_repo.repo, "qq", &[TargetKind::Commit])
+            .unwrap_err()
+            .to_string();
+        assert_eq!(err, "'qq' did not resolve to a commit");
+    });
+}
+
+#[test]
+fn ref_moves_lists_moved_created_and_deleted_branches() {
+    let test_repo = TestRepo::new_with_remote();
+    let a1 = test_repo.commit_empty("A1");
+    test_repo.create_branch_at_commit("feature-a", a1);
+    test_repo.create_branch_at_commit("gone", a1);
+    test_repo.create_branch_at_commit("still", a1);
+    let before = repo::snapshot_branch_refs(&test_repo.repo).unwrap();
+
+    let a2 = test_repo.commit_empty("A2");
+    test_repo
+        .repo
+        .branch("feature-a", &test_repo.find_commit(a2), true)
+        .unwrap();
+    test_repo.delete_branch("gone");
+    test_repo.create_branch_at_commit("created", a2);
+    test_repo.create_branch_at_commit("_loom-track", a2);
+
+    let moves = repo::ref_moves(&test_repo.repo, &before).unwrap();
+    let names: Vec<&str> = moves.iter().map(|m| m.name.as_str()).collect();
+    let integration = test_repo.current_branch_name();
+    let mut expected = vec!["created", "feature-a", "gone", integration.as_str()];
+    expected.sort();
+    assert_eq!(names, expected);
+
+    let feature = moves.iter().find(|m| m.name == "feature-a").unwrap();
+    assert_eq!(
+        (feature.old, feature.new, feature.delta),
+        (Some(a1), Some(a2), 1)
+    );
+    let gone = moves.iter().find(|m| m.name == "gone").unwrap();
+    assert_eq!((gone.old, gone.new), (Some(a1), None));
+    let created = moves.iter().find(|m| m.name == "created").unwrap();
+    assert_eq!((created.old, created.new), (None, Some(a2)));
+}
+
+#[test]
+fn ref_moves_counts_a_rewrite_as_no_change() {
+    let test_repo = TestRepo::new_with_remote();
+    let a1 = test_repo.commit_empty("A1");
+    let before = repo::snapshot_branch_refs(&test_repo.repo).unwrap();
+
+    // Replace A1 with another commit on the same parent
+    test_repo.reset_hard(test_repo.find_commit(a1).parent_id(0).unwrap());
+    test_repo.commit("A1 again", "a1.txt");
+
+    let moves = repo::ref_moves(&test_repo.repo, &before).unwrap();
+    assert_eq!(moves.len(), 1);
+    assert_eq!(moves[0].delta, 0);
+}
+
+#[test]
+fn ref_move_describe() {
+    colored::control::set_override(false);
+    let a = git2::Oid::from_str("ab12345000000000000000000000000000000000").unwrap();
+    let b = git2::Oid::from_str("cd67890000000000000000000000000000000000").unwrap();
+    let describe = |old, new, delta| {
+        repo::RefMove {
+            name: "feature-a".to_string(),
+            old,
+            new,
+            delta,
+        }
+        .describe(10)
+    };
+    assert_eq!(
+        describe(Some(a), Some(b), 1),
+        "feature-a   ab12345 → cd67890  (+1 commit)"
+    );
+    assert_eq!(
+        describe(Some(a), Some(b), -2),
+        "feature-a   ab12345 → cd67890  (−2 commits)"
+    );
+    assert_eq!(describe(None, Some(b), 0), "feature-a   created → cd67890");
+    assert_eq!(describe(Some(a), None, 0), "feature-a   ab12345 → deleted");
+}
+
+#[test]
+fn resolution_info_is_reused_until_a_ref_moves() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit_empty("A1");
+    test_repo.in_dir(|| {
+        let info = super::resolution_info(&test_repo.repo, false).unwrap();
+        assert_eq!(info.commits.len(), 1);
+
+        // A planted answer proves the history is not walked again
+        super::RESOLUTION_INFO.with_borrow_mut(|cached| {
+            cached.as_mut().unwrap().1.commits.clear();
+        });
+        let info = super::resolution_info(&test_repo.repo, false).unwrap();
+        assert!(info.commits.is_empty());
+
+        test_repo.commit_empty("A2");
+        let info = super::resolution_info(&test_repo.repo, false).unwrap();
+        assert_eq!(info.commits.len(), 2);
+    });
+}
+
+#[test]
+fn resolution_info_reads_working_changes_afresh() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit_empty("A1");
+    test_repo.in_dir(|| {
+        let info = super::resolution_info(&test_repo.repo, false).unwrap();
+        assert!(info.working_changes.is_empty());
+
+        test_repo.write_file("new.txt", "new");
+        let info = super::resolution_info(&test_repo.repo, false).unwrap();
+        let alloc = crate::core::shortid::IdAllocator::new(info.collect_entities());
+        let result = repo::resolve_arg(
+            &test_repo.repo,
+            alloc.get_file("new.txt"),
+            &[TargetKind::File],
+        )
+        .unwrap();
+        assert_eq!(result, Target::File("new.txt".to_string()));
+    });
+}
+
+#[test]
+fn working_changes_report_the_kind_of_conflict() {
+    let test_repo = TestRepo::new();
+    test_repo.write_file("both.txt", "base\n");
+    test_repo.write_file("gone.txt", "base\n");
+    test_repo.stage_files(&["both.txt", "gone.txt"]);
+    test_repo.commit_staged("Base");
+    let base = test_repo.current_branch_name();
+
+    test_repo.create_branch("theirs");
+    test_repo.switch_branch("theirs");
+    test_repo.write_file("both.txt", "theirs\n");
+    test_repo.write_file("gone.txt", "theirs\n");
+    test_repo.stage_files(&["both.txt", "gone.txt"]);
+    test_repo.commit_staged("Theirs");
+
+    test_repo.switch_branch(&base);
+    test_repo.write_file("both.txt", "ours\n");
+    test_repo.stage_files(&["both.txt"]);
+    std::fs::remove_file(test_repo.workdir().join("gone.txt")).unwrap();
+    crate::git::run_git(&test_repo.workdir(), &["rm", "-q", "gone.txt"]).unwrap();
+    test_repo.commit_staged("Ours");
+    let _ = crate::git::run_git(&test_repo.workdir(), &["merge", "theirs"]);
+    test_repo.repo.index().unwrap().read(true).unwrap();
+
+    let changes = get_working_changes(&test_repo.repo).unwrap();
+    let state = |path: &str| {
+        let change = changes.iter().find(|c| c.path == path).unwrap();
+        assert!(change.is_conflicted());
+        (change.index, change.worktree)
+    };
+    assert_eq!(state("both.txt"), ('U', 'U'));
+    assert_eq!(state("gone.txt"), ('D', 'U'));
+}
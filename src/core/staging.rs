@@ -25,6 +25,7 @@ pub fn run_hunk_picker(
         return Ok(false);
     }
 
+    msg::require_input("the hunks to stage")?;
     let tui_theme = TuiTheme::from_graph_theme(theme);
     let result = crate::tui::hunk_selector::run_hunk_selector(entries, tui_theme)?;
 
@@ -37,6 +38,36 @@ pub fn run_hunk_picker(
     }
 }
 
+/// Open the file checklist for the given files (or all if empty / `zz`):
+/// whole files are picked for staging, and the others are left unstaged.
+///
+/// Returns `true` if the user confirmed, `false` if they cancelled.
+pub fn run_file_picker(
+    repo: &Repository,
+    workdir: &Path,
+    files: &[String],
+    theme: &graph::Theme,
+) -> Result<bool> {
+    let entries = collect_file_entries(repo, workdir, files)?;
+
+    if entries.is_empty() {
+        msg::warn("No changes to stage");
+        return Ok(false);
+    }
+
+    msg::require_input("the files to stage")?;
+    let tui_theme = TuiTheme::from_graph_theme(theme);
+    let result = crate::tui::hunk_selector::run_file_checklist(entries, tui_theme)?;
+
+    match result {
+        None => Ok(false),
+        Some(selected_files) => {
+            apply_selections(workdir, &selected_files)?;
+            Ok(true)
+        }
+    }
+}
+
 /// Collect file entries with git status, staged/unstaged hunks, and proper initial selection.
 pub(crate) fn collect_file_entries(
     repo: &Repository,
@@ -68,6 +99,9 @@ pub(crate) fn collect_file_entries(
             continue;
         }
 
+        if change.is_conflicted() {
+            continue;
+        }
         let has_staged = matches!(change.index, 'A' | 'M' | 'D' | 'R');
         let has_unstaged = matches!(change.worktree, 'M' | 'D' | '?');
 
@@ -347,7 +381,7 @@ fn collect_unstaged_hunks(
 ///
 /// Shows the diff of `oid` vs its parent. All hunks start unselected (no-op).
 /// Returns `Some(files)` with the user's selections on confirm, `None` on cancel.
-pub fn run_commit_hunk_picker(
+pub(crate) fn run_commit_hunk_picker(
     workdir: &Path,
     oid: &str,
     files: &[String],
@@ -360,6 +394,7 @@ pub fn run_commit_hunk_picker(
         return Ok(None);
     }
 
+    msg::require_input("the hunks to select")?;
     let tui_theme = TuiTheme::from_graph_theme(theme);
     crate::tui::hunk_selector::run_hunk_selector(entries, tui_theme)
 }
@@ -21,6 +21,12 @@ pub struct TestRepo {
     _dir: TempDir,
 }
 
+impl Default for TestRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TestRepo {
     /// Create a new test repository with an initial commit.
     pub fn new() -> Self {
@@ -98,6 +104,14 @@ impl TestRepo {
         TestRepo { repo, _dir: dir }
     }
 
+    /// Like `new_with_remote`, with `feature-a` and `feature-b` woven into the
+    /// integration branch (see [`weave_branches`](Self::weave_branches)).
+    pub fn new_with_woven_branches() -> Self {
+        let test_repo = Self::new_with_remote();
+        test_repo.weave_branches(&["feature-a", "feature-b"]);
+        test_repo
+    }
+
     /// Like `new_with_remote`, but stays on the `main` branch instead of
     /// creating a separate `integration` branch. Useful for testing the
     /// loose-commit path where the branch name must match the upstream's
@@ -481,6 +495,16 @@ impl TestRepo {
         }
     }
 
+    /// Clone the remote of this repository (see `new_with_remote`) into a
+    /// new test repository, on its default branch: a teammate's checkout.
+    pub fn clone_remote(&self) -> TestRepo {
+        let remote_path = self.remote_path().expect("No remote repository found");
+        let dir = tempfile::tempdir().unwrap();
+        let repo = Repository::clone(remote_path.to_str().unwrap(), dir.path()).unwrap();
+        Self::configure_identity(&repo);
+        TestRepo { repo, _dir: dir }
+    }
+
     /// Add commits directly to the remote repository.
     ///
     /// This is useful for simulating upstream changes.
@@ -618,6 +642,23 @@ impl TestRepo {
             matches!(outcome, crate::git::MergeOutcome::Completed),
             "merge_no_ff: expected Completed, got Conflicted"
         );
+        // The merge ran in a subprocess: reload the index git2 keeps in
+        // memory, or the next `commit` would build its tree from a stale one.
+        self.repo.index().unwrap().read(true).unwrap();
+    }
+
+    /// For each name, create a branch at `origin/main` with one commit
+    /// (`"<name> commit"`, adding `<name>.txt`) and merge it into the
+    /// integration branch with --no-ff.
+    pub fn weave_branches(&self, names: &[&str]) {
+        let base_oid = self.find_remote_branch_target("origin/main");
+        for name in names {
+            self.create_branch_at(name, &base_oid.to_string());
+            self.switch_branch(name);
+            self.commit(&format!("{} commit", name), &format!("{}.txt", name));
+            self.switch_branch("integration");
+            self.merge_no_ff(name);
+        }
     }
 
     /// Stage files in the working directory.
@@ -640,6 +681,20 @@ impl TestRepo {
         crate::git::diff_commit(self.workdir().as_path(), oid).unwrap()
     }
 
+    /// Install a `commit-msg` hook that rejects every message, so `git commit`
+    /// fails the same way it does when the user aborts the editor.
+    pub fn reject_commit_messages(&self) {
+        let hooks = self.repo.path().join("hooks");
+        fs::create_dir_all(&hooks).unwrap();
+        let hook = hooks.join("commit-msg");
+        fs::write(&hook, "#!/bin/sh\nexit 1\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+    }
+
     /// Set a git config value.
     pub fn set_config(&self, key: &str, value: &str) {
         crate::git::run_git(self.workdir().as_path(), &["config", key, value]).unwrap();
@@ -667,6 +722,15 @@ impl TestRepo {
         info.branches.iter().map(|b| b.name.clone()).collect()
     }
 
+    /// Get the name and tip of every branch in the commit range.
+    pub fn branch_tips(&self) -> Vec<(String, git2::Oid)> {
+        let info = crate::core::repo::gather_repo_info(&self.repo, false, 1).unwrap();
+        info.branches
+            .into_iter()
+            .map(|b| (b.name, b.tip_oid))
+            .collect()
+    }
+
     /// Get the commit summary at the tip of a branch.
     pub fn branch_commit_summary(&self, name: &str) -> String {
         let oid = self.get_branch_target(name);
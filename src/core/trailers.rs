@@ -0,0 +1,111 @@
+//! Commit message trailers (`Signed-off-by`, `Co-authored-by`, ...).
+
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use git2::{Oid, Repository};
+
+use crate::core::repo;
+use crate::git;
+
+/// A `(key, value)` trailer pair.
+pub type Trailer = (String, String);
+
+/// Parse the trailer block of a commit message.
+pub fn parse(message: &str) -> Vec<Trailer> {
+    git2::message_trailers_strs(message)
+        .map(|trailers| {
+            trailers
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.trim().to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Append the `trailers` that `message` does not already carry.
+///
+/// Trailers match on a case-insensitive key and an exact value. Returns `None`
+/// when every trailer is already present.
+pub fn append_missing(message: &str, trailers: &[Trailer]) -> Option<String> {
+    let existing = parse(message);
+    let mut missing: Vec<&Trailer> = Vec::new();
+    for trailer in trailers {
+        let seen = |(k, v): &Trailer| k.eq_ignore_ascii_case(&trailer.0) && *v == trailer.1;
+        if !existing.iter().any(seen) && !missing.iter().any(|t| seen(t)) {
+            missing.push(trailer);
+        }
+    }
+    if missing.is_empty() {
+        return None;
+    }
+
+    let mut out = message.trim_end().to_string();
+    out.push_str(if existing.is_empty() { "\n\n" } else { "\n" });
+    for (key, value) in missing {
+        out.push_str(&format!("{}: {}\n", key, value));
+    }
+    Some(out)
+}
+
+/// Build a `Co-authored-by` trailer from a `Name <email>` argument.
+pub fn coauthor(value: &str) -> Result<Trailer> {
+    let value = value.trim();
+    let valid = match value.split_once('<') {
+        Some((name, rest)) => {
+            !name.trim().is_empty() && rest.ends_with('>') && rest.len() > 1 && !rest.contains('<')
+        }
+        None => false,
+    };
+    if !valid {
+        bail!(
+            "Invalid co-author '{}'\nExpected the form \"Name <email>\"",
+            value
+        );
+    }
+    Ok(("Co-authored-by".to_string(), value.to_string()))
+}
+
+/// Create a copy of commit `oid` with the missing `trailers` appended to its
+/// message. Returns `None` when there is nothing to add.
+///
+/// The copy keeps the original's tree, parents, author, and committer, so a
+/// rebase can pick it in place of the original. The new message goes through
+/// the `commit-msg` hook.
+pub fn copy_with_trailers(
+    repo: &Repository,
+    oid: Oid,
+    trailers: &[Trailer],
+) -> Result<Option<Oid>> {
+    let commit = repo.find_commit(oid)?;
+    let Some(message) = append_missing(commit.message().unwrap_or(""), trailers) else {
+        return Ok(None);
+    };
+    // libgit2 does not run hooks: run commit-msg as `git commit` would.
+    let message = git::run_commit_msg_hook(repo::command_dir(repo), &message)?;
+
+    let parents: Vec<git2::Commit> = commit.parents().collect();
+    let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+    let new_oid = repo.commit(
+        None,
+        &commit.author(),
+        &commit.committer(),
+        &message,
+        &commit.tree()?,
+        &parent_refs,
+    )?;
+    Ok(Some(new_oid))
+}
+
+/// Reword HEAD to carry the missing `trailers`. No-op when none are missing.
+pub fn amend_head(repo: &Repository, workdir: &Path, trailers: &[Trailer]) -> Result<()> {
+    let head = repo.find_commit(repo::head_oid(repo)?)?;
+    if let Some(message) = append_missing(head.message().unwrap_or(""), trailers) {
+        git::commit_amend(workdir, Some(&message))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "trailers_test.rs"]
+mod tests;
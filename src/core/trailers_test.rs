@@ -0,0 +1,127 @@
+use crate::core::test_helpers::TestRepo;
+
+fn trailer(key: &str, value: &str) -> super::Trailer {
+    (key.to_string(), value.to_string())
+}
+
+#[test]
+fn parse_reads_trailer_block() {
+    let trailers = super::parse(
+        "Subject\n\nBody.\n\nSigned-off-by: Ada <ada@example.com>\nCo-authored-by: Bob <bob@example.com>\n",
+    );
+    assert_eq!(
+        trailers,
+        vec![
+            trailer("Signed-off-by", "Ada <ada@example.com>"),
+            trailer("Co-authored-by", "Bob <bob@example.com>"),
+        ]
+    );
+    assert!(super::parse("Subject only\n").is_empty());
+}
+
+#[test]
+fn append_missing_adds_block_when_message_has_none() {
+    let merged = super::append_missing(
+        "Subject\n\nBody text.\n",
+        &[trailer("Signed-off-by", "Bob <bob@example.com>")],
+    );
+    assert_eq!(
+        merged.as_deref(),
+        Some("Subject\n\nBody text.\n\nSigned-off-by: Bob <bob@example.com>\n")
+    );
+}
+
+#[test]
+fn append_missing_extends_existing_block() {
+    let merged = super::append_missing(
+        "Subject\n\nSigned-off-by: Ada <ada@example.com>\n",
+        &[trailer("Co-authored-by", "Bob <bob@example.com>")],
+    );
+    assert_eq!(
+        merged.as_deref(),
+        Some(
+            "Subject\n\nSigned-off-by: Ada <ada@example.com>\n\
+             Co-authored-by: Bob <bob@example.com>\n"
+        )
+    );
+}
+
+#[test]
+fn append_missing_none_when_nothing_new() {
+    let message = "Subject\n\nSigned-off-by: Bob <bob@example.com>\n";
+    assert_eq!(super::append_missing(message, &[]), None);
+    assert_eq!(
+        super::append_missing(
+            message,
+            &[trailer("signed-off-by", "Bob <bob@example.com>")]
+        ),
+        None
+    );
+}
+
+#[test]
+fn append_missing_skips_duplicates_in_input() {
+    let bob = trailer("Co-authored-by", "Bob <bob@example.com>");
+    let merged = super::append_missing("Subject", &[bob.clone(), bob]).unwrap();
+    assert_eq!(merged, "Subject\n\nCo-authored-by: Bob <bob@example.com>\n");
+}
+
+#[test]
+fn coauthor_validates_name_and_email() {
+    assert_eq!(
+        super::coauthor("  Ada Lovelace <ada@example.com> ").unwrap(),
+        trailer("Co-authored-by", "Ada Lovelace <ada@example.com>")
+    );
+    assert!(super::coauthor("ada@example.com").is_err());
+    assert!(super::coauthor("<ada@example.com>").is_err());
+    assert!(super::coauthor("Ada <>").is_err());
+    assert!(super::coauthor("Ada <ada@example.com").is_err());
+}
+
+#[test]
+fn copy_with_trailers_keeps_tree_and_parents() {
+    let test_repo = TestRepo::new();
+    let oid = test_repo.commit("Add file", "file.txt");
+
+    let copy = super::copy_with_trailers(
+        &test_repo.repo,
+        oid,
+        &[trailer("Co-authored-by", "Bob <bob@example.com>")],
+    )
+    .unwrap()
+    .unwrap();
+
+    let original = test_repo.find_commit(oid);
+    let copy = test_repo.find_commit(copy);
+    assert_eq!(copy.tree_id(), original.tree_id());
+    assert_eq!(
+        copy.parent_ids().collect::<Vec<_>>(),
+        original.parent_ids().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        copy.message(),
+        Some("Add file\n\nCo-authored-by: Bob <bob@example.com>\n")
+    );
+
+    assert!(
+        super::copy_with_trailers(&test_repo.repo, oid, &[])
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn copy_with_trailers_runs_commit_msg_hook() {
+    let test_repo = TestRepo::new();
+    let oid = test_repo.commit("Add file", "file.txt");
+    test_repo.reject_commit_messages();
+    let bob = [trailer("Co-authored-by", "Bob <bob@example.com>")];
+
+    let err = super::copy_with_trailers(&test_repo.repo, oid, &bob).unwrap_err();
+    assert!(err.to_string().contains("commit-msg hook"), "{}", err);
+
+    crate::git::set_no_verify(true);
+    let copy = super::copy_with_trailers(&test_repo.repo, oid, &bob);
+    crate::git::set_no_verify(false);
+    assert!(copy.unwrap().is_some());
+}
@@ -1,3 +1,5 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, bail};
@@ -10,6 +12,11 @@ use crate::git;
 pub struct LoomState {
     /// The name of the interrupted command (e.g., "update", "commit").
     pub command: String,
+    /// Local branch tips before the command ran, for the summary of the
+    /// branches it moved once `loom continue` completes it. Empty when the
+    /// command prints no summary.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub refs_before: HashMap<String, String>,
     /// Shared rollback information for `loom abort`.
     pub rollback: Rollback,
     /// Command-specific resume context (opaque JSON).
@@ -39,6 +46,11 @@ pub struct Rollback {
     /// Working-tree diff saved before the rebase (may be empty).
     #[serde(default)]
     pub saved_worktree_patch: String,
+    /// Local branch tips when the command started (see [`begin`]). Filled
+    /// in by [`save`]; `loom abort` puts back any branch still moved once
+    /// the rest of the rollback ran.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub branch_refs: HashMap<String, String>,
 }
 
 impl Rollback {
@@ -70,6 +82,51 @@ impl Rollback {
     }
 }
 
+/// Run a step that may open the user's editor, undoing the operation's
+/// partial state if it fails (e.g. the user saved an empty message).
+///
+/// Every command that opens an editor wraps that step here. On error,
+/// `rollback` is applied first, then any rebase the operation started is
+/// aborted, so the repository is left as it was before the command ran.
+pub fn editor_step<T>(
+    workdir: &Path,
+    rollback: &Rollback,
+    step: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let err = match step() {
+        Ok(value) => return Ok(value),
+        Err(e) => e,
+    };
+    rollback
+        .apply_abort(workdir)
+        .context("Could not roll back after the editor step failed")?;
+    let git_dir = git2::Repository::discover(workdir)?.path().to_path_buf();
+    if git::rebase_is_in_progress(&git_dir) {
+        git::rebase_abort(workdir)?;
+    }
+    Err(err.context("Aborted — nothing was changed"))
+}
+
+thread_local! {
+    /// Branch tips recorded by [`begin`] for the command being run.
+    static JOURNAL: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+/// Record the local branch tips before a command that may change them runs,
+/// so that a paused operation can be aborted back to them whatever moved
+/// before the conflict.
+pub fn begin(repo: &git2::Repository) {
+    let Ok(refs) = crate::core::repo::snapshot_branch_refs(repo) else {
+        return;
+    };
+    JOURNAL.with(|journal| {
+        *journal.borrow_mut() = refs
+            .into_iter()
+            .map(|(name, oid)| (name, oid.to_string()))
+            .collect();
+    });
+}
+
 /// Return the path to the state file: `<git_dir>/loom/state.json`.
 pub fn state_path(git_dir: &Path) -> PathBuf {
     git_dir.join("loom").join("state.json")
@@ -77,7 +134,9 @@ pub fn state_path(git_dir: &Path) -> PathBuf {
 
 /// Save `state` to `.git/loom/state.json`.
 ///
-/// Creates `.git/loom/` if it does not exist.
+/// Creates `.git/loom/` if it does not exist. Unless `state` has its own,
+/// the branch tips to abort to are those of the operation already paused
+/// (when saving again from `loom continue`), or else those of [`begin`].
 pub fn save(git_dir: &Path, state: &LoomState) -> Result<()> {
     let path = state_path(git_dir);
     if let Some(parent) = path.parent() {
@@ -88,7 +147,17 @@ pub fn save(git_dir: &Path, state: &LoomState) -> Result<()> {
             )
         })?;
     }
-    let json = serde_json::to_string_pretty(state)?;
+    let mut value = serde_json::to_value(state)?;
+    if state.rollback.branch_refs.is_empty() {
+        let refs = match load(git_dir).ok().flatten() {
+            Some(paused) if !paused.rollback.branch_refs.is_empty() => paused.rollback.branch_refs,
+            _ => JOURNAL.with(|journal| journal.borrow().clone()),
+        };
+        if !refs.is_empty() {
+            value["rollback"]["branch_refs"] = serde_json::to_value(refs)?;
+        }
+    }
+    let json = serde_json::to_string_pretty(&value)?;
     std::fs::write(&path, json)
         .with_context(|| format!("Failed to write state file '{}'", path.display()))?;
     Ok(())
@@ -160,14 +229,14 @@ pub fn continue_cmd(workdir: &Path, git_dir: &Path) -> Result<()> {
     let state = load_required(git_dir)?;
 
     if git::rebase_is_in_progress(git_dir) {
-        match git::continue_rebase(workdir)? {
+        match crate::resolution::replay(workdir, git::continue_rebase(workdir)?)? {
             git::RebaseOutcome::Conflicted => {
                 crate::core::msg::warn(
                     "Conflicts remain — resolve them and run `loom continue` again",
                 );
                 return Ok(());
             }
-            git::RebaseOutcome::Completed => {}
+            git::RebaseOutcome::Completed => crate::core::weave::finish_retag(workdir)?,
         }
     } else if git::merge_is_in_progress(git_dir) {
         match git::continue_merge(workdir, git_dir)? {
@@ -185,9 +254,50 @@ pub fn continue_cmd(workdir: &Path, git_dir: &Path) -> Result<()> {
 
     dispatch_after_continue(workdir, &state)?;
     delete(git_dir)?;
+    if !state.refs_before.is_empty() {
+        let before = state
+            .refs_before
+            .iter()
+            .filter_map(|(name, oid)| Some((name.clone(), git2::Oid::from_str(oid).ok()?)))
+            .collect();
+        print_ref_moves(&git2::Repository::discover(workdir)?, &before);
+    }
+    Ok(())
+}
+
+/// Run `op`, then print the branches it moved. When `op` pauses on a
+/// conflict, the tips it started from go into the saved state instead, for
+/// `loom continue` to print once the operation completes.
+pub fn reporting_ref_moves(repo: &git2::Repository, op: impl FnOnce() -> Result<()>) -> Result<()> {
+    let before = crate::core::repo::snapshot_branch_refs(repo)?;
+    op()?;
+    let git_dir = repo.path();
+    match load(git_dir)? {
+        Some(mut state) => {
+            state.refs_before = before
+                .iter()
+                .map(|(name, oid)| (name.clone(), oid.to_string()))
+                .collect();
+            save(git_dir, &state)?;
+        }
+        None => print_ref_moves(repo, &before),
+    }
     Ok(())
 }
 
+/// Print one line per branch that moved since `before`: its old and new
+/// tips, and how many commits it gained or lost. Best effort: a summary
+/// that cannot be computed is left out.
+fn print_ref_moves(repo: &git2::Repository, before: &HashMap<String, git2::Oid>) {
+    let Ok(moves) = crate::core::repo::ref_moves(repo, before) else {
+        return;
+    };
+    let width = moves.iter().map(|m| m.name.len()).max().unwrap_or(0);
+    for line in moves.iter().map(|m| m.describe(width)) {
+        println!("  {}", line);
+    }
+}
+
 /// Implement `loom abort`.
 ///
 /// 1. Aborts any active rebase (`git rebase --abort` restores HEAD, branch
@@ -195,7 +305,9 @@ pub fn continue_cmd(workdir: &Path, git_dir: &Path) -> Result<()> {
 /// 2. Calls `rollback.apply_abort()` for any cleanup `git rebase --abort`
 ///    cannot do on its own (un-committing staged changes, deleting temp branches,
 ///    restoring saved patches).
-/// 3. Deletes state.
+/// 3. Puts back the branches that are still not where they were when the
+///    command started (moved before its rebase, or outside `--update-refs`).
+/// 4. Deletes state.
 pub fn abort_cmd(workdir: &Path, git_dir: &Path) -> Result<()> {
     let state = load_required(git_dir)?;
 
@@ -206,6 +318,7 @@ pub fn abort_cmd(workdir: &Path, git_dir: &Path) -> Result<()> {
     }
 
     state.rollback.apply_abort(workdir)?;
+    restore_branch_refs(workdir, &state.rollback.branch_refs)?;
     delete(git_dir)?;
 
     crate::core::msg::success(&format!(
@@ -215,6 +328,40 @@ pub fn abort_cmd(workdir: &Path, git_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Point each branch of `refs` that moved back at its recorded tip, and
+/// recreate the ones that were deleted. The checked-out branch is left to
+/// the rollback's reset; a branch git refuses to move (checked out in
+/// another worktree) is reported and skipped.
+fn restore_branch_refs(workdir: &Path, refs: &HashMap<String, String>) -> Result<()> {
+    if refs.is_empty() {
+        return Ok(());
+    }
+    let repo = git2::Repository::discover(workdir)?;
+    let current = crate::core::repo::snapshot_branch_refs(&repo)?;
+    let head = repo
+        .head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(str::to_string));
+    let mut names: Vec<&String> = refs.keys().collect();
+    names.sort();
+    for name in names {
+        let oid = &refs[name];
+        let moved = current.get(name).is_none_or(|tip| tip.to_string() != *oid);
+        if !moved || head.as_deref() == Some(name.as_str()) {
+            continue;
+        }
+        if git::branch_force_create(workdir, name, oid).is_err() {
+            crate::core::msg::warn(&format!(
+                "Could not restore branch '{}' to {}",
+                name,
+                git::short_hash(oid)
+            ));
+        }
+    }
+    Ok(())
+}
+
 /// Dispatch to the command-specific `after_continue` handler.
 fn dispatch_after_continue(workdir: &Path, state: &LoomState) -> Result<()> {
     match state.command.as_str() {
@@ -222,9 +369,10 @@ fn dispatch_after_continue(workdir: &Path, state: &LoomState) -> Result<()> {
         "commit" => crate::commit::after_continue(workdir, &state.rollback, &state.context),
         "absorb" => crate::absorb::after_continue(workdir, &state.rollback, &state.context),
         "drop" => crate::drop::after_continue(workdir, &state.context),
-        "fold" => crate::fold::after_continue(workdir, &state.context),
+        "fold" => crate::fold::after_continue(workdir, &state.rollback, &state.context),
         "swap" => crate::swap::after_continue(workdir, &state.context),
         "merge" => crate::branch::merge::after_continue(&state.context),
+        "batch" => crate::batch::after_continue(workdir, &state.context),
         other => bail!("Unknown command '{}' in loom state file", other),
     }
 }
@@ -237,6 +385,7 @@ mod tests {
     fn state_roundtrip() {
         let state = LoomState {
             command: "commit".to_string(),
+            refs_before: Default::default(),
             rollback: Rollback {
                 reset_mixed_to: "abc123".to_string(),
                 delete_branches: vec!["new-branch".to_string()],
@@ -288,6 +437,7 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let state = LoomState {
             command: "update".to_string(),
+            refs_before: Default::default(),
             rollback: Rollback::default(),
             context: serde_json::Value::Null,
         };
@@ -298,4 +448,66 @@ mod tests {
         // Second delete is a no-op
         delete(dir.path()).unwrap();
     }
+
+    #[test]
+    fn paused_operation_keeps_refs_before() {
+        let test_repo = crate::core::test_helpers::TestRepo::new();
+        let head = test_repo.head_oid();
+        let git_dir = test_repo.repo.path().to_path_buf();
+
+        reporting_ref_moves(&test_repo.repo, || {
+            test_repo.commit("Moved", "moved.txt");
+            save(
+                &git_dir,
+                &LoomState {
+                    command: "fold".to_string(),
+                    refs_before: Default::default(),
+                    rollback: Rollback::default(),
+                    context: serde_json::Value::Null,
+                },
+            )
+        })
+        .unwrap();
+
+        let state = load_required(&git_dir).unwrap();
+        let branch = test_repo.current_branch_name();
+        assert_eq!(state.refs_before.get(&branch), Some(&head.to_string()));
+    }
+
+    #[test]
+    fn abort_restores_branches_moved_since_begin() {
+        let test_repo = crate::core::test_helpers::TestRepo::new();
+        let start = test_repo.head_oid();
+        test_repo.create_branch("feature-a");
+        let workdir = test_repo.workdir();
+        let git_dir = test_repo.repo.path().to_path_buf();
+
+        begin(&test_repo.repo);
+        let moved = test_repo.commit("Moved", "moved.txt");
+        git::branch_force_create(&workdir, "feature-a", &moved.to_string()).unwrap();
+        save(
+            &git_dir,
+            &LoomState {
+                command: "fold".to_string(),
+                refs_before: Default::default(),
+                rollback: Rollback {
+                    reset_hard_to: start.to_string(),
+                    ..Default::default()
+                },
+                context: serde_json::Value::Null,
+            },
+        )
+        .unwrap();
+        let saved = load_required(&git_dir).unwrap();
+        assert_eq!(
+            saved.rollback.branch_refs.get("feature-a"),
+            Some(&start.to_string())
+        );
+
+        abort_cmd(&workdir, &git_dir).unwrap();
+
+        assert_eq!(test_repo.get_branch_target("feature-a"), start);
+        assert_eq!(test_repo.head_oid(), start);
+        assert!(load(&git_dir).unwrap().is_none());
+    }
 }
@@ -1,9 +1,12 @@
+use std::cell::Cell;
 use std::collections::HashSet;
 use std::path::Path;
 
 use anyhow::{Context, Result, bail};
 use git2::{Oid, Repository};
 
+use crate::core::ancestry;
+use crate::core::clock;
 use crate::core::msg;
 use crate::core::repo;
 use crate::git;
@@ -40,7 +43,8 @@ pub struct CommitEntry {
 /// A woven branch section in the todo file.
 #[derive(Debug, Clone)]
 pub struct BranchSection {
-    /// The reset target ("onto" or another branch label).
+    /// The reset target ("onto", another branch label, or the full OID of
+    /// the base recorded by `loom update --only`).
     pub reset_target: String,
     /// Commits in the section, oldest first.
     pub commits: Vec<CommitEntry>,
@@ -138,6 +142,96 @@ impl Weave {
         out
     }
 
+    /// Serialize the weave, then parse the todo back and check it describes
+    /// the same structure: pick order, labels, merges, and where each ref
+    /// lands. A mismatch is a serializer bug, reported before git rewrites
+    /// any history.
+    pub fn checked_todo(&self) -> Result<String> {
+        let todo = self.to_todo();
+        let expected = self.todo_blocks();
+        if let Err(e) =
+            parse_todo_blocks(&todo).and_then(|actual| compare_todo_blocks(&expected, &actual))
+        {
+            crate::trace::annotate("invalid todo", &todo);
+            return Err(e.context(
+                "The generated rebase todo does not match the intended history — nothing was changed\n\
+                 This is a loom bug: please report it with the output of `loom bugreport`",
+            ));
+        }
+        Ok(todo)
+    }
+
+    /// The structure `to_todo` is meant to produce, one block per `reset`.
+    fn todo_blocks(&self) -> Vec<TodoBlock> {
+        let mut blocks = Vec::new();
+        for section in &self.branch_sections {
+            let steps = section
+                .commits
+                .iter()
+                .map(|c| format!("{} {}", c.command.as_str(), c.short_hash))
+                .collect();
+            let fixups: Vec<bool> = section
+                .commits
+                .iter()
+                .map(|c| c.command == Command::Fixup)
+                .collect();
+            let mut refs = Vec::new();
+            for (i, commit) in section.commits.iter().enumerate() {
+                for r in &commit.update_refs {
+                    refs.push((r.clone(), Some(ref_anchor(&fixups, i))));
+                }
+            }
+            let tip = section.commits.len().checked_sub(1);
+            for name in &section.branch_names {
+                refs.push((name.clone(), tip));
+            }
+            refs.sort();
+            blocks.push(TodoBlock {
+                reset: section.reset_target.clone(),
+                steps,
+                refs,
+                label: Some(section.label.clone()),
+            });
+        }
+
+        let mut steps = Vec::new();
+        let mut fixups = Vec::new();
+        for entry in &self.integration_line {
+            match entry {
+                IntegrationEntry::Pick(commit) => {
+                    steps.push(format!("{} {}", commit.command.as_str(), commit.short_hash));
+                    fixups.push(commit.command == Command::Fixup);
+                }
+                IntegrationEntry::Merge {
+                    original_oid,
+                    label,
+                } => {
+                    steps.push(merge_step(
+                        original_oid.map(|oid| oid.to_string()).as_deref(),
+                        label,
+                    ));
+                    fixups.push(false);
+                }
+            }
+        }
+        let mut refs = Vec::new();
+        for (i, entry) in self.integration_line.iter().enumerate() {
+            if let IntegrationEntry::Pick(commit) = entry {
+                for r in &commit.update_refs {
+                    refs.push((r.clone(), Some(ref_anchor(&fixups, i))));
+                }
+            }
+        }
+        refs.sort();
+        blocks.push(TodoBlock {
+            reset: "onto".to_string(),
+            steps,
+            refs,
+            label: None,
+        });
+        blocks
+    }
+
     /// Build the weave from the current repository state.
     ///
     /// Convenience wrapper that calls `gather_repo_info` internally.
@@ -171,6 +265,7 @@ impl Weave {
             map
         };
 
+        let branch_bases = repo::branch_bases(repo)?;
         let mut branch_sections = Vec::new();
         let mut integration_line = Vec::new();
 
@@ -189,9 +284,18 @@ impl Weave {
                         .cloned()
                         .unwrap_or_default();
 
+                    // A branch restacked by `loom update --only` starts from
+                    // its own base instead of the weave's
+                    let own_base = branch_names_at_tip
+                        .iter()
+                        .find_map(|name| branch_bases.get(name).copied());
+
                     // Walk the second parent backward to collect branch commits
-                    let branch_commits =
-                        walk_branch_commits(repo, merge_parent_oid, merge_base_oid)?;
+                    let branch_commits = walk_branch_commits(
+                        repo,
+                        merge_parent_oid,
+                        own_base.unwrap_or(merge_base_oid),
+                    )?;
 
                     if !branch_commits.is_empty() || !branch_names_at_tip.is_empty() {
                         // Determine the section label (use the first branch name, or generate one)
@@ -229,7 +333,8 @@ impl Weave {
                             .collect();
 
                         let section = BranchSection {
-                            reset_target: "onto".to_string(),
+                            reset_target: own_base
+                                .map_or_else(|| "onto".to_string(), |oid| oid.to_string()),
                             commits: todo_commits,
                             label: label.clone(),
                             branch_names: branch_names_at_tip.clone(),
@@ -283,9 +388,11 @@ impl Weave {
     /// Remove branch-section commits that are already in the new upstream
     /// (merged or cherry-picked). Empty sections and their merges are removed.
     ///
-    /// Uses two strategies:
+    /// Uses three strategies:
     /// 1. Exact OID ancestry (commit was directly merged)
     /// 2. `git cherry` for cherry-pick detection (only when candidates remain)
+    /// 3. Squash-merge detection for sections with commits left: the
+    ///    section's cumulative diff is already in the upstream tree
     pub fn filter_upstream_commits(
         &mut self,
         repo: &Repository,
@@ -323,6 +430,22 @@ impl Weave {
             }
         }
 
+        // Strategy 3: a squash merge has no per-commit patch-id match, so
+        // compare each remaining section as a whole against upstream's tree
+        let dropped: HashSet<Oid> = to_drop.iter().copied().collect();
+        for section in &self.branch_sections {
+            if section.commits.iter().all(|c| dropped.contains(&c.oid)) {
+                continue;
+            }
+            let (Some(first), Some(tip)) = (section.commits.first(), section.commits.last()) else {
+                continue;
+            };
+            let base = repo.find_commit(first.oid)?.parent_id(0)?;
+            if is_squash_merged(repo, base, tip.oid, new_upstream_oid)? {
+                to_drop.extend(section.commits.iter().map(|c| c.oid));
+            }
+        }
+
         for oid in to_drop {
             self.drop_commit(oid);
         }
@@ -584,6 +707,34 @@ impl Weave {
         self.set_command(oid, Command::Edit);
     }
 
+    /// Pick `new_oid` in place of `oid`.
+    ///
+    /// `new_oid` must have the same parents and tree as `oid` (e.g. a copy with
+    /// a rewritten message), or parents that were replaced the same way, so
+    /// replaying it yields the same content.
+    pub fn replace_commit(&mut self, oid: Oid, new_oid: Oid) {
+        let replace = |commit: &mut CommitEntry| {
+            commit.oid = new_oid;
+            commit.short_hash = git::short_hash(&new_oid.to_string()).to_string();
+        };
+
+        for section in &mut self.branch_sections {
+            if let Some(commit) = section.commits.iter_mut().find(|c| c.oid == oid) {
+                replace(commit);
+                return;
+            }
+        }
+
+        for entry in &mut self.integration_line {
+            if let IntegrationEntry::Pick(commit) = entry
+                && commit.oid == oid
+            {
+                replace(commit);
+                return;
+            }
+        }
+    }
+
     /// Add a new branch section to the graph.
     pub fn add_branch_section(
         &mut self,
@@ -883,6 +1034,151 @@ fn flush_refs(out: &mut String, refs: &[String]) {
     }
 }
 
+/// One `reset` block of a rebase todo, reduced to what matters for the
+/// rewritten history.
+#[derive(Debug, PartialEq, Eq)]
+struct TodoBlock {
+    reset: String,
+    /// `pick <hash>`, `fixup <hash>`, `merge -C <hash> <label>`, ... in order.
+    steps: Vec<String>,
+    /// Each updated ref with the index of the step it follows (None before
+    /// the first step), sorted.
+    refs: Vec<(String, Option<usize>)>,
+    /// The label closing a branch section (None for the integration line).
+    label: Option<String>,
+}
+
+/// The step a ref attached to step `i` follows: the end of the fixup chain
+/// squashed into it, so the ref points at the squashed commit.
+fn ref_anchor(fixups: &[bool], i: usize) -> usize {
+    let mut anchor = i;
+    while fixups.get(anchor + 1) == Some(&true) {
+        anchor += 1;
+    }
+    anchor
+}
+
+fn merge_step(original_oid: Option<&str>, label: &str) -> String {
+    match original_oid {
+        Some(oid) => format!("merge -C {} {}", git::short_hash(oid), label),
+        None => format!("merge {}", label),
+    }
+}
+
+/// Parse a todo written by `Weave::to_todo` back into blocks, checking that
+/// every label is defined once before use and every ref is updated once.
+fn parse_todo_blocks(todo: &str) -> Result<Vec<TodoBlock>> {
+    let mut lines = todo
+        .lines()
+        .map(|l| l.split(" # ").next().unwrap_or("").trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#'));
+    if lines.next() != Some("label onto") {
+        bail!("the todo does not start with `label onto`");
+    }
+
+    let mut labels: HashSet<String> = HashSet::from(["onto".to_string()]);
+    let mut updated: HashSet<String> = HashSet::new();
+    let mut picked: HashSet<String> = HashSet::new();
+    let mut blocks: Vec<TodoBlock> = Vec::new();
+    for line in lines {
+        let (op, rest) = line.split_once(' ').unwrap_or((line, ""));
+        if op == "reset" {
+            // A full OID is the base of a section restacked by `update --only`
+            let is_oid = rest.len() == 40 && Oid::from_str(rest).is_ok();
+            if !labels.contains(rest) && !is_oid {
+                bail!("`reset {}` uses a label that is not defined yet", rest);
+            }
+            blocks.push(TodoBlock {
+                reset: rest.to_string(),
+                steps: Vec::new(),
+                refs: Vec::new(),
+                label: None,
+            });
+            continue;
+        }
+        let Some(block) = blocks.last_mut() else {
+            bail!("`{}` comes before the first `reset`", line);
+        };
+        match op {
+            "pick" | "edit" | "fixup" => {
+                if !picked.insert(rest.to_string()) {
+                    bail!("commit {} is picked twice", rest);
+                }
+                block.steps.push(line.to_string());
+            }
+            "merge" => {
+                let label = rest.rsplit(' ').next().unwrap_or(rest);
+                if !labels.contains(label) {
+                    bail!("`{}` uses a label that is not defined yet", line);
+                }
+                block.steps.push(line.to_string());
+            }
+            "update-ref" => {
+                let name = rest.strip_prefix("refs/heads/").unwrap_or(rest);
+                if !updated.insert(name.to_string()) {
+                    bail!("`{}` is updated twice", rest);
+                }
+                block
+                    .refs
+                    .push((name.to_string(), block.steps.len().checked_sub(1)));
+            }
+            "label" => {
+                if block.label.is_some() || !labels.insert(rest.to_string()) {
+                    bail!("label `{}` is defined twice", rest);
+                }
+                block.label = Some(rest.to_string());
+            }
+            _ => bail!("unexpected todo line `{}`", line),
+        }
+    }
+    for block in &mut blocks {
+        block.refs.sort();
+    }
+    Ok(blocks)
+}
+
+/// Describe the first difference between the intended and parsed blocks.
+fn compare_todo_blocks(expected: &[TodoBlock], actual: &[TodoBlock]) -> Result<()> {
+    if expected.len() != actual.len() {
+        bail!(
+            "expected {} `reset` blocks, found {}",
+            expected.len(),
+            actual.len()
+        );
+    }
+    for (i, (want, got)) in expected.iter().zip(actual).enumerate() {
+        let block = format!("block {} (`reset {}`)", i + 1, want.reset);
+        if want.reset != got.reset {
+            bail!("{}: resets to `{}`", block, got.reset);
+        }
+        if want.steps != got.steps {
+            bail!(
+                "{}: expected steps [{}], found [{}]",
+                block,
+                want.steps.join(", "),
+                got.steps.join(", ")
+            );
+        }
+        if want.refs != got.refs {
+            bail!(
+                "{}: expected ref updates {:?}, found {:?}",
+                block,
+                want.refs,
+                got.refs
+            );
+        }
+        if want.label != got.label {
+            bail!(
+                "{}: expected label {:?}, found {:?}",
+                block,
+                want.label,
+                got.label
+            );
+        }
+    }
+    Ok(())
+}
+
 /// An entry from the first-parent walk of the integration branch.
 #[derive(Debug)]
 struct FirstParentEntry {
@@ -1002,7 +1298,7 @@ fn walk_branch_commits(repo: &Repository, tip: Oid, stop: Oid) -> Result<Vec<Bra
     let actual_stop = if tip == stop {
         stop
     } else {
-        repo.merge_base(tip, stop).unwrap_or(stop)
+        ancestry::merge_base(repo, tip, stop).unwrap_or(stop)
     };
 
     let mut entries = Vec::new();
@@ -1055,10 +1351,13 @@ struct BranchCommitEntry {
 /// integration branches. Falls back to a minimal linear todo for non-integration
 /// repos (no upstream tracking).
 pub fn start_edit_rebase(repo: &Repository, workdir: &Path, commit_oid: Oid) -> Result<()> {
-    // Try Weave::from_repo first (for integration branches)
-    if let Ok(mut graph) = Weave::from_repo(repo) {
+    // Try Weave::from_repo first (for integration branches). The base itself
+    // (e.g. the root commit of a local-only repository) is not in the weave.
+    if let Ok(mut graph) = Weave::from_repo(repo)
+        && repo.graph_descendant_of(commit_oid, graph.base_oid)?
+    {
         graph.edit_commit(commit_oid);
-        let todo = graph.to_todo();
+        let todo = graph.checked_todo()?;
         return run_rebase_or_abort(workdir, Some(&graph.base_oid.to_string()), &todo);
     }
 
@@ -1132,6 +1431,368 @@ fn build_and_run_linear_edit(repo: &Repository, workdir: &Path, commit_oid: Oid)
 /// Outcome of a weave-based rebase.
 pub use crate::git::RebaseOutcome;
 
+/// How much of a todo recreates existing commits as they are, found by
+/// replaying it step by step against the repository.
+struct ReusedPrefix {
+    /// Number of todo lines, from the top, that change nothing.
+    lines: usize,
+    /// Commits picked or merged by those lines.
+    commits: usize,
+    /// Where the replay stands after them.
+    current: Option<Oid>,
+    /// Labels defined by those lines, `onto` excluded.
+    labels: Vec<(String, Oid)>,
+    /// Whether the whole todo is reused and ends at HEAD.
+    complete: bool,
+}
+
+/// Replay `todo` onto `upstream` as long as every `pick` and `merge -C`
+/// reuses its commit on the parents it already has and every `update-ref`
+/// names a branch already at that commit. Anything else (fixups, edits, new
+/// merges, moved commits) ends the prefix.
+fn reused_prefix(repo: &Repository, upstream: &str, todo: &str) -> Option<ReusedPrefix> {
+    let commit = |rev: &str| repo.revparse_single(rev).ok()?.peel_to_commit().ok();
+    let onto = commit(upstream)?.id();
+    let mut prefix = ReusedPrefix {
+        lines: 0,
+        commits: 0,
+        current: None,
+        labels: Vec::new(),
+        complete: false,
+    };
+    let label = |labels: &[(String, Oid)], name: &str| {
+        if name == "onto" {
+            return Some(onto);
+        }
+        // A section restacked by `loom update --only` resets to its own base
+        if name.len() == 40
+            && let Ok(oid) = Oid::from_str(name)
+        {
+            return Some(oid);
+        }
+        labels
+            .iter()
+            .rev()
+            .find(|(l, _)| l == name)
+            .map(|(_, oid)| *oid)
+    };
+    for line in todo.lines() {
+        let step = line.split(" # ").next().unwrap_or("").trim();
+        let (op, rest) = step.split_once(' ').unwrap_or((step, ""));
+        let reused = match op {
+            "" => true,
+            _ if op.starts_with('#') => true,
+            "label" if rest == "onto" => true,
+            "label" => match prefix.current {
+                Some(oid) => {
+                    prefix.labels.push((rest.to_string(), oid));
+                    true
+                }
+                None => false,
+            },
+            "reset" => match label(&prefix.labels, rest) {
+                Some(oid) => {
+                    prefix.current = Some(oid);
+                    true
+                }
+                None => false,
+            },
+            "pick" => match commit(rest) {
+                Some(c) if c.parent_count() == 1 && c.parent_id(0).ok() == prefix.current => {
+                    prefix.current = Some(c.id());
+                    prefix.commits += 1;
+                    true
+                }
+                _ => false,
+            },
+            "merge" => {
+                let merged = rest
+                    .strip_prefix("-C ")
+                    .and_then(|rest| rest.split_once(' '))
+                    .and_then(|(hash, name)| Some((commit(hash)?, label(&prefix.labels, name)?)));
+                match (merged, prefix.current) {
+                    (Some((c, other)), Some(current))
+                        if c.parent_ids().collect::<Vec<_>>() == [current, other] =>
+                    {
+                        prefix.current = Some(c.id());
+                        prefix.commits += 1;
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            "update-ref" => {
+                prefix.current.is_some() && repo.refname_to_id(rest).ok() == prefix.current
+            }
+            _ => false,
+        };
+        if !reused {
+            return Some(prefix);
+        }
+        prefix.lines += 1;
+    }
+    prefix.complete = prefix.current.is_some() && prefix.current == repo.head().ok()?.target();
+    Some(prefix)
+}
+
+/// Whether replaying `todo` onto `upstream` would leave history untouched.
+fn is_noop_todo(workdir: &Path, upstream: &str, todo: &str) -> bool {
+    Repository::discover(workdir)
+        .ok()
+        .and_then(|repo| reused_prefix(&repo, upstream, todo))
+        .is_some_and(|prefix| prefix.complete)
+}
+
+/// Rewrite `todo` to start at its first change: the unchanged prefix is
+/// dropped, the labels it defined are pointed at the existing commits, and
+/// the rebase starts from where the prefix ends. Returns the new upstream and
+/// todo, or `None` if the todo changes something from its first commit.
+///
+/// Git already fast-forwards over unchanged picks, but starting later makes
+/// it explicit: nothing before the first change can be rewritten, so
+/// branches untouched by a command never need a force-push.
+fn partial_todo(workdir: &Path, upstream: &str, todo: &str) -> Option<(String, String)> {
+    let repo = Repository::discover(workdir).ok()?;
+    let prefix = reused_prefix(&repo, upstream, todo)?;
+    let current = prefix.current?;
+    if prefix.commits == 0 || prefix.complete {
+        return None;
+    }
+    let onto = repo
+        .revparse_single(upstream)
+        .ok()?
+        .peel_to_commit()
+        .ok()?
+        .id();
+
+    let mut out = format!("reset {}\nlabel onto\n", onto);
+    for (name, oid) in &prefix.labels {
+        out.push_str(&format!("reset {}\nlabel {}\n", oid, name));
+    }
+    out.push_str(&format!("reset {}\n", current));
+    for line in todo.lines().skip(prefix.lines) {
+        out.push_str(line);
+        out.push('\n');
+    }
+    Some((current.to_string(), out))
+}
+
+thread_local! {
+    /// Set by `--retag`: move the tags of rewritten commits to their new
+    /// versions instead of refusing the rewrite.
+    static RETAG: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Allow rewrites to move tags along (see [`retag_todo`]).
+pub fn set_retag(retag: bool) {
+    RETAG.with(|cell| cell.set(retag));
+}
+
+thread_local! {
+    /// Whether rebases may stash uncommitted changes: `Some(false)` with
+    /// `--no-autostash`, `Some(true)` once the `loom.autostash = prompt`
+    /// question was answered yes. `None` follows `loom.autostash`.
+    static AUTOSTASH: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Forbid rebases to stash uncommitted changes, whatever `loom.autostash`
+/// says (see [`check_autostash`]).
+pub fn set_no_autostash(no_autostash: bool) {
+    AUTOSTASH.with(|cell| cell.set(no_autostash.then_some(false)));
+}
+
+/// Check that the rebase about to run may stash the uncommitted changes to
+/// tracked files, as `loom.autostash` and `--no-autostash` decide. Untracked
+/// files are not stashed, so they don't count.
+///
+/// A refused rewrite hasn't started: the pending loom operation, if any, is
+/// rolled back so the repository is left as it was.
+pub fn check_autostash(workdir: &Path) -> Result<()> {
+    let repo = Repository::discover(workdir)?;
+    let mut opts = git2::StatusOptions::new();
+    opts.include_untracked(false).exclude_submodules(true);
+    if repo.statuses(Some(&mut opts))?.is_empty() {
+        return Ok(());
+    }
+
+    let mut input_required = None;
+    let hint = match AUTOSTASH.with(Cell::get) {
+        Some(true) => return Ok(()),
+        Some(false) => "Commit or stash them first, or run again without `--no-autostash`",
+        None => match repo::autostash_policy(&repo) {
+            repo::AutostashPolicy::Always => return Ok(()),
+            repo::AutostashPolicy::Prompt => {
+                let stash =
+                    match msg::confirm("Stash the uncommitted changes while history is rewritten?")
+                    {
+                        Ok(answer) => answer,
+                        Err(e) if msg::is_input_required(&e) => {
+                            input_required = Some(e);
+                            false
+                        }
+                        Err(_) => false,
+                    };
+                if stash {
+                    AUTOSTASH.with(|cell| cell.set(Some(true)));
+                    return Ok(());
+                }
+                "Commit or stash them first"
+            }
+            repo::AutostashPolicy::Never => {
+                "Commit or stash them first (`loom.autostash` is `never`)"
+            }
+        },
+    };
+    let git_dir = repo.path().to_path_buf();
+    if let Ok(Some(state)) = crate::core::transaction::load(&git_dir) {
+        state.rollback.apply_abort(workdir)?;
+        crate::core::transaction::delete(&git_dir)?;
+    }
+    if let Some(err) = input_required {
+        return Err(err);
+    }
+    bail!("The working tree has uncommitted changes\n{}", hint);
+}
+
+/// Refs holding the rewritten commits of tags until the rebase completes.
+pub(crate) const RETAG_PREFIX: &str = "refs/loom/retag/";
+
+/// Check the tags on the commits `todo` rewrites: those after `upstream` up
+/// to HEAD. A rewrite would leave them on the old commits, so it is refused
+/// unless `--retag` was given, in which case an `update-ref` to
+/// `refs/loom/retag/<tag>` follows each tagged commit and [`finish_retag`]
+/// moves the tags once the rebase completes.
+///
+/// A refused rewrite hasn't started: the pending loom operation, if any, is
+/// rolled back so the repository is left as it was.
+fn retag_todo(workdir: &Path, upstream: Option<&str>, todo: &str) -> Result<String> {
+    let repo = Repository::discover(workdir)?;
+    let tags = repo::tags_by_commit(&repo)?;
+    if tags.is_empty() {
+        return Ok(todo.to_string());
+    }
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+    if let Some(upstream) = upstream {
+        walk.hide(repo.revparse_single(upstream)?.peel_to_commit()?.id())?;
+    }
+    let mut at_risk: Vec<(Oid, &Vec<String>)> = Vec::new();
+    for oid in walk {
+        let oid = oid?;
+        if let Some(names) = tags.get(&oid) {
+            at_risk.push((oid, names));
+        }
+    }
+    if at_risk.is_empty() {
+        return Ok(todo.to_string());
+    }
+
+    if !RETAG.with(Cell::get) {
+        let mut names: Vec<&str> = at_risk
+            .iter()
+            .flat_map(|(_, names)| names.iter().map(String::as_str))
+            .collect();
+        names.sort();
+        let git_dir = repo.path().to_path_buf();
+        if let Ok(Some(state)) = crate::core::transaction::load(&git_dir) {
+            state.rollback.apply_abort(workdir)?;
+            crate::core::transaction::delete(&git_dir)?;
+        }
+        bail!(
+            "Rewriting history would leave tag `{}` on the old commits\n\
+             Run again with `--retag` to move the tags to the rewritten commits",
+            names.join("`, `")
+        );
+    }
+
+    let mut out = String::new();
+    let mut pending: Vec<&str> = Vec::new();
+    let mut moved: HashSet<Oid> = HashSet::new();
+    for line in todo.lines() {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let is_fixup = matches!(words.first(), Some(&"fixup") | Some(&"squash"));
+        if !is_fixup {
+            for name in pending.drain(..) {
+                out.push_str(&format!("update-ref {}{}\n", RETAG_PREFIX, name));
+            }
+        }
+        out.push_str(line);
+        out.push('\n');
+
+        let hash = match words.as_slice() {
+            ["pick" | "edit", hash, ..] => Some(*hash),
+            ["merge", "-C", hash, ..] => Some(*hash),
+            _ => None,
+        };
+        if let Some(hash) = hash
+            && let Ok(oid) = repo.revparse_single(hash).map(|o| o.id())
+            && let Some((_, names)) = at_risk.iter().find(|(o, _)| *o == oid)
+        {
+            moved.insert(oid);
+            pending.extend(names.iter().map(String::as_str));
+        }
+    }
+    for name in pending {
+        out.push_str(&format!("update-ref {}{}\n", RETAG_PREFIX, name));
+    }
+    for (oid, names) in &at_risk {
+        if !moved.contains(oid) {
+            msg::warn(&format!(
+                "Tag `{}` points at a commit this rewrite removes: left in place",
+                names.join("`, `")
+            ));
+        }
+    }
+    Ok(out)
+}
+
+/// Move each tag recorded under `refs/loom/retag/` to its rewritten commit.
+/// Annotated tags are recreated with their tagger and message.
+pub fn finish_retag(workdir: &Path) -> Result<()> {
+    let repo = Repository::discover(workdir)?;
+    let mut moved = Vec::new();
+    for reference in repo.references_glob(&format!("{}*", RETAG_PREFIX))? {
+        let mut reference = reference?;
+        let Some(name) = reference
+            .name()
+            .and_then(|n| n.strip_prefix(RETAG_PREFIX))
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        let target = reference.peel_to_commit()?;
+        let tag_ref = format!("refs/tags/{}", name);
+        match repo.find_reference(&tag_ref)?.peel_to_tag() {
+            Ok(tag) => {
+                let tagger = tag
+                    .tagger()
+                    .map(|t| t.to_owned())
+                    .map_or_else(|| clock::committer(&repo), Ok)?;
+                repo.tag(
+                    &name,
+                    target.as_object(),
+                    &tagger,
+                    tag.message().unwrap_or(""),
+                    true,
+                )?;
+            }
+            Err(_) => {
+                repo.reference(&tag_ref, target.id(), true, "loom: retag")?;
+            }
+        }
+        reference.delete()?;
+        moved.push(name);
+    }
+    if !moved.is_empty() {
+        moved.sort();
+        msg::success(&format!(
+            "Moved tag `{}` to the rewritten commits",
+            moved.join("`, `")
+        ));
+    }
+    Ok(())
+}
+
 /// Execute a weave-based rebase, aborting automatically on conflict.
 ///
 /// This is the legacy wrapper for out-of-scope callers (`reword`, `split`,
@@ -1160,12 +1821,67 @@ pub fn run_rebase_or_abort(
 /// `<upstream>` argument to `git rebase`, NOT with a `^` suffix. For root
 /// commits, pass `None` to use `--root`.
 ///
+/// A todo that would recreate every commit unchanged is not run at all: the
+/// user is told and `Completed` is returned, leaving all OIDs as they were.
+/// Otherwise the rebase starts at the first step that changes something, so
+/// the commits before it keep their OIDs.
+///
+/// Conflicts that saved resolutions cover are resolved and the rebase
+/// continued (see [`crate::resolution::replay`]).
+///
 /// Returns `RebaseOutcome::Completed` on success, `RebaseOutcome::Conflicted`
 /// if the rebase stopped due to a conflict. Does NOT abort on conflict.
 pub fn run_rebase(
     workdir: &Path,
     upstream: Option<&str>,
     todo_content: &str,
+) -> Result<RebaseOutcome> {
+    // A todo that would recreate every commit as it is only costs time and
+    // churns the reflog: skip it.
+    if let Some(upstream) = upstream
+        && is_noop_todo(workdir, upstream, todo_content)
+    {
+        crate::trace::annotate("skipped no-op todo", todo_content);
+        msg::warn("History already has the requested shape — nothing to rewrite");
+        return Ok(RebaseOutcome::Completed);
+    }
+    let partial = upstream.and_then(|upstream| partial_todo(workdir, upstream, todo_content));
+    let (upstream, todo) = match &partial {
+        Some((start, todo)) => {
+            crate::trace::annotate("partial rebase", todo);
+            (Some(start.as_str()), todo.as_str())
+        }
+        None => (upstream, todo_content),
+    };
+    check_autostash(workdir)?;
+    let todo = retag_todo(workdir, upstream, todo)? + &record_rewritten_step()?;
+    let outcome = crate::resolution::replay(workdir, run_git_rebase(workdir, upstream, &todo)?)?;
+    if matches!(outcome, RebaseOutcome::Completed) {
+        finish_retag(workdir)?;
+    }
+    Ok(outcome)
+}
+
+/// The last step of every todo: record the old→new OIDs of the rebase in
+/// the oplog (see [`crate::core::oplog`]), while git still has them. Runs
+/// on `git rebase --continue` too, so resumed operations are recorded. Its
+/// failure must never stop the rebase.
+fn record_rewritten_step() -> Result<String> {
+    let exe = git::loom_exe_path()?
+        .display()
+        .to_string()
+        .replace('\\', "/");
+    Ok(format!(
+        "exec {} internal-record-rewritten || true\n",
+        shell_escape::unix::escape(exe.into())
+    ))
+}
+
+/// Run `git rebase` with the pre-generated todo, even if it changes nothing.
+fn run_git_rebase(
+    workdir: &Path,
+    upstream: Option<&str>,
+    todo_content: &str,
 ) -> Result<RebaseOutcome> {
     use std::io::Write;
     use std::process::Command;
@@ -1199,23 +1915,34 @@ pub fn run_rebase(
     );
 
     let mut cmd = Command::new("git");
-    cmd.current_dir(workdir)
-        .args([
-            "rebase",
-            "--interactive",
-            "--autostash",
-            "--keep-empty",
-            "--empty=drop",
-            "--no-autosquash",
-            "--rebase-merges",
-            "--update-refs",
-        ])
-        .env("GIT_SEQUENCE_EDITOR", sequence_editor)
-        // Suppress editor for new merge commits (those without -C in the todo).
-        // `true` is a no-op that leaves the default "Merge branch '...'" message intact.
-        // This only affects the rebase process — not the user's shell when rebase
-        // pauses at an `edit` command.
-        .env("GIT_EDITOR", "true");
+    cmd.current_dir(workdir);
+    // Notes follow the rebased commits, as with `notes.rewriteRef`.
+    if let Ok(repo) = Repository::discover(workdir) {
+        for notes_ref in repo::notes_rewrite_refs(&repo) {
+            cmd.args(["-c", &format!("notes.rewriteRef={}", notes_ref)]);
+        }
+    }
+    if git::no_verify() {
+        // `git rebase --no-verify` only skips pre-rebase; the merge commits
+        // the sequencer creates would still run commit-msg.
+        cmd.args(["-c", "core.hooksPath=.git/no-hooks"]);
+    }
+    cmd.args([
+        "rebase",
+        "--interactive",
+        "--autostash",
+        "--keep-empty",
+        "--empty=drop",
+        "--no-autosquash",
+        "--rebase-merges",
+        "--update-refs",
+    ])
+    .env("GIT_SEQUENCE_EDITOR", sequence_editor)
+    // Suppress editor for new merge commits (those without -C in the todo).
+    // `true` is a no-op that leaves the default "Merge branch '...'" message intact.
+    // This only affects the rebase process — not the user's shell when rebase
+    // pauses at an `edit` command.
+    .env("GIT_EDITOR", "true");
 
     match upstream {
         Some(oid) => {
@@ -1302,6 +2029,28 @@ fn cherry_pick_equivalents(workdir: &Path, upstream: &Oid, base: &Oid) -> Option
     )
 }
 
+/// Whether the changes from `base` to `tip` are already in `upstream`, as
+/// after a squash merge: replaying them onto upstream's tree (a three-way
+/// merge with `base` as ancestor) leaves that tree unchanged. A range with
+/// no changes of its own never counts as merged.
+pub fn is_squash_merged(repo: &Repository, base: Oid, tip: Oid, upstream: Oid) -> Result<bool> {
+    let base_tree = repo.find_commit(base)?.tree()?;
+    let tip_tree = repo.find_commit(tip)?.tree()?;
+    let upstream_tree = repo.find_commit(upstream)?.tree()?;
+    if base_tree.id() == tip_tree.id() {
+        return Ok(false);
+    }
+    let mut merged = repo.merge_trees(&base_tree, &upstream_tree, &tip_tree, None)?;
+    if merged.has_conflicts() {
+        return Ok(false);
+    }
+    Ok(merged.write_tree_to(repo)? == upstream_tree.id())
+}
+
 #[cfg(test)]
 #[path = "weave_test.rs"]
 mod tests;
+
+#[cfg(test)]
+#[path = "weave_prop_test.rs"]
+mod prop_tests;
@@ -0,0 +1,210 @@
+//! Property tests for the todo serializer: random integration topologies are
+//! built in a real repository, serialized, replayed by git, and read back.
+//!
+//! Every case is derived from a seed, so a failure can be replayed:
+//! `LOOM_WEAVE_SEED=<seed> LOOM_WEAVE_CASES=1 cargo test prop_tests`.
+//! Raise `LOOM_WEAVE_CASES` to fuzz longer.
+
+use super::*;
+use crate::core::test_helpers::TestRepo;
+
+const DEFAULT_CASES: u64 = 16;
+
+/// The structure of a weave without OIDs: commits are named by message, so
+/// a graph read back after a rebase compares equal to the one serialized.
+fn shape(graph: &Weave) -> Vec<String> {
+    let commit_line = |c: &CommitEntry| {
+        let mut refs = c.update_refs.clone();
+        refs.sort();
+        if refs.is_empty() {
+            c.message.clone()
+        } else {
+            format!("{} [{}]", c.message, refs.join(", "))
+        }
+    };
+    let mut out = Vec::new();
+    for section in &graph.branch_sections {
+        out.push(format!("reset {}", section.reset_target));
+        out.extend(
+            section
+                .commits
+                .iter()
+                .filter(|c| c.command != Command::Fixup)
+                .map(commit_line),
+        );
+        let mut names = section.branch_names.clone();
+        names.sort();
+        out.push(format!("label {} [{}]", section.label, names.join(", ")));
+    }
+    out.push("reset onto".to_string());
+    for entry in &graph.integration_line {
+        match entry {
+            IntegrationEntry::Pick(c) if c.command == Command::Fixup => {}
+            IntegrationEntry::Pick(c) => out.push(commit_line(c)),
+            IntegrationEntry::Merge { label, .. } => out.push(format!("merge {}", label)),
+        }
+    }
+    out
+}
+
+/// Build a random integration branch: independent and stacked sections,
+/// co-located branch names, loose commits, and refs on loose commits.
+/// Every commit adds its own file, so no replay can conflict.
+fn random_topology(rng: &mut fastrand::Rng) -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let base = test_repo
+        .find_remote_branch_target("origin/main")
+        .to_string();
+    let mut n = 0;
+    let mut commit = |test_repo: &TestRepo| {
+        n += 1;
+        test_repo.commit(&format!("C{}", n), &format!("f{}.txt", n))
+    };
+
+    for s in 0..rng.usize(0..=3) {
+        for _ in 0..rng.usize(0..=1) {
+            let oid = commit(&test_repo);
+            if rng.u8(..) < 48 {
+                test_repo.create_branch_at_commit(&format!("wip-{}", oid), oid);
+            }
+        }
+
+        let layers = if rng.u8(..) < 80 { 2 } else { 1 };
+        let mut at = base.clone();
+        let mut top = String::new();
+        for l in 0..layers {
+            let name = format!("s{}-l{}", s, l);
+            test_repo.create_branch_at(&name, &at);
+            test_repo.switch_branch(&name);
+            for _ in 0..rng.usize(1..=3) {
+                commit(&test_repo);
+            }
+            at = test_repo.head_oid().to_string();
+            if rng.u8(..) < 48 {
+                test_repo.create_branch_at(&format!("{}-alias", name), &at);
+            }
+            top = name;
+        }
+        test_repo.switch_branch("integration");
+        test_repo.merge_no_ff(&top);
+    }
+
+    for _ in 0..rng.usize(0..=2) {
+        let oid = commit(&test_repo);
+        if rng.u8(..) < 48 {
+            test_repo.create_branch_at_commit(&format!("wip-{}", oid), oid);
+        }
+    }
+    test_repo
+}
+
+/// A random `(source, target)` fixup within one block, with the target
+/// first and no ref anywhere in between: squashing then moves no ref.
+fn random_fixup(rng: &mut fastrand::Rng, graph: &Weave) -> Option<(Oid, Oid)> {
+    let mut blocks: Vec<Vec<&CommitEntry>> = graph
+        .branch_sections
+        .iter()
+        .map(|s| s.commits.iter().collect())
+        .collect();
+    blocks.push(
+        graph
+            .integration_line
+            .iter()
+            .filter_map(|e| match e {
+                IntegrationEntry::Pick(c) => Some(c),
+                IntegrationEntry::Merge { .. } => None,
+            })
+            .collect(),
+    );
+
+    let mut candidates = Vec::new();
+    for block in &blocks {
+        for t in 0..block.len() {
+            for s in t + 1..block.len() {
+                if block[t..=s].iter().all(|c| c.update_refs.is_empty()) {
+                    candidates.push((block[s].oid, block[t].oid));
+                }
+            }
+        }
+    }
+    (!candidates.is_empty()).then(|| candidates[rng.usize(..candidates.len())])
+}
+
+fn check_case(seed: u64) {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let test_repo = random_topology(&mut rng);
+    let workdir = test_repo.workdir();
+
+    let mut graph = Weave::from_repo(&test_repo.repo)
+        .unwrap_or_else(|e| panic!("seed {}: from_repo failed: {:#}", seed, e));
+    let mut fixup = false;
+    if rng.bool()
+        && let Some((source, target)) = random_fixup(&mut rng, &graph)
+    {
+        graph.fixup_commit(source, target).unwrap();
+        fixup = true;
+    }
+    let expected = shape(&graph);
+
+    let todo = graph
+        .checked_todo()
+        .unwrap_or_else(|e| panic!("seed {}: {:#}", seed, e));
+    // An unchanged weave goes through git too, so its serialization is
+    // checked; a changed one takes the partial rebase commands use.
+    let upstream = graph.base_oid.to_string();
+    let outcome = if fixup {
+        run_rebase(&workdir, Some(&upstream), &todo)
+    } else {
+        run_git_rebase(&workdir, Some(&upstream), &todo)
+    }
+    .unwrap_or_else(|e| panic!("seed {}: rebase failed: {:#}\n{}", seed, e, todo));
+    assert!(
+        matches!(outcome, RebaseOutcome::Completed),
+        "seed {}: rebase stopped\n{}",
+        seed,
+        todo
+    );
+
+    let actual = shape(&Weave::from_repo(&test_repo.repo).unwrap());
+    assert_eq!(
+        actual, expected,
+        "seed {}: the rebased history does not match the weave\n{}",
+        seed, todo
+    );
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    std::env::var(name).ok().and_then(|v| v.parse().ok())
+}
+
+#[test]
+fn random_weaves_survive_a_rebase_round_trip() {
+    let first = env_u64("LOOM_WEAVE_SEED").unwrap_or(0);
+    let cases = env_u64("LOOM_WEAVE_CASES").unwrap_or(DEFAULT_CASES);
+    for seed in first..first + cases {
+        check_case(seed);
+    }
+}
+
+#[test]
+fn random_weaves_cover_every_shape() {
+    // Guard the generator itself: the default seeds must exercise stacks,
+    // co-located branches, loose commits, and refs on loose commits.
+    let mut seen = [false; 4];
+    for seed in 0..DEFAULT_CASES {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        let test_repo = random_topology(&mut rng);
+        let graph = Weave::from_repo(&test_repo.repo).unwrap();
+        for section in &graph.branch_sections {
+            seen[0] |= section.commits.iter().any(|c| !c.update_refs.is_empty());
+            seen[1] |= section.branch_names.len() > 1;
+        }
+        for entry in &graph.integration_line {
+            if let IntegrationEntry::Pick(c) = entry {
+                seen[2] = true;
+                seen[3] |= !c.update_refs.is_empty();
+            }
+        }
+    }
+    assert_eq!(seen, [true; 4], "stacked, co-located, loose, loose ref");
+}
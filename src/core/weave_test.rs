@@ -193,6 +193,96 @@ fn serialize_empty_graph() {
     assert_eq!(todo, "label onto\n\nreset onto\n");
 }
 
+// ── Round-trip validation tests ─────────────────────────────────────────
+
+/// A stack (feature-b on feature-a) with a fixup chain carrying a ref, plus
+/// an integration commit with a ref.
+fn stacked_graph_with_fixup() -> Weave {
+    let mut fixup = make_commit(OID_FIX, "fixup! A1");
+    fixup.command = Command::Fixup;
+    Weave {
+        base_oid: oid(BASE),
+        branch_sections: vec![BranchSection {
+            reset_target: "onto".to_string(),
+            commits: vec![
+                make_commit_with_refs(OID_A1, "A1", vec!["feature-a"]),
+                fixup,
+                make_commit(OID_B1, "B1"),
+            ],
+            label: "feature-b".to_string(),
+            branch_names: vec!["feature-b".to_string()],
+        }],
+        integration_line: vec![
+            IntegrationEntry::Pick(make_commit_with_refs(OID_INT, "Int", vec!["local"])),
+            IntegrationEntry::Merge {
+                original_oid: Some(oid(OID_MERGE1)),
+                label: "feature-b".to_string(),
+            },
+        ],
+    }
+}
+
+#[test]
+fn checked_todo_accepts_serialized_weave() {
+    let graph = stacked_graph_with_fixup();
+    assert_eq!(graph.checked_todo().unwrap(), graph.to_todo());
+}
+
+#[test]
+fn checked_todo_rejects_label_used_before_definition() {
+    let mut graph = stacked_graph_with_fixup();
+    graph.branch_sections.push(BranchSection {
+        reset_target: "feature-c".to_string(),
+        commits: vec![make_commit(OID_C1, "C1")],
+        label: "feature-d".to_string(),
+        branch_names: vec!["feature-d".to_string()],
+    });
+
+    let err = graph.checked_todo().unwrap_err();
+    assert!(
+        format!("{:#}", err).contains("`reset feature-c` uses a label that is not defined yet"),
+        "{:#}",
+        err
+    );
+}
+
+#[test]
+fn validation_catches_ref_moved_off_fixup_chain() {
+    let graph = stacked_graph_with_fixup();
+    // A ref updated between a commit and its fixup would miss the squash.
+    let todo = graph
+        .to_todo()
+        .replace("update-ref refs/heads/feature-a\n", "");
+    let todo = todo.replace(
+        &format!("pick {} # A1\n", OID_A1),
+        &format!("pick {} # A1\nupdate-ref refs/heads/feature-a\n", OID_A1),
+    );
+
+    let actual = parse_todo_blocks(&todo).unwrap();
+    let err = compare_todo_blocks(&graph.todo_blocks(), &actual).unwrap_err();
+    assert!(err.to_string().contains("expected ref updates"), "{}", err);
+}
+
+#[test]
+fn validation_catches_pick_order_and_duplicates() {
+    let graph = stacked_graph_with_fixup();
+    let todo = graph.to_todo();
+    let a1 = format!("pick {} # A1\n", OID_A1);
+    let b1 = format!("pick {} # B1\n", OID_B1);
+
+    let swapped = todo
+        .replace(&a1, "A1\n")
+        .replace(&b1, &a1)
+        .replace("A1\n", &b1);
+    let actual = parse_todo_blocks(&swapped).unwrap();
+    let err = compare_todo_blocks(&graph.todo_blocks(), &actual).unwrap_err();
+    assert!(err.to_string().contains("expected steps"), "{}", err);
+
+    let duplicated = todo.replace(&b1, &format!("{}{}", b1, b1));
+    let err = parse_todo_blocks(&duplicated).unwrap_err();
+    assert!(err.to_string().contains("picked twice"), "{}", err);
+}
+
 // ── Mutation tests ──────────────────────────────────────────────────────
 
 #[test]
@@ -545,6 +635,29 @@ fn edit_commit_changes_command() {
     }
 }
 
+#[test]
+fn replace_commit_swaps_oid_in_place() {
+    let mut graph = Weave {
+        base_oid: oid(BASE),
+
+        branch_sections: vec![BranchSection {
+            reset_target: "onto".to_string(),
+            commits: vec![make_commit(OID_A1, "A1"), make_commit(OID_A2, "A2")],
+            label: "feature-a".to_string(),
+            branch_names: vec!["feature-a".to_string()],
+        }],
+        integration_line: vec![],
+    };
+
+    graph.replace_commit(oid(OID_A1), oid(OID_FIX));
+
+    let commits = &graph.branch_sections[0].commits;
+    assert_eq!(commits[0].oid, oid(OID_FIX));
+    assert_eq!(commits[0].short_hash, OID_FIX);
+    assert_eq!(commits[0].message, "A1");
+    assert_eq!(commits[1].oid, oid(OID_A2));
+}
+
 #[test]
 fn add_branch_section_and_merge() {
     let mut graph = Weave {
@@ -606,6 +719,74 @@ fn reassign_branch_renames_section() {
 
 // ── Integration test: from_repo ─────────────────────────────────────────
 
+#[test]
+fn partial_todo_starts_at_first_change() {
+    use crate::core::test_helpers::TestRepo;
+
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    for name in ["feature-a", "feature-b"] {
+        test_repo.create_branch_at(name, &base_oid.to_string());
+    }
+    test_repo.switch_branch("feature-a");
+    let a1 = test_repo.commit("A1", "a1.txt");
+    test_repo.switch_branch("feature-b");
+    let b1 = test_repo.commit("B1", "b1.txt");
+    let b2 = test_repo.commit("B2", "b2.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-a");
+    test_repo.merge_no_ff("feature-b");
+    let workdir = test_repo.workdir();
+    let base = base_oid.to_string();
+
+    let mut graph = Weave::from_repo(&test_repo.repo).unwrap();
+    graph.edit_commit(b2);
+    let (start, todo) = partial_todo(&workdir, &base, &graph.to_todo()).unwrap();
+
+    assert_eq!(start, b1.to_string());
+    assert!(todo.starts_with(&format!("reset {}\nlabel onto\n", base)));
+    assert!(todo.contains(&format!("reset {}\nlabel feature-a\n", a1)));
+    assert!(!todo.contains("pick"), "{}", todo);
+    assert!(todo.contains("edit"), "{}", todo);
+
+    // A change in the first section leaves nothing to skip.
+    let mut graph = Weave::from_repo(&test_repo.repo).unwrap();
+    graph.edit_commit(a1);
+    assert!(partial_todo(&workdir, &base, &graph.to_todo()).is_none());
+}
+
+#[test]
+fn noop_todo_only_for_unchanged_history() {
+    use crate::core::test_helpers::TestRepo;
+
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    let a1 = test_repo.commit("A1", "a1.txt");
+    test_repo.commit("A2", "a2.txt");
+    test_repo.switch_branch("integration");
+    test_repo.commit("Int", "int.txt");
+    test_repo.merge_no_ff("feature-a");
+    let workdir = test_repo.workdir();
+    let base = base_oid.to_string();
+
+    let graph = Weave::from_repo(&test_repo.repo).unwrap();
+    assert!(is_noop_todo(&workdir, &base, &graph.to_todo()));
+
+    let mut reordered = graph.clone();
+    reordered.move_commit(a1, "feature-a").unwrap();
+    assert!(!is_noop_todo(&workdir, &base, &reordered.to_todo()));
+
+    let mut edited = graph.clone();
+    edited.edit_commit(a1);
+    assert!(!is_noop_todo(&workdir, &base, &edited.to_todo()));
+
+    let mut unwoven = graph;
+    unwoven.drop_branch("feature-a");
+    assert!(!is_noop_todo(&workdir, &base, &unwoven.to_todo()));
+}
+
 #[test]
 fn from_repo_linear_integration() {
     use crate::core::test_helpers::TestRepo;
@@ -1093,3 +1274,229 @@ fn swap_commits_on_integration_line_with_interleaved_merge() {
         panic!("Expected Pick at 2");
     }
 }
+
+// ── Tags in the rewritten range ─────────────────────────────────────────
+
+/// `feature-a` (A0, A1, A2) woven, with A1 tagged `v1` and the remote base
+/// tagged `base`. Returns A0 and A1.
+fn tagged_weave() -> (crate::core::test_helpers::TestRepo, Oid, Oid) {
+    use crate::core::test_helpers::TestRepo;
+
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    let a0 = test_repo.commit("A0", "a0.txt");
+    let a1 = test_repo.commit("A1", "a1.txt");
+    test_repo.commit("A2", "a2.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-a");
+
+    let repo = &test_repo.repo;
+    repo.tag_lightweight("v1", &repo.find_object(a1, None).unwrap(), false)
+        .unwrap();
+    repo.tag_lightweight("base", &repo.find_object(base_oid, None).unwrap(), false)
+        .unwrap();
+    (test_repo, a0, a1)
+}
+
+/// Drop A0 through the weave, so A1 and A2 are picked again on a new parent.
+fn drop_a0(test_repo: &crate::core::test_helpers::TestRepo, a0: Oid) -> Result<RebaseOutcome> {
+    let mut graph = Weave::from_repo(&test_repo.repo).unwrap();
+    graph.drop_commit(a0);
+    run_rebase(
+        &test_repo.workdir(),
+        Some(&graph.base_oid.to_string()),
+        &graph.to_todo(),
+    )
+}
+
+fn tag_target(test_repo: &crate::core::test_helpers::TestRepo, name: &str) -> Oid {
+    test_repo
+        .repo
+        .revparse_single(&format!("refs/tags/{}^{{commit}}", name))
+        .unwrap()
+        .id()
+}
+
+#[test]
+fn rewrite_of_tagged_commit_is_refused() {
+    let (test_repo, a0, a1) = tagged_weave();
+    let head = test_repo.head_oid();
+
+    let Err(err) = drop_a0(&test_repo, a0) else {
+        panic!("rewrite of a tagged commit went through");
+    };
+
+    assert!(err.to_string().contains("tag `v1`"), "{}", err);
+    assert!(err.to_string().contains("--retag"), "{}", err);
+    assert_eq!(test_repo.head_oid(), head);
+    assert_eq!(tag_target(&test_repo, "v1"), a1);
+}
+
+#[test]
+fn retag_moves_lightweight_tag() {
+    let (test_repo, a0, a1) = tagged_weave();
+    let base = tag_target(&test_repo, "base");
+
+    set_retag(true);
+    let outcome = drop_a0(&test_repo, a0);
+    set_retag(false);
+    outcome.unwrap();
+
+    let moved = test_repo.find_commit(tag_target(&test_repo, "v1"));
+    assert_ne!(moved.id(), a1);
+    assert_eq!(moved.summary().unwrap(), "A1");
+    assert!(!test_repo.workdir().join("a0.txt").exists());
+    assert_eq!(tag_target(&test_repo, "base"), base);
+    assert!(
+        test_repo
+            .repo
+            .references_glob("refs/loom/retag/*")
+            .unwrap()
+            .next()
+            .is_none()
+    );
+}
+
+#[test]
+fn retag_recreates_annotated_tag() {
+    let (test_repo, a0, a1) = tagged_weave();
+    let repo = &test_repo.repo;
+    let sig = git2::Signature::now("Releaser", "rel@test.com").unwrap();
+    repo.tag(
+        "v1-notes",
+        &repo.find_object(a1, None).unwrap(),
+        &sig,
+        "Release notes",
+        false,
+    )
+    .unwrap();
+
+    set_retag(true);
+    let outcome = drop_a0(&test_repo, a0);
+    set_retag(false);
+    outcome.unwrap();
+
+    let tag = repo
+        .find_reference("refs/tags/v1-notes")
+        .unwrap()
+        .peel_to_tag()
+        .unwrap();
+    assert_eq!(tag.message(), Some("Release notes"));
+    assert_eq!(tag.tagger().unwrap().name(), Some("Releaser"));
+    assert_eq!(tag.target_id(), tag_target(&test_repo, "v1"));
+    assert_ne!(tag.target_id(), a1);
+}
+
+// ── Autostash policy ────────────────────────────────────────────────────
+
+/// The tagged weave without its tags, and a local edit to `a2.txt`.
+fn dirty_weave() -> (crate::core::test_helpers::TestRepo, Oid) {
+    let (test_repo, a0, _) = tagged_weave();
+    for name in ["v1", "base"] {
+        test_repo.repo.tag_delete(name).unwrap();
+    }
+    test_repo.write_file("a2.txt", "local edit");
+    (test_repo, a0)
+}
+
+#[test]
+fn autostash_never_refuses_rewrite_over_local_changes() {
+    let (test_repo, a0) = dirty_weave();
+    test_repo.set_config("loom.autostash", "never");
+    let head = test_repo.head_oid();
+
+    let Err(err) = drop_a0(&test_repo, a0) else {
+        panic!("rewrite over local changes went through");
+    };
+
+    assert!(err.to_string().contains("uncommitted changes"), "{}", err);
+    assert_eq!(test_repo.head_oid(), head);
+    assert_eq!(test_repo.read_file("a2.txt"), "local edit");
+}
+
+#[test]
+fn autostash_never_ignores_untracked_files() {
+    let (test_repo, a0) = dirty_weave();
+    test_repo.set_config("loom.autostash", "never");
+    git::run_git(&test_repo.workdir(), &["checkout", "--", "a2.txt"]).unwrap();
+    test_repo.write_file("notes.txt", "untracked");
+
+    drop_a0(&test_repo, a0).unwrap();
+
+    assert!(!test_repo.workdir().join("a0.txt").exists());
+    assert_eq!(test_repo.read_file("notes.txt"), "untracked");
+}
+
+#[test]
+fn no_autostash_overrides_config() {
+    let (test_repo, a0) = dirty_weave();
+    let head = test_repo.head_oid();
+
+    set_no_autostash(true);
+    let outcome = drop_a0(&test_repo, a0);
+    set_no_autostash(false);
+
+    let err = outcome
+        .err()
+        .expect("rewrite over local changes went through");
+    assert!(err.to_string().contains("--no-autostash"), "{}", err);
+    assert_eq!(test_repo.head_oid(), head);
+
+    // Without the flag, `always` stashes the edit and restores it
+    drop_a0(&test_repo, a0).unwrap();
+    assert_ne!(test_repo.head_oid(), head);
+    assert_eq!(test_repo.read_file("a2.txt"), "local edit");
+}
+
+#[test]
+fn notes_follow_rebased_commits_when_enabled() {
+    let (test_repo, a0, a1) = tagged_weave();
+    test_repo.set_config("loom.rewriteNotes", "true");
+    let repo = &test_repo.repo;
+    let sig = repo.signature().unwrap();
+    repo.note(&sig, &sig, None, a1, "LGTM", false).unwrap();
+    repo.find_reference("refs/tags/v1")
+        .unwrap()
+        .delete()
+        .unwrap();
+
+    drop_a0(&test_repo, a0).unwrap();
+
+    let new_a1 = test_repo.find_commit(test_repo.get_branch_target("feature-a"));
+    let new_a1 = new_a1.parent(0).unwrap();
+    assert_eq!(new_a1.summary(), Some("A1"));
+    assert_ne!(new_a1.id(), a1);
+    assert_eq!(
+        repo.find_note(None, new_a1.id()).unwrap().message(),
+        Some("LGTM")
+    );
+}
+
+// ── is_squash_merged ────────────────────────────────────────────────────
+
+#[test]
+fn squash_merged_when_upstream_has_branch_changes() {
+    let test_repo = crate::core::test_helpers::TestRepo::new();
+    let base = test_repo.head_oid();
+    test_repo.commit("F1", "f1.txt");
+    let tip = test_repo.commit("F2", "f2.txt");
+    test_repo.reset_hard(base);
+    test_repo.commit("Other", "other.txt");
+    assert!(!is_squash_merged(&test_repo.repo, base, tip, test_repo.head_oid()).unwrap());
+
+    // Squash the branch onto upstream
+    test_repo.write_file("f1.txt", "F1");
+    test_repo.write_file("f2.txt", "F2");
+    test_repo.stage_files(&["f1.txt", "f2.txt"]);
+    test_repo.commit_staged("Feature (#1)");
+    assert!(is_squash_merged(&test_repo.repo, base, tip, test_repo.head_oid()).unwrap());
+}
+
+#[test]
+fn squash_merged_ignores_branch_without_changes() {
+    let test_repo = crate::core::test_helpers::TestRepo::new();
+    let base = test_repo.head_oid();
+    assert!(!is_squash_merged(&test_repo.repo, base, base, base).unwrap());
+}
@@ -1,6 +1,8 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
+use git2::{Oid, Repository};
 
 use crate::core::repo::{self, Target, TargetKind};
+use crate::core::weave::Weave;
 use crate::git;
 
 /// Show a diff using short IDs (like `git diff`).
@@ -10,7 +12,9 @@ use crate::git;
 /// (working tree vs HEAD).
 pub fn run(args: Vec<String>, staged: bool, all: bool) -> Result<()> {
     let repo = repo::open_repo()?;
-    let workdir = repo::require_workdir(&repo, "diff")?;
+    // Commit diffs also work in a bare repository; git reports the missing
+    // working tree for the other forms.
+    let workdir = repo::command_dir(&repo);
 
     let mut git_args: Vec<String> = vec!["diff".to_string()];
     if staged {
@@ -56,6 +60,93 @@ pub fn run(args: Vec<String>, staged: bool, all: bool) -> Result<()> {
     git::run_git_interactive(workdir, &refs)
 }
 
+/// Show what a branch adds over its base as one combined diff, the way a
+/// reviewer reads it.
+///
+/// The base is where the branch's own commits start: the branch stacked
+/// below it, or the upstream merge-base for a branch at the bottom of a
+/// stack. `base` overrides it: `upstream` diffs the whole stack against the
+/// upstream merge-base; a branch or commit diffs against its merge-base with
+/// the branch. `--stat` shows a diffstat instead of the patch.
+pub fn run_branch(branch: String, base: Option<String>, stat: bool) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::command_dir(&repo);
+
+    let name = repo::resolve_arg(&repo, &branch, &[TargetKind::Branch])?.expect_branch()?;
+    let (from, to) = branch_range(&repo, &name, base.as_deref())?;
+
+    let mut git_args: Vec<String> = vec!["diff".to_string()];
+    if stat {
+        git_args.push("--stat".to_string());
+    }
+    git_args.push(from.to_string());
+    git_args.push(to.to_string());
+
+    let refs: Vec<&str> = git_args.iter().map(|s| s.as_str()).collect();
+    git::run_git_interactive(workdir, &refs)
+}
+
+/// The `(base, tip)` commits `loom diff-branch` compares for `name`.
+fn branch_range(repo: &Repository, name: &str, base: Option<&str>) -> Result<(Oid, Oid)> {
+    let tip = repo
+        .find_branch(name, git2::BranchType::Local)?
+        .get()
+        .peel_to_commit()?
+        .id();
+    let graph = Weave::from_repo(repo)?;
+
+    let from = match base {
+        Some("upstream") => graph.base_oid,
+        Some(arg) => {
+            let other =
+                match repo::resolve_arg(repo, arg, &[TargetKind::Branch, TargetKind::Commit])? {
+                    Target::Branch(b) => repo
+                        .find_branch(&b, git2::BranchType::Local)?
+                        .get()
+                        .peel_to_commit()?
+                        .id(),
+                    Target::Commit(hash) => Oid::from_str(&hash)?,
+                    _ => unreachable!(),
+                };
+            repo.merge_base(other, tip)?
+        }
+        None => section_base(repo, &graph, name)?,
+    };
+    Ok((from, tip))
+}
+
+/// The commit the own commits of `name` start from in the weave: the tip of
+/// the branch stacked below it in its section, or the parent of the
+/// section's first commit.
+fn section_base(repo: &Repository, graph: &Weave, name: &str) -> Result<Oid> {
+    for section in &graph.branch_sections {
+        let end = if section.branch_names.iter().any(|b| b == name) {
+            section.commits.len()
+        } else if let Some(i) = section
+            .commits
+            .iter()
+            .position(|c| c.update_refs.iter().any(|r| r == name))
+        {
+            i + 1
+        } else {
+            continue;
+        };
+        if end == 0 {
+            bail!("Branch `{}` has no commits", name);
+        }
+        let own = &section.commits[..end];
+        if let Some(below) = own[..end - 1]
+            .iter()
+            .rev()
+            .find(|c| !c.update_refs.is_empty())
+        {
+            return Ok(below.oid);
+        }
+        return Ok(repo.find_commit(own[0].oid)?.parent_id(0)?);
+    }
+    bail!("Branch `{}` is not woven into the integration branch", name)
+}
+
 /// Resolve a commit reference leniently: tries short ID and direct ref resolution.
 /// Falls back to the raw string for refs that can't be resolved (HEAD, tags, etc.)
 /// and does not reject merge commits, making it suitable for range endpoints.
@@ -64,3 +64,75 @@ fn diff_invalid_target_fails() {
     let result = test_repo.in_dir(|| super::run(vec!["nonexistent_xyz".to_string()], false, false));
     assert!(result.is_err(), "diff with invalid target should fail");
 }
+
+/// `feature-a` with one commit and `feature-b` stacked on it with another,
+/// woven as a single section.
+fn setup_stacked_branches() -> (TestRepo, git2::Oid) {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a1.txt");
+    test_repo.create_branch("feature-b");
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1", "b1.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-b");
+    (test_repo, base_oid)
+}
+
+#[test]
+fn branch_range_of_stacked_branch_starts_at_branch_below() {
+    let (test_repo, _) = setup_stacked_branches();
+
+    let (from, to) = super::branch_range(&test_repo.repo, "feature-b", None).unwrap();
+
+    assert_eq!(from, test_repo.get_branch_target("feature-a"));
+    assert_eq!(to, test_repo.get_branch_target("feature-b"));
+}
+
+#[test]
+fn branch_range_of_bottom_branch_starts_at_merge_base() {
+    let (test_repo, base_oid) = setup_stacked_branches();
+
+    let (from, to) = super::branch_range(&test_repo.repo, "feature-a", None).unwrap();
+
+    assert_eq!(from, base_oid);
+    assert_eq!(to, test_repo.get_branch_target("feature-a"));
+}
+
+#[test]
+fn branch_range_upstream_covers_whole_stack() {
+    let (test_repo, base_oid) = setup_stacked_branches();
+
+    let (from, _) = super::branch_range(&test_repo.repo, "feature-b", Some("upstream")).unwrap();
+
+    assert_eq!(from, base_oid);
+}
+
+#[test]
+fn diff_branch_stat() {
+    no_pager();
+    let (test_repo, _) = setup_stacked_branches();
+
+    let result = test_repo.in_dir(|| super::run_branch("feature-b".to_string(), None, true));
+    assert!(
+        result.is_ok(),
+        "diff-branch --stat should succeed: {:?}",
+        result
+    );
+}
+
+#[test]
+fn diff_branch_not_woven() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.create_branch("loose");
+
+    let result = test_repo.in_dir(|| super::run_branch("loose".to_string(), None, false));
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("is not woven into the integration branch")
+    );
+}
@@ -46,8 +46,12 @@ pub fn run(target: String, skip_confirm: bool) -> Result<()> {
     )?;
 
     match resolved {
-        Target::Commit(hash) => drop_commit(&repo, &hash, skip_confirm),
-        Target::Branch(name) => drop_branch(&repo, &name, skip_confirm),
+        Target::Commit(hash) => {
+            transaction::reporting_ref_moves(&repo, || drop_commit(&repo, &hash, skip_confirm))
+        }
+        Target::Branch(name) => {
+            transaction::reporting_ref_moves(&repo, || drop_branch(&repo, &name, skip_confirm))
+        }
         Target::File(path) => drop_file(&repo, &path, skip_confirm),
         Target::Unstaged => drop_all(&repo, skip_confirm),
         _ => unreachable!(),
@@ -175,12 +179,13 @@ fn drop_commit(repo: &Repository, commit_hash: &str, skip_confirm: bool) -> Resu
     };
     let state = LoomState {
         command: "drop".to_string(),
+        refs_before: Default::default(),
         rollback: Rollback::default(),
         context: serde_json::to_value(&ctx)?,
     };
     transaction::save(&git_dir, &state)?;
 
-    let todo = graph.to_todo();
+    let todo = graph.checked_todo()?;
     match weave::run_rebase(workdir, Some(&graph.base_oid.to_string()), &todo)? {
         RebaseOutcome::Completed => {
             transaction::delete(&git_dir)?;
@@ -296,7 +301,7 @@ fn drop_branch_with_info(
         }
     }
 
-    let todo = graph.to_todo();
+    let todo = graph.checked_todo()?;
     weave::run_rebase_or_abort(workdir, Some(&graph.base_oid.to_string()), &todo)?;
 
     // Delete the branch ref (warn on failure — extremely unlikely)
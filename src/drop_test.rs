@@ -725,3 +725,22 @@ fn drop_abort_preserves_working_state() {
     );
     assert_eq!(test_repo.read_file("new-file.txt"), "new-content");
 }
+
+#[test]
+fn drop_branch_confirmation_fails_under_no_input() {
+    let test_repo = setup_woven_branch(1);
+    let head = test_repo.head_oid();
+
+    let output = std::process::Command::new(crate::git::loom_exe_path().unwrap())
+        .args(["--no-input", "drop", "feature-a"])
+        .current_dir(test_repo.workdir())
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        output.status.code(),
+        Some(crate::core::msg::NO_INPUT_EXIT_CODE)
+    );
+    assert!(test_repo.branch_exists("feature-a"));
+    assert_eq!(test_repo.head_oid(), head);
+}
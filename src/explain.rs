@@ -0,0 +1,228 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use git2::Repository;
+
+use crate::core::{msg, repo};
+use crate::{git, trace};
+
+/// Set in the environment of the command `loom explain` runs, so that it
+/// leaves out what reaches beyond the sandbox: forge tools, checks, and
+/// notifications.
+pub const SANDBOX_VAR: &str = "LOOM_EXPLAIN_SANDBOX";
+
+/// Whether this loom runs inside the sandbox of `loom explain`.
+pub fn in_sandbox() -> bool {
+    std::env::var_os(SANDBOX_VAR).is_some()
+}
+
+/// Show the git commands `loom <args>` would run, without touching the
+/// repository: the command runs in a throwaway copy, and its trace (rebase
+/// arguments, generated todos, apply and commit steps) is printed.
+pub fn run(args: Vec<String>) -> Result<()> {
+    let Some(command) = args.first() else {
+        bail!("Nothing to explain\nUse: loom explain <command> [args...]");
+    };
+    if command == "explain" {
+        bail!("`loom explain` can't explain itself");
+    }
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "explain")?.to_path_buf();
+
+    let dir = tempfile::tempdir()?;
+    let sandbox = create_sandbox(&repo, dir.path())?;
+
+    let exe = git::loom_exe_path()?;
+    let status = Command::new(&exe)
+        .args(&args)
+        .current_dir(&sandbox)
+        .env(SANDBOX_VAR, "1")
+        .env_remove("GIT_DIR")
+        .env_remove("GIT_WORK_TREE")
+        .env_remove("GIT_INDEX_FILE")
+        .status()
+        .with_context(|| format!("Failed to run `{}`", exe.display()))?;
+
+    let command_line = format!("loom {}", args.join(" "));
+    let log = trace::latest_log_path(&sandbox.join(".git"))
+        .and_then(|path| std::fs::read_to_string(path).ok());
+    println!();
+    match log {
+        Some(log) => {
+            let sandbox_path = sandbox.to_string_lossy().replace('\\', "/");
+            let workdir_path = workdir.to_string_lossy().replace('\\', "/");
+            trace::print_log_colored(&log.replace(&sandbox_path, &workdir_path));
+        }
+        None => msg::notice(&format!(
+            "`{}` runs no git commands that change the repository",
+            command_line
+        )),
+    }
+    if !status.success() {
+        bail!("`{}` would stop with the error above", command_line);
+    }
+    Ok(())
+}
+
+/// Build a copy of `repo` under `root` that a loom command can freely
+/// rewrite, and return its working directory.
+///
+/// The copy borrows the objects of `repo` through `objects/info/alternates`
+/// and gets its refs, HEAD, configuration, loom state, and working tree
+/// changes (staged, unstaged, and untracked). Each remote is replaced by a
+/// local bare repository holding the remote-tracking branches, so fetches
+/// and pushes stay in the sandbox, and hooks are disabled.
+pub fn create_sandbox(repo: &Repository, root: &Path) -> Result<PathBuf> {
+    let source = repo::require_workdir(repo, "explain")?;
+    let objects = repo.commondir().join("objects");
+    let sandbox = root.join("work");
+    git::run_git(root, &["init", "-q", &sandbox.to_string_lossy()])?;
+    let target = Repository::open(&sandbox)?;
+    borrow_objects(&target, &objects)?;
+
+    std::fs::copy(
+        repo.commondir().join("config"),
+        target.path().join("config"),
+    )
+    .context("Failed to copy the repository configuration")?;
+    let hooks = root.join("hooks");
+    std::fs::create_dir_all(&hooks)?;
+    {
+        let mut config = target.config()?.open_level(git2::ConfigLevel::Local)?;
+        for key in ["core.worktree", "core.bare", "loom.notifyCommand"] {
+            let _ = config.remove(key);
+        }
+        for key in ["loom.checks", "loom.promoteChecks"] {
+            let _ = config.remove_multivar(key, ".*");
+        }
+        config.set_str("core.hooksPath", &hooks.to_string_lossy())?;
+    }
+
+    for reference in repo.references()? {
+        let reference = reference?;
+        let Some(name) = reference.name() else {
+            continue;
+        };
+        if let Some(symbolic) = reference.symbolic_target() {
+            target.reference_symbolic(name, symbolic, true, "loom explain")?;
+        } else if let Some(oid) = reference.target() {
+            target.reference(name, oid, true, "loom explain")?;
+        }
+    }
+    let head = repo.find_reference("HEAD")?;
+    match head.symbolic_target() {
+        Some(branch) => target.set_head(branch)?,
+        None => target.set_head_detached(repo.head()?.peel_to_commit()?.id())?,
+    }
+
+    for remote in repo.remotes()?.iter().flatten() {
+        let stand_in = stand_in_remote(repo, remote, &objects, &root.join("remotes"))?;
+        let mut config = target.config()?.open_level(git2::ConfigLevel::Local)?;
+        config.set_str(
+            &format!("remote.{}.url", remote),
+            &stand_in.to_string_lossy(),
+        )?;
+        let _ = config.remove_multivar(&format!("remote.{}.pushurl", remote), ".*");
+    }
+
+    copy_loom_state(&repo.commondir().join("loom"), &target.path().join("loom"))?;
+    if repo.head().is_ok() {
+        git::run_git(&sandbox, &["reset", "--hard", "-q"])?;
+    }
+    copy_working_changes(source, &sandbox)?;
+    Ok(sandbox)
+}
+
+/// Let `target` read the objects of another repository instead of copying
+/// them.
+fn borrow_objects(target: &Repository, objects: &Path) -> Result<()> {
+    let info = target.path().join("objects").join("info");
+    std::fs::create_dir_all(&info)?;
+    std::fs::write(
+        info.join("alternates"),
+        format!("{}\n", objects.to_string_lossy()),
+    )?;
+    Ok(())
+}
+
+/// A bare repository in `dir` standing in for `remote`, whose branches are
+/// the remote-tracking branches `repo` has for it.
+fn stand_in_remote(repo: &Repository, remote: &str, objects: &Path, dir: &Path) -> Result<PathBuf> {
+    let path = dir.join(format!("{}.git", remote));
+    let stand_in = Repository::init_bare(&path)?;
+    borrow_objects(&stand_in, objects)?;
+    let prefix = format!("refs/remotes/{}/", remote);
+    for reference in repo.references_glob(&format!("{}*", prefix))? {
+        let reference = reference?;
+        let (Some(name), Some(oid)) = (reference.name(), reference.target()) else {
+            continue;
+        };
+        let branch = name.trim_start_matches(&prefix);
+        stand_in.reference(&format!("refs/heads/{}", branch), oid, true, "loom explain")?;
+    }
+    Ok(path)
+}
+
+/// Copy the state loom keeps in `.git/loom`, leaving out what belongs to
+/// the commands running in the repository itself: trace logs, the lock,
+/// and a paused operation.
+fn copy_loom_state(from: &Path, to: &Path) -> Result<()> {
+    let Ok(entries) = std::fs::read_dir(from) else {
+        return Ok(());
+    };
+    std::fs::create_dir_all(to)?;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name();
+        if ["logs", "lock", "state.json"].iter().any(|n| name == *n) {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            copy_loom_state(&path, &to.join(&name))?;
+        } else {
+            std::fs::copy(&path, to.join(&name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Reproduce the staged, unstaged, and untracked changes of `source` in
+/// `sandbox`, whose working tree is clean at the same HEAD.
+fn copy_working_changes(source: &Path, sandbox: &Path) -> Result<()> {
+    for (diff_args, apply_args) in [
+        (&["--cached"][..], &["apply", "--index"][..]),
+        (&[][..], &["apply"][..]),
+    ] {
+        let mut args = vec!["diff", "--binary", "--no-color", "--no-ext-diff"];
+        args.extend_from_slice(diff_args);
+        let patch = git::run_git_stdout(source, &args)?;
+        if patch.is_empty() {
+            continue;
+        }
+        let patch_file = sandbox.join(".git").join("loom-explain.patch");
+        std::fs::write(&patch_file, patch)?;
+        let mut args = apply_args.to_vec();
+        let patch_arg = patch_file.to_string_lossy();
+        args.push(&patch_arg);
+        git::run_git(sandbox, &args)?;
+        std::fs::remove_file(&patch_file)?;
+    }
+
+    let untracked = git::run_git_stdout(
+        source,
+        &["ls-files", "--others", "--exclude-standard", "-z"],
+    )?;
+    for path in untracked.split('\0').filter(|p| !p.is_empty()) {
+        let to = sandbox.join(path);
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(source.join(path), &to)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "explain_test.rs"]
+mod tests;
@@ -0,0 +1,92 @@
+use git2::{BranchType, Repository};
+
+use crate::core::test_helpers::TestRepo;
+
+#[test]
+fn sandbox_copies_refs_head_and_working_changes() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit("Add a", "a.txt");
+    test_repo.create_branch("feature-a");
+    test_repo.write_file("staged.txt", "staged\n");
+    test_repo.stage_files(&["staged.txt"]);
+    test_repo.write_file("a.txt", "changed\n");
+    test_repo.write_file("untracked.txt", "new\n");
+
+    let dir = tempfile::tempdir().unwrap();
+    let sandbox = super::create_sandbox(&test_repo.repo, dir.path()).unwrap();
+    let copy = Repository::open(&sandbox).unwrap();
+
+    assert_eq!(copy.head().unwrap().shorthand(), Some("integration"));
+    assert_eq!(
+        copy.head().unwrap().target(),
+        Some(test_repo.head_oid()),
+        "HEAD should point at the same commit"
+    );
+    assert!(copy.find_branch("feature-a", BranchType::Local).is_ok());
+    assert!(copy.find_branch("origin/main", BranchType::Remote).is_ok());
+
+    let index = copy.index().unwrap();
+    assert!(index.get_path("staged.txt".as_ref(), 0).is_some());
+    assert!(index.get_path("untracked.txt".as_ref(), 0).is_none());
+    assert_eq!(
+        std::fs::read_to_string(sandbox.join("a.txt")).unwrap(),
+        "changed\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(sandbox.join("untracked.txt")).unwrap(),
+        "new\n"
+    );
+}
+
+#[test]
+fn sandbox_replaces_remotes_with_local_stand_ins() {
+    let test_repo = TestRepo::new_with_remote();
+    let main = test_repo
+        .repo
+        .refname_to_id("refs/remotes/origin/main")
+        .unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let sandbox = super::create_sandbox(&test_repo.repo, dir.path()).unwrap();
+    let copy = Repository::open(&sandbox).unwrap();
+
+    let url = copy
+        .find_remote("origin")
+        .unwrap()
+        .url()
+        .unwrap()
+        .to_string();
+    assert!(url.starts_with(&dir.path().to_string_lossy().to_string()));
+    let stand_in = Repository::open_bare(&url).unwrap();
+    assert_eq!(
+        stand_in.refname_to_id("refs/heads/main").unwrap(),
+        main,
+        "the stand-in should hold the remote-tracking branches"
+    );
+}
+
+#[test]
+fn explain_leaves_the_repository_untouched() {
+    let test_repo = TestRepo::new_with_remote();
+    let oid = test_repo.commit("Original message", "a.txt");
+
+    test_repo
+        .in_dir(|| {
+            super::run(vec![
+                "reword".to_string(),
+                oid.to_string(),
+                "-m".to_string(),
+                "New message".to_string(),
+            ])
+        })
+        .unwrap();
+
+    assert_eq!(test_repo.head_oid(), oid);
+    assert_eq!(test_repo.get_message(0), "Original message");
+}
+
+#[test]
+fn explain_refuses_to_explain_itself() {
+    let err = super::run(vec!["explain".to_string(), "status".to_string()]).unwrap_err();
+    assert!(err.to_string().contains("can't explain itself"));
+}
@@ -0,0 +1,253 @@
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::Oid;
+use serde::Deserialize;
+
+use crate::core::graph::{self, Theme};
+use crate::core::repo::{
+    BranchInfo, CommitInfo, ContextCommit, FileChange, RemoteStatus, RepoInfo, UpstreamInfo,
+};
+use crate::core::shortid::IdAllocator;
+
+/// Render the status fixture at `path` (`-` for stdin) the way `loom status`
+/// would draw that repository. The output depends on nothing but the
+/// fixture, `width`, and `theme`, so it can be compared to a golden file.
+pub fn run(path: &Path, width: Option<u16>, theme: Theme) -> Result<()> {
+    let json = if path == Path::new("-") {
+        let mut json = String::new();
+        std::io::stdin()
+            .read_to_string(&mut json)
+            .context("Failed to read the fixture from stdin")?;
+        json
+    } else {
+        std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read `{}`", path.display()))?
+    };
+    print!("{}", render(&json, width, theme)?);
+    Ok(())
+}
+
+/// Render a JSON fixture as text. See [`parse`] for the format.
+pub fn render(json: &str, width: Option<u16>, theme: Theme) -> Result<String> {
+    let info = parse(json)?;
+    let ids = IdAllocator::new(info.collect_entities());
+    let mut opts = graph::default_render_opts(theme, String::new());
+    opts.terminal_width = width;
+    Ok(graph::render(info, &ids, &opts))
+}
+
+/// Read a fixture: the [`RepoInfo`] `loom status` renders, as JSON with the
+/// same field names. Object ids may be abbreviated (`"a2"` stands for
+/// `a200000…`); a commit's `short_id` defaults to the first 7 digits of its
+/// id. Everything but `upstream.label` and the commits' `oid` and `message`
+/// is optional:
+///
+/// ```json
+/// {
+///   "upstream": { "label": "origin/main", "commits_ahead": 2 },
+///   "commits": [
+///     { "oid": "a2", "message": "Add the parser", "parent_oid": "a1",
+///       "files": [{ "path": "src/parser.rs", "index": "A" }] },
+///     { "oid": "a1", "message": "Add the lexer" }
+///   ],
+///   "branches": [{ "name": "feature-a", "tip_oid": "a2", "remote": "synced" }],
+///   "working_changes": [{ "path": "README.md", "worktree": "M" }]
+/// }
+/// ```
+pub fn parse(json: &str) -> Result<RepoInfo> {
+    let fixture: Fixture = serde_json::from_str(json).context("Invalid status fixture")?;
+    fixture.into_info()
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Fixture {
+    #[serde(default = "default_branch_name")]
+    branch_name: String,
+    upstream: FixtureUpstream,
+    #[serde(default)]
+    commits: Vec<FixtureCommit>,
+    #[serde(default)]
+    branches: Vec<FixtureBranch>,
+    #[serde(default)]
+    working_changes: Vec<FixtureFile>,
+    #[serde(default)]
+    context_commits: Vec<FixtureContext>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FixtureUpstream {
+    label: String,
+    #[serde(default = "default_base_oid")]
+    merge_base_oid: String,
+    base_short_id: Option<String>,
+    #[serde(default = "default_base_message")]
+    base_message: String,
+    #[serde(default = "default_base_date")]
+    base_date: String,
+    #[serde(default)]
+    commits_ahead: usize,
+    #[serde(default)]
+    local_only: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FixtureCommit {
+    oid: String,
+    short_id: Option<String>,
+    message: String,
+    parent_oid: Option<String>,
+    #[serde(default)]
+    files: Vec<FixtureFile>,
+    #[serde(default)]
+    time: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FixtureBranch {
+    name: String,
+    tip_oid: String,
+    remote: Option<FixtureRemote>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum FixtureRemote {
+    Synced,
+    Ahead,
+    Gone,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FixtureFile {
+    path: String,
+    #[serde(default = "default_status")]
+    index: char,
+    #[serde(default = "default_status")]
+    worktree: char,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FixtureContext {
+    short_hash: String,
+    message: String,
+    #[serde(default)]
+    date: String,
+}
+
+fn default_branch_name() -> String {
+    "integration".to_string()
+}
+
+fn default_base_oid() -> String {
+    "0".to_string()
+}
+
+fn default_base_message() -> String {
+    "Initial commit".to_string()
+}
+
+fn default_base_date() -> String {
+    "2025-01-01".to_string()
+}
+
+fn default_status() -> char {
+    ' '
+}
+
+impl Fixture {
+    fn into_info(self) -> Result<RepoInfo> {
+        let merge_base_oid = oid(&self.upstream.merge_base_oid)?;
+        let commits = self
+            .commits
+            .into_iter()
+            .map(|c| {
+                let oid = oid(&c.oid)?;
+                Ok(CommitInfo {
+                    short_id: c.short_id.unwrap_or_else(|| short(oid)),
+                    oid,
+                    message: c.message,
+                    parent_oid: c.parent_oid.as_deref().map(self::oid).transpose()?,
+                    files: c.files.into_iter().map(FixtureFile::into_change).collect(),
+                    time: c.time,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let branches = self
+            .branches
+            .into_iter()
+            .map(|b| {
+                Ok(BranchInfo {
+                    name: b.name,
+                    tip_oid: oid(&b.tip_oid)?,
+                    remote: b.remote.map(|r| match r {
+                        FixtureRemote::Synced => RemoteStatus::Synced,
+                        FixtureRemote::Ahead => RemoteStatus::Ahead,
+                        FixtureRemote::Gone => RemoteStatus::Gone,
+                    }),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(RepoInfo {
+            branch_name: self.branch_name,
+            upstream: UpstreamInfo {
+                label: self.upstream.label,
+                base_short_id: self
+                    .upstream
+                    .base_short_id
+                    .unwrap_or_else(|| short(merge_base_oid)),
+                merge_base_oid,
+                base_message: self.upstream.base_message,
+                base_date: self.upstream.base_date,
+                commits_ahead: self.upstream.commits_ahead,
+                local_only: self.upstream.local_only,
+            },
+            commits,
+            branches,
+            working_changes: self
+                .working_changes
+                .into_iter()
+                .map(FixtureFile::into_change)
+                .collect(),
+            context_commits: self
+                .context_commits
+                .into_iter()
+                .map(|c| ContextCommit {
+                    short_hash: c.short_hash,
+                    message: c.message,
+                    date: c.date,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl FixtureFile {
+    fn into_change(self) -> FileChange {
+        FileChange {
+            path: self.path,
+            index: self.index,
+            worktree: self.worktree,
+        }
+    }
+}
+
+/// An object id from a possibly abbreviated hex string, padded with zeros.
+fn oid(hex: &str) -> Result<Oid> {
+    Oid::from_str(hex).with_context(|| format!("`{}` is not a hexadecimal object id", hex))
+}
+
+fn short(oid: Oid) -> String {
+    oid.to_string()[..7].to_string()
+}
+
+#[cfg(test)]
+#[path = "fixture_test.rs"]
+mod tests;
@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+
+use crate::core::graph::{self, Theme};
+
+/// Strip ANSI escape codes: golden files hold the text `render-fixture`
+/// prints to a pipe.
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            graph::skip_escape(&mut chars);
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn corpus_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("render")
+}
+
+/// Every `<name>.json` of the corpus renders as `<name>.txt`. Run with
+/// `LOOM_UPDATE_FIXTURES=1` to rewrite the golden files after an intended
+/// rendering change, then review their diff.
+#[test]
+fn corpus_matches_golden_files() {
+    let update = std::env::var_os("LOOM_UPDATE_FIXTURES").is_some();
+    let mut fixtures: Vec<PathBuf> = std::fs::read_dir(corpus_dir())
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    fixtures.sort();
+    assert!(!fixtures.is_empty());
+
+    let mut mismatches = Vec::new();
+    for fixture in fixtures {
+        let json = std::fs::read_to_string(&fixture).unwrap();
+        let rendered = strip_ansi(&super::render(&json, None, Theme::dark()).unwrap());
+        let golden = fixture.with_extension("txt");
+        if update {
+            std::fs::write(&golden, &rendered).unwrap();
+        } else if std::fs::read_to_string(&golden).unwrap_or_default() != rendered {
+            mismatches.push(format!("{}:\n{}", golden.display(), rendered));
+        }
+    }
+    assert!(
+        mismatches.is_empty(),
+        "rendering differs from the golden files (LOOM_UPDATE_FIXTURES=1 to update):\n{}",
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn parse_expands_abbreviated_ids_and_defaults() {
+    let info = super::parse(
+        r#"{
+            "upstream": { "label": "origin/main" },
+            "commits": [{ "oid": "a2", "message": "A2", "parent_oid": "a1" }],
+            "branches": [{ "name": "feature-a", "tip_oid": "a2", "remote": "synced" }],
+            "working_changes": [{ "path": "README.md", "worktree": "M" }]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(info.branch_name, "integration");
+    assert_eq!(info.upstream.merge_base_oid, git2::Oid::zero());
+    assert_eq!(info.upstream.base_short_id, "0000000");
+    let commit = &info.commits[0];
+    assert_eq!(
+        commit.oid.to_string(),
+        "a200000000000000000000000000000000000000"
+    );
+    assert_eq!(commit.short_id, "a200000");
+    assert_eq!(
+        commit.parent_oid.map(|p| p.to_string()[..2].to_string()),
+        Some("a1".to_string())
+    );
+    assert_eq!(info.branches[0].tip_oid, commit.oid);
+    assert!(matches!(
+        info.branches[0].remote,
+        Some(crate::core::repo::RemoteStatus::Synced)
+    ));
+    assert_eq!(info.working_changes[0].index, ' ');
+    assert_eq!(info.working_changes[0].worktree, 'M');
+}
+
+#[test]
+fn parse_rejects_unknown_fields_and_bad_ids() {
+    let unknown = super::parse(r#"{ "upstream": { "label": "origin/main" }, "comits": [] }"#);
+    assert!(unknown.is_err());
+
+    let bad_id = super::parse(
+        r#"{ "upstream": { "label": "origin/main" },
+             "commits": [{ "oid": "xyz", "message": "A" }] }"#,
+    );
+    let err = format!("{:#}", bad_id.unwrap_err());
+    assert!(
+        err.contains("`xyz` is not a hexadecimal object id"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn render_honors_ascii_and_width() {
+    let json = std::fs::read_to_string(corpus_dir().join("stacked-branches.json")).unwrap();
+    let mut theme = Theme::dark();
+    theme.ascii = true;
+
+    let rendered = strip_ansi(&super::render(&json, Some(30), theme).unwrap());
+
+    assert!(rendered.is_ascii(), "{}", rendered);
+    assert!(
+        rendered.contains("b200000 Use the parse...\n"),
+        "{}",
+        rendered
+    );
+}
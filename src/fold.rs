@@ -1,4 +1,5 @@
 use anyhow::{Context, Result, bail};
+use colored::Colorize;
 use git2::{Repository, StatusOptions};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -8,9 +9,11 @@ use crate::core::graph;
 use crate::core::msg;
 use crate::core::repo::{self, Target, TargetKind};
 use crate::core::staging;
+use crate::core::trailers::{self, Trailer};
 use crate::core::transaction::{self, LoomState, Rollback};
 use crate::core::weave::{self, RebaseOutcome, Weave};
 use crate::git;
+use crate::size_check;
 use crate::tui::hunk_selector::FileEntry;
 
 #[derive(Serialize, Deserialize)]
@@ -19,7 +22,6 @@ enum FoldVariant {
     FilesIntoCommit {
         original_commit_hash: String,
         files_count: usize,
-        saved_staged: String,
     },
     CommitIntoCommit {
         source_hash: String,
@@ -47,7 +49,30 @@ const COMMAND: &str = "fold";
 /// - Commit + Branch   → move commit to the branch
 ///
 /// With `--create` (`-c`): create a new branch and move the source commit into it.
-pub fn run(create: bool, patch: bool, args: Vec<String>, theme: &graph::Theme) -> Result<()> {
+///
+/// With `--keep-trailers`: when folding a commit into a commit, append the
+/// source's trailers (`Signed-off-by`, `Co-authored-by`, ...) that the target
+/// does not already carry to the target's message.
+///
+/// With `--coauthor`: when folding working-tree changes into a commit, add a
+/// `Co-authored-by` trailer for each co-author to the amended commit.
+///
+/// With `--staged`: the only argument is the target commit, and exactly what
+/// is staged is folded into it — as with a single argument, but explicit.
+///
+/// With `--preview`: show the patch the fold moves through the pager and ask
+/// for confirmation before touching anything.
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    create: bool,
+    patch: bool,
+    keep_trailers: bool,
+    staged: bool,
+    preview: bool,
+    coauthors: Vec<String>,
+    args: Vec<String>,
+    theme: &graph::Theme,
+) -> Result<()> {
     if args.is_empty() {
         bail!(
             "At least one argument required\n\
@@ -55,19 +80,69 @@ pub fn run(create: bool, patch: bool, args: Vec<String>, theme: &graph::Theme) -
         );
     }
 
+    if keep_trailers && (create || patch) {
+        bail!("--keep-trailers cannot be combined with --create or --patch");
+    }
+    if staged {
+        if create || patch || keep_trailers {
+            bail!("--staged cannot be combined with --create, --patch, or --keep-trailers");
+        }
+        if args.len() > 1 {
+            bail!(
+                "--staged folds the index into a single target commit\n\
+                 Usage: git-loom fold --staged <commit>"
+            );
+        }
+    }
+    if preview && (create || patch) {
+        bail!("--preview cannot be combined with --create or --patch");
+    }
+    let coauthors: Vec<Trailer> = coauthors
+        .iter()
+        .map(|c| trailers::coauthor(c))
+        .collect::<Result<_>>()?;
+    if create && !coauthors.is_empty() {
+        bail!("--coauthor cannot be combined with --create");
+    }
+
     let repo = repo::open_repo()?;
+    transaction::reporting_ref_moves(&repo, || {
+        fold(
+            &repo,
+            create,
+            patch,
+            keep_trailers,
+            preview,
+            &coauthors,
+            &args,
+            theme,
+        )
+    })
+}
 
+/// Dispatch a validated fold: see [`run`].
+#[allow(clippy::too_many_arguments)]
+fn fold(
+    repo: &Repository,
+    create: bool,
+    patch: bool,
+    keep_trailers: bool,
+    preview: bool,
+    coauthors: &[Trailer],
+    args: &[String],
+    theme: &graph::Theme,
+) -> Result<()> {
     if create {
-        return run_create(&repo, &args);
+        return run_create(repo, args);
     }
 
     if patch {
-        return run_patch_fold(&repo, &args, theme);
+        return run_patch_fold(repo, args, coauthors, theme);
     }
 
     // Single argument: fold staged files into the target commit
     if args.len() == 1 {
-        return run_staged(&repo, &args[0]);
+        return run_staged(repo, &args[0], coauthors, preview);
     }
 
     // Last argument is the target, everything else is a source
@@ -76,7 +151,7 @@ pub fn run(create: bool, patch: bool, args: Vec<String>, theme: &graph::Theme) -
 
     // If any source is "zz", expand to all changed files (zz takes precedence)
     let source_args = if source_args.iter().any(|s| s == "zz") {
-        let files = collect_changed_files(&repo)?;
+        let files = collect_changed_files(repo)?;
         if files.is_empty() {
             bail!("No changes to fold — working tree is clean");
         }
@@ -90,7 +165,7 @@ pub fn run(create: bool, patch: bool, args: Vec<String>, theme: &graph::Theme) -
         .iter()
         .map(|s| {
             repo::resolve_arg(
-                &repo,
+                repo,
                 s,
                 &[
                     TargetKind::Commit,
@@ -102,7 +177,7 @@ pub fn run(create: bool, patch: bool, args: Vec<String>, theme: &graph::Theme) -
         })
         .collect::<Result<Vec<_>, _>>()?;
     let resolved_target = repo::resolve_arg(
-        &repo,
+        repo,
         target_arg,
         &[
             TargetKind::Branch,
@@ -114,24 +189,61 @@ pub fn run(create: bool, patch: bool, args: Vec<String>, theme: &graph::Theme) -
     )?;
 
     // Classify and dispatch
-    match classify(&resolved_sources, &resolved_target)? {
+    let op = classify(&resolved_sources, &resolved_target)?;
+    if keep_trailers && !matches!(op, FoldOp::CommitIntoCommit { .. }) {
+        bail!("--keep-trailers only applies when folding a commit into another commit");
+    }
+    if !coauthors.is_empty() && !matches!(op, FoldOp::FilesIntoCommit { .. }) {
+        bail!("--coauthor only applies when folding working-tree changes into a commit");
+    }
+    warn_hidden_paths(repo, &fold_paths(repo, &op)?)?;
+    if preview {
+        confirm_preview(repo, &op, false)?;
+    }
+    match op {
+        // Saves the staged changes of the other files itself
         FoldOp::FilesIntoCommit { files, commit } => {
-            fold_files_into_commit(&repo, &files, &commit, false)
+            fold_files_into_commit(repo, &files, &commit, false, coauthors)
+        }
+        FoldOp::CommitIntoCommit { source, target } => keeping_index(repo, || {
+            fold_commit_into_commit(repo, &source, &target, keep_trailers)
+        }),
+        FoldOp::CommitToBranch { commit, branch } => {
+            keeping_index(repo, || fold_commit_to_branch(repo, &commit, &branch))
         }
-        FoldOp::CommitIntoCommit { source, target } => {
-            fold_commit_into_commit(&repo, &source, &target)
+        FoldOp::CommitToUnstaged { commit } => {
+            keeping_index(repo, || fold_commit_to_unstaged(repo, &commit))
         }
-        FoldOp::CommitToBranch { commit, branch } => fold_commit_to_branch(&repo, &commit, &branch),
-        FoldOp::CommitToUnstaged { commit } => fold_commit_to_unstaged(&repo, &commit),
         FoldOp::CommitFileToUnstaged { commit, path } => {
-            fold_commit_file_to_unstaged(&repo, &commit, &path)
+            keeping_index(repo, || fold_commit_file_to_unstaged(repo, &commit, &path))
         }
         FoldOp::CommitFileToCommit {
             source_commit,
             path,
             target_commit,
-        } => fold_commit_file_to_commit(&repo, &source_commit, &path, &target_commit),
+        } => keeping_index(repo, || {
+            fold_commit_file_to_commit(repo, &source_commit, &path, &target_commit)
+        }),
+    }
+}
+
+/// Run a fold that does not take the staged changes with the index saved
+/// aside: they are unstaged first, so neither an amend nor a rebase's
+/// autostash (which restores them unstaged) touches them, and staged again
+/// afterwards. When the fold pauses on a conflict, they go into its
+/// rollback, for `loom continue` or `loom abort` to stage again.
+fn keeping_index(repo: &Repository, fold: impl FnOnce() -> Result<()>) -> Result<()> {
+    let workdir = repo::require_workdir(repo, COMMAND)?;
+    let saved_staged = staging::save_and_unstage_staged(repo, workdir)?;
+    let result = fold();
+    let git_dir = repo.path();
+    if let Ok(Some(mut state)) = transaction::load(git_dir) {
+        state.rollback.saved_staged_patch = saved_staged;
+        transaction::save(git_dir, &state)?;
+    } else {
+        git::restore_staged_patch(workdir, &saved_staged)?;
     }
+    result
 }
 
 /// Create a new branch and move the source commit(s) into it.
@@ -178,9 +290,13 @@ fn run_create(repo: &Repository, args: &[String]) -> Result<()> {
         ));
         // A single commit keeps the resumable commit-to-branch path.
         if commit_hashes.len() == 1 {
-            return fold_commit_to_branch(repo, &commit_hashes[0], branch_name);
+            return keeping_index(repo, || {
+                fold_commit_to_branch(repo, &commit_hashes[0], branch_name)
+            });
         }
-        return move_commits_and_report(workdir, repo, &commit_hashes, branch_name, None);
+        return keeping_index(repo, || {
+            move_commits_and_report(workdir, repo, &commit_hashes, branch_name, None)
+        });
     }
 
     // Create the branch at the merge-base so it has no commits of its own yet;
@@ -194,7 +310,9 @@ fn run_create(repo: &Repository, args: &[String]) -> Result<()> {
         ),
     };
 
-    move_commits_and_report(workdir, repo, &commit_hashes, branch_name, Some(&base_hash))
+    keeping_index(repo, || {
+        move_commits_and_report(workdir, repo, &commit_hashes, branch_name, Some(&base_hash))
+    })
 }
 
 /// Sort commit hashes oldest-first (ancestors before descendants), removing
@@ -294,19 +412,37 @@ fn move_commits_and_report(
 /// - `fold -p [<files>...] <commit>` — pick working-tree hunks, fold into commit
 /// - `fold -p <commit1> <commit2>` — pick hunks from commit1 to move into commit2
 /// - `fold -p <commit> zz` — pick hunks from commit to uncommit to working tree
-fn run_patch_fold(repo: &Repository, args: &[String], theme: &graph::Theme) -> Result<()> {
+fn run_patch_fold(
+    repo: &Repository,
+    args: &[String],
+    coauthors: &[Trailer],
+    theme: &graph::Theme,
+) -> Result<()> {
     let workdir = repo::require_workdir(repo, COMMAND)?;
 
     let (target_arg, source_args) = args.split_last().expect("args is non-empty");
 
-    // Detect commit-source forms: fold -p <commit> <commit|zz>
+    // Detect commit-source forms: fold -p <commit> <commit|zz>, and
+    // fold -p <commit>:<index> zz
     if source_args.len() == 1 {
         let source_arg = &source_args[0];
+        if target_arg == "zz"
+            && let Ok(Target::CommitFile { commit, path }) =
+                repo::resolve_arg(repo, source_arg, &[TargetKind::CommitFile])
+        {
+            if !coauthors.is_empty() {
+                bail!("--coauthor only applies when folding working-tree changes into a commit");
+            }
+            return run_patch_fold_commit_to_unstaged(repo, workdir, &commit, &[path], theme);
+        }
         if let Ok(Target::Commit(source_hash)) =
             repo::resolve_arg(repo, source_arg, &[TargetKind::Commit])
         {
+            if !coauthors.is_empty() {
+                bail!("--coauthor only applies when folding working-tree changes into a commit");
+            }
             if target_arg == "zz" {
-                return run_patch_fold_commit_to_unstaged(repo, workdir, &source_hash, theme);
+                return run_patch_fold_commit_to_unstaged(repo, workdir, &source_hash, &[], theme);
             }
             if let Ok(Target::Commit(target_hash)) =
                 repo::resolve_arg(repo, target_arg, &[TargetKind::Commit])
@@ -356,10 +492,11 @@ fn run_patch_fold(repo: &Repository, args: &[String], theme: &graph::Theme) -> R
     if staged.is_empty() {
         bail!("Nothing to commit");
     }
-    fold_files_into_commit(repo, &staged, &commit_hash, true)
+    fold_files_into_commit(repo, &staged, &commit_hash, true, coauthors)
 }
 
 /// Build a unified diff patch from the selected text hunks across all files.
+/// Binary and Git LFS files are left out.
 fn build_selected_patch(selections: &[FileEntry]) -> String {
     let mut patch = String::new();
     for file in selections {
@@ -372,9 +509,19 @@ fn build_selected_patch(selections: &[FileEntry]) -> String {
             .filter(|h| h.selected)
             .map(|h| &h.hunk)
             .collect();
-        if !selected.is_empty() {
-            patch.push_str(&diff::build_hunk_patch(&file.path, &selected));
+        if selected.is_empty() {
+            continue;
+        }
+        let file_patch = diff::build_hunk_patch(&file.path, &selected);
+        // Hunks of a pointer make no sense: the file moves whole or not at all
+        if !git::lfs_pointers(&file_patch).is_empty() {
+            msg::warn(&format!(
+                "`{}` is a Git LFS file: skipped — fold it without -p",
+                file.path
+            ));
+            continue;
         }
+        patch.push_str(&file_patch);
     }
     patch
 }
@@ -390,6 +537,67 @@ fn apply_and_amend_path(workdir: &Path, patch: &str, path: &str, reverse: bool)
     git::commit_amend_no_edit(workdir)
 }
 
+/// The state to return to when a fold that runs several rebases fails
+/// halfway through.
+struct Snapshot {
+    /// The branch HEAD was on, or `None` if it was detached.
+    head_branch: Option<String>,
+    head: String,
+    refs: std::collections::HashMap<String, git2::Oid>,
+}
+
+impl Snapshot {
+    fn take(repo: &Repository) -> Result<Self> {
+        let head = repo.head()?;
+        Ok(Self {
+            head_branch: head
+                .is_branch()
+                .then(|| head.shorthand().map(str::to_string))
+                .flatten(),
+            head: repo::head_oid(repo)?.to_string(),
+            refs: repo::snapshot_branch_refs(repo)?,
+        })
+    }
+
+    /// Put HEAD, the branch refs, and the working tree back as they were.
+    ///
+    /// Never leaves a rebase in progress: when `git rebase --abort` fails,
+    /// falls back to `git rebase --quit` (which keeps any autostash in the
+    /// stash list) and resets from the snapshot.
+    fn restore(&self, workdir: &Path, git_dir: &Path) -> Result<()> {
+        if git::rebase_is_in_progress(git_dir) {
+            let _ = git::rebase_abort(workdir);
+        }
+        if git::rebase_is_in_progress(git_dir) {
+            let _ = git::rebase_quit(workdir);
+            msg::warn(
+                "Could not abort the rebase: dropped it with `git rebase --quit`\n\
+                 Changes stashed by the rebase are in `git stash list`",
+            );
+        }
+        if git::rebase_is_in_progress(git_dir) {
+            bail!(
+                "A rebase is still in progress\nRun `git rebase --quit` and `git reset --hard {}`",
+                git::short_hash(&self.head)
+            );
+        }
+
+        let _ = git::branch_delete(workdir, TRACK_BRANCH);
+        if let Some(branch) = &self.head_branch {
+            let head_ref = format!("refs/heads/{}", branch);
+            git::run_git(workdir, &["symbolic-ref", "HEAD", &head_ref])?;
+        }
+        git::reset_hard(workdir, &self.head)?;
+        repo::restore_branch_refs(workdir, &self.refs)
+            .context("Could not restore the branch refs")?;
+
+        if git::rev_parse(workdir, "HEAD")? != self.head {
+            bail!("HEAD is not back at `{}`", git::short_hash(&self.head));
+        }
+        Ok(())
+    }
+}
+
 /// At a rebase edit pause: apply (or reverse-apply) patch, stage affected files, amend.
 fn apply_and_amend(
     workdir: &Path,
@@ -456,7 +664,7 @@ fn run_patch_fold_commit_to_commit(
     // Phase 1: edit source, remove selected hunks.
     let mut graph = Weave::from_repo(repo)?;
     graph.edit_commit(source_oid);
-    let todo = graph.to_todo();
+    let todo = graph.checked_todo()?;
     git::branch_force_create(workdir, TRACK_BRANCH, target_hash)?;
 
     if let Err(e) = weave::run_rebase_or_abort(workdir, Some(&graph.base_oid.to_string()), &todo) {
@@ -489,7 +697,7 @@ fn run_patch_fold_commit_to_commit(
     let repo2 = Repository::open(workdir)?;
     let mut graph2 = Weave::from_repo(&repo2)?;
     graph2.edit_commit(phase2_target_oid);
-    let todo2 = graph2.to_todo();
+    let todo2 = graph2.checked_todo()?;
 
     let rollback = |saved_head: &str, saved_refs: &std::collections::HashMap<String, git2::Oid>| {
         let _ = git::reset_hard(workdir, saved_head);
@@ -540,11 +748,22 @@ fn run_patch_fold_commit_to_unstaged(
     repo: &Repository,
     workdir: &Path,
     commit_hash: &str,
+    files: &[String],
     theme: &graph::Theme,
 ) -> Result<()> {
-    let selections = staging::run_commit_hunk_picker(workdir, commit_hash, &[], theme)?
+    let selections = staging::run_commit_hunk_picker(workdir, commit_hash, files, theme)?
         .ok_or_else(|| anyhow::anyhow!("Cancelled"))?;
+    uncommit_hunks(repo, workdir, commit_hash, &selections)
+}
 
+/// Remove the selected hunks of `selections` from a commit and apply them to
+/// the working directory as unstaged modifications.
+fn uncommit_hunks(
+    repo: &Repository,
+    workdir: &Path,
+    commit_hash: &str,
+    selections: &[FileEntry],
+) -> Result<()> {
     if !selections
         .iter()
         .any(|f| f.hunks.iter().any(|h| h.selected))
@@ -552,7 +771,7 @@ fn run_patch_fold_commit_to_unstaged(
         bail!("No hunks selected");
     }
 
-    let selected_patch = build_selected_patch(&selections);
+    let selected_patch = build_selected_patch(selections);
     if selected_patch.is_empty() {
         bail!("No text hunks selected — binary and deleted files are not supported with -p");
     }
@@ -570,7 +789,7 @@ fn run_patch_fold_commit_to_unstaged(
     if is_head {
         let pre_amend_hash = head_oid.to_string();
         git::apply_patch_reverse(workdir, &selected_patch)?;
-        for file in &selections {
+        for file in selections {
             if file.hunks.iter().any(|h| h.selected) {
                 git::stage_path(workdir, &file.path)?;
             }
@@ -589,7 +808,7 @@ fn run_patch_fold_commit_to_unstaged(
 
         let mut graph = Weave::from_repo(repo)?;
         graph.edit_commit(target_oid);
-        let todo = graph.to_todo();
+        let todo = graph.checked_todo()?;
         if let Err(e) =
             weave::run_rebase_or_abort(workdir, Some(&graph.base_oid.to_string()), &todo)
         {
@@ -597,7 +816,7 @@ fn run_patch_fold_commit_to_unstaged(
             return Err(e);
         }
 
-        if let Err(e) = apply_and_amend(workdir, &selections, &selected_patch, true) {
+        if let Err(e) = apply_and_amend(workdir, selections, &selected_patch, true) {
             let _ = git::rebase_abort(workdir);
             let _ = git::restore_staged_patch(workdir, &saved_staged);
             return Err(e);
@@ -632,7 +851,12 @@ fn run_patch_fold_commit_to_unstaged(
 ///
 /// Single-argument form: `loom fold <target>`. The target must resolve to a
 /// commit. If nothing is staged, bails with the same message as `loom commit`.
-fn run_staged(repo: &Repository, target_arg: &str) -> Result<()> {
+fn run_staged(
+    repo: &Repository,
+    target_arg: &str,
+    coauthors: &[Trailer],
+    preview: bool,
+) -> Result<()> {
     let resolved = repo::resolve_arg(repo, target_arg, &[TargetKind::Commit])?;
     let commit_hash = match resolved {
         Target::Commit(hash) => hash,
@@ -643,7 +867,20 @@ fn run_staged(repo: &Repository, target_arg: &str) -> Result<()> {
     if staged.is_empty() {
         bail!("Nothing to commit");
     }
-    fold_files_into_commit(repo, &staged, &commit_hash, true)
+    let mut paths = staged.clone();
+    paths.extend(repo::commit_file_paths(
+        repo,
+        git2::Oid::from_str(&commit_hash)?,
+    )?);
+    warn_hidden_paths(repo, &paths)?;
+    if preview {
+        let op = FoldOp::FilesIntoCommit {
+            files: staged.clone(),
+            commit: commit_hash.clone(),
+        };
+        confirm_preview(repo, &op, true)?;
+    }
+    fold_files_into_commit(repo, &staged, &commit_hash, true, coauthors)
 }
 
 /// The classified fold operation.
@@ -802,6 +1039,202 @@ fn classify(sources: &[Target], target: &Target) -> Result<FoldOp> {
     }
 }
 
+/// The files a fold takes from or puts back into the working tree: the
+/// source files and the files of the commit it rewrites.
+fn fold_paths(repo: &Repository, op: &FoldOp) -> Result<Vec<String>> {
+    let commit_paths = |hash: &str| repo::commit_file_paths(repo, git2::Oid::from_str(hash)?);
+    Ok(match op {
+        FoldOp::FilesIntoCommit { files, commit } => {
+            let mut paths = files.clone();
+            paths.extend(commit_paths(commit)?);
+            paths
+        }
+        FoldOp::CommitIntoCommit { target, .. } => commit_paths(target)?,
+        FoldOp::CommitToBranch { .. } => Vec::new(),
+        FoldOp::CommitToUnstaged { commit } => commit_paths(commit)?,
+        FoldOp::CommitFileToUnstaged { path, .. } => vec![path.clone()],
+        FoldOp::CommitFileToCommit {
+            path,
+            target_commit,
+            ..
+        } => {
+            let mut paths = vec![path.clone()];
+            paths.extend(commit_paths(target_commit)?);
+            paths
+        }
+    })
+}
+
+/// Show the patch a fold is about to move through the pager, and ask for
+/// confirmation. `staged` folds take the index content rather than the
+/// working-tree files.
+fn confirm_preview(repo: &Repository, op: &FoldOp, staged: bool) -> Result<()> {
+    let (summary, patch) = preview(repo, op, staged)?;
+    crate::help::show(&format!("{}\n\n{}", summary.bold(), colorize_patch(&patch)));
+    if !msg::confirm(&format!("{}?", summary))? {
+        bail!("Cancelled");
+    }
+    Ok(())
+}
+
+/// What a fold does, in one line, and the patch it moves.
+fn preview(repo: &Repository, op: &FoldOp, staged: bool) -> Result<(String, String)> {
+    let commit = |hash: &str| -> Result<String> {
+        let commit = repo.find_commit(git2::Oid::from_str(hash)?)?;
+        Ok(format!(
+            "`{}` ({})",
+            git::short_hash(hash),
+            repo::commit_subject(&commit)
+        ))
+    };
+    let head_tree = repo.head()?.peel_to_tree()?;
+    Ok(match op {
+        FoldOp::FilesIntoCommit {
+            files,
+            commit: target,
+        } => {
+            let mut opts = git2::DiffOptions::new();
+            let diff = if staged {
+                repo.diff_tree_to_index(Some(&head_tree), None, Some(&mut opts))?
+            } else {
+                for file in files {
+                    opts.pathspec(file);
+                }
+                opts.disable_pathspec_match(true)
+                    .include_untracked(true)
+                    .recurse_untracked_dirs(true)
+                    .show_untracked_content(true);
+                repo.diff_tree_to_workdir_with_index(Some(&head_tree), Some(&mut opts))?
+            };
+            let count = diff.deltas().len();
+            (
+                format!(
+                    "Fold {} {} into {}",
+                    count,
+                    if count == 1 { "file" } else { "files" },
+                    commit(target)?
+                ),
+                patch_text(&diff)?,
+            )
+        }
+        FoldOp::CommitIntoCommit { source, target } => (
+            format!("Fold {} into {}", commit(source)?, commit(target)?),
+            commit_patch(repo, source, None)?,
+        ),
+        FoldOp::CommitToBranch {
+            commit: hash,
+            branch,
+        } => (
+            format!("Move {} to branch `{}`", commit(hash)?, branch),
+            commit_patch(repo, hash, None)?,
+        ),
+        FoldOp::CommitToUnstaged { commit: hash } => (
+            format!("Uncommit {} to the working tree", commit(hash)?),
+            commit_patch(repo, hash, None)?,
+        ),
+        FoldOp::CommitFileToUnstaged { commit: hash, path } => (
+            format!("Uncommit `{}` from {}", path, commit(hash)?),
+            commit_patch(repo, hash, Some(path))?,
+        ),
+        FoldOp::CommitFileToCommit {
+            source_commit,
+            path,
+            target_commit,
+        } => (
+            format!(
+                "Move `{}` from {} into {}",
+                path,
+                commit(source_commit)?,
+                commit(target_commit)?
+            ),
+            commit_patch(repo, source_commit, Some(path))?,
+        ),
+    })
+}
+
+/// The patch of a commit against its first parent, limited to `path`.
+fn commit_patch(repo: &Repository, hash: &str, path: Option<&str>) -> Result<String> {
+    let commit = repo.find_commit(git2::Oid::from_str(hash)?)?;
+    let parent_tree = match commit.parent(0) {
+        Ok(parent) => Some(parent.tree()?),
+        Err(_) => None,
+    };
+    let mut opts = git2::DiffOptions::new();
+    if let Some(path) = path {
+        opts.pathspec(path).disable_pathspec_match(true);
+    }
+    let diff =
+        repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), Some(&mut opts))?;
+    patch_text(&diff)
+}
+
+fn patch_text(diff: &git2::Diff) -> Result<String> {
+    let mut text = String::new();
+    diff.print(git2::DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            text.push(line.origin());
+        }
+        text.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })?;
+    Ok(text)
+}
+
+/// Color a patch the way `git diff` does.
+fn colorize_patch(patch: &str) -> String {
+    patch
+        .lines()
+        .map(|line| {
+            if line.starts_with("diff --git") || line.starts_with("+++") || line.starts_with("---")
+            {
+                line.bold().to_string()
+            } else if line.starts_with("@@") {
+                line.cyan().to_string()
+            } else if line.starts_with('+') {
+                line.green().to_string()
+            } else if line.starts_with('-') {
+                line.red().to_string()
+            } else {
+                line.to_string()
+            }
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+/// Warn about `paths` whose local changes git hides (skip-worktree or
+/// assume-unchanged): the fold cannot see them, which is easy to miss.
+fn warn_hidden_paths(repo: &Repository, paths: &[String]) -> Result<()> {
+    for (path, flag) in repo::hidden_files(repo)? {
+        if paths.contains(&path) {
+            msg::warn(&format!(
+                "`{}` is marked {}: git hides its local changes from this fold\n\
+                 Clear the flag with `git update-index --no-{} {}`",
+                path,
+                flag.as_str(),
+                flag.as_str(),
+                path
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Warn about the Git LFS files of an uncommitted `diff`: their objects,
+/// often large, are now plain uncommitted files in the working tree.
+fn warn_uncommitted_lfs(diff: &str) {
+    for pointer in git::lfs_pointers(diff) {
+        msg::warn(&format!(
+            "`{}` is a Git LFS file: its {}object is now uncommitted in the working tree",
+            pointer.path,
+            pointer
+                .size
+                .map(|size| format!("{} ", msg::format_size(size)))
+                .unwrap_or_default()
+        ));
+    }
+}
+
 /// Collect all file paths with staged or unstaged changes.
 fn collect_changed_files(repo: &Repository) -> Result<Vec<String>> {
     let mut opts = StatusOptions::new();
@@ -820,11 +1253,14 @@ fn collect_changed_files(repo: &Repository) -> Result<Vec<String>> {
 ///
 /// When `skip_staging` is true the caller has already staged exactly the right
 /// content (e.g. from a hunk picker), so the file-level `git add` is skipped.
+///
+/// `coauthors` are added as trailers to the amended commit.
 fn fold_files_into_commit(
     repo: &Repository,
     files: &[String],
     commit_hash: &str,
     skip_staging: bool,
+    coauthors: &[Trailer],
 ) -> Result<()> {
     let workdir = repo::require_workdir(repo, COMMAND)?;
 
@@ -845,20 +1281,33 @@ fn fold_files_into_commit(
     // Save and unstage any pre-existing staged files not in our target list,
     // so they don't accidentally end up in this commit/amend.
     let saved_staged = staging::save_and_unstage_other_staged(repo, workdir, &file_refs)?;
+    // What was staged of the files themselves, for when the commit fails
+    let saved_target = if skip_staging {
+        String::new()
+    } else {
+        git::diff_cached_files(workdir, &file_refs)?
+    };
+    let restore_index = || {
+        if !skip_staging {
+            let _ = git::unstage_files(workdir, &file_refs);
+            let _ = git::restore_staged_patch(workdir, &saved_target);
+        }
+        let _ = git::restore_staged_patch(workdir, &saved_staged);
+    };
 
     let new_hash;
 
+    if !skip_staging {
+        git::stage_files(workdir, &file_refs)?;
+    }
+    size_check::warn_staged(repo);
+
     if is_head {
-        if !skip_staging {
-            git::stage_files(workdir, &file_refs)?;
-        }
         if let Err(e) = git::commit_amend_no_edit(workdir) {
-            if !skip_staging {
-                let _ = git::unstage_files(workdir, &file_refs);
-            }
-            let _ = git::restore_staged_patch(workdir, &saved_staged);
+            restore_index();
             return Err(e);
         }
+        trailers::amend_head(repo, workdir, coauthors)?;
         git::restore_staged_patch(workdir, &saved_staged)?;
         new_hash = git::rev_parse(workdir, "HEAD")?;
     } else {
@@ -870,14 +1319,8 @@ fn fold_files_into_commit(
         let subject = target_commit.summary().unwrap_or("fixup");
         let message = format!("fixup! {}", subject);
 
-        if !skip_staging {
-            git::stage_files(workdir, &file_refs)?;
-        }
         if let Err(e) = git::commit(workdir, &message) {
-            if !skip_staging {
-                let _ = git::unstage_files(workdir, &file_refs);
-            }
-            let _ = git::restore_staged_patch(workdir, &saved_staged);
+            restore_index();
             return Err(e);
         }
 
@@ -889,22 +1332,29 @@ fn fold_files_into_commit(
         let mut graph = Weave::from_repo(&repo2)?;
         graph.fixup_commit(fixup_oid, target_oid)?;
 
+        // The fixup's message is discarded, so co-authors go on a copy of
+        // the target that is picked in its place.
+        let mut target_oid = target_oid;
+        if let Some(new_oid) = trailers::copy_with_trailers(&repo2, target_oid, coauthors)? {
+            repo::copy_notes(&repo2, target_oid, new_oid)?;
+            graph.replace_commit(target_oid, new_oid);
+            target_oid = new_oid;
+        }
+
         // Track target commit through the rebase via a temp branch.
         // The branch must exist before the rebase AND have an update-ref
         // line in the todo so git keeps it in sync.
-        git::branch_force_create(workdir, TRACK_BRANCH, commit_hash)?;
+        git::branch_force_create(workdir, TRACK_BRANCH, &target_oid.to_string())?;
         graph.track_commit(target_oid, TRACK_BRANCH);
 
         let git_dir = repo.path().to_path_buf();
         let fold_ctx = serde_json::to_value(FoldVariant::FilesIntoCommit {
             original_commit_hash: commit_hash.to_string(),
             files_count: files.len(),
-            saved_staged: saved_staged.clone(),
         })?;
-        // saved_staged is stored in both rollback (for `loom abort`) and context
-        // (for `loom continue` → after_continue). Both paths are required.
         let loom_state = LoomState {
             command: COMMAND.to_string(),
+            refs_before: Default::default(),
             rollback: Rollback {
                 saved_staged_patch: saved_staged.clone(),
                 delete_branches: vec![TRACK_BRANCH.to_string()],
@@ -914,7 +1364,7 @@ fn fold_files_into_commit(
         };
         transaction::save(&git_dir, &loom_state)?;
 
-        let todo = graph.to_todo();
+        let todo = graph.checked_todo()?;
         match weave::run_rebase(workdir, Some(&graph.base_oid.to_string()), &todo)? {
             RebaseOutcome::Completed => {
                 transaction::delete(&git_dir)?;
@@ -940,7 +1390,15 @@ fn fold_files_into_commit(
 }
 
 /// Fold a commit into another commit (Case 2: Commit + Commit → Fixup).
-fn fold_commit_into_commit(repo: &Repository, source_hash: &str, target_hash: &str) -> Result<()> {
+///
+/// With `keep_trailers`, the target is first copied with the source's missing
+/// trailers appended to its message, and the copy is picked in its place.
+fn fold_commit_into_commit(
+    repo: &Repository,
+    source_hash: &str,
+    target_hash: &str,
+    keep_trailers: bool,
+) -> Result<()> {
     let workdir = repo::require_workdir(repo, COMMAND)?;
 
     let source_oid = git2::Oid::from_str(source_hash)?;
@@ -957,8 +1415,19 @@ fn fold_commit_into_commit(repo: &Repository, source_hash: &str, target_hash: &s
     let mut graph = Weave::from_repo(repo)?;
     graph.fixup_commit(source_oid, target_oid)?;
 
+    let mut target_oid = target_oid;
+    if keep_trailers {
+        let source_trailers =
+            trailers::parse(repo.find_commit(source_oid)?.message().unwrap_or(""));
+        if let Some(new_oid) = trailers::copy_with_trailers(repo, target_oid, &source_trailers)? {
+            repo::copy_notes(repo, target_oid, new_oid)?;
+            graph.replace_commit(target_oid, new_oid);
+            target_oid = new_oid;
+        }
+    }
+
     // Track target commit through the rebase via a temp branch.
-    git::branch_force_create(workdir, TRACK_BRANCH, target_hash)?;
+    git::branch_force_create(workdir, TRACK_BRANCH, &target_oid.to_string())?;
     graph.track_commit(target_oid, TRACK_BRANCH);
 
     let git_dir = repo.path().to_path_buf();
@@ -968,6 +1437,7 @@ fn fold_commit_into_commit(repo: &Repository, source_hash: &str, target_hash: &s
     })?;
     let loom_state = LoomState {
         command: COMMAND.to_string(),
+        refs_before: Default::default(),
         rollback: Rollback {
             delete_branches: vec![TRACK_BRANCH.to_string()],
             ..Default::default()
@@ -976,7 +1446,7 @@ fn fold_commit_into_commit(repo: &Repository, source_hash: &str, target_hash: &s
     };
     transaction::save(&git_dir, &loom_state)?;
 
-    let todo = graph.to_todo();
+    let todo = graph.checked_todo()?;
     match weave::run_rebase(workdir, Some(&graph.base_oid.to_string()), &todo)? {
         RebaseOutcome::Completed => {
             transaction::delete(&git_dir)?;
@@ -1008,6 +1478,7 @@ fn fold_commit_to_branch(repo: &Repository, commit_hash: &str, branch_name: &str
     })?;
     let state = LoomState {
         command: COMMAND.to_string(),
+        refs_before: Default::default(),
         rollback: Rollback::default(),
         context: ctx,
     };
@@ -1061,12 +1532,30 @@ pub fn move_commits_to_branch(
     let workdir = repo::require_workdir(repo, COMMAND)?;
 
     let mut graph = Weave::from_repo(repo)?;
+    ensure_branch_section(repo, &mut graph, branch_name)?;
+
+    for commit_hash in commit_hashes {
+        let commit_oid = git2::Oid::from_str(commit_hash)?;
+        graph.move_commit(commit_oid, branch_name)?;
+    }
 
-    // If the target branch has no section in the Weave graph, create one.
-    // This happens when the branch is at the merge-base (no commits of its
-    // own) — either it was never woven, or a previous rebase dropped the
-    // degenerate merge (merging two identical commits is a no-op for git).
-    // Same pattern as commit.rs for empty branches.
+    let todo = graph.checked_todo()?;
+    weave::run_rebase(workdir, Some(&graph.base_oid.to_string()), &todo)
+}
+
+/// Make sure `branch_name` has a section in the weave so commits can be moved
+/// onto it.
+///
+/// If the target branch has no section in the Weave graph, create one.
+/// This happens when the branch is at the merge-base (no commits of its
+/// own) — either it was never woven, or a previous rebase dropped the
+/// degenerate merge (merging two identical commits is a no-op for git).
+/// Same pattern as commit.rs for empty branches.
+pub(crate) fn ensure_branch_section(
+    repo: &Repository,
+    graph: &mut Weave,
+    branch_name: &str,
+) -> Result<()> {
     let has_section = graph
         .branch_sections
         .iter()
@@ -1098,14 +1587,7 @@ pub fn move_commits_to_branch(
         );
         graph.add_merge(branch_name.to_string(), None, None);
     }
-
-    for commit_hash in commit_hashes {
-        let commit_oid = git2::Oid::from_str(commit_hash)?;
-        graph.move_commit(commit_oid, branch_name)?;
-    }
-
-    let todo = graph.to_todo();
-    weave::run_rebase(workdir, Some(&graph.base_oid.to_string()), &todo)
+    Ok(())
 }
 
 /// Uncommit a single file from a commit to the working directory.
@@ -1147,7 +1629,7 @@ fn fold_commit_file_to_unstaged(repo: &Repository, commit_hash: &str, path: &str
         let mut graph = Weave::from_repo(repo)?;
         graph.edit_commit(target_oid);
 
-        let todo = graph.to_todo();
+        let todo = graph.checked_todo()?;
         weave::run_rebase_or_abort(workdir, Some(&graph.base_oid.to_string()), &todo)?;
 
         if let Err(e) = apply_and_amend_path(workdir, &file_diff, path, true) {
@@ -1169,6 +1651,7 @@ fn fold_commit_file_to_unstaged(repo: &Repository, commit_hash: &str, path: &str
         }
     }
 
+    warn_uncommitted_lfs(&file_diff);
     msg::success(&format!(
         "Uncommitted `{}` from `{}` (now `{}`) to working directory",
         path,
@@ -1209,6 +1692,17 @@ fn fold_commit_file_to_commit(
 
     let source_is_newer = repo.graph_descendant_of(source_oid, target_oid)?;
 
+    // Every failure from here on puts the repository back as it was, even
+    // if the rebase it failed in cannot be aborted.
+    let snapshot = Snapshot::take(repo)?;
+    let git_dir = repo.path().to_path_buf();
+    let fail = |e: anyhow::Error| -> anyhow::Error {
+        if let Err(re) = snapshot.restore(workdir, &git_dir) {
+            msg::warn(&format!("Could not roll back: {:#}", re));
+        }
+        e
+    };
+
     let new_source_hash;
     let new_target_hash;
 
@@ -1219,124 +1713,70 @@ fn fold_commit_file_to_commit(
         // conflict when source (newer) is replayed — source still has the file.
         //   Phase 1: Remove the file from source via edit+continue.
         //   Phase 2: Add the file to target via edit+continue.
-        // On phase 2 failure, roll back to pre-phase-1 state.
-        let saved_head = repo::head_oid(repo)?.to_string();
-        let saved_refs = repo::snapshot_branch_refs(repo)?;
-
-        let rollback =
-            |saved_head: &str, saved_refs: &std::collections::HashMap<String, git2::Oid>| {
-                let _ = git::branch_delete(workdir, TRACK_BRANCH);
-                let _ = git::reset_hard(workdir, saved_head);
-                if let Err(re) = repo::restore_branch_refs(workdir, saved_refs) {
-                    msg::warn(&format!("failed to restore branch refs: {re}"));
-                }
-            };
+        // On failure in either phase, roll back to the pre-phase-1 state.
 
         // Phase 1: edit at source, remove file, continue.
         // Create temp branch AFTER from_repo to avoid polluting the Weave graph,
         // but before the rebase so git's --update-refs tracks the target's new OID.
         let mut graph = Weave::from_repo(repo)?;
         graph.edit_commit(source_oid);
-        let todo = graph.to_todo();
+        let todo = graph.checked_todo()?;
         git::branch_force_create(workdir, TRACK_BRANCH, target_hash)?;
 
-        if let Err(e) =
-            weave::run_rebase_or_abort(workdir, Some(&graph.base_oid.to_string()), &todo)
-        {
-            let _ = git::branch_delete(workdir, TRACK_BRANCH);
-            return Err(e);
-        }
-
-        if let Err(e) = apply_and_amend_path(workdir, &file_diff, path, true) {
-            let _ = git::rebase_abort(workdir);
-            let _ = git::branch_delete(workdir, TRACK_BRANCH);
-            return Err(e);
-        }
+        weave::run_rebase_or_abort(workdir, Some(&graph.base_oid.to_string()), &todo)
+            .map_err(fail)?;
+        apply_and_amend_path(workdir, &file_diff, path, true).map_err(fail)?;
 
         // Capture source's new hash before continue moves HEAD;
         // it will be tracked through phase 2 via a temp branch.
-        let phase1_source_hash = git::rev_parse(workdir, "HEAD")?;
-
-        if let Err(e) = git::continue_rebase_or_abort(workdir) {
-            let _ = git::branch_delete(workdir, TRACK_BRANCH);
-            return Err(e);
-        }
+        let phase1_source_hash = git::rev_parse(workdir, "HEAD").map_err(fail)?;
+        git::continue_rebase_or_abort(workdir).map_err(fail)?;
 
         // Phase 2: resolve the target's new OID via the temp branch.
-        let phase2_target_hash = git::rev_parse(workdir, TRACK_BRANCH)?;
+        let phase2_target_hash = git::rev_parse(workdir, TRACK_BRANCH).map_err(fail)?;
         let _ = git::branch_delete(workdir, TRACK_BRANCH);
         let phase2_target_oid = git2::Oid::from_str(&phase2_target_hash)?;
 
         // Re-open repo after phase 1 rebase (OIDs changed)
         let repo2 = Repository::open(workdir)?;
-        let mut graph2 = Weave::from_repo(&repo2)?;
+        let mut graph2 = Weave::from_repo(&repo2).map_err(fail)?;
         graph2.edit_commit(phase2_target_oid);
 
         // Track source through phase 2 — it will be rewritten when the
         // graph is replayed from base_oid.
         let phase1_source_oid = git2::Oid::from_str(&phase1_source_hash)?;
-        git::branch_force_create(workdir, TRACK_BRANCH, &phase1_source_hash)?;
+        git::branch_force_create(workdir, TRACK_BRANCH, &phase1_source_hash).map_err(fail)?;
         graph2.track_commit(phase1_source_oid, TRACK_BRANCH);
 
-        let todo2 = graph2.to_todo();
+        let todo2 = graph2.checked_todo().map_err(fail)?;
+        weave::run_rebase_or_abort(workdir, Some(&graph2.base_oid.to_string()), &todo2)
+            .map_err(fail)?;
+        apply_and_amend_path(workdir, &file_diff, path, false).map_err(fail)?;
 
-        if let Err(e) =
-            weave::run_rebase_or_abort(workdir, Some(&graph2.base_oid.to_string()), &todo2)
-        {
-            rollback(&saved_head, &saved_refs);
-            return Err(e);
-        }
+        new_target_hash = git::rev_parse(workdir, "HEAD").map_err(fail)?;
+        git::continue_rebase_or_abort(workdir).map_err(fail)?;
 
-        if let Err(e) = apply_and_amend_path(workdir, &file_diff, path, false) {
-            let _ = git::rebase_abort(workdir);
-            rollback(&saved_head, &saved_refs);
-            return Err(e);
-        }
-
-        new_target_hash = git::rev_parse(workdir, "HEAD")?;
-
-        if let Err(e) = git::continue_rebase_or_abort(workdir) {
-            rollback(&saved_head, &saved_refs);
-            return Err(e);
-        }
-
-        new_source_hash = git::rev_parse(workdir, TRACK_BRANCH)?;
+        new_source_hash = git::rev_parse(workdir, TRACK_BRANCH).map_err(fail)?;
         let _ = git::branch_delete(workdir, TRACK_BRANCH);
     } else {
         // Source is older than target: single rebase with two edit pauses.
         // Source is picked first (older), target second (newer). Removing
         // the file from source before target is replayed avoids conflicts.
-        let saved_head = repo::head_oid(repo)?.to_string();
-        let saved_refs = repo::snapshot_branch_refs(repo)?;
-
         let mut graph = Weave::from_repo(repo)?;
         graph.edit_commit(source_oid);
         graph.edit_commit(target_oid);
 
-        let todo = graph.to_todo();
-        weave::run_rebase_or_abort(workdir, Some(&graph.base_oid.to_string()), &todo)?;
-
-        if let Err(e) = apply_and_amend_path(workdir, &file_diff, path, true) {
-            let _ = git::rebase_abort(workdir);
-            return Err(e);
-        }
+        let todo = graph.checked_todo()?;
+        weave::run_rebase_or_abort(workdir, Some(&graph.base_oid.to_string()), &todo)
+            .map_err(fail)?;
+        apply_and_amend_path(workdir, &file_diff, path, true).map_err(fail)?;
 
-        new_source_hash = git::rev_parse(workdir, "HEAD")?;
+        new_source_hash = git::rev_parse(workdir, "HEAD").map_err(fail)?;
+        git::continue_rebase_or_abort(workdir).map_err(fail)?;
+        apply_and_amend_path(workdir, &file_diff, path, false).map_err(fail)?;
 
-        git::continue_rebase_or_abort(workdir)?;
-
-        if let Err(e) = apply_and_amend_path(workdir, &file_diff, path, false) {
-            let _ = git::rebase_abort(workdir);
-            let _ = git::reset_hard(workdir, &saved_head);
-            if let Err(re) = repo::restore_branch_refs(workdir, &saved_refs) {
-                msg::warn(&format!("failed to restore branch refs: {re}"));
-            }
-            return Err(e);
-        }
-
-        new_target_hash = git::rev_parse(workdir, "HEAD")?;
-
-        git::continue_rebase_or_abort(workdir)?;
+        new_target_hash = git::rev_parse(workdir, "HEAD").map_err(fail)?;
+        git::continue_rebase_or_abort(workdir).map_err(fail)?;
     }
 
     msg::success(&format!(
@@ -1362,11 +1802,11 @@ fn fold_commit_to_unstaged(repo: &Repository, commit_hash: &str) -> Result<()> {
     let target_oid = git2::Oid::from_str(commit_hash)?;
     let is_head = head_oid == target_oid;
 
+    let diff = git::diff_commit(workdir, commit_hash)?;
     if is_head {
         git::reset_mixed(workdir, "HEAD~1")?;
     } else {
-        // Non-HEAD: capture the diff, drop the commit, then apply the diff
-        let diff = git::diff_commit(workdir, commit_hash)?;
+        // Non-HEAD: drop the commit, then apply the captured diff
         let saved_head = head_oid.to_string();
         let saved_refs = repo::snapshot_branch_refs(repo)?;
 
@@ -1380,12 +1820,13 @@ fn fold_commit_to_unstaged(repo: &Repository, commit_hash: &str) -> Result<()> {
         })?;
         let loom_state = LoomState {
             command: COMMAND.to_string(),
+            refs_before: Default::default(),
             rollback: Rollback::default(),
             context: fold_ctx,
         };
         transaction::save(&git_dir, &loom_state)?;
 
-        let todo = graph.to_todo();
+        let todo = graph.checked_todo()?;
         match weave::run_rebase(workdir, Some(&graph.base_oid.to_string()), &todo)? {
             RebaseOutcome::Completed => {
                 transaction::delete(&git_dir)?;
@@ -1408,6 +1849,7 @@ fn fold_commit_to_unstaged(repo: &Repository, commit_hash: &str) -> Result<()> {
         }
     }
 
+    warn_uncommitted_lfs(&diff);
     msg::success(&format!(
         "Uncommitted `{}` to working directory",
         git::short_hash(commit_hash)
@@ -1417,7 +1859,11 @@ fn fold_commit_to_unstaged(repo: &Repository, commit_hash: &str) -> Result<()> {
 }
 
 /// Resume a `fold` operation after a conflict has been resolved.
-pub fn after_continue(workdir: &Path, context: &serde_json::Value) -> Result<()> {
+pub fn after_continue(
+    workdir: &Path,
+    rollback: &Rollback,
+    context: &serde_json::Value,
+) -> Result<()> {
     let variant: FoldVariant =
         serde_json::from_value(context.clone()).context("Failed to parse fold resume context")?;
 
@@ -1425,11 +1871,9 @@ pub fn after_continue(workdir: &Path, context: &serde_json::Value) -> Result<()>
         FoldVariant::FilesIntoCommit {
             original_commit_hash,
             files_count,
-            saved_staged,
         } => {
             let new_hash = git::rev_parse(workdir, TRACK_BRANCH)?;
             let _ = git::branch_delete(workdir, TRACK_BRANCH);
-            git::restore_staged_patch(workdir, &saved_staged)?;
             msg::success(&format!(
                 "Folded {} file(s) into `{}` (now `{}`)",
                 files_count,
@@ -1482,12 +1926,14 @@ pub fn after_continue(workdir: &Path, context: &serde_json::Value) -> Result<()>
                     patch_path.display()
                 ));
             }
+            warn_uncommitted_lfs(&diff);
             msg::success(&format!(
                 "Uncommitted `{}` to working directory",
                 git::short_hash(&commit_hash)
             ));
         }
     }
+    git::restore_staged_patch(workdir, &rollback.saved_staged_patch)?;
 
     Ok(())
 }
@@ -20,6 +20,7 @@ fn fold_file_into_head() {
         &["file1.txt".to_string()],
         &head_oid.to_string(),
         false,
+        &[],
     );
 
     assert!(
@@ -52,6 +53,7 @@ fn fold_multiple_files_into_head() {
         &["file1.txt".to_string(), "new_file.txt".to_string()],
         &head_oid.to_string(),
         false,
+        &[],
     );
 
     assert!(result.is_ok(), "fold failed: {:?}", result);
@@ -74,6 +76,7 @@ fn fold_file_into_non_head_commit() {
         &["file1.txt".to_string()],
         &c1_oid.to_string(),
         false,
+        &[],
     );
 
     assert!(
@@ -103,6 +106,7 @@ fn fold_file_no_changes_fails() {
         &["file1.txt".to_string()],
         &head_oid.to_string(),
         false,
+        &[],
     );
 
     assert!(result.is_err());
@@ -124,6 +128,7 @@ fn fold_file_into_non_head_with_other_changes_autostashed() {
         &["file1.txt".to_string()],
         &c1_oid.to_string(),
         false,
+        &[],
     );
 
     assert!(
@@ -165,6 +170,7 @@ fn fold_file_into_woven_branch_commit() {
         &["feature1".to_string()],
         &feat1_oid.to_string(),
         false,
+        &[],
     );
 
     assert!(
@@ -236,7 +242,7 @@ fn fold_patch_only_staged_hunk_is_folded_into_head() {
     assert_eq!(staged, vec!["file.txt"]);
 
     let result =
-        super::fold_files_into_commit(&test_repo.repo, &staged, &head_oid.to_string(), true);
+        super::fold_files_into_commit(&test_repo.repo, &staged, &head_oid.to_string(), true, &[]);
     assert!(
         result.is_ok(),
         "fold_files_into_commit failed: {:?}",
@@ -295,7 +301,7 @@ fn fold_patch_only_staged_hunk_is_folded_into_non_head() {
 
     let staged = crate::core::repo::get_staged_files(&test_repo.repo).unwrap();
     let result =
-        super::fold_files_into_commit(&test_repo.repo, &staged, &target_oid.to_string(), true);
+        super::fold_files_into_commit(&test_repo.repo, &staged, &target_oid.to_string(), true, &[]);
     assert!(
         result.is_ok(),
         "fold_files_into_commit failed: {:?}",
@@ -323,8 +329,12 @@ fn fold_commit_into_earlier_commit() {
     let c1_oid = test_repo.commit("Original feature", "feature.txt");
     let c2_oid = test_repo.commit("Fix typo in feature", "feature.txt");
 
-    let result =
-        super::fold_commit_into_commit(&test_repo.repo, &c2_oid.to_string(), &c1_oid.to_string());
+    let result = super::fold_commit_into_commit(
+        &test_repo.repo,
+        &c2_oid.to_string(),
+        &c1_oid.to_string(),
+        false,
+    );
 
     assert!(
         result.is_ok(),
@@ -349,8 +359,12 @@ fn fold_commit_into_commit_preserves_other_commits() {
     let c3_oid = test_repo.commit("Fix for first", "file1.txt");
 
     // Fold c3 into c1 (c3 is the fixup that should be part of c1)
-    let result =
-        super::fold_commit_into_commit(&test_repo.repo, &c3_oid.to_string(), &c1_oid.to_string());
+    let result = super::fold_commit_into_commit(
+        &test_repo.repo,
+        &c3_oid.to_string(),
+        &c1_oid.to_string(),
+        false,
+    );
 
     assert!(result.is_ok(), "fold failed: {:?}", result);
 
@@ -364,8 +378,12 @@ fn fold_commit_same_commit_fails() {
     let test_repo = TestRepo::new();
     let c1_oid = test_repo.commit("First", "file1.txt");
 
-    let result =
-        super::fold_commit_into_commit(&test_repo.repo, &c1_oid.to_string(), &c1_oid.to_string());
+    let result = super::fold_commit_into_commit(
+        &test_repo.repo,
+        &c1_oid.to_string(),
+        &c1_oid.to_string(),
+        false,
+    );
 
     assert!(result.is_err());
     assert!(result.unwrap_err().to_string().contains("same commit"));
@@ -378,8 +396,12 @@ fn fold_commit_wrong_direction_fails() {
     let c2_oid = test_repo.commit("Second", "file2.txt");
 
     // Try to fold the older commit into the newer one (wrong direction)
-    let result =
-        super::fold_commit_into_commit(&test_repo.repo, &c1_oid.to_string(), &c2_oid.to_string());
+    let result = super::fold_commit_into_commit(
+        &test_repo.repo,
+        &c1_oid.to_string(),
+        &c2_oid.to_string(),
+        false,
+    );
 
     assert!(result.is_err());
     assert!(
@@ -399,8 +421,12 @@ fn fold_commit_dirty_working_tree_autostashed() {
     // Dirty the working tree
     test_repo.write_file("file1.txt", "dirty");
 
-    let result =
-        super::fold_commit_into_commit(&test_repo.repo, &c2_oid.to_string(), &c1_oid.to_string());
+    let result = super::fold_commit_into_commit(
+        &test_repo.repo,
+        &c2_oid.to_string(),
+        &c1_oid.to_string(),
+        false,
+    );
 
     assert!(
         result.is_ok(),
@@ -412,6 +438,166 @@ fn fold_commit_dirty_working_tree_autostashed() {
     assert_eq!(test_repo.read_file("file1.txt"), "dirty");
 }
 
+#[test]
+fn fold_commit_keep_trailers_merges_missing_trailers() {
+    let test_repo = TestRepo::new_with_remote();
+    let c1_oid = test_repo.commit(
+        "First\n\nSigned-off-by: Alice <alice@example.com>",
+        "file1.txt",
+    );
+    test_repo.commit("Second", "file2.txt");
+    let c3_oid = test_repo.commit(
+        "Fix first\n\nSigned-off-by: alice <alice@example.com>\n\
+         signed-off-by: Alice <alice@example.com>\n\
+         Co-authored-by: Bob <bob@example.com>",
+        "file1.txt",
+    );
+
+    let result = super::fold_commit_into_commit(
+        &test_repo.repo,
+        &c3_oid.to_string(),
+        &c1_oid.to_string(),
+        true,
+    );
+    assert!(result.is_ok(), "fold failed: {:?}", result);
+
+    assert_eq!(test_repo.get_message(0), "Second");
+    assert_eq!(
+        test_repo.get_message(1),
+        "First\n\n\
+         Signed-off-by: Alice <alice@example.com>\n\
+         Signed-off-by: alice <alice@example.com>\n\
+         Co-authored-by: Bob <bob@example.com>"
+    );
+    assert!(!test_repo.branch_exists(super::TRACK_BRANCH));
+}
+
+#[test]
+fn fold_commit_without_keep_trailers_drops_source_trailers() {
+    let test_repo = TestRepo::new_with_remote();
+    let c1_oid = test_repo.commit("First", "file1.txt");
+    let c2_oid = test_repo.commit(
+        "Fix first\n\nCo-authored-by: Bob <bob@example.com>",
+        "file1.txt",
+    );
+
+    super::fold_commit_into_commit(
+        &test_repo.repo,
+        &c2_oid.to_string(),
+        &c1_oid.to_string(),
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(test_repo.get_message(0), "First");
+}
+
+#[test]
+fn fold_files_coauthor_into_head() {
+    let test_repo = TestRepo::new_with_remote();
+    let head_oid = test_repo.commit("First", "file1.txt");
+    test_repo.write_file("file1.txt", "pair work");
+
+    let coauthor = super::trailers::coauthor("Bob <bob@example.com>").unwrap();
+    super::fold_files_into_commit(
+        &test_repo.repo,
+        &["file1.txt".to_string()],
+        &head_oid.to_string(),
+        false,
+        &[coauthor],
+    )
+    .unwrap();
+
+    assert_eq!(
+        test_repo.get_message(0),
+        "First\n\nCo-authored-by: Bob <bob@example.com>"
+    );
+    assert_eq!(test_repo.read_file("file1.txt"), "pair work");
+    assert_eq!(
+        test_repo.commit_file_paths(test_repo.head_oid()),
+        vec!["file1.txt"]
+    );
+}
+
+#[test]
+fn fold_files_coauthor_into_older_commit() {
+    let test_repo = TestRepo::new_with_remote();
+    let c1_oid = test_repo.commit("First", "file1.txt");
+    test_repo.commit("Second", "file2.txt");
+    test_repo.write_file("file1.txt", "pair work");
+
+    let coauthor = super::trailers::coauthor("Bob <bob@example.com>").unwrap();
+    super::fold_files_into_commit(
+        &test_repo.repo,
+        &["file1.txt".to_string()],
+        &c1_oid.to_string(),
+        false,
+        &[coauthor],
+    )
+    .unwrap();
+
+    assert_eq!(test_repo.get_message(0), "Second");
+    assert_eq!(
+        test_repo.get_message(1),
+        "First\n\nCo-authored-by: Bob <bob@example.com>"
+    );
+    assert!(!test_repo.branch_exists(super::TRACK_BRANCH));
+}
+
+#[test]
+fn coauthor_rejects_commit_source() {
+    let test_repo = TestRepo::new_with_remote();
+    let c1_oid = test_repo.commit("First", "file1.txt");
+    let c2_oid = test_repo.commit("Second", "file1.txt");
+
+    let result = test_repo.in_dir(|| {
+        super::run(
+            false,
+            false,
+            false,
+            false,
+            false,
+            vec!["Bob <bob@example.com>".into()],
+            vec![c2_oid.to_string(), c1_oid.to_string()],
+            &crate::core::graph::Theme::dark(),
+        )
+    });
+
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("--coauthor only applies")
+    );
+}
+
+#[test]
+fn keep_trailers_rejects_non_commit_target() {
+    let test_repo = TestRepo::new_with_remote();
+    let c1_oid = test_repo.commit("First", "file1.txt");
+    test_repo.write_file("file1.txt", "changed");
+
+    let result = test_repo.in_dir(|| {
+        super::run(
+            false,
+            false,
+            true,
+            false,
+            false,
+            vec![],
+            vec!["file1.txt".into(), c1_oid.to_string()],
+            &crate::core::graph::Theme::dark(),
+        )
+    });
+
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("only applies when folding a commit")
+    );
+}
+
 // ── Case 3: Commit + Branch (Move) ──────────────────────────────────────
 
 #[test]
@@ -459,6 +645,25 @@ fn fold_commit_to_branch() {
     );
 }
 
+#[test]
+fn fold_commit_to_own_branch_skips_rebase() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a1.txt");
+    let a2_oid = test_repo.commit("A2", "a2.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-a");
+    let head = test_repo.head_oid();
+
+    // A2 already is the tip of feature-a: nothing to rewrite.
+    super::fold_commit_to_branch(&test_repo.repo, &a2_oid.to_string(), "feature-a").unwrap();
+
+    assert_eq!(test_repo.head_oid(), head);
+    assert_eq!(test_repo.get_branch_target("feature-a"), a2_oid);
+}
+
 #[test]
 fn fold_commit_to_branch_via_short_ids() {
     // Regression: `run()` was missing TargetKind::Branch when resolving the
@@ -492,6 +697,10 @@ fn fold_commit_to_branch_via_short_ids() {
         super::run(
             false,
             false,
+            false,
+            false,
+            false,
+            vec![],
             vec![commit_sid.clone(), branch_sid.clone()],
             &crate::core::graph::Theme::dark(),
         )
@@ -923,6 +1132,10 @@ fn fold_unstaged_into_commit() {
         super::run(
             false,
             false,
+            false,
+            false,
+            false,
+            vec![],
             vec!["zz".into(), "HEAD".into()],
             &crate::core::graph::Theme::dark(),
         )
@@ -943,6 +1156,10 @@ fn fold_unstaged_clean_tree_fails() {
         super::run(
             false,
             false,
+            false,
+            false,
+            false,
+            vec![],
             vec!["zz".into(), "HEAD".into()],
             &crate::core::graph::Theme::dark(),
         )
@@ -1101,6 +1318,157 @@ fn fold_commit_file_to_commit_same_commit_fails() {
     assert!(result.unwrap_err().to_string().contains("same commit"));
 }
 
+/// Install a pre-commit hook that passes `passes` times, then fails.
+fn fail_pre_commit_after(test_repo: &TestRepo, passes: usize) {
+    let hooks = test_repo.repo.path().join("hooks");
+    std::fs::create_dir_all(&hooks).unwrap();
+    let counter = test_repo.repo.path().join("pre-commit-count");
+    let hook = hooks.join("pre-commit");
+    std::fs::write(
+        &hook,
+        format!(
+            "#!/bin/sh\nn=$(cat '{0}' 2>/dev/null || echo 0)\necho $((n + 1)) > '{0}'\n[ \"$n\" -lt {1} ]\n",
+            counter.display(),
+            passes
+        ),
+    )
+    .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+}
+
+/// Woven `feature-a` with C1 (older, target) adding file_a.txt and C2
+/// (newer, source) modifying file_a.txt and adding file_b.txt.
+fn setup_file_move() -> (TestRepo, git2::Oid, git2::Oid) {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.write_file("file_a.txt", "aaa");
+    test_repo.stage_files(&["file_a.txt"]);
+    test_repo.commit_staged("Add file_a");
+    let c1_oid = test_repo.head_oid();
+    test_repo.write_file("file_a.txt", "aaa modified");
+    test_repo.write_file("file_b.txt", "bbb");
+    test_repo.stage_files(&["file_a.txt", "file_b.txt"]);
+    test_repo.commit_staged("Add file_b and modify file_a");
+    let c2_oid = test_repo.head_oid();
+    (test_repo, c1_oid, c2_oid)
+}
+
+fn assert_rolled_back(
+    test_repo: &TestRepo,
+    head: git2::Oid,
+    refs: &std::collections::HashMap<String, git2::Oid>,
+) {
+    assert!(!crate::git::rebase_is_in_progress(test_repo.repo.path()));
+    assert_eq!(test_repo.current_branch_name(), "integration");
+    assert_eq!(test_repo.head_oid(), head);
+    assert_eq!(&repo::snapshot_branch_refs(&test_repo.repo).unwrap(), refs);
+    assert_eq!(test_repo.status_porcelain(), "");
+}
+
+#[test]
+fn fold_commit_file_to_older_commit_rolls_back_phase_1_failure() {
+    let (test_repo, c1_oid, c2_oid) = setup_file_move();
+    let refs = repo::snapshot_branch_refs(&test_repo.repo).unwrap();
+    fail_pre_commit_after(&test_repo, 0);
+
+    let result = super::fold_commit_file_to_commit(
+        &test_repo.repo,
+        &c2_oid.to_string(),
+        "file_a.txt",
+        &c1_oid.to_string(),
+    );
+
+    assert!(result.is_err());
+    assert_rolled_back(&test_repo, c2_oid, &refs);
+}
+
+#[test]
+fn fold_commit_file_to_older_commit_rolls_back_phase_2_failure() {
+    let (test_repo, c1_oid, c2_oid) = setup_file_move();
+    let refs = repo::snapshot_branch_refs(&test_repo.repo).unwrap();
+    fail_pre_commit_after(&test_repo, 1);
+
+    let result = super::fold_commit_file_to_commit(
+        &test_repo.repo,
+        &c2_oid.to_string(),
+        "file_a.txt",
+        &c1_oid.to_string(),
+    );
+
+    assert!(result.is_err());
+    assert_rolled_back(&test_repo, c2_oid, &refs);
+}
+
+#[test]
+fn fold_commit_file_to_newer_commit_rolls_back_second_edit_failure() {
+    let (test_repo, _, c2_oid) = setup_file_move();
+    test_repo.write_file("c3.txt", "ccc");
+    test_repo.stage_files(&["c3.txt"]);
+    test_repo.commit_staged("C3");
+    let head = test_repo.head_oid();
+    let refs = repo::snapshot_branch_refs(&test_repo.repo).unwrap();
+    fail_pre_commit_after(&test_repo, 1);
+
+    let result = super::fold_commit_file_to_commit(
+        &test_repo.repo,
+        &c2_oid.to_string(),
+        "file_b.txt",
+        &head.to_string(),
+    );
+
+    assert!(result.is_err());
+    assert_rolled_back(&test_repo, head, &refs);
+}
+
+#[test]
+fn snapshot_restore_quits_a_rebase_that_cannot_be_aborted() {
+    let (test_repo, c1_oid, c2_oid) = setup_file_move();
+    let refs = repo::snapshot_branch_refs(&test_repo.repo).unwrap();
+    let snapshot = super::Snapshot::take(&test_repo.repo).unwrap();
+
+    // Pause a rebase at C1 and amend it, then break `git rebase --abort`.
+    let mut graph = Weave::from_repo(&test_repo.repo).unwrap();
+    graph.edit_commit(c1_oid);
+    let workdir = test_repo.workdir();
+    crate::core::weave::run_rebase(
+        &workdir,
+        Some(&graph.base_oid.to_string()),
+        &graph.checked_todo().unwrap(),
+    )
+    .unwrap();
+    test_repo.write_file("file_a.txt", "amended");
+    test_repo.stage_files(&["file_a.txt"]);
+    crate::git::commit_amend_no_edit(&workdir).unwrap();
+    let git_dir = test_repo.repo.path().to_path_buf();
+    std::fs::remove_file(git_dir.join("rebase-merge").join("head-name")).unwrap();
+    assert!(crate::git::rebase_abort(&workdir).is_err());
+
+    snapshot.restore(&workdir, &git_dir).unwrap();
+
+    assert_rolled_back(&test_repo, c2_oid, &refs);
+}
+
+#[test]
+fn snapshot_restore_reattaches_head_and_branches() {
+    let (test_repo, c1_oid, c2_oid) = setup_file_move();
+    test_repo.create_branch_at("scratch", &c1_oid.to_string());
+    let refs = repo::snapshot_branch_refs(&test_repo.repo).unwrap();
+    let snapshot = super::Snapshot::take(&test_repo.repo).unwrap();
+
+    test_repo.set_detached_head(c1_oid);
+    test_repo.force_checkout();
+    let workdir = test_repo.workdir();
+    crate::git::branch_force_create(&workdir, "_loom-track", &c1_oid.to_string()).unwrap();
+    crate::git::branch_force_create(&workdir, "scratch", &c2_oid.to_string()).unwrap();
+
+    snapshot.restore(&workdir, test_repo.repo.path()).unwrap();
+
+    assert_rolled_back(&test_repo, c2_oid, &refs);
+}
+
 /// Bug: moving a file from a newer commit to an older commit should
 /// remove the file from source and add it to target. Previously, the
 /// reverse fixup was incorrectly applied to the source, creating a
@@ -1660,7 +2028,7 @@ fn fold_staged_into_head() {
 
     let head_oid = test_repo.head_oid();
 
-    let result = super::run_staged(&test_repo.repo, &head_oid.to_string());
+    let result = super::run_staged(&test_repo.repo, &head_oid.to_string(), &[], false);
     assert!(result.is_ok(), "run_staged failed: {:?}", result);
 
     // HEAD should have been amended
@@ -1676,7 +2044,7 @@ fn fold_staged_nothing_staged_fails() {
 
     let head_oid = test_repo.head_oid();
 
-    let result = super::run_staged(&test_repo.repo, &head_oid.to_string());
+    let result = super::run_staged(&test_repo.repo, &head_oid.to_string(), &[], false);
     assert!(result.is_err());
     assert!(
         result
@@ -1700,7 +2068,7 @@ fn fold_staged_only_uses_staged_not_unstaged() {
 
     let head_oid = test_repo.head_oid();
 
-    let result = super::run_staged(&test_repo.repo, &head_oid.to_string());
+    let result = super::run_staged(&test_repo.repo, &head_oid.to_string(), &[], false);
     assert!(result.is_ok(), "run_staged failed: {:?}", result);
 
     // Only file1.txt should be in the commit; file2.txt should remain as unstaged
@@ -1720,7 +2088,7 @@ fn fold_staged_non_commit_target_fails() {
     test_repo.stage_files(&["file1.txt"]);
 
     // Passing a branch name when only Commit is accepted should fail
-    let result = test_repo.in_dir(|| super::run_staged(&test_repo.repo, "feature-a"));
+    let result = test_repo.in_dir(|| super::run_staged(&test_repo.repo, "feature-a", &[], false));
     assert!(result.is_err(), "should have failed");
     let err_msg = result.unwrap_err().to_string();
     assert!(
@@ -1729,6 +2097,159 @@ fn fold_staged_non_commit_target_fails() {
     );
 }
 
+#[test]
+fn fold_staged_flag_folds_index_content_into_non_head() {
+    let test_repo = TestRepo::new_with_remote();
+    let lines: Vec<String> = (1..=10).map(|i| format!("line {}", i)).collect();
+    test_repo.write_file("notes.txt", &(lines.join("\n") + "\n"));
+    test_repo.stage_files(&["notes.txt"]);
+    test_repo.commit_staged("Notes");
+    let notes_oid = test_repo.head_oid();
+    test_repo.write_file("other.txt", "other");
+    test_repo.stage_files(&["other.txt"]);
+    test_repo.commit_staged("Other");
+
+    // Stage the first line's change, keep the last one's unstaged
+    let mut edited = lines.clone();
+    edited[0] = "line 1 (staged)".to_string();
+    test_repo.write_file("notes.txt", &(edited.join("\n") + "\n"));
+    test_repo.stage_files(&["notes.txt"]);
+    edited[9] = "line 10 (unstaged)".to_string();
+    let worktree = edited.join("\n") + "\n";
+    test_repo.write_file("notes.txt", &worktree);
+
+    let result = test_repo.in_dir(|| {
+        super::run(
+            false,
+            false,
+            false,
+            true,
+            false,
+            vec![],
+            vec![notes_oid.to_string()],
+            &crate::core::graph::Theme::dark(),
+        )
+    });
+    assert!(result.is_ok(), "fold --staged failed: {:?}", result);
+
+    let workdir = test_repo.workdir();
+    let folded = crate::git::run_git_stdout(&workdir, &["show", "HEAD~1:notes.txt"]).unwrap();
+    assert!(folded.starts_with("line 1 (staged)\n"), "{}", folded);
+    assert!(folded.ends_with("line 10\n"), "{}", folded);
+    assert_eq!(test_repo.get_message(1), "Notes");
+    assert_eq!(test_repo.read_file("notes.txt"), worktree);
+    assert_eq!(staged_names(&test_repo), "");
+}
+
+#[test]
+fn fold_staged_flag_rejects_sources() {
+    let test_repo = TestRepo::new_with_remote();
+    let c1_oid = test_repo.commit("First", "file1.txt");
+    test_repo.write_file("file1.txt", "changed");
+
+    let result = test_repo.in_dir(|| {
+        super::run(
+            false,
+            false,
+            false,
+            true,
+            false,
+            vec![],
+            vec!["file1.txt".into(), c1_oid.to_string()],
+            &crate::core::graph::Theme::dark(),
+        )
+    });
+
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("single target commit")
+    );
+}
+
+// ── Staged changes survive folds that don't take them ───────────────────
+
+fn staged_names(test_repo: &TestRepo) -> String {
+    crate::git::run_git_stdout(&test_repo.workdir(), &["diff", "--cached", "--name-only"])
+        .unwrap()
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn fold_commit_into_commit_keeps_index() {
+    let test_repo = TestRepo::new_with_remote();
+    let c1_oid = test_repo.commit("First", "file1.txt");
+    test_repo.commit("Second", "file2.txt");
+    let c3_oid = test_repo.commit("Fix for first", "file1.txt");
+    test_repo.write_file("file2.txt", "staged edit");
+    test_repo.stage_files(&["file2.txt"]);
+
+    // The rebase's autostash alone would bring the edit back unstaged
+    super::keeping_index(&test_repo.repo, || {
+        super::fold_commit_into_commit(
+            &test_repo.repo,
+            &c3_oid.to_string(),
+            &c1_oid.to_string(),
+            false,
+        )
+    })
+    .unwrap();
+
+    assert_eq!(test_repo.get_message(0), "Second");
+    assert_eq!(staged_names(&test_repo), "file2.txt");
+    assert_eq!(test_repo.read_file("file2.txt"), "staged edit");
+}
+
+#[test]
+fn fold_commit_file_to_unstaged_head_keeps_index() {
+    let test_repo = TestRepo::new();
+    test_repo.write_file("file1.txt", "content1");
+    test_repo.write_file("file2.txt", "content2");
+    test_repo.stage_files(&["file1.txt", "file2.txt"]);
+    test_repo.commit_staged("Two files");
+    test_repo.write_file("staged.txt", "staged");
+    test_repo.stage_files(&["staged.txt"]);
+    let head_oid = test_repo.head_oid();
+
+    super::keeping_index(&test_repo.repo, || {
+        super::fold_commit_file_to_unstaged(&test_repo.repo, &head_oid.to_string(), "file1.txt")
+    })
+    .unwrap();
+
+    // The amend did not pick up the staged file, which is still staged
+    let repo = git2::Repository::open(test_repo.workdir()).unwrap();
+    assert_eq!(
+        repo::commit_file_paths(&repo, repo::head_oid(&repo).unwrap()).unwrap(),
+        vec!["file2.txt"]
+    );
+    assert_eq!(staged_names(&test_repo), "staged.txt");
+}
+
+#[test]
+fn paused_fold_keeps_index_in_rollback() {
+    let test_repo = TestRepo::new_with_remote();
+    let a_oid = test_repo.commit("version-a", "shared.txt");
+    test_repo.write_file("shared.txt", "version-b");
+    test_repo.stage_files(&["shared.txt"]);
+    test_repo.commit_staged("Commit B");
+    test_repo.write_file("staged.txt", "staged");
+    test_repo.stage_files(&["staged.txt"]);
+
+    // Dropping A leaves B modifying a file that no longer exists
+    super::keeping_index(&test_repo.repo, || {
+        super::fold_commit_to_unstaged(&test_repo.repo, &a_oid.to_string())
+    })
+    .unwrap();
+    let git_dir = test_repo.repo.path().to_path_buf();
+    let state = crate::core::transaction::load(&git_dir).unwrap().unwrap();
+    assert!(state.rollback.saved_staged_patch.contains("staged.txt"));
+
+    crate::core::transaction::abort_cmd(&test_repo.workdir(), &git_dir).unwrap();
+    assert_eq!(staged_names(&test_repo), "staged.txt");
+}
+
 // ── Abort preserves working state ────────────────────────────────────────
 
 /// Regression: loom abort after a fold conflict must preserve staged changes
@@ -1761,6 +2282,7 @@ fn fold_abort_preserves_working_state() {
         &["shared.txt".to_string()],
         &a_oid.to_string(),
         false,
+        &[],
     );
     assert!(
         result.is_ok(),
@@ -1789,3 +2311,282 @@ fn fold_abort_preserves_working_state() {
     );
     assert_eq!(test_repo.read_file("new-file.txt"), "new-content");
 }
+
+#[test]
+fn fold_commit_to_later_branch_keeps_earlier_commits() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    for name in ["feature-a", "feature-b"] {
+        test_repo.create_branch_at(name, &base_oid.to_string());
+    }
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a1.txt");
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1", "b1.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-a");
+    let merge_a = test_repo.head_oid();
+    test_repo.merge_no_ff("feature-b");
+    let x_oid = test_repo.commit("X", "x.txt");
+    let a_tip = test_repo.get_branch_target("feature-a");
+
+    super::fold_commit_to_branch(&test_repo.repo, &x_oid.to_string(), "feature-b").unwrap();
+
+    assert_eq!(test_repo.get_branch_target("feature-a"), a_tip);
+    let head = test_repo.find_commit(test_repo.head_oid());
+    assert_eq!(head.parent_id(0).unwrap(), merge_a);
+    let b_tip = test_repo.find_commit(test_repo.get_branch_target("feature-b"));
+    assert_eq!(b_tip.summary(), Some("X"));
+}
+
+/// Content of an LFS pointer file.
+fn lfs_pointer(oid_char: char, size: usize) -> String {
+    format!(
+        "version https://git-lfs.github.com/spec/v1\noid sha256:{}\nsize {}\n",
+        oid_char.to_string().repeat(64),
+        size
+    )
+}
+
+/// Reverse each line, like the `rev` filter standing in for git-lfs.
+fn rev_lines(text: &str) -> String {
+    text.lines()
+        .map(|l| format!("{}\n", l.chars().rev().collect::<String>()))
+        .collect()
+}
+
+/// Repo where `*.bin` goes through an `lfs` filter: `rev` both ways, so
+/// the index holds pointers while the working tree holds other content.
+/// `data.bin` is added, then changed by the HEAD commit.
+#[cfg(unix)]
+fn setup_lfs() -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.set_config("filter.lfs.clean", "rev");
+    test_repo.set_config("filter.lfs.smudge", "rev");
+    test_repo.write_file(".gitattributes", "*.bin filter=lfs\n");
+    test_repo.stage_files(&[".gitattributes"]);
+    test_repo.commit_staged("Track bin files with LFS");
+    for (message, oid_char, size) in [("Add data", 'a', 100), ("Update data", 'b', 200)] {
+        test_repo.write_file("data.bin", &rev_lines(&lfs_pointer(oid_char, size)));
+        test_repo.stage_files(&["data.bin"]);
+        test_repo.commit_staged(message);
+    }
+    test_repo
+}
+
+#[cfg(unix)]
+#[test]
+fn fold_lfs_file_to_unstaged_keeps_worktree_content() {
+    let test_repo = setup_lfs();
+    let head = test_repo.head_oid();
+
+    test_repo
+        .in_dir(|| {
+            super::fold_commit_file_to_unstaged(&test_repo.repo, &head.to_string(), "data.bin")
+        })
+        .unwrap();
+
+    // The working tree keeps the smudged content, not the pointer text
+    assert_eq!(
+        test_repo.read_file("data.bin"),
+        rev_lines(&lfs_pointer('b', 200))
+    );
+    let new_head = test_repo.head_oid();
+    assert_eq!(
+        read_file_from_commit(&test_repo.repo, new_head, "data.bin"),
+        lfs_pointer('a', 100)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn fold_lfs_commit_to_unstaged_non_head() {
+    let test_repo = setup_lfs();
+    let update = test_repo.head_oid();
+    test_repo.write_file("other.txt", "other");
+    test_repo.stage_files(&["other.txt"]);
+    test_repo.commit_staged("Other");
+
+    test_repo
+        .in_dir(|| super::fold_commit_to_unstaged(&test_repo.repo, &update.to_string()))
+        .unwrap();
+
+    assert_eq!(
+        test_repo.read_file("data.bin"),
+        rev_lines(&lfs_pointer('b', 200))
+    );
+    assert_eq!(
+        read_file_from_commit(&test_repo.repo, test_repo.head_oid(), "data.bin"),
+        lfs_pointer('a', 100)
+    );
+}
+
+// ── fold -p <commit>:<index> zz — per-hunk uncommit ─────────────────────
+
+/// Commit `file.txt` with two distant changes plus `other.txt`, and return
+/// the commit OID.
+fn commit_two_hunks(test_repo: &TestRepo) -> git2::Oid {
+    let initial = "line 1\nline 2\nline 3\nline 4\nline 5\n\
+                   line 6\nline 7\nline 8\nline 9\nline 10\n\
+                   line 11\nline 12\nline 13\nline 14\nline 15\n";
+    test_repo.write_file("file.txt", initial);
+    test_repo.stage_files(&["file.txt"]);
+    test_repo.commit_staged("initial");
+
+    let modified = initial
+        .replace("line 2\n", "MODIFIED TOP\n")
+        .replace("line 14\n", "MODIFIED BOTTOM\n");
+    test_repo.write_file("file.txt", &modified);
+    test_repo.write_file("other.txt", "other\n");
+    test_repo.stage_files(&["file.txt", "other.txt"]);
+    test_repo.commit_staged("Two hunks");
+    test_repo.head_oid()
+}
+
+/// Uncommit the first hunk of `file.txt` from `oid`.
+fn uncommit_first_hunk(test_repo: &TestRepo, oid: git2::Oid) {
+    let workdir = test_repo.workdir();
+    let mut entries = crate::core::staging::collect_commit_hunks(
+        &workdir,
+        &oid.to_string(),
+        &["file.txt".to_string()],
+    )
+    .unwrap();
+    assert_eq!(entries.len(), 1, "only the picked file is offered");
+    assert_eq!(entries[0].hunks.len(), 2);
+    entries[0].hunks[0].selected = true;
+
+    test_repo
+        .in_dir(|| super::uncommit_hunks(&test_repo.repo, &workdir, &oid.to_string(), &entries))
+        .unwrap();
+}
+
+#[test]
+fn uncommit_hunk_of_file_from_head() {
+    let test_repo = TestRepo::new();
+    let oid = commit_two_hunks(&test_repo);
+
+    uncommit_first_hunk(&test_repo, oid);
+
+    let head = test_repo.head_oid();
+    let committed = read_file_from_commit(&test_repo.repo, head, "file.txt");
+    assert!(!committed.contains("MODIFIED TOP"));
+    assert!(committed.contains("MODIFIED BOTTOM"));
+    assert_eq!(
+        read_file_from_commit(&test_repo.repo, head, "other.txt"),
+        "other\n"
+    );
+    let worktree = test_repo.read_file("file.txt");
+    assert!(worktree.contains("MODIFIED TOP"));
+    assert!(worktree.contains("MODIFIED BOTTOM"));
+    assert!(
+        repo::get_staged_files(&test_repo.repo).unwrap().is_empty(),
+        "uncommitted hunks land unstaged"
+    );
+}
+
+#[test]
+fn uncommit_hunk_of_file_from_non_head() {
+    let test_repo = TestRepo::new_with_remote();
+    let oid = commit_two_hunks(&test_repo);
+    test_repo.write_file("later.txt", "later\n");
+    test_repo.stage_files(&["later.txt"]);
+    test_repo.commit_staged("Later");
+
+    uncommit_first_hunk(&test_repo, oid);
+
+    let head = test_repo.head_oid();
+    assert_eq!(test_repo.get_message(0), "Later");
+    let committed = read_file_from_commit(&test_repo.repo, head, "file.txt");
+    assert!(!committed.contains("MODIFIED TOP"));
+    assert!(committed.contains("MODIFIED BOTTOM"));
+    assert!(test_repo.read_file("file.txt").contains("MODIFIED TOP"));
+}
+
+// ── --preview ───────────────────────────────────────────────────────────
+
+#[test]
+fn preview_files_into_commit_includes_new_files() {
+    let test_repo = TestRepo::new();
+    let head = test_repo.commit("First", "file1.txt");
+    test_repo.write_file("file1.txt", "changed\n");
+    test_repo.write_file("new.txt", "brand new\n");
+    test_repo.write_file("other.txt", "not folded\n");
+
+    let op = super::FoldOp::FilesIntoCommit {
+        files: vec!["file1.txt".into(), "new.txt".into()],
+        commit: head.to_string(),
+    };
+    let (summary, patch) = super::preview(&test_repo.repo, &op, false).unwrap();
+
+    assert!(summary.starts_with("Fold 2 files into `"), "{}", summary);
+    assert!(summary.ends_with("(First)"), "{}", summary);
+    assert!(patch.contains("+changed"), "{}", patch);
+    assert!(patch.contains("+brand new"), "{}", patch);
+    assert!(!patch.contains("not folded"), "{}", patch);
+}
+
+#[test]
+fn preview_staged_shows_index_content() {
+    let test_repo = TestRepo::new();
+    let head = test_repo.commit("First", "file1.txt");
+    test_repo.write_file("file1.txt", "staged\n");
+    test_repo.stage_files(&["file1.txt"]);
+    test_repo.write_file("file1.txt", "unstaged\n");
+
+    let op = super::FoldOp::FilesIntoCommit {
+        files: vec!["file1.txt".into()],
+        commit: head.to_string(),
+    };
+    let (summary, patch) = super::preview(&test_repo.repo, &op, true).unwrap();
+
+    assert!(summary.starts_with("Fold 1 file into `"), "{}", summary);
+    assert!(patch.contains("+staged"), "{}", patch);
+    assert!(!patch.contains("unstaged"), "{}", patch);
+}
+
+#[test]
+fn preview_commit_file_shows_only_that_file() {
+    let test_repo = TestRepo::new();
+    test_repo.write_file("a.txt", "a\n");
+    test_repo.write_file("b.txt", "b\n");
+    test_repo.stage_files(&["a.txt", "b.txt"]);
+    test_repo.commit_staged("Both");
+    let head = test_repo.head_oid();
+
+    let op = super::FoldOp::CommitFileToUnstaged {
+        commit: head.to_string(),
+        path: "b.txt".into(),
+    };
+    let (summary, patch) = super::preview(&test_repo.repo, &op, false).unwrap();
+
+    assert!(
+        summary.starts_with("Uncommit `b.txt` from `"),
+        "{}",
+        summary
+    );
+    assert!(patch.contains("+++ b/b.txt"), "{}", patch);
+    assert!(!patch.contains("a.txt"), "{}", patch);
+}
+
+#[test]
+fn preview_rejects_patch_mode() {
+    let test_repo = TestRepo::new();
+    let head = test_repo.commit("First", "file1.txt");
+
+    let err = test_repo
+        .in_dir(|| {
+            super::run(
+                false,
+                true,
+                false,
+                false,
+                true,
+                vec![],
+                vec![head.to_string()],
+                &crate::core::graph::Theme::dark(),
+            )
+        })
+        .unwrap_err();
+    assert!(err.to_string().contains("--preview cannot be combined"));
+}
@@ -0,0 +1,218 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Duration, Local, NaiveDateTime, TimeZone};
+use colored::Colorize;
+use git2::Repository;
+use serde::Serialize;
+
+use crate::core::{ancestry, msg, oplog, repo, transaction, weave};
+use crate::{activity, branch, check, meta, state, trace};
+
+/// What `loom gc` removed (or would remove) from one kind of loom state.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Pruned {
+    /// What was removed, e.g. `3 trace logs`.
+    pub what: String,
+    /// Disk space given back. Refs are counted as taking none.
+    pub bytes: u64,
+}
+
+/// Prune the state loom keeps for itself: trace logs, operation log and
+/// activity log entries older than `days` (by default `loom.gcDays`), the
+/// cached ancestry of commits and check results of trees that no longer
+/// exist, and the refs left over by interrupted operations. With `dry_run`,
+/// only report what would go.
+pub fn run(days: Option<i64>, dry_run: bool) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let days = days.unwrap_or_else(|| repo::gc_days(&repo));
+    let pruned = prune(&repo, Local::now() - Duration::days(days), dry_run)?;
+    if pruned.is_empty() {
+        msg::success("Nothing to prune");
+        return Ok(());
+    }
+
+    for p in &pruned {
+        if p.bytes > 0 {
+            println!(
+                "  {} {}",
+                p.what,
+                format!("({})", msg::format_size(p.bytes)).dimmed()
+            );
+        } else {
+            println!("  {}", p.what);
+        }
+    }
+    let total = msg::format_size(pruned.iter().map(|p| p.bytes).sum());
+    if dry_run {
+        msg::notice(&format!("Would reclaim {}", total));
+    } else {
+        msg::success(&format!("Reclaimed {}", total));
+    }
+    Ok(())
+}
+
+/// Prune what is older than `before`, and what no longer serves a purpose.
+/// Returns one [`Pruned`] per kind of state that had something to remove.
+pub fn prune(repo: &Repository, before: DateTime<Local>, dry_run: bool) -> Result<Vec<Pruned>> {
+    let git_dir = repo.path();
+    if transaction::load(git_dir)?.is_some() {
+        bail!(
+            "A loom operation is paused\n\
+             Run `loom continue` or `loom abort` before pruning its state"
+        );
+    }
+
+    let mut pruned = Vec::new();
+    let mut add = |count: usize, noun: &str, bytes: u64| {
+        if count > 0 {
+            pruned.push(Pruned {
+                what: format!("{} {}", count, plural(count, noun)),
+                bytes,
+            });
+        }
+    };
+
+    let (count, bytes) = prune_trace_logs(git_dir, before, dry_run)?;
+    add(count, "trace log", bytes);
+
+    let (count, bytes) = prune_log(
+        &oplog::log_path(git_dir),
+        oplog::load(git_dir),
+        |e| &e.time,
+        before,
+        dry_run,
+    )?;
+    add(count, "operation log entry", bytes);
+
+    let (count, bytes) = prune_log(
+        &activity::log_path(git_dir),
+        activity::load(git_dir),
+        |e| &e.time,
+        before,
+        dry_run,
+    )?;
+    add(count, "activity log entry", bytes);
+
+    let (count, bytes) = ancestry::prune(repo, dry_run)?;
+    add(count, "cached ancestry entry", bytes);
+
+    let (count, bytes) = check::prune(repo, dry_run)?;
+    add(count, "cached check result", bytes);
+
+    let count = prune_refs(repo, dry_run)?;
+    add(count, "leftover ref", 0);
+
+    Ok(pruned)
+}
+
+/// Delete the trace logs started before `before`. The latest one is kept
+/// for `loom trace`, however old.
+fn prune_trace_logs(
+    git_dir: &Path,
+    before: DateTime<Local>,
+    dry_run: bool,
+) -> Result<(usize, u64)> {
+    let latest = trace::latest_log_path(git_dir);
+    let Ok(entries) = std::fs::read_dir(git_dir.join("loom").join("logs")) else {
+        return Ok((0, 0));
+    };
+    let mut count = 0;
+    let mut bytes = 0;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let started = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| NaiveDateTime::parse_from_str(stem, "%Y-%m-%d_%H-%M-%S_%3f").ok())
+            .and_then(|time| Local.from_local_datetime(&time).single());
+        if Some(&path) == latest.as_ref() || started.is_none_or(|t| t >= before) {
+            continue;
+        }
+        bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        count += 1;
+        if !dry_run {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok((count, bytes))
+}
+
+/// Remove the entries of a JSON-lines log recorded before `before`. Entries
+/// whose time can't be read are kept.
+fn prune_log<E: Serialize>(
+    path: &Path,
+    entries: Vec<E>,
+    time: impl Fn(&E) -> &str,
+    before: DateTime<Local>,
+    dry_run: bool,
+) -> Result<(usize, u64)> {
+    let (old, kept): (Vec<E>, Vec<E>) = entries
+        .into_iter()
+        .partition(|e| DateTime::parse_from_rfc3339(time(e)).is_ok_and(|t| t < before));
+    let bytes = old
+        .iter()
+        .filter_map(|e| serde_json::to_string(e).ok())
+        .map(|line| line.len() as u64 + 1)
+        .sum();
+    if !dry_run && !old.is_empty() {
+        let lines: Vec<String> = kept
+            .iter()
+            .filter_map(|e| serde_json::to_string(e).ok())
+            .collect();
+        if lines.is_empty() {
+            std::fs::remove_file(path)?;
+        } else {
+            std::fs::write(path, lines.join("\n") + "\n")?;
+        }
+    }
+    Ok((old.len(), bytes))
+}
+
+/// Delete the temporary refs of operations that were interrupted before
+/// cleaning up after themselves, and the `update --only` bases recorded
+/// for branches that no longer exist.
+fn prune_refs(repo: &Repository, dry_run: bool) -> Result<usize> {
+    let mut stale = Vec::new();
+    for prefix in [
+        branch::attach::ATTACH_PREFIX,
+        state::IMPORT_PREFIX,
+        weave::RETAG_PREFIX,
+    ] {
+        for reference in repo.references_glob(&format!("{}*", prefix))? {
+            stale.extend(reference?.name().map(str::to_string));
+        }
+    }
+    if repo.find_reference(meta::INCOMING_NOTES_REF).is_ok() {
+        stale.push(meta::INCOMING_NOTES_REF.to_string());
+    }
+    for reference in repo.references_glob(&format!("{}*", repo::BRANCH_BASE_PREFIX))? {
+        let reference = reference?;
+        let Some(name) = reference.name() else {
+            continue;
+        };
+        let branch = name.trim_start_matches(repo::BRANCH_BASE_PREFIX);
+        if repo.find_branch(branch, git2::BranchType::Local).is_err() {
+            stale.push(name.to_string());
+        }
+    }
+
+    if !dry_run {
+        for name in &stale {
+            repo.find_reference(name)?.delete()?;
+        }
+    }
+    Ok(stale.len())
+}
+
+fn plural(n: usize, noun: &str) -> String {
+    match (n, noun.strip_suffix('y')) {
+        (1, _) => noun.to_string(),
+        (_, Some(stem)) => format!("{}ies", stem),
+        (_, None) => format!("{}s", noun),
+    }
+}
+
+#[cfg(test)]
+#[path = "gc_test.rs"]
+mod tests;
@@ -0,0 +1,144 @@
+use chrono::{Duration, Local};
+
+use crate::activity;
+use crate::core::oplog;
+use crate::core::test_helpers::TestRepo;
+
+/// Write an empty trace log named like the ones `loom trace` reads.
+fn write_log(test_repo: &TestRepo, name: &str) {
+    let dir = test_repo.repo.path().join("loom").join("logs");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join(name), "log\n").unwrap();
+}
+
+fn log_names(test_repo: &TestRepo) -> Vec<String> {
+    let dir = test_repo.repo.path().join("loom").join("logs");
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+fn month_ago() -> chrono::DateTime<Local> {
+    Local::now() - Duration::days(30)
+}
+
+#[test]
+fn prunes_old_trace_logs_but_keeps_the_latest() {
+    let test_repo = TestRepo::new();
+    write_log(&test_repo, "2020-01-01_10-00-00_000.log");
+    write_log(&test_repo, "2020-01-02_10-00-00_000.log");
+
+    let pruned = super::prune(&test_repo.repo, month_ago(), false).unwrap();
+
+    assert_eq!(pruned.len(), 1);
+    assert_eq!(pruned[0].what, "1 trace log");
+    assert_eq!(pruned[0].bytes, 4);
+    assert_eq!(log_names(&test_repo), vec!["2020-01-02_10-00-00_000.log"]);
+
+    let recent = Local::now().format("%Y-%m-%d_%H-%M-%S_%3f.log").to_string();
+    write_log(&test_repo, &recent);
+    super::prune(&test_repo.repo, month_ago(), false).unwrap();
+    assert_eq!(log_names(&test_repo), vec![recent]);
+}
+
+#[test]
+fn prunes_old_log_entries() {
+    let test_repo = TestRepo::new();
+    let git_dir = test_repo.repo.path();
+    let old = (Local::now() - Duration::days(60)).to_rfc3339();
+    let now = Local::now().to_rfc3339();
+    for time in [&old, &old, &now] {
+        oplog::append(
+            git_dir,
+            &oplog::Entry {
+                time: time.clone(),
+                rewritten: vec![("a".to_string(), "b".to_string())],
+            },
+        )
+        .unwrap();
+    }
+    for time in [&old, &now] {
+        activity::append(
+            git_dir,
+            &activity::Entry {
+                time: time.clone(),
+                command: "status".to_string(),
+                duration_ms: 10,
+                success: true,
+            },
+        )
+        .unwrap();
+    }
+
+    let pruned = super::prune(&test_repo.repo, month_ago(), false).unwrap();
+
+    let what: Vec<&str> = pruned.iter().map(|p| p.what.as_str()).collect();
+    assert_eq!(
+        what,
+        vec!["2 operation log entries", "1 activity log entry"]
+    );
+    assert_eq!(oplog::load(git_dir).len(), 1);
+    assert_eq!(oplog::load(git_dir)[0].time, now);
+    assert_eq!(activity::load(git_dir).len(), 1);
+    assert_eq!(activity::load(git_dir)[0].time, now);
+}
+
+#[test]
+fn deletes_leftover_refs_and_bases_of_deleted_branches() {
+    let test_repo = TestRepo::new();
+    let head = test_repo.head_oid();
+    test_repo.create_branch("feature-a");
+    let repo = &test_repo.repo;
+    repo.reference("refs/loom/attach/feature-x", head, true, "test")
+        .unwrap();
+    repo.reference("refs/loom/retag/v1.0", head, true, "test")
+        .unwrap();
+    crate::core::repo::set_branch_base(repo, "feature-a", head).unwrap();
+    crate::core::repo::set_branch_base(repo, "deleted", head).unwrap();
+
+    let pruned = super::prune(repo, month_ago(), false).unwrap();
+
+    assert_eq!(pruned.len(), 1);
+    assert_eq!(pruned[0].what, "3 leftover refs");
+    assert!(repo.find_reference("refs/loom/attach/feature-x").is_err());
+    assert!(repo.find_reference("refs/loom/retag/v1.0").is_err());
+    assert!(repo.find_reference("refs/loom/base/deleted").is_err());
+    assert!(repo.find_reference("refs/loom/base/feature-a").is_ok());
+}
+
+#[test]
+fn dry_run_removes_nothing() {
+    let test_repo = TestRepo::new();
+    let head = test_repo.head_oid();
+    write_log(&test_repo, "2020-01-01_10-00-00_000.log");
+    write_log(&test_repo, "2020-01-02_10-00-00_000.log");
+    test_repo
+        .repo
+        .reference("refs/loom/attach/feature-x", head, true, "test")
+        .unwrap();
+
+    let pruned = super::prune(&test_repo.repo, month_ago(), true).unwrap();
+
+    assert_eq!(pruned.len(), 2);
+    assert_eq!(log_names(&test_repo).len(), 2);
+    assert!(
+        test_repo
+            .repo
+            .find_reference("refs/loom/attach/feature-x")
+            .is_ok()
+    );
+}
+
+#[test]
+fn nothing_to_prune_in_a_fresh_repository() {
+    let test_repo = TestRepo::new();
+
+    assert!(
+        super::prune(&test_repo.repo, month_ago(), false)
+            .unwrap()
+            .is_empty()
+    );
+}
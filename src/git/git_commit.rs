@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 
 /// Amend the current commit, optionally replacing its message.
 ///
@@ -11,10 +11,14 @@ pub fn commit_amend(workdir: &Path, message: Option<&str>) -> Result<()> {
     if let Some(msg) = message {
         super::run_git(
             workdir,
-            &["commit", "--allow-empty", "--amend", "--only", "-m", msg],
+            &super::verified_args(&["commit", "--allow-empty", "--amend", "--only", "-m", msg]),
         )
     } else {
-        super::run_git_interactive(workdir, &["commit", "--allow-empty", "--amend", "--only"])
+        crate::core::msg::require_editor("the commit message", "Pass it with `-m`")?;
+        super::run_git_interactive(
+            workdir,
+            &super::verified_args(&["commit", "--allow-empty", "--amend", "--only"]),
+        )
     }
 }
 
@@ -25,7 +29,7 @@ pub fn commit_amend(workdir: &Path, message: Option<&str>) -> Result<()> {
 pub fn commit_amend_no_edit(workdir: &Path) -> Result<()> {
     super::run_git(
         workdir,
-        &["commit", "--amend", "--no-edit", "--allow-empty"],
+        &super::verified_args(&["commit", "--amend", "--no-edit", "--allow-empty"]),
     )
 }
 
@@ -53,7 +57,7 @@ pub fn stage_path(workdir: &Path, path: &str) -> Result<()> {
 ///
 /// Wraps `git commit -m <message>`.
 pub fn commit(workdir: &Path, message: &str) -> Result<()> {
-    super::run_git(workdir, &["commit", "-m", message])
+    super::run_git(workdir, &super::verified_args(&["commit", "-m", message]))
 }
 
 /// Mixed reset to a target ref (uncommit and unstage).
@@ -72,11 +76,15 @@ pub fn reset_hard(workdir: &Path, target: &str) -> Result<()> {
     super::run_git(workdir, &["reset", "--hard", target])
 }
 
-/// Stage all changes (staged, unstaged, and untracked).
+/// Stage all changes (staged, unstaged, and untracked), except paths matching
+/// one of the `exclude` pathspecs.
 ///
-/// Wraps `git add -A`.
-pub fn stage_all(workdir: &Path) -> Result<()> {
-    super::run_git(workdir, &["add", "-A"])
+/// Wraps `git add -A -- . :(exclude)<pattern>...`.
+pub fn stage_all(workdir: &Path, exclude: &[String]) -> Result<()> {
+    let excludes: Vec<String> = exclude.iter().map(|p| format!(":(exclude){}", p)).collect();
+    let mut args = vec!["add", "-A", "--", "."];
+    args.extend(excludes.iter().map(|s| s.as_str()));
+    super::run_git(workdir, &args)
 }
 
 /// Create a commit by opening the user's editor for the message.
@@ -84,5 +92,77 @@ pub fn stage_all(workdir: &Path) -> Result<()> {
 /// Wraps `git commit` (no -m flag). Inherits stdin/stdout so the editor
 /// can interact with the terminal.
 pub fn commit_with_editor(workdir: &Path) -> Result<()> {
-    super::run_git_interactive(workdir, &["commit"])
+    crate::core::msg::require_editor("the commit message", "Pass it with `-m`")?;
+    super::run_git_interactive(workdir, &super::verified_args(&["commit"]))
+}
+
+/// Write a commit object for `tree` on top of `parent`, with the committer
+/// date set to now, and return its OID. `author` keeps the original author
+/// (name, email and date); `None` uses the current identity. `sign` passes
+/// `-S`; otherwise `commit.gpgSign` decides.
+///
+/// Wraps `git commit-tree <tree> -p <parent> -F <file> [-S]`.
+pub fn commit_tree(
+    workdir: &Path,
+    tree: &str,
+    parent: &str,
+    message: &str,
+    author: Option<&git2::Signature>,
+    sign: bool,
+) -> Result<String> {
+    use std::io::Write;
+
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(message.as_bytes())?;
+    file.flush()?;
+    let path = file.path().display().to_string();
+    let mut args = vec!["commit-tree", tree, "-p", parent, "-F", &path];
+    if sign {
+        args.push("-S");
+    }
+
+    let mut envs = Vec::new();
+    if let Some(author) = author {
+        let when = author.when();
+        let offset = when.offset_minutes();
+        envs.push(("GIT_AUTHOR_NAME", author.name().unwrap_or("").to_string()));
+        envs.push(("GIT_AUTHOR_EMAIL", author.email().unwrap_or("").to_string()));
+        envs.push((
+            "GIT_AUTHOR_DATE",
+            format!(
+                "@{} {}{:02}{:02}",
+                when.seconds(),
+                if offset < 0 { '-' } else { '+' },
+                offset.abs() / 60,
+                offset.abs() % 60
+            ),
+        ));
+    }
+    let output = super::run_git_captured_env(workdir, &args, &envs)
+        .context("Could not write the new commit")?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Run the `commit-msg` hook on `message` and return the message the hook
+/// left behind (hooks may rewrite it). For commits written through libgit2,
+/// which bypasses hooks. A no-op when hooks are disabled.
+///
+/// Wraps `git hook run --ignore-missing commit-msg -- <file>`, which honors
+/// `core.hooksPath`.
+pub fn run_commit_msg_hook(workdir: &Path, message: &str) -> Result<String> {
+    use std::io::Write;
+
+    if super::no_verify() {
+        return Ok(message.to_string());
+    }
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(message.as_bytes())?;
+    file.flush()?;
+    let path = file.path().display().to_string();
+    super::run_git(
+        workdir,
+        &["hook", "run", "--ignore-missing", "commit-msg", "--", &path],
+    )
+    .context("The commit-msg hook rejected the message")?;
+    Ok(std::fs::read_to_string(file.path())?)
 }
@@ -0,0 +1,28 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+/// Append a value to a multi-valued key in the repository's local config.
+///
+/// Wraps `git config --local --add <key> <value>`.
+pub fn config_add(workdir: &Path, key: &str, value: &str) -> Result<()> {
+    super::run_git(workdir, &["config", "--local", "--add", key, value])
+}
+
+/// Remove every occurrence of `value` from a multi-valued key in the
+/// repository's local config.
+///
+/// Wraps `git config --local --fixed-value --unset-all <key> <value>`.
+pub fn config_unset_value(workdir: &Path, key: &str, value: &str) -> Result<()> {
+    super::run_git(
+        workdir,
+        &[
+            "config",
+            "--local",
+            "--fixed-value",
+            "--unset-all",
+            key,
+            value,
+        ],
+    )
+}
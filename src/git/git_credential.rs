@@ -0,0 +1,103 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+use anyhow::{Result, bail};
+
+use crate::trace as loom_trace;
+
+/// A username and secret (password or token) for a host, as returned by the
+/// configured git credential helpers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Credential {
+    pub username: String,
+    pub password: String,
+}
+
+/// Ask the credential helpers for the credential of `url`.
+///
+/// Wraps `git credential fill`. Prompts are disabled, so only helpers and
+/// credential managers are consulted: `None` when none of them knows the
+/// host.
+pub fn credential_fill(workdir: &Path, url: &str) -> Option<Credential> {
+    let output = run_credential(workdir, "fill", &format!("url={}\n\n", url)).ok()?;
+    parse_credential(&output)
+}
+
+/// Tell the credential helpers that `credential` worked for `url`, so they
+/// can store it.
+///
+/// Wraps `git credential approve`.
+pub fn credential_approve(workdir: &Path, url: &str, credential: &Credential) -> Result<()> {
+    run_credential(workdir, "approve", &credential_input(url, credential)).map(|_| ())
+}
+
+/// Tell the credential helpers that `credential` was refused for `url`, so
+/// they can forget it.
+///
+/// Wraps `git credential reject`.
+pub fn credential_reject(workdir: &Path, url: &str, credential: &Credential) -> Result<()> {
+    run_credential(workdir, "reject", &credential_input(url, credential)).map(|_| ())
+}
+
+fn credential_input(url: &str, credential: &Credential) -> String {
+    format!(
+        "url={}\nusername={}\npassword={}\n\n",
+        url, credential.username, credential.password
+    )
+}
+
+/// The credential in the `key=value` output of `git credential fill`.
+fn parse_credential(output: &str) -> Option<Credential> {
+    let mut username = None;
+    let mut password = None;
+    for line in output.lines() {
+        match line.split_once('=') {
+            Some(("username", value)) => username = Some(value.to_string()),
+            Some(("password", value)) => password = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    Some(Credential {
+        username: username.unwrap_or_default(),
+        password: password.filter(|p| !p.is_empty())?,
+    })
+}
+
+/// Run `git credential <action>` with `input` on stdin. The input holds
+/// secrets, so only the action is traced.
+fn run_credential(workdir: &Path, action: &str, input: &str) -> Result<String> {
+    let start = Instant::now();
+    let mut child = Command::new("git")
+        .current_dir(workdir)
+        .args(["credential", action])
+        // Never prompt: an empty askpass also shadows `core.askPass`
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_ASKPASS", "")
+        .env("GCM_INTERACTIVE", "never")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    let duration_ms = start.elapsed().as_millis();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    loom_trace::log_command(
+        "git",
+        &format!("credential {}", action),
+        duration_ms,
+        output.status.success(),
+        &stderr,
+    );
+
+    if !output.status.success() {
+        bail!("git credential {} failed", action);
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
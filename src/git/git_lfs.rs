@@ -0,0 +1,75 @@
+/// First line of every Git LFS pointer file.
+const POINTER_VERSION: &str = "version https://git-lfs.github.com/spec/v1";
+
+/// A Git LFS pointer changed by a patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LfsPointer {
+    pub path: String,
+    /// Size of the object the new side of the patch points to, if it has one.
+    pub size: Option<u64>,
+}
+
+/// Split a patch into its file parts. A part starts at a `diff --git` line,
+/// or at a `---`/`+++` pair following hunks (patches built from picked hunks
+/// have no `diff --git` lines).
+fn file_parts(patch: &str) -> Vec<&str> {
+    let lines: Vec<&str> = patch.split_inclusive('\n').collect();
+    let mut parts = Vec::new();
+    let (mut start, mut offset) = (0, 0);
+    let mut in_hunks = false;
+    for (i, line) in lines.iter().enumerate() {
+        let new_file = line.starts_with("diff --git ")
+            || (in_hunks
+                && line.starts_with("--- ")
+                && lines
+                    .get(i + 1)
+                    .is_some_and(|next| next.starts_with("+++ ")));
+        if new_file && offset > start {
+            parts.push(&patch[start..offset]);
+            start = offset;
+            in_hunks = false;
+        }
+        in_hunks |= line.starts_with("@@");
+        offset += line.len();
+    }
+    if offset > start {
+        parts.push(&patch[start..offset]);
+    }
+    parts
+}
+
+/// Path of a file part, from its `+++ b/` line (`--- a/` for a deletion).
+fn part_path(part: &str) -> Option<String> {
+    let line = |prefix: &str| {
+        part.lines()
+            .find_map(|l| l.strip_prefix(prefix))
+            .map(|p| p.trim_end().to_string())
+    };
+    line("+++ b/").or_else(|| line("--- a/"))
+}
+
+/// Whether a file part changes a Git LFS pointer rather than file content.
+fn is_pointer_part(part: &str) -> bool {
+    part.lines().any(|l| {
+        matches!(l.split_at_checked(1), Some(("+" | "-" | " ", line)) if line == POINTER_VERSION)
+    })
+}
+
+/// The Git LFS pointers among the files of `patch`.
+pub fn lfs_pointers(patch: &str) -> Vec<LfsPointer> {
+    file_parts(patch)
+        .into_iter()
+        .filter(|part| is_pointer_part(part))
+        .filter_map(|part| {
+            let size = part
+                .lines()
+                .filter(|l| l.starts_with('+') || l.starts_with(' '))
+                .find_map(|l| l[1..].strip_prefix("size "))
+                .and_then(|s| s.trim().parse().ok());
+            Some(LfsPointer {
+                path: part_path(part)?,
+                size,
+            })
+        })
+        .collect()
+}
@@ -14,7 +14,11 @@ pub enum MergeOutcome {
 /// Returns `Conflicted` if the merge stopped due to conflicts,
 /// `Completed` on success, or `Err` on any other failure.
 pub fn merge_no_ff(workdir: &Path, git_dir: &Path, branch: &str) -> Result<MergeOutcome> {
-    run_merge_cmd(workdir, git_dir, &["merge", "--no-ff", branch, "--no-edit"])
+    run_merge_cmd(
+        workdir,
+        git_dir,
+        &super::verified_args(&["merge", "--no-ff", branch, "--no-edit"]),
+    )
 }
 
 /// Continue an in-progress merge (equivalent to `git merge --continue`).
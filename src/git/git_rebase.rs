@@ -40,13 +40,13 @@ pub fn continue_rebase(workdir: &Path) -> Result<RebaseOutcome> {
 pub fn rebase(git_dir: &Path, workdir: &Path, upstream: &str) -> Result<RebaseOutcome> {
     match super::run_git(
         workdir,
-        &[
+        &super::verified_args(&[
             "rebase",
             "--autostash",
             "--update-refs",
             "--rebase-merges",
             upstream,
-        ],
+        ]),
     ) {
         Ok(()) => Ok(RebaseOutcome::Completed),
         Err(e) => {
@@ -83,6 +83,12 @@ pub fn rebase_abort(workdir: &Path) -> Result<()> {
     super::run_git(workdir, &["rebase", "--abort"])
 }
 
+/// Drop an in-progress rebase without touching HEAD, the index or the
+/// working tree. An autostash is kept in the stash list.
+pub fn rebase_quit(workdir: &Path) -> Result<()> {
+    super::run_git(workdir, &["rebase", "--quit"])
+}
+
 /// Check whether a rebase is currently in progress in the repository.
 ///
 /// Detects the presence of `rebase-merge/` or `rebase-apply/` directories
@@ -1,7 +1,13 @@
+//! Thin wrappers around the `git` CLI. Every invocation is recorded in the
+//! command trace; failures bail with a short message (details go to the trace).
+
 pub mod git_apply;
 pub mod git_branch;
 pub mod git_commit;
+pub mod git_config;
+pub mod git_credential;
 pub mod git_diff;
+pub mod git_lfs;
 pub mod git_merge;
 pub mod git_rebase;
 
@@ -14,22 +20,26 @@ pub use git_branch::{
     branch_switch_create_tracking, branch_switch_detach, branch_validate_name,
 };
 pub use git_commit::{
-    commit, commit_amend, commit_amend_no_edit, commit_with_editor, reset_hard, reset_mixed,
-    stage_all, stage_files, stage_path,
+    commit, commit_amend, commit_amend_no_edit, commit_tree, commit_with_editor, reset_hard,
+    reset_mixed, run_commit_msg_hook, stage_all, stage_files, stage_path,
 };
+pub use git_config::{config_add, config_unset_value};
+pub use git_credential::{Credential, credential_approve, credential_fill, credential_reject};
 pub use git_diff::{
     diff_cached_file, diff_cached_file_is_binary, diff_cached_files, diff_commit, diff_commit_file,
     diff_commit_file_is_binary, diff_commit_name_status, diff_file, diff_file_is_binary, diff_head,
     diff_head_file, diff_head_file_is_binary, diff_head_files, diff_head_name_only,
 };
+pub use git_lfs::{LfsPointer, lfs_pointers};
 pub use git_merge::{MergeOutcome, continue_merge, merge_abort, merge_is_in_progress, merge_no_ff};
 #[cfg(test)]
 pub use git_rebase::rebase_onto;
 pub use git_rebase::{
     RebaseOutcome, continue_rebase, continue_rebase_or_abort, rebase, rebase_abort,
-    rebase_is_in_progress,
+    rebase_is_in_progress, rebase_quit,
 };
 
+use std::cell::Cell;
 use std::path::Path;
 use std::process::Command;
 use std::time::Instant;
@@ -41,12 +51,48 @@ use crate::trace as loom_trace;
 /// Minimum Git version required (--update-refs was added in 2.38).
 const MIN_GIT_VERSION: (u32, u32) = (2, 38);
 
+thread_local! {
+    /// Set by `--no-verify`: skip the pre-commit, commit-msg,
+    /// pre-merge-commit, and pre-rebase hooks in every git command loom runs.
+    static NO_VERIFY: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Enable or disable git hooks for the commits created from now on.
+pub fn set_no_verify(no_verify: bool) {
+    NO_VERIFY.with(|cell| cell.set(no_verify));
+}
+
+/// Whether git hooks are currently disabled (see [`set_no_verify`]).
+pub fn no_verify() -> bool {
+    NO_VERIFY.with(Cell::get)
+}
+
+/// `args` for a hook-running git command (commit, merge, rebase), with
+/// `--no-verify` appended when hooks are disabled.
+fn verified_args<'a>(args: &[&'a str]) -> Vec<&'a str> {
+    let mut args = args.to_vec();
+    if no_verify() {
+        args.push("--no-verify");
+    }
+    args
+}
+
 /// Run a git command, capture output, trace-log it, and bail on failure.
 fn run_git_captured(workdir: &Path, args: &[&str]) -> Result<std::process::Output> {
+    run_git_captured_env(workdir, args, &[])
+}
+
+/// [`run_git_captured`] with extra environment variables.
+fn run_git_captured_env(
+    workdir: &Path,
+    args: &[&str],
+    envs: &[(&str, String)],
+) -> Result<std::process::Output> {
     let start = Instant::now();
     let output = Command::new("git")
         .current_dir(workdir)
         .args(args)
+        .envs(envs.iter().map(|(k, v)| (k, v)))
         .output()?;
 
     let duration_ms = start.elapsed().as_millis();
@@ -90,8 +136,7 @@ pub fn run_git_combined(workdir: &Path, args: &[&str]) -> Result<String> {
 /// Check that the installed Git version meets the minimum requirement.
 /// Returns an error with an actionable message if the version is too old.
 pub fn check_git_version() -> Result<()> {
-    let output = Command::new("git").arg("--version").output()?;
-    let version_str = String::from_utf8_lossy(&output.stdout);
+    let version_str = git_version()?;
 
     // Parse "git version X.Y.Z..." → (X, Y)
     let (major, minor) = parse_git_version(&version_str)
@@ -112,6 +157,12 @@ pub fn check_git_version() -> Result<()> {
     Ok(())
 }
 
+/// The output of `git --version`, e.g. "git version 2.43.0".
+pub fn git_version() -> Result<String> {
+    let output = Command::new("git").arg("--version").output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
 /// Parse "git version X.Y.Z..." into (major, minor).
 fn parse_git_version(version_str: &str) -> Option<(u32, u32)> {
     let version_part = version_str.trim().strip_prefix("git version ")?;
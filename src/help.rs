@@ -0,0 +1,163 @@
+use std::io::{IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use anyhow::{Result, bail};
+use colored::Colorize;
+
+/// A long-form help page, shown by `loom help <topic>`.
+pub struct Topic {
+    pub name: &'static str,
+    /// Other names accepted for the topic.
+    pub aliases: &'static [&'static str],
+    /// One-line description for the topic list.
+    pub summary: &'static str,
+    pub body: &'static str,
+}
+
+pub const TOPICS: &[Topic] = &[
+    Topic {
+        name: "workflows",
+        aliases: &["workflow"],
+        summary: "The daily loop: init, commit, fold, push, update",
+        body: include_str!("help/workflows.md"),
+    },
+    Topic {
+        name: "stacking",
+        aliases: &["stack", "stacks"],
+        summary: "Branches built on top of other branches",
+        body: include_str!("help/stacking.md"),
+    },
+    Topic {
+        name: "conflicts",
+        aliases: &["conflict"],
+        summary: "Resolving, continuing, or aborting a paused operation",
+        body: include_str!("help/conflicts.md"),
+    },
+    Topic {
+        name: "shortids",
+        aliases: &["shortid", "short-ids", "ids"],
+        summary: "The two-letter IDs of the status output",
+        body: include_str!("help/shortids.md"),
+    },
+];
+
+/// Look up a topic by name or alias (case-insensitive).
+pub fn find(name: &str) -> Option<&'static Topic> {
+    let name = name.to_ascii_lowercase();
+    TOPICS
+        .iter()
+        .find(|t| t.name == name || t.aliases.contains(&name.as_str()))
+}
+
+/// Show a topic page through the pager.
+pub fn run(topic: &str) -> Result<()> {
+    let Some(page) = find(topic) else {
+        bail!(
+            "Unknown command or help topic `{}`\nTopics: {}",
+            topic,
+            TOPICS.iter().map(|t| t.name).collect::<Vec<_>>().join(", ")
+        );
+    };
+    show(&render(page.body));
+    Ok(())
+}
+
+/// The topic list appended to `loom help`.
+pub fn topic_list() -> String {
+    let mut out = format!("{}\n", "Topics:".yellow().bold());
+    for topic in TOPICS {
+        out.push_str(&format!(
+            "  {}{}\n",
+            format!("{:<18}", topic.name).green(),
+            topic.summary
+        ));
+    }
+    out.push_str(&format!(
+        "\nRun {} to read one.\n",
+        "loom help <topic>".green()
+    ));
+    out
+}
+
+/// Style a topic page: `#` headings in bold yellow, backtick spans in green.
+/// Plain text when colors are disabled.
+pub fn render(body: &str) -> String {
+    let mut out = String::new();
+    for line in body.lines() {
+        if let Some(heading) = line.strip_prefix("# ") {
+            out.push_str(&heading.to_uppercase().yellow().bold().to_string());
+        } else if let Some(heading) = line.strip_prefix("## ") {
+            out.push_str(&heading.yellow().bold().to_string());
+        } else {
+            out.push_str(&code_spans(line));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Drop the backticks around code spans and color their content.
+fn code_spans(line: &str) -> String {
+    let mut out = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('`') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('`') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(&after[..end].green().to_string());
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Print `text` through git's pager (`GIT_PAGER`, `core.pager`, `PAGER`,
+/// then `less`) when stdout is a terminal, or straight to stdout otherwise.
+pub fn show(text: &str) {
+    if std::io::stdout().is_terminal()
+        && let Some(pager) = pager()
+        && page(&pager, text).is_ok()
+    {
+        return;
+    }
+    print!("{}", text);
+}
+
+/// The pager git would use, or None when paging is disabled.
+fn pager() -> Option<String> {
+    let output = Command::new("git")
+        .args(["var", "GIT_PAGER"])
+        .output()
+        .ok()?;
+    let pager = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !pager.is_empty() && pager != "cat").then_some(pager)
+}
+
+fn page(pager: &str, text: &str) -> Result<()> {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", pager]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", pager]);
+        cmd
+    };
+    // Same defaults as git: quit if one screen, keep colors, don't clear.
+    if std::env::var_os("LESS").is_none() {
+        cmd.env("LESS", "FRX");
+    }
+    let mut child = cmd.stdin(Stdio::piped()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit the pager before reading everything.
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "help_test.rs"]
+mod tests;
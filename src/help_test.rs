@@ -0,0 +1,31 @@
+#[test]
+fn find_accepts_names_and_aliases() {
+    assert_eq!(super::find("stacking").unwrap().name, "stacking");
+    assert_eq!(super::find("Stack").unwrap().name, "stacking");
+    assert_eq!(super::find("short-ids").unwrap().name, "shortids");
+    assert!(super::find("fold").is_none());
+}
+
+#[test]
+fn every_topic_has_a_title_and_is_listed() {
+    colored::control::set_override(false);
+    let list = super::topic_list();
+    for topic in super::TOPICS {
+        assert!(topic.body.starts_with("# "), "{} has no title", topic.name);
+        assert!(list.contains(topic.name));
+    }
+}
+
+#[test]
+fn render_strips_markup_without_colors() {
+    colored::control::set_override(false);
+    let rendered = super::render("# Title\n\n## Section\nRun `loom fold` now, `unclosed\n");
+    assert_eq!(rendered, "TITLE\n\nSection\nRun loom fold now, `unclosed\n");
+}
+
+#[test]
+fn run_rejects_unknown_topics() {
+    let err = super::run("nope").unwrap_err().to_string();
+    assert!(err.contains("Unknown command or help topic `nope`"));
+    assert!(err.contains("workflows, stacking, conflicts, shortids"));
+}
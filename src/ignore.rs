@@ -0,0 +1,89 @@
+use anyhow::{Result, bail};
+use git2::Repository;
+
+use crate::core::msg;
+use crate::core::repo::{self, Target, TargetKind};
+use crate::git;
+
+/// Git config key holding the loom-level ignore patterns (multi-valued).
+const CONFIG_KEY: &str = "loom.ignorePaths";
+
+/// Manage the working files hidden from `loom status`.
+///
+/// With no arguments, lists the current patterns. Otherwise adds each
+/// argument (or removes it with `remove`). Arguments may be short IDs or paths
+/// of changed files, or pathspec globs relative to the repository root.
+pub fn run(patterns: Vec<String>, remove: bool) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "ignore")?.to_path_buf();
+
+    if patterns.is_empty() {
+        if remove {
+            bail!("Specify the paths to stop ignoring");
+        }
+        let current = repo::ignore_path_patterns(&repo);
+        if current.is_empty() {
+            println!("No paths are ignored");
+        }
+        for pattern in current {
+            println!("{}", pattern);
+        }
+        return Ok(());
+    }
+
+    for arg in &patterns {
+        let pattern = resolve_pattern(&repo, arg);
+        if remove {
+            if remove_pattern(&repo, &workdir, &pattern)? {
+                msg::success(&format!("No longer ignoring `{}`", pattern));
+            } else {
+                msg::warn(&format!("`{}` is not ignored", pattern));
+            }
+        } else if add_pattern(&repo, &workdir, &pattern)? {
+            msg::success(&format!("Ignoring `{}`", pattern));
+        } else {
+            msg::warn(&format!("`{}` is already ignored", pattern));
+        }
+    }
+    Ok(())
+}
+
+/// Add `pattern` to `loom.ignorePaths`. Returns `false` if it was already there.
+pub fn add_pattern(repo: &Repository, workdir: &std::path::Path, pattern: &str) -> Result<bool> {
+    if pattern.trim().is_empty() {
+        bail!("Ignore pattern cannot be empty");
+    }
+    if repo::ignore_path_patterns(repo)
+        .iter()
+        .any(|p| p == pattern)
+    {
+        return Ok(false);
+    }
+    git::config_add(workdir, CONFIG_KEY, pattern)?;
+    Ok(true)
+}
+
+/// Remove `pattern` from `loom.ignorePaths`. Returns `false` if it was not there.
+pub fn remove_pattern(repo: &Repository, workdir: &std::path::Path, pattern: &str) -> Result<bool> {
+    if !repo::ignore_path_patterns(repo)
+        .iter()
+        .any(|p| p == pattern)
+    {
+        return Ok(false);
+    }
+    git::config_unset_value(workdir, CONFIG_KEY, pattern)?;
+    Ok(true)
+}
+
+/// Turn a user argument into a pattern: a short ID or path of a changed file
+/// becomes its repo-relative path, anything else is kept as a glob.
+fn resolve_pattern(repo: &Repository, arg: &str) -> String {
+    match repo::resolve_arg(repo, arg, &[TargetKind::File]) {
+        Ok(Target::File(path)) => path,
+        _ => arg.to_string(),
+    }
+}
+
+#[cfg(test)]
+#[path = "ignore_test.rs"]
+mod tests;
@@ -0,0 +1,38 @@
+use crate::core::repo;
+use crate::core::test_helpers::TestRepo;
+
+#[test]
+fn add_and_remove_patterns() {
+    let test_repo = TestRepo::new();
+    let workdir = test_repo.workdir();
+
+    assert!(super::add_pattern(&test_repo.repo, &workdir, ".idea").unwrap());
+    assert!(super::add_pattern(&test_repo.repo, &workdir, "*.local").unwrap());
+    assert!(!super::add_pattern(&test_repo.repo, &workdir, ".idea").unwrap());
+    assert_eq!(
+        repo::ignore_path_patterns(&test_repo.repo),
+        vec![".idea", "*.local"]
+    );
+
+    assert!(super::remove_pattern(&test_repo.repo, &workdir, ".idea").unwrap());
+    assert!(!super::remove_pattern(&test_repo.repo, &workdir, ".idea").unwrap());
+    assert_eq!(repo::ignore_path_patterns(&test_repo.repo), vec!["*.local"]);
+}
+
+#[test]
+fn remove_pattern_matches_literally() {
+    let test_repo = TestRepo::new();
+    let workdir = test_repo.workdir();
+    super::add_pattern(&test_repo.repo, &workdir, "*.local").unwrap();
+    super::add_pattern(&test_repo.repo, &workdir, "a.local").unwrap();
+
+    // Glob characters are not treated as a regex when removing
+    assert!(super::remove_pattern(&test_repo.repo, &workdir, "*.local").unwrap());
+    assert_eq!(repo::ignore_path_patterns(&test_repo.repo), vec!["a.local"]);
+}
+
+#[test]
+fn add_empty_pattern_fails() {
+    let test_repo = TestRepo::new();
+    assert!(super::add_pattern(&test_repo.repo, &test_repo.workdir(), " ").is_err());
+}
@@ -1,4 +1,6 @@
-use anyhow::{Result, bail};
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
 use git2::{BranchType, Repository};
 
 use crate::core::msg;
@@ -7,14 +9,20 @@ use crate::git;
 
 /// Initialize a new integration branch tracking a remote upstream.
 ///
-/// Creates a branch (default name: "integration") at the upstream tip and switches to it.
-/// The remote is auto-detected from the current branch's upstream tracking ref.
-/// If no upstream is found, the user is prompted to choose one.
-pub fn run(name: Option<String>) -> Result<()> {
+/// Creates a branch (default name: `loom.integrationBranch`, or else
+/// "integration") at the upstream tip and switches to it.
+/// The upstream is `base` when given (e.g. `origin/develop`, or `develop` on
+/// `remote`), otherwise the default branch of `remote`, otherwise it is
+/// auto-detected from the current branch's upstream tracking ref or the
+/// remotes' default branches. If several candidates remain, the user is
+/// prompted to choose one.
+pub fn run(name: Option<String>, remote: Option<String>, base: Option<String>) -> Result<()> {
     let repo = repo::open_repo()?;
     let workdir = repo::require_workdir(&repo, "initialize")?;
 
-    let name = name.unwrap_or_else(|| "integration".to_string());
+    let name = name
+        .or_else(|| repo::integration_branch_name(&repo))
+        .unwrap_or_else(|| "integration".to_string());
     let name = name.trim().to_string();
     if name.is_empty() {
         bail!("Branch name cannot be empty");
@@ -24,7 +32,21 @@ pub fn run(name: Option<String>) -> Result<()> {
 
     repo::ensure_branch_not_exists(&repo, &name)?;
 
-    let upstream = detect_upstream(&repo)?;
+    let upstream = match (base, remote) {
+        (Some(base), remote) => resolve_base(&repo, workdir, &base, remote.as_deref())?,
+        (None, Some(remote)) => {
+            find_remote(&repo, &remote)?;
+            let Some(branch) = remote_default_branch(&repo, workdir, &remote) else {
+                bail!(
+                    "Could not find the default branch of `{}`\n\
+                     Pass it with `--base <branch>`",
+                    remote
+                );
+            };
+            ensure_tracking_ref(&repo, workdir, &remote, &branch)?
+        }
+        (None, None) => detect_upstream(&repo, workdir)?,
+    };
 
     git::branch_switch_create_tracking(workdir, &name, &upstream)?;
 
@@ -41,10 +63,9 @@ pub fn run(name: Option<String>) -> Result<()> {
 /// Strategy:
 /// 1. On GitHub repos with an "upstream" remote (fork workflow), use it.
 /// 2. If the current branch has an upstream, use it (e.g., "origin/main").
-/// 3. Otherwise, check each remote's HEAD symref (e.g., refs/remotes/origin/HEAD).
-/// 4. Fall back to scanning for common branch names (main, master, develop).
+/// 3. Otherwise, find each remote's default branch (see [`remote_default_branch`]).
 /// 5. If exactly one candidate, use it. If multiple, prompt the user.
-fn detect_upstream(repo: &Repository) -> Result<String> {
+fn detect_upstream(repo: &Repository, workdir: &Path) -> Result<String> {
     // On GitHub repos with a fork workflow, prefer the "upstream" remote
     if let Some(upstream) = try_github_upstream(repo) {
         return Ok(upstream);
@@ -62,7 +83,7 @@ fn detect_upstream(repo: &Repository) -> Result<String> {
     }
 
     // No upstream on current branch — gather remote candidates
-    let candidates = gather_remote_candidates(repo)?;
+    let candidates = gather_remote_candidates(repo, workdir)?;
 
     match candidates.len() {
         0 => bail!(
@@ -120,41 +141,141 @@ fn try_github_upstream(repo: &Repository) -> Option<String> {
     None
 }
 
-/// Gather candidate remote tracking branches.
-///
-/// For each remote, first checks the remote's HEAD symref (e.g., refs/remotes/origin/HEAD)
-/// which points to the remote's default branch. Falls back to scanning for common
-/// branch names (main, master, develop) if the HEAD symref is not available.
-fn gather_remote_candidates(repo: &Repository) -> Result<Vec<String>> {
+/// Gather candidate remote tracking branches: the default branch of each
+/// remote, fetched first if it has no remote-tracking branch yet.
+fn gather_remote_candidates(repo: &Repository, workdir: &Path) -> Result<Vec<String>> {
     let mut candidates = Vec::new();
 
     let remotes = repo.remotes()?;
-    for remote_name in remotes.iter() {
-        let Some(remote_name) = remote_name else {
-            continue;
-        };
-
-        // Try the remote's HEAD symref first (e.g., refs/remotes/origin/HEAD → origin/main)
-        let head_ref = format!("refs/remotes/{}/HEAD", remote_name);
-        if let Ok(reference) = repo.find_reference(&head_ref)
-            && let Ok(resolved) = reference.resolve()
-            && let Some(name) = resolved.shorthand()
+    for remote_name in remotes.iter().flatten() {
+        // A remote that cannot be fetched is just not a candidate
+        if let Some(branch) = remote_default_branch(repo, workdir, remote_name)
+            && let Ok(tracking) = ensure_tracking_ref(repo, workdir, remote_name, &branch)
         {
-            candidates.push(name.to_string());
-            continue;
+            candidates.push(tracking);
         }
+    }
+
+    Ok(candidates)
+}
+
+/// The default branch of `remote` (e.g. "main"), from:
+/// 1. The remote's HEAD symref (refs/remotes/origin/HEAD → origin/main).
+/// 2. The remote itself (`git ls-remote --symref <remote> HEAD`), which
+///    needs network access but works before the first fetch.
+/// 3. The first common default branch name (main, master, trunk, develop)
+///    with a remote-tracking branch.
+fn remote_default_branch(repo: &Repository, workdir: &Path, remote: &str) -> Option<String> {
+    let head_ref = format!("refs/remotes/{}/HEAD", remote);
+    if let Ok(reference) = repo.find_reference(&head_ref)
+        && let Ok(resolved) = reference.resolve()
+        && let Some(name) = resolved.name()
+        && let Some(branch) = name.strip_prefix(&format!("refs/remotes/{}/", remote))
+    {
+        return Some(branch.to_string());
+    }
+
+    if let Ok(out) = git::run_git_stdout(workdir, &["ls-remote", "--symref", remote, "HEAD"])
+        && let Some(branch) = parse_symref_head(&out)
+    {
+        return Some(branch);
+    }
 
-        // Fall back to common default branch names
-        for branch_name in &["main", "master", "develop"] {
-            let ref_name = format!("{}/{}", remote_name, branch_name);
-            if repo.find_branch(&ref_name, BranchType::Remote).is_ok() {
-                candidates.push(ref_name);
-                break; // Use the first match per remote
+    ["main", "master", "trunk", "develop"]
+        .iter()
+        .find(|name| {
+            repo.find_branch(&format!("{}/{}", remote, name), BranchType::Remote)
+                .is_ok()
+        })
+        .map(|name| name.to_string())
+}
+
+/// Parse the branch out of `git ls-remote --symref <remote> HEAD` output:
+/// `ref: refs/heads/main\tHEAD`.
+fn parse_symref_head(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (target, name) = line.strip_prefix("ref: ")?.split_once('\t')?;
+        (name == "HEAD")
+            .then(|| target.strip_prefix("refs/heads/"))
+            .flatten()
+            .map(str::to_string)
+    })
+}
+
+/// Resolve `--base`: a remote-tracking branch (`origin/develop`), or a
+/// branch name on `remote` (default: the only remote, or `origin`).
+fn resolve_base(
+    repo: &Repository,
+    workdir: &Path,
+    base: &str,
+    remote: Option<&str>,
+) -> Result<String> {
+    if remote.is_none()
+        && let Some((remote, branch)) = base.split_once('/')
+        && repo.find_remote(remote).is_ok()
+    {
+        return ensure_tracking_ref(repo, workdir, remote, branch);
+    }
+
+    let remote = match remote {
+        Some(remote) => remote.to_string(),
+        None => {
+            let remotes = repo.remotes()?;
+            let names: Vec<&str> = remotes.iter().flatten().collect();
+            match names.as_slice() {
+                [only] => only.to_string(),
+                _ if names.contains(&"origin") => "origin".to_string(),
+                [] => bail!(
+                    "No remote tracking branches found\n\
+                     Set up a remote with: `git remote add origin <url>`"
+                ),
+                _ => bail!(
+                    "Several remotes could provide `{}`\nPick one with `--remote <name>`",
+                    base
+                ),
             }
         }
+    };
+    find_remote(repo, &remote)?;
+    ensure_tracking_ref(repo, workdir, &remote, base)
+}
+
+fn find_remote<'r>(repo: &'r Repository, remote: &str) -> Result<git2::Remote<'r>> {
+    repo.find_remote(remote)
+        .map_err(|_| anyhow::anyhow!("Remote `{}` does not exist", remote))
+}
+
+/// Make sure `<remote>/<branch>` exists as a remote-tracking branch and
+/// return its name. The branch is fetched, and when the remote's fetch
+/// refspecs don't cover it (e.g. a `--single-branch` clone), a refspec is
+/// added so later fetches keep it up to date.
+fn ensure_tracking_ref(
+    repo: &Repository,
+    workdir: &Path,
+    remote: &str,
+    branch: &str,
+) -> Result<String> {
+    let tracking = format!("{}/{}", remote, branch);
+    if repo.find_branch(&tracking, BranchType::Remote).is_ok() {
+        return Ok(tracking);
     }
 
-    Ok(candidates)
+    let source = format!("refs/heads/{}", branch);
+    let refspec = format!("+{}:refs/remotes/{}", source, tracking);
+    git::run_git(workdir, &["fetch", remote, &refspec])
+        .with_context(|| format!("Branch `{}` does not exist on `{}`", branch, remote))?;
+
+    let covered = find_remote(repo, remote)?
+        .refspecs()
+        .any(|r| r.direction() == git2::Direction::Fetch && r.src_matches(&source));
+    if !covered {
+        git::config_add(workdir, &format!("remote.{}.fetch", remote), &refspec)?;
+        msg::warn(&format!(
+            "Added fetch refspec `{}` to `{}`",
+            refspec, remote
+        ));
+    }
+    Ok(tracking)
 }
 
 #[cfg(test)]
@@ -9,7 +9,7 @@ fn init_creates_tracking_branch_with_default_name() {
     // Delete the pre-existing "integration" branch so the default name is available
     test_repo.delete_branch("integration");
 
-    let result = test_repo.in_dir(|| super::run(None));
+    let result = test_repo.in_dir(|| super::run(None, None, None));
     assert!(result.is_ok(), "init failed: {:?}", result.err());
 
     // Should have switched to the new branch
@@ -30,7 +30,7 @@ fn init_creates_tracking_branch_with_custom_name() {
     let test_repo = TestRepo::new_with_remote();
     test_repo.switch_branch("main");
 
-    let result = test_repo.in_dir(|| super::run(Some("my-integration".to_string())));
+    let result = test_repo.in_dir(|| super::run(Some("my-integration".to_string()), None, None));
     assert!(result.is_ok(), "init failed: {:?}", result.err());
 
     assert_eq!(test_repo.current_branch_name(), "my-integration");
@@ -43,7 +43,7 @@ fn init_fails_if_branch_already_exists() {
     test_repo.switch_branch("main");
 
     // "integration" already exists from new_with_remote()
-    let result = test_repo.in_dir(|| super::run(Some("integration".to_string())));
+    let result = test_repo.in_dir(|| super::run(Some("integration".to_string()), None, None));
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(
@@ -58,7 +58,7 @@ fn init_fails_with_empty_name() {
     let test_repo = TestRepo::new_with_remote();
     test_repo.switch_branch("main");
 
-    let result = test_repo.in_dir(|| super::run(Some("  ".to_string())));
+    let result = test_repo.in_dir(|| super::run(Some("  ".to_string()), None, None));
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(
@@ -73,7 +73,7 @@ fn init_fails_with_invalid_name() {
     let test_repo = TestRepo::new_with_remote();
     test_repo.switch_branch("main");
 
-    let result = test_repo.in_dir(|| super::run(Some("my..branch".to_string())));
+    let result = test_repo.in_dir(|| super::run(Some("my..branch".to_string()), None, None));
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(
@@ -93,7 +93,7 @@ fn init_points_branch_at_upstream_tip() {
     // The upstream tip should be origin/main
     let origin_main_oid = test_repo.find_remote_branch_target("origin/main");
 
-    let result = test_repo.in_dir(|| super::run(None));
+    let result = test_repo.in_dir(|| super::run(None, None, None));
     assert!(result.is_ok(), "init failed: {:?}", result.err());
 
     // The new branch should point at the same commit as origin/main
@@ -107,7 +107,7 @@ fn init_detects_upstream_from_current_branch() {
     // The "integration" branch tracks origin/main, switch to it
     test_repo.switch_branch("integration");
 
-    let result = test_repo.in_dir(|| super::run(Some("my-loom".to_string())));
+    let result = test_repo.in_dir(|| super::run(Some("my-loom".to_string()), None, None));
     assert!(result.is_ok(), "init failed: {:?}", result.err());
 
     let branch = test_repo
@@ -146,7 +146,7 @@ fn init_prefers_upstream_remote_on_github() {
         .fetch(&["main"], None, None)
         .unwrap();
 
-    let result = test_repo.in_dir(|| super::run(None));
+    let result = test_repo.in_dir(|| super::run(None, None, None));
     assert!(result.is_ok(), "init failed: {:?}", result.err());
 
     // Should track upstream/main, not origin/main
@@ -171,7 +171,7 @@ fn init_no_upstream_remote_uses_origin_on_github() {
         .remote_set_url("origin", "https://github.com/user/repo.git")
         .unwrap();
 
-    let result = test_repo.in_dir(|| super::run(None));
+    let result = test_repo.in_dir(|| super::run(None, None, None));
     assert!(result.is_ok(), "init failed: {:?}", result.err());
 
     // Should still track origin/main since there's no "upstream" remote
@@ -183,3 +183,145 @@ fn init_no_upstream_remote_uses_origin_on_github() {
     let upstream_name = upstream.name().unwrap().unwrap();
     assert_eq!(upstream_name, "origin/main");
 }
+
+/// Publish `main` as `trunk` on the remote and make it the remote's default
+/// branch, without any local trace of it (as before the first fetch).
+fn setup_remote_trunk(test_repo: &TestRepo) {
+    let workdir = test_repo.workdir();
+    crate::git::run_git(&workdir, &["push", "-q", "origin", "main:trunk"]).unwrap();
+    let remote = test_repo.remote_path().unwrap();
+    crate::git::run_git(&remote, &["symbolic-ref", "HEAD", "refs/heads/trunk"]).unwrap();
+    crate::git::run_git(&workdir, &["update-ref", "-d", "refs/remotes/origin/trunk"]).unwrap();
+    crate::git::run_git(
+        &workdir,
+        &["symbolic-ref", "-d", "refs/remotes/origin/HEAD"],
+    )
+    .unwrap();
+}
+
+fn upstream_of(test_repo: &TestRepo, name: &str) -> String {
+    let branch = test_repo.repo.find_branch(name, BranchType::Local).unwrap();
+    branch
+        .upstream()
+        .unwrap()
+        .name()
+        .unwrap()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn init_remote_asks_remote_for_its_default_branch() {
+    let test_repo = TestRepo::new_with_remote();
+    setup_remote_trunk(&test_repo);
+
+    let result =
+        test_repo.in_dir(|| super::run(Some("loom".to_string()), Some("origin".to_string()), None));
+    assert!(result.is_ok(), "init failed: {:?}", result.err());
+
+    assert_eq!(upstream_of(&test_repo, "loom"), "origin/trunk");
+}
+
+#[test]
+fn init_base_accepts_remote_tracking_branch() {
+    let test_repo = TestRepo::new_with_remote();
+    setup_remote_trunk(&test_repo);
+
+    let result = test_repo.in_dir(|| {
+        super::run(
+            Some("loom".to_string()),
+            None,
+            Some("origin/main".to_string()),
+        )
+    });
+    assert!(result.is_ok(), "init failed: {:?}", result.err());
+
+    assert_eq!(upstream_of(&test_repo, "loom"), "origin/main");
+}
+
+#[test]
+fn init_base_branch_name_uses_only_remote() {
+    let test_repo = TestRepo::new_with_remote();
+    setup_remote_trunk(&test_repo);
+
+    let result =
+        test_repo.in_dir(|| super::run(Some("loom".to_string()), None, Some("trunk".to_string())));
+    assert!(result.is_ok(), "init failed: {:?}", result.err());
+
+    assert_eq!(upstream_of(&test_repo, "loom"), "origin/trunk");
+}
+
+#[test]
+fn init_adds_missing_fetch_refspec() {
+    let test_repo = TestRepo::new_with_remote();
+    setup_remote_trunk(&test_repo);
+    // A single-branch clone only fetches main
+    test_repo.set_config(
+        "remote.origin.fetch",
+        "+refs/heads/main:refs/remotes/origin/main",
+    );
+
+    let result =
+        test_repo.in_dir(|| super::run(Some("loom".to_string()), Some("origin".to_string()), None));
+    assert!(result.is_ok(), "init failed: {:?}", result.err());
+
+    assert_eq!(upstream_of(&test_repo, "loom"), "origin/trunk");
+    let refspecs = crate::git::run_git_stdout(
+        &test_repo.workdir(),
+        &["config", "--get-all", "remote.origin.fetch"],
+    )
+    .unwrap();
+    assert!(
+        refspecs.contains("+refs/heads/trunk:refs/remotes/origin/trunk"),
+        "refspecs: {}",
+        refspecs
+    );
+}
+
+#[test]
+fn init_base_missing_on_remote_fails() {
+    let test_repo = TestRepo::new_with_remote();
+
+    let result =
+        test_repo.in_dir(|| super::run(Some("loom".to_string()), None, Some("nope".to_string())));
+
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("does not exist on `origin`"), "{}", err);
+    assert!(!test_repo.branch_exists("loom"));
+    let refspecs = crate::git::run_git_stdout(
+        &test_repo.workdir(),
+        &["config", "--get-all", "remote.origin.fetch"],
+    )
+    .unwrap();
+    assert!(!refspecs.contains("nope"), "refspecs: {}", refspecs);
+}
+
+#[test]
+fn init_unknown_remote_fails() {
+    let test_repo = TestRepo::new_with_remote();
+
+    let result =
+        test_repo.in_dir(|| super::run(Some("loom".to_string()), Some("nope".to_string()), None));
+
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("Remote `nope` does not exist"), "{}", err);
+}
+
+#[test]
+fn parse_symref_head_reads_default_branch() {
+    let output = "ref: refs/heads/trunk\tHEAD\n0123456789abcdef\tHEAD\n";
+    assert_eq!(super::parse_symref_head(output), Some("trunk".to_string()));
+    assert_eq!(super::parse_symref_head("0123456789abcdef\tHEAD\n"), None);
+}
+
+#[test]
+fn init_takes_default_name_from_project_file() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.switch_branch("main");
+    test_repo.write_file(".loom.toml", "integrationBranch = \"weave\"\n");
+
+    let result = test_repo.in_dir(|| super::run(None, None, None));
+    assert!(result.is_ok(), "init failed: {:?}", result.err());
+
+    assert_eq!(test_repo.current_branch_name(), "weave");
+}
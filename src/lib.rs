@@ -0,0 +1,95 @@
+//! git-loom: weave feature branches into an integration branch.
+//!
+//! This crate powers the `git-loom` binary and can also be driven directly by
+//! other Rust tools (GUIs, bots, editor backends). The binary is a thin clap
+//! front-end over the functions exposed here.
+//!
+//! # Layout
+//!
+//! - [`core`] — the building blocks:
+//!   - [`core::repo`] gathers repository state ([`core::repo::gather_repo_info`])
+//!     and resolves user arguments ([`core::repo::resolve_arg`]).
+//!   - [`core::weave`] models the integration topology ([`core::weave::Weave`]),
+//!     applies mutations to it, and executes the resulting rebase.
+//!   - [`core::transaction`] saves, resumes, and rolls back paused operations.
+//!   - [`core::graph`] and [`core::shortid`] render the status graph.
+//! - [`git`] — thin wrappers around the `git` CLI.
+//! - One module per command ([`fold`], [`drop`](mod@drop), [`commit`], [`update`], ...).
+//!   Each exposes a `run` function taking the same arguments as the CLI.
+//!
+//! # Example
+//!
+//! Plan a commit move without touching the repository:
+//!
+//! ```no_run
+//! use git_loom::core::{repo, weave::Weave};
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let repository = repo::open_repo()?;
+//! let info = repo::gather_repo_info(&repository, false, 0)?;
+//! let mut graph = Weave::from_repo_with_info(&repository, &info)?;
+//! if let (Some(commit), Some(branch)) = (info.commits.first(), info.branches.first()) {
+//!     graph.move_commit(commit.oid, &branch.name)?;
+//! }
+//! println!("{}", graph.to_todo());
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Commands print progress with [`core::msg`] and may prompt interactively
+//! when optional arguments are missing; pass every argument explicitly to
+//! keep them non-interactive.
+
+pub mod absorb;
+pub mod activity;
+pub mod add;
+pub mod api;
+pub mod archive;
+pub mod backport;
+pub mod batch;
+pub mod bisect;
+pub mod branch;
+pub mod bugreport;
+pub mod check;
+pub mod cherry;
+pub mod commit;
+pub mod completions;
+pub mod core;
+pub mod diff;
+pub mod drop;
+pub mod explain;
+pub mod fixture;
+pub mod fold;
+pub mod gc;
+pub mod git;
+pub mod help;
+pub mod ignore;
+pub mod init;
+pub mod meta;
+pub mod open;
+pub mod overlap;
+pub mod owns;
+pub mod promote;
+pub mod push;
+pub mod resolution;
+pub mod revert;
+pub mod reword;
+#[cfg(feature = "selfupdate")]
+pub mod selfupdate;
+pub mod serve;
+pub mod show;
+pub mod size_check;
+pub mod split;
+pub mod stack;
+pub mod state;
+pub mod status;
+pub mod swap;
+pub mod switch;
+pub mod topology;
+pub mod touch;
+pub mod tour;
+pub mod trace;
+pub mod tui;
+pub mod update;
+pub mod who;
+pub mod worktree;
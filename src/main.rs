@@ -1,32 +1,16 @@
-mod absorb;
-mod add;
-mod branch;
-mod commit;
-mod completions;
-mod core;
-mod diff;
-mod drop;
-mod fold;
-mod git;
-mod init;
-mod push;
-mod reword;
-mod show;
-mod split;
-mod status;
-mod swap;
-mod switch;
-mod trace;
-mod tui;
-mod update;
-
-use crate::core::{graph, msg, repo, transaction};
+use git_loom::core::{clock, graph, lock, msg, oplog, repo, transaction, weave};
+use git_loom::{
+    absorb, activity, add, api, archive, backport, batch, bisect, branch, bugreport, check, cherry,
+    commit, completions, diff, drop, explain, fixture, fold, gc, git, help, ignore, init, meta,
+    open, overlap, owns, promote, push, resolution, revert, reword, serve, show, size_check, split,
+    stack, state, status, swap, switch, topology, touch, tour, trace, update, who, worktree,
+};
 
 use std::io::IsTerminal;
 
 use anyhow::Context;
 use clap::builder::styling::{AnsiColor, Styles};
-use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap::{Args, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
 use colored::control;
 
 const STYLES: Styles = Styles::styled()
@@ -45,15 +29,40 @@ enum ThemeArg {
     Light,
 }
 
+// `self-update` is only listed in builds with the `selfupdate` feature.
+#[cfg(feature = "selfupdate")]
+macro_rules! self_update_help {
+    () => {
+        "  \x1b[32mself-update\x1b[0m       Update loom to the latest release [\x1b[32m--check\x1b[0m to only look]\n"
+    };
+}
+#[cfg(not(feature = "selfupdate"))]
+macro_rules! self_update_help {
+    () => {
+        ""
+    };
+}
+
 // Grouped command help — ANSI codes match STYLES (yellow bold = headers, green = literals)
-const GROUPED_COMMANDS: &str = "\
+const GROUPED_COMMANDS: &str = concat!(
+    "\
 \x1b[1;33mWorkflow:\x1b[0m
   \x1b[32minit\x1b[0m              Initialize a new integration branch
-  \x1b[32mupdate\x1b[0m, \x1b[32mup\x1b[0m        Pull-rebase and update submodules
-  \x1b[32mpush\x1b[0m, \x1b[32mpr\x1b[0m          Push a branch to remote
-
+  \x1b[32mupdate\x1b[0m, \x1b[32mup\x1b[0m        Pull-rebase and update submodules [\x1b[32m--only\x1b[0m one branch]
+  \x1b[32mpush\x1b[0m, \x1b[32mpr\x1b[0m          Push a branch to remote [\x1b[32m--all\x1b[0m]
+  \x1b[32mpromote\x1b[0m           Rebase a branch onto upstream, check, and push it [\x1b[32m--squash\x1b[0m]
+  \x1b[32mexport-state\x1b[0m      Bundle the weave to move it to another machine
+  \x1b[32mimport-state\x1b[0m      Recreate a weave from an export-state bundle
+  \x1b[32mmeta\x1b[0m              Share the weave structure with teammates [\x1b[32mpush\x1b[0m, \x1b[32mpull\x1b[0m]
+  \x1b[32mtour\x1b[0m              Learn loom step by step in a scratch repository
+  \x1b[32mhelp\x1b[0m              Show help for a command or read a guide [\x1b[32mworkflows\x1b[0m, \x1b[32mstacking\x1b[0m, ...]
+",
+    self_update_help!(),
+    "
 \x1b[1;33mStaging:\x1b[0m
   \x1b[32madd\x1b[0m               Stage files using short IDs or paths [\x1b[32m-p\x1b[0m for interactive hunks]
+  \x1b[32mignore\x1b[0m            Hide always-dirty files from the working changes
+  \x1b[32msize-check\x1b[0m        Flag large or binary staged files (for a pre-commit hook)
 
 \x1b[1;33mCommits:\x1b[0m
   \x1b[32mcommit\x1b[0m, \x1b[32mci\x1b[0m        Create a commit on a feature branch
@@ -63,20 +72,49 @@ const GROUPED_COMMANDS: &str = "\
   \x1b[32mswap\x1b[0m              Swap two commits
   \x1b[32mreword\x1b[0m, \x1b[32mrw\x1b[0m        Reword a commit message or rename a branch
   \x1b[32mdrop\x1b[0m, \x1b[32mrm\x1b[0m          Drop a change, commit, or branch
+  \x1b[32mrevert\x1b[0m            Undo a commit with a new commit on its branch [\x1b[32m--loose\x1b[0m]
+  \x1b[32mbackport\x1b[0m          Apply an upstream fix under the weave before the next update [\x1b[32m--branch\x1b[0m]
+  \x1b[32mbatch\x1b[0m             Run several drop/fixup/move/swap operations in one rebase
+  \x1b[32mtouch\x1b[0m             Recreate a branch's commits (new dates, author, signature)
 
 \x1b[1;33mBranches:\x1b[0m
   \x1b[32mbranch\x1b[0m, \x1b[32mbr\x1b[0m        Manage feature branches (create, merge, unmerge)
   \x1b[32mswitch\x1b[0m, \x1b[32msw\x1b[0m        Switch to any branch for testing (without weaving)
+  \x1b[32mnext\x1b[0m, \x1b[32mprev\x1b[0m        Check out the next/previous woven branch [\x1b[32mtop\x1b[0m to return]
+  \x1b[32mrebase-branch\x1b[0m     Move a woven branch onto another base, out of the weave
+  \x1b[32msplit-branch\x1b[0m      Divide a woven branch into two [\x1b[32m--commit\x1b[0m, \x1b[32m--stack\x1b[0m]
+  \x1b[32mmerge-branches\x1b[0m    Combine two woven branches into one [\x1b[32m--keep\x1b[0m]
+  \x1b[32mattach\x1b[0m            Adopt a branch made outside loom into the weave
+  \x1b[32marchive\x1b[0m           Freeze a branch out of the weave [\x1b[32m--list\x1b[0m, \x1b[32m--restore\x1b[0m]
+  \x1b[32mworktree\x1b[0m, \x1b[32mwt\x1b[0m      Check out a branch in its own directory [\x1b[32madd\x1b[0m, \x1b[32mpath\x1b[0m, \x1b[32mprune\x1b[0m]
 
 \x1b[1;33mInspection:\x1b[0m
   \x1b[32mstatus\x1b[0m            Show the branch-aware status (\x1b[34mdefault\x1b[0m command)
   \x1b[32mshow\x1b[0m, \x1b[32msh\x1b[0m          Show commit details (like git show)
   \x1b[32mdiff\x1b[0m, \x1b[32mdi\x1b[0m          Show a diff using short IDs (like git diff)
+  \x1b[32mdiff-branch\x1b[0m       Show what a branch adds over its base [\x1b[32m--stat\x1b[0m]
+  \x1b[32mcherry\x1b[0m            Show which commits of the weave are already upstream
+  \x1b[32mwho\x1b[0m               Summarize the authors of a branch
+  \x1b[32mcheck\x1b[0m             Run the configured checks on a branch tip [\x1b[32m--force\x1b[0m]
+  \x1b[32mowns\x1b[0m              Find the branches and commits that change a file
+  \x1b[32moverlap\x1b[0m           Show which woven branches change the same files and lines
+  \x1b[32mgraph\x1b[0m             Export the weave as Graphviz or Mermaid text [\x1b[32m--dot\x1b[0m, \x1b[32m--mermaid\x1b[0m]
+  \x1b[32mopen\x1b[0m              Open a branch, commit, or file on the forge [\x1b[32m--print\x1b[0m]
+  \x1b[32mbisect\x1b[0m            Find the commit and branch that introduced a bug [\x1b[32mstart\x1b[0m, \x1b[32mgood\x1b[0m, \x1b[32mbad\x1b[0m, \x1b[32mreset\x1b[0m]
   \x1b[32mtrace\x1b[0m             Show the latest command trace
+  \x1b[32mexplain\x1b[0m           Show the git commands a loom command would run, without running it
+  \x1b[32mbugreport\x1b[0m         Print a redacted environment report for bug reports
+  \x1b[32mactivity\x1b[0m          Summarize your local command usage and durations
+  \x1b[32mapi\x1b[0m               Query the repository as JSON (for editor integrations)
+  \x1b[32mserve\x1b[0m             Answer editor requests as a JSON-RPC server [\x1b[32m--stdio\x1b[0m]
+  \x1b[32mrender-fixture\x1b[0m    Render a status fixture (JSON) as text, for snapshot tests
 
 \x1b[1;33mRecovery:\x1b[0m
   \x1b[32mcontinue\x1b[0m, \x1b[32mc\x1b[0m       Resume a paused operation after resolving conflicts
-  \x1b[32mabort\x1b[0m, \x1b[32ma\x1b[0m          Cancel a paused operation and restore original state";
+  \x1b[32mabort\x1b[0m, \x1b[32ma\x1b[0m          Cancel a paused operation and restore original state
+  \x1b[32mresolution\x1b[0m        Save conflict resolutions and replay them [\x1b[32msave\x1b[0m, \x1b[32mapply\x1b[0m, \x1b[32mlist\x1b[0m, \x1b[32mpush\x1b[0m]
+  \x1b[32mgc\x1b[0m                Prune old loom logs, caches, and leftover refs [\x1b[32m--dry-run\x1b[0m]"
+);
 
 #[derive(Parser)]
 #[command(
@@ -86,6 +124,7 @@ const GROUPED_COMMANDS: &str = "\
     version,
     after_help = GROUPED_COMMANDS,
     help_template = "{about-with-newline}\n{usage-heading} {usage}{after-help}\n\n\x1b[1;33mOptions:\x1b[0m\n{options}\n",
+    disable_help_subcommand = true,
 )]
 struct Cli {
     /// Disable colored output
@@ -96,6 +135,34 @@ struct Cli {
     #[arg(long, default_value = "auto")]
     theme: ThemeArg,
 
+    /// Draw the graph with ASCII characters only (default when the locale is not UTF-8)
+    #[arg(long, global = true)]
+    ascii: bool,
+
+    /// Skip the git hooks (pre-commit, commit-msg, ...) of the commits loom creates
+    #[arg(long = "no-verify", global = true)]
+    no_verify: bool,
+
+    /// Move the tags of rewritten commits along instead of refusing the rewrite
+    #[arg(long, global = true)]
+    retag: bool,
+
+    /// Refuse to rewrite history over uncommitted changes instead of stashing them
+    #[arg(long = "no-autostash", global = true)]
+    no_autostash: bool,
+
+    /// Fail (exit status 3) instead of prompting or opening an editor, for scripts and CI
+    #[arg(long = "no-input", global = true)]
+    no_input: bool,
+
+    /// Path to the repository (e.g. a bare repository); read-only commands only
+    #[arg(long = "git-dir", value_name = "PATH")]
+    git_dir: Option<std::path::PathBuf>,
+
+    /// Path to the working tree; read-only commands only
+    #[arg(long = "work-tree", value_name = "PATH")]
+    work_tree: Option<std::path::PathBuf>,
+
     /// Show files changed in each commit (optionally filtered to specific commits)
     #[arg(short = 'f', long = "files", num_args = 0.., hide = true)]
     files: Option<Vec<String>>,
@@ -108,6 +175,30 @@ struct Cli {
     #[arg(short = 'a', long = "all", hide = true)]
     all: bool,
 
+    /// Only show commits touching paths matching this glob (repeatable)
+    #[arg(long = "path", value_name = "GLOB", hide = true)]
+    paths: Vec<String>,
+
+    /// Group working changes under the woven branch that last touched them
+    #[arg(short = 'g', long = "group", hide = true)]
+    group: bool,
+
+    /// Show a compact diffstat (+added/-removed lines) next to each commit
+    #[arg(long = "stat", hide = true)]
+    stat: bool,
+
+    /// Show the last `loom check` result of each branch tip (pass, fail, or unknown)
+    #[arg(long = "checks", hide = true)]
+    checks: bool,
+
+    /// When HEAD is detached, show the integration branch containing it (read-only)
+    #[arg(long = "detached", hide = true)]
+    detached: bool,
+
+    /// Only list files changed these ways: A, D, M, R, U (lowercase to exclude)
+    #[arg(long = "diff-filter", value_name = "FILTER", value_parser = status::DiffFilter::parse, hide = true)]
+    diff_filter: Option<status::DiffFilter>,
+
     #[command(subcommand)]
     command: Option<Command>,
 }
@@ -119,6 +210,12 @@ enum Command {
     Init {
         /// Branch name (defaults to "integration")
         name: Option<String>,
+        /// Track the default branch of this remote
+        #[arg(long)]
+        remote: Option<String>,
+        /// Track this branch (e.g. "origin/develop", or "develop" on --remote)
+        #[arg(long)]
+        base: Option<String>,
     },
     /// Pull-rebase the integration branch and update submodules
     #[command(visible_alias = "up")]
@@ -126,16 +223,74 @@ enum Command {
         /// Remove local branches whose upstream tracking branch was deleted on remote
         #[arg(short, long)]
         yes: bool,
+
+        /// Only restack this branch onto the new upstream, leaving the rest of the weave
+        #[arg(long, value_name = "BRANCH")]
+        only: Option<String>,
+
+        /// Keep running, updating every INTERVAL (e.g. 15m) when it is safe
+        #[arg(long, value_name = "INTERVAL", value_parser = update::parse_interval, conflicts_with = "only")]
+        daemon: Option<std::time::Duration>,
     },
     /// Push a feature branch to remote and optionally create a PR or Gerrit review
     #[command(visible_alias = "pr")]
     Push {
         /// Branch name or short ID (if not provided, will prompt interactively)
         branch: Option<String>,
+        /// Push every woven branch whose tip changed (no PR or review is created)
+        #[arg(long, conflicts_with = "branch")]
+        all: bool,
         /// Push branch without creating a PR or Gerrit review
         #[arg(long)]
         no_pr: bool,
     },
+    /// Rebase a branch onto the latest upstream out of the weave, run the checks, and push it
+    Promote {
+        /// Branch name or short ID
+        branch: String,
+        /// Squash the branch into a single commit
+        #[arg(long)]
+        squash: bool,
+        /// Stop before pushing
+        #[arg(long)]
+        no_push: bool,
+    },
+    /// Bundle the integration branch, its woven branches, and their loom state into a file
+    ExportState {
+        /// Bundle file to write
+        file: String,
+    },
+    /// Recreate the integration branch and woven branches of an export-state bundle
+    ImportState {
+        /// Bundle file written by export-state
+        file: String,
+    },
+    /// Share the weave structure with teammates through refs/loom/meta
+    Meta {
+        #[command(subcommand)]
+        action: MetaAction,
+    },
+    /// Walk through init, branch, commit, fold, and update in a scratch repository
+    Tour {
+        /// Keep the scratch repository instead of deleting it at the end
+        #[arg(long)]
+        keep: bool,
+    },
+    /// Show help for a command, or read a guide (workflows, stacking, conflicts, shortids)
+    Help {
+        /// Command name or guide topic
+        topic: Option<String>,
+    },
+    /// Update loom to the latest GitHub release
+    #[cfg(feature = "selfupdate")]
+    SelfUpdate {
+        /// Only report whether a newer release is available
+        #[arg(long)]
+        check: bool,
+        /// Skip the confirmation prompt
+        #[arg(short = 'y', long = "yes")]
+        yes: bool,
+    },
 
     // -- Staging --
     /// Stage files using short IDs, paths, or 'zz' for all
@@ -147,6 +302,19 @@ enum Command {
         #[arg(short = 'p', long = "patch")]
         patch: bool,
     },
+    /// Hide always-dirty files from the working changes (lists patterns without arguments)
+    Ignore {
+        /// Short IDs, paths, or glob patterns (relative to the repository root)
+        #[arg(num_args = 0..)]
+        patterns: Vec<String>,
+        /// Stop ignoring the given patterns
+        #[arg(short = 'r', long = "remove")]
+        remove: bool,
+    },
+    /// Flag staged files over `loom.maxFileSize` or matching `loom.binaryPatterns`
+    ///
+    /// Fails when a file is flagged, so it can run as a pre-commit hook.
+    SizeCheck,
 
     // -- Commits --
     /// Create a commit on a feature branch without leaving integration
@@ -161,6 +329,12 @@ enum Command {
         /// Interactively select hunks to stage before committing
         #[arg(short = 'p', long = "patch")]
         patch: bool,
+        /// Pick the files to commit from a checklist of the changes
+        #[arg(short = 'i', long = "interactive", conflicts_with = "patch")]
+        interactive: bool,
+        /// Add a Co-authored-by trailer (repeatable)
+        #[arg(long, value_name = "NAME <EMAIL>")]
+        coauthor: Vec<String>,
         /// Files to stage (short IDs, filenames, or 'zz' for all), none for all tracked changes
         files: Vec<String>,
     },
@@ -173,6 +347,18 @@ enum Command {
         /// Interactively select hunks to stage before folding
         #[arg(short = 'p', long = "patch")]
         patch: bool,
+        /// Carry the source commit's trailers (Signed-off-by, Co-authored-by, ...) into the target
+        #[arg(long)]
+        keep_trailers: bool,
+        /// Fold exactly what is staged (the index content) into the target commit
+        #[arg(long)]
+        staged: bool,
+        /// Show the patch that will move and ask for confirmation first
+        #[arg(long)]
+        preview: bool,
+        /// Add a Co-authored-by trailer to the amended commit (repeatable)
+        #[arg(long, value_name = "NAME <EMAIL>")]
+        coauthor: Vec<String>,
         /// Source(s) and target: files, commits, or branches (last arg is the target)
         #[arg(required = true, num_args = 1..)]
         args: Vec<String>,
@@ -223,6 +409,44 @@ enum Command {
         #[arg(short, long)]
         yes: bool,
     },
+    /// Undo a commit with a revert commit at the tip of the branch that owns it
+    Revert {
+        /// Commit hash or short ID to revert
+        commit: String,
+        /// Place the revert on the integration line instead of the owning branch
+        #[arg(short, long)]
+        loose: bool,
+    },
+
+    /// Apply an upstream commit at the bottom of the integration line (or of a branch)
+    Backport {
+        /// Commit to apply: a hash or any git revision (e.g. origin/main~2)
+        commit: String,
+        /// Branch name or short ID to put the commit at the bottom of
+        #[arg(short, long)]
+        branch: Option<String>,
+    },
+
+    /// Apply several operations (drop, fixup, move, swap) in a single rebase
+    Batch {
+        /// File with one operation per line (reads stdin if omitted or `-`)
+        file: Option<String>,
+        /// Print the generated rebase todo without running it
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+
+    /// Recreate a branch's commits with a fresh committer date
+    Touch {
+        /// Branch name or short ID
+        branch: String,
+        /// Make yourself the author of the commits, dated now
+        #[arg(long)]
+        reset_author: bool,
+        /// GPG-sign the recreated commits
+        #[arg(short = 'S', long)]
+        sign: bool,
+    },
 
     // -- Branches --
     /// Manage feature branches (create, merge, unmerge)
@@ -236,6 +460,89 @@ enum Command {
         branch: Option<String>,
     },
 
+    /// Move a woven branch onto another base, removing it from the integration branch
+    RebaseBranch {
+        /// Branch name or short ID
+        branch: String,
+
+        /// New base: a branch, tag, or commit (e.g. a release branch for a hotfix)
+        #[arg(long)]
+        onto: String,
+    },
+
+    /// Divide a woven branch in two: picked commits move to a new branch
+    SplitBranch {
+        /// Branch name or short ID
+        branch: String,
+
+        /// Name of the new branch (if not provided, will prompt interactively)
+        new_branch: Option<String>,
+
+        /// Commit moving to the new branch (repeatable; a checklist if omitted)
+        #[arg(short = 'c', long = "commit", value_name = "COMMIT")]
+        commits: Vec<String>,
+
+        /// Stack the new branch on the old one even when they could be independent
+        #[arg(long)]
+        stack: bool,
+    },
+
+    /// Combine two woven branches into one
+    MergeBranches {
+        /// Branch name or short ID; it receives the commits of the other
+        branch: String,
+
+        /// Branch name or short ID of the branch to fold in (deleted unless --keep)
+        other: String,
+
+        /// Keep the other branch, pointing at the tip of the combined branch
+        #[arg(long)]
+        keep: bool,
+    },
+
+    /// Adopt a branch made outside loom: rebase it onto the merge-base and weave it
+    Attach {
+        /// Branch name (local, or remote like origin/feature)
+        branch: String,
+    },
+
+    /// Freeze a branch out of the weave into refs/loom/archived/
+    Archive {
+        /// Branch name or short ID (the archived branch name with --restore)
+        #[arg(required_unless_present = "list")]
+        branch: Option<String>,
+        /// List the archived branches
+        #[arg(short = 'l', long, conflicts_with_all = ["branch", "restore"])]
+        list: bool,
+        /// Recreate the branch from its archive
+        #[arg(short = 'r', long)]
+        restore: bool,
+    },
+
+    /// Check out the next woven branch up the stack
+    Next {
+        /// Detach HEAD at the branch tip instead of checking out the branch
+        #[arg(short = 'd', long = "detach")]
+        detach: bool,
+    },
+
+    /// Check out the previous woven branch down the stack
+    Prev {
+        /// Detach HEAD at the branch tip instead of checking out the branch
+        #[arg(short = 'd', long = "detach")]
+        detach: bool,
+    },
+
+    /// Return to the integration branch after `next` / `prev`
+    Top,
+
+    /// Check out woven branches in their own worktrees
+    #[command(visible_alias = "wt")]
+    Worktree {
+        #[command(subcommand)]
+        action: WorktreeAction,
+    },
+
     // -- Inspection --
     /// Show the branch-aware status
     Status {
@@ -248,6 +555,37 @@ enum Command {
         /// Show all branches including hidden ones (those matching loom.hideBranchPattern)
         #[arg(short = 'a', long = "all")]
         all: bool,
+        /// Only show commits touching paths matching this glob (repeatable)
+        #[arg(long = "path", value_name = "GLOB", conflicts_with = "porcelain")]
+        paths: Vec<String>,
+        /// Group working changes under the woven branch that last touched them
+        #[arg(short = 'g', long = "group", conflicts_with = "porcelain")]
+        group: bool,
+        /// Show a compact diffstat (+added/-removed lines) next to each commit
+        #[arg(long = "stat", conflicts_with = "porcelain")]
+        stat: bool,
+        /// Show the last `loom check` result of each branch tip (pass, fail, or unknown)
+        #[arg(long = "checks", conflicts_with = "porcelain")]
+        checks: bool,
+        /// When HEAD is detached, show the integration branch containing it (read-only)
+        #[arg(long = "detached")]
+        detached: bool,
+        /// Only list files changed these ways: A, D, M, R, U (lowercase to exclude)
+        #[arg(long = "diff-filter", value_name = "FILTER", value_parser = status::DiffFilter::parse)]
+        diff_filter: Option<status::DiffFilter>,
+        /// Machine-readable, tab-separated output in a stable, versioned format
+        #[arg(
+            long,
+            value_name = "VERSION",
+            num_args = 0..=1,
+            require_equals = true,
+            default_missing_value = "v1",
+            value_parser = ["v1"]
+        )]
+        porcelain: Option<String>,
+        /// Write the status as a standalone HTML page, with colors and forge links
+        #[arg(long, value_name = "FILE", conflicts_with = "porcelain")]
+        html: Option<std::path::PathBuf>,
     },
     /// Show the diff and metadata for a commit (like `git show`)
     #[command(visible_alias = "sh")]
@@ -267,8 +605,98 @@ enum Command {
         #[arg(short = 'a', long = "all")]
         all: bool,
     },
+    /// Show what a branch adds over its base as one combined diff
+    DiffBranch {
+        /// Branch name or short ID
+        branch: String,
+        /// What to diff against: `upstream`, or a branch or commit (default: the branch's own base)
+        base: Option<String>,
+        /// Show a diffstat instead of the patch
+        #[arg(long)]
+        stat: bool,
+    },
+    /// Run the configured `loom.checks` against a branch tip
+    Check {
+        /// Branch name or short ID
+        branch: String,
+        /// Run every check, even those that already passed on this tree
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Show which commits of the weave already have an equivalent upstream (by patch-id)
+    Cherry {
+        /// Upstream revision or `<from>..<to>` range (defaults to the tracked upstream)
+        upstream: Option<String>,
+    },
+    /// Summarize the authors of a woven branch (commits and lines per author)
+    Who {
+        /// Branch name or short ID
+        branch: String,
+    },
+    /// Open the forge page of a branch (its pull requests), commit, or commit file
+    Open {
+        /// Branch, commit, or commit file (short ID or name); the repository when omitted
+        target: Option<String>,
+        /// Print the URL instead of opening it
+        #[arg(long)]
+        print: bool,
+    },
+    /// Find which woven branches and commits modify a file or directory
+    Owns {
+        /// File or directory path
+        path: String,
+    },
+    /// Show which woven branches change the same files and lines
+    Overlap,
+    /// Export the weave topology as Graphviz DOT or Mermaid text
+    Graph {
+        /// Graphviz DOT (the default)
+        #[arg(long, conflicts_with = "mermaid")]
+        dot: bool,
+        /// Mermaid flowchart, for Markdown documents and pull request descriptions
+        #[arg(long)]
+        mermaid: bool,
+    },
+    /// Bisect the integration branch with short IDs and report the culprit's branch
+    Bisect {
+        #[command(subcommand)]
+        action: BisectAction,
+    },
     /// Show the latest command trace
     Trace,
+    /// Show the git commands a loom command would run, without changing the repository
+    Explain {
+        /// The loom command and its arguments, e.g. `fold ab cd`
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
+        command: Vec<String>,
+    },
+    /// Print a redacted environment report to attach to bug reports
+    Bugreport,
+    /// Summarize the local log of loom command usage and durations
+    Activity {
+        /// Delete the activity log
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Query the repository and print the result as JSON (for editor integrations)
+    Api {
+        #[command(subcommand)]
+        query: ApiQuery,
+    },
+    /// Run as a long-lived JSON-RPC server for editor integrations
+    Serve {
+        /// Read requests from stdin and write responses to stdout, one JSON object per line
+        #[arg(long, required = true)]
+        stdio: bool,
+    },
+    /// Render a status fixture (a repository described as JSON) as text, for snapshot tests
+    RenderFixture {
+        /// JSON fixture to render (`-` reads it from stdin)
+        fixture: std::path::PathBuf,
+        /// Shorten messages to fit this many columns (default: no limit)
+        #[arg(long)]
+        width: Option<u16>,
+    },
 
     // -- Recovery --
     /// Resume a paused loom operation after resolving conflicts
@@ -277,6 +705,20 @@ enum Command {
     /// Cancel a paused loom operation and restore original state
     #[command(visible_alias = "a")]
     Abort,
+    /// Save how conflicts were resolved and replay it when they come up again
+    Resolution {
+        #[command(subcommand)]
+        action: ResolutionAction,
+    },
+    /// Prune old loom logs, caches, and refs left over by interrupted operations
+    Gc {
+        /// Prune logs older than this many days (default: `loom.gcDays`, or 30)
+        #[arg(long)]
+        days: Option<i64>,
+        /// Show what would be pruned without removing anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
 
     // -- Hidden --
     /// Generate shell completions (powershell, clink)
@@ -294,6 +736,9 @@ enum Command {
         /// Path to the git rebase todo file (provided by git)
         todo_file: String,
     },
+    /// Internal: run by the rebase todo to record the rewritten commits in the oplog
+    #[command(hide = true)]
+    InternalRecordRewritten,
 }
 
 #[derive(Args)]
@@ -320,6 +765,10 @@ enum BranchAction {
         /// Also show remote branches without a local counterpart
         #[arg(short = 'a', long = "all")]
         all: bool,
+
+        /// Print which woven branches change the same files as this one
+        #[arg(long)]
+        explain: bool,
     },
 
     /// Remove a branch from the integration branch (keeps the branch ref)
@@ -329,25 +778,240 @@ enum BranchAction {
     },
 }
 
+#[derive(Subcommand)]
+enum WorktreeAction {
+    /// Create a worktree for a branch, or move it to the branch tip
+    Add {
+        /// Branch name or short ID
+        branch: String,
+
+        /// Where to create the worktree (default: next to the repository)
+        path: Option<String>,
+    },
+
+    /// Print the worktree path of a branch (for `cd "$(git loom worktree path fa)"`)
+    Path {
+        /// Branch name or short ID
+        branch: String,
+    },
+
+    /// Remove the worktrees of deleted branches
+    Prune,
+}
+
+#[derive(Subcommand)]
+enum MetaAction {
+    /// Record the weave structure in refs/loom/meta and push it
+    Push {
+        /// Remote to share on (default: the remote the integration branch tracks)
+        remote: Option<String>,
+    },
+
+    /// Fetch refs/loom/meta and recreate the branches it records
+    Pull {
+        /// Remote to fetch from (default: the remote the integration branch tracks)
+        remote: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ResolutionAction {
+    /// Save the resolved conflicts of the paused operation under a name
+    Save {
+        /// Name of the resolution (added to if it exists)
+        name: String,
+    },
+
+    /// Resolve the current conflicts with saved resolutions and stage them
+    Apply {
+        /// Only use this resolution (default: every saved one)
+        name: Option<String>,
+    },
+
+    /// List the saved resolutions
+    List,
+
+    /// Delete a saved resolution
+    Drop {
+        /// Name of the resolution
+        name: String,
+    },
+
+    /// Push the saved resolutions to share them with teammates
+    Push {
+        /// Remote to share on (default: the remote the integration branch tracks)
+        remote: Option<String>,
+    },
+
+    /// Fetch the resolutions teammates shared
+    Fetch {
+        /// Remote to fetch from (default: the remote the integration branch tracks)
+        remote: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum BisectAction {
+    /// Start bisecting (default: the integration tip is bad, the upstream base good)
+    Start {
+        /// Known bad commit: hash, branch, or short ID
+        bad: Option<String>,
+
+        /// Known good commits: hashes, branches, or short IDs
+        good: Vec<String>,
+
+        /// Only bisect the commits of this woven branch
+        #[arg(short, long)]
+        branch: Option<String>,
+    },
+
+    /// Mark commits (default: the checked-out one) as good
+    Good {
+        /// Commit hashes or short IDs
+        commits: Vec<String>,
+    },
+
+    /// Mark commits (default: the checked-out one) as bad
+    Bad {
+        /// Commit hashes or short IDs
+        commits: Vec<String>,
+    },
+
+    /// Skip commits (default: the checked-out one) that can't be tested
+    Skip {
+        /// Commit hashes or short IDs
+        commits: Vec<String>,
+    },
+
+    /// End the bisection and return to the integration branch
+    Reset,
+}
+
+#[derive(Subcommand)]
+enum ApiQuery {
+    /// Resolve a short ID, hash, branch name, or path
+    Resolve {
+        /// Short ID, commit hash, branch name, or file path
+        target: String,
+    },
+    /// List branch sections, loose commits, and working changes with their short IDs
+    Sections,
+    /// Print the rebase todo a hypothetical operation would run, without running it
+    Todo {
+        #[command(subcommand)]
+        op: ApiTodoOp,
+    },
+    /// Snapshot HEAD and all local branch refs
+    Refs,
+}
+
+#[derive(Subcommand)]
+enum ApiTodoOp {
+    /// Drop a commit or a branch
+    Drop {
+        /// Commit hash, branch name, or short ID
+        target: String,
+    },
+    /// Fixup a commit into another
+    Fixup {
+        /// Commit to fold away
+        source: String,
+        /// Commit that absorbs it
+        target: String,
+    },
+    /// Move a commit to a branch
+    Move {
+        /// Commit hash or short ID
+        commit: String,
+        /// Destination branch name or short ID
+        branch: String,
+    },
+    /// Swap two commits
+    Swap {
+        /// First commit hash or short ID
+        a: String,
+        /// Second commit hash or short ID
+        b: String,
+    },
+}
+
 #[derive(Args, Clone)]
 struct BranchNewArgs {
     /// Branch name (if not provided, will prompt interactively)
     name: Option<String>,
 
     /// Target commit, branch, or shortID (defaults to upstream base)
-    #[arg(short = 't', long = "target")]
+    #[arg(short = 't', long = "target", conflicts_with = "from_changes")]
     target: Option<String>,
+
+    /// Commit the working changes onto the new branch (staged ones, or all)
+    #[arg(long)]
+    from_changes: bool,
+
+    /// Message of the commit (with --from-changes; opens editor if omitted)
+    #[arg(short = 'm', long, requires = "from_changes")]
+    message: Option<String>,
+
+    /// Files to commit with --from-changes: short IDs, paths, or 'zz' for all
+    #[arg(requires = "from_changes")]
+    files: Vec<String>,
+}
+
+impl BranchNewArgs {
+    fn run(self, theme: &graph::Theme) -> anyhow::Result<()> {
+        if self.from_changes {
+            branch::new::run_from_changes(self.name, self.message, self.files, theme)
+        } else {
+            branch::new::run(self.name, self.target)
+        }
+    }
 }
 
 fn main() {
-    let cli = Cli::parse();
+    let started = (chrono::Local::now(), std::time::Instant::now());
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    // Canonical subcommand name (aliases resolved) for the activity log
+    let command_name = matches.subcommand_name().unwrap_or("status").to_string();
 
+    // CLICOLOR_FORCE keeps the colors in piped output (demos, golden files)
+    let force_color = std::env::var_os("CLICOLOR_FORCE").is_some_and(|v| !v.is_empty() && v != "0");
     if cli.no_color
         || std::env::var_os("NO_COLOR").is_some()
         || std::env::var_os("TERM").is_some_and(|v| v == "dumb")
-        || !std::io::stdout().is_terminal()
+        || !(force_color || std::io::stdout().is_terminal())
     {
         control::set_override(false);
+    } else if force_color {
+        control::set_override(true);
+    }
+
+    // A frozen clock also dates the commits git makes on loom's behalf
+    match clock::fake_time() {
+        Ok(Some(time)) => {
+            let date = clock::git_date(time);
+            for var in ["GIT_AUTHOR_DATE", "GIT_COMMITTER_DATE"] {
+                if std::env::var_os(var).is_none() {
+                    // SAFETY: single-threaded at this point, before any command runs.
+                    unsafe { std::env::set_var(var, &date) };
+                }
+            }
+        }
+        Ok(None) => {}
+        Err(e) => {
+            msg::error(&e.to_string());
+            std::process::exit(1);
+        }
+    }
+
+    // `--no-input` reaches the loom processes git runs for us through the
+    // environment; git itself must not ask for credentials either
+    if cli.no_input {
+        // SAFETY: single-threaded at this point, before any command runs.
+        unsafe {
+            std::env::set_var(msg::NO_INPUT_VAR, "1");
+            std::env::set_var("GIT_TERMINAL_PROMPT", "0");
+        }
     }
 
     // Completions don't need git, handle before version check
@@ -359,21 +1023,112 @@ fn main() {
         return;
     }
 
+    // Neither does help
+    if let Some(Command::Help { topic }) = &cli.command {
+        print_help(topic.as_deref());
+        return;
+    }
+
+    // Nor does rendering a fixture, which must not depend on one
+    if let Some(Command::RenderFixture { fixture, width }) = &cli.command {
+        let mut theme = resolve_theme(cli.theme);
+        theme.ascii = cli.ascii;
+        if let Err(e) = fixture::run(fixture, *width, theme) {
+            msg::error(&e.to_string());
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Self-update doesn't need a repository either
+    #[cfg(feature = "selfupdate")]
+    if let Some(Command::SelfUpdate { check, yes }) = cli.command {
+        if let Err(e) = git_loom::selfupdate::run(check, yes) {
+            msg::error(&e.to_string());
+            std::process::exit(1);
+        }
+        return;
+    }
+
     if let Err(e) = git::check_git_version() {
         msg::error(&e.to_string());
         std::process::exit(1);
     }
 
+    git::set_no_verify(cli.no_verify);
+    weave::set_retag(cli.retag);
+    weave::set_no_autostash(cli.no_autostash);
+
+    // `--git-dir` / `--work-tree` are exported so git subprocesses see the
+    // same repository. They are meant for inspecting a repository from
+    // outside (e.g. a bare one on a server), so mutating commands refuse them,
+    // and loom writes neither a trace nor an activity entry into it.
+    let inspecting = cli.git_dir.is_some() || cli.work_tree.is_some();
+    if inspecting {
+        let read_only = matches!(
+            cli.command,
+            None | Some(Command::Status { .. })
+                | Some(Command::Show { .. })
+                | Some(Command::Diff { .. })
+                | Some(Command::DiffBranch { .. })
+                | Some(Command::Cherry { .. })
+                | Some(Command::Who { .. })
+                | Some(Command::Owns { .. })
+                | Some(Command::Overlap)
+                | Some(Command::SizeCheck)
+                | Some(Command::Graph { .. })
+                | Some(Command::Open { .. })
+                | Some(Command::Api { .. })
+                | Some(Command::Trace)
+                | Some(Command::Bugreport)
+                | Some(Command::Activity { .. })
+        );
+        if !read_only {
+            msg::error(
+                "`--git-dir` and `--work-tree` are only supported by read-only commands\n\
+                 Use: status, show, diff, diff-branch, cherry, who, owns, overlap, size-check, graph, open, api, trace, bugreport, activity",
+            );
+            std::process::exit(1);
+        }
+        // SAFETY: single-threaded at this point, before any command runs.
+        if let Some(dir) = &cli.git_dir {
+            unsafe { std::env::set_var("GIT_DIR", dir) };
+        }
+        if let Some(dir) = &cli.work_tree {
+            unsafe { std::env::set_var("GIT_WORK_TREE", dir) };
+        }
+    }
+
     // Initialize logger for commands that modify the repo (skip for
-    // InternalWriteTodo — it runs as a subprocess — and Status/Trace/Show which are read-only).
+    // InternalWriteTodo — it runs as a subprocess — Status/Trace/Show/Api which are read-only,
+    // and Tour which only touches its own scratch repository).
     let should_log = !matches!(
         cli.command,
         Some(Command::InternalWriteTodo { .. })
+            | Some(Command::InternalRecordRewritten)
+            | Some(Command::Tour { .. })
+            | Some(Command::Api { .. })
+            | Some(Command::Serve { .. })
             | Some(Command::Trace)
+            | Some(Command::Explain { .. })
+            | Some(Command::Bugreport)
+            | Some(Command::Activity { .. })
             | Some(Command::Show { .. })
             | Some(Command::Diff { .. })
+            | Some(Command::DiffBranch { .. })
+            | Some(Command::Cherry { .. })
+            | Some(Command::Who { .. })
+            | Some(Command::Check { .. })
+            | Some(Command::Owns { .. })
+            | Some(Command::Overlap)
+            | Some(Command::SizeCheck)
+            | Some(Command::Graph { .. })
+            | Some(Command::Open { .. })
     );
-    if should_log && let Ok(repo) = repo::open_repo() {
+    if should_log
+        && !inspecting
+        && let Ok(repo) = repo::open_repo()
+    {
         let git_dir = repo.path().to_path_buf();
         let cmd_line = std::env::args().collect::<Vec<_>>().join(" ");
         if matches!(cli.command, Some(Command::Abort) | Some(Command::Continue)) {
@@ -384,16 +1139,32 @@ fn main() {
     }
 
     // Check for a paused loom operation and block most commands if one exists.
-    // Exempt: show, trace, continue, abort, completions, internal-write-todo.
+    // Exempt: show, api, serve, cherry, who, check, owns, overlap, size-check, graph, open, trace, bugreport, activity, continue, abort, resolution, tour, completions, internal-write-todo, internal-record-rewritten.
     let is_exempt = matches!(
         cli.command,
         Some(Command::Show { .. })
+            | Some(Command::Api { .. })
+            | Some(Command::Serve { .. })
+            | Some(Command::Tour { .. })
             | Some(Command::Diff { .. })
+            | Some(Command::DiffBranch { .. })
+            | Some(Command::Cherry { .. })
+            | Some(Command::Who { .. })
+            | Some(Command::Check { .. })
+            | Some(Command::Owns { .. })
+            | Some(Command::Overlap)
+            | Some(Command::SizeCheck)
+            | Some(Command::Graph { .. })
+            | Some(Command::Open { .. })
             | Some(Command::Trace)
+            | Some(Command::Bugreport)
+            | Some(Command::Activity { .. })
             | Some(Command::Continue)
             | Some(Command::Abort)
+            | Some(Command::Resolution { .. })
             | Some(Command::Completions { .. })
             | Some(Command::InternalWriteTodo { .. })
+            | Some(Command::InternalRecordRewritten)
     );
     if !is_exempt && let Ok(repo) = repo::open_repo() {
         let git_dir = repo.path().to_path_buf();
@@ -408,61 +1179,313 @@ fn main() {
         }
     }
 
-    let theme = resolve_theme(cli.theme);
+    // Commands that modify the repository hold `.git/loom/lock` while they
+    // run, so two of them never interleave their rebases. Read-only commands
+    // and the `internal-*` commands (run by git on behalf of a locked command)
+    // don't take it, nor does `update --daemon`, which locks each round.
+    let takes_lock = !matches!(
+        cli.command,
+        None | Some(Command::Status { .. })
+            | Some(Command::Update {
+                daemon: Some(_),
+                ..
+            })
+            | Some(Command::Show { .. })
+            | Some(Command::Diff { .. })
+            | Some(Command::DiffBranch { .. })
+            | Some(Command::Cherry { .. })
+            | Some(Command::Who { .. })
+            | Some(Command::Check { .. })
+            | Some(Command::Owns { .. })
+            | Some(Command::Overlap)
+            | Some(Command::SizeCheck)
+            | Some(Command::Graph { .. })
+            | Some(Command::Open { .. })
+            | Some(Command::Api { .. })
+            | Some(Command::Serve { .. })
+            | Some(Command::Trace)
+            | Some(Command::Explain { .. })
+            | Some(Command::Bugreport)
+            | Some(Command::Activity { .. })
+            | Some(Command::Tour { .. })
+            | Some(Command::Completions { .. })
+            | Some(Command::InternalWriteTodo { .. })
+            | Some(Command::InternalRecordRewritten)
+    );
+    let lock = match repo::open_repo() {
+        Ok(repo) if takes_lock => match lock::acquire_repo(&repo, &command_name) {
+            Ok(lock) => {
+                transaction::begin(&repo);
+                Some(lock)
+            }
+            Err(e) => {
+                msg::error(&e.to_string());
+                std::process::exit(1);
+            }
+        },
+        _ => None,
+    };
+
+    let mut theme = resolve_theme(cli.theme);
+    theme.ascii = cli.ascii
+        || graph::locale_is_ascii(
+            std::env::var("LC_ALL").ok().as_deref(),
+            std::env::var("LC_CTYPE").ok().as_deref(),
+            std::env::var("LANG").ok().as_deref(),
+        );
 
     let result = match cli.command {
-        None => status::run(cli.files, cli.context, cli.all, theme),
+        None => status::run(
+            cli.files,
+            cli.context,
+            cli.all,
+            cli.paths,
+            cli.group,
+            cli.stat,
+            cli.checks,
+            cli.detached,
+            cli.diff_filter,
+            false,
+            None,
+            theme,
+        ),
         Some(Command::Status {
             files,
             context,
             all,
-        }) => status::run(files, context, all, theme),
-        Some(Command::Init { name }) => init::run(name),
+            paths,
+            group,
+            stat,
+            checks,
+            detached,
+            diff_filter,
+            porcelain,
+            html,
+        }) => status::run(
+            files,
+            context,
+            all,
+            paths,
+            group,
+            stat,
+            checks,
+            detached,
+            diff_filter,
+            porcelain.is_some(),
+            html,
+            theme,
+        ),
+        Some(Command::Init { name, remote, base }) => init::run(name, remote, base),
         Some(Command::Add { files, patch }) => add::run(files, patch, &theme),
+        Some(Command::Ignore { patterns, remove }) => ignore::run(patterns, remove),
+        Some(Command::SizeCheck) => size_check::run(),
         Some(Command::Switch { branch }) => switch::run(branch),
+        Some(Command::RebaseBranch { branch, onto }) => branch::rebase::run(branch, onto),
+        Some(Command::SplitBranch {
+            branch,
+            new_branch,
+            commits,
+            stack,
+        }) => branch::split::run(branch, new_branch, commits, stack),
+        Some(Command::MergeBranches {
+            branch,
+            other,
+            keep,
+        }) => branch::combine::run(branch, other, keep),
+        Some(Command::Attach { branch }) => branch::attach::run(branch),
+        Some(Command::Archive {
+            branch,
+            list,
+            restore,
+        }) => archive::run(branch, list, restore),
+        Some(Command::Next { detach }) => stack::run(stack::Step::Next, detach),
+        Some(Command::Prev { detach }) => stack::run(stack::Step::Prev, detach),
+        Some(Command::Top) => stack::run(stack::Step::Top, false),
+        Some(Command::Bisect { action }) => match action {
+            BisectAction::Start { bad, good, branch } => bisect::start(bad, good, branch),
+            BisectAction::Good { commits } => bisect::mark("good", commits),
+            BisectAction::Bad { commits } => bisect::mark("bad", commits),
+            BisectAction::Skip { commits } => bisect::mark("skip", commits),
+            BisectAction::Reset => bisect::reset(),
+        },
+        Some(Command::Worktree { action }) => match action {
+            WorktreeAction::Add { branch, path } => worktree::run_add(branch, path),
+            WorktreeAction::Path { branch } => worktree::run_path(branch),
+            WorktreeAction::Prune => worktree::run_prune(),
+        },
         Some(Command::Branch(cmd)) => match cmd.action {
-            Some(BranchAction::New(args)) => branch::new::run(args.name, args.target),
-            Some(BranchAction::Merge { branch, all }) => branch::merge::run(branch, all),
+            Some(BranchAction::New(args)) => args.run(&theme),
+            Some(BranchAction::Merge {
+                branch,
+                all,
+                explain,
+            }) => branch::merge::run(branch, all, explain),
             Some(BranchAction::Unmerge { branch }) => branch::unmerge::run(branch),
-            None => branch::new::run(cmd.new_args.name, cmd.new_args.target),
+            None => cmd.new_args.run(&theme),
         },
         Some(Command::Reword { target, message }) => reword::run(target, message),
         Some(Command::Commit {
             branch,
             message,
             patch,
+            interactive,
+            coauthor,
             files,
-        }) => commit::run(branch, message, patch, files, &theme),
+        }) => commit::run(branch, message, patch, interactive, coauthor, files, &theme),
         Some(Command::Swap { a, b }) => swap::run(a, b),
         Some(Command::Drop { target, yes }) => drop::run(target, yes),
+        Some(Command::Revert { commit, loose }) => revert::run(commit, loose),
+        Some(Command::Backport { commit, branch }) => backport::run(commit, branch),
+        Some(Command::Batch { file, dry_run }) => batch::run(file, dry_run),
+        Some(Command::Touch {
+            branch,
+            reset_author,
+            sign,
+        }) => touch::run(branch, touch::TouchOpts { reset_author, sign }),
         Some(Command::Absorb { dry_run, files }) => absorb::run(dry_run, files),
         Some(Command::Show { target }) => show::run(target),
         Some(Command::Diff { args, staged, all }) => diff::run(args, staged, all),
+        Some(Command::DiffBranch { branch, base, stat }) => diff::run_branch(branch, base, stat),
+        Some(Command::Check { branch, force }) => check::run(branch, force),
+        Some(Command::Cherry { upstream }) => cherry::run(upstream),
+        Some(Command::Who { branch }) => who::run(branch),
+        Some(Command::Owns { path }) => owns::run(path),
+        Some(Command::Overlap) => overlap::run(),
+        Some(Command::Graph { mermaid, .. }) => topology::run(if mermaid {
+            topology::Format::Mermaid
+        } else {
+            topology::Format::Dot
+        }),
+        Some(Command::Open { target, print }) => open::run(target, print),
         Some(Command::Split {
             target,
             message,
             patch,
             files,
         }) => split::run(target, message, patch, files, &theme),
-        Some(Command::Push { branch, no_pr }) => push::run(branch, no_pr),
-        Some(Command::Update { yes }) => update::run(yes),
+        Some(Command::Push { all: true, .. }) => push::run_all(),
+        Some(Command::Push { branch, no_pr, .. }) => push::run(branch, no_pr),
+        Some(Command::Promote {
+            branch,
+            squash,
+            no_push,
+        }) => promote::run(branch, squash, no_push),
+        Some(Command::ExportState { file }) => state::run_export(file),
+        Some(Command::ImportState { file }) => state::run_import(file),
+        Some(Command::Meta { action }) => match action {
+            MetaAction::Push { remote } => meta::run_push(remote),
+            MetaAction::Pull { remote } => meta::run_pull(remote),
+        },
+        Some(Command::Update {
+            yes,
+            daemon: Some(interval),
+            ..
+        }) => update::daemon(interval, yes),
+        Some(Command::Update { yes, only, .. }) => update::run(yes, only),
+        Some(Command::Tour { keep }) => tour::run(keep),
         Some(Command::Fold {
             create,
             patch,
+            keep_trailers,
+            staged,
+            preview,
+            coauthor,
+            args,
+        }) => fold::run(
+            create,
+            patch,
+            keep_trailers,
+            staged,
+            preview,
+            coauthor,
             args,
-        }) => fold::run(create, patch, args, &theme),
+            &theme,
+        ),
         Some(Command::Trace) => trace::run(),
+        Some(Command::Explain { command }) => explain::run(command),
+        Some(Command::Bugreport) => bugreport::run(),
+        Some(Command::Activity { clear }) => activity::run(clear),
+        Some(Command::Serve { .. }) => serve::run(),
+        Some(Command::Api { query }) => api::run(match query {
+            ApiQuery::Resolve { target } => api::Query::Resolve(target),
+            ApiQuery::Sections => api::Query::Sections,
+            ApiQuery::Refs => api::Query::Refs,
+            ApiQuery::Todo { op } => api::Query::Todo(match op {
+                ApiTodoOp::Drop { target } => api::TodoOp::Drop { target },
+                ApiTodoOp::Fixup { source, target } => api::TodoOp::Fixup { source, target },
+                ApiTodoOp::Move { commit, branch } => api::TodoOp::Move { commit, branch },
+                ApiTodoOp::Swap { a, b } => api::TodoOp::Swap { a, b },
+            }),
+        }),
         Some(Command::Continue) => transaction::continue_run(),
         Some(Command::Abort) => transaction::abort_run(),
-        Some(Command::Completions { .. }) => unreachable!(),
+        Some(Command::Resolution { action }) => match action {
+            ResolutionAction::Save { name } => resolution::run_save(name),
+            ResolutionAction::Apply { name } => resolution::run_apply(name),
+            ResolutionAction::List => resolution::run_list(),
+            ResolutionAction::Drop { name } => resolution::run_drop(name),
+            ResolutionAction::Push { remote } => resolution::run_push(remote),
+            ResolutionAction::Fetch { remote } => resolution::run_fetch(remote),
+        },
+        Some(Command::Gc { days, dry_run }) => gc::run(days, dry_run),
+        Some(Command::Completions { .. })
+        | Some(Command::Help { .. })
+        | Some(Command::RenderFixture { .. }) => unreachable!(),
+        #[cfg(feature = "selfupdate")]
+        Some(Command::SelfUpdate { .. }) => unreachable!(),
         Some(Command::InternalWriteTodo { source, todo_file }) => {
             handle_write_todo(&source, &todo_file)
         }
+        Some(Command::InternalRecordRewritten) => {
+            repo::open_repo().and_then(|repo| oplog::record_rewritten(repo.path()))
+        }
     };
 
+    drop(lock);
     trace::finalize();
 
+    if !inspecting
+        && !matches!(
+            command_name.as_str(),
+            "internal-write-todo" | "internal-record-rewritten" | "activity"
+        )
+        && let Ok(repo) = repo::open_repo()
+    {
+        activity::record(
+            &repo,
+            &command_name,
+            started.0,
+            started.1.elapsed(),
+            result.is_ok(),
+        );
+    }
+
     if let Err(e) = result {
+        // The missing input is what to act on, whatever the command made of it
+        if let Some(cause) = e.chain().find(|c| c.is::<msg::InputRequired>()) {
+            msg::error(&cause.to_string());
+            std::process::exit(msg::NO_INPUT_EXIT_CODE);
+        }
+        msg::error(&e.to_string());
+        std::process::exit(1);
+    }
+}
+
+/// `loom help [topic]`: the command overview followed by the guide topics,
+/// a command's help, or a guide page.
+fn print_help(topic: Option<&str>) {
+    let Some(name) = topic else {
+        let _ = Cli::command().print_help();
+        println!("\n{}", help::topic_list());
+        return;
+    };
+    if help::find(name).is_none()
+        && let Err(e) = Cli::command().try_get_matches_from(["git-loom", name, "--help"])
+        && e.kind() == clap::error::ErrorKind::DisplayHelp
+    {
+        e.exit();
+    }
+    if let Err(e) = help::run(name) {
         msg::error(&e.to_string());
         std::process::exit(1);
     }
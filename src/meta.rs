@@ -0,0 +1,166 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use git2::{BranchType, Oid, Repository};
+
+use crate::core::msg;
+use crate::core::repo;
+use crate::git;
+use crate::state::{self, WeaveState};
+
+/// Ref holding the structure of the weave shared with teammates.
+pub const META_REF: &str = "refs/loom/meta";
+
+/// Where the notes shared along the metadata are fetched before being merged.
+pub(crate) const INCOMING_NOTES_REF: &str = "refs/loom/incoming-notes";
+
+/// Share the structure of the weave on `remote` (by default the remote the
+/// integration branch tracks).
+pub fn run_push(remote: Option<String>) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "share the weave")?;
+    let remote = resolve_remote(&repo, remote)?;
+    let state = publish(&repo, workdir, &remote)?;
+    msg::success(&format!(
+        "Shared the structure of `{}` and {} woven {} on `{}`",
+        state.integration.name,
+        state.branches.len(),
+        if state.branches.len() == 1 {
+            "branch"
+        } else {
+            "branches"
+        },
+        remote
+    ));
+    Ok(())
+}
+
+/// Recreate the branches of the weave structure shared on `remote`.
+pub fn run_pull(remote: Option<String>) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "fetch the shared weave")?;
+    let remote = resolve_remote(&repo, remote)?;
+    let created = pull(&repo, workdir, &remote)?;
+    if created.is_empty() {
+        msg::success(&format!(
+            "Every branch of the weave shared on `{}` is already here",
+            remote
+        ));
+    } else {
+        let names: Vec<String> = created.iter().map(|n| format!("`{}`", n)).collect();
+        msg::success(&format!(
+            "Recreated {} from the weave shared on `{}`",
+            names.join(", "),
+            remote
+        ));
+    }
+    Ok(())
+}
+
+/// `remote` if it exists, or else the remote the current branch tracks.
+pub fn resolve_remote(repo: &Repository, remote: Option<String>) -> Result<String> {
+    if let Some(remote) = remote {
+        repo.find_remote(&remote)
+            .with_context(|| format!("Remote `{}` not found", remote))?;
+        return Ok(remote);
+    }
+    let head = repo.head().context("Failed to get HEAD reference")?;
+    head.name()
+        .and_then(|name| repo.branch_upstream_remote(name).ok())
+        .and_then(|remote| remote.as_str().map(str::to_string))
+        .context("The current branch tracks no remote\nName the remote to share the weave with")
+}
+
+/// Record the weave checked out in `repo` in `refs/loom/meta` and push it to
+/// `remote`, with the notes.
+///
+/// Archived branches and push remotes are left out: they are personal. The
+/// previous metadata is kept as the parent commit, but the push replaces
+/// what is on the remote: the last one to share wins.
+pub fn publish(repo: &Repository, workdir: &Path, remote: &str) -> Result<WeaveState> {
+    let mut state = state::collect(repo)?;
+    state.archived.clear();
+    state.integration.push_remote = None;
+    for branch in &mut state.branches {
+        branch.push_remote = None;
+    }
+
+    let parent = repo.refname_to_id(META_REF).ok();
+    let commit = state::commit_state(repo, &state, parent, "loom: share weave")?;
+    repo.reference(META_REF, commit, true, "loom: share weave")?;
+    let refspec = format!("+{0}:{0}", META_REF);
+    git::run_git(workdir, &["push", "-q", remote, &refspec])
+        .with_context(|| format!("Could not push `{}` to `{}`", META_REF, remote))?;
+
+    if let Some(notes_ref) = &state.notes_ref
+        && git::run_git(
+            workdir,
+            &["push", "-q", remote, &format!("{0}:{0}", notes_ref)],
+        )
+        .is_err()
+    {
+        msg::warn(&format!(
+            "Could not push the notes of `{}` to `{}`\n\
+             Run `loom meta pull` to merge the shared notes first",
+            notes_ref, remote
+        ));
+    }
+    Ok(state)
+}
+
+/// Fetch `refs/loom/meta` from `remote` and recreate the branches it records
+/// that are missing here, with their tracking configuration. Returns their
+/// names.
+///
+/// Existing branches are left alone, and branches whose commits have not been
+/// fetched yet are skipped with a warning. Shared notes are merged.
+pub fn pull(repo: &Repository, workdir: &Path, remote: &str) -> Result<Vec<String>> {
+    let refspec = format!("+{0}:{0}", META_REF);
+    git::run_git(workdir, &["fetch", "-q", "--no-tags", remote, &refspec]).with_context(|| {
+        format!(
+            "`{}` has no shared weave\nRun `loom meta push` where the weave was built",
+            remote
+        )
+    })?;
+    let state = state::read_state(repo, META_REF)?;
+
+    if let Some(notes_ref) = &state.notes_ref {
+        let refspec = format!("+{}:{}", notes_ref, INCOMING_NOTES_REF);
+        if git::run_git(workdir, &["fetch", "-q", "--no-tags", remote, &refspec]).is_ok() {
+            let merged = state::merge_notes(repo, workdir, notes_ref, INCOMING_NOTES_REF);
+            repo.find_reference(INCOMING_NOTES_REF)?.delete()?;
+            merged?;
+        }
+    }
+
+    let mut created = Vec::new();
+    let mut missing = Vec::new();
+    for branch in std::iter::once(&state.integration).chain(&state.branches) {
+        if repo.find_branch(&branch.name, BranchType::Local).is_ok() {
+            continue;
+        }
+        let tip = Oid::from_str(&branch.tip)?;
+        if repo.find_commit(tip).is_err() {
+            missing.push(format!("`{}`", branch.name));
+            continue;
+        }
+        state::restore_branch(repo, branch, tip, false)?;
+        created.push(branch.name.clone());
+    }
+    if !missing.is_empty() {
+        msg::warn(&format!(
+            "{} not recreated: {} commits are not here\n\
+             Fetch the integration branch `{}` from `{}`, then pull again",
+            missing.join(", "),
+            if missing.len() == 1 { "its" } else { "their" },
+            state.integration.name,
+            remote
+        ));
+    }
+    state::adopt_settings(repo, &state)?;
+    Ok(created)
+}
+
+#[cfg(test)]
+#[path = "meta_test.rs"]
+mod tests;
@@ -0,0 +1,64 @@
+use crate::core::test_helpers::TestRepo;
+use crate::git;
+
+/// Integration branch weaving `feature-a` and `feature-b`, with `feature-b`
+/// pushed to `fork`.
+fn setup() -> TestRepo {
+    let test_repo = TestRepo::new_with_woven_branches();
+    test_repo.set_config("branch.feature-b.loomPushRemote", "fork");
+    test_repo
+}
+
+#[test]
+fn push_then_pull_recreates_the_weave_structure() {
+    let source = setup();
+    git::run_git(&source.workdir(), &["push", "-q", "origin", "integration"]).unwrap();
+    source.in_dir(|| super::run_push(None)).unwrap();
+
+    let target = source.clone_remote();
+    target.in_dir(|| super::run_pull(None)).unwrap();
+
+    target.switch_branch("integration");
+    assert_eq!(target.branch_tips(), source.branch_tips());
+    let config = target.repo.config().unwrap().snapshot().unwrap();
+    assert_eq!(
+        config.get_str("branch.integration.merge").unwrap(),
+        "refs/heads/main"
+    );
+    // Push remotes name someone's fork: they are not shared
+    assert!(config.get_str("branch.feature-b.loomPushRemote").is_err());
+}
+
+#[test]
+fn pull_keeps_local_branches_and_skips_missing_commits() {
+    let source = setup();
+    source.in_dir(|| super::run_push(None)).unwrap();
+
+    // The integration branch was never pushed: its commits are not there
+    let target = source.clone_remote();
+    let main = target.head_oid();
+    target.create_branch_at_commit("feature-a", main);
+    let created = super::pull(&target.repo, &target.workdir(), "origin").unwrap();
+
+    assert!(created.is_empty());
+    assert_eq!(target.get_branch_target("feature-a"), main);
+    assert!(!target.branch_exists("feature-b"));
+}
+
+#[test]
+fn push_keeps_the_previous_structure_as_parent() {
+    let source = setup();
+    source.in_dir(|| super::run_push(None)).unwrap();
+    let first = source.repo.refname_to_id(super::META_REF).unwrap();
+    source.in_dir(|| super::run_push(None)).unwrap();
+
+    let meta = source
+        .repo
+        .find_reference(super::META_REF)
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(meta.parent_id(0).unwrap(), first);
+    let remote = git2::Repository::open(source.remote_path().unwrap()).unwrap();
+    assert_eq!(remote.refname_to_id(super::META_REF).unwrap(), meta.id());
+}
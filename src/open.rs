@@ -0,0 +1,130 @@
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use git2::{BranchType, Oid, Repository};
+
+use crate::core::forge::Forge;
+use crate::core::msg;
+use crate::core::repo::{self, Target, TargetKind};
+use crate::git;
+
+/// Open the forge page of a branch, commit, or commit file in the browser.
+///
+/// Branches open the pull requests made from them, commits their commit
+/// page, and commit files (`02:0`) the file as of that commit. Without a
+/// target, the repository's home page opens. With `print`, the URL is
+/// printed instead, for scripts.
+pub fn run(target: Option<String>, print: bool) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let forge = upstream_forge(&repo)?;
+    let url = match target {
+        Some(target) => target_url(&repo, &forge, &target)?,
+        None => forge.web_url.clone(),
+    };
+
+    if print {
+        println!("{}", url);
+        return Ok(());
+    }
+    open_in_browser(&url)?;
+    msg::success(&format!("Opened {}", url));
+    Ok(())
+}
+
+/// The forge hosting the remote of the integration branch's upstream.
+fn upstream_forge(repo: &Repository) -> Result<Forge> {
+    let info = repo::gather_repo_info(repo, false, 1)?;
+    if info.upstream.local_only {
+        bail!("The integration branch has no upstream remote to open pages on");
+    }
+    let remote = info.upstream.label.split('/').next().unwrap_or_default();
+    Forge::detect(repo, remote).with_context(|| {
+        format!(
+            "Remote `{}` is not on a known forge (GitHub, GitLab, Bitbucket, Gitea, Azure DevOps)\n\
+             Set `loom.remote-type` if it is self-hosted",
+            remote
+        )
+    })
+}
+
+/// The forge page of `target`.
+pub fn target_url(repo: &Repository, forge: &Forge, target: &str) -> Result<String> {
+    let resolved = repo::resolve_arg(
+        repo,
+        target,
+        &[
+            TargetKind::Branch,
+            TargetKind::Commit,
+            TargetKind::CommitFile,
+        ],
+    )?;
+    Ok(match resolved {
+        Target::Branch(name) => {
+            let branch = repo.find_branch(&name, BranchType::Local)?;
+            if branch.upstream().is_err() {
+                bail!(
+                    "Branch `{}` was never pushed, it has no pull request\n\
+                     Push it with `loom push {}`",
+                    name,
+                    name
+                );
+            }
+            forge.branch_url(&name)
+        }
+        Target::Commit(hash) => {
+            warn_if_unpushed(repo, Oid::from_str(&hash)?)?;
+            forge.commit_url(&hash)
+        }
+        Target::CommitFile { commit, path } => {
+            warn_if_unpushed(repo, Oid::from_str(&commit)?)?;
+            forge.file_url(&commit, &path)
+        }
+        _ => bail!("Target must be a branch, commit, or commit file"),
+    })
+}
+
+/// Warn when no remote branch contains `oid`: the forge doesn't know it.
+fn warn_if_unpushed(repo: &Repository, oid: Oid) -> Result<()> {
+    for branch in repo.branches(Some(BranchType::Remote))? {
+        let Some(tip) = branch?.0.get().target() else {
+            continue;
+        };
+        if tip == oid || repo.graph_descendant_of(tip, oid)? {
+            return Ok(());
+        }
+    }
+    msg::warn(&format!(
+        "Commit `{}` is not pushed yet, its page may not exist",
+        git::short_hash(&oid.to_string())
+    ));
+    Ok(())
+}
+
+/// Open `url` with `$BROWSER`, or the platform's default handler.
+pub fn open_in_browser(url: &str) -> Result<()> {
+    let mut cmd = match std::env::var("BROWSER") {
+        Ok(browser) if !browser.is_empty() => Command::new(browser),
+        _ if cfg!(windows) => {
+            let mut cmd = Command::new("cmd");
+            cmd.args(["/C", "start", ""]);
+            cmd
+        }
+        _ if cfg!(target_os = "macos") => Command::new("open"),
+        _ => Command::new("xdg-open"),
+    };
+    let status = cmd
+        .arg(url)
+        .status()
+        .context("Could not start a browser\nUse --print to get the URL instead")?;
+    if !status.success() {
+        bail!(
+            "The browser failed to open {}\nUse --print to get the URL instead",
+            url
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "open_test.rs"]
+mod tests;
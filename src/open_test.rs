@@ -0,0 +1,49 @@
+use crate::core::forge::Forge;
+use crate::core::repo::gather_repo_info;
+use crate::core::shortid::IdAllocator;
+use crate::core::test_helpers::TestRepo;
+
+use super::target_url;
+
+fn github() -> Forge {
+    Forge::from_url("git@github.com:owner/repo.git", None).unwrap()
+}
+
+#[test]
+fn commit_and_commit_file_urls() {
+    let test_repo = TestRepo::new_with_remote();
+    let oid = test_repo.commit("Add guide", "guide.md");
+
+    let url = target_url(&test_repo.repo, &github(), &oid.to_string()).unwrap();
+    assert_eq!(url, format!("https://github.com/owner/repo/commit/{}", oid));
+
+    let info = gather_repo_info(&test_repo.repo, true, 1).unwrap();
+    let ids = IdAllocator::new(info.collect_entities());
+    let sid = ids.get_commit(oid);
+    let url = target_url(&test_repo.repo, &github(), &format!("{}:0", sid)).unwrap();
+    assert_eq!(
+        url,
+        format!("https://github.com/owner/repo/blob/{}/guide.md", oid)
+    );
+}
+
+#[test]
+fn pushed_branch_links_to_its_pull_requests() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.create_branch_tracking("feature-a", "origin/main");
+
+    let url = target_url(&test_repo.repo, &github(), "feature-a").unwrap();
+    assert_eq!(
+        url,
+        "https://github.com/owner/repo/pulls?q=is%3Apr+head%3Afeature-a"
+    );
+}
+
+#[test]
+fn unpushed_branch_is_refused() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.create_branch("feature-a");
+
+    let err = target_url(&test_repo.repo, &github(), "feature-a").unwrap_err();
+    assert!(err.to_string().contains("never pushed"), "{}", err);
+}
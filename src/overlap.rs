@@ -0,0 +1,355 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::RangeInclusive;
+
+use anyhow::Result;
+use colored::Colorize;
+use git2::{DiffOptions, Oid, Repository};
+
+use crate::core::msg;
+use crate::core::repo;
+use crate::core::weave::{BranchSection, Weave};
+
+/// Files changed by one woven branch section, or by a branch about to be
+/// woven.
+#[derive(Debug, PartialEq, Eq)]
+pub struct BranchFiles {
+    pub name: String,
+    pub files: BTreeSet<String>,
+}
+
+/// Two woven branches changing the same files.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Overlap {
+    pub a: String,
+    pub b: String,
+    /// Files both branches change, with the line ranges of each side when
+    /// they touch the same lines of the base.
+    pub files: Vec<SharedFile>,
+}
+
+/// A file changed by both branches of an [`Overlap`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct SharedFile {
+    pub path: String,
+    /// Overlapping base line ranges of each side, empty when the branches
+    /// change different parts of the file.
+    pub clashes: Vec<(RangeInclusive<u32>, RangeInclusive<u32>)>,
+}
+
+impl Overlap {
+    /// Number of shared files whose changes touch the same lines.
+    pub fn clashing_files(&self) -> usize {
+        self.files.iter().filter(|f| !f.clashes.is_empty()).count()
+    }
+}
+
+/// Report which woven branches change the same files.
+///
+/// Prints the overlap matrix, then each overlapping pair, those changing the
+/// same lines first: they are the ones likely to conflict when the weave is
+/// reordered or updated onto a new upstream.
+pub fn run() -> Result<()> {
+    let repo = repo::open_repo()?;
+    let graph = Weave::from_repo(&repo)?;
+    if graph.branch_sections.is_empty() {
+        println!("No woven branches");
+        return Ok(());
+    }
+    print!("{}", render_matrix(&woven_files(&repo, &graph)?));
+
+    let overlaps = find_overlaps(&repo, &graph)?;
+    println!();
+    if overlaps.is_empty() {
+        println!("No woven branches change the same files");
+        return Ok(());
+    }
+    println!(
+        "{}",
+        "Overlapping branches, most likely to conflict first:".bold()
+    );
+    for overlap in &overlaps {
+        let clashing = overlap.clashing_files();
+        let summary = format!(
+            "{} {}, {} with overlapping lines",
+            overlap.files.len(),
+            if overlap.files.len() == 1 {
+                "file"
+            } else {
+                "files"
+            },
+            clashing
+        );
+        println!(
+            "  {} {} {}  {}",
+            format!("[{}]", overlap.a).green().bold(),
+            "↔".dimmed(),
+            format!("[{}]", overlap.b).green().bold(),
+            if clashing > 0 {
+                summary.red().to_string()
+            } else {
+                summary.dimmed().to_string()
+            }
+        );
+        for file in &overlap.files {
+            if file.clashes.is_empty() {
+                println!("      {}", file.path.dimmed());
+                continue;
+            }
+            let ranges: Vec<String> = file
+                .clashes
+                .iter()
+                .map(|(a, b)| format!("{} / {}", format_range(a), format_range(b)))
+                .collect();
+            println!(
+                "      {}  {}",
+                file.path.yellow(),
+                format!("lines {}", ranges.join(", ")).dimmed()
+            );
+        }
+    }
+    Ok(())
+}
+
+fn format_range(range: &RangeInclusive<u32>) -> String {
+    if range.start() == range.end() {
+        range.start().to_string()
+    } else {
+        format!("{}-{}", range.start(), range.end())
+    }
+}
+
+/// Every pair of woven branches changing the same files, those with the
+/// most files changing the same lines first, then the most shared files.
+pub fn find_overlaps(repo: &Repository, graph: &Weave) -> Result<Vec<Overlap>> {
+    let ranges: Vec<BTreeMap<String, Vec<RangeInclusive<u32>>>> = graph
+        .branch_sections
+        .iter()
+        .map(|section| section_ranges(repo, section))
+        .collect::<Result<_>>()?;
+
+    let mut overlaps = Vec::new();
+    for (i, a) in ranges.iter().enumerate() {
+        for (j, b) in ranges.iter().enumerate().skip(i + 1) {
+            let files: Vec<SharedFile> = a
+                .iter()
+                .filter_map(|(path, a_ranges)| {
+                    let b_ranges = b.get(path)?;
+                    let mut clashes = Vec::new();
+                    for ra in a_ranges {
+                        for rb in b_ranges {
+                            // Adjacent changes conflict too
+                            if ra.start() <= &(rb.end() + 1) && rb.start() <= &(ra.end() + 1) {
+                                clashes.push((ra.clone(), rb.clone()));
+                            }
+                        }
+                    }
+                    Some(SharedFile {
+                        path: path.clone(),
+                        clashes,
+                    })
+                })
+                .collect();
+            if !files.is_empty() {
+                overlaps.push(Overlap {
+                    a: graph.branch_sections[i].label.clone(),
+                    b: graph.branch_sections[j].label.clone(),
+                    files,
+                });
+            }
+        }
+    }
+    overlaps.sort_by_key(|o| std::cmp::Reverse((o.clashing_files(), o.files.len())));
+    Ok(overlaps)
+}
+
+/// Lines of the section's base each file's changes replace, from the parent
+/// of its first commit to its tip. A pure insertion covers the line it
+/// follows.
+fn section_ranges(
+    repo: &Repository,
+    section: &BranchSection,
+) -> Result<BTreeMap<String, Vec<RangeInclusive<u32>>>> {
+    let mut ranges: BTreeMap<String, Vec<RangeInclusive<u32>>> = BTreeMap::new();
+    let (Some(first), Some(last)) = (section.commits.first(), section.commits.last()) else {
+        return Ok(ranges);
+    };
+    let first = repo.find_commit(first.oid)?;
+    let base_tree = if first.parent_count() > 0 {
+        Some(first.parent(0)?.tree()?)
+    } else {
+        None
+    };
+    let tip_tree = repo.find_commit(last.oid)?.tree()?;
+    let mut opts = DiffOptions::new();
+    opts.context_lines(0);
+    let diff = repo.diff_tree_to_tree(base_tree.as_ref(), Some(&tip_tree), Some(&mut opts))?;
+    diff.foreach(
+        &mut |_, _| true,
+        None,
+        Some(&mut |delta, hunk| {
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let start = hunk.old_start();
+            let end = start + hunk.old_lines().max(1) - 1;
+            ranges.entry(path).or_default().push(start..=end);
+            true
+        }),
+        None,
+    )?;
+    // Binary files and mode changes have no hunks but still overlap
+    for delta in diff.deltas() {
+        if let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) {
+            ranges
+                .entry(path.to_string_lossy().into_owned())
+                .or_default();
+        }
+    }
+    Ok(ranges)
+}
+
+/// Files changed by each branch section of the weave, in weave order. A
+/// stacked section only counts its own commits.
+pub fn woven_files(repo: &Repository, graph: &Weave) -> Result<Vec<BranchFiles>> {
+    graph
+        .branch_sections
+        .iter()
+        .map(|section| {
+            let oids: Vec<Oid> = section.commits.iter().map(|c| c.oid).collect();
+            Ok(BranchFiles {
+                name: section.label.clone(),
+                files: commits_files(repo, &oids)?,
+            })
+        })
+        .collect()
+}
+
+/// Files changed by the commits reachable from `tip` but not from HEAD:
+/// what weaving that branch would bring in.
+pub fn incoming_files(repo: &Repository, tip: Oid) -> Result<BTreeSet<String>> {
+    let mut walk = repo.revwalk()?;
+    walk.push(tip)?;
+    walk.hide(repo.head()?.peel_to_commit()?.id())?;
+    let oids = walk.collect::<Result<Vec<Oid>, _>>()?;
+    commits_files(repo, &oids)
+}
+
+fn commits_files(repo: &Repository, oids: &[Oid]) -> Result<BTreeSet<String>> {
+    let mut files = BTreeSet::new();
+    for &oid in oids {
+        files.extend(crate::core::repo::commit_file_paths(repo, oid)?);
+    }
+    Ok(files)
+}
+
+/// Before weaving `name` at `tip`, suggest where it conflicts least.
+///
+/// A branch sharing files with woven sections is best stacked on the one it
+/// overlaps most, so its changes apply on top of that section instead of
+/// meeting them in a merge. Sections `tip` is already stacked on are not
+/// suggested. With `explain`, the overlap matrix is printed first.
+pub fn advise_weave(repo: &Repository, name: &str, tip: Oid, explain: bool) -> Result<()> {
+    let graph = Weave::from_repo(repo)?;
+    let mut branches = woven_files(repo, &graph)?;
+    branches.push(BranchFiles {
+        name: name.to_string(),
+        files: incoming_files(repo, tip)?,
+    });
+    if explain {
+        print!("{}", render_matrix(&branches));
+    }
+
+    let incoming = branches.pop().expect("incoming branch was just pushed");
+    let mut overlapping: Vec<(&BranchFiles, usize)> = Vec::new();
+    for (branch, section) in branches.iter().zip(&graph.branch_sections) {
+        let shared = branch.files.intersection(&incoming.files).count();
+        let Some(section_tip) = section.commits.last().map(|c| c.oid) else {
+            continue;
+        };
+        if shared > 0 && tip != section_tip && !repo.graph_descendant_of(tip, section_tip)? {
+            overlapping.push((branch, shared));
+        }
+    }
+    // Most shared files first, weave order among ties
+    overlapping.sort_by_key(|(_, shared)| std::cmp::Reverse(*shared));
+    let Some((best, shared)) = overlapping.first() else {
+        return Ok(());
+    };
+
+    let others: Vec<String> = overlapping[1..]
+        .iter()
+        .map(|(branch, count)| format!("`{}` ({})", branch.name, count))
+        .collect();
+    msg::warn(&format!(
+        "`{}` changes {} {} also changed by `{}`{}\n\
+         Stacking it on `{}` avoids a conflicting merge: `git rebase {} {}`, then weave it",
+        name,
+        shared,
+        if *shared == 1 { "file" } else { "files" },
+        best.name,
+        if others.is_empty() {
+            String::new()
+        } else {
+            format!(", and by {}", others.join(", "))
+        },
+        best.name,
+        best.name,
+        name
+    ));
+    Ok(())
+}
+
+/// Render the number of files each pair of branches changes in common.
+/// The diagonal holds the number of files each branch changes.
+pub fn render_matrix(branches: &[BranchFiles]) -> String {
+    let width = branches
+        .iter()
+        .map(|b| b.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut out = format!("{}\n", "Files changed in common:".bold());
+    out.push_str(&format!("  {:width$}    ", "", width = width + 4));
+    for i in 1..=branches.len() {
+        out.push_str(&format!("{:>5}", format!("[{}]", i)));
+    }
+    out.push('\n');
+    for (i, row) in branches.iter().enumerate() {
+        out.push_str(&format!(
+            "  {:>4} {:width$}   ",
+            format!("[{}]", i + 1),
+            row.name,
+            width = width
+        ));
+        for (j, column) in branches.iter().enumerate() {
+            let count = if i == j {
+                row.files.len()
+            } else {
+                row.files.intersection(&column.files).count()
+            };
+            let cell = format!(
+                "{:>5}",
+                if count == 0 {
+                    "·".to_string()
+                } else {
+                    count.to_string()
+                }
+            );
+            out.push_str(&if i != j && count > 0 {
+                cell.yellow().to_string()
+            } else if count == 0 {
+                cell.dimmed().to_string()
+            } else {
+                cell
+            });
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+#[path = "overlap_test.rs"]
+mod tests;
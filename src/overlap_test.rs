@@ -0,0 +1,165 @@
+use std::collections::BTreeSet;
+
+use crate::core::test_helpers::TestRepo;
+use crate::core::weave::Weave;
+
+/// Integration branch weaving `feature-a` (a.txt, shared.txt) and
+/// `feature-b` (b.txt), plus an unwoven `feature-c` touching `c_files`.
+fn setup(c_files: &[&str]) -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    for name in ["feature-a", "feature-b", "feature-c"] {
+        test_repo.create_branch_at(name, &base_oid.to_string());
+    }
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a.txt");
+    test_repo.commit("A2", "shared.txt");
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1", "b.txt");
+    test_repo.switch_branch("feature-c");
+    for (i, file) in c_files.iter().enumerate() {
+        test_repo.commit(&format!("C{}", i + 1), file);
+    }
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-a");
+    test_repo.merge_no_ff("feature-b");
+    test_repo
+}
+
+fn files(names: &[&str]) -> BTreeSet<String> {
+    names.iter().map(|n| n.to_string()).collect()
+}
+
+#[test]
+fn woven_files_per_section() {
+    let test_repo = setup(&["c.txt"]);
+    let graph = Weave::from_repo(&test_repo.repo).unwrap();
+
+    let woven = super::woven_files(&test_repo.repo, &graph).unwrap();
+
+    assert_eq!(woven.len(), 2);
+    assert_eq!(woven[0].name, "feature-a");
+    assert_eq!(woven[0].files, files(&["a.txt", "shared.txt"]));
+    assert_eq!(woven[1].name, "feature-b");
+    assert_eq!(woven[1].files, files(&["b.txt"]));
+}
+
+#[test]
+fn incoming_files_exclude_integration_commits() {
+    let test_repo = setup(&["c.txt", "shared.txt"]);
+    let tip = test_repo.get_branch_target("feature-c");
+
+    let incoming = super::incoming_files(&test_repo.repo, tip).unwrap();
+
+    assert_eq!(incoming, files(&["c.txt", "shared.txt"]));
+}
+
+#[test]
+fn matrix_counts_shared_files() {
+    colored::control::set_override(false);
+    let branches = vec![
+        super::BranchFiles {
+            name: "feature-a".to_string(),
+            files: files(&["a.txt", "shared.txt"]),
+        },
+        super::BranchFiles {
+            name: "c".to_string(),
+            files: files(&["shared.txt"]),
+        },
+    ];
+
+    let matrix = super::render_matrix(&branches);
+
+    let lines: Vec<&str> = matrix.lines().collect();
+    assert_eq!(lines[0], "Files changed in common:");
+    assert_eq!(lines[1], "                     [1]  [2]");
+    assert_eq!(lines[2], "   [1] feature-a       2    1");
+    assert_eq!(lines[3], "   [2] c               1    1");
+}
+
+#[test]
+fn merge_with_explain_weaves() {
+    let test_repo = setup(&["c.txt"]);
+
+    test_repo
+        .in_dir(|| crate::branch::merge::run(Some("feature-c".to_string()), false, true))
+        .unwrap();
+
+    let head = test_repo.find_commit(test_repo.head_oid());
+    assert_eq!(head.parent_count(), 2);
+    assert_eq!(
+        head.parent_id(1).unwrap(),
+        test_repo.get_branch_target("feature-c")
+    );
+}
+
+/// Integration branch weaving `feature-a`, `feature-b` and `feature-c` off a
+/// base with a ten-line `file.txt`. `a` and `b` change the same lines of it,
+/// `c` changes a distant one.
+fn setup_lines() -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let lines: Vec<String> = (1..=10).map(|i| format!("line {}", i)).collect();
+    let base = test_repo.commit_multi(&[("file.txt", &(lines.join("\n") + "\n"))], "Base");
+    let edit = |line: usize, text: &str| {
+        let mut edited = lines.clone();
+        edited[line - 1] = text.to_string();
+        edited.join("\n") + "\n"
+    };
+    for (name, line) in [("feature-a", 2), ("feature-b", 3), ("feature-c", 9)] {
+        test_repo.create_branch_at(name, &base.to_string());
+        test_repo.switch_branch(name);
+        test_repo.commit_multi(&[("file.txt", &edit(line, name))], name);
+        test_repo.switch_branch("integration");
+    }
+    test_repo
+}
+
+#[test]
+fn overlaps_rank_same_lines_first() {
+    let test_repo = setup_lines();
+    let graph = Weave::from_repo(&test_repo.repo).unwrap();
+    assert!(graph.branch_sections.is_empty());
+
+    // Weave them through the sections directly: the merges of file.txt
+    // would conflict, which is the point.
+    let graph = {
+        let mut graph = graph;
+        for name in ["feature-a", "feature-b", "feature-c"] {
+            let oid = test_repo.get_branch_target(name);
+            graph
+                .branch_sections
+                .push(crate::core::weave::BranchSection {
+                    reset_target: "onto".to_string(),
+                    commits: vec![crate::core::weave::CommitEntry {
+                        oid,
+                        short_hash: String::new(),
+                        message: name.to_string(),
+                        command: crate::core::weave::Command::Pick,
+                        update_refs: Vec::new(),
+                    }],
+                    label: name.to_string(),
+                    branch_names: vec![name.to_string()],
+                });
+        }
+        graph
+    };
+
+    let overlaps = super::find_overlaps(&test_repo.repo, &graph).unwrap();
+
+    assert_eq!(overlaps.len(), 3);
+    assert_eq!(
+        (overlaps[0].a.as_str(), overlaps[0].b.as_str()),
+        ("feature-a", "feature-b")
+    );
+    assert_eq!(overlaps[0].clashing_files(), 1);
+    assert_eq!(overlaps[0].files[0].clashes, vec![(2..=2, 3..=3)]);
+    assert_eq!(overlaps[1].clashing_files(), 0);
+    assert_eq!(overlaps[2].clashing_files(), 0);
+    assert_eq!(overlaps[1].files[0].path, "file.txt");
+}
+
+#[test]
+fn overlap_command_runs_on_weave() {
+    let test_repo = setup(&["c.txt"]);
+    test_repo.in_dir(super::run).unwrap();
+}
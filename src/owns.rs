@@ -0,0 +1,129 @@
+use anyhow::Result;
+use colored::Colorize;
+use git2::{Oid, Repository};
+
+use crate::core::repo;
+use crate::core::shortid::IdAllocator;
+use crate::core::weave::{IntegrationEntry, Weave};
+
+/// Commits of one part of the weave that modify a path.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Owner {
+    /// Branch names of the section, empty for loose commits on the
+    /// integration line.
+    pub branch_names: Vec<String>,
+    /// Matching commits, newest first.
+    pub commits: Vec<Oid>,
+}
+
+/// Report which woven branches and commits modify `path`.
+///
+/// `path` is relative to the current directory and may name a file or a
+/// directory. The answer tells where a follow-up fix for that path belongs.
+pub fn run(path: String) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let repo_path = repo::cwd_to_repo_path(&repo, &path)?;
+    let owners = find_owners(&repo, &repo_path)?;
+
+    if owners.is_empty() {
+        println!("No woven commit changes `{}`", path);
+        return Ok(());
+    }
+
+    let info = repo::gather_repo_info(&repo, false, 0)?;
+    let ids = IdAllocator::new(info.collect_entities());
+    println!(
+        "{} {}",
+        format!("`{}`", path).yellow(),
+        "is changed by:".bold()
+    );
+    for owner in &owners {
+        if owner.branch_names.is_empty() {
+            println!("  {}", "(loose commits)".dimmed());
+        } else {
+            let names: Vec<String> = owner
+                .branch_names
+                .iter()
+                .map(|name| {
+                    format!(
+                        "{} {}",
+                        ids.get_branch(name).blue().underline(),
+                        format!("[{}]", name).green().bold()
+                    )
+                })
+                .collect();
+            println!("  {}", names.join(" "));
+        }
+        for oid in &owner.commits {
+            let commit = repo.find_commit(*oid)?;
+            println!(
+                "    {} {}",
+                ids.get_commit(*oid).blue().underline(),
+                repo::commit_subject(&commit)
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Find the commits of the weave that modify `path` (repo-relative), grouped
+/// by branch section. A directory path matches every file below it. Loose
+/// commits come first, then branch sections in weave order.
+pub fn find_owners(repo: &Repository, path: &str) -> Result<Vec<Owner>> {
+    let path = path.trim_start_matches("./").trim_end_matches('/');
+    let graph = Weave::from_repo(repo)?;
+    let mut owners = Vec::new();
+
+    let loose: Vec<Oid> = graph
+        .integration_line
+        .iter()
+        .filter_map(|entry| match entry {
+            IntegrationEntry::Pick(commit) => Some(commit.oid),
+            IntegrationEntry::Merge { .. } => None,
+        })
+        .collect();
+    let commits = matching_commits(repo, &loose, path)?;
+    if !commits.is_empty() {
+        owners.push(Owner {
+            branch_names: Vec::new(),
+            commits,
+        });
+    }
+
+    for section in &graph.branch_sections {
+        let oids: Vec<Oid> = section.commits.iter().map(|c| c.oid).collect();
+        let commits = matching_commits(repo, &oids, path)?;
+        if !commits.is_empty() {
+            owners.push(Owner {
+                branch_names: section.branch_names.clone(),
+                commits,
+            });
+        }
+    }
+    Ok(owners)
+}
+
+/// The commits among `oids` (oldest first) that modify `path`, newest first.
+fn matching_commits(repo: &Repository, oids: &[Oid], path: &str) -> Result<Vec<Oid>> {
+    let mut matching = Vec::new();
+    for &oid in oids.iter().rev() {
+        let files = repo::commit_file_paths(repo, oid)?;
+        if files.iter().any(|file| path_matches(file, path)) {
+            matching.push(oid);
+        }
+    }
+    Ok(matching)
+}
+
+/// Whether `file` is `path` or lies below the directory `path`.
+fn path_matches(file: &str, path: &str) -> bool {
+    path.is_empty()
+        || file == path
+        || file
+            .strip_prefix(path)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+#[path = "owns_test.rs"]
+mod tests;
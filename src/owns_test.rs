@@ -0,0 +1,89 @@
+use crate::core::test_helpers::TestRepo;
+
+/// Commit `message` to `path`, creating its parent directory first.
+fn commit(test_repo: &TestRepo, message: &str, path: &str) -> git2::Oid {
+    if let Some(parent) = std::path::Path::new(path).parent() {
+        std::fs::create_dir_all(test_repo.workdir().join(parent)).unwrap();
+    }
+    test_repo.commit(message, path)
+}
+
+/// Woven `feature-a` (A1 on `src/a.rs`, A2 on `docs/a.md`), woven
+/// `feature-b` (B1 on `src/b.rs`), and a loose commit on `src/c.rs`.
+fn setup_weave() -> (TestRepo, [git2::Oid; 4]) {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    let a1 = commit(&test_repo, "A1", "src/a.rs");
+    let a2 = commit(&test_repo, "A2", "docs/a.md");
+
+    test_repo.create_branch_at("feature-b", &base_oid.to_string());
+    test_repo.switch_branch("feature-b");
+    let b1 = commit(&test_repo, "B1", "src/b.rs");
+
+    test_repo.switch_branch("integration");
+    let loose = commit(&test_repo, "Loose fix", "src/c.rs");
+    test_repo.merge_no_ff("feature-a");
+    test_repo.merge_no_ff("feature-b");
+    (test_repo, [a1, a2, b1, loose])
+}
+
+#[test]
+fn find_owners_of_file() {
+    let (test_repo, [a1, _, _, _]) = setup_weave();
+
+    let owners = super::find_owners(&test_repo.repo, "src/a.rs").unwrap();
+
+    assert_eq!(
+        owners,
+        vec![super::Owner {
+            branch_names: vec!["feature-a".to_string()],
+            commits: vec![a1],
+        }]
+    );
+}
+
+#[test]
+fn find_owners_of_directory() {
+    let (test_repo, [a1, _, b1, loose]) = setup_weave();
+
+    let owners = super::find_owners(&test_repo.repo, "src/").unwrap();
+
+    // Loose commits come first, then one entry per branch section
+    assert_eq!(owners.len(), 3);
+    assert!(owners[0].branch_names.is_empty());
+    assert_eq!(owners[0].commits, vec![loose]);
+    let commits: Vec<git2::Oid> = owners[1..].iter().flat_map(|o| o.commits.clone()).collect();
+    assert!(commits.contains(&a1) && commits.contains(&b1));
+}
+
+#[test]
+fn find_owners_lists_branch_commits_newest_first() {
+    let (test_repo, [a1, a2, _, _]) = setup_weave();
+
+    let owners = super::find_owners(&test_repo.repo, "").unwrap();
+
+    let feature_a = owners
+        .iter()
+        .find(|o| o.branch_names == ["feature-a"])
+        .unwrap();
+    assert_eq!(feature_a.commits, vec![a2, a1]);
+}
+
+#[test]
+fn find_owners_no_match() {
+    let (test_repo, _) = setup_weave();
+
+    assert!(
+        super::find_owners(&test_repo.repo, "src/a")
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        super::find_owners(&test_repo.repo, "missing.txt")
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -0,0 +1,156 @@
+use anyhow::{Context, Result, bail};
+use git2::{Oid, Repository};
+use std::path::Path;
+
+use crate::branch::rebase;
+use crate::core::clock;
+use crate::core::msg;
+use crate::core::repo::{self, TargetKind};
+use crate::core::weave::{self, Weave};
+use crate::git;
+use crate::{check, explain, push, update};
+
+/// Prepare a woven branch for its final submission: rebase it onto the
+/// latest upstream, run the `loom.promoteChecks` commands on the result,
+/// optionally squash it, then push it.
+///
+/// The branch is rebased in memory and checked in a temporary worktree, so a
+/// conflict or a failing check changes nothing. Only then does the branch
+/// leave the weave (as with `rebase-branch`) and its ref move to the new
+/// commits, a fast-forward of the upstream tip.
+pub fn run(branch: String, squash: bool, no_push: bool) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "promote a branch")?.to_path_buf();
+    let (branch_name, tracked_name, upstream_name) = update::tracking(&repo)?;
+    let name = repo::resolve_arg(&repo, &branch, &[TargetKind::Branch])?.expect_branch()?;
+
+    update::fetch(&repo, &workdir, &branch_name, &tracked_name, &upstream_name)?;
+    // Re-open repo after fetch (remote refs changed)
+    let repo = Repository::discover(&workdir)?;
+    let upstream_oid = repo
+        .revparse_single(&upstream_name)
+        .with_context(|| format!("Could not resolve `{}`", upstream_name))?
+        .peel_to_commit()?
+        .id();
+
+    let info = repo::gather_repo_info(&repo, false, 1)?;
+    let mut graph = Weave::from_repo_with_info(&repo, &info)?;
+    if let Some(below) = stacked_on(&graph, &name) {
+        bail!(
+            "Branch `{}` is stacked on `{}`\nPromote `{}` first",
+            name,
+            below,
+            below
+        );
+    }
+    let commits = rebase::section_commits(&graph, &name)?;
+
+    let mut tip = rebase::replay(&repo, &commits, upstream_oid).with_context(|| {
+        format!(
+            "Could not rebase `{}` onto `{}` — nothing was changed",
+            name, upstream_name
+        )
+    })?;
+    let squashed = squash && commits.len() > 1;
+    if squashed {
+        tip = squash_commits(&repo, &commits, tip, upstream_oid)?;
+    }
+
+    run_checks(&workdir, tip, &repo::promote_checks(&repo))
+        .with_context(|| format!("`{}` was not promoted — nothing was changed", name))?;
+
+    graph.drop_branch(&name);
+    weave::run_rebase_or_abort(
+        &workdir,
+        Some(&graph.base_oid.to_string()),
+        &graph.checked_todo()?,
+    )?;
+    repo.reference(
+        &format!("refs/heads/{}", name),
+        tip,
+        true,
+        &format!("loom: promote onto {}", upstream_name),
+    )?;
+
+    let count = if squashed {
+        format!("{} commits squashed into 1", commits.len())
+    } else if commits.len() == 1 {
+        "1 commit".to_string()
+    } else {
+        format!("{} commits", commits.len())
+    };
+    msg::success(&format!(
+        "Promoted `{}` onto `{}` ({}, now `{}`)",
+        name,
+        upstream_name,
+        count,
+        git::short_hash(&tip.to_string())
+    ));
+
+    if no_push {
+        return Ok(());
+    }
+    let info = repo::gather_repo_info(&repo, false, 1)?;
+    push::push_branch(&repo, &workdir, &info, &name, upstream_oid, false)
+}
+
+/// The branch `name` is stacked on, when its own commits don't start from
+/// the weave's base: promoted alone, it would miss that branch's changes.
+fn stacked_on(graph: &Weave, name: &str) -> Option<String> {
+    let section = graph
+        .branch_sections
+        .iter()
+        .find(|s| s.branch_names.iter().any(|b| b == name))?;
+    if let Some(below) = section
+        .commits
+        .iter()
+        .rev()
+        .find(|c| !c.update_refs.is_empty())
+    {
+        return below.update_refs.first().cloned();
+    }
+    graph
+        .branch_sections
+        .iter()
+        .find(|s| s.label == section.reset_target)
+        .and_then(|s| s.branch_names.first().cloned())
+}
+
+/// Squash the replayed `commits`, whose tip is `tip`, into one commit on
+/// `onto`: the first commit's author, and the messages of all the commits,
+/// oldest first.
+fn squash_commits(repo: &Repository, commits: &[Oid], tip: Oid, onto: Oid) -> Result<Oid> {
+    let mut messages = Vec::new();
+    for &oid in commits {
+        let commit = repo.find_commit(oid)?;
+        messages.push(commit.message_raw().unwrap_or("").trim_end().to_string());
+    }
+    let first = repo.find_commit(commits[0])?;
+    let tree = repo.find_commit(tip)?.tree()?;
+    let parent = repo.find_commit(onto)?;
+    Ok(repo.commit(
+        None,
+        &first.author(),
+        &clock::committer(repo)?,
+        &format!("{}\n", messages.join("\n\n")),
+        &tree,
+        &[&parent],
+    )?)
+}
+
+/// Run each `loom.promoteChecks` command in a temporary worktree checked out
+/// at `tip`, stopping at the first that fails.
+fn run_checks(workdir: &Path, tip: Oid, checks: &[String]) -> Result<()> {
+    if checks.is_empty() || explain::in_sandbox() {
+        return Ok(());
+    }
+    check::in_worktree(workdir, tip, |dir| {
+        checks
+            .iter()
+            .try_for_each(|check| check::run_check(dir, check))
+    })?
+}
+
+#[cfg(test)]
+#[path = "promote_test.rs"]
+mod tests;
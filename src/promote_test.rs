@@ -0,0 +1,103 @@
+use crate::core::test_helpers::TestRepo;
+
+/// Woven `feature-a` with two commits, and a new commit upstream.
+fn setup_woven_branch() -> (TestRepo, git2::Oid) {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.head_oid();
+    test_repo.create_branch_at_commit("feature-a", base_oid);
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("F1", "f1.txt");
+    test_repo.commit("F2", "f2.txt");
+    test_repo.switch_branch("integration");
+    test_repo.commit("Int", "int.txt");
+    test_repo.merge_no_ff("feature-a");
+    let upstream_oid = test_repo.add_remote_commits(&["Upstream"]);
+    (test_repo, upstream_oid)
+}
+
+#[test]
+fn promote_rebases_onto_upstream_and_unweaves() {
+    let (test_repo, upstream_oid) = setup_woven_branch();
+    let integration_before = test_repo.head_oid();
+
+    let result = test_repo.in_dir(|| super::run("feature-a".to_string(), false, true));
+    assert!(result.is_ok(), "promote failed: {:?}", result.err());
+
+    let tip = test_repo.find_commit(test_repo.get_branch_target("feature-a"));
+    assert_eq!(tip.summary().unwrap(), "F2");
+    assert_eq!(tip.parent(0).unwrap().summary().unwrap(), "F1");
+    assert_eq!(tip.parent(0).unwrap().parent_id(0).unwrap(), upstream_oid);
+
+    let head = test_repo.head_commit();
+    assert_ne!(head.id(), integration_before);
+    assert_eq!(
+        head.summary().unwrap(),
+        "Int",
+        "feature-a should be unwoven"
+    );
+}
+
+#[test]
+fn promote_squash_makes_one_commit() {
+    let (test_repo, upstream_oid) = setup_woven_branch();
+
+    let result = test_repo.in_dir(|| super::run("feature-a".to_string(), true, true));
+    assert!(result.is_ok(), "promote failed: {:?}", result.err());
+
+    let tip = test_repo.find_commit(test_repo.get_branch_target("feature-a"));
+    assert_eq!(tip.parent_id(0).unwrap(), upstream_oid);
+    assert_eq!(tip.message().unwrap(), "F1\n\nF2\n");
+    assert_eq!(
+        test_repo.commit_file_paths(tip.id()),
+        vec!["f1.txt".to_string(), "f2.txt".to_string()]
+    );
+}
+
+#[test]
+fn promote_runs_checks_on_promoted_commits() {
+    let (test_repo, _) = setup_woven_branch();
+    test_repo.set_config("loom.promoteChecks", "test -f f1.txt && test -f f2.txt");
+
+    let result = test_repo.in_dir(|| super::run("feature-a".to_string(), false, true));
+    assert!(result.is_ok(), "promote failed: {:?}", result.err());
+}
+
+#[test]
+fn promote_failing_check_changes_nothing() {
+    let (test_repo, _) = setup_woven_branch();
+    test_repo.set_config("loom.promoteChecks", "false");
+    let head_before = test_repo.head_oid();
+    let branch_before = test_repo.get_branch_target("feature-a");
+
+    let result = test_repo.in_dir(|| super::run("feature-a".to_string(), false, true));
+
+    let err = result.unwrap_err();
+    assert!(err.to_string().contains("nothing was changed"), "{:#}", err);
+    assert!(format!("{:#}", err).contains("Check `false` failed"));
+    assert_eq!(test_repo.head_oid(), head_before);
+    assert_eq!(test_repo.get_branch_target("feature-a"), branch_before);
+    assert_eq!(
+        test_repo.repo.worktrees().unwrap().len(),
+        0,
+        "the check worktree should be removed"
+    );
+}
+
+#[test]
+fn promote_stacked_branch_fails() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.head_oid();
+    test_repo.create_branch_at_commit("feature-a", base_oid);
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a1.txt");
+    test_repo.create_branch("feature-b");
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1", "b1.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-b");
+
+    let result = test_repo.in_dir(|| super::run("feature-b".to_string(), false, true));
+
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("is stacked on `feature-a`"), "{}", err);
+}
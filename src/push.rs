@@ -6,8 +6,11 @@ use std::time::Instant;
 use anyhow::{Context, Result, bail};
 use git2::{BranchType, Repository, Sort};
 
+use crate::core::forge::{Forge, ForgeKind};
 use crate::core::msg;
 use crate::core::repo;
+use crate::core::weave::Weave;
+use crate::explain;
 use crate::git;
 use crate::trace as loom_trace;
 
@@ -17,13 +20,16 @@ enum RemoteType {
     Plain,
     GitHub,
     GitLab,
+    Bitbucket,
+    Gitea,
     AzureDevOps,
     Gerrit { target_branch: String },
 }
 
 /// Push a feature branch to remote.
 ///
-/// Detects the remote type (plain, GitHub, Gerrit) and dispatches to the
+/// Detects the remote type (plain, GitHub, GitLab, Bitbucket, Gitea, Azure
+/// DevOps, Gerrit) and dispatches to the
 /// appropriate push strategy. Accepts an optional branch argument (name or
 /// shortID); if omitted, shows an interactive picker.
 ///
@@ -33,6 +39,13 @@ pub fn run(branch: Option<String>, no_pr: bool) -> Result<()> {
     let repo = repo::open_repo()?;
     let workdir = repo::require_workdir(&repo, "push")?.to_path_buf();
     let info = repo::gather_repo_info(&repo, false, 1)?;
+    if info.upstream.local_only {
+        bail!(
+            "Branch `{}` has no upstream tracking branch\n\
+             Run `loom init` to set up an integration branch",
+            info.branch_name
+        );
+    }
 
     if info.branches.is_empty() {
         bail!("No woven branches to push\nCreate a branch with `git loom branch` first");
@@ -43,43 +56,197 @@ pub fn run(branch: Option<String>, no_pr: bool) -> Result<()> {
         None => pick_branch(&info)?,
     };
 
-    let remote_type = detect_remote_type(&repo, &workdir, &info.upstream.label)?;
-    let remote_name = resolve_push_remote(&repo, &workdir, &info.upstream.label, &remote_type);
+    push_branch(
+        &repo,
+        &workdir,
+        &info,
+        &branch_name,
+        info.upstream.merge_base_oid,
+        no_pr,
+    )
+}
+
+/// Push `branch_name`, whose own commits start at `base_oid`, with the
+/// strategy of the upstream's remote type.
+pub(crate) fn push_branch(
+    repo: &Repository,
+    workdir: &Path,
+    info: &repo::RepoInfo,
+    branch_name: &str,
+    base_oid: git2::Oid,
+    no_pr: bool,
+) -> Result<()> {
+    let upstream_label = info.upstream.label.as_str();
+    let remote_type = detect_remote_type(repo, upstream_label)?;
+    let remote_name = branch_remote(repo, info, branch_name, &remote_type)?;
 
-    let target_branch = extract_target_branch(&info.upstream.label);
+    let target_branch = extract_target_branch(upstream_label);
 
     if no_pr {
-        return match remote_type {
-            RemoteType::Gerrit { .. } => push_gerrit_no_pr(&workdir, &remote_name, &branch_name),
-            _ => push_plain(&workdir, &remote_name, &branch_name),
+        match remote_type {
+            RemoteType::Gerrit { .. } => push_gerrit_no_pr(workdir, &remote_name, branch_name)?,
+            _ => push_plain(workdir, &remote_name, branch_name)?,
         };
+        share_weave(repo, workdir);
+        return Ok(());
     }
 
-    let base_oid = info.upstream.merge_base_oid;
-
     match remote_type {
-        RemoteType::Plain => push_plain(&workdir, &remote_name, &branch_name),
+        RemoteType::Plain => push_plain(workdir, &remote_name, branch_name),
         RemoteType::GitHub => push_github(
-            &repo,
-            &workdir,
+            repo,
+            workdir,
             &remote_name,
-            &branch_name,
+            branch_name,
             &target_branch,
             base_oid,
-            &info.upstream.label,
+            upstream_label,
         ),
-        RemoteType::GitLab => push_gitlab(&workdir, &remote_name, &branch_name, &target_branch),
+        RemoteType::GitLab => push_gitlab(workdir, &remote_name, branch_name, &target_branch),
+        RemoteType::Bitbucket | RemoteType::Gitea => {
+            push_web_pr(repo, workdir, &remote_name, branch_name, &target_branch)
+        }
         RemoteType::AzureDevOps => push_azure(
-            &repo,
-            &workdir,
+            repo,
+            workdir,
             &remote_name,
-            &branch_name,
+            branch_name,
             &target_branch,
             base_oid,
         ),
         RemoteType::Gerrit { target_branch } => {
-            push_gerrit(&workdir, &remote_name, &branch_name, &target_branch)
+            push_gerrit(workdir, &remote_name, branch_name, &target_branch)
         }
+    }?;
+    share_weave(repo, workdir);
+    Ok(())
+}
+
+/// Push every woven branch, in weave order, to its remote with
+/// `--force-with-lease`, without creating pull requests, and report the tips
+/// that moved.
+///
+/// Branches matching `loom.hideBranchPattern` (`local-` by default) stay
+/// local, and branches whose remote-tracking branch is already at their tip
+/// are left alone. A rejected push doesn't stop the others.
+pub fn run_all() -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "push")?.to_path_buf();
+    let info = repo::gather_repo_info(&repo, false, 1)?;
+    if info.upstream.local_only {
+        bail!(
+            "Branch `{}` has no upstream tracking branch\n\
+             Run `loom init` to set up an integration branch",
+            info.branch_name
+        );
+    }
+    let remote_type = detect_remote_type(&repo, &info.upstream.label)?;
+    if matches!(remote_type, RemoteType::Gerrit { .. }) {
+        bail!(
+            "`--all` pushes branches as they are, which Gerrit reviews don't use\n\
+             Push each branch for review with `loom push <branch>`"
+        );
+    }
+
+    let pattern =
+        repo::hide_branch_pattern(&repo).unwrap_or_else(|| repo::DEFAULT_HIDE_PATTERN.to_string());
+    let graph = Weave::from_repo(&repo)?;
+    let mut branches: Vec<&String> = Vec::new();
+    for name in graph.branch_sections.iter().flat_map(|s| &s.branch_names) {
+        if !branches.contains(&name) && (pattern.is_empty() || !name.starts_with(&pattern)) {
+            branches.push(name);
+        }
+    }
+    if branches.is_empty() {
+        bail!("No woven branches to push\nCreate a branch with `git loom branch` first");
+    }
+
+    let mut pushed = 0;
+    let mut failed = Vec::new();
+    for name in branches {
+        let remote = branch_remote(&repo, &info, name, &remote_type)?;
+        let tip = repo.refname_to_id(&format!("refs/heads/{}", name))?;
+        let before = repo
+            .refname_to_id(&format!("refs/remotes/{}/{}", remote, name))
+            .ok();
+        if before == Some(tip) {
+            msg::notice(&format!("`{}` is up to date on `{}`", name, remote));
+            continue;
+        }
+        let args = [
+            "push",
+            "--force-with-lease",
+            "--force-if-includes",
+            "-u",
+            remote.as_str(),
+            name.as_str(),
+        ];
+        match run_push_capture(&workdir, &args) {
+            Ok(_) => {
+                let new = git::short_hash(&tip.to_string()).to_string();
+                let moved = match before {
+                    Some(before) => format!("{} → {}", git::short_hash(&before.to_string()), new),
+                    None => format!("new, {}", new),
+                };
+                msg::success(&format!("Pushed `{}` to `{}` ({})", name, remote, moved));
+                pushed += 1;
+            }
+            Err(_) => {
+                msg::error(&format!("Could not push `{}` to `{}`", name, remote));
+                failed.push(name.clone());
+            }
+        }
+    }
+    if pushed > 0 {
+        share_weave(&repo, &workdir);
+    }
+    if !failed.is_empty() {
+        bail!(
+            "{} {} not pushed: `{}`\nRun `loom update` if the remote moved, then push again",
+            failed.len(),
+            if failed.len() == 1 {
+                "branch was"
+            } else {
+                "branches were"
+            },
+            failed.join("`, `")
+        );
+    }
+    if pushed == 0 {
+        msg::success("Every woven branch is up to date on its remote");
+    }
+    Ok(())
+}
+
+/// The remote `branch_name` is pushed to: its `branch.<name>.loomPushRemote`,
+/// or else the one [`resolve_push_remote`] picks.
+fn branch_remote(
+    repo: &Repository,
+    info: &repo::RepoInfo,
+    branch_name: &str,
+    remote_type: &RemoteType,
+) -> Result<String> {
+    Ok(match repo::branch_push_remote(repo, branch_name) {
+        Some(remote) if repo.find_remote(&remote).is_ok() => remote,
+        Some(remote) => bail!(
+            "`branch.{}.loomPushRemote` is set to `{}`, which is not a remote",
+            branch_name,
+            remote
+        ),
+        None => resolve_push_remote(repo, &info.branch_name, &info.upstream.label, remote_type),
+    })
+}
+
+/// With `loom.shareMeta`, share the weave structure on the upstream remote
+/// after a push. A failure only warns: the branch itself was pushed.
+fn share_weave(repo: &Repository, workdir: &Path) {
+    if !repo::share_meta(repo) {
+        return;
+    }
+    let shared = crate::meta::resolve_remote(repo, None)
+        .and_then(|remote| crate::meta::publish(repo, workdir, &remote));
+    if let Err(e) = shared {
+        msg::warn(&format!("Could not share the weave structure: {}", e));
     }
 }
 
@@ -99,15 +266,15 @@ fn pick_branch(info: &repo::RepoInfo) -> Result<String> {
 
 /// Detect the remote type from config, URL heuristics, or hook inspection.
 ///
-/// Priority: git config `loom.remote-type` → URL contains `github.com` →
+/// Priority: git config `loom.remote-type` → URL of a known forge (GitHub,
+/// GitLab, Bitbucket Cloud, Gitea/Forgejo, Azure DevOps) →
 /// `.git/hooks/commit-msg` contains "gerrit" → Plain fallback.
-fn detect_remote_type(
-    repo: &Repository,
-    workdir: &Path,
-    upstream_label: &str,
-) -> Result<RemoteType> {
-    if let Ok(config_value) = git::run_git_stdout(workdir, &["config", "--get", "loom.remote-type"])
-    {
+fn detect_remote_type(repo: &Repository, upstream_label: &str) -> Result<RemoteType> {
+    // `loom explain` pushes to a local stand-in for the remote
+    if explain::in_sandbox() {
+        return Ok(RemoteType::Plain);
+    }
+    if let Some(config_value) = repo::config_string(repo, "loom.remote-type") {
         let value = config_value.trim().to_lowercase();
         if value == "github" {
             return Ok(RemoteType::GitHub);
@@ -115,6 +282,12 @@ fn detect_remote_type(
         if value == "gitlab" {
             return Ok(RemoteType::GitLab);
         }
+        if value == "bitbucket" {
+            return Ok(RemoteType::Bitbucket);
+        }
+        if value == "gitea" || value == "forgejo" {
+            return Ok(RemoteType::Gitea);
+        }
         if value == "azure" {
             return Ok(RemoteType::AzureDevOps);
         }
@@ -124,7 +297,7 @@ fn detect_remote_type(
         }
         msg::warn(&format!(
             "Unknown loom.remote-type '{}' — falling back to auto-detection.\n\
-             Valid values: github, gitlab, azure, gerrit",
+             Valid values: github, gitlab, bitbucket, gitea, azure, gerrit",
             config_value.trim()
         ));
     }
@@ -142,6 +315,11 @@ fn detect_remote_type(
         if url.contains("dev.azure.com") {
             return Ok(RemoteType::AzureDevOps);
         }
+        match Forge::from_url(url, None).map(|forge| forge.kind) {
+            Some(ForgeKind::Bitbucket) => return Ok(RemoteType::Bitbucket),
+            Some(ForgeKind::Gitea) => return Ok(RemoteType::Gitea),
+            _ => {}
+        }
     }
 
     // Use repo.commondir() so this works in worktrees (where hooks are shared)
@@ -217,27 +395,37 @@ fn extract_target_branch(upstream_label: &str) -> String {
 /// Determine the push remote for the given upstream label and remote type.
 ///
 /// Priority:
-/// 1. `git config loom.push-remote` — explicit override
-/// 2. GitHub fork convention — if integration remote is `upstream` and `origin` exists, use `origin`
-/// 3. Integration branch's remote — fallback
+/// 1. `git config loom.pushRemote` (or `loom.push-remote`) — explicit override
+/// 2. With `loom.baseRemote` set, the remote the integration branch tracks —
+///    the base comes from `loom.baseRemote`, pushes go where the branch lives
+/// 3. GitHub fork convention — if integration remote is `upstream` and `origin` exists, use `origin`
+/// 4. Integration branch's remote — fallback
 ///
 /// For non-standard fork setups (e.g., integration tracks `origin`, fork is `personal`),
-/// set `git config loom.push-remote personal`.
+/// set `git config loom.pushRemote personal`.
 fn resolve_push_remote(
     repo: &Repository,
-    workdir: &Path,
+    integration_branch: &str,
     upstream_label: &str,
     remote_type: &RemoteType,
 ) -> String {
-    if let Ok(push_remote) = git::run_git_stdout(workdir, &["config", "--get", "loom.push-remote"])
+    if let Some(remote) = repo::push_remote(repo)
+        && repo.find_remote(remote.trim()).is_ok()
     {
-        let remote = push_remote.trim();
-        if !remote.is_empty() && repo.find_remote(remote).is_ok() {
-            return remote.to_string();
-        }
+        return remote.trim().to_string();
     }
 
     let remote_name = extract_remote_name(upstream_label);
+    if repo::base_remote(repo).is_some()
+        && let Ok(tracked) =
+            repo.branch_upstream_remote(&format!("refs/heads/{}", integration_branch))
+        && let Some(tracked) = tracked.as_str()
+        && tracked != remote_name
+        && repo.find_remote(tracked).is_ok()
+    {
+        return tracked.to_string();
+    }
+
     if *remote_type == RemoteType::GitHub
         && remote_name == "upstream"
         && repo.find_remote("origin").is_ok()
@@ -573,6 +761,48 @@ fn push_gitlab(workdir: &Path, remote: &str, branch: &str, target_branch: &str)
     Ok(())
 }
 
+/// Push to a forge whose pull requests are created in the browser (Bitbucket
+/// Cloud, Gitea, Forgejo): push the branch, then open the forge's new pull
+/// request page for it against the target branch.
+///
+/// If a pull request is already open for the branch, found through the
+/// forge's API with the git credential helpers' credentials, its URL is
+/// printed instead. The page is opened on the repository the branch was
+/// pushed to. If the browser cannot be started, the URL is printed instead.
+/// Pushing the target branch itself falls back to a plain push.
+fn push_web_pr(
+    repo: &Repository,
+    workdir: &Path,
+    remote: &str,
+    branch: &str,
+    target_branch: &str,
+) -> Result<()> {
+    git_push(workdir, remote, branch)?;
+    if branch == target_branch {
+        return Ok(());
+    }
+
+    let Some(forge) = Forge::detect(repo, remote) else {
+        msg::warn(&format!(
+            "Could not find the web page of remote `{}` — open the pull request manually",
+            remote
+        ));
+        return Ok(());
+    };
+    // If a PR already exists, show its URL instead of opening the browser
+    if let Some(pr_url) = forge.find_open_pull_request(workdir, branch, target_branch) {
+        msg::success(&format!("PR updated: {}", pr_url));
+        return Ok(());
+    }
+
+    let url = forge.new_pull_request_url(branch, target_branch);
+    match crate::open::open_in_browser(&url) {
+        Ok(()) => msg::success(&format!("Opened pull request page {}", url)),
+        Err(_) => msg::warn(&format!("Open the pull request at {}", url)),
+    }
+    Ok(())
+}
+
 /// Azure DevOps coordinates parsed from a git remote URL.
 struct AzureRemote {
     /// Organization URL, e.g. `https://dev.azure.com/<org>`.
@@ -27,10 +27,9 @@ fn extract_target_branch_parses_correctly() {
 #[test]
 fn detect_remote_type_plain_by_default() {
     let test_repo = TestRepo::new_with_remote();
-    let workdir = test_repo.workdir();
     test_repo.commit("C1", "c1.txt");
 
-    let result = super::detect_remote_type(&test_repo.repo, &workdir, "origin/main");
+    let result = super::detect_remote_type(&test_repo.repo, "origin/main");
     assert!(result.is_ok());
     assert_eq!(result.unwrap(), super::RemoteType::Plain);
 }
@@ -38,13 +37,12 @@ fn detect_remote_type_plain_by_default() {
 #[test]
 fn detect_remote_type_gerrit_by_config() {
     let test_repo = TestRepo::new_with_remote();
-    let workdir = test_repo.workdir();
     test_repo.commit("C1", "c1.txt");
 
     // Set loom.remote-type to gerrit
     test_repo.set_config("loom.remote-type", "gerrit");
 
-    let result = super::detect_remote_type(&test_repo.repo, &workdir, "origin/main");
+    let result = super::detect_remote_type(&test_repo.repo, "origin/main");
     assert!(result.is_ok());
     assert_eq!(
         result.unwrap(),
@@ -57,13 +55,12 @@ fn detect_remote_type_gerrit_by_config() {
 #[test]
 fn detect_remote_type_github_by_config() {
     let test_repo = TestRepo::new_with_remote();
-    let workdir = test_repo.workdir();
     test_repo.commit("C1", "c1.txt");
 
     // Set loom.remote-type to github
     test_repo.set_config("loom.remote-type", "github");
 
-    let result = super::detect_remote_type(&test_repo.repo, &workdir, "origin/main");
+    let result = super::detect_remote_type(&test_repo.repo, "origin/main");
     assert!(result.is_ok());
     assert_eq!(result.unwrap(), super::RemoteType::GitHub);
 }
@@ -71,14 +68,13 @@ fn detect_remote_type_github_by_config() {
 #[test]
 fn detect_remote_type_config_overrides_url() {
     let test_repo = TestRepo::new_with_remote();
-    let workdir = test_repo.workdir();
     test_repo.commit("C1", "c1.txt");
 
     // Even though remote URL is a local path (not github.com),
     // explicit config should take priority
     test_repo.set_config("loom.remote-type", "gerrit");
 
-    let result = super::detect_remote_type(&test_repo.repo, &workdir, "origin/main");
+    let result = super::detect_remote_type(&test_repo.repo, "origin/main");
     assert!(result.is_ok());
     assert_eq!(
         result.unwrap(),
@@ -103,7 +99,7 @@ fn detect_remote_type_gerrit_by_hook() {
     )
     .unwrap();
 
-    let result = super::detect_remote_type(&test_repo.repo, &workdir, "origin/main");
+    let result = super::detect_remote_type(&test_repo.repo, "origin/main");
     assert!(result.is_ok());
     assert_eq!(
         result.unwrap(),
@@ -133,7 +129,7 @@ fn resolve_push_remote_github_fork_uses_origin() {
     // When tracking upstream/main on GitHub, push should go to origin
     let result = super::resolve_push_remote(
         &test_repo.repo,
-        &test_repo.workdir(),
+        "integration",
         "upstream/main",
         &super::RemoteType::GitHub,
     );
@@ -147,7 +143,7 @@ fn resolve_push_remote_github_origin_stays_origin() {
     // When tracking origin/main on GitHub, push should stay on origin
     let result = super::resolve_push_remote(
         &test_repo.repo,
-        &test_repo.workdir(),
+        "integration",
         "origin/main",
         &super::RemoteType::GitHub,
     );
@@ -167,13 +163,171 @@ fn resolve_push_remote_plain_upstream_stays_upstream() {
     // Plain remote type should NOT redirect, even if "upstream" remote exists
     let result = super::resolve_push_remote(
         &test_repo.repo,
-        &test_repo.workdir(),
+        "integration",
         "upstream/main",
         &super::RemoteType::Plain,
     );
     assert_eq!(result, "upstream");
 }
 
+#[test]
+fn resolve_push_remote_base_remote_pushes_to_tracked_remote() {
+    let test_repo = TestRepo::new_with_remote();
+
+    let remote_path = test_repo.remote_path().unwrap();
+    test_repo
+        .repo
+        .remote("upstream", remote_path.to_str().unwrap())
+        .unwrap();
+    test_repo.set_config("loom.baseRemote", "upstream");
+
+    // The base comes from upstream, but integration tracks origin
+    let result = super::resolve_push_remote(
+        &test_repo.repo,
+        "integration",
+        "upstream/main",
+        &super::RemoteType::Plain,
+    );
+    assert_eq!(result, "origin");
+}
+
+#[test]
+fn resolve_push_remote_config_overrides() {
+    let test_repo = TestRepo::new_with_remote();
+
+    let remote_path = test_repo.remote_path().unwrap();
+    test_repo
+        .repo
+        .remote("personal", remote_path.to_str().unwrap())
+        .unwrap();
+    test_repo.set_config("loom.pushRemote", "personal");
+
+    let result = super::resolve_push_remote(
+        &test_repo.repo,
+        "integration",
+        "origin/main",
+        &super::RemoteType::GitHub,
+    );
+    assert_eq!(result, "personal");
+}
+
+/// Weave `feature-a` and `feature-b` into integration, and add a `fork`
+/// remote: a bare clone of origin.
+fn setup_fork(test_repo: &TestRepo) -> std::path::PathBuf {
+    test_repo.weave_branches(&["feature-a", "feature-b"]);
+
+    let remote_path = test_repo.remote_path().unwrap();
+    let fork_path = remote_path.with_file_name("fork.git");
+    crate::git::run_git(
+        &test_repo.workdir(),
+        &[
+            "clone",
+            "-q",
+            "--bare",
+            remote_path.to_str().unwrap(),
+            fork_path.to_str().unwrap(),
+        ],
+    )
+    .unwrap();
+    test_repo
+        .repo
+        .remote("fork", fork_path.to_str().unwrap())
+        .unwrap();
+    fork_path
+}
+
+fn has_branch(bare: &std::path::Path, name: &str) -> bool {
+    git2::Repository::open_bare(bare)
+        .unwrap()
+        .find_branch(name, git2::BranchType::Local)
+        .is_ok()
+}
+
+#[test]
+fn push_uses_per_branch_remote() {
+    let test_repo = TestRepo::new_with_remote();
+    let fork_path = setup_fork(&test_repo);
+    let origin_path = test_repo.remote_path().unwrap();
+    test_repo.set_config("branch.feature-a.loomPushRemote", "fork");
+
+    for name in ["feature-a", "feature-b"] {
+        let result = test_repo.in_dir(|| super::run(Some(name.to_string()), true));
+        assert!(result.is_ok(), "push {} failed: {:?}", name, result.err());
+    }
+
+    assert!(has_branch(&fork_path, "feature-a"));
+    assert!(!has_branch(&origin_path, "feature-a"));
+    assert!(has_branch(&origin_path, "feature-b"));
+    assert!(!has_branch(&fork_path, "feature-b"));
+}
+
+#[test]
+fn push_per_branch_remote_must_exist() {
+    let test_repo = TestRepo::new_with_remote();
+    setup_fork(&test_repo);
+    test_repo.set_config("branch.feature-a.loomPushRemote", "nope");
+
+    let err = test_repo
+        .in_dir(|| super::run(Some("feature-a".to_string()), true))
+        .unwrap_err();
+
+    assert!(err.to_string().contains("which is not a remote"), "{}", err);
+}
+
+#[test]
+fn push_all_pushes_every_woven_branch() {
+    let test_repo = TestRepo::new_with_remote();
+    let fork_path = setup_fork(&test_repo);
+    let origin_path = test_repo.remote_path().unwrap();
+    test_repo.set_config("branch.feature-a.loomPushRemote", "fork");
+
+    let result = test_repo.in_dir(super::run_all);
+    assert!(result.is_ok(), "push --all failed: {:?}", result.err());
+
+    assert!(has_branch(&fork_path, "feature-a"));
+    assert!(has_branch(&origin_path, "feature-b"));
+    assert!(!has_branch(&origin_path, "feature-a"));
+}
+
+#[test]
+fn push_all_skips_hidden_branches() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.weave_branches(&["feature-a", "local-notes"]);
+    let origin_path = test_repo.remote_path().unwrap();
+
+    test_repo.in_dir(super::run_all).unwrap();
+
+    assert!(has_branch(&origin_path, "feature-a"));
+    assert!(!has_branch(&origin_path, "local-notes"));
+}
+
+#[test]
+fn push_all_leaves_up_to_date_branches_alone() {
+    let test_repo = TestRepo::new_with_remote();
+    setup_fork(&test_repo);
+    test_repo.in_dir(super::run_all).unwrap();
+    let origin_path = test_repo.remote_path().unwrap();
+    let pushed = |name: &str| {
+        git2::Repository::open_bare(&origin_path)
+            .unwrap()
+            .refname_to_id(&format!("refs/heads/{}", name))
+            .unwrap()
+    };
+    let before = pushed("feature-a");
+
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B2", "b2.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-b");
+    test_repo.in_dir(super::run_all).unwrap();
+
+    assert_eq!(pushed("feature-a"), before);
+    assert_eq!(
+        pushed("feature-b"),
+        test_repo.get_branch_target("feature-b")
+    );
+}
+
 // ── resolve_branch tests ─────────────────────────────────────────────────
 
 #[test]
@@ -247,12 +401,11 @@ fn resolve_branch_rejects_commit_target() {
 #[test]
 fn detect_remote_type_azure_by_config() {
     let test_repo = TestRepo::new_with_remote();
-    let workdir = test_repo.workdir();
     test_repo.commit("C1", "c1.txt");
 
     test_repo.set_config("loom.remote-type", "azure");
 
-    let result = super::detect_remote_type(&test_repo.repo, &workdir, "origin/main");
+    let result = super::detect_remote_type(&test_repo.repo, "origin/main");
     assert!(result.is_ok());
     assert_eq!(result.unwrap(), super::RemoteType::AzureDevOps);
 }
@@ -290,7 +443,7 @@ fn detect_remote_type_gerrit_in_worktree() {
     );
 
     // detect_remote_type should still find the Gerrit hook via repo.path()
-    let result = super::detect_remote_type(&wt_repo, &wt_path, "origin/main");
+    let result = super::detect_remote_type(&wt_repo, "origin/main");
     assert!(result.is_ok(), "detect_remote_type failed: {:?}", result);
     assert_eq!(
         result.unwrap(),
@@ -304,12 +457,11 @@ fn detect_remote_type_gerrit_in_worktree() {
 #[test]
 fn detect_remote_type_gitlab_by_config() {
     let test_repo = TestRepo::new_with_remote();
-    let workdir = test_repo.workdir();
     test_repo.commit("C1", "c1.txt");
 
     test_repo.set_config("loom.remote-type", "gitlab");
 
-    let result = super::detect_remote_type(&test_repo.repo, &workdir, "origin/main");
+    let result = super::detect_remote_type(&test_repo.repo, "origin/main");
     assert!(result.is_ok());
     assert_eq!(result.unwrap(), super::RemoteType::GitLab);
 }
@@ -317,7 +469,6 @@ fn detect_remote_type_gitlab_by_config() {
 #[test]
 fn detect_remote_type_gitlab_by_url() {
     let test_repo = TestRepo::new_with_remote();
-    let workdir = test_repo.workdir();
     test_repo.commit("C1", "c1.txt");
 
     test_repo
@@ -325,11 +476,45 @@ fn detect_remote_type_gitlab_by_url() {
         .remote_set_url("origin", "git@gitlab.com:group/repo.git")
         .unwrap();
 
-    let result = super::detect_remote_type(&test_repo.repo, &workdir, "origin/main");
+    let result = super::detect_remote_type(&test_repo.repo, "origin/main");
     assert!(result.is_ok());
     assert_eq!(result.unwrap(), super::RemoteType::GitLab);
 }
 
+#[test]
+fn detect_remote_type_bitbucket_and_gitea_by_config() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit("C1", "c1.txt");
+
+    test_repo.set_config("loom.remote-type", "bitbucket");
+    let result = super::detect_remote_type(&test_repo.repo, "origin/main");
+    assert_eq!(result.unwrap(), super::RemoteType::Bitbucket);
+
+    test_repo.set_config("loom.remote-type", "forgejo");
+    let result = super::detect_remote_type(&test_repo.repo, "origin/main");
+    assert_eq!(result.unwrap(), super::RemoteType::Gitea);
+}
+
+#[test]
+fn detect_remote_type_bitbucket_and_gitea_by_url() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit("C1", "c1.txt");
+
+    test_repo
+        .repo
+        .remote_set_url("origin", "git@bitbucket.org:team/repo.git")
+        .unwrap();
+    let result = super::detect_remote_type(&test_repo.repo, "origin/main");
+    assert_eq!(result.unwrap(), super::RemoteType::Bitbucket);
+
+    test_repo
+        .repo
+        .remote_set_url("origin", "https://codeberg.org/owner/repo.git")
+        .unwrap();
+    let result = super::detect_remote_type(&test_repo.repo, "origin/main");
+    assert_eq!(result.unwrap(), super::RemoteType::Gitea);
+}
+
 // ── append_remote_urls tests ─────────────────────────────────────────────
 
 #[test]
@@ -368,7 +553,6 @@ fn append_remote_urls_ignores_non_url_lines() {
 #[test]
 fn detect_remote_type_azure_by_url() {
     let test_repo = TestRepo::new_with_remote();
-    let workdir = test_repo.workdir();
     test_repo.commit("C1", "c1.txt");
 
     // Set remote URL to a dev.azure.com URL
@@ -380,7 +564,7 @@ fn detect_remote_type_azure_by_url() {
         )
         .unwrap();
 
-    let result = super::detect_remote_type(&test_repo.repo, &workdir, "origin/main");
+    let result = super::detect_remote_type(&test_repo.repo, "origin/main");
     assert!(result.is_ok());
     assert_eq!(result.unwrap(), super::RemoteType::AzureDevOps);
 }
@@ -0,0 +1,393 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use git2::{ObjectType, Oid, Repository, Tree};
+
+use crate::core::{clock, msg, repo};
+use crate::git::{self, RebaseOutcome};
+use crate::meta;
+
+/// Namespace holding saved conflict resolutions:
+/// `refs/loom/resolutions/<name>`.
+pub const RESOLUTION_PREFIX: &str = "refs/loom/resolutions/";
+
+/// A conflicted file, identified by its path and the three versions git
+/// tried to merge.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub path: String,
+    /// Hash of the path and the base, ours, and theirs blobs: the same
+    /// conflict recurring (the same change replayed onto the same upstream
+    /// change) has the same id.
+    pub id: String,
+}
+
+/// Record how the conflicts of the paused operation were resolved, under
+/// `name`.
+pub fn run_save(name: String) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "save a resolution")?;
+    let count = save(&repo, workdir, &name)?;
+    msg::success(&format!(
+        "Saved {} {} as `{}`\nIt is replayed whenever the same {} again",
+        count,
+        if count == 1 {
+            "resolution"
+        } else {
+            "resolutions"
+        },
+        name,
+        if count == 1 {
+            "conflict comes up"
+        } else {
+            "conflicts come up"
+        }
+    ));
+    Ok(())
+}
+
+/// Resolve the conflicted files of the paused operation with the saved
+/// resolutions (those of `name` only, when given) and stage them.
+pub fn run_apply(name: Option<String>) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "apply a resolution")?;
+    let saved = load(&repo, name.as_deref())?;
+    if saved.is_empty() {
+        match name {
+            Some(name) => bail!("No resolution named `{}`", name),
+            None => bail!("No saved resolutions\nSave one with `loom resolution save <name>`"),
+        }
+    }
+    let (applied, remaining) = apply(&repo, workdir, &saved)?;
+    if applied.is_empty() {
+        bail!("No saved resolution matches the current conflicts");
+    }
+    report(&applied);
+    if remaining > 0 {
+        msg::warn(&format!(
+            "{} conflicted {} left to resolve by hand",
+            remaining,
+            if remaining == 1 { "file" } else { "files" }
+        ));
+    } else {
+        msg::success("Every conflict is resolved\nRun `loom continue` to resume");
+    }
+    Ok(())
+}
+
+/// List the saved resolutions.
+pub fn run_list() -> Result<()> {
+    let repo = repo::open_repo()?;
+    let names = names(&repo)?;
+    if names.is_empty() {
+        println!("No saved resolutions");
+    }
+    for name in names {
+        let count = resolution_tree(&repo, &name)?.len();
+        println!(
+            "{}  {} {}",
+            name,
+            count,
+            if count == 1 { "conflict" } else { "conflicts" }
+        );
+    }
+    Ok(())
+}
+
+/// Delete the saved resolution `name`.
+pub fn run_drop(name: String) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let mut reference = repo
+        .find_reference(&format!("{}{}", RESOLUTION_PREFIX, name))
+        .with_context(|| format!("No resolution named `{}`", name))?;
+    reference.delete()?;
+    msg::success(&format!("Dropped the resolution `{}`", name));
+    Ok(())
+}
+
+/// Push every saved resolution to `remote` (by default the remote the
+/// integration branch tracks).
+pub fn run_push(remote: Option<String>) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "share resolutions")?;
+    let remote = meta::resolve_remote(&repo, remote)?;
+    let refspec = format!("{0}*:{0}*", RESOLUTION_PREFIX);
+    git::run_git(workdir, &["push", "-q", &remote, &refspec])
+        .with_context(|| format!("Could not push the resolutions to `{}`", remote))?;
+    msg::success(&format!("Shared the saved resolutions on `{}`", remote));
+    Ok(())
+}
+
+/// Fetch the resolutions shared on `remote` (by default the remote the
+/// integration branch tracks).
+pub fn run_fetch(remote: Option<String>) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "fetch resolutions")?;
+    let remote = meta::resolve_remote(&repo, remote)?;
+    let refspec = format!("{0}*:{0}*", RESOLUTION_PREFIX);
+    git::run_git(workdir, &["fetch", "-q", "--no-tags", &remote, &refspec])
+        .with_context(|| format!("Could not fetch the resolutions of `{}`", remote))?;
+    msg::success(&format!(
+        "Fetched the resolutions shared on `{}` ({} saved)",
+        remote,
+        names(&repo)?.len()
+    ));
+    Ok(())
+}
+
+/// Resolve the conflicts a rebase stopped on with the saved resolutions, and
+/// continue it for as long as they resolve every conflicted file. Returns
+/// `outcome` unchanged when there is nothing to replay, or the outcome of
+/// the last `git rebase --continue`.
+pub fn replay(workdir: &Path, mut outcome: RebaseOutcome) -> Result<RebaseOutcome> {
+    while matches!(outcome, RebaseOutcome::Conflicted) {
+        let repo = Repository::discover(workdir)?;
+        let saved = load(&repo, None)?;
+        if saved.is_empty() {
+            break;
+        }
+        let (applied, remaining) = apply(&repo, workdir, &saved)?;
+        report(&applied);
+        if applied.is_empty() || remaining > 0 {
+            break;
+        }
+        crate::trace::annotate("replayed resolutions", &format!("{:?}", applied));
+        outcome = git::continue_rebase(workdir)?;
+    }
+    Ok(outcome)
+}
+
+/// Add the resolved conflicts of the paused operation to the resolution
+/// `name`, created if needed. Returns how many were saved.
+///
+/// Files still unmerged are saved with their content in the working tree,
+/// which must be free of conflict markers. Once they are staged the index no
+/// longer knows them as conflicts: the three versions are then found again
+/// from the commit the stopped rebase was applying.
+pub fn save(repo: &Repository, workdir: &Path, name: &str) -> Result<usize> {
+    let refname = format!("{}{}", RESOLUTION_PREFIX, name);
+    if name.is_empty() || !git2::Reference::is_valid_name(&refname) {
+        bail!("`{}` is not a valid resolution name", name);
+    }
+    let mut resolved = Vec::new();
+    for conflict in unmerged(repo)? {
+        let path = workdir.join(&conflict.path);
+        let Ok(content) = std::fs::read(&path) else {
+            msg::warn(&format!(
+                "`{}` was resolved by deleting it: not saved",
+                conflict.path
+            ));
+            continue;
+        };
+        if has_conflict_markers(&content) {
+            bail!(
+                "`{}` still has conflict markers\nResolve it before saving",
+                conflict.path
+            );
+        }
+        resolved.push((conflict, repo.blob(&content)?));
+    }
+    if resolved.is_empty() {
+        resolved = staged_resolutions(repo)?;
+    }
+    if resolved.is_empty() {
+        bail!(
+            "No resolved conflict to save\n\
+             Run it while a loom command is paused on conflicts, once they are resolved"
+        );
+    }
+
+    let parent = repo
+        .find_reference(&refname)
+        .ok()
+        .and_then(|r| r.peel_to_commit().ok());
+    let base_tree = parent.as_ref().map(|c| c.tree()).transpose()?;
+    let mut builder = repo.treebuilder(base_tree.as_ref())?;
+    for (conflict, blob) in &resolved {
+        builder.insert(&conflict.id, *blob, 0o100644)?;
+    }
+    let tree = repo.find_tree(builder.write()?)?;
+    let signature = clock::committer(repo)?;
+    let message = format!(
+        "loom: save resolution {}\n\n{}\n",
+        name,
+        resolved
+            .iter()
+            .map(|(c, _)| format!("{} {}", c.id, c.path))
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let commit = repo.commit(None, &signature, &signature, &message, &tree, &parents)?;
+    repo.reference(&refname, commit, true, "loom: save resolution")?;
+    Ok(resolved.len())
+}
+
+/// The saved resolutions (of `name` only, when given): conflict id →
+/// (resolution name, resolved blob).
+pub fn load(repo: &Repository, name: Option<&str>) -> Result<HashMap<String, (String, Oid)>> {
+    let names = match name {
+        Some(name) => vec![name.to_string()],
+        None => names(repo)?,
+    };
+    let mut saved = HashMap::new();
+    for name in names {
+        let Ok(tree) = resolution_tree(repo, &name) else {
+            continue;
+        };
+        for entry in tree.iter() {
+            if let Some(id) = entry.name() {
+                saved.insert(id.to_string(), (name.clone(), entry.id()));
+            }
+        }
+    }
+    Ok(saved)
+}
+
+/// Write the saved resolution of each unmerged file that has one and stage
+/// it. Returns the files resolved, with the name of their resolution, and
+/// how many conflicted files are left.
+pub fn apply(
+    repo: &Repository,
+    workdir: &Path,
+    saved: &HashMap<String, (String, Oid)>,
+) -> Result<(Vec<(String, String)>, usize)> {
+    let mut applied = Vec::new();
+    let mut remaining = 0;
+    for conflict in unmerged(repo)? {
+        let Some((name, blob)) = saved.get(&conflict.id) else {
+            remaining += 1;
+            continue;
+        };
+        let content = repo.find_blob(*blob)?;
+        std::fs::write(workdir.join(&conflict.path), content.content())
+            .with_context(|| format!("Failed to write `{}`", conflict.path))?;
+        git::run_git(workdir, &["add", "--", &conflict.path])?;
+        applied.push((conflict.path, name.clone()));
+    }
+    Ok((applied, remaining))
+}
+
+/// The id of a conflict on `path` between the `base`, `ours`, and `theirs`
+/// blobs (`None` where the file is missing).
+pub fn conflict_id(
+    path: &str,
+    base: Option<Oid>,
+    ours: Option<Oid>,
+    theirs: Option<Oid>,
+) -> Result<String> {
+    let side = |oid: Option<Oid>| oid.map(|o| o.to_string()).unwrap_or_default();
+    let key = format!("{}\0{}\0{}\0{}", path, side(base), side(ours), side(theirs));
+    Ok(Oid::hash_object(ObjectType::Blob, key.as_bytes())?.to_string())
+}
+
+/// The files the index holds as unmerged.
+fn unmerged(repo: &Repository) -> Result<Vec<Conflict>> {
+    let mut index = repo.index()?;
+    index.read(false)?;
+    let mut conflicts = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let Some(entry) = conflict
+            .our
+            .as_ref()
+            .or(conflict.their.as_ref())
+            .or(conflict.ancestor.as_ref())
+        else {
+            continue;
+        };
+        let path = String::from_utf8_lossy(&entry.path).into_owned();
+        let id = conflict_id(
+            &path,
+            conflict.ancestor.as_ref().map(|e| e.id),
+            conflict.our.as_ref().map(|e| e.id),
+            conflict.their.as_ref().map(|e| e.id),
+        )?;
+        conflicts.push(Conflict { path, id });
+    }
+    Ok(conflicts)
+}
+
+/// The conflicts of the commit a stopped rebase was applying that are
+/// already resolved and staged: the files that the commit and HEAD both
+/// changed differently from the commit's parent, with their staged content.
+fn staged_resolutions(repo: &Repository) -> Result<Vec<(Conflict, Oid)>> {
+    let Ok(theirs) = repo
+        .revparse_single("REBASE_HEAD")
+        .and_then(|o| o.peel_to_commit())
+    else {
+        return Ok(Vec::new());
+    };
+    let base_tree = theirs.parent(0).ok().map(|p| p.tree()).transpose()?;
+    let their_tree = theirs.tree()?;
+    let our_tree = repo.head()?.peel_to_tree()?;
+    let mut index = repo.index()?;
+    index.read(false)?;
+    let diff = repo.diff_tree_to_index(Some(&our_tree), Some(&index), None)?;
+
+    let mut resolved = Vec::new();
+    for delta in diff.deltas() {
+        let Some(path) = delta.new_file().path().and_then(|p| p.to_str()) else {
+            continue;
+        };
+        if delta.status() == git2::Delta::Deleted {
+            continue;
+        }
+        let base = base_tree.as_ref().and_then(|t| blob_at(t, path));
+        let ours = blob_at(&our_tree, path);
+        let theirs = blob_at(&their_tree, path);
+        if ours != base && theirs != base && ours != theirs {
+            let id = conflict_id(path, base, ours, theirs)?;
+            let conflict = Conflict {
+                path: path.to_string(),
+                id,
+            };
+            resolved.push((conflict, delta.new_file().id()));
+        }
+    }
+    Ok(resolved)
+}
+
+fn blob_at(tree: &Tree, path: &str) -> Option<Oid> {
+    tree.get_path(Path::new(path)).ok().map(|e| e.id())
+}
+
+/// Whether `content` still has a `<<<<<<<` … `>>>>>>>` conflict block.
+fn has_conflict_markers(content: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(content);
+    text.lines().any(|l| l.starts_with("<<<<<<< "))
+        && text.lines().any(|l| l.starts_with(">>>>>>> "))
+}
+
+/// The names of the saved resolutions, sorted.
+fn names(repo: &Repository) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for reference in repo.references_glob(&format!("{}*", RESOLUTION_PREFIX))? {
+        if let Some(name) = reference?.name() {
+            names.push(name.trim_start_matches(RESOLUTION_PREFIX).to_string());
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+fn resolution_tree<'r>(repo: &'r Repository, name: &str) -> Result<Tree<'r>> {
+    Ok(repo
+        .find_reference(&format!("{}{}", RESOLUTION_PREFIX, name))
+        .with_context(|| format!("No resolution named `{}`", name))?
+        .peel_to_tree()?)
+}
+
+fn report(applied: &[(String, String)]) {
+    for (path, name) in applied {
+        msg::notice(&format!(
+            "Resolved `{}` with the saved resolution `{}`",
+            path, name
+        ));
+    }
+}
+
+#[cfg(test)]
+#[path = "resolution_test.rs"]
+mod tests;
@@ -0,0 +1,133 @@
+use std::path::Path;
+
+use crate::core::test_helpers::TestRepo;
+use crate::git::{self, RebaseOutcome};
+
+/// `feature` and the initial branch both change `a.txt` from a common base,
+/// with `feature` checked out.
+fn setup_conflict() -> TestRepo {
+    let test_repo = TestRepo::new();
+    test_repo.commit_multi(&[("a.txt", "base\n")], "Base");
+    let base = test_repo.current_branch_name();
+    test_repo.create_branch("feature");
+    test_repo.commit_multi(&[("a.txt", "main\n")], "Main change");
+    test_repo.create_branch("upstream");
+    test_repo.switch_branch("feature");
+    test_repo.commit_multi(&[("a.txt", "feature\n")], "Feature change");
+    test_repo.delete_branch(&base);
+    test_repo
+}
+
+/// Rebase `feature` onto `upstream`, stopping on the conflict.
+fn start_rebase(test_repo: &TestRepo) -> RebaseOutcome {
+    git::rebase(test_repo.repo.path(), &test_repo.workdir(), "upstream").unwrap()
+}
+
+fn rebase_in_progress(test_repo: &TestRepo) -> bool {
+    git::rebase_is_in_progress(test_repo.repo.path())
+}
+
+fn has_conflicts(test_repo: &TestRepo) -> bool {
+    let mut index = test_repo.repo.index().unwrap();
+    index.read(false).unwrap();
+    index.has_conflicts()
+}
+
+fn abort(workdir: &Path) {
+    git::rebase_abort(workdir).unwrap();
+}
+
+#[test]
+fn saved_resolution_is_replayed_on_the_same_conflict() {
+    let test_repo = setup_conflict();
+    let workdir = test_repo.workdir();
+    assert!(matches!(
+        start_rebase(&test_repo),
+        RebaseOutcome::Conflicted
+    ));
+
+    test_repo.write_file("a.txt", "merged\n");
+    assert_eq!(super::save(&test_repo.repo, &workdir, "fix").unwrap(), 1);
+    abort(&workdir);
+
+    let outcome = start_rebase(&test_repo);
+    let outcome = super::replay(&workdir, outcome).unwrap();
+    assert!(matches!(outcome, RebaseOutcome::Completed));
+    assert!(!rebase_in_progress(&test_repo));
+    assert_eq!(test_repo.read_file("a.txt"), "merged\n");
+    assert_eq!(test_repo.get_message(0), "Feature change");
+}
+
+#[test]
+fn apply_stages_the_saved_resolution_without_continuing() {
+    let test_repo = setup_conflict();
+    let workdir = test_repo.workdir();
+    start_rebase(&test_repo);
+    test_repo.write_file("a.txt", "merged\n");
+    super::save(&test_repo.repo, &workdir, "fix").unwrap();
+    abort(&workdir);
+
+    start_rebase(&test_repo);
+    let saved = super::load(&test_repo.repo, Some("fix")).unwrap();
+    let (applied, remaining) = super::apply(&test_repo.repo, &workdir, &saved).unwrap();
+
+    assert_eq!(applied, vec![("a.txt".to_string(), "fix".to_string())]);
+    assert_eq!(remaining, 0);
+    assert!(rebase_in_progress(&test_repo));
+    assert!(!has_conflicts(&test_repo));
+    assert_eq!(test_repo.read_file("a.txt"), "merged\n");
+}
+
+#[test]
+fn staged_resolution_is_saved_under_the_same_id() {
+    let test_repo = setup_conflict();
+    let workdir = test_repo.workdir();
+    start_rebase(&test_repo);
+    test_repo.write_file("a.txt", "merged\n");
+    super::save(&test_repo.repo, &workdir, "unstaged").unwrap();
+    test_repo.stage_files(&["a.txt"]);
+    assert_eq!(
+        super::save(&test_repo.repo, &workdir, "staged").unwrap(),
+        1,
+        "the conflict should be found from REBASE_HEAD once staged"
+    );
+
+    let unstaged = super::load(&test_repo.repo, Some("unstaged")).unwrap();
+    let staged = super::load(&test_repo.repo, Some("staged")).unwrap();
+    let ids = |saved: &std::collections::HashMap<String, (String, git2::Oid)>| {
+        saved
+            .iter()
+            .map(|(id, (_, blob))| (id.clone(), *blob))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(ids(&unstaged), ids(&staged));
+    abort(&workdir);
+}
+
+#[test]
+fn save_refuses_files_with_conflict_markers() {
+    let test_repo = setup_conflict();
+    let workdir = test_repo.workdir();
+    start_rebase(&test_repo);
+
+    let err = super::save(&test_repo.repo, &workdir, "fix").unwrap_err();
+    assert!(err.to_string().contains("still has conflict markers"));
+    assert!(
+        test_repo
+            .repo
+            .find_reference("refs/loom/resolutions/fix")
+            .is_err()
+    );
+    abort(&workdir);
+}
+
+#[test]
+fn replay_leaves_conflicts_without_a_saved_resolution() {
+    let test_repo = setup_conflict();
+    let workdir = test_repo.workdir();
+
+    let outcome = super::replay(&workdir, start_rebase(&test_repo)).unwrap();
+    assert!(matches!(outcome, RebaseOutcome::Conflicted));
+    assert!(has_conflicts(&test_repo));
+    abort(&workdir);
+}
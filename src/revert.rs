@@ -0,0 +1,121 @@
+use anyhow::{Result, bail};
+use git2::{Oid, Repository};
+
+use crate::core::graph;
+use crate::core::msg;
+use crate::core::repo::{self, TargetKind};
+use crate::core::weave::{self, CommitEntry, IntegrationEntry, Weave};
+use crate::git;
+
+/// Revert a commit with a new commit placed where it belongs.
+///
+/// By default the revert goes at the tip of the woven branch that owns the
+/// original commit, so the branch carries its own undo. With `loose`, or when
+/// the original is on the integration line, it lands on the integration line.
+pub fn run(target: String, loose: bool) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let oid = match repo::resolve_arg(&repo, &target, &[TargetKind::Commit])? {
+        repo::Target::Commit(hash) => Oid::from_str(&hash)?,
+        _ => bail!("Target must be a commit"),
+    };
+
+    let branch = revert(&repo, oid, loose)?;
+    let new_hash = git::rev_parse(
+        repo::require_workdir(&repo, "revert")?,
+        branch.as_deref().unwrap_or("HEAD"),
+    )?;
+    msg::success(&format!(
+        "Reverted `{}` with `{}` {}",
+        git::short_hash(&oid.to_string()),
+        git::short_hash(&new_hash),
+        match &branch {
+            Some(name) => format!("on branch `{}`", name),
+            None => "on the integration line".to_string(),
+        }
+    ));
+    Ok(())
+}
+
+/// Create the revert of `oid` and weave it in. Returns the branch it went to,
+/// or `None` for the integration line.
+pub fn revert(repo: &Repository, oid: Oid, loose: bool) -> Result<Option<String>> {
+    let workdir = repo::require_workdir(repo, "revert")?;
+    let info = repo::gather_repo_info(repo, false, 1)?;
+    let commit = repo.find_commit(oid)?;
+    if commit.parent_count() > 1 {
+        bail!(
+            "Cannot revert merge commit `{}`\nUse `loom branch unmerge` to remove a branch from the weave",
+            git::short_hash(&oid.to_string())
+        );
+    }
+
+    let mut graph = Weave::from_repo_with_info(repo, &info)?;
+    let branch = if loose {
+        None
+    } else {
+        graph::assign_commits_to_branches(&info).remove(&oid)
+    };
+    if let Some(name) = &branch
+        && !graph
+            .branch_sections
+            .iter()
+            .any(|s| s.branch_names.contains(name))
+    {
+        bail!(
+            "Branch `{}` has branches stacked on it\nUse --loose to revert on the integration line",
+            name
+        );
+    }
+
+    // Revert against the commit the revert is placed on, so it applies
+    // cleanly when the rebase replays it there.
+    let onto = match &branch {
+        Some(name) => repo.revparse_single(name)?.peel_to_commit()?,
+        None => repo.head()?.peel_to_commit()?,
+    };
+    let mut index = repo.revert_commit(&commit, &onto, 0, None)?;
+    if index.has_conflicts() {
+        bail!(
+            "Reverting `{}` conflicts with later changes{}\nRevert it manually with `git revert`",
+            git::short_hash(&oid.to_string()),
+            match &branch {
+                Some(name) => format!(" on `{}`", name),
+                None => String::new(),
+            }
+        );
+    }
+    let tree = index.write_tree_to(repo)?;
+    let subject = repo::commit_subject(&commit);
+    let message = format!("Revert \"{}\"\n\nThis reverts commit {}.\n", subject, oid);
+    let new_oid = Oid::from_str(&git::commit_tree(
+        workdir,
+        &tree.to_string(),
+        &onto.id().to_string(),
+        &message,
+        None,
+        false,
+    )?)?;
+
+    graph
+        .integration_line
+        .push(IntegrationEntry::Pick(CommitEntry {
+            oid: new_oid,
+            short_hash: git::short_hash(&new_oid.to_string()).to_string(),
+            message: format!("Revert \"{}\"", subject),
+            command: weave::Command::Pick,
+            update_refs: Vec::new(),
+        }));
+    if let Some(name) = &branch {
+        graph.move_commit(new_oid, name)?;
+    }
+    weave::run_rebase_or_abort(
+        workdir,
+        Some(&graph.base_oid.to_string()),
+        &graph.checked_todo()?,
+    )?;
+    Ok(branch)
+}
+
+#[cfg(test)]
+#[path = "revert_test.rs"]
+mod tests;
@@ -0,0 +1,98 @@
+use crate::core::repo;
+use crate::core::test_helpers::TestRepo;
+
+/// Integration branch weaving a two-commit `feature-a` and `feature-b`.
+fn setup() -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    for name in ["feature-a", "feature-b"] {
+        test_repo.create_branch_at(name, &base_oid.to_string());
+        test_repo.switch_branch(name);
+        test_repo.commit(&format!("{} 1", name), &format!("{}-1.txt", name));
+        test_repo.commit(&format!("{} 2", name), &format!("{}-2.txt", name));
+        test_repo.switch_branch("integration");
+        test_repo.merge_no_ff(name);
+    }
+    test_repo
+}
+
+fn commit_by_message(test_repo: &TestRepo, message: &str) -> git2::Oid {
+    let info = repo::gather_repo_info(&test_repo.repo, false, 1).unwrap();
+    info.commits
+        .iter()
+        .find(|c| c.message == message)
+        .unwrap_or_else(|| panic!("no commit `{}`", message))
+        .oid
+}
+
+#[test]
+fn revert_lands_on_owning_branch() {
+    let test_repo = setup();
+    let oid = commit_by_message(&test_repo, "feature-a 1");
+
+    test_repo
+        .in_dir(|| super::run(oid.to_string(), false))
+        .unwrap();
+
+    let tip = test_repo.find_commit(test_repo.get_branch_target("feature-a"));
+    assert_eq!(
+        tip.summary().unwrap(),
+        "Revert \"feature-a 1\"",
+        "revert is the new tip of feature-a"
+    );
+    assert!(
+        tip.message()
+            .unwrap()
+            .contains(&format!("This reverts commit {}.", oid))
+    );
+    assert!(!test_repo.workdir().join("feature-a-1.txt").exists());
+    assert!(test_repo.workdir().join("feature-a-2.txt").exists());
+    // Still woven, still a merge at the top
+    let head = test_repo.find_commit(test_repo.head_oid());
+    assert_eq!(head.parent_count(), 2);
+}
+
+#[test]
+fn revert_loose_lands_on_integration_line() {
+    let test_repo = setup();
+    let oid = commit_by_message(&test_repo, "feature-b 2");
+    let branch_tip = test_repo.get_branch_target("feature-b");
+
+    test_repo
+        .in_dir(|| super::run(oid.to_string(), true))
+        .unwrap();
+
+    let head = test_repo.find_commit(test_repo.head_oid());
+    assert_eq!(head.summary().unwrap(), "Revert \"feature-b 2\"");
+    assert_eq!(head.parent_count(), 1);
+    assert_eq!(test_repo.get_branch_target("feature-b"), branch_tip);
+    assert!(!test_repo.workdir().join("feature-b-2.txt").exists());
+}
+
+#[test]
+fn revert_of_integration_commit_stays_loose() {
+    let test_repo = setup();
+    let oid = test_repo.commit("Loose change", "loose.txt");
+
+    test_repo
+        .in_dir(|| super::run(oid.to_string(), false))
+        .unwrap();
+
+    let head = test_repo.find_commit(test_repo.head_oid());
+    assert_eq!(head.summary().unwrap(), "Revert \"Loose change\"");
+    assert_eq!(head.parent_id(0).unwrap(), oid);
+    assert!(!test_repo.workdir().join("loose.txt").exists());
+}
+
+#[test]
+fn revert_refuses_merge_commit() {
+    let test_repo = setup();
+    let head = test_repo.head_oid();
+
+    let err = test_repo
+        .in_dir(|| super::run(head.to_string(), false))
+        .unwrap_err();
+
+    assert!(err.to_string().contains("merge commit"), "{}", err);
+    assert_eq!(test_repo.head_oid(), head);
+}
@@ -5,8 +5,9 @@ use crate::branch;
 use crate::core::repo::{self, Target};
 
 use crate::core::msg;
+use crate::core::transaction::{self, Rollback};
 use crate::core::weave;
-use crate::git;
+use crate::{git, worktree};
 
 /// Reword a commit message or rename a branch.
 pub fn run(target: String, message: Option<String>) -> Result<()> {
@@ -56,15 +57,17 @@ pub fn reword_commit(repo: &Repository, commit_hash: &str, message: Option<Strin
     let workdir = repo::require_workdir(repo, "reword")?;
 
     let commit_oid = repo.revparse_single(commit_hash)?.peel_to_commit()?.id();
+    if message.is_none() {
+        msg::require_editor("the new commit message", "Pass it with `-m`")?;
+    }
 
     // Step 1: Start interactive rebase with edit at target
     weave::start_edit_rebase(repo, workdir, commit_oid)?;
 
-    // Step 2: Amend the commit message
-    if let Err(e) = git::commit_amend(workdir, message.as_deref()) {
-        let _ = git::rebase_abort(workdir);
-        return Err(e);
-    }
+    // Step 2: Amend the commit message (aborts the rebase on failure)
+    transaction::editor_step(workdir, &Rollback::default(), || {
+        git::commit_amend(workdir, message.as_deref())
+    })?;
 
     // Capture the new hash right after amending (before rebase --continue moves HEAD)
     let new_hash = repo.head()?.peel_to_commit()?.id().to_string();
@@ -86,6 +89,7 @@ pub fn reword_branch(repo: &Repository, old_name: &str, new_name: &str) -> Resul
     let workdir = repo::require_workdir(repo, "rename branch")?;
 
     git::branch_rename(workdir, old_name, new_name)?;
+    worktree::rename_branch(repo, old_name, new_name)?;
 
     branch::warn_if_hidden(repo, new_name);
     msg::success(&format!("Renamed branch `{}` to `{}`", old_name, new_name));
@@ -368,3 +368,44 @@ fn reword_branch_by_full_name_via_run() {
         "New branch should exist after rename"
     );
 }
+
+#[test]
+fn reword_commit_failure_aborts_rebase() {
+    let test_repo = TestRepo::new();
+    let c1_oid = test_repo.commit("First commit", "file1.txt");
+    let head_oid = test_repo.commit("Second commit", "file2.txt");
+    test_repo.reject_commit_messages();
+
+    let result = super::reword_commit(
+        &test_repo.repo,
+        &c1_oid.to_string(),
+        Some("Updated first commit".to_string()),
+    );
+
+    assert!(result.is_err());
+    assert!(!crate::git::rebase_is_in_progress(test_repo.repo.path()));
+    assert_eq!(test_repo.head_oid(), head_oid);
+    assert!(test_repo.is_on_branch());
+}
+
+#[test]
+fn reword_without_message_fails_under_no_input() {
+    let test_repo = TestRepo::new();
+    let c1_oid = test_repo.commit("First commit", "file1.txt");
+    test_repo.commit("Second commit", "file2.txt");
+    let head = test_repo.head_oid();
+
+    let output = std::process::Command::new(crate::git::loom_exe_path().unwrap())
+        .args(["--no-input", "reword", &c1_oid.to_string()])
+        .current_dir(test_repo.workdir())
+        .output()
+        .unwrap();
+
+    assert_eq!(
+        output.status.code(),
+        Some(crate::core::msg::NO_INPUT_EXIT_CODE)
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Input required"));
+    assert_eq!(test_repo.head_oid(), head, "nothing should be rewritten");
+    assert_eq!(test_repo.get_message(1), "First commit");
+}
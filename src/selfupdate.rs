@@ -0,0 +1,219 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use sha2::{Digest, Sha256};
+
+use crate::core::msg;
+
+/// GitHub API endpoint of the latest release.
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/narnaud/git-loom/releases/latest";
+
+/// Base URL of the release downloads, followed by `<tag>/<asset>`.
+const DOWNLOAD_URL: &str = "https://github.com/narnaud/git-loom/releases/download";
+
+/// Check GitHub for a newer release and install it in place of the running
+/// binary.
+///
+/// The archive is checked against the `.sha256` file published next to it
+/// before anything is replaced. With `check`, only report whether an update
+/// is available.
+pub fn run(check: bool, yes: bool) -> Result<()> {
+    let current = env!("CARGO_PKG_VERSION");
+    let tag = latest_tag()?;
+    if !is_newer(&tag, current)? {
+        msg::success(&format!("loom is up to date (`v{}`)", current));
+        return Ok(());
+    }
+    if check {
+        msg::success(&format!(
+            "`{}` is available (current: `v{}`)\nRun `loom self-update` to install it",
+            tag, current
+        ));
+        return Ok(());
+    }
+    if !yes && !msg::confirm(&format!("Update loom from v{} to {}?", current, tag))? {
+        bail!("Cancelled");
+    }
+
+    let asset = asset_name()?;
+    let exe = std::env::current_exe().context("Could not locate the running loom binary")?;
+    let dir = tempfile::tempdir()?;
+
+    let spinner = msg::spinner();
+    spinner.start(&format!("Downloading {}...", asset));
+    let binary = match download_verified(&tag, &asset, dir.path()) {
+        Ok(binary) => {
+            spinner.stop(&format!("Downloaded and verified {}", asset));
+            binary
+        }
+        Err(e) => {
+            spinner.error("Download failed");
+            return Err(e);
+        }
+    };
+
+    replace_binary(&binary, &exe)?;
+    msg::success(&format!("Updated loom from `v{}` to `{}`", current, tag));
+    Ok(())
+}
+
+/// Tag of the latest GitHub release, e.g. `v0.20.0`.
+fn latest_tag() -> Result<String> {
+    let body = curl(&[LATEST_RELEASE_URL])?;
+    let release: serde_json::Value =
+        serde_json::from_str(&body).context("Unexpected response from GitHub")?;
+    release["tag_name"]
+        .as_str()
+        .map(str::to_string)
+        .context("The latest release has no tag")
+}
+
+/// Download `asset` of release `tag` and its checksum into `dir`, verify
+/// it, and extract it. Returns the path of the extracted binary.
+fn download_verified(tag: &str, asset: &str, dir: &Path) -> Result<PathBuf> {
+    let archive = dir.join(asset);
+    let checksum = dir.join(format!("{}.sha256", asset));
+    let url = format!("{}/{}/{}", DOWNLOAD_URL, tag, asset);
+    curl(&["-o", &archive.to_string_lossy(), &url])?;
+    curl(&[
+        "-o",
+        &checksum.to_string_lossy(),
+        &format!("{}.sha256", url),
+    ])?;
+
+    let expected = std::fs::read_to_string(&checksum)?;
+    verify_checksum(&archive, &expected)?;
+
+    let output = Command::new("tar")
+        .arg("-xf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(dir)
+        .output()
+        .context("Failed to run tar")?;
+    if !output.status.success() {
+        bail!("Failed to extract {}", asset);
+    }
+    let binary = dir.join(binary_name());
+    if !binary.exists() {
+        bail!("{} does not contain {}", asset, binary_name());
+    }
+    Ok(binary)
+}
+
+/// Fail unless the SHA-256 of `path` matches the first word of `expected`
+/// (the format of the published `.sha256` files).
+pub fn verify_checksum(path: &Path, expected: &str) -> Result<()> {
+    let expected = expected
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+        .to_ascii_lowercase();
+    let actual = sha256_hex(&std::fs::read(path)?);
+    if expected != actual {
+        bail!(
+            "Checksum mismatch for {}\nThe download was not installed",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        );
+    }
+    Ok(())
+}
+
+/// Swap `new` in place of `exe`. The running binary is renamed aside first,
+/// which works even on Windows, and restored if the copy fails.
+fn replace_binary(new: &Path, exe: &Path) -> Result<()> {
+    let backup = exe.with_extension("old");
+    let _ = std::fs::remove_file(&backup);
+    std::fs::rename(exe, &backup).with_context(|| format!("Cannot replace '{}'", exe.display()))?;
+    if let Err(e) = std::fs::copy(new, exe) {
+        let _ = std::fs::rename(&backup, exe);
+        return Err(e).with_context(|| format!("Cannot write '{}'", exe.display()));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(exe, std::fs::Permissions::from_mode(0o755))?;
+    }
+    // Windows keeps the running binary locked; the next update removes it.
+    let _ = std::fs::remove_file(&backup);
+    Ok(())
+}
+
+/// Run `curl` with `args` and return its stdout. Follows redirects and
+/// fails on HTTP errors.
+fn curl(args: &[&str]) -> Result<String> {
+    let output = Command::new("curl")
+        .args(["-fsSL", "-H", "User-Agent: git-loom"])
+        .args(args)
+        .output()
+        .context("Failed to run curl\nInstall curl to use `loom self-update`")?;
+    if !output.status.success() {
+        bail!("Could not reach GitHub\nCheck your connection and try again");
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Whether release `tag` (e.g. `v0.20.0`) is newer than `current`.
+pub fn is_newer(tag: &str, current: &str) -> Result<bool> {
+    Ok(parse_version(tag)? > parse_version(current)?)
+}
+
+/// Parse `[v]X.Y.Z` into a comparable tuple. Pre-release suffixes are
+/// ignored.
+fn parse_version(version: &str) -> Result<(u64, u64, u64)> {
+    let core = version
+        .trim()
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or("");
+    let parts: Vec<u64> = core
+        .split('.')
+        .map(|p| p.parse())
+        .collect::<std::result::Result<_, _>>()
+        .with_context(|| format!("Invalid version `{}`", version))?;
+    match parts[..] {
+        [major, minor, patch] => Ok((major, minor, patch)),
+        _ => bail!("Invalid version `{}`", version),
+    }
+}
+
+/// Name of the release archive for this platform.
+pub fn asset_name() -> Result<String> {
+    asset_for(std::env::consts::OS, std::env::consts::ARCH)
+}
+
+fn asset_for(os: &str, arch: &str) -> Result<String> {
+    let (target, archive) = match (os, arch) {
+        ("linux", "x86_64") => ("x86_64-unknown-linux-gnu", "tar.gz"),
+        ("linux", "aarch64") => ("aarch64-unknown-linux-gnu", "tar.gz"),
+        ("macos", "aarch64") => ("aarch64-apple-darwin", "tar.gz"),
+        ("windows", "x86_64") => ("x86_64-pc-windows-msvc", "zip"),
+        _ => bail!(
+            "No release binary for {} {}\nUpdate with `cargo install git-loom` instead",
+            os,
+            arch
+        ),
+    };
+    Ok(format!("git-loom-{}.{}", target, archive))
+}
+
+fn binary_name() -> &'static str {
+    if cfg!(windows) {
+        "git-loom.exe"
+    } else {
+        "git-loom"
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "selfupdate_test.rs"]
+mod tests;
@@ -0,0 +1,45 @@
+#[test]
+fn is_newer_compares_release_tags() {
+    assert!(super::is_newer("v0.20.0", "0.19.0").unwrap());
+    assert!(super::is_newer("v1.0.0", "0.19.3").unwrap());
+    assert!(super::is_newer("v0.19.10", "0.19.9").unwrap());
+    assert!(!super::is_newer("v0.19.0", "0.19.0").unwrap());
+    assert!(!super::is_newer("v0.18.2", "0.19.0").unwrap());
+    assert!(!super::is_newer("v0.19.0-rc.1", "0.19.0").unwrap());
+    assert!(super::is_newer("latest", "0.19.0").is_err());
+}
+
+#[test]
+fn asset_for_known_platforms() {
+    assert_eq!(
+        super::asset_for("linux", "x86_64").unwrap(),
+        "git-loom-x86_64-unknown-linux-gnu.tar.gz"
+    );
+    assert_eq!(
+        super::asset_for("macos", "aarch64").unwrap(),
+        "git-loom-aarch64-apple-darwin.tar.gz"
+    );
+    assert_eq!(
+        super::asset_for("windows", "x86_64").unwrap(),
+        "git-loom-x86_64-pc-windows-msvc.zip"
+    );
+    let err = super::asset_for("freebsd", "x86_64").unwrap_err();
+    assert!(err.to_string().contains("cargo install"));
+    // Not in the release matrix
+    let err = super::asset_for("macos", "x86_64").unwrap_err();
+    assert!(err.to_string().contains("cargo install"));
+}
+
+#[test]
+fn verify_checksum_accepts_matching_digest() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("archive.tar.gz");
+    std::fs::write(&path, "abc").unwrap();
+
+    // SHA-256 of "abc"
+    let digest = "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD";
+    super::verify_checksum(&path, &format!("{} *archive.tar.gz\n", digest)).unwrap();
+
+    let err = super::verify_checksum(&path, &"0".repeat(64)).unwrap_err();
+    assert!(err.to_string().contains("Checksum mismatch"));
+}
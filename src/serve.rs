@@ -0,0 +1,328 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use git2::Repository;
+use serde_json::{Value, json};
+
+use crate::api::{self, API_VERSION, Query, TodoOp};
+use crate::core::repo;
+
+/// Interval between two checks of the repository for changes.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Methods answered by the server, listed by `initialize`.
+const METHODS: &[&str] = &[
+    "initialize",
+    "sections",
+    "resolve",
+    "refs",
+    "todo",
+    "fold",
+    "drop",
+    "reword",
+    "exit",
+];
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// A query or operation failed; the message is loom's error.
+const COMMAND_FAILED: i64 = -32000;
+
+/// Where the server writes its messages, one JSON object per line. Shared by
+/// the request loop and the repository watcher.
+#[derive(Clone)]
+pub struct Output(Arc<Mutex<Box<dyn Write + Send>>>);
+
+impl Output {
+    pub fn new(writer: impl Write + Send + 'static) -> Output {
+        Output(Arc::new(Mutex::new(Box::new(writer))))
+    }
+
+    fn send(&self, message: &Value) {
+        let mut writer = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        // A closed stdout means the editor went away: the read loop ends next
+        let _ = writeln!(writer, "{}", message);
+        let _ = writer.flush();
+    }
+
+    fn notify(&self, method: &str, params: Value) {
+        self.send(&json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+}
+
+/// A JSON-RPC error response.
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> RpcError {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(e: anyhow::Error) -> RpcError {
+        RpcError::new(COMMAND_FAILED, format!("{:#}", e))
+    }
+}
+
+/// Serve JSON-RPC 2.0 requests on stdin, one per line, until stdin closes or
+/// `exit` is received.
+///
+/// Queries are answered in-process, like `loom api`. Operations run the loom
+/// executable, relaying its output as `progress` notifications. A `changed`
+/// notification is pushed whenever HEAD, a branch, the index, or a paused
+/// operation changes.
+pub fn run() -> Result<()> {
+    let repo = repo::open_repo()?;
+    let git_dir = repo.path().to_path_buf();
+    drop(repo);
+    let exe = std::env::current_exe().context("Could not find the loom executable")?;
+    let out = Output::new(std::io::stdout());
+
+    let watcher = out.clone();
+    std::thread::spawn(move || watch(&git_dir, &watcher));
+
+    serve(std::io::stdin().lock(), &out, &exe)
+}
+
+/// Answer each request of `input`, running operations with `exe`.
+pub fn serve(input: impl BufRead, out: &Output, exe: &Path) -> Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: Value = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                reply(
+                    out,
+                    &Value::Null,
+                    Err(RpcError::new(PARSE_ERROR, e.to_string())),
+                );
+                continue;
+            }
+        };
+        // Requests without an id are notifications: they get no response
+        let id = request.get("id").cloned();
+        let Some(method) = request["method"].as_str() else {
+            reply(
+                out,
+                &id.unwrap_or(Value::Null),
+                Err(RpcError::new(INVALID_REQUEST, "Missing method")),
+            );
+            continue;
+        };
+        if method == "exit" {
+            break;
+        }
+        let params = request.get("params").cloned().unwrap_or(json!({}));
+        let result = dispatch(method, &params, id.as_ref(), out, exe);
+        if let Some(id) = id {
+            reply(out, &id, result);
+        }
+    }
+    Ok(())
+}
+
+fn reply(out: &Output, id: &Value, result: std::result::Result<Value, RpcError>) {
+    out.send(&match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": e.code, "message": e.message },
+        }),
+    });
+}
+
+fn dispatch(
+    method: &str,
+    params: &Value,
+    id: Option<&Value>,
+    out: &Output,
+    exe: &Path,
+) -> std::result::Result<Value, RpcError> {
+    let query = |query: Query| -> std::result::Result<Value, RpcError> {
+        let repo = repo::open_repo()?;
+        Ok(api::answer(&repo, query)?)
+    };
+    match method {
+        "initialize" => Ok(json!({ "version": API_VERSION, "methods": METHODS })),
+        "sections" => query(Query::Sections),
+        "resolve" => query(Query::Resolve(string_param(params, "arg")?)),
+        "refs" => query(Query::Refs),
+        "todo" => query(Query::Todo(todo_op(params)?)),
+        "fold" => {
+            let args = string_list_param(params, "args")?;
+            let mut command = vec!["fold".to_string()];
+            command.extend(args);
+            run_operation(exe, &command, id, out)
+        }
+        "drop" => {
+            let target = string_param(params, "target")?;
+            run_operation(exe, &["drop".into(), "--yes".into(), target], id, out)
+        }
+        "reword" => {
+            let target = string_param(params, "target")?;
+            // No editor can open: the message is required
+            let message = string_param(params, "message")?;
+            run_operation(
+                exe,
+                &["reword".into(), target, "-m".into(), message],
+                id,
+                out,
+            )
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method `{}`", method),
+        )),
+    }
+}
+
+fn string_param(params: &Value, name: &str) -> std::result::Result<String, RpcError> {
+    params[name]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Missing string `{}`", name)))
+}
+
+fn string_list_param(params: &Value, name: &str) -> std::result::Result<Vec<String>, RpcError> {
+    let invalid = || {
+        RpcError::new(
+            INVALID_PARAMS,
+            format!("`{}` must be a list of strings", name),
+        )
+    };
+    let list = params[name].as_array().ok_or_else(invalid)?;
+    list.iter()
+        .map(|arg| arg.as_str().map(str::to_string).ok_or_else(invalid))
+        .collect()
+}
+
+/// The hypothetical operation of a `todo` request, named by its `op` param.
+fn todo_op(params: &Value) -> std::result::Result<TodoOp, RpcError> {
+    let param = |name| string_param(params, name);
+    match params["op"].as_str() {
+        Some("drop") => Ok(TodoOp::Drop {
+            target: param("target")?,
+        }),
+        Some("fixup") => Ok(TodoOp::Fixup {
+            source: param("source")?,
+            target: param("target")?,
+        }),
+        Some("move") => Ok(TodoOp::Move {
+            commit: param("commit")?,
+            branch: param("branch")?,
+        }),
+        Some("swap") => Ok(TodoOp::Swap {
+            a: param("a")?,
+            b: param("b")?,
+        }),
+        _ => Err(RpcError::new(
+            INVALID_PARAMS,
+            "`op` must be one of drop, fixup, move, swap",
+        )),
+    }
+}
+
+/// Run `loom <args>` without colors nor input (`--no-input`: anything it
+/// would prompt for fails instead), sending each line it prints as a
+/// `progress` notification for request `id`. The result holds its standard
+/// output; a failure, its error.
+fn run_operation(
+    exe: &Path,
+    args: &[String],
+    id: Option<&Value>,
+    out: &Output,
+) -> std::result::Result<Value, RpcError> {
+    let mut child = Command::new(exe)
+        .args(["--no-color", "--no-input"])
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| RpcError::new(COMMAND_FAILED, format!("Could not run loom: {}", e)))?;
+
+    let id = id.cloned().unwrap_or(Value::Null);
+    let relay = |stream: Box<dyn Read + Send>| {
+        let (out, id) = (out.clone(), id.clone());
+        std::thread::spawn(move || {
+            let mut lines = Vec::new();
+            for line in BufReader::new(stream).lines().map_while(|l| l.ok()) {
+                out.notify("progress", json!({ "id": id, "message": line }));
+                lines.push(line);
+            }
+            lines
+        })
+    };
+    let stdout = relay(Box::new(child.stdout.take().expect("piped stdout")));
+    let stderr = relay(Box::new(child.stderr.take().expect("piped stderr")));
+    let status = child
+        .wait()
+        .map_err(|e| RpcError::new(COMMAND_FAILED, e.to_string()))?;
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    if !status.success() {
+        let message = if stderr.is_empty() { &stdout } else { &stderr };
+        return Err(RpcError::new(COMMAND_FAILED, message.join("\n")));
+    }
+    Ok(json!({ "output": stdout.join("\n") }))
+}
+
+/// Push a `changed` notification each time the repository state moves.
+fn watch(git_dir: &Path, out: &Output) {
+    let mut last = fingerprint(git_dir);
+    loop {
+        std::thread::sleep(WATCH_INTERVAL);
+        let current = fingerprint(git_dir);
+        if current != last {
+            out.notify("changed", json!({}));
+            last = current;
+        }
+    }
+}
+
+/// What the watcher compares: HEAD, the local branches, the index
+/// modification time, and whether a loom operation is paused.
+pub fn fingerprint(git_dir: &Path) -> Option<String> {
+    let repo = Repository::open(git_dir).ok()?;
+    let head = repo.head().ok().and_then(|h| h.target());
+    let mut branches: Vec<_> = repo::snapshot_branch_refs(&repo)
+        .ok()?
+        .into_iter()
+        .collect();
+    branches.sort();
+    let index = std::fs::metadata(git_dir.join("index"))
+        .and_then(|m| m.modified())
+        .ok();
+    let paused: PathBuf = git_dir.join("loom").join("state.json");
+    Some(format!(
+        "{:?} {:?} {:?} {}",
+        head,
+        branches,
+        index,
+        paused.exists()
+    ))
+}
+
+#[cfg(test)]
+#[path = "serve_test.rs"]
+mod tests;
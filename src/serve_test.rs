@@ -0,0 +1,164 @@
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{Value, json};
+
+use super::Output;
+use crate::core::test_helpers::TestRepo;
+
+/// A writer tests can read back.
+#[derive(Clone, Default)]
+struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Serve `requests` in `test_repo`, running operations with `exe`, and
+/// return the messages written.
+fn serve(test_repo: &TestRepo, requests: &[Value], exe: &str) -> Vec<Value> {
+    let buffer = Buffer::default();
+    let input: String = requests.iter().map(|r| format!("{}\n", r)).collect();
+    test_repo
+        .in_dir(|| {
+            super::serve(
+                input.as_bytes(),
+                &Output::new(buffer.clone()),
+                Path::new(exe),
+            )
+        })
+        .unwrap();
+    let bytes = buffer.0.lock().unwrap().clone();
+    String::from_utf8(bytes)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+#[test]
+fn answers_queries_in_process() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit("Loose", "loose.txt");
+
+    let messages = serve(
+        &test_repo,
+        &[
+            request(1, "initialize", json!({})),
+            request(2, "sections", json!({})),
+            request(3, "resolve", json!({ "arg": "integration" })),
+        ],
+        "false",
+    );
+
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[0]["id"], 1);
+    assert_eq!(messages[0]["result"]["version"], crate::api::API_VERSION);
+    assert_eq!(messages[1]["result"]["loose"][0]["message"], "Loose");
+    assert_eq!(messages[2]["result"]["kind"], "branch");
+}
+
+#[test]
+fn reports_protocol_errors() {
+    let test_repo = TestRepo::new_with_remote();
+    let buffer = Buffer::default();
+    let input = "not json\n\
+                 {\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"frobnicate\"}\n\
+                 {\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"resolve\"}\n\
+                 {\"jsonrpc\":\"2.0\",\"method\":\"sections\"}\n\
+                 {\"jsonrpc\":\"2.0\",\"id\":3,\"method\":\"resolve\",\"params\":{\"arg\":\"nope\"}}\n\
+                 {\"jsonrpc\":\"2.0\",\"method\":\"exit\"}\n\
+                 {\"jsonrpc\":\"2.0\",\"id\":4,\"method\":\"sections\"}\n";
+    test_repo
+        .in_dir(|| {
+            super::serve(
+                input.as_bytes(),
+                &Output::new(buffer.clone()),
+                Path::new("false"),
+            )
+        })
+        .unwrap();
+    let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let messages: Vec<Value> = output
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+
+    // The notification gets no response, and nothing is read after `exit`
+    let codes: Vec<i64> = messages
+        .iter()
+        .map(|m| m["error"]["code"].as_i64().unwrap())
+        .collect();
+    assert_eq!(codes, vec![-32700, -32601, -32602, -32000]);
+    assert_eq!(messages[0]["id"], Value::Null);
+    assert_eq!(messages[3]["id"], 3);
+}
+
+#[test]
+fn operations_relay_progress_then_respond() {
+    let test_repo = TestRepo::new_with_remote();
+
+    // `echo` stands in for loom: it prints the arguments it was given
+    let messages = serve(
+        &test_repo,
+        &[request(7, "drop", json!({ "target": "ab" }))],
+        "echo",
+    );
+
+    assert_eq!(
+        messages[0],
+        json!({
+            "jsonrpc": "2.0",
+            "method": "progress",
+            "params": { "id": 7, "message": "--no-color --no-input drop --yes ab" },
+        })
+    );
+    assert_eq!(messages[1]["id"], 7);
+    assert_eq!(
+        messages[1]["result"]["output"],
+        "--no-color --no-input drop --yes ab"
+    );
+}
+
+#[test]
+fn failed_operations_return_their_error() {
+    let test_repo = TestRepo::new_with_remote();
+
+    let messages = serve(
+        &test_repo,
+        &[request(
+            1,
+            "reword",
+            json!({ "target": "ab", "message": "New" }),
+        )],
+        "false",
+    );
+
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["error"]["code"], -32000);
+}
+
+#[test]
+fn fingerprint_follows_branches_and_paused_operations() {
+    let test_repo = TestRepo::new_with_remote();
+    let git_dir = test_repo.repo.path().to_path_buf();
+    let before = super::fingerprint(&git_dir);
+
+    test_repo.create_branch("feature-a");
+    let with_branch = super::fingerprint(&git_dir);
+    assert_ne!(before, with_branch);
+
+    std::fs::create_dir_all(git_dir.join("loom")).unwrap();
+    std::fs::write(git_dir.join("loom").join("state.json"), "{}").unwrap();
+    assert_ne!(with_branch, super::fingerprint(&git_dir));
+}
@@ -33,8 +33,7 @@ pub fn run(target: Option<String>) -> Result<()> {
         }
     };
 
-    let workdir = repo::require_workdir(&repo, "show")?;
-    git::run_git_interactive(workdir, &["show", &git_ref])
+    git::run_git_interactive(repo::command_dir(&repo), &["show", &git_ref])
 }
 
 #[cfg(test)]
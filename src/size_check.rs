@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use git2::Repository;
+
+use crate::core::msg;
+use crate::core::repo;
+
+/// Why a staged file is flagged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    /// Larger than `loom.maxFileSize` (the limit).
+    TooLarge(u64),
+    /// Matches a `loom.binaryPatterns` pattern.
+    Binary(String),
+}
+
+/// A staged file that should probably not be committed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flagged {
+    pub path: String,
+    /// Size of the staged content.
+    pub size: u64,
+    pub reason: Reason,
+}
+
+/// Check the staged files, for use in a pre-commit hook: lists the flagged
+/// ones and fails if there are any.
+pub fn run() -> Result<()> {
+    let repo = repo::open_repo()?;
+    let flagged = check_staged(&repo)?;
+    if flagged.is_empty() {
+        msg::success("No large or binary file staged");
+        return Ok(());
+    }
+    for file in &flagged {
+        msg::warn(&describe(file));
+    }
+    bail!(
+        "{} staged {} flagged\nUnstage with `git restore --staged <file>`, or track with Git LFS",
+        flagged.len(),
+        if flagged.len() == 1 { "file" } else { "files" }
+    );
+}
+
+/// Warn about the flagged staged files, before they are committed. Never
+/// fails: the check must not get in the way of the commit.
+pub fn warn_staged(repo: &Repository) {
+    let Ok(flagged) = check_staged(repo) else {
+        return;
+    };
+    for file in &flagged {
+        msg::warn(&describe(file));
+    }
+}
+
+/// The staged files over `loom.maxFileSize` or matching `loom.binaryPatterns`.
+/// Deleted files are never flagged; sizes are those of the staged blobs, so
+/// Git LFS files count as their pointer.
+pub fn check_staged(repo: &Repository) -> Result<Vec<Flagged>> {
+    let max_size = repo::max_file_size(repo);
+    let patterns = repo::binary_file_patterns(repo);
+    if max_size.is_none() && patterns.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut index = repo.index()?;
+    index.read(false)?;
+    let odb = repo.odb()?;
+    let mut flagged = Vec::new();
+    for path in repo::get_staged_files(repo)? {
+        let Some(entry) = index.get_path(Path::new(&path), 0) else {
+            continue;
+        };
+        let (size, _) = odb.read_header(entry.id)?;
+        let size = size as u64;
+        let reason = if let Some(max) = max_size.filter(|max| size > *max) {
+            Reason::TooLarge(max)
+        } else if let Some(pattern) = matching_pattern(&patterns, &path) {
+            Reason::Binary(pattern.clone())
+        } else {
+            continue;
+        };
+        flagged.push(Flagged { path, size, reason });
+    }
+    Ok(flagged)
+}
+
+/// The first of `patterns` that `path` matches.
+fn matching_pattern<'a>(patterns: &'a [String], path: &str) -> Option<&'a String> {
+    patterns.iter().find(|pattern| {
+        git2::Pathspec::new([pattern.as_str()])
+            .is_ok_and(|p| p.matches_path(Path::new(path), git2::PathspecFlags::DEFAULT))
+    })
+}
+
+fn describe(file: &Flagged) -> String {
+    match &file.reason {
+        Reason::TooLarge(max) => format!(
+            "`{}` is {}, over loom.maxFileSize ({})",
+            file.path,
+            msg::format_size(file.size),
+            msg::format_size(*max)
+        ),
+        Reason::Binary(pattern) => format!(
+            "`{}` ({}) matches loom.binaryPatterns `{}`",
+            file.path,
+            msg::format_size(file.size),
+            pattern
+        ),
+    }
+}
+
+#[cfg(test)]
+#[path = "size_check_test.rs"]
+mod tests;
@@ -0,0 +1,88 @@
+use crate::core::test_helpers::TestRepo;
+use crate::git;
+
+use super::{Flagged, Reason, check_staged};
+
+#[test]
+fn flags_staged_file_over_max_size() {
+    let test_repo = TestRepo::new();
+    test_repo.set_config("loom.maxFileSize", "1k");
+    test_repo.write_file("big.txt", &"x".repeat(2000));
+    test_repo.write_file("small.txt", "small");
+    test_repo.stage_files(&["big.txt", "small.txt"]);
+
+    assert_eq!(
+        check_staged(&test_repo.repo).unwrap(),
+        vec![Flagged {
+            path: "big.txt".to_string(),
+            size: 2000,
+            reason: Reason::TooLarge(1024),
+        }]
+    );
+}
+
+#[test]
+fn flags_staged_file_matching_binary_pattern() {
+    let test_repo = TestRepo::new();
+    let workdir = test_repo.workdir();
+    git::run_git(
+        &workdir,
+        &["config", "--add", "loom.binaryPatterns", "*.zip"],
+    )
+    .unwrap();
+    git::run_git(
+        &workdir,
+        &["config", "--add", "loom.binaryPatterns", "dist/**"],
+    )
+    .unwrap();
+    std::fs::create_dir(workdir.join("dist")).unwrap();
+    test_repo.write_file("archive.zip", "PK");
+    test_repo.write_file("dist/app.js", "bundle");
+    test_repo.write_file("src.txt", "source");
+    test_repo.stage_files(&["archive.zip", "dist/app.js", "src.txt"]);
+
+    let reasons: Vec<(String, Reason)> = check_staged(&test_repo.repo)
+        .unwrap()
+        .into_iter()
+        .map(|f| (f.path, f.reason))
+        .collect();
+    assert_eq!(
+        reasons,
+        vec![
+            (
+                "archive.zip".to_string(),
+                Reason::Binary("*.zip".to_string())
+            ),
+            (
+                "dist/app.js".to_string(),
+                Reason::Binary("dist/**".to_string())
+            ),
+        ]
+    );
+}
+
+#[test]
+fn unstaged_and_deleted_files_are_not_flagged() {
+    let test_repo = TestRepo::new();
+    test_repo.set_config("loom.maxFileSize", "10");
+    test_repo.write_file("big.txt", &"x".repeat(100));
+    test_repo.stage_files(&["big.txt"]);
+    test_repo.commit_staged("Add big file");
+
+    // Deleted: nothing is added to the history
+    git::run_git(&test_repo.workdir(), &["rm", "-q", "big.txt"]).unwrap();
+    // Unstaged: not part of the next commit
+    test_repo.write_file("other.txt", &"x".repeat(100));
+
+    assert!(check_staged(&test_repo.repo).unwrap().is_empty());
+}
+
+#[test]
+fn zero_max_size_disables_check() {
+    let test_repo = TestRepo::new();
+    test_repo.set_config("loom.maxFileSize", "0");
+    test_repo.write_file("big.txt", &"x".repeat(10 * 1024 * 1024));
+    test_repo.stage_files(&["big.txt"]);
+
+    assert!(check_staged(&test_repo.repo).unwrap().is_empty());
+}
@@ -2,6 +2,7 @@ use anyhow::{Result, bail};
 use git2::{Oid, Repository};
 
 use crate::core::repo::{self, Target, TargetKind};
+use crate::core::transaction::{self, Rollback};
 use crate::core::weave;
 use crate::core::{diff, graph, msg, staging};
 use crate::git;
@@ -10,11 +11,22 @@ use crate::tui::hunk_selector::FileEntry;
 const COMMAND: &str = "split";
 
 /// Commit with `-m` message or open the editor.
-fn commit_or_editor(workdir: &std::path::Path, message: Option<&str>) -> Result<()> {
-    match message {
+///
+/// `original_head` is the commit being split: if the commit step fails (e.g.
+/// the editor is closed with an empty message), HEAD is reset back to it.
+fn commit_or_editor(
+    workdir: &std::path::Path,
+    original_head: &str,
+    message: Option<&str>,
+) -> Result<()> {
+    let rollback = Rollback {
+        reset_mixed_to: original_head.to_string(),
+        ..Default::default()
+    };
+    transaction::editor_step(workdir, &rollback, || match message {
         Some(m) => git::commit(workdir, m),
         None => git::commit_with_editor(workdir),
-    }
+    })
 }
 
 /// Split a commit into two sequential commits.
@@ -132,6 +144,7 @@ pub fn split_commit_with_selection(
 
 /// Show an interactive file picker for splitting.
 fn pick_files(files: &[String]) -> Result<Vec<String>> {
+    msg::require_input("the files for the first commit")?;
     let selected = inquire::MultiSelect::new("Select files for the first commit:", files.to_vec())
         .with_validator(|selection: &[inquire::list_option::ListOption<&String>]| {
             if selection.is_empty() {
@@ -238,11 +251,12 @@ fn perform_head_split(
     msg1: Option<&str>,
     msg2: &str,
 ) -> Result<(String, String)> {
+    let original_head = git::rev_parse(workdir, "HEAD")?;
     git::reset_mixed(workdir, "HEAD~1")?;
 
     let selected_refs: Vec<&str> = selected.iter().map(|s| s.as_str()).collect();
     git::stage_files(workdir, &selected_refs)?;
-    commit_or_editor(workdir, msg1)?;
+    commit_or_editor(workdir, &original_head, msg1)?;
 
     let remaining_refs: Vec<&str> = remaining.iter().map(|s| s.as_str()).collect();
     git::stage_files(workdir, &remaining_refs)?;
@@ -285,6 +299,7 @@ fn perform_head_split_by_hunks(
     msg1: Option<&str>,
     msg2: &str,
 ) -> Result<(String, String)> {
+    let original_head = git::rev_parse(workdir, "HEAD")?;
     git::reset_mixed(workdir, "HEAD~1")?;
 
     let mut selected_patch = String::new();
@@ -308,7 +323,7 @@ fn perform_head_split_by_hunks(
         git::apply_cached_patch(workdir, &selected_patch)?;
     }
 
-    commit_or_editor(workdir, msg1)?;
+    commit_or_editor(workdir, &original_head, msg1)?;
 
     for file in selections {
         if file.hunks.iter().any(|h| !h.selected) {
@@ -213,3 +213,50 @@ fn split_with_woven_branches() {
         "HEAD should still be a merge commit"
     );
 }
+
+// ── Rollback tests ───────────────────────────────────────────────────
+
+#[test]
+fn split_head_commit_failure_restores_commit() {
+    let test_repo = TestRepo::new();
+    test_repo.commit("Add files", "file1.txt");
+    let target_oid = test_repo.commit_multi(
+        &[("file_a.txt", "content a"), ("file_b.txt", "content b")],
+        "Two files commit",
+    );
+    test_repo.reject_commit_messages();
+
+    let result = super::split_commit_with_selection(
+        &test_repo.repo,
+        &target_oid.to_string(),
+        vec!["file_a.txt".to_string()],
+        "First part".to_string(),
+    );
+
+    assert!(result.is_err());
+    assert_eq!(test_repo.head_oid(), target_oid);
+    assert_eq!(test_repo.status_porcelain(), "");
+}
+
+#[test]
+fn split_non_head_commit_failure_aborts_rebase() {
+    let test_repo = TestRepo::new_with_remote();
+    let target_oid = test_repo.commit_multi(
+        &[("file_a.txt", "content a"), ("file_b.txt", "content b")],
+        "Two files commit",
+    );
+    let head_oid = test_repo.commit("Later commit", "later.txt");
+    test_repo.reject_commit_messages();
+
+    let result = super::split_commit_with_selection(
+        &test_repo.repo,
+        &target_oid.to_string(),
+        vec!["file_a.txt".to_string()],
+        "First part".to_string(),
+    );
+
+    assert!(result.is_err());
+    assert!(!crate::git::rebase_is_in_progress(test_repo.repo.path()));
+    assert_eq!(test_repo.head_oid(), head_oid);
+    assert_eq!(test_repo.status_porcelain(), "");
+}
@@ -0,0 +1,223 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use git2::{Oid, Repository};
+
+use crate::core::{msg, repo};
+use crate::git;
+
+/// A move along the stack of woven branches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// One layer up, toward the integration branch.
+    Next,
+    /// One layer down, toward the upstream base.
+    Prev,
+    /// Back to the integration branch.
+    Top,
+}
+
+/// One layer of the stack: the branches sharing a tip commit.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Layer {
+    /// Branch names at this tip, sorted. The first one is checked out.
+    pub branch_names: Vec<String>,
+    /// Tip commit of the layer.
+    pub tip: Oid,
+}
+
+/// Check out the next or previous woven branch tip, or return to the
+/// integration branch with `Step::Top`.
+pub fn run(step: Step, detach: bool) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "navigate the stack")?.to_path_buf();
+    let landed = navigate(&repo, &workdir, step, detach)?;
+    match landed {
+        Some(name) if detach => msg::success(&format!("Detached HEAD at `{}`", name)),
+        Some(name) => msg::success(&format!("Switched to `{}`", name)),
+        None => {
+            let head = repo.head()?;
+            msg::success(&format!("Back on `{}`", head.shorthand().unwrap_or("HEAD")));
+        }
+    }
+    Ok(())
+}
+
+/// Perform `step` and return the branch now checked out, or None when back
+/// on the integration branch.
+///
+/// Leaving the integration branch records its name in `.git/loom/stack-top`
+/// so later steps (and `Step::Top`) know which stack is being walked; the
+/// file is removed on return.
+pub fn navigate(
+    repo: &Repository,
+    workdir: &Path,
+    step: Step,
+    detach: bool,
+) -> Result<Option<String>> {
+    let integration = integration_branch(repo)?;
+    let target = if step == Step::Top {
+        None
+    } else {
+        let layers = stack_layers(repo, &integration)?;
+        let current = current_layer(repo, &integration, &layers)?;
+        target_layer(&layers, current, step)?.map(|i| layers[i].branch_names[0].clone())
+    };
+
+    if is_checked_out(repo, target.as_deref().unwrap_or(&integration), detach) {
+        match &target {
+            Some(name) => bail!("Already on `{}`", name),
+            None => bail!("Already on the integration branch `{}`", integration),
+        }
+    }
+    crate::switch::check_clean(repo)?;
+
+    match &target {
+        Some(name) => {
+            if detach {
+                git::branch_switch_detach(workdir, name)?;
+            } else {
+                git::branch_switch(workdir, name)?;
+            }
+            save_top(repo, &integration)?;
+        }
+        None => {
+            git::branch_switch(workdir, &integration)?;
+            clear_top(repo)?;
+        }
+    }
+    Ok(target)
+}
+
+/// The woven branches of `integration`, one layer per distinct tip, from the
+/// bottom of the stack (closest to the base) to the top. Hidden branches are
+/// skipped.
+pub fn stack_layers(repo: &Repository, integration: &str) -> Result<Vec<Layer>> {
+    let info = repo::gather_branch_info(repo, integration, false, 0)?;
+    let hide =
+        repo::hide_branch_pattern(repo).unwrap_or_else(|| repo::DEFAULT_HIDE_PATTERN.to_string());
+
+    // `info.commits` is newest first: a larger index is lower in the stack.
+    let position = |oid: Oid| info.commits.iter().position(|c| c.oid == oid);
+    let mut layers: Vec<Layer> = Vec::new();
+    for branch in &info.branches {
+        if !hide.is_empty() && branch.name.starts_with(&hide) {
+            continue;
+        }
+        match layers.iter_mut().find(|l| l.tip == branch.tip_oid) {
+            Some(layer) => layer.branch_names.push(branch.name.clone()),
+            None => layers.push(Layer {
+                branch_names: vec![branch.name.clone()],
+                tip: branch.tip_oid,
+            }),
+        }
+    }
+    for layer in &mut layers {
+        layer.branch_names.sort();
+    }
+    layers.sort_by(|a, b| {
+        position(b.tip)
+            .cmp(&position(a.tip))
+            .then_with(|| a.branch_names.cmp(&b.branch_names))
+    });
+    Ok(layers)
+}
+
+/// Index of the layer to move to from `current` (None = the integration
+/// branch), or None to return to the integration branch. `Next` from the
+/// integration branch wraps around to the bottom layer.
+fn target_layer(layers: &[Layer], current: Option<usize>, step: Step) -> Result<Option<usize>> {
+    if layers.is_empty() {
+        bail!("No woven branches to navigate");
+    }
+    Ok(match (step, current) {
+        (Step::Top, _) => None,
+        (Step::Next, None) => Some(0),
+        (Step::Next, Some(i)) if i + 1 < layers.len() => Some(i + 1),
+        (Step::Next, Some(_)) => None,
+        (Step::Prev, None) => Some(layers.len() - 1),
+        (Step::Prev, Some(0)) => bail!("Already at the bottom of the stack"),
+        (Step::Prev, Some(i)) => Some(i - 1),
+    })
+}
+
+/// Locate HEAD in the stack: None on the integration branch, otherwise the
+/// index of the layer whose branch (or, when detached, tip) HEAD is at.
+fn current_layer(repo: &Repository, integration: &str, layers: &[Layer]) -> Result<Option<usize>> {
+    let head = repo.head()?;
+    let found = if head.is_branch() {
+        let name = head.shorthand().unwrap_or("HEAD");
+        if name == integration {
+            return Ok(None);
+        }
+        layers
+            .iter()
+            .position(|l| l.branch_names.iter().any(|n| n == name))
+    } else {
+        let oid = head.target().context("HEAD does not point to a commit")?;
+        layers.iter().position(|l| l.tip == oid)
+    };
+    found.map(Some).with_context(|| {
+        format!(
+            "HEAD is not on a branch woven into `{}`\nRun `loom top` to return to it",
+            integration
+        )
+    })
+}
+
+/// The integration branch whose stack is being walked: the one recorded when
+/// navigation started, or the current branch.
+fn integration_branch(repo: &Repository) -> Result<String> {
+    if let Ok(name) = std::fs::read_to_string(top_path(repo)) {
+        let name = name.trim();
+        if repo.find_branch(name, git2::BranchType::Local).is_ok() {
+            return Ok(name.to_string());
+        }
+    }
+    let head = repo.head()?;
+    if !head.is_branch() {
+        bail!("HEAD is detached\nSwitch to an integration branch");
+    }
+    Ok(head.shorthand().unwrap_or("HEAD").to_string())
+}
+
+/// Whether HEAD already is `name` (as a branch, or detached at its tip).
+fn is_checked_out(repo: &Repository, name: &str, detach: bool) -> bool {
+    let Ok(head) = repo.head() else {
+        return false;
+    };
+    if head.is_branch() {
+        return !detach && head.shorthand() == Some(name);
+    }
+    detach
+        && repo
+            .revparse_single(name)
+            .is_ok_and(|obj| Some(obj.id()) == head.target())
+}
+
+/// Path of the file recording the integration branch being walked.
+fn top_path(repo: &Repository) -> PathBuf {
+    repo.path().join("loom").join("stack-top")
+}
+
+fn save_top(repo: &Repository, integration: &str) -> Result<()> {
+    let path = top_path(repo);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, integration)
+        .with_context(|| format!("Failed to write '{}'", path.display()))
+}
+
+fn clear_top(repo: &Repository) -> Result<()> {
+    let path = top_path(repo);
+    if path.exists() {
+        std::fs::remove_file(&path)
+            .with_context(|| format!("Failed to delete '{}'", path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "stack_test.rs"]
+mod tests;
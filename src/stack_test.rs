@@ -0,0 +1,102 @@
+use super::{Step, navigate, stack_layers};
+use crate::core::test_helpers::TestRepo;
+
+/// Integration branch with `feature-b` stacked on `feature-a`, both woven.
+fn setup_stack() -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a.txt");
+    test_repo.create_branch("feature-b");
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1", "b.txt");
+    test_repo.switch_branch("integration");
+    test_repo.commit("Int", "int.txt");
+    test_repo.merge_no_ff("feature-b");
+    test_repo
+}
+
+fn step(test_repo: &TestRepo, step: Step, detach: bool) -> Option<String> {
+    navigate(&test_repo.repo, &test_repo.workdir(), step, detach).unwrap()
+}
+
+#[test]
+fn layers_ordered_from_bottom_to_top() {
+    let test_repo = setup_stack();
+
+    let layers = stack_layers(&test_repo.repo, "integration").unwrap();
+    let names: Vec<&str> = layers.iter().map(|l| l.branch_names[0].as_str()).collect();
+    assert_eq!(names, vec!["feature-a", "feature-b"]);
+}
+
+#[test]
+fn next_walks_up_the_stack_and_back_to_integration() {
+    let test_repo = setup_stack();
+
+    assert_eq!(
+        step(&test_repo, Step::Next, false).as_deref(),
+        Some("feature-a")
+    );
+    assert_eq!(test_repo.current_branch_name(), "feature-a");
+    assert_eq!(
+        step(&test_repo, Step::Next, false).as_deref(),
+        Some("feature-b")
+    );
+    assert_eq!(test_repo.current_branch_name(), "feature-b");
+    assert_eq!(step(&test_repo, Step::Next, false), None);
+    assert_eq!(test_repo.current_branch_name(), "integration");
+    assert!(!test_repo.repo.path().join("loom/stack-top").exists());
+}
+
+#[test]
+fn prev_walks_down_and_stops_at_the_bottom() {
+    let test_repo = setup_stack();
+
+    assert_eq!(
+        step(&test_repo, Step::Prev, false).as_deref(),
+        Some("feature-b")
+    );
+    assert_eq!(
+        step(&test_repo, Step::Prev, false).as_deref(),
+        Some("feature-a")
+    );
+    let err = navigate(&test_repo.repo, &test_repo.workdir(), Step::Prev, false).unwrap_err();
+    assert!(err.to_string().contains("bottom of the stack"), "{}", err);
+
+    assert_eq!(step(&test_repo, Step::Top, false), None);
+    assert_eq!(test_repo.current_branch_name(), "integration");
+}
+
+#[test]
+fn detached_navigation_follows_branch_tips() {
+    let test_repo = setup_stack();
+    let tip_a = test_repo.get_branch_target("feature-a");
+    let tip_b = test_repo.get_branch_target("feature-b");
+
+    step(&test_repo, Step::Next, true);
+    assert!(!test_repo.is_on_branch());
+    assert_eq!(test_repo.head_oid(), tip_a);
+    step(&test_repo, Step::Next, true);
+    assert_eq!(test_repo.head_oid(), tip_b);
+    step(&test_repo, Step::Top, false);
+    assert_eq!(test_repo.current_branch_name(), "integration");
+}
+
+#[test]
+fn dirty_tree_blocks_navigation() {
+    let test_repo = setup_stack();
+    test_repo.write_file("int.txt", "changed");
+
+    let err = navigate(&test_repo.repo, &test_repo.workdir(), Step::Next, false).unwrap_err();
+    assert!(err.to_string().contains("uncommitted changes"), "{}", err);
+    assert_eq!(test_repo.current_branch_name(), "integration");
+}
+
+#[test]
+fn top_on_integration_is_an_error() {
+    let test_repo = setup_stack();
+
+    let err = navigate(&test_repo.repo, &test_repo.workdir(), Step::Top, false).unwrap_err();
+    assert!(err.to_string().contains("Already on"), "{}", err);
+}
@@ -0,0 +1,430 @@
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use git2::{BranchType, Oid, Repository};
+use serde::{Deserialize, Serialize};
+
+use crate::archive::{self, ARCHIVE_PREFIX};
+use crate::core::clock;
+use crate::core::msg;
+use crate::core::repo;
+use crate::git;
+
+/// Ref of the commit carrying `state.json` in an exported bundle.
+const STATE_REF: &str = "refs/loom/state";
+
+/// Namespace the refs of a bundle are fetched into while it is imported.
+pub(crate) const IMPORT_PREFIX: &str = "refs/loom/import/";
+
+/// Version of the `state.json` format, bumped on incompatible changes.
+const STATE_VERSION: u32 = 1;
+
+/// What a bundle carries besides the branch refs: how the integration branch
+/// and its woven branches are set up, and the loom settings they rely on.
+///
+/// The weave itself (sections, stacking, merge order) lives in the history of
+/// the integration branch, and short IDs are derived from the objects, so
+/// both come out the same once the refs are recreated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WeaveState {
+    pub version: u32,
+    pub integration: TrackedBranch,
+    /// Base of a local-only integration branch (`loom.base`).
+    pub base: Option<String>,
+    /// Woven branches, in the order `loom status` lists them.
+    pub branches: Vec<TrackedBranch>,
+    /// Branches frozen with `loom archive`.
+    pub archived: Vec<String>,
+    /// `loom.hideBranchPattern`, which decides which branches are hidden.
+    pub hide_branch_pattern: Option<String>,
+    /// Notes ref carried along, when the repository has notes.
+    pub notes_ref: Option<String>,
+}
+
+/// A branch and its tracking configuration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackedBranch {
+    pub name: String,
+    pub tip: String,
+    /// `branch.<name>.remote`
+    pub remote: Option<String>,
+    /// `branch.<name>.merge`
+    pub merge: Option<String>,
+    /// `branch.<name>.loomPushRemote`
+    pub push_remote: Option<String>,
+}
+
+/// Write the integration branch, its woven branches, and their loom state to
+/// a bundle `file`.
+pub fn run_export(file: String) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "export the weave")?;
+    let state = collect(&repo)?;
+    export(&repo, workdir, &state, Path::new(&file))?;
+    msg::success(&format!(
+        "Exported `{}` and {} woven {} to `{}`\nImport it with `loom import-state {}`",
+        state.integration.name,
+        state.branches.len(),
+        if state.branches.len() == 1 {
+            "branch"
+        } else {
+            "branches"
+        },
+        file,
+        file
+    ));
+    Ok(())
+}
+
+/// Recreate the integration branch and woven branches of a bundle written by
+/// `loom export-state`.
+pub fn run_import(file: String) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "import a weave")?;
+    let state = import(&repo, workdir, Path::new(&file))?;
+    let current = repo
+        .head()
+        .ok()
+        .and_then(|h| h.shorthand().map(str::to_string));
+    msg::success(&format!(
+        "Imported `{}` and {} woven {}{}",
+        state.integration.name,
+        state.branches.len(),
+        if state.branches.len() == 1 {
+            "branch"
+        } else {
+            "branches"
+        },
+        if current.as_deref() == Some(state.integration.name.as_str()) {
+            String::new()
+        } else {
+            format!(
+                "\nRun `git switch {}` to work on it",
+                state.integration.name
+            )
+        }
+    ));
+    Ok(())
+}
+
+/// The state of the integration branch checked out in `repo`.
+pub fn collect(repo: &Repository) -> Result<WeaveState> {
+    let info = repo::gather_repo_info(repo, false, 0)?;
+    let integration = tracked_branch(repo, &info.branch_name)?;
+    let branches = info
+        .branches
+        .iter()
+        .map(|b| tracked_branch(repo, &b.name))
+        .collect::<Result<_>>()?;
+    let archived = archive::archived_branches(repo)?
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    let notes_ref = repo
+        .note_default_ref()
+        .ok()
+        .filter(|r| repo.find_reference(r).is_ok());
+    Ok(WeaveState {
+        version: STATE_VERSION,
+        integration,
+        base: (info.upstream.local_only && !info.upstream.label.is_empty())
+            .then(|| info.upstream.label.clone()),
+        branches,
+        archived,
+        hide_branch_pattern: repo::hide_branch_pattern(repo),
+        notes_ref,
+    })
+}
+
+fn tracked_branch(repo: &Repository, name: &str) -> Result<TrackedBranch> {
+    let tip = repo
+        .find_branch(name, BranchType::Local)?
+        .get()
+        .target()
+        .with_context(|| format!("Branch `{}` does not point to a commit", name))?;
+    let config = repo.config()?;
+    let get = |key: &str| config.get_string(&format!("branch.{}.{}", name, key)).ok();
+    Ok(TrackedBranch {
+        name: name.to_string(),
+        tip: tip.to_string(),
+        remote: get("remote"),
+        merge: get("merge"),
+        push_remote: get("loomPushRemote"),
+    })
+}
+
+/// Bundle the refs of `state` and `state.json` into `file`.
+///
+/// Commits of the upstream below the integration branch's merge-base are left
+/// out: the importing repository gets them by fetching the upstream.
+pub fn export(repo: &Repository, workdir: &Path, state: &WeaveState, file: &Path) -> Result<()> {
+    let commit = commit_state(repo, state, None, "loom: export state")?;
+    let mut state_ref = repo.reference(STATE_REF, commit, true, "loom: export state")?;
+
+    let mut refs = vec![format!("refs/heads/{}", state.integration.name)];
+    refs.extend(
+        state
+            .branches
+            .iter()
+            .map(|b| format!("refs/heads/{}", b.name)),
+    );
+    refs.extend(
+        state
+            .archived
+            .iter()
+            .map(|n| format!("{}{}", ARCHIVE_PREFIX, n)),
+    );
+    refs.extend(state.notes_ref.clone());
+    refs.push(STATE_REF.to_string());
+    if state.base.is_none() && state.integration.remote.is_some() {
+        let info = repo::gather_repo_info(repo, false, 0)?;
+        refs.push(format!("^{}", info.upstream.merge_base_oid));
+    }
+
+    let file = file.to_string_lossy();
+    let mut args = vec!["bundle", "create", "-q", &file];
+    args.extend(refs.iter().map(String::as_str));
+    let result = git::run_git(workdir, &args);
+    state_ref.delete()?;
+    result.with_context(|| format!("Could not write the bundle `{}`", file))
+}
+
+/// Record `state` as `state.json` in a commit on top of `parent`.
+pub fn commit_state(
+    repo: &Repository,
+    state: &WeaveState,
+    parent: Option<Oid>,
+    message: &str,
+) -> Result<Oid> {
+    let json = serde_json::to_string_pretty(state)?;
+    let mut tree = repo.treebuilder(None)?;
+    tree.insert("state.json", repo.blob(json.as_bytes())?, 0o100644)?;
+    let tree = repo.find_tree(tree.write()?)?;
+    let sig = clock::signature(
+        repo,
+        Some("git-loom".to_string()),
+        Some("git-loom@localhost".to_string()),
+    )?;
+    let parents = match parent {
+        Some(oid) => vec![repo.find_commit(oid)?],
+        None => Vec::new(),
+    };
+    let parents: Vec<&git2::Commit> = parents.iter().collect();
+    Ok(repo.commit(None, &sig, &sig, message, &tree, &parents)?)
+}
+
+/// Fetch the refs of bundle `file` and recreate the branches and settings of
+/// its state. Branches that already exist must point to the same commits.
+pub fn import(repo: &Repository, workdir: &Path, file: &Path) -> Result<WeaveState> {
+    let file = file.to_string_lossy();
+    git::run_git(workdir, &["bundle", "verify", "-q", &file]).with_context(|| {
+        format!(
+            "`{}` is not a bundle, or this repository lacks the commits it is based on\n\
+             Run `git fetch` to get the upstream, then import again",
+            file
+        )
+    })?;
+    let refspec = format!("+refs/*:{}*", IMPORT_PREFIX);
+    git::run_git(
+        workdir,
+        &[
+            "fetch",
+            "-q",
+            "--no-tags",
+            "--no-write-fetch-head",
+            &file,
+            &refspec,
+        ],
+    )?;
+
+    let result = read_state(repo, &imported(STATE_REF))
+        .context("The bundle was not written by `loom export-state`")
+        .and_then(|state| {
+            apply(repo, workdir, &state)?;
+            Ok(state)
+        });
+    for reference in repo.references_glob(&format!("{}*", IMPORT_PREFIX))? {
+        reference?.delete()?;
+    }
+    result
+}
+
+/// The fetched ref of `refname` from the imported bundle.
+fn imported(refname: &str) -> String {
+    format!(
+        "{}{}",
+        IMPORT_PREFIX,
+        refname.strip_prefix("refs/").unwrap_or(refname)
+    )
+}
+
+/// The state recorded in the `state.json` of the commit at `refname`.
+pub fn read_state(repo: &Repository, refname: &str) -> Result<WeaveState> {
+    let commit = repo
+        .find_reference(refname)
+        .and_then(|r| r.peel_to_commit())
+        .ok()
+        .with_context(|| format!("`{}` does not exist", refname))?;
+    let blob = commit
+        .tree()?
+        .get_name("state.json")
+        .with_context(|| format!("`{}` has no `state.json`", refname))?
+        .to_object(repo)?
+        .peel_to_blob()?;
+    let state: WeaveState = serde_json::from_slice(blob.content())
+        .with_context(|| format!("The `state.json` of `{}` is invalid", refname))?;
+    if state.version > STATE_VERSION {
+        bail!(
+            "`{}` was written by a newer loom (state version {})\nUpdate loom to read it",
+            refname,
+            state.version
+        );
+    }
+    Ok(state)
+}
+
+fn apply(repo: &Repository, workdir: &Path, state: &WeaveState) -> Result<()> {
+    let branches: Vec<&TrackedBranch> = std::iter::once(&state.integration)
+        .chain(&state.branches)
+        .collect();
+    let mut tips = Vec::new();
+    let mut clashes = Vec::new();
+    for branch in &branches {
+        let tip = Oid::from_str(&branch.tip)?;
+        if let Ok(existing) = repo.find_branch(&branch.name, BranchType::Local)
+            && existing.get().target() != Some(tip)
+        {
+            clashes.push(format!("`{}`", branch.name));
+        }
+        tips.push(tip);
+    }
+    if !clashes.is_empty() {
+        bail!(
+            "{} already {} here with other commits\n\
+             Rename or delete {}, then import again",
+            clashes.join(", "),
+            if clashes.len() == 1 {
+                "exists"
+            } else {
+                "exist"
+            },
+            if clashes.len() == 1 { "it" } else { "them" }
+        );
+    }
+
+    for (branch, tip) in branches.iter().zip(tips) {
+        restore_branch(repo, branch, tip, true)?;
+    }
+    adopt_settings(repo, state)?;
+
+    for name in &state.archived {
+        let archive_ref = format!("{}{}", ARCHIVE_PREFIX, name);
+        if repo.find_reference(&archive_ref).is_err()
+            && let Ok(incoming) = repo.find_reference(&imported(&archive_ref))
+            && let Some(oid) = incoming.target()
+        {
+            repo.reference(&archive_ref, oid, false, "loom: import state")?;
+        }
+    }
+
+    if let Some(notes_ref) = &state.notes_ref {
+        merge_notes(repo, workdir, notes_ref, &imported(notes_ref))?;
+    }
+    Ok(())
+}
+
+/// Create `branch` at `tip` unless it exists, and set its tracking
+/// configuration. Its push remote is only set with `push_remote`: it names
+/// someone's fork, which is personal.
+pub fn restore_branch(
+    repo: &Repository,
+    branch: &TrackedBranch,
+    tip: Oid,
+    push_remote: bool,
+) -> Result<()> {
+    if repo.find_branch(&branch.name, BranchType::Local).is_err() {
+        repo.branch(&branch.name, &repo.find_commit(tip)?, false)?;
+    }
+    let mut config = repo.config()?;
+    for (key, value) in [
+        ("remote", &branch.remote),
+        ("merge", &branch.merge),
+        (
+            "loomPushRemote",
+            &branch.push_remote.clone().filter(|_| push_remote),
+        ),
+    ] {
+        if let Some(value) = value {
+            config.set_str(&format!("branch.{}.{}", branch.name, key), value)?;
+        }
+    }
+    if let Some(remote) = &branch.remote
+        && remote != "."
+        && repo.find_remote(remote).is_err()
+    {
+        msg::warn(&format!(
+            "`{}` tracks remote `{}`, which does not exist here\n\
+             Add it with `git remote add {} <url>`",
+            branch.name, remote, remote
+        ));
+    }
+    Ok(())
+}
+
+/// Set the loom settings of `state` that are not configured yet.
+pub fn adopt_settings(repo: &Repository, state: &WeaveState) -> Result<()> {
+    let mut config = repo.config()?;
+    for (key, value) in [
+        ("loom.base", &state.base),
+        ("loom.hideBranchPattern", &state.hide_branch_pattern),
+    ] {
+        if let Some(value) = value
+            && config.get_string(key).is_err()
+        {
+            config.set_str(key, value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Bring the notes fetched into `incoming` into `notes_ref`: taken as is when
+/// there are no local notes, merged otherwise. A failed merge only warns.
+pub fn merge_notes(
+    repo: &Repository,
+    workdir: &Path,
+    notes_ref: &str,
+    incoming: &str,
+) -> Result<()> {
+    if repo.find_reference(notes_ref).is_err() {
+        let oid = repo
+            .find_reference(incoming)?
+            .target()
+            .with_context(|| format!("`{}` is symbolic", incoming))?;
+        repo.reference(notes_ref, oid, false, "loom: import notes")?;
+        return Ok(());
+    }
+    let merged = git::run_git(
+        workdir,
+        &[
+            "notes",
+            "--ref",
+            notes_ref,
+            "merge",
+            "-q",
+            "-s",
+            "cat_sort_uniq",
+            incoming,
+        ],
+    );
+    if merged.is_err() {
+        msg::warn(&format!(
+            "Could not merge the incoming notes into `{}`",
+            notes_ref
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "state_test.rs"]
+mod tests;
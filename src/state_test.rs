@@ -0,0 +1,118 @@
+use crate::core::test_helpers::TestRepo;
+
+/// Integration branch weaving `feature-a` and `feature-b`, with `feature-b`
+/// pushed to `fork`.
+fn setup() -> TestRepo {
+    let test_repo = TestRepo::new_with_woven_branches();
+    test_repo.set_config("branch.feature-b.loomPushRemote", "fork");
+    test_repo.set_config("loom.hideBranchPattern", "private/");
+    test_repo
+}
+
+#[test]
+fn export_then_import_recreates_the_weave() {
+    let source = setup();
+    let bundle = source.workdir().join("weave.bundle");
+    source
+        .in_dir(|| super::run_export(bundle.to_string_lossy().into_owned()))
+        .unwrap();
+    assert!(source.repo.find_reference(super::STATE_REF).is_err());
+
+    let target = source.clone_remote();
+    target
+        .in_dir(|| super::run_import(bundle.to_string_lossy().into_owned()))
+        .unwrap();
+
+    assert_eq!(
+        target.get_branch_target("integration"),
+        source.get_branch_target("integration")
+    );
+    target.switch_branch("integration");
+    assert_eq!(target.branch_tips(), source.branch_tips());
+    let config = target.repo.config().unwrap().snapshot().unwrap();
+    assert_eq!(
+        config.get_str("branch.integration.merge").unwrap(),
+        "refs/heads/main"
+    );
+    assert_eq!(
+        config.get_str("branch.feature-b.loomPushRemote").unwrap(),
+        "fork"
+    );
+    assert_eq!(
+        config.get_str("loom.hideBranchPattern").unwrap(),
+        "private/"
+    );
+    assert!(
+        target
+            .repo
+            .references_glob("refs/loom/*")
+            .unwrap()
+            .next()
+            .is_none()
+    );
+}
+
+#[test]
+fn import_carries_archived_branches_and_notes() {
+    let source = setup();
+    let tip = source.get_branch_target("feature-a");
+    source
+        .repo
+        .note(
+            &source.repo.signature().unwrap(),
+            &source.repo.signature().unwrap(),
+            None,
+            tip,
+            "reviewed",
+            false,
+        )
+        .unwrap();
+    source
+        .in_dir(|| crate::archive::run(Some("feature-b".to_string()), false, false))
+        .unwrap();
+    let bundle = source.workdir().join("weave.bundle");
+    source
+        .in_dir(|| super::run_export(bundle.to_string_lossy().into_owned()))
+        .unwrap();
+
+    let target = source.clone_remote();
+    target
+        .in_dir(|| super::run_import(bundle.to_string_lossy().into_owned()))
+        .unwrap();
+
+    assert_eq!(
+        crate::archive::archived_branches(&target.repo).unwrap(),
+        crate::archive::archived_branches(&source.repo).unwrap()
+    );
+    assert_eq!(
+        target.repo.find_note(None, tip).unwrap().message(),
+        Some("reviewed")
+    );
+}
+
+#[test]
+fn import_refuses_diverged_branches() {
+    let source = setup();
+    let bundle = source.workdir().join("weave.bundle");
+    source
+        .in_dir(|| super::run_export(bundle.to_string_lossy().into_owned()))
+        .unwrap();
+
+    let target = source.clone_remote();
+    let main = target.head_oid();
+    target.create_branch_at_commit("feature-a", main);
+
+    let err = target
+        .in_dir(|| super::run_import(bundle.to_string_lossy().into_owned()))
+        .unwrap_err();
+    assert!(err.to_string().contains("`feature-a` already exists"));
+    assert!(!target.branch_exists("feature-b"));
+    assert!(
+        target
+            .repo
+            .references_glob("refs/loom/*")
+            .unwrap()
+            .next()
+            .is_none()
+    );
+}
@@ -1,22 +1,76 @@
 use std::collections::{HashMap, HashSet};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+use colored::Colorize;
 
-use crate::core::{graph, repo, shortid};
+use crate::core::{clock, diff, forge, graph, msg, repo, shortid};
+use crate::{check, git, worktree};
 
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     file_filter: Option<Vec<String>>,
     context: usize,
     show_all: bool,
+    paths: Vec<String>,
+    group: bool,
+    stat: bool,
+    checks: bool,
+    detached: bool,
+    diff_filter: Option<DiffFilter>,
+    porcelain: bool,
+    html: Option<PathBuf>,
     theme: graph::Theme,
 ) -> Result<()> {
+    // No working tree is required: a bare repository shows no local changes.
     let repo = repo::open_repo()?;
-    let _ = repo::require_workdir(&repo, "display status")?;
 
     let cwd_prefix = repo::cwd_relative_to_repo(&repo).unwrap_or_default();
-    let opts = graph::default_render_opts(theme, cwd_prefix);
+    let light = theme.light;
+    let mut opts = graph::default_render_opts(theme, cwd_prefix);
+    if html.is_some() {
+        // A page has no width to fit
+        opts.terminal_width = None;
+    }
     let show_files = file_filter.is_some();
-    let mut info = repo::gather_repo_info(&repo, show_files, context)?;
+    // While a rebase is paused at an `edit` stop HEAD is detached: show the
+    // branch being rebased and highlight the commit checked out for editing.
+    let pause = repo::edit_pause(&repo);
+    // Commit file lists are computed as their sections are printed, unless
+    // --path needs all of them upfront to hide the commits outside it.
+    let gather_files = !paths.is_empty();
+    let mut info = match &pause {
+        Some(pause) => repo::gather_branch_info(&repo, &pause.branch_name, gather_files, context)?,
+        None => match detached_integration(&repo, detached)? {
+            Some((branch, head_oid)) => {
+                opts.detached_head = Some(head_oid);
+                repo::gather_branch_info(&repo, &branch, gather_files, context)?
+            }
+            None => repo::gather_repo_info(&repo, gather_files, context)?,
+        },
+    };
+    opts.editing_commit = pause.map(|p| p.commit);
+    opts.worktrees = worktree::status_map(&repo);
+    opts.age_colors = repo::stale_days(&repo).map(graph::AgeColors::new);
+    // Link hashes and branch names to the forge in terminals that can, and
+    // in HTML pages
+    if !info.upstream.local_only
+        && repo::hyperlinks(&repo)
+            .unwrap_or_else(|| html.is_some() || graph::terminal_supports_hyperlinks())
+    {
+        let remote = info.upstream.label.split('/').next().unwrap_or_default();
+        opts.forge = forge::Forge::detect(&repo, remote);
+    }
+    opts.overflow = repo::overflow_mode(&repo)
+        .and_then(|mode| graph::Overflow::parse(&mode))
+        .unwrap_or_default();
+    if show_all {
+        info.working_changes = repo::get_all_working_changes(&repo)?;
+    }
+    opts.hidden_files = repo::hidden_files(&repo)?;
+    opts.hidden_files
+        .retain(|(path, _)| !info.working_changes.iter().any(|c| c.path == *path));
 
     // Collect entities from the full info BEFORE filtering so that short IDs
     // are stable regardless of which branches are hidden.
@@ -30,23 +84,453 @@ pub fn run(
         }
     }
 
-    // When specific commits are requested, clear files from non-matching commits.
-    if let Some(filter_ids) = &file_filter
-        && !filter_ids.is_empty()
-    {
-        let filter_oids = resolve_commit_filter(&repo, filter_ids, &info, &ids);
-        for commit in &mut info.commits {
-            if !filter_oids.contains(&commit.oid) {
+    // With --path, hide commits that touch no matching file. Their file
+    // lists are gathered for the match, and only kept when -f was given.
+    if !paths.is_empty() {
+        opts.hidden_commits = commits_outside_paths(&info, &paths)?;
+    }
+
+    // When specific commits are requested, only those list their files.
+    let filter_oids = match &file_filter {
+        Some(filter_ids) if !filter_ids.is_empty() => {
+            Some(resolve_commit_filter(&repo, filter_ids, &info, &ids))
+        }
+        _ => None,
+    };
+
+    // With --diff-filter, only list the files changed the selected ways.
+    if let Some(filter) = &diff_filter {
+        info.working_changes
+            .retain(|change| filter.matches(&working_change_kinds(change)));
+    }
+
+    // Badge the branches whose commits all landed upstream. Only possible
+    // once upstream moved past the merge-base.
+    if !porcelain && info.upstream.commits_ahead > 0 {
+        opts.merged_upstream = merged_upstream_branches(&repo, &info);
+    }
+
+    // Cluster working changes under the woven branch that last touched them.
+    if group {
+        opts.change_groups = probable_branches(&repo, &info)?;
+    }
+
+    // Badge each branch with the last `loom check` result on its tip.
+    if checks {
+        opts.branch_checks = branch_checks(&repo, &info, clock::now())?;
+    }
+
+    // Tags inside the range are shown, since rewrites must move them along.
+    if !porcelain {
+        opts.tags = repo::tags_by_commit(&repo)?;
+    }
+
+    // Flag branches that are too old or too large to stay in the weave.
+    let health = if porcelain {
+        Vec::new()
+    } else {
+        branch_health(&repo, &info, repo::branch_limits(&repo), clock::now())?
+    };
+    opts.unhealthy_branches = health.iter().map(|(name, _)| name.clone()).collect();
+
+    let mut merged: Vec<&String> = opts.merged_upstream.iter().collect();
+    merged.sort();
+    let mut hints: Vec<String> = merged
+        .into_iter()
+        .map(|name| {
+            format!(
+                "`{}` was merged upstream: run `loom drop {}` to clean it up",
+                name,
+                ids.get_branch(name)
+            )
+        })
+        .collect();
+    hints.extend(
+        health
+            .into_iter()
+            .map(|(name, note)| format!("⚠ `{}` {}", name, note)),
+    );
+    if info.upstream.local_only && info.upstream.label.is_empty() {
+        hints.push(
+            "No upstream tracking branch: showing history since the root commit\n\
+             Run `loom init` to set one up, or set `loom.base` to a local base branch"
+                .to_string(),
+        );
+    }
+
+    // Commit file lists and diffstats are computed section by section, while
+    // the sections above are already printed.
+    let prepare = |commits: &mut [repo::CommitInfo], opts: &mut graph::RenderOpts| {
+        for commit in commits.iter_mut() {
+            if show_files && filter_oids.as_ref().is_none_or(|f| f.contains(&commit.oid)) {
+                if !gather_files {
+                    commit.files = repo::commit_files(&repo, commit.oid)?;
+                }
+            } else {
                 commit.files.clear();
             }
+            if let Some(filter) = &diff_filter {
+                commit.files.retain(|file| filter.matches(&[file.index]));
+            }
+            // Diffstats are only computed for the commits actually displayed.
+            if stat && !opts.hidden_commits.contains(&commit.oid) {
+                let stats = repo::commit_line_stats(&repo, commit.oid)?;
+                opts.commit_stats.insert(commit.oid, stats);
+            }
+        }
+        // Collapse generated files into a single line; `--all` lists them.
+        if show_files && !show_all {
+            opts.collapsed_files.extend(repo::generated_paths(
+                &repo,
+                commits
+                    .iter()
+                    .flat_map(|c| c.files.iter().map(|f| f.path.as_str())),
+            ));
+        }
+        Ok(())
+    };
+
+    if let Some(path) = html {
+        // The page always shows colors, whatever the terminal does
+        let colorize = colored::control::SHOULD_COLORIZE.should_colorize();
+        colored::control::set_override(true);
+        let title = format!("loom status: {}", info.branch_name);
+        let mut rendered = Vec::new();
+        let result = graph::render_streamed(info, &ids, &mut opts, &mut rendered, prepare);
+        let mut output = String::from_utf8_lossy(&rendered).into_owned();
+        for hint in hints {
+            output.push_str(&format!("{}\n", hint.dimmed()));
         }
+        colored::control::set_override(colorize);
+        result?;
+        std::fs::write(&path, graph::to_html(&output, &title, light))
+            .with_context(|| format!("Failed to write `{}`", path.display()))?;
+        msg::success(&format!("Wrote the status to `{}`", path.display()));
+        return Ok(());
     }
 
-    let output = graph::render(info, &ids, &opts);
-    print!("{}", output);
+    if porcelain {
+        print!("{}", graph::render_porcelain(info, &ids));
+    } else {
+        graph::render_streamed(
+            info,
+            &ids,
+            &mut opts,
+            &mut std::io::stdout().lock(),
+            prepare,
+        )?;
+    }
+    for hint in hints {
+        println!("{}", hint.dimmed());
+    }
     Ok(())
 }
 
+/// A `--diff-filter` selection, as in `git diff`: uppercase letters keep the
+/// files changed those ways, lowercase letters drop them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffFilter {
+    include: Vec<char>,
+    exclude: Vec<char>,
+}
+
+impl DiffFilter {
+    /// Parse letters among `A` (added), `D` (deleted), `M` (modified),
+    /// `R` (renamed), and `U` (unmerged).
+    pub fn parse(spec: &str) -> std::result::Result<Self, String> {
+        let mut filter = DiffFilter {
+            include: Vec::new(),
+            exclude: Vec::new(),
+        };
+        for c in spec.chars() {
+            match c {
+                'A' | 'D' | 'M' | 'R' | 'U' => filter.include.push(c),
+                'a' | 'd' | 'm' | 'r' | 'u' => filter.exclude.push(c.to_ascii_uppercase()),
+                _ => {
+                    return Err(format!(
+                        "unknown change type `{}` (expected letters among A, D, M, R, U)",
+                        c
+                    ));
+                }
+            }
+        }
+        if filter.include.is_empty() && filter.exclude.is_empty() {
+            return Err("expected letters among A, D, M, R, U".to_string());
+        }
+        Ok(filter)
+    }
+
+    /// Whether a file changed the `kinds` ways is listed. A file changed
+    /// several ways (staged and unstaged) is listed if any kept way applies.
+    pub fn matches(&self, kinds: &[char]) -> bool {
+        kinds.iter().any(|kind| {
+            !self.exclude.contains(kind) && (self.include.is_empty() || self.include.contains(kind))
+        })
+    }
+}
+
+/// The `--diff-filter` letters of a working change: untracked files count as
+/// added, conflicts as unmerged.
+fn working_change_kinds(change: &repo::FileChange) -> Vec<char> {
+    if change.is_conflicted() {
+        return vec!['U'];
+    }
+    [change.index, change.worktree]
+        .into_iter()
+        .filter_map(|c| match c {
+            '?' => Some('A'),
+            ' ' => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+/// Handle a detached HEAD before gathering. With `detached`, returns the
+/// nearest integration branch containing HEAD (and HEAD's OID) for a
+/// read-only view. Otherwise, on a terminal, offers to switch back to that
+/// branch. Returns None when status should proceed from HEAD as usual, which
+/// reports the detached HEAD if it is still detached.
+fn detached_integration(
+    repo: &git2::Repository,
+    detached: bool,
+) -> Result<Option<(String, git2::Oid)>> {
+    let head = repo.head()?;
+    if head.is_branch() {
+        return Ok(None);
+    }
+    let head_oid = head.target().context("HEAD does not point to a commit")?;
+    let Some(branch) = repo::integration_branches_containing(repo, head_oid)?
+        .into_iter()
+        .next()
+    else {
+        if detached {
+            bail!("HEAD is detached outside of any integration branch");
+        }
+        return Ok(None);
+    };
+
+    if detached {
+        msg::warn(&format!(
+            "HEAD is detached — showing `{}` read-only\nSwitch back with `git switch {}`",
+            branch, branch
+        ));
+        return Ok(Some((branch, head_oid)));
+    }
+
+    if std::io::stdin().is_terminal()
+        && std::io::stdout().is_terminal()
+        && !msg::no_input()
+        && msg::confirm(&format!(
+            "HEAD is detached inside `{}`. Switch back to it?",
+            branch
+        ))?
+    {
+        let workdir = repo::require_workdir(repo, "switch back")?;
+        git::branch_switch(workdir, &branch)?;
+        msg::success(&format!("Switched to `{}`", branch));
+    }
+    Ok(None)
+}
+
+/// Map each modified working file to the woven branch it most likely belongs
+/// to, for `status --group`. New, untracked, and conflicted files are left out,
+/// as are files no visible branch has touched.
+fn probable_branches(
+    repo: &git2::Repository,
+    info: &repo::RepoInfo,
+) -> Result<HashMap<String, String>> {
+    let workdir = repo::require_workdir(repo, "group changes")?;
+    let owners = graph::assign_commits_to_branches(info);
+    let mut groups = HashMap::new();
+    for change in &info.working_changes {
+        if change.is_conflicted() || matches!(change.index, 'A' | '?') {
+            continue;
+        }
+        if let Some(branch) = probable_branch(repo, workdir, &change.path, info, &owners)? {
+            groups.insert(change.path.clone(), branch);
+        }
+    }
+    Ok(groups)
+}
+
+/// The branch owning most of the lines `path` modifies, according to blame.
+/// Changes that only add lines fall back to the branch of the newest commit
+/// that touched the file.
+fn probable_branch(
+    repo: &git2::Repository,
+    workdir: &Path,
+    path: &str,
+    info: &repo::RepoInfo,
+    owners: &HashMap<git2::Oid, String>,
+) -> Result<Option<String>> {
+    let mut votes: HashMap<&String, usize> = HashMap::new();
+    if let Ok(blame) = repo.blame_file(Path::new(path), None) {
+        let diff = git::diff_head_file(workdir, path)?;
+        for hunk in diff::parse_hunks(&diff) {
+            for line in hunk.modified_lines {
+                if let Some(blame_hunk) = blame.get_line(line)
+                    && let Some(branch) = owners.get(&blame_hunk.final_commit_id())
+                {
+                    *votes.entry(branch).or_default() += 1;
+                }
+            }
+        }
+    }
+    // Most votes wins; ties go to the alphabetically first branch.
+    if let Some((branch, _)) = votes
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+    {
+        return Ok(Some(branch.clone()));
+    }
+
+    for commit in &info.commits {
+        if let Some(branch) = owners.get(&commit.oid)
+            && repo::commit_file_paths(repo, commit.oid)?
+                .iter()
+                .any(|f| f == path)
+        {
+            return Ok(Some(branch.clone()));
+        }
+    }
+    Ok(None)
+}
+
+/// Woven branches whose every commit has an equivalent upstream, by
+/// patch-id (`git cherry`): typically a pull request merged with a rebase
+/// or a cherry-pick. The badge is a hint: when `git cherry` fails, no branch
+/// is badged and status still renders.
+fn merged_upstream_branches(repo: &git2::Repository, info: &repo::RepoInfo) -> HashSet<String> {
+    let dir = repo.workdir().unwrap_or(repo.path());
+    let base = info.upstream.merge_base_oid.to_string();
+    let head = format!("refs/heads/{}", info.branch_name);
+    let cherry = git::run_git_stdout(dir, &["cherry", &info.upstream.label, &head, &base])
+        .unwrap_or_default();
+    let mut upstream_equivalent = HashMap::new();
+    for line in cherry.lines() {
+        if let Some((mark, hash)) = line.split_once(' ')
+            && let Ok(oid) = git2::Oid::from_str(hash.trim())
+        {
+            upstream_equivalent.insert(oid, mark == "-");
+        }
+    }
+
+    let owners = graph::assign_commits_to_branches(info);
+    let mut verdicts: HashMap<&String, bool> = HashMap::new();
+    for (oid, branch) in &owners {
+        if let Some(&merged) = upstream_equivalent.get(oid) {
+            let verdict = verdicts.entry(branch).or_insert(true);
+            *verdict &= merged;
+        }
+    }
+    // Co-located branches share the commits of the one that owns them.
+    let merged_tips: HashSet<git2::Oid> = info
+        .branches
+        .iter()
+        .filter(|b| verdicts.get(&b.name) == Some(&true))
+        .map(|b| b.tip_oid)
+        .collect();
+    info.branches
+        .iter()
+        .filter(|b| merged_tips.contains(&b.tip_oid))
+        .map(|b| b.name.clone())
+        .collect()
+}
+
+/// Woven branches past `limits`, with a note on why, sorted by name: their
+/// oldest commit is older than the age limit, or their commits together
+/// change more lines than the size limit. Co-located branches share the
+/// verdict of the commits they own.
+/// The combined `loom check` result on the tip of each branch of `info`, and
+/// its age at `now`, read from the check cache.
+fn branch_checks(
+    repo: &git2::Repository,
+    info: &repo::RepoInfo,
+    now: i64,
+) -> Result<HashMap<String, (graph::CheckState, Option<i64>)>> {
+    let cache = check::Cache::load(repo);
+    let checks = repo::checks(repo);
+    let mut states = HashMap::new();
+    for branch in &info.branches {
+        let tree = repo.find_commit(branch.tip_oid)?.tree_id();
+        let (state, time) = cache.summarize(tree, &checks);
+        states.insert(branch.name.clone(), (state, time.map(|t| now - t)));
+    }
+    Ok(states)
+}
+
+fn branch_health(
+    repo: &git2::Repository,
+    info: &repo::RepoInfo,
+    limits: repo::BranchLimits,
+    now: i64,
+) -> Result<Vec<(String, String)>> {
+    let owners = graph::assign_commits_to_branches(info);
+    let mut owned: HashMap<&String, Vec<&repo::CommitInfo>> = HashMap::new();
+    for commit in &info.commits {
+        if let Some(branch) = owners.get(&commit.oid) {
+            owned.entry(branch).or_default().push(commit);
+        }
+    }
+
+    let mut notes: HashMap<git2::Oid, String> = HashMap::new();
+    for commits in owned.into_values() {
+        let mut reasons = Vec::new();
+        let oldest = commits.iter().map(|c| c.time).min().unwrap_or(now);
+        let age_days = (now - oldest) / (24 * 60 * 60);
+        if limits.max_age_days.is_some_and(|max| age_days > max) {
+            reasons.push(format!("is {} days old: consider landing it", age_days));
+        }
+        if let Some(max) = limits.max_lines {
+            // The branch's commits are a chain: diff its base against its tip.
+            let ids: HashSet<git2::Oid> = commits.iter().map(|c| c.oid).collect();
+            let tip = commits[0].oid;
+            let base = commits
+                .iter()
+                .find(|c| c.parent_oid.is_none_or(|p| !ids.contains(&p)))
+                .and_then(|c| c.parent_oid);
+            let base_tree = base.map(|b| repo.find_commit(b)?.tree()).transpose()?;
+            let tip_tree = repo.find_commit(tip)?.tree()?;
+            let stats = repo
+                .diff_tree_to_tree(base_tree.as_ref(), Some(&tip_tree), None)?
+                .stats()?;
+            let lines = stats.insertions() + stats.deletions();
+            if lines > max {
+                reasons.push(format!("changes {} lines: consider splitting it", lines));
+            }
+        }
+        if !reasons.is_empty() {
+            notes.insert(commits[0].oid, reasons.join(", "));
+        }
+    }
+
+    let mut health: Vec<(String, String)> = info
+        .branches
+        .iter()
+        .filter_map(|b| notes.get(&b.tip_oid).map(|n| (b.name.clone(), n.clone())))
+        .collect();
+    health.sort();
+    Ok(health)
+}
+
+/// OIDs of the commits in `info` that change no file matching `paths`
+/// (git pathspecs, e.g. `src/` or `*.rs`). Requires the commit file lists.
+fn commits_outside_paths(info: &repo::RepoInfo, paths: &[String]) -> Result<HashSet<git2::Oid>> {
+    let pathspec = git2::Pathspec::new(paths.iter())
+        .with_context(|| format!("Invalid path pattern: {}", paths.join(" ")))?;
+    Ok(info
+        .commits
+        .iter()
+        .filter(|commit| {
+            !commit
+                .files
+                .iter()
+                .any(|f| pathspec.matches_path(Path::new(&f.path), git2::PathspecFlags::DEFAULT))
+        })
+        .map(|commit| commit.oid)
+        .collect())
+}
+
 /// OID of the commit shown at the top of `loom status`: the tip of the
 /// integration line, skipping merge commits and hidden branches. Returns None
 /// when the integration branch has no commits of its own above the merge-base.
@@ -2,7 +2,10 @@ use crate::core::repo::gather_repo_info;
 use crate::core::shortid::IdAllocator;
 use crate::core::test_helpers::TestRepo;
 
-use super::{hide_branches, resolve_commit_filter};
+use super::{
+    DiffFilter, branch_health, commits_outside_paths, detached_integration, hide_branches,
+    merged_upstream_branches, probable_branches, resolve_commit_filter, working_change_kinds,
+};
 
 #[test]
 fn hidden_branch_removed_from_branches() {
@@ -215,3 +218,209 @@ fn filter_unknown_id_silently_skipped() {
     );
     assert!(filter.is_empty());
 }
+
+// ── commits_outside_paths tests ─────────────────────────────────────────────
+
+#[test]
+fn path_filter_hides_commits_without_matching_files() {
+    let test_repo = TestRepo::new_with_remote();
+    std::fs::create_dir_all(test_repo.workdir().join("docs")).unwrap();
+    std::fs::create_dir_all(test_repo.workdir().join("src")).unwrap();
+    let docs_oid = test_repo.commit("Docs", "docs/guide.md");
+    let src_oid = test_repo.commit("Code", "src/lib.rs");
+    let readme_oid = test_repo.commit("Readme", "README.md");
+
+    let info = gather_repo_info(&test_repo.repo, true, 1).unwrap();
+
+    let hidden = commits_outside_paths(&info, &["src/*.rs".to_string()]).unwrap();
+    assert_eq!(hidden, [docs_oid, readme_oid].into());
+
+    // Directory prefixes and several patterns are accepted
+    let hidden = commits_outside_paths(&info, &["docs".to_string(), "*.md".to_string()]).unwrap();
+    assert_eq!(hidden, [src_oid].into());
+}
+
+#[test]
+fn diff_filter_parses_included_and_excluded_letters() {
+    let filter = DiffFilter::parse("AD").unwrap();
+    assert!(filter.matches(&['A']));
+    assert!(filter.matches(&['M', 'D']));
+    assert!(!filter.matches(&['M']));
+
+    let filter = DiffFilter::parse("d").unwrap();
+    assert!(filter.matches(&['M']));
+    assert!(!filter.matches(&['D']));
+
+    assert!(DiffFilter::parse("X").is_err());
+    assert!(DiffFilter::parse("").is_err());
+}
+
+#[test]
+fn diff_filter_selects_working_changes_by_kind() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit("Tracked", "tracked.txt");
+    test_repo.commit("Doomed", "doomed.txt");
+    test_repo.write_file("tracked.txt", "changed");
+    std::fs::remove_file(test_repo.workdir().join("doomed.txt")).unwrap();
+    test_repo.write_file("new.txt", "new");
+
+    let info = gather_repo_info(&test_repo.repo, false, 1).unwrap();
+    let select = |spec: &str| -> Vec<String> {
+        let filter = DiffFilter::parse(spec).unwrap();
+        let mut paths: Vec<String> = info
+            .working_changes
+            .iter()
+            .filter(|c| filter.matches(&working_change_kinds(c)))
+            .map(|c| c.path.clone())
+            .collect();
+        paths.sort();
+        paths
+    };
+    assert_eq!(select("D"), ["doomed.txt"]);
+    assert_eq!(select("A"), ["new.txt"]);
+    assert_eq!(select("a"), ["doomed.txt", "tracked.txt"]);
+}
+
+// ── probable_branches tests ─────────────────────────────────────────────────
+
+#[test]
+fn working_changes_grouped_by_blamed_branch() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("line one\nline two\n", "a.txt");
+    test_repo.create_branch_at("feature-b", &base_oid.to_string());
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1", "b.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-a");
+    test_repo.merge_no_ff("feature-b");
+
+    // Modify a line from feature-a, and only append to feature-b's file
+    test_repo.write_file("a.txt", "line one\nline 2\n");
+    test_repo.write_file("b.txt", "B1\nmore\n");
+    test_repo.write_file("new.txt", "untracked");
+
+    let info = gather_repo_info(&test_repo.repo, false, 1).unwrap();
+    let groups = probable_branches(&test_repo.repo, &info).unwrap();
+
+    assert_eq!(groups.get("a.txt").map(String::as_str), Some("feature-a"));
+    assert_eq!(groups.get("b.txt").map(String::as_str), Some("feature-b"));
+    assert!(!groups.contains_key("new.txt"));
+}
+
+// ── detached_integration tests ──────────────────────────────────────────────
+
+#[test]
+fn detached_view_uses_containing_integration_branch() {
+    let test_repo = TestRepo::new_with_remote();
+    let c1 = test_repo.commit("C1", "c1.txt");
+    test_repo.commit("C2", "c2.txt");
+
+    // On a branch there is nothing to recover
+    assert_eq!(detached_integration(&test_repo.repo, true).unwrap(), None);
+
+    test_repo.set_detached_head(c1);
+    assert_eq!(
+        detached_integration(&test_repo.repo, true).unwrap(),
+        Some(("integration".to_string(), c1))
+    );
+    // Without --detached (and no terminal) status falls through to the error
+    assert_eq!(detached_integration(&test_repo.repo, false).unwrap(), None);
+}
+
+#[test]
+fn branch_merged_upstream_detected_by_patch_id() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    for name in ["feature-a", "feature-b"] {
+        test_repo.create_branch_at(name, &base_oid.to_string());
+    }
+    test_repo.switch_branch("feature-a");
+    let a1 = test_repo.commit("A1", "a1.txt");
+    let a2 = test_repo.commit("A2", "a2.txt");
+    test_repo.switch_branch("feature-b");
+    let b1 = test_repo.commit("B1", "b1.txt");
+    test_repo.commit("B2", "b2.txt");
+    test_repo.switch_branch("integration");
+    test_repo.commit("Int", "int.txt");
+    test_repo.merge_no_ff("feature-a");
+    test_repo.merge_no_ff("feature-b");
+
+    // feature-a fully landed upstream, feature-b only partially.
+    test_repo.cherry_pick_to_remote(a1, "A1");
+    test_repo.cherry_pick_to_remote(a2, "A2 (#12)");
+    test_repo.cherry_pick_to_remote(b1, "B1");
+    test_repo.fetch_remote();
+
+    let info = gather_repo_info(&test_repo.repo, false, 1).unwrap();
+    assert_eq!(info.branches.len(), 2);
+    let merged = merged_upstream_branches(&test_repo.repo, &info);
+    assert_eq!(merged.into_iter().collect::<Vec<_>>(), vec!["feature-a"]);
+
+    // A failing `git cherry` leaves the branches unbadged
+    let mut info = info;
+    info.upstream.label = "origin/missing".to_string();
+    assert!(merged_upstream_branches(&test_repo.repo, &info).is_empty());
+}
+
+#[test]
+fn branch_health_flags_old_and_large_branches() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    for name in ["feature-a", "feature-b", "feature-c"] {
+        test_repo.create_branch_at(name, &base_oid.to_string());
+    }
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a1.txt");
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1", "b1.txt");
+    test_repo.switch_branch("feature-c");
+    test_repo.write_file("big.txt", &"line\n".repeat(30));
+    test_repo.stage_files(&["big.txt"]);
+    test_repo.commit_staged("C1");
+    test_repo.switch_branch("integration");
+    for name in ["feature-a", "feature-b", "feature-c"] {
+        test_repo.merge_no_ff(name);
+    }
+    let info = gather_repo_info(&test_repo.repo, false, 1).unwrap();
+    let limits = crate::core::repo::BranchLimits {
+        max_age_days: Some(60),
+        max_lines: Some(20),
+    };
+    let feature_b_time = info
+        .commits
+        .iter()
+        .find(|c| c.message == "B1")
+        .unwrap()
+        .time;
+
+    // 90 days after feature-b's commit: every branch is old, feature-c large.
+    let now = feature_b_time + 90 * 24 * 60 * 60;
+    let health = branch_health(&test_repo.repo, &info, limits, now).unwrap();
+
+    let names: Vec<&str> = health.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, vec!["feature-a", "feature-b", "feature-c"]);
+    assert_eq!(health[1].1, "is 90 days old: consider landing it");
+    assert!(health[2].1.contains("changes 30 lines"), "{}", health[2].1);
+
+    // Today, only feature-c is past a limit.
+    let health = branch_health(&test_repo.repo, &info, limits, feature_b_time).unwrap();
+    let names: Vec<&str> = health.iter().map(|(n, _)| n.as_str()).collect();
+    assert_eq!(names, vec!["feature-c"]);
+}
+
+#[test]
+fn branch_limits_read_config() {
+    let test_repo = TestRepo::new_with_remote();
+    let limits = crate::core::repo::branch_limits(&test_repo.repo);
+    assert_eq!(limits.max_age_days, Some(60));
+    assert_eq!(limits.max_lines, Some(1000));
+
+    test_repo.set_config("loom.branchMaxAgeDays", "0");
+    test_repo.set_config("loom.branchMaxLines", "400");
+    let limits = crate::core::repo::branch_limits(&test_repo.repo);
+    assert_eq!(limits.max_age_days, None);
+    assert_eq!(limits.max_lines, Some(400));
+}
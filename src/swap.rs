@@ -44,6 +44,7 @@ fn swap_two_commits(repo: &Repository, hash_a: String, hash_b: String) -> Result
 
     let state = LoomState {
         command: "swap".to_string(),
+        refs_before: Default::default(),
         rollback: Rollback::default(),
         context: serde_json::to_value(&SwapContext {
             display_a: display_a.to_string(),
@@ -52,7 +53,7 @@ fn swap_two_commits(repo: &Repository, hash_a: String, hash_b: String) -> Result
     };
     transaction::save(&git_dir, &state)?;
 
-    let todo = graph.to_todo();
+    let todo = graph.checked_todo()?;
     match weave::run_rebase(workdir, Some(&graph.base_oid.to_string()), &todo)? {
         RebaseOutcome::Completed => {
             transaction::delete(&git_dir)?;
@@ -33,7 +33,7 @@ pub fn run(branch: Option<String>) -> Result<()> {
 }
 
 /// Fail if the working tree has staged or unstaged changes to tracked files.
-fn check_clean(repo: &Repository) -> Result<()> {
+pub(crate) fn check_clean(repo: &Repository) -> Result<()> {
     let mut opts = StatusOptions::new();
     opts.include_untracked(false);
     let statuses = repo.statuses(Some(&mut opts))?;
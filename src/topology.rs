@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::core::repo;
+use crate::core::weave::{CommitEntry, IntegrationEntry, Weave};
+use crate::git;
+
+/// Text format of `loom graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Graphviz DOT.
+    Dot,
+    /// Mermaid flowchart, rendered by most forges in Markdown.
+    Mermaid,
+}
+
+/// What a node of the topology stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// The base of the weave, or a commit outside it a section starts from.
+    Base,
+    Commit,
+    /// A merge of a branch into the integration branch.
+    Merge,
+    /// The tip of the integration branch.
+    Head,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Node {
+    pub id: String,
+    pub label: String,
+    pub kind: NodeKind,
+}
+
+/// The commits of one branch, drawn as a box around them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cluster {
+    /// The branches at the tip of the commits.
+    pub title: String,
+    /// Indexes in [`Topology::nodes`].
+    pub nodes: Vec<usize>,
+}
+
+/// The weave as a graph: commits and merges, grouped per branch, with edges
+/// from each commit to its children.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Topology {
+    pub nodes: Vec<Node>,
+    pub clusters: Vec<Cluster>,
+    pub edges: Vec<(String, String)>,
+}
+
+/// Print the weave topology of the integration branch as DOT or Mermaid.
+pub fn run(format: Format) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let info = repo::gather_repo_info(&repo, false, 1)?;
+    let weave = Weave::from_repo_with_info(&repo, &info)?;
+    let base = format!(
+        "{} {}",
+        info.upstream.label,
+        git::short_hash(&weave.base_oid.to_string())
+    );
+    let topology = Topology::new(&weave, base.trim(), &info.branch_name);
+    match format {
+        Format::Dot => print!("{}", topology.to_dot()),
+        Format::Mermaid => print!("{}", topology.to_mermaid()),
+    }
+    Ok(())
+}
+
+impl Topology {
+    /// Build the graph of `weave`, labeling its base `base` and its tip
+    /// `head`.
+    ///
+    /// A section is split into one cluster per branch, so branches stacked
+    /// inside it get their own box, above the branch they build on.
+    pub fn new(weave: &Weave, base: &str, head: &str) -> Topology {
+        let mut topology = Topology {
+            nodes: Vec::new(),
+            clusters: Vec::new(),
+            edges: Vec::new(),
+        };
+        topology.add_node("base", base.to_string(), NodeKind::Base);
+
+        let mut tips: HashMap<&str, String> = HashMap::new();
+        for section in &weave.branch_sections {
+            let mut parent = match tips.get(section.reset_target.as_str()) {
+                Some(tip) => tip.clone(),
+                None if section.reset_target == "onto" => "base".to_string(),
+                None => {
+                    // A section `loom update --only` left on an older base
+                    let id = format!("c{}", git::short_hash(&section.reset_target));
+                    let label = git::short_hash(&section.reset_target).to_string();
+                    topology.add_node(&id, label, NodeKind::Base);
+                    id
+                }
+            };
+            let mut cluster = Vec::new();
+            for (i, commit) in section.commits.iter().enumerate() {
+                let id = commit_id(commit);
+                let index = topology.add_node(&id, commit_label(commit, false), NodeKind::Commit);
+                topology.edges.push((parent, id.clone()));
+                parent = id;
+                cluster.push(index);
+                let last = i + 1 == section.commits.len();
+                if !commit.update_refs.is_empty() && !last {
+                    topology.clusters.push(Cluster {
+                        title: commit.update_refs.join(", "),
+                        nodes: std::mem::take(&mut cluster),
+                    });
+                }
+            }
+            let mut names = section.commits.last().map_or(Vec::new(), |tip| {
+                tip.update_refs.iter().map(String::as_str).collect()
+            });
+            names.extend(section.branch_names.iter().map(String::as_str));
+            if !cluster.is_empty() {
+                topology.clusters.push(Cluster {
+                    title: names.join(", "),
+                    nodes: cluster,
+                });
+            }
+            tips.insert(&section.label, parent);
+        }
+
+        let mut parent = "base".to_string();
+        for (i, entry) in weave.integration_line.iter().enumerate() {
+            let id = match entry {
+                IntegrationEntry::Pick(commit) => {
+                    let id = commit_id(commit);
+                    topology.add_node(&id, commit_label(commit, true), NodeKind::Commit);
+                    id
+                }
+                IntegrationEntry::Merge { label, .. } => {
+                    let id = format!("m{}", i);
+                    topology.add_node(&id, format!("Merge {}", label), NodeKind::Merge);
+                    if let Some(tip) = tips.get(label.as_str()) {
+                        topology.edges.push((tip.clone(), id.clone()));
+                    }
+                    id
+                }
+            };
+            topology.edges.push((parent, id.clone()));
+            parent = id;
+        }
+        topology.add_node("head", head.to_string(), NodeKind::Head);
+        topology.edges.push((parent, "head".to_string()));
+        topology
+    }
+
+    fn add_node(&mut self, id: &str, label: String, kind: NodeKind) -> usize {
+        self.nodes.push(Node {
+            id: id.to_string(),
+            label,
+            kind,
+        });
+        self.nodes.len() - 1
+    }
+
+    /// The graph in Graphviz DOT, newest commits at the top like
+    /// `loom status`.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph weave {\n");
+        out.push_str("    rankdir=BT;\n");
+        out.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+        let clustered: Vec<usize> = self.clusters.iter().flat_map(|c| c.nodes.clone()).collect();
+        for (i, cluster) in self.clusters.iter().enumerate() {
+            out.push_str(&format!("    subgraph cluster_{} {{\n", i));
+            out.push_str(&format!(
+                "        label=\"{}\";\n",
+                dot_escape(&cluster.title)
+            ));
+            for &index in &cluster.nodes {
+                out.push_str(&format!("        {}\n", dot_node(&self.nodes[index])));
+            }
+            out.push_str("    }\n");
+        }
+        for (index, node) in self.nodes.iter().enumerate() {
+            if !clustered.contains(&index) {
+                out.push_str(&format!("    {}\n", dot_node(node)));
+            }
+        }
+        for (from, to) in &self.edges {
+            out.push_str(&format!("    \"{}\" -> \"{}\";\n", from, to));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// The graph as a Mermaid flowchart, newest commits at the top like
+    /// `loom status`.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart BT\n");
+        let clustered: Vec<usize> = self.clusters.iter().flat_map(|c| c.nodes.clone()).collect();
+        for (i, cluster) in self.clusters.iter().enumerate() {
+            out.push_str(&format!(
+                "    subgraph s{} [\"{}\"]\n",
+                i,
+                mermaid_escape(&cluster.title)
+            ));
+            for &index in &cluster.nodes {
+                out.push_str(&format!("        {}\n", mermaid_node(&self.nodes[index])));
+            }
+            out.push_str("    end\n");
+        }
+        for (index, node) in self.nodes.iter().enumerate() {
+            if !clustered.contains(&index) {
+                out.push_str(&format!("    {}\n", mermaid_node(node)));
+            }
+        }
+        for (from, to) in &self.edges {
+            out.push_str(&format!("    {} --> {}\n", from, to));
+        }
+        out
+    }
+}
+
+fn commit_id(commit: &CommitEntry) -> String {
+    format!("c{}", commit.short_hash)
+}
+
+/// `<hash> <subject>`, with the branches at the commit when they are not
+/// shown as a cluster (`loose`).
+fn commit_label(commit: &CommitEntry, loose: bool) -> String {
+    let mut label = format!("{} {}", commit.short_hash, commit.message);
+    if loose && !commit.update_refs.is_empty() {
+        label.push_str(&format!(" ({})", commit.update_refs.join(", ")));
+    }
+    label
+}
+
+fn dot_node(node: &Node) -> String {
+    let shape = match node.kind {
+        NodeKind::Base => ", shape=house",
+        NodeKind::Commit => "",
+        NodeKind::Merge => ", shape=diamond",
+        NodeKind::Head => ", shape=ellipse, style=bold",
+    };
+    format!(
+        "\"{}\" [label=\"{}\"{}];",
+        node.id,
+        dot_escape(&node.label),
+        shape
+    )
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn mermaid_node(node: &Node) -> String {
+    let label = mermaid_escape(&node.label);
+    match node.kind {
+        NodeKind::Base => format!("{}[(\"{}\")]", node.id, label),
+        NodeKind::Commit => format!("{}[\"{}\"]", node.id, label),
+        NodeKind::Merge => format!("{}{{{{\"{}\"}}}}", node.id, label),
+        NodeKind::Head => format!("{}([\"{}\"])", node.id, label),
+    }
+}
+
+/// Mermaid labels are HTML: quotes and angle brackets become entities.
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+#[cfg(test)]
+#[path = "topology_test.rs"]
+mod tests;
@@ -0,0 +1,106 @@
+use crate::core::test_helpers::TestRepo;
+use crate::core::weave::Weave;
+
+use super::{NodeKind, Topology};
+
+/// Integration branch with a loose commit, `feature-b` stacked on
+/// `feature-a`, and an independent `feature-c`.
+fn setup() -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.commit("Loose", "loose.txt");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a.txt");
+    test_repo.create_branch("feature-b");
+    test_repo.switch_branch("feature-b");
+    test_repo.commit("B1 \"quoted\" <b>", "b.txt");
+    test_repo.create_branch_at("feature-c", &base_oid.to_string());
+    test_repo.switch_branch("feature-c");
+    test_repo.commit("C1", "c.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-b");
+    test_repo.merge_no_ff("feature-c");
+    test_repo
+}
+
+fn topology(test_repo: &TestRepo) -> Topology {
+    let weave = Weave::from_repo(&test_repo.repo).unwrap();
+    Topology::new(&weave, "origin/main", "integration")
+}
+
+/// The id of the node whose label mentions `subject` (hashes are lowercase,
+/// so capitalized subjects never match one).
+fn node_id(topology: &Topology, subject: &str) -> String {
+    topology
+        .nodes
+        .iter()
+        .find(|n| n.label.contains(subject))
+        .unwrap_or_else(|| panic!("no node `{}`", subject))
+        .id
+        .clone()
+}
+
+#[test]
+fn stacked_branches_get_their_own_cluster() {
+    let test_repo = setup();
+    let topology = topology(&test_repo);
+
+    let titles: Vec<&str> = topology.clusters.iter().map(|c| c.title.as_str()).collect();
+    assert_eq!(titles, vec!["feature-a", "feature-b", "feature-c"]);
+
+    let a1 = node_id(&topology, "A1");
+    let b1 = node_id(&topology, "B1");
+    let c1 = node_id(&topology, "C1");
+    let edge = |from: &str, to: &str| topology.edges.contains(&(from.to_string(), to.to_string()));
+    assert!(edge("base", &a1));
+    assert!(edge(&a1, &b1), "feature-b builds on feature-a");
+    assert!(edge("base", &c1));
+
+    let merges: Vec<&str> = topology
+        .nodes
+        .iter()
+        .filter(|n| n.kind == NodeKind::Merge)
+        .map(|n| n.label.as_str())
+        .collect();
+    assert_eq!(merges, vec!["Merge feature-b", "Merge feature-c"]);
+    let loose = node_id(&topology, "Loose");
+    assert!(edge("base", &loose));
+    assert!(topology.edges.iter().any(|(_, to)| to == "head"));
+}
+
+#[test]
+fn dot_output_escapes_labels() {
+    let test_repo = setup();
+    let dot = topology(&test_repo).to_dot();
+
+    assert!(dot.starts_with("digraph weave {\n    rankdir=BT;\n"));
+    assert!(dot.contains("subgraph cluster_0 {\n        label=\"feature-a\";"));
+    assert!(dot.contains("B1 \\\"quoted\\\" <b>"));
+    assert!(dot.contains("\"head\" [label=\"integration\", shape=ellipse, style=bold];"));
+    assert!(dot.ends_with("}\n"));
+}
+
+#[test]
+fn mermaid_output_escapes_labels() {
+    let test_repo = setup();
+    let mermaid = topology(&test_repo).to_mermaid();
+
+    assert!(mermaid.starts_with("flowchart BT\n"));
+    assert!(mermaid.contains("    subgraph s1 [\"feature-b\"]\n"));
+    assert!(mermaid.contains("B1 #quot;quoted#quot; #lt;b#gt;"));
+    assert!(mermaid.contains("    base[(\"origin/main\")]\n"));
+    assert!(mermaid.contains("{{\"Merge feature-c\"}}"));
+}
+
+#[test]
+fn empty_weave_links_base_to_head() {
+    let test_repo = TestRepo::new_with_remote();
+    let topology = topology(&test_repo);
+
+    assert!(topology.clusters.is_empty());
+    assert_eq!(
+        topology.edges,
+        vec![("base".to_string(), "head".to_string())]
+    );
+}
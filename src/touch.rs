@@ -0,0 +1,101 @@
+use anyhow::{Result, bail};
+use git2::{Oid, Repository};
+
+use crate::core::msg;
+use crate::core::repo::{self, TargetKind};
+use crate::core::weave::{self, Weave};
+use crate::git;
+
+/// Options for `loom touch`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TouchOpts {
+    /// Make the current identity the author of every commit, dated now.
+    pub reset_author: bool,
+    /// GPG-sign the recreated commits.
+    pub sign: bool,
+}
+
+/// Recreate the commits of a woven branch with the same content and message,
+/// giving them a fresh committer date (and optionally a new author or a
+/// signature).
+///
+/// Only the branch's own commits are recreated: sections before it keep
+/// their OIDs, and the commits after it are replayed as any rebase would.
+pub fn run(branch: String, opts: TouchOpts) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let name = repo::resolve_arg(&repo, &branch, &[TargetKind::Branch])?.expect_branch()?;
+    let count = touch(&repo, &name, opts)?;
+    let new_hash = git::rev_parse(repo::require_workdir(&repo, "touch a branch")?, &name)?;
+    msg::success(&format!(
+        "Recreated {} {} of `{}` (now `{}`)",
+        count,
+        if count == 1 { "commit" } else { "commits" },
+        name,
+        git::short_hash(&new_hash)
+    ));
+    Ok(())
+}
+
+/// Recreate the commits of `branch` and weave them back in. Returns the
+/// number of commits recreated.
+pub fn touch(repo: &Repository, branch: &str, opts: TouchOpts) -> Result<usize> {
+    let workdir = repo::require_workdir(repo, "touch a branch")?;
+    let mut graph = Weave::from_repo(repo)?;
+    if repo.revparse_single(branch)?.peel_to_commit()?.id() == graph.base_oid {
+        bail!("Branch `{}` has no commits yet", branch);
+    }
+    let commits = branch_commits(&graph, branch)?;
+
+    let mut parent = repo.find_commit(commits[0])?.parent_id(0)?;
+    for &oid in &commits {
+        let commit = repo.find_commit(oid)?;
+        let author = commit.author();
+        let new_oid = git::commit_tree(
+            workdir,
+            &commit.tree_id().to_string(),
+            &parent.to_string(),
+            commit.message_raw().unwrap_or(""),
+            (!opts.reset_author).then_some(&author),
+            opts.sign,
+        )?;
+        parent = Oid::from_str(&new_oid)?;
+        repo::copy_notes(repo, oid, parent)?;
+        graph.replace_commit(oid, parent);
+    }
+
+    weave::run_rebase_or_abort(
+        workdir,
+        Some(&graph.base_oid.to_string()),
+        &graph.checked_todo()?,
+    )?;
+    Ok(commits.len())
+}
+
+/// The commits of a woven branch, oldest first, excluding those of the
+/// branches it is stacked on.
+fn branch_commits(graph: &Weave, name: &str) -> Result<Vec<Oid>> {
+    for section in &graph.branch_sections {
+        let mut start = 0;
+        for (i, commit) in section.commits.iter().enumerate() {
+            if commit.update_refs.iter().any(|r| r == name) {
+                return Ok(section.commits[start..=i].iter().map(|c| c.oid).collect());
+            }
+            if !commit.update_refs.is_empty() {
+                start = i + 1;
+            }
+        }
+        if section.branch_names.iter().any(|b| b == name) && start < section.commits.len() {
+            return Ok(section.commits[start..].iter().map(|c| c.oid).collect());
+        }
+    }
+    bail!(
+        "Branch `{}` is not woven into the integration branch\n\
+         Run `loom branch merge {}` to weave it",
+        name,
+        name
+    );
+}
+
+#[cfg(test)]
+#[path = "touch_test.rs"]
+mod tests;
@@ -0,0 +1,136 @@
+use git2::{Signature, Time};
+
+use super::{TouchOpts, touch};
+use crate::core::test_helpers::TestRepo;
+
+/// Commit `filename` on HEAD, authored and committed in 2001 by someone else.
+fn old_commit(test_repo: &TestRepo, message: &str, filename: &str) -> git2::Oid {
+    let oid = test_repo.commit(message, filename);
+    let commit = test_repo.find_commit(oid);
+    let sig = Signature::new("Old", "old@test.com", &Time::new(1_000_000_000, 60)).unwrap();
+    let parent = commit.parent(0).unwrap();
+    let new_oid = test_repo
+        .repo
+        .commit(
+            None,
+            &sig,
+            &sig,
+            message,
+            &commit.tree().unwrap(),
+            &[&parent],
+        )
+        .unwrap();
+    test_repo.reset_hard(new_oid);
+    new_oid
+}
+
+/// `feature-a` and a two-commit `feature-b` woven, `feature-b` made in 2001.
+fn setup() -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    for name in ["feature-a", "feature-b"] {
+        test_repo.create_branch_at(name, &base_oid.to_string());
+    }
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a1.txt");
+    test_repo.switch_branch("feature-b");
+    old_commit(&test_repo, "B1", "b1.txt");
+    old_commit(&test_repo, "B2", "b2.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-a");
+    test_repo.merge_no_ff("feature-b");
+    test_repo
+}
+
+#[test]
+fn touch_recreates_branch_commits_keeping_author() {
+    let test_repo = setup();
+    let a_tip = test_repo.get_branch_target("feature-a");
+    let b_tip = test_repo.find_commit(test_repo.get_branch_target("feature-b"));
+
+    let count = touch(&test_repo.repo, "feature-b", TouchOpts::default()).unwrap();
+
+    assert_eq!(count, 2);
+    assert_eq!(test_repo.get_branch_target("feature-a"), a_tip);
+    let new_tip = test_repo.find_commit(test_repo.get_branch_target("feature-b"));
+    assert_ne!(new_tip.id(), b_tip.id());
+    assert_eq!(new_tip.tree_id(), b_tip.tree_id());
+    assert_eq!(new_tip.summary(), Some("B2"));
+    assert_eq!(new_tip.author().name(), Some("Old"));
+    assert_eq!(new_tip.author().when(), Time::new(1_000_000_000, 60));
+    assert!(new_tip.committer().when().seconds() > 1_000_000_000);
+
+    let first = new_tip.parent(0).unwrap();
+    assert_eq!(first.summary(), Some("B1"));
+    assert_ne!(first.id(), b_tip.parent_id(0).unwrap());
+    assert!(test_repo.workdir().join("b2.txt").exists());
+}
+
+#[test]
+fn touch_reset_author_uses_current_identity() {
+    let test_repo = setup();
+
+    touch(
+        &test_repo.repo,
+        "feature-b",
+        TouchOpts {
+            reset_author: true,
+            ..TouchOpts::default()
+        },
+    )
+    .unwrap();
+
+    let tip = test_repo.find_commit(test_repo.get_branch_target("feature-b"));
+    assert_eq!(tip.author().name(), Some("Test"));
+    assert!(tip.author().when().seconds() > 1_000_000_000);
+}
+
+#[test]
+fn touch_stacked_branch_keeps_the_branch_below() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    let a1 = test_repo.commit("A1", "a1.txt");
+    test_repo.create_branch("feature-b");
+    test_repo.switch_branch("feature-b");
+    old_commit(&test_repo, "B1", "b1.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-b");
+
+    let count = touch(&test_repo.repo, "feature-b", TouchOpts::default()).unwrap();
+
+    assert_eq!(count, 1);
+    assert_eq!(test_repo.get_branch_target("feature-a"), a1);
+    let tip = test_repo.find_commit(test_repo.get_branch_target("feature-b"));
+    assert_eq!(tip.parent_id(0).unwrap(), a1);
+}
+
+#[test]
+fn touch_refuses_unwoven_branch() {
+    let test_repo = setup();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-c", &base_oid.to_string());
+
+    let err = touch(&test_repo.repo, "feature-c", TouchOpts::default()).unwrap_err();
+    assert!(err.to_string().contains("no commits yet"), "{}", err);
+}
+
+#[test]
+fn touch_copies_notes_when_enabled() {
+    let test_repo = setup();
+    test_repo.set_config("loom.rewriteNotes", "true");
+    let b_tip = test_repo.get_branch_target("feature-b");
+    let sig = test_repo.repo.signature().unwrap();
+    test_repo
+        .repo
+        .note(&sig, &sig, None, b_tip, "Reviewed-by: Ada", false)
+        .unwrap();
+
+    touch(&test_repo.repo, "feature-b", TouchOpts::default()).unwrap();
+
+    let new_tip = test_repo.get_branch_target("feature-b");
+    assert_ne!(new_tip, b_tip);
+    let note = test_repo.repo.find_note(None, new_tip).unwrap();
+    assert_eq!(note.message(), Some("Reviewed-by: Ada"));
+}
@@ -0,0 +1,389 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use colored::Colorize;
+use git2::{BranchType, Repository};
+
+use crate::core::msg;
+use crate::git;
+
+/// Name of the integration branch created during the tour.
+const INTEGRATION: &str = "integration";
+
+/// Name of the feature branch created during the tour.
+const FEATURE: &str = "feature-greeting";
+
+/// File the tour edits to demonstrate `commit` and `fold`.
+const GREETING_FILE: &str = "greeting.txt";
+
+/// Subject of the commit created during the `commit` step.
+const FEATURE_SUBJECT: &str = "Add a greeting";
+
+/// Subject of the commit a "teammate" pushes upstream before the `update` step.
+const UPSTREAM_SUBJECT: &str = "Teammate: document the project";
+
+/// A scratch repository used by the tour.
+///
+/// Layout inside the temp dir:
+/// - `remote.git` — a bare repository acting as `origin`
+/// - `work` — the user's clone, where every loom command runs
+/// - `teammate` — a second clone used to push upstream changes
+pub struct Scratch {
+    pub work: PathBuf,
+    pub teammate: PathBuf,
+}
+
+/// Run the interactive tour.
+///
+/// Creates a scratch repository in a temp dir and steps through `init`,
+/// `branch`, `commit`, `fold`, and `update`. Each step explains what is about
+/// to happen, runs the real `git-loom` binary inside the scratch repository,
+/// and validates the resulting repository state before moving on.
+///
+/// The scratch repository is deleted at the end unless `keep` is set.
+pub fn run(keep: bool) -> Result<()> {
+    let dir = tempfile::Builder::new()
+        .prefix("loom-tour-")
+        .tempdir()
+        .context("Failed to create a temporary directory for the tour")?;
+
+    let spinner = msg::spinner();
+    spinner.start("Creating a scratch repository...");
+    let scratch = match setup_scratch(dir.path()) {
+        Ok(scratch) => {
+            spinner.stop("Created a scratch repository");
+            scratch
+        }
+        Err(e) => {
+            spinner.error("Failed to create a scratch repository");
+            return Err(e);
+        }
+    };
+
+    println!();
+    println!(
+        "Welcome to the {} tour! Every step runs a real command in a throwaway repository.",
+        "git-loom".bold()
+    );
+    println!("Nothing outside {} is touched.", scratch.work.display());
+
+    let completed = run_steps(&scratch)?;
+
+    println!();
+    if completed {
+        msg::success(
+            "Tour complete\n\
+             Run `loom init` in one of your own repositories to get started\n\
+             Full docs: https://narnaud.github.io/git-loom/",
+        );
+    } else {
+        msg::warn("Tour stopped");
+    }
+
+    if keep {
+        let path = dir.keep();
+        msg::success(&format!(
+            "Kept the scratch repository at `{}`",
+            path.join("work").display()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Walk through every step. Returns `false` if the user stopped early.
+fn run_steps(scratch: &Scratch) -> Result<bool> {
+    let work = &scratch.work;
+    let total = 5;
+
+    // 1. init
+    if !step(
+        work,
+        (1, total),
+        "Create an integration branch",
+        "An integration branch tracks the upstream (here `origin/main`) and weaves\n\
+         your feature branches together so you can work on all of them at once.",
+        &["init"],
+    )? {
+        return Ok(false);
+    }
+    validate_init(&open(work)?)?;
+
+    // 2. branch
+    if !step(
+        work,
+        (2, total),
+        "Create a feature branch",
+        "Feature branches start at the upstream base. Commits you add to them\n\
+         are woven into the integration branch automatically.",
+        &["branch", FEATURE],
+    )? {
+        return Ok(false);
+    }
+    validate_branch(&open(work)?)?;
+
+    // 3. commit
+    std::fs::write(work.join(GREETING_FILE), "Hello\n")?;
+    println!();
+    println!(
+        "The tour just created {} in the working tree.",
+        GREETING_FILE.yellow()
+    );
+    if !step(
+        work,
+        (3, total),
+        "Commit to the feature branch",
+        "`loom commit -b <branch>` commits to any woven branch without leaving\n\
+         the integration branch.",
+        &[
+            "commit",
+            "-b",
+            FEATURE,
+            "-m",
+            FEATURE_SUBJECT,
+            GREETING_FILE,
+        ],
+    )? {
+        return Ok(false);
+    }
+    validate_commit(&open(work)?)?;
+
+    // 4. fold
+    std::fs::write(work.join(GREETING_FILE), "Hello, world!\n")?;
+    let target = {
+        let repo = open(work)?;
+        let tip = repo.revparse_single(FEATURE)?.id().to_string();
+        git::short_hash(&tip).to_string()
+    };
+    println!();
+    println!(
+        "The tour just edited {} — let's amend the commit that added it.",
+        GREETING_FILE.yellow()
+    );
+    if !step(
+        work,
+        (4, total),
+        "Fold a change into an earlier commit",
+        "`loom fold <file> <commit>` amends any commit in the integration branch,\n\
+         not just the last one. Short IDs from `loom status` work too.",
+        &["fold", GREETING_FILE, &target],
+    )? {
+        return Ok(false);
+    }
+    validate_fold(&open(work)?)?;
+
+    // 5. update
+    push_teammate_commit(&scratch.teammate)?;
+    println!();
+    println!(
+        "A teammate just pushed a new commit to {}.",
+        "origin/main".yellow()
+    );
+    if !step(
+        work,
+        (5, total),
+        "Update from upstream",
+        "`loom update` fetches and rebases the integration branch — and every\n\
+         woven feature branch — onto the new upstream.",
+        &["update"],
+    )? {
+        return Ok(false);
+    }
+    validate_update(&open(work)?)?;
+
+    Ok(true)
+}
+
+/// Explain a step, ask for confirmation, then run the loom command in `workdir`
+/// and show the resulting status. Returns `false` if the user declined.
+fn step(
+    workdir: &Path,
+    (index, total): (usize, usize),
+    title: &str,
+    explanation: &str,
+    args: &[&str],
+) -> Result<bool> {
+    println!();
+    println!(
+        "{} {}",
+        format!("[{}/{}]", index, total).dimmed(),
+        title.bold()
+    );
+    for line in explanation.lines() {
+        println!("  {}", line);
+    }
+    println!("  {} git loom {}", "$".dimmed(), args.join(" ").green());
+
+    if !msg::confirm("Run it?")? {
+        return Ok(false);
+    }
+
+    run_loom(workdir, args)?;
+    run_loom(workdir, &["status"])?;
+    Ok(true)
+}
+
+/// Run the current loom binary in the scratch repository, inheriting stdio.
+fn run_loom(workdir: &Path, args: &[&str]) -> Result<()> {
+    let exe = git::loom_exe_path()?;
+    let status = Command::new(&exe)
+        .args(args)
+        .current_dir(workdir)
+        .status()
+        .with_context(|| format!("Failed to run `{}`", exe.display()))?;
+    if !status.success() {
+        bail!("`git loom {}` failed", args.join(" "));
+    }
+    Ok(())
+}
+
+fn open(workdir: &Path) -> Result<Repository> {
+    Repository::open(workdir).context("Failed to open the scratch repository")
+}
+
+/// Create the scratch layout (bare remote, working clone, teammate clone).
+///
+/// The working clone is left on `main` tracking `origin/main`, ready for
+/// `loom init`.
+pub fn setup_scratch(root: &Path) -> Result<Scratch> {
+    let remote = root.join("remote.git");
+    let work = root.join("work");
+    let teammate = root.join("teammate");
+
+    git::run_git(
+        root,
+        &[
+            "init",
+            "--quiet",
+            "--bare",
+            "--initial-branch=main",
+            "remote.git",
+        ],
+    )?;
+    git::run_git(root, &["init", "--quiet", "--initial-branch=main", "work"])?;
+    configure_scratch_repo(&work)?;
+
+    std::fs::write(work.join("README.md"), "# Loom tour\n")?;
+    git::run_git(&work, &["add", "README.md"])?;
+    git::run_git(&work, &["commit", "--quiet", "-m", "Initial commit"])?;
+    let remote_url = remote.to_string_lossy().to_string();
+    git::run_git(&work, &["remote", "add", "origin", &remote_url])?;
+    git::run_git(&work, &["push", "--quiet", "-u", "origin", "main"])?;
+
+    git::run_git(root, &["clone", "--quiet", &remote_url, "teammate"])?;
+    configure_scratch_repo(&teammate)?;
+
+    Ok(Scratch { work, teammate })
+}
+
+/// Give a scratch repository a fixed identity and disable signing and hooks,
+/// so the tour does not depend on (or trip over) the user's global config.
+fn configure_scratch_repo(workdir: &Path) -> Result<()> {
+    git::run_git(workdir, &["config", "user.name", "Loom Tour"])?;
+    git::run_git(workdir, &["config", "user.email", "tour@git-loom.invalid"])?;
+    git::run_git(workdir, &["config", "commit.gpgsign", "false"])?;
+    git::run_git(workdir, &["config", "core.hooksPath", ".git/no-hooks"])?;
+    Ok(())
+}
+
+/// Simulate a teammate pushing a new commit to `origin/main`.
+pub fn push_teammate_commit(teammate: &Path) -> Result<()> {
+    std::fs::write(
+        teammate.join("README.md"),
+        "# Loom tour\n\nA scratch project for learning git-loom.\n",
+    )?;
+    git::run_git(teammate, &["commit", "--quiet", "-am", UPSTREAM_SUBJECT])?;
+    git::run_git(teammate, &["push", "--quiet", "origin", "main"])?;
+    Ok(())
+}
+
+/// `init`: HEAD is on the integration branch, tracking `origin/main`.
+pub fn validate_init(repo: &Repository) -> Result<()> {
+    let head = repo.head()?;
+    if head.shorthand() != Some(INTEGRATION) {
+        bail!("Expected to be on branch `{}`", INTEGRATION);
+    }
+    let branch = repo.find_branch(INTEGRATION, BranchType::Local)?;
+    let upstream = branch
+        .upstream()
+        .with_context(|| format!("Branch `{}` has no upstream", INTEGRATION))?;
+    if upstream.name()? != Some("origin/main") {
+        bail!("Expected `{}` to track `origin/main`", INTEGRATION);
+    }
+    Ok(())
+}
+
+/// `branch`: the feature branch exists at the upstream base.
+pub fn validate_branch(repo: &Repository) -> Result<()> {
+    let branch = repo
+        .find_branch(FEATURE, BranchType::Local)
+        .with_context(|| format!("Expected branch `{}` to exist", FEATURE))?;
+    let base = repo.revparse_single("origin/main")?.id();
+    if branch.get().target() != Some(base) {
+        bail!("Expected `{}` to start at `origin/main`", FEATURE);
+    }
+    Ok(())
+}
+
+/// `commit`: the feature branch has the greeting commit, woven into HEAD.
+pub fn validate_commit(repo: &Repository) -> Result<()> {
+    let tip = repo.revparse_single(FEATURE)?.peel_to_commit()?;
+    if tip.summary() != Some(FEATURE_SUBJECT) {
+        bail!("Expected `{}` to end with `{}`", FEATURE, FEATURE_SUBJECT);
+    }
+    let head = repo.head()?.peel_to_commit()?.id();
+    if !repo.graph_descendant_of(head, tip.id())? {
+        bail!("Expected `{}` to be woven into `{}`", FEATURE, INTEGRATION);
+    }
+    Ok(())
+}
+
+/// `fold`: the greeting commit carries the edited content and the tree is clean.
+pub fn validate_fold(repo: &Repository) -> Result<()> {
+    let tip = repo.revparse_single(FEATURE)?.peel_to_commit()?;
+    if tip.summary() != Some(FEATURE_SUBJECT) {
+        bail!(
+            "Expected `{}` to still end with `{}`",
+            FEATURE,
+            FEATURE_SUBJECT
+        );
+    }
+    let entry = tip.tree()?.get_path(Path::new(GREETING_FILE))?;
+    let blob = repo.find_blob(entry.id())?;
+    if blob.content() != b"Hello, world!\n" {
+        bail!("Expected the edit to be folded into `{}`", FEATURE_SUBJECT);
+    }
+    let statuses = repo.statuses(None)?;
+    if statuses.iter().any(|s| !s.status().is_ignored()) {
+        bail!("Expected a clean working tree after the fold");
+    }
+    Ok(())
+}
+
+/// `update`: HEAD contains the new upstream tip and the feature branch.
+pub fn validate_update(repo: &Repository) -> Result<()> {
+    let head = repo.head()?.peel_to_commit()?.id();
+    let upstream = repo.revparse_single("origin/main")?.peel_to_commit()?;
+    if upstream.summary() != Some(UPSTREAM_SUBJECT) {
+        bail!("Expected `origin/main` to include the teammate's commit");
+    }
+    if head != upstream.id() && !repo.graph_descendant_of(head, upstream.id())? {
+        bail!(
+            "Expected `{}` to be rebased onto `origin/main`",
+            INTEGRATION
+        );
+    }
+    let tip = repo.revparse_single(FEATURE)?.id();
+    if !repo.graph_descendant_of(head, tip)? {
+        bail!("Expected `{}` to still be woven in", FEATURE);
+    }
+    if !repo.graph_descendant_of(tip, upstream.id())? {
+        bail!("Expected `{}` to be rebased onto `origin/main`", FEATURE);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "tour_test.rs"]
+mod tests;
@@ -0,0 +1,106 @@
+use git2::Repository;
+
+use crate::git;
+
+fn scratch() -> (tempfile::TempDir, super::Scratch) {
+    let dir = tempfile::tempdir().unwrap();
+    let scratch = super::setup_scratch(dir.path()).unwrap();
+    (dir, scratch)
+}
+
+#[test]
+fn setup_scratch_leaves_main_tracking_origin() {
+    let (_dir, scratch) = scratch();
+    let repo = Repository::open(&scratch.work).unwrap();
+
+    assert_eq!(repo.head().unwrap().shorthand(), Some("main"));
+    let main = repo.find_branch("main", git2::BranchType::Local).unwrap();
+    let upstream = main.upstream().unwrap();
+    assert_eq!(upstream.name().unwrap(), Some("origin/main"));
+    assert!(Repository::open(&scratch.teammate).is_ok());
+}
+
+#[test]
+fn validate_init_requires_integration_branch() {
+    let (_dir, scratch) = scratch();
+    let repo = Repository::open(&scratch.work).unwrap();
+    assert!(super::validate_init(&repo).is_err());
+
+    git::run_git(
+        &scratch.work,
+        &[
+            "switch",
+            "--quiet",
+            "-c",
+            "integration",
+            "--track",
+            "origin/main",
+        ],
+    )
+    .unwrap();
+    assert!(super::validate_init(&repo).is_ok());
+}
+
+#[test]
+fn validate_branch_requires_branch_at_base() {
+    let (_dir, scratch) = scratch();
+    let repo = Repository::open(&scratch.work).unwrap();
+    assert!(super::validate_branch(&repo).is_err());
+
+    git::run_git(&scratch.work, &["branch", super::FEATURE, "origin/main"]).unwrap();
+    assert!(super::validate_branch(&repo).is_ok());
+}
+
+#[test]
+fn validate_commit_requires_woven_commit() {
+    let (_dir, scratch) = scratch();
+    let work = &scratch.work;
+    git::run_git(work, &["switch", "--quiet", "-c", super::FEATURE]).unwrap();
+    std::fs::write(work.join(super::GREETING_FILE), "Hello\n").unwrap();
+    git::run_git(work, &["add", super::GREETING_FILE]).unwrap();
+    git::run_git(work, &["commit", "--quiet", "-m", super::FEATURE_SUBJECT]).unwrap();
+
+    // Not woven yet: HEAD is on main, which does not contain the commit.
+    git::run_git(work, &["switch", "--quiet", "main"]).unwrap();
+    let repo = Repository::open(work).unwrap();
+    assert!(super::validate_commit(&repo).is_err());
+
+    git::run_git(
+        work,
+        &["merge", "--quiet", "--no-ff", "--no-edit", super::FEATURE],
+    )
+    .unwrap();
+    assert!(super::validate_commit(&repo).is_ok());
+}
+
+#[test]
+fn validate_fold_requires_amended_content_and_clean_tree() {
+    let (_dir, scratch) = scratch();
+    let work = &scratch.work;
+    git::run_git(work, &["switch", "--quiet", "-c", super::FEATURE]).unwrap();
+    std::fs::write(work.join(super::GREETING_FILE), "Hello\n").unwrap();
+    git::run_git(work, &["add", super::GREETING_FILE]).unwrap();
+    git::run_git(work, &["commit", "--quiet", "-m", super::FEATURE_SUBJECT]).unwrap();
+
+    std::fs::write(work.join(super::GREETING_FILE), "Hello, world!\n").unwrap();
+    let repo = Repository::open(work).unwrap();
+    assert!(super::validate_fold(&repo).is_err());
+
+    git::run_git(work, &["commit", "--quiet", "-a", "--amend", "--no-edit"]).unwrap();
+    assert!(super::validate_fold(&repo).is_ok());
+}
+
+#[test]
+fn push_teammate_commit_advances_origin_main() {
+    let (_dir, scratch) = scratch();
+    super::push_teammate_commit(&scratch.teammate).unwrap();
+    git::run_git(&scratch.work, &["fetch", "--quiet"]).unwrap();
+
+    let repo = Repository::open(&scratch.work).unwrap();
+    let upstream = repo
+        .revparse_single("origin/main")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    assert_eq!(upstream.summary(), Some(super::UPSTREAM_SUBJECT));
+}
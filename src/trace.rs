@@ -172,7 +172,7 @@ pub fn print_latest_log(git_dir: &Path) -> anyhow::Result<()> {
 }
 
 /// Print a log file's content with colored output.
-fn print_log_colored(content: &str) {
+pub fn print_log_colored(content: &str) {
     let mut lines = content.lines();
 
     // Header line: [timestamp] command
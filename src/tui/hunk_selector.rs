@@ -45,7 +45,7 @@ pub(crate) struct FileEntry {
     pub hunks: Vec<HunkEntry>,
     /// Index (staged) status character: ' ', 'A', 'M', 'D', 'R', or '?'.
     pub index_status: char,
-    /// Worktree (unstaged) status character: ' ', 'M', 'D', 'R', '?', or '!'.
+    /// Worktree (unstaged) status character: ' ', 'M', 'D', 'R', or '?'.
     pub worktree_status: char,
     /// Whether this file is binary (no hunk-level patching possible).
     pub binary: bool,
@@ -127,6 +127,8 @@ struct HunkSelectorApp {
     hunk_index: usize,
     active_pane: Pane,
     theme: TuiTheme,
+    /// File checklist: files are picked whole, the diff pane only shows them.
+    whole_files: bool,
     should_quit: bool,
     confirmed: bool,
     scroll_offset: u16,
@@ -196,6 +198,7 @@ impl HunkSelectorApp {
             hunk_index: 0,
             active_pane: Pane::Left,
             theme,
+            whole_files: false,
             should_quit: false,
             confirmed: false,
             scroll_offset: 0,
@@ -204,6 +207,21 @@ impl HunkSelectorApp {
         }
     }
 
+    /// Build the app as a file checklist. A file with some hunks selected
+    /// starts fully selected.
+    fn new_file_checklist(mut files: Vec<FileEntry>, theme: TuiTheme) -> Self {
+        for file in &mut files {
+            let selected = file.hunks.iter().any(|h| h.selected);
+            for h in &mut file.hunks {
+                h.selected = selected;
+            }
+        }
+        Self {
+            whole_files: true,
+            ..Self::new(files, theme)
+        }
+    }
+
     /// Return the file index if the cursor is on a file row, or `None` on a
     /// directory header.
     fn current_file_index(&self) -> Option<usize> {
@@ -257,6 +275,16 @@ impl HunkSelectorApp {
                         &f.path
                     };
                     let indent = if in_dir { "  " } else { "" };
+                    let indent = if self.whole_files {
+                        let check = if eff_idx != ' ' && eff_idx != '?' {
+                            "[x] "
+                        } else {
+                            "[ ] "
+                        };
+                        format!("{}{}", indent, check)
+                    } else {
+                        indent.to_string()
+                    };
 
                     let is_untracked = eff_idx == '?' && eff_wt == '?';
                     let mut spans: Vec<Span> = if is_untracked {
@@ -266,7 +294,7 @@ impl HunkSelectorApp {
                         )]
                     } else {
                         vec![
-                            Span::raw(indent.to_string()),
+                            Span::raw(indent),
                             Span::styled(eff_idx.to_string(), self.theme.staged_status),
                             Span::styled(eff_wt.to_string(), self.theme.unstaged_status),
                         ]
@@ -386,13 +414,17 @@ impl HunkSelectorApp {
                 HunkOrigin::Unstaged => "",
                 HunkOrigin::Commit => "",
             };
-            let header_text = format!(
-                "[{}] Hunk {}/{}{}",
-                marker,
-                i + 1,
-                total_hunks,
-                origin_label
-            );
+            let header_text = if self.whole_files {
+                format!("Hunk {}/{}{}", i + 1, total_hunks, origin_label)
+            } else {
+                format!(
+                    "[{}] Hunk {}/{}{}",
+                    marker,
+                    i + 1,
+                    total_hunks,
+                    origin_label
+                )
+            };
 
             // Highlight the focused hunk header when right pane is active.
             let header_style = if self.active_pane == Pane::Right && i == self.hunk_index {
@@ -483,7 +515,11 @@ impl HunkSelectorApp {
     }
 
     fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
-        let text = " Navigate: \u{2191}/\u{2193} or j/k | Switch Pane: tab | Toggle: space | Confirm: c or Enter | Quit: q or Esc";
+        let text = if self.whole_files {
+            " Navigate: \u{2191}/\u{2193} or j/k | View Diff: tab | Toggle File: space | Confirm: c or Enter | Quit: q or Esc"
+        } else {
+            " Navigate: \u{2191}/\u{2193} or j/k | Switch Pane: tab | Toggle: space | Confirm: c or Enter | Quit: q or Esc"
+        };
         let bar = Paragraph::new(text).style(self.theme.status_bar);
         frame.render_widget(bar, area);
     }
@@ -577,7 +613,7 @@ impl HunkSelectorApp {
                         if clicked_line < line + hunk_lines {
                             // Clicked inside this hunk — toggle if on header row.
                             self.hunk_index = i;
-                            if clicked_line == line {
+                            if clicked_line == line && !self.whole_files {
                                 let h = &mut self.files[file_idx].hunks[i];
                                 h.selected = !h.selected;
                             }
@@ -665,7 +701,13 @@ impl HunkSelectorApp {
         if self.display_rows.is_empty() {
             return;
         }
-        match self.active_pane {
+        // In a file checklist, the diff pane toggles the file it shows
+        let pane = if self.whole_files {
+            Pane::Left
+        } else {
+            self.active_pane
+        };
+        match pane {
             Pane::Left => match self.display_rows[self.cursor_pos] {
                 DisplayRow::Directory { dir_start, dir_end } => {
                     // Toggle all hunks in all files under this directory.
@@ -749,11 +791,32 @@ impl HunkSelectorApp {
 ///
 /// Returns `Ok(Some(files))` with updated selection state if the user confirms,
 /// or `Ok(None)` if cancelled / empty input.
-pub fn run_hunk_selector(files: Vec<FileEntry>, theme: TuiTheme) -> Result<Option<Vec<FileEntry>>> {
+pub(crate) fn run_hunk_selector(
+    files: Vec<FileEntry>,
+    theme: TuiTheme,
+) -> Result<Option<Vec<FileEntry>>> {
+    if files.is_empty() {
+        return Ok(None);
+    }
+    run_app(HunkSelectorApp::new(files, theme))
+}
+
+/// Run the selector as a checklist of whole files, their diffs viewable in
+/// the right pane.
+///
+/// Returns `Ok(Some(files))` with every hunk of a picked file selected if the
+/// user confirms, or `Ok(None)` if cancelled / empty input.
+pub(crate) fn run_file_checklist(
+    files: Vec<FileEntry>,
+    theme: TuiTheme,
+) -> Result<Option<Vec<FileEntry>>> {
     if files.is_empty() {
         return Ok(None);
     }
+    run_app(HunkSelectorApp::new_file_checklist(files, theme))
+}
 
+fn run_app(app: HunkSelectorApp) -> Result<Option<Vec<FileEntry>>> {
     let mut terminal = ratatui::init();
     crossterm::execute!(std::io::stdout(), EnableMouseCapture)?;
 
@@ -766,7 +829,7 @@ pub fn run_hunk_selector(files: Vec<FileEntry>, theme: TuiTheme) -> Result<Optio
         prev_hook(info);
     }));
 
-    let result = run_event_loop(&mut terminal, files, theme);
+    let result = run_event_loop(&mut terminal, app);
 
     // Restore terminal state on normal exit.
     crossterm::execute!(std::io::stdout(), DisableMouseCapture)?;
@@ -780,11 +843,8 @@ pub fn run_hunk_selector(files: Vec<FileEntry>, theme: TuiTheme) -> Result<Optio
 
 fn run_event_loop(
     terminal: &mut ratatui::DefaultTerminal,
-    files: Vec<FileEntry>,
-    theme: TuiTheme,
+    mut app: HunkSelectorApp,
 ) -> Result<Option<Vec<FileEntry>>> {
-    let mut app = HunkSelectorApp::new(files, theme);
-
     loop {
         terminal.draw(|frame| app.render(frame))?;
 
@@ -1053,6 +1113,26 @@ mod tests {
         assert!(app.files[0].hunks.iter().all(|h| h.selected));
     }
 
+    #[test]
+    fn file_checklist_selects_partially_staged_files_whole() {
+        let app = HunkSelectorApp::new_file_checklist(make_files(), make_theme());
+        // main.rs had one staged hunk out of two → picked whole
+        assert!(app.files[0].hunks.iter().all(|h| h.selected));
+        assert!(app.files[1].hunks.iter().all(|h| !h.selected));
+    }
+
+    #[test]
+    fn file_checklist_toggles_whole_file_from_diff_pane() {
+        let mut app = HunkSelectorApp::new_file_checklist(make_files(), make_theme());
+        app.active_pane = Pane::Right;
+        app.navigate_down(); // second hunk of main.rs
+
+        app.toggle();
+        assert!(app.files[0].hunks.iter().all(|h| !h.selected));
+        app.toggle();
+        assert!(app.files[0].hunks.iter().all(|h| h.selected));
+    }
+
     #[test]
     fn quit_sets_flags() {
         let mut app = HunkSelectorApp::new(make_files(), make_theme());
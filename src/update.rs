@@ -1,29 +1,80 @@
+use std::collections::BTreeSet;
+use std::io::Write;
 use std::path::Path;
+use std::time::Duration;
 
 use anyhow::{Context, Result, bail};
 use git2::BranchType;
 use serde::{Deserialize, Serialize};
 
-use crate::core::repo;
+use crate::core::repo::{self, TargetKind};
 
+use crate::core::ancestry;
+use crate::core::lock;
 use crate::core::msg;
 use crate::core::transaction::{self, LoomState, Rollback};
-use crate::core::weave::Weave;
+use crate::core::weave::{self, Weave};
 use crate::git::{self, RebaseOutcome};
+use crate::{explain, meta};
 
 #[derive(Serialize, Deserialize)]
 struct UpdateContext {
     branch_name: String,
     upstream_name: String,
     skip_confirm: bool,
+    /// With `--only`: the branch restacked, and the upstream commit it was
+    /// restacked onto.
+    #[serde(default)]
+    only: Option<(String, String)>,
+    /// Set by `--daemon`: never prompt, only report branches with a gone
+    /// upstream unless `--yes` was given.
+    #[serde(default)]
+    no_prompt: bool,
 }
 
 /// Update the integration branch by fetching and rebasing from upstream.
-pub fn run(skip_confirm: bool) -> Result<()> {
+///
+/// With `only`, just that branch is restacked onto the new upstream: the rest
+/// of the weave stays on its base, and the branch's own base is recorded in
+/// `refs/loom/base/<branch>` until the next full update.
+pub fn run(skip_confirm: bool, only: Option<String>) -> Result<()> {
     let repo = repo::open_repo()?;
     let workdir = repo::require_workdir(&repo, "update")?.to_path_buf();
     let git_dir = repo.path().to_path_buf();
+    let (branch_name, tracked_name, upstream_name) = tracking(&repo)?;
+
+    let only = match only {
+        Some(target) => Some(restack_target(&repo, &target)?),
+        None => None,
+    };
+
+    transaction::reporting_ref_moves(&repo, || {
+        fetch(&repo, &workdir, &branch_name, &tracked_name, &upstream_name)?;
+        if repo::share_meta(&repo) {
+            let remote = tracked_name.split('/').next().unwrap_or_default();
+            if let Err(e) = meta::pull(&repo, &workdir, remote) {
+                msg::warn(&format!(
+                    "Could not fetch the shared weave structure: {}",
+                    e
+                ));
+            }
+        }
 
+        let ctx = UpdateContext {
+            branch_name,
+            upstream_name,
+            skip_confirm,
+            only: None,
+            no_prompt: false,
+        };
+        rebase_and_finish(&workdir, &git_dir, ctx, only)?;
+        Ok(())
+    })
+}
+
+/// The current branch, the upstream it tracks, and the upstream it is based
+/// on (they differ with `loom.baseRemote`).
+pub(crate) fn tracking(repo: &git2::Repository) -> Result<(String, String, String)> {
     // Validate that we're on a branch with an upstream tracking ref
     let head = repo.head().context("Failed to get HEAD reference")?;
     if !head.is_branch() {
@@ -43,24 +94,46 @@ pub fn run(skip_confirm: bool) -> Result<()> {
             branch_name
         )
     })?;
-    let upstream_name = upstream
+    let tracked_name = upstream
         .name()?
         .context("Upstream branch name is not valid UTF-8")?
         .to_string();
+    let upstream_name = repo::base_upstream(repo, &branch_name, &tracked_name);
+    Ok((branch_name, tracked_name, upstream_name))
+}
 
-    // Fetch with tags, force-update, and prune deleted remote branches.
-    // The spinner reassures the user that work is happening (fetches can be slow);
-    // afterwards we print git's own summary so they can tell whether anything was
-    // actually pulled (e.g. `a309e49..7b3c4c1 main -> origin/main`). `--no-progress`
-    // drops the transfer noise (`remote: ...`, `Receiving objects`) so the captured
-    // output is just the clean ref-update summary.
+/// Fetch with tags, force-update, and prune deleted remote branches.
+///
+/// The spinner reassures the user that work is happening (fetches can be slow);
+/// afterwards we print git's own summary so they can tell whether anything was
+/// actually pulled (e.g. `a309e49..7b3c4c1 main -> origin/main`). `--no-progress`
+/// drops the transfer noise (`remote: ...`, `Receiving objects`) so the captured
+/// output is just the clean ref-update summary.
+pub(crate) fn fetch(
+    repo: &git2::Repository,
+    workdir: &Path,
+    branch_name: &str,
+    tracked_name: &str,
+    upstream_name: &str,
+) -> Result<()> {
     let spinner = msg::spinner();
     spinner.start("Fetching latest changes...");
 
-    let result = git::run_git_combined(
-        &workdir,
-        &["fetch", "--no-progress", "--tags", "--force", "--prune"],
-    );
+    // With `loom.baseRemote`, the base comes from another remote than the
+    // tracked one: fetch both.
+    let mut fetch_args = vec!["fetch", "--no-progress", "--tags", "--force", "--prune"];
+    let remotes;
+    if upstream_name != tracked_name {
+        let tracked_remote = repo.branch_upstream_remote(&format!("refs/heads/{}", branch_name))?;
+        let base_remote = repo::base_remote(repo).unwrap_or_default();
+        remotes = [
+            tracked_remote.as_str().unwrap_or("origin").to_string(),
+            base_remote,
+        ];
+        fetch_args.push("--multiple");
+        fetch_args.extend(remotes.iter().map(String::as_str));
+    }
+    let result = git::run_git_combined(workdir, &fetch_args);
 
     match result {
         Ok(summary) => {
@@ -68,27 +141,45 @@ pub fn run(skip_confirm: bool) -> Result<()> {
             if !summary.is_empty() {
                 println!("{}", summary);
             }
+            Ok(())
         }
         Err(e) => {
             spinner.error("Fetch failed");
-            return Err(e);
+            Err(e)
         }
     }
+}
+
+/// Rebase onto the fetched upstream (only the `only` branch, if given), then
+/// run the post-update steps. A conflict leaves the update paused.
+fn rebase_and_finish(
+    workdir: &Path,
+    git_dir: &Path,
+    mut ctx: UpdateContext,
+    only: Option<String>,
+) -> Result<RebaseOutcome> {
+    // Re-open repo after fetch (remote refs changed)
+    let repo = git2::Repository::discover(workdir)?;
+    let new_upstream_oid = repo
+        .revparse_single(&ctx.upstream_name)
+        .context("Failed to resolve upstream ref")?
+        .peel_to_commit()?
+        .id();
+    let upstream_name = ctx.upstream_name.clone();
 
     // Save rollback state before the rebase
-    let ctx = UpdateContext {
-        branch_name: branch_name.clone(),
-        upstream_name: upstream_name.clone(),
-        skip_confirm,
-    };
+    ctx.only = only
+        .clone()
+        .map(|name| (name, new_upstream_oid.to_string()));
     let state = LoomState {
         command: "update".to_string(),
+        refs_before: Default::default(),
         rollback: Rollback {
             ..Default::default()
         },
         context: serde_json::to_value(&ctx)?,
     };
-    transaction::save(&git_dir, &state)?;
+    transaction::save(git_dir, &state)?;
 
     // Rebase onto upstream using the weave model.
     //
@@ -97,55 +188,350 @@ pub fn run(skip_confirm: bool) -> Result<()> {
     // (inside a feature branch instead of on the base line). The weave model
     // generates a clean todo where every branch section `reset onto`, ensuring
     // branches are correctly rebased onto the new upstream tip.
+    //
+    // `loom.autostash` is checked before the spinner starts, as it may ask.
+    weave::check_autostash(workdir)?;
     let spinner = msg::spinner();
     spinner.start("Rebasing onto upstream...");
 
-    // Re-open repo after fetch (remote refs changed)
-    let repo = git2::Repository::discover(&workdir)?;
-
-    let outcome = match Weave::from_repo(&repo) {
-        Ok(mut graph) => {
+    let outcome = match (&only, Weave::from_repo(&repo)) {
+        (Some(name), Ok(graph)) => restack_only(workdir, graph, name, new_upstream_oid),
+        (Some(_), Err(e)) => Err(e),
+        (None, Ok(mut graph)) => {
+            // Sections restacked by `--only` join the rest of the weave
+            let own_bases: Vec<String> = repo::branch_bases(&repo)?
+                .values()
+                .map(|oid| oid.to_string())
+                .collect();
+            for section in &mut graph.branch_sections {
+                if own_bases.contains(&section.reset_target) {
+                    section.reset_target = "onto".to_string();
+                }
+            }
             // Drop branch-section commits already in the new upstream
             // (merged or cherry-picked). This prevents conflicts from
             // replaying commits whose content is already in the base.
-            let new_upstream_oid = repo
-                .revparse_single(&upstream_name)
-                .context("Failed to resolve upstream ref")?
-                .id();
-            graph.filter_upstream_commits(&repo, &workdir, new_upstream_oid)?;
-            let todo = graph.to_todo();
-            crate::core::weave::run_rebase(&workdir, Some(&upstream_name), &todo)
+            graph.filter_upstream_commits(&repo, workdir, new_upstream_oid)?;
+            let todo = graph.checked_todo()?;
+            weave::run_rebase(workdir, Some(&upstream_name), &todo)
         }
-        Err(_) => {
+        (None, Err(_)) => {
             // Fallback: no integration topology (e.g., plain branch with no weave).
             // Use plain rebase.
-            git::rebase(&git_dir, &workdir, &upstream_name)
+            git::rebase(git_dir, workdir, &upstream_name)
+                .and_then(|outcome| crate::resolution::replay(workdir, outcome))
         }
     };
 
     match outcome {
         Ok(RebaseOutcome::Completed) => {
-            spinner.stop("Rebased onto upstream");
-            transaction::delete(&git_dir)?;
+            spinner.stop(match &only {
+                Some(_) => "Restacked branch onto upstream",
+                None => "Rebased onto upstream",
+            });
+            transaction::delete(git_dir)?;
             // Re-open repo after rebase (OIDs changed)
-            let repo2 = git2::Repository::discover(&workdir)?;
-            post_update(&workdir, &repo2, &ctx)?;
+            let repo2 = git2::Repository::discover(workdir)?;
+            post_update(workdir, &repo2, &ctx)?;
+            Ok(RebaseOutcome::Completed)
         }
         Ok(RebaseOutcome::Conflicted) => {
             spinner.error("Rebase paused due to conflicts");
-            transaction::warn_conflict_paused("update");
+            if !ctx.no_prompt {
+                transaction::warn_conflict_paused("update");
+            }
+            Ok(RebaseOutcome::Conflicted)
         }
         Err(e) => {
-            let _ = git::rebase_abort(&workdir);
-            transaction::delete(&git_dir)?;
+            let _ = git::rebase_abort(workdir);
+            transaction::delete(git_dir)?;
             spinner.error("Rebase failed");
-            return Err(e);
+            Err(e)
+        }
+    }
+}
+
+/// Log of `loom update --daemon` rounds, in `.git/loom/`.
+const DAEMON_LOG: &str = "daemon.log";
+
+/// What one round of `loom update --daemon` did.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Round {
+    /// The integration branch already contains the upstream.
+    UpToDate,
+    /// Rebased onto `commits` new upstream commits.
+    Updated { commits: usize },
+    /// Not attempted, for the given reason.
+    Skipped(String),
+    /// Not attempted: merging the new upstream conflicts in these files.
+    Conflicts(Vec<String>),
+    /// The rebase conflicted although no conflict was predicted; it was
+    /// aborted.
+    Aborted,
+}
+
+impl Round {
+    fn message(&self, branch: &str) -> String {
+        match self {
+            Round::UpToDate => format!("`{}` is up to date", branch),
+            Round::Updated { commits } => format!(
+                "Updated `{}` with {} new upstream {}",
+                branch,
+                commits,
+                if *commits == 1 { "commit" } else { "commits" }
+            ),
+            Round::Skipped(reason) => format!("Skipped update: {}", reason),
+            Round::Conflicts(files) => format!(
+                "Not updating `{}`: the new upstream conflicts in {}\nRun `loom update` to resolve them",
+                branch,
+                files.join(", ")
+            ),
+            Round::Aborted => format!(
+                "Updating `{}` hit conflicts and was rolled back\nRun `loom update` to resolve them",
+                branch
+            ),
+        }
+    }
+
+    /// Whether the user should hear about this round.
+    fn notifies(&self) -> bool {
+        !matches!(self, Round::UpToDate | Round::Skipped(_))
+    }
+}
+
+/// Update the integration branch every `interval`, for as long as the
+/// process runs.
+///
+/// A round only updates when it is safe unattended: clean working tree, no
+/// paused loom operation, no other loom command running, and no conflict
+/// predicted between HEAD and the fetched upstream. Every round is logged to
+/// `.git/loom/daemon.log`. Updates and rounds needing attention are also
+/// passed to `loom.notifyCommand`, once until the outcome changes.
+pub fn daemon(interval: Duration, skip_confirm: bool) -> Result<()> {
+    let repo = repo::open_repo()?;
+    repo::require_workdir(&repo, "update")?;
+    let git_dir = repo.path().to_path_buf();
+    let (branch_name, _, _) = tracking(&repo)?;
+    msg::success(&format!(
+        "Updating `{}` every {} — press Ctrl-C to stop",
+        branch_name,
+        format_interval(interval)
+    ));
+
+    let mut last_notified: Option<String> = None;
+    loop {
+        let (message, notifies) = match daemon_round(skip_confirm) {
+            Ok(round) => (round.message(&branch_name), round.notifies()),
+            Err(e) => (format!("Update failed: {:#}", e), true),
+        };
+        log_round(&git_dir, &message)?;
+        let first_line = message.lines().next().unwrap_or_default();
+        if notifies {
+            msg::warn(&message);
+            if last_notified.as_deref() != Some(&message) {
+                notify(&repo, first_line);
+            }
+            last_notified = Some(message);
+        } else {
+            println!("{}", first_line);
+            last_notified = None;
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// One unattended update attempt.
+pub fn daemon_round(skip_confirm: bool) -> Result<Round> {
+    let repo = repo::open_repo()?;
+    let workdir = repo::require_workdir(&repo, "update")?.to_path_buf();
+    let git_dir = repo.path().to_path_buf();
+    if let Some(state) = transaction::load(&git_dir)? {
+        return Ok(Round::Skipped(format!(
+            "a `loom {}` is paused",
+            state.command
+        )));
+    }
+    if git::rebase_is_in_progress(&git_dir) {
+        return Ok(Round::Skipped("a rebase is in progress".to_string()));
+    }
+    let Ok(_lock) = lock::acquire_repo(&repo, "update --daemon") else {
+        return Ok(Round::Skipped(
+            "another loom command is running".to_string(),
+        ));
+    };
+    let (branch_name, tracked_name, upstream_name) = tracking(&repo)?;
+    let mut status_opts = git2::StatusOptions::new();
+    status_opts.include_untracked(false);
+    if !repo.statuses(Some(&mut status_opts))?.is_empty() {
+        return Ok(Round::Skipped(
+            "the working tree has local changes".to_string(),
+        ));
+    }
+
+    fetch(&repo, &workdir, &branch_name, &tracked_name, &upstream_name)?;
+    let repo = git2::Repository::discover(&workdir)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let upstream = repo.revparse_single(&upstream_name)?.peel_to_commit()?;
+    let base = repo.merge_base(head.id(), upstream.id())?;
+    if base == upstream.id() {
+        return Ok(Round::UpToDate);
+    }
+    let (commits, _) = repo.graph_ahead_behind(upstream.id(), base)?;
+
+    // Merging the new upstream into HEAD conflicts where replaying the weave
+    // onto it would, without touching the working tree
+    let index = repo.merge_commits(&head, &upstream, None)?;
+    if index.has_conflicts() {
+        let files: BTreeSet<String> = index
+            .conflicts()?
+            .filter_map(|c| {
+                let c = c.ok()?;
+                let entry = c.our.or(c.their).or(c.ancestor)?;
+                Some(String::from_utf8_lossy(&entry.path).into_owned())
+            })
+            .collect();
+        return Ok(Round::Conflicts(files.into_iter().collect()));
+    }
+
+    let ctx = UpdateContext {
+        branch_name,
+        upstream_name,
+        skip_confirm,
+        only: None,
+        no_prompt: true,
+    };
+    match rebase_and_finish(&workdir, &git_dir, ctx, None)? {
+        RebaseOutcome::Completed => Ok(Round::Updated { commits }),
+        RebaseOutcome::Conflicted => {
+            git::rebase_abort(&workdir)?;
+            transaction::delete(&git_dir)?;
+            Ok(Round::Aborted)
         }
     }
+}
 
+/// Append a timestamped line to `.git/loom/daemon.log`.
+fn log_round(git_dir: &Path, message: &str) -> Result<()> {
+    let path = git_dir.join("loom").join(DAEMON_LOG);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    writeln!(
+        file,
+        "{} {}",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        message.replace('\n', " — ")
+    )?;
     Ok(())
 }
 
+/// Pass `message` to `loom.notifyCommand`, if set. A failing notifier is
+/// reported but does not stop the daemon.
+///
+/// The message names upstream files, so it never goes through the shell
+/// parser: `sh` gets it as `$1`, and `cmd` reads it from `LOOM_MESSAGE` with
+/// delayed expansion, which happens after the command line is parsed.
+fn notify(repo: &git2::Repository, message: &str) {
+    let Some(command) = repo::notify_command(repo).filter(|_| !explain::in_sandbox()) else {
+        return;
+    };
+    let status = if cfg!(windows) {
+        std::process::Command::new("cmd")
+            .args(["/V:ON", "/C", &format!("{} \"!LOOM_MESSAGE!\"", command)])
+            .env("LOOM_MESSAGE", message)
+            .status()
+    } else {
+        std::process::Command::new("sh")
+            .args(["-c", &format!("{} \"$1\"", command), "loom", message])
+            .status()
+    };
+    if !status.is_ok_and(|s| s.success()) {
+        msg::warn(&format!("`loom.notifyCommand` failed: `{}`", command));
+    }
+}
+
+/// Parse a `--daemon` interval: a number followed by `s`, `m`, `h` or `d`
+/// (seconds when there is no unit).
+pub fn parse_interval(value: &str) -> std::result::Result<Duration, String> {
+    let value = value.trim();
+    let (number, unit) = value.split_at(
+        value
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(value.len()),
+    );
+    let seconds = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => 0,
+    };
+    match number.parse::<u64>() {
+        Ok(n) if n > 0 && seconds > 0 => Ok(Duration::from_secs(n * seconds)),
+        _ => Err(format!(
+            "invalid interval `{}` (expected e.g. 30s, 15m, 1h)",
+            value
+        )),
+    }
+}
+
+fn format_interval(interval: Duration) -> String {
+    let secs = interval.as_secs();
+    match secs {
+        _ if secs.is_multiple_of(24 * 60 * 60) => format!("{}d", secs / (24 * 60 * 60)),
+        _ if secs.is_multiple_of(60 * 60) => format!("{}h", secs / (60 * 60)),
+        _ if secs.is_multiple_of(60) => format!("{}m", secs / 60),
+        _ => format!("{}s", secs),
+    }
+}
+
+/// Resolve the `--only` branch. It must be woven, at the top of its stack.
+fn restack_target(repo: &git2::Repository, target: &str) -> Result<String> {
+    let name = repo::resolve_arg(repo, target, &[TargetKind::Branch])?.expect_branch()?;
+    let graph = Weave::from_repo(repo)?;
+    if !graph
+        .branch_sections
+        .iter()
+        .any(|s| s.branch_names.contains(&name))
+    {
+        if graph
+            .branch_sections
+            .iter()
+            .any(|s| s.commits.iter().any(|c| c.update_refs.contains(&name)))
+        {
+            bail!(
+                "Branch `{}` has branches stacked on it\nRestack the top branch of the stack instead",
+                name
+            );
+        }
+        bail!("Branch `{}` is not woven into the integration branch", name);
+    }
+    Ok(name)
+}
+
+/// Rebase the section of `name` onto `new_base`, leaving the rest of the weave
+/// on its base.
+fn restack_only(
+    workdir: &Path,
+    mut graph: Weave,
+    name: &str,
+    new_base: git2::Oid,
+) -> Result<RebaseOutcome> {
+    let section = graph
+        .branch_sections
+        .iter_mut()
+        .find(|s| s.branch_names.iter().any(|n| n == name))
+        .with_context(|| format!("Branch `{}` is not woven into the integration branch", name))?;
+    section.reset_target = new_base.to_string();
+    let todo = graph.checked_todo()?;
+    weave::run_rebase(workdir, Some(&graph.base_oid.to_string()), &todo)
+}
+
 /// Resume an `update` operation after a conflict has been resolved.
 pub fn after_continue(workdir: &Path, context: &serde_json::Value) -> Result<()> {
     let ctx: UpdateContext =
@@ -174,6 +560,27 @@ fn post_update(workdir: &Path, repo: &git2::Repository, ctx: &UpdateContext) ->
         }
     }
 
+    // Status and every rebase walk the history just fetched
+    ancestry::ensure_commit_graph(repo, workdir);
+
+    if let Some((name, base)) = &ctx.only {
+        repo::set_branch_base(repo, name, git2::Oid::from_str(base)?)?;
+        let commit = repo.find_commit(git2::Oid::from_str(base)?)?;
+        msg::success(&format!(
+            "Restacked `{}` onto `{}` ({} {})",
+            name,
+            ctx.upstream_name,
+            git::short_hash(base),
+            repo::commit_subject(&commit)
+        ));
+        msg::warn(&format!(
+            "The rest of the weave stays on its base\nRun `loom update` to bring every branch onto `{}`",
+            ctx.upstream_name
+        ));
+        return Ok(());
+    }
+    repo::clear_branch_bases(repo)?;
+
     // Show the latest upstream commit
     let upstream_info = repo
         .revparse_single(&ctx.upstream_name)
@@ -208,6 +615,9 @@ fn post_update(workdir: &Path, repo: &git2::Repository, ctx: &UpdateContext) ->
             warn_msg.push_str(name);
         }
         msg::warn(&warn_msg);
+        if ctx.no_prompt && !ctx.skip_confirm {
+            return Ok(());
+        }
         let confirmed = ctx.skip_confirm
             || repo::prune_gone_branches(repo)
             || msg::confirm(if gone.len() == 1 {
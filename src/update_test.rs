@@ -12,7 +12,7 @@ fn update_pulls_upstream_changes() {
     let before_oid = test_repo.head_oid();
     assert_ne!(before_oid, remote_oid);
 
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_ok(), "update failed: {:?}", result.err());
 
     // After update, integration should point at the remote commit
@@ -23,11 +23,60 @@ fn update_pulls_upstream_changes() {
     );
 }
 
+#[test]
+fn update_rebases_onto_base_remote() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit("Int", "int.txt");
+
+    // Fork workflow: origin is a fork that doesn't get the new commits,
+    // upstream is the original repository
+    let remote_path = test_repo.remote_path().unwrap();
+    let fork_path = remote_path.with_file_name("fork.git");
+    let workdir = test_repo.workdir();
+    crate::git::run_git(
+        &workdir,
+        &[
+            "clone",
+            "-q",
+            "--bare",
+            remote_path.to_str().unwrap(),
+            fork_path.to_str().unwrap(),
+        ],
+    )
+    .unwrap();
+    test_repo
+        .repo
+        .remote_set_url("origin", fork_path.to_str().unwrap())
+        .unwrap();
+    test_repo
+        .repo
+        .remote("upstream", remote_path.to_str().unwrap())
+        .unwrap();
+    test_repo.set_config("loom.baseRemote", "upstream");
+    let remote_oid = test_repo.add_remote_commits(&["Remote commit 1"]);
+
+    let result = test_repo.in_dir(|| super::run(false, None));
+    assert!(result.is_ok(), "update failed: {:?}", result.err());
+
+    let head = test_repo.head_commit();
+    assert_eq!(head.summary(), Some("Int"));
+    assert_eq!(head.parent_id(0).unwrap(), remote_oid);
+    // The integration branch still tracks origin
+    let integration = test_repo
+        .repo
+        .find_branch("integration", BranchType::Local)
+        .unwrap();
+    assert_eq!(
+        integration.upstream().unwrap().name().unwrap(),
+        Some("origin/main")
+    );
+}
+
 #[test]
 fn update_works_when_already_up_to_date() {
     let test_repo = TestRepo::new_with_remote();
 
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_ok(), "update failed: {:?}", result.err());
 }
 
@@ -37,7 +86,7 @@ fn update_fails_on_detached_head() {
     let oid = test_repo.head_oid();
     test_repo.set_detached_head(oid);
 
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(
@@ -52,7 +101,7 @@ fn update_fails_without_upstream() {
     let test_repo = TestRepo::new();
     // new() creates a repo without remote/upstream
 
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_err());
     let err = result.unwrap_err().to_string();
     assert!(
@@ -72,7 +121,7 @@ fn update_rebases_local_commits_on_top_of_upstream() {
     // Add commits to the remote
     test_repo.add_remote_commits(&["Remote commit"]);
 
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_ok(), "update failed: {:?}", result.err());
 
     // Local commit should still be on top
@@ -110,7 +159,7 @@ fn update_fetches_tags_from_remote() {
         "Tag should not exist locally before update"
     );
 
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_ok(), "update failed: {:?}", result.err());
 
     // Tag should now exist locally
@@ -165,7 +214,7 @@ fn update_prunes_deleted_remote_branches() {
         branch.delete().unwrap();
     }
 
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_ok(), "update failed: {:?}", result.err());
 
     // Remote-tracking branch should be pruned
@@ -207,7 +256,7 @@ fn update_preserves_merge_topology() {
     test_repo.add_remote_commits(&["Upstream change"]);
 
     // Run update
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_ok(), "update failed: {:?}", result.err());
 
     // After update, HEAD should still be a merge commit (topology preserved)
@@ -283,7 +332,7 @@ fn update_removes_gone_upstream_branches_via_config() {
     }
 
     // Run update WITHOUT --yes; the config setting should skip the prompt
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_ok(), "update failed: {:?}", result.err());
 
     assert!(
@@ -334,7 +383,7 @@ fn update_removes_branches_with_gone_upstream() {
     }
 
     // Run update with --yes to skip the interactive prompt
-    let result = test_repo.in_dir(|| super::run(true));
+    let result = test_repo.in_dir(|| super::run(true, None));
     assert!(result.is_ok(), "update failed: {:?}", result.err());
 
     // The local branch with gone upstream should be removed
@@ -351,7 +400,7 @@ fn update_keeps_branches_without_tracking_config() {
     // Create a local branch with no upstream tracking configured
     test_repo.create_branch("local-only");
 
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_ok(), "update failed: {:?}", result.err());
 
     // Branch without upstream config should not be touched
@@ -388,7 +437,7 @@ fn update_does_not_put_upstream_commits_in_feature_branch() {
     test_repo.add_remote_commits(&["Remote 1", "Remote 2", "Remote 3"]);
 
     // Run update
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_ok(), "update failed: {:?}", result.err());
 
     // Verify: HEAD is still a merge commit
@@ -480,7 +529,7 @@ fn update_with_multiple_woven_branches() {
     // Push upstream commits
     test_repo.add_remote_commits(&["Remote work"]);
 
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_ok(), "update failed: {:?}", result.err());
 
     // Verify merge topology is preserved (HEAD is a merge)
@@ -551,7 +600,7 @@ fn update_preserves_multi_commit_branch() {
     // Push upstream changes (no overlap with feature commits)
     test_repo.add_remote_commits(&["Upstream work"]);
 
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_ok(), "update failed: {:?}", result.err());
 
     // Verify merge topology
@@ -624,7 +673,7 @@ fn update_with_partially_cherry_picked_branch() {
     test_repo.cherry_pick_to_remote(f1_oid, "F1");
     test_repo.cherry_pick_to_remote(f2_oid, "F2");
 
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(result.is_ok(), "update failed: {:?}", result.err());
 
     let repo = &test_repo.repo;
@@ -664,6 +713,59 @@ fn update_with_partially_cherry_picked_branch() {
     );
 }
 
+/// A branch squash-merged upstream has no commit with a matching patch-id,
+/// but its combined changes are all in upstream: update unweaves it instead
+/// of replaying its first commit onto the squash, which would conflict.
+#[test]
+fn update_drops_squash_merged_branch() {
+    let test_repo = TestRepo::new_with_remote();
+
+    let merge_base_oid = test_repo.head_oid();
+    test_repo.create_branch_at_commit("feature-a", merge_base_oid);
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("F1", "feature.txt");
+    test_repo.commit("F2", "feature.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-a");
+
+    // Upstream squashes the branch: the merge's diff against its first
+    // parent is the branch's cumulative diff
+    test_repo.cherry_pick_to_remote(test_repo.head_oid(), "Feature A (#12)");
+
+    let result = test_repo.in_dir(|| super::run(false, None));
+    assert!(result.is_ok(), "update failed: {:?}", result.err());
+
+    assert!(
+        test_repo.is_on_branch(),
+        "update should not pause replaying the squashed commits"
+    );
+    let head = test_repo.head_commit();
+    assert_eq!(head.parent_count(), 1, "the branch should be unwoven");
+    assert_eq!(head.summary().unwrap(), "Feature A (#12)");
+}
+
+/// A branch with changes beyond the squashed upstream commit stays woven.
+#[test]
+fn update_keeps_branch_with_changes_beyond_squash() {
+    let test_repo = TestRepo::new_with_remote();
+
+    let merge_base_oid = test_repo.head_oid();
+    test_repo.create_branch_at_commit("feature-a", merge_base_oid);
+    test_repo.switch_branch("feature-a");
+    let f1_oid = test_repo.commit("F1", "f1.txt");
+    test_repo.commit("F2", "f2.txt");
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-a");
+
+    // Only part of the branch lands upstream, under another patch
+    test_repo.cherry_pick_to_remote(f1_oid, "F1 reworded");
+
+    let result = test_repo.in_dir(|| super::run(false, None));
+    assert!(result.is_ok(), "update failed: {:?}", result.err());
+
+    assert_eq!(test_repo.head_commit().parent_count(), 2);
+}
+
 /// When a feature branch's commit has been cherry-picked into upstream,
 /// the weave-based rebase should handle it gracefully (git detects the
 /// duplicate via patch-id and skips it).
@@ -689,7 +791,7 @@ fn update_handles_branch_cherry_picked_into_upstream() {
     test_repo.cherry_pick_to_remote(feature_oid, "Feature A work");
 
     // Run update
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(
         result.is_ok(),
         "update should succeed when branch is cherry-picked upstream: {:?}",
@@ -715,7 +817,7 @@ fn update_handles_partial_cherry_pick_to_upstream() {
     // Upstream cherry-picks only F1
     test_repo.cherry_pick_to_remote(f1_oid, "F1");
 
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(
         result.is_ok(),
         "update should succeed with partial cherry-pick: {:?}",
@@ -780,7 +882,7 @@ fn update_handles_fully_cherry_picked_branch() {
     test_repo.cherry_pick_to_remote(f1_oid, "F1");
     test_repo.cherry_pick_to_remote(f2_oid, "F2");
 
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(
         result.is_ok(),
         "update should succeed when all branch commits are cherry-picked: {:?}",
@@ -980,7 +1082,7 @@ fn update_handles_inverted_parent_merge_on_integration() {
     test_repo.add_remote_commits(&["C3"]);
 
     // Run update — should succeed and flatten (no conflicts)
-    let result = test_repo.in_dir(|| super::run(false));
+    let result = test_repo.in_dir(|| super::run(false, None));
     assert!(
         result.is_ok(),
         "update should handle inverted-parent merges: {:?}",
@@ -1002,3 +1104,218 @@ fn update_handles_inverted_parent_merge_on_integration() {
         "HEAD should be linear after update (redundant merge dropped)"
     );
 }
+
+/// Integration branch weaving `feature-a` and `feature-b`, with a loose
+/// commit on top, and one new commit upstream. Returns the old base and the
+/// new upstream commit.
+fn setup_restack() -> (TestRepo, git2::Oid, git2::Oid) {
+    let test_repo = TestRepo::new_with_remote();
+    let old_base = test_repo.find_remote_branch_target("origin/main");
+    for name in ["feature-a", "feature-b"] {
+        test_repo.create_branch_at(name, &old_base.to_string());
+        test_repo.switch_branch(name);
+        test_repo.commit(&format!("{} 1", name), &format!("{}.txt", name));
+        test_repo.switch_branch("integration");
+        test_repo.merge_no_ff(name);
+    }
+    test_repo.commit("Loose", "loose.txt");
+    let remote_oid = test_repo.add_remote_commits(&["Remote commit 1"]);
+    (test_repo, old_base, remote_oid)
+}
+
+fn first_commit_parent(test_repo: &TestRepo, branch: &str) -> git2::Oid {
+    test_repo
+        .find_commit(test_repo.get_branch_target(branch))
+        .parent_id(0)
+        .unwrap()
+}
+
+#[test]
+fn update_only_restacks_one_branch() {
+    let (test_repo, old_base, remote_oid) = setup_restack();
+
+    test_repo
+        .in_dir(|| super::run(false, Some("feature-a".to_string())))
+        .unwrap();
+
+    assert_eq!(first_commit_parent(&test_repo, "feature-a"), remote_oid);
+    assert_eq!(first_commit_parent(&test_repo, "feature-b"), old_base);
+    assert_eq!(
+        test_repo
+            .repo
+            .refname_to_id("refs/loom/base/feature-a")
+            .unwrap(),
+        remote_oid
+    );
+
+    // The weave keeps its base, and the new upstream commit is not part of it
+    let info = crate::core::repo::gather_repo_info(&test_repo.repo, false, 1).unwrap();
+    assert_eq!(info.upstream.merge_base_oid, old_base);
+    let messages: Vec<&str> = info.commits.iter().map(|c| c.message.as_str()).collect();
+    assert_eq!(messages, vec!["Loose", "feature-b 1", "feature-a 1"]);
+    let graph = crate::core::weave::Weave::from_repo(&test_repo.repo).unwrap();
+    assert_eq!(
+        graph.branch_sections[0].reset_target,
+        remote_oid.to_string()
+    );
+    assert_eq!(graph.branch_sections[0].commits.len(), 1);
+    assert_eq!(graph.branch_sections[1].reset_target, "onto");
+}
+
+#[test]
+fn restacked_branch_keeps_its_base_through_rewrites() {
+    let (test_repo, _, remote_oid) = setup_restack();
+    test_repo
+        .in_dir(|| super::run(false, Some("feature-a".to_string())))
+        .unwrap();
+
+    let mut graph = crate::core::weave::Weave::from_repo(&test_repo.repo).unwrap();
+    let loose = test_repo.head_oid();
+    graph.drop_commit(loose);
+    crate::core::weave::run_rebase_or_abort(
+        &test_repo.workdir(),
+        Some(&graph.base_oid.to_string()),
+        &graph.checked_todo().unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(first_commit_parent(&test_repo, "feature-a"), remote_oid);
+    assert!(!test_repo.workdir().join("loose.txt").exists());
+}
+
+#[test]
+fn full_update_after_only_rebases_everything() {
+    let (test_repo, _, remote_oid) = setup_restack();
+    test_repo
+        .in_dir(|| super::run(false, Some("feature-a".to_string())))
+        .unwrap();
+
+    test_repo.in_dir(|| super::run(false, None)).unwrap();
+
+    assert_eq!(first_commit_parent(&test_repo, "feature-a"), remote_oid);
+    assert_eq!(first_commit_parent(&test_repo, "feature-b"), remote_oid);
+    assert!(
+        test_repo
+            .repo
+            .find_reference("refs/loom/base/feature-a")
+            .is_err()
+    );
+    let info = crate::core::repo::gather_repo_info(&test_repo.repo, false, 1).unwrap();
+    assert_eq!(info.upstream.merge_base_oid, remote_oid);
+}
+
+#[test]
+fn update_only_refuses_unwoven_branch() {
+    let (test_repo, old_base, _) = setup_restack();
+    test_repo.create_branch_at("loner", &old_base.to_string());
+    let head = test_repo.head_oid();
+
+    let err = test_repo
+        .in_dir(|| super::run(false, Some("loner".to_string())))
+        .unwrap_err();
+
+    assert!(err.to_string().contains("not woven"), "{}", err);
+    assert_eq!(test_repo.head_oid(), head);
+}
+
+#[test]
+fn parse_interval_units() {
+    use std::time::Duration;
+    assert_eq!(super::parse_interval("45"), Ok(Duration::from_secs(45)));
+    assert_eq!(super::parse_interval("30s"), Ok(Duration::from_secs(30)));
+    assert_eq!(super::parse_interval("15m"), Ok(Duration::from_secs(900)));
+    assert_eq!(super::parse_interval("2h"), Ok(Duration::from_secs(7200)));
+    assert_eq!(super::parse_interval("1d"), Ok(Duration::from_secs(86400)));
+    assert!(super::parse_interval("0m").is_err());
+    assert!(super::parse_interval("5 minutes").is_err());
+    assert!(super::parse_interval("m").is_err());
+}
+
+#[test]
+fn daemon_round_updates_when_safe() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit("Int", "int.txt");
+    let remote_oid = test_repo.add_remote_commits(&["Remote 1", "Remote 2"]);
+
+    let round = test_repo.in_dir(|| super::daemon_round(false)).unwrap();
+
+    assert_eq!(round, super::Round::Updated { commits: 2 });
+    assert_eq!(test_repo.head_commit().parent_id(0).unwrap(), remote_oid);
+
+    let round = test_repo.in_dir(|| super::daemon_round(false)).unwrap();
+    assert_eq!(round, super::Round::UpToDate);
+}
+
+#[test]
+fn daemon_round_skips_dirty_tree() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit("Int", "int.txt");
+    test_repo.add_remote_commits(&["Remote 1"]);
+    test_repo.write_file("int.txt", "edited");
+    let head = test_repo.head_oid();
+
+    let round = test_repo.in_dir(|| super::daemon_round(false)).unwrap();
+
+    assert!(matches!(round, super::Round::Skipped(_)), "{:?}", round);
+    assert_eq!(test_repo.head_oid(), head);
+}
+
+#[test]
+fn daemon_round_refuses_predicted_conflicts() {
+    let test_repo = TestRepo::new_with_remote();
+    test_repo.commit("Local edit", "shared.txt");
+    let local = test_repo.head_oid();
+    // Same file, different content upstream
+    let remote_path = test_repo.remote_path().unwrap();
+    let remote = Repository::open_bare(&remote_path).unwrap();
+    let parent = remote
+        .find_branch("main", BranchType::Local)
+        .unwrap()
+        .get()
+        .peel_to_commit()
+        .unwrap();
+    let blob = remote.blob(b"upstream content").unwrap();
+    let mut tree = remote.treebuilder(Some(&parent.tree().unwrap())).unwrap();
+    tree.insert("shared.txt", blob, 0o100644).unwrap();
+    let tree = remote.find_tree(tree.write().unwrap()).unwrap();
+    let sig = Signature::now("Test", "test@example.com").unwrap();
+    remote
+        .commit(
+            Some("refs/heads/main"),
+            &sig,
+            &sig,
+            "Upstream rewrite",
+            &tree,
+            &[&parent],
+        )
+        .unwrap();
+
+    let round = test_repo.in_dir(|| super::daemon_round(false)).unwrap();
+
+    assert_eq!(
+        round,
+        super::Round::Conflicts(vec!["shared.txt".to_string()])
+    );
+    assert_eq!(test_repo.head_oid(), local);
+    assert!(!crate::git::rebase_is_in_progress(test_repo.repo.path()));
+}
+
+#[test]
+fn notify_passes_the_message_without_shell_parsing() {
+    let test_repo = TestRepo::new_with_remote();
+    let out = test_repo.workdir().join("notified.txt");
+    let pwned = test_repo.workdir().join("pwned");
+    test_repo.set_config(
+        "loom.notifyCommand",
+        &format!("printf '%s\\n' >> '{}'", out.display()),
+    );
+    let message = format!("Conflicts in `$(touch {0})`; touch {0}", pwned.display());
+
+    super::notify(&test_repo.repo, &message);
+
+    assert_eq!(
+        std::fs::read_to_string(&out).unwrap(),
+        format!("{}\n", message)
+    );
+    assert!(!pwned.exists());
+}
@@ -0,0 +1,132 @@
+use anyhow::{Result, bail};
+use colored::Colorize;
+use git2::Repository;
+
+use crate::core::repo::{self, TargetKind};
+use crate::core::weave::Weave;
+
+/// Commit and line counts for one author of a branch.
+#[derive(Debug, PartialEq, Eq)]
+pub struct AuthorStats {
+    pub name: String,
+    pub email: String,
+    pub commits: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// Summarize who wrote the commits of a woven branch.
+///
+/// Lists each author with their commit count and the lines they added and
+/// removed, most active first. Useful before picking reviewers.
+pub fn run(branch: String) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let name = repo::resolve_arg(&repo, &branch, &[TargetKind::Branch])?.expect_branch()?;
+    let authors = summarize(&repo, &name)?;
+
+    let commits: usize = authors.iter().map(|a| a.commits).sum();
+    println!(
+        "{} {} {}",
+        "Authors of".bold(),
+        format!("`{}`", name).yellow(),
+        format!("({} {})", commits, plural(commits, "commit")).dimmed()
+    );
+
+    let width = authors
+        .iter()
+        .map(|a| author_label(a).chars().count())
+        .max()
+        .unwrap_or(0);
+    for author in &authors {
+        println!(
+            "  {:<width$}  {:>4} {:<7}  {} {}",
+            author_label(author),
+            author.commits,
+            plural(author.commits, "commit"),
+            format!("+{}", author.insertions).green(),
+            format!("-{}", author.deletions).red(),
+            width = width
+        );
+    }
+    Ok(())
+}
+
+/// Collect per-author statistics for the commits in `branch`'s section of the
+/// weave. Authors are grouped by email (case-insensitive) and sorted by
+/// commit count, then by lines changed.
+pub fn summarize(repo: &Repository, branch: &str) -> Result<Vec<AuthorStats>> {
+    let graph = Weave::from_repo(repo)?;
+    if repo.revparse_single(branch)?.peel_to_commit()?.id() == graph.base_oid {
+        bail!("Branch `{}` has no commits yet", branch);
+    }
+    let Some(section) = graph
+        .branch_sections
+        .iter()
+        .find(|s| s.branch_names.iter().any(|n| n == branch))
+    else {
+        bail!(
+            "Branch `{}` is not woven into the integration branch\n\
+             Run `loom branch merge {}` to weave it",
+            branch,
+            branch
+        );
+    };
+
+    let mut authors: Vec<AuthorStats> = Vec::new();
+    for entry in &section.commits {
+        let commit = repo.find_commit(entry.oid)?;
+        let author = commit.author();
+        let name = author.name().unwrap_or("").to_string();
+        let email = author.email().unwrap_or("").to_string();
+
+        let tree = commit.tree()?;
+        let parent_tree = commit.parent(0).ok().map(|p| p.tree()).transpose()?;
+        let stats = repo
+            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?
+            .stats()?;
+
+        let stats_entry = match authors
+            .iter()
+            .position(|a| a.email.eq_ignore_ascii_case(&email))
+        {
+            Some(idx) => &mut authors[idx],
+            None => {
+                authors.push(AuthorStats {
+                    name,
+                    email,
+                    commits: 0,
+                    insertions: 0,
+                    deletions: 0,
+                });
+                authors.last_mut().unwrap()
+            }
+        };
+        stats_entry.commits += 1;
+        stats_entry.insertions += stats.insertions();
+        stats_entry.deletions += stats.deletions();
+    }
+
+    authors.sort_by(|a, b| {
+        b.commits
+            .cmp(&a.commits)
+            .then((b.insertions + b.deletions).cmp(&(a.insertions + a.deletions)))
+            .then(a.name.cmp(&b.name))
+    });
+    Ok(authors)
+}
+
+fn author_label(author: &AuthorStats) -> String {
+    format!("{} <{}>", author.name, author.email)
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        word.to_string()
+    } else {
+        format!("{}s", word)
+    }
+}
+
+#[cfg(test)]
+#[path = "who_test.rs"]
+mod tests;
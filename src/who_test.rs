@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use crate::core::test_helpers::TestRepo;
+
+/// Commit `lines` lines to `filename` on HEAD, authored by `name <email>`.
+fn commit_as(test_repo: &TestRepo, name: &str, email: &str, filename: &str, lines: usize) {
+    let content: String = (0..lines).map(|i| format!("line {}\n", i)).collect();
+    test_repo.write_file(filename, &content);
+
+    let repo = &test_repo.repo;
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(filename)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = git2::Signature::now(name, email).unwrap();
+    let parent = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.commit(Some("HEAD"), &sig, &sig, filename, &tree, &[&parent])
+        .unwrap();
+}
+
+/// Woven `feature-a` with commits from two authors (Ada twice, Bob once).
+fn setup_shared_branch() -> TestRepo {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    commit_as(&test_repo, "Ada", "ada@example.com", "a1.txt", 3);
+    commit_as(&test_repo, "Bob", "bob@example.com", "b1.txt", 10);
+    commit_as(&test_repo, "Ada L.", "ADA@example.com", "a2.txt", 2);
+    test_repo.switch_branch("integration");
+    test_repo.commit("Int", "int.txt");
+    test_repo.merge_no_ff("feature-a");
+    test_repo
+}
+
+#[test]
+fn summarize_groups_by_email_and_sorts_by_commits() {
+    let test_repo = setup_shared_branch();
+
+    let authors = super::summarize(&test_repo.repo, "feature-a").unwrap();
+
+    assert_eq!(
+        authors,
+        vec![
+            super::AuthorStats {
+                name: "Ada".to_string(),
+                email: "ada@example.com".to_string(),
+                commits: 2,
+                insertions: 5,
+                deletions: 0,
+            },
+            super::AuthorStats {
+                name: "Bob".to_string(),
+                email: "bob@example.com".to_string(),
+                commits: 1,
+                insertions: 10,
+                deletions: 0,
+            },
+        ]
+    );
+}
+
+#[test]
+fn summarize_ignores_other_branches_and_loose_commits() {
+    let test_repo = setup_shared_branch();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-b", &base_oid.to_string());
+    test_repo.switch_branch("feature-b");
+    commit_as(&test_repo, "Cy", "cy@example.com", "c1.txt", 1);
+    test_repo.switch_branch("integration");
+    test_repo.merge_no_ff("feature-b");
+
+    let authors = super::summarize(&test_repo.repo, "feature-b").unwrap();
+    assert_eq!(authors.len(), 1);
+    assert_eq!(authors[0].email, "cy@example.com");
+}
+
+#[test]
+fn summarize_empty_branch_fails() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+
+    let err = super::summarize(&test_repo.repo, "feature-a").unwrap_err();
+    assert!(err.to_string().contains("has no commits"));
+}
+
+#[test]
+fn summarize_non_woven_branch_fails() {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a1.txt");
+    test_repo.switch_branch("integration");
+
+    let err = super::summarize(&test_repo.repo, "feature-a").unwrap_err();
+    assert!(err.to_string().contains("not woven"));
+}
@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+use crate::core::msg;
+use crate::core::repo::{self, TargetKind};
+use crate::git;
+
+/// A worktree created by `loom worktree add`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Registered {
+    pub branch: String,
+    pub path: PathBuf,
+}
+
+/// Create (or refresh) a worktree with `branch` checked out, for building
+/// and testing it on its own.
+///
+/// The worktree is detached at the branch tip: git refuses to rebase a branch
+/// checked out in another worktree, and loom rewrites woven branches all the
+/// time. Running `add` again moves a clean worktree to the current tip.
+pub fn run_add(branch: String, path: Option<String>) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let name = repo::resolve_arg(&repo, &branch, &[TargetKind::Branch])?.expect_branch()?;
+    let (path, created) = add(&repo, &name, path.map(PathBuf::from))?;
+    let shown = path.display().to_string().replace('\\', "/");
+    if created {
+        msg::success(&format!(
+            "Created worktree for `{}` at `{}`\nRun `cd \"$(git loom worktree path {})\"` to enter it",
+            name, shown, name
+        ));
+    } else {
+        msg::success(&format!(
+            "Moved worktree `{}` to the tip of `{}`",
+            shown, name
+        ));
+    }
+    Ok(())
+}
+
+/// Print the path of a branch's worktree, for `cd "$(loom worktree path fa)"`.
+pub fn run_path(branch: String) -> Result<()> {
+    let repo = repo::open_repo()?;
+    let name = repo::resolve_arg(&repo, &branch, &[TargetKind::Branch])?.expect_branch()?;
+    let entry = find(&repo, &name).with_context(|| {
+        format!(
+            "Branch `{}` has no worktree\nRun `loom worktree add {}` to create one",
+            name, name
+        )
+    })?;
+    println!("{}", entry.path.display());
+    Ok(())
+}
+
+/// Remove the worktrees of deleted branches and forget the missing ones.
+pub fn run_prune() -> Result<()> {
+    let repo = repo::open_repo()?;
+    let removed = prune(&repo)?;
+    if removed.is_empty() {
+        msg::success("No worktrees to prune");
+    }
+    for entry in removed {
+        msg::success(&format!(
+            "Removed worktree of `{}` at `{}`",
+            entry.branch,
+            entry.path.display().to_string().replace('\\', "/")
+        ));
+    }
+    Ok(())
+}
+
+/// Create the worktree of `branch` at `path` (default: next to the main
+/// working tree, named `<repo>.<branch>`), or move its existing worktree to
+/// the branch tip. Returns the path and whether it was created.
+pub fn add(repo: &Repository, branch: &str, path: Option<PathBuf>) -> Result<(PathBuf, bool)> {
+    let workdir = repo::require_workdir(repo, "create a worktree")?.to_path_buf();
+    let tip = repo
+        .find_branch(branch, git2::BranchType::Local)?
+        .get()
+        .peel_to_commit()?
+        .id()
+        .to_string();
+
+    if let Some(entry) = find(repo, branch)
+        && entry.path.exists()
+    {
+        if path.is_some_and(|p| p != entry.path) {
+            bail!(
+                "Branch `{}` already has a worktree at `{}`",
+                branch,
+                entry.path.display()
+            );
+        }
+        if !git::run_git_stdout(&entry.path, &["status", "--porcelain"])?
+            .trim()
+            .is_empty()
+        {
+            bail!(
+                "The worktree of `{}` has uncommitted changes\nCommit or discard them in `{}` first",
+                branch,
+                entry.path.display()
+            );
+        }
+        git::run_git(&entry.path, &["checkout", "--quiet", "--detach", &tip])?;
+        return Ok((entry.path, false));
+    }
+
+    let path = match path {
+        Some(path) => path,
+        None => default_path(&workdir, branch)?,
+    };
+    if path.exists() {
+        bail!("`{}` already exists", path.display());
+    }
+    let path_str = path.to_string_lossy().to_string();
+    git::run_git(&workdir, &["worktree", "add", "--detach", &path_str, &tip])?;
+    let path = path.canonicalize().unwrap_or(path);
+
+    let mut entries = load(repo);
+    entries.retain(|e| e.branch != branch);
+    entries.push(Registered {
+        branch: branch.to_string(),
+        path: path.clone(),
+    });
+    save(repo, &entries)?;
+    Ok((path, true))
+}
+
+/// Remove the worktrees whose branch is gone, keeping any with uncommitted
+/// changes, and forget the ones whose directory was deleted. Returns the
+/// removed worktrees.
+pub fn prune(repo: &Repository) -> Result<Vec<Registered>> {
+    let workdir = repo::require_workdir(repo, "prune worktrees")?.to_path_buf();
+    let mut kept = Vec::new();
+    let mut removed = Vec::new();
+    for entry in load(repo) {
+        if !entry.path.exists() {
+            continue;
+        }
+        if repo
+            .find_branch(&entry.branch, git2::BranchType::Local)
+            .is_ok()
+        {
+            kept.push(entry);
+            continue;
+        }
+        let dirty = !git::run_git_stdout(&entry.path, &["status", "--porcelain"])?
+            .trim()
+            .is_empty();
+        if dirty {
+            msg::warn(&format!(
+                "Kept the worktree of deleted branch `{}`: it has uncommitted changes\n`{}`",
+                entry.branch,
+                entry.path.display()
+            ));
+            kept.push(entry);
+            continue;
+        }
+        let path = entry.path.to_string_lossy().to_string();
+        git::run_git(&workdir, &["worktree", "remove", &path])?;
+        removed.push(entry);
+    }
+    git::run_git(&workdir, &["worktree", "prune"])?;
+    save(repo, &kept)?;
+    Ok(removed)
+}
+
+/// Branch name → whether its worktree is at the branch tip, for every
+/// registered worktree that still exists.
+pub fn status_map(repo: &Repository) -> HashMap<String, bool> {
+    load(repo)
+        .into_iter()
+        .filter_map(|entry| {
+            let tip = repo
+                .find_branch(&entry.branch, git2::BranchType::Local)
+                .ok()?
+                .get()
+                .target()?;
+            let head = Repository::open(&entry.path).ok()?.head().ok()?.target()?;
+            Some((entry.branch, head == tip))
+        })
+        .collect()
+}
+
+/// Keep the registry in sync when a branch is renamed.
+pub fn rename_branch(repo: &Repository, old_name: &str, new_name: &str) -> Result<()> {
+    let mut entries = load(repo);
+    if !entries.iter().any(|e| e.branch == old_name) {
+        return Ok(());
+    }
+    for entry in &mut entries {
+        if entry.branch == old_name {
+            entry.branch = new_name.to_string();
+        }
+    }
+    save(repo, &entries)
+}
+
+/// The registered worktree of `branch`, if any.
+pub fn find(repo: &Repository, branch: &str) -> Option<Registered> {
+    load(repo).into_iter().find(|e| e.branch == branch)
+}
+
+/// `<parent of workdir>/<workdir name>.<branch>`, with `/` in the branch
+/// name replaced by `-`.
+fn default_path(workdir: &Path, branch: &str) -> Result<PathBuf> {
+    let workdir = workdir.canonicalize()?;
+    let name = workdir
+        .file_name()
+        .context("Cannot name a worktree next to the repository root")?
+        .to_string_lossy();
+    let parent = workdir
+        .parent()
+        .context("Cannot create a worktree next to the repository root")?;
+    Ok(parent.join(format!("{}.{}", name, branch.replace('/', "-"))))
+}
+
+/// The registry lives in the common git dir, so linked worktrees share it.
+fn registry_path(repo: &Repository) -> PathBuf {
+    repo.commondir().join("loom").join("worktrees.json")
+}
+
+fn load(repo: &Repository) -> Vec<Registered> {
+    std::fs::read_to_string(registry_path(repo))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save(repo: &Repository, entries: &[Registered]) -> Result<()> {
+    let path = registry_path(repo);
+    if entries.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(&path)
+                .with_context(|| format!("Failed to delete '{}'", path.display()))?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(entries)?)
+        .with_context(|| format!("Failed to write '{}'", path.display()))
+}
+
+#[cfg(test)]
+#[path = "worktree_test.rs"]
+mod tests;
@@ -0,0 +1,136 @@
+use std::path::Path;
+
+use super::{add, default_path, find, prune, rename_branch, status_map};
+use crate::core::test_helpers::TestRepo;
+
+/// Integration branch with `feature-a` woven, plus a scratch directory to
+/// hold worktrees.
+fn setup() -> (TestRepo, tempfile::TempDir) {
+    let test_repo = TestRepo::new_with_remote();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-a", &base_oid.to_string());
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A1", "a.txt");
+    test_repo.switch_branch("integration");
+    test_repo.commit("Int", "int.txt");
+    test_repo.merge_no_ff("feature-a");
+    (test_repo, tempfile::TempDir::new().unwrap())
+}
+
+fn head_of(path: &Path) -> git2::Oid {
+    git2::Repository::open(path)
+        .unwrap()
+        .head()
+        .unwrap()
+        .target()
+        .unwrap()
+}
+
+#[test]
+fn add_creates_detached_worktree_at_branch_tip() {
+    let (test_repo, dir) = setup();
+    let path = dir.path().join("wt-a");
+
+    let (created_path, created) = add(&test_repo.repo, "feature-a", Some(path.clone())).unwrap();
+
+    assert!(created);
+    assert!(created_path.join("a.txt").exists());
+    let tip = test_repo.get_branch_target("feature-a");
+    assert_eq!(head_of(&created_path), tip);
+    let wt = git2::Repository::open(&created_path).unwrap();
+    assert!(wt.head_detached().unwrap());
+    assert_eq!(
+        find(&test_repo.repo, "feature-a").unwrap().path,
+        created_path
+    );
+    assert_eq!(
+        status_map(&test_repo.repo).get("feature-a").copied(),
+        Some(true)
+    );
+}
+
+#[test]
+fn add_again_moves_worktree_to_new_tip() {
+    let (test_repo, dir) = setup();
+    let (path, _) = add(&test_repo.repo, "feature-a", Some(dir.path().join("wt-a"))).unwrap();
+
+    test_repo.switch_branch("feature-a");
+    test_repo.commit("A2", "a2.txt");
+    test_repo.switch_branch("integration");
+    assert_eq!(
+        status_map(&test_repo.repo).get("feature-a").copied(),
+        Some(false)
+    );
+
+    let (same_path, created) = add(&test_repo.repo, "feature-a", None).unwrap();
+    assert!(!created);
+    assert_eq!(same_path, path);
+    assert_eq!(head_of(&path), test_repo.get_branch_target("feature-a"));
+}
+
+#[test]
+fn add_again_refuses_dirty_worktree() {
+    let (test_repo, dir) = setup();
+    let (path, _) = add(&test_repo.repo, "feature-a", Some(dir.path().join("wt-a"))).unwrap();
+    std::fs::write(path.join("a.txt"), "edited").unwrap();
+
+    let err = add(&test_repo.repo, "feature-a", None).unwrap_err();
+    assert!(err.to_string().contains("uncommitted changes"), "{}", err);
+}
+
+#[test]
+fn prune_removes_worktrees_of_deleted_branches_unless_dirty() {
+    let (test_repo, dir) = setup();
+    let base_oid = test_repo.find_remote_branch_target("origin/main");
+    test_repo.create_branch_at("feature-b", &base_oid.to_string());
+    let (path_a, _) = add(&test_repo.repo, "feature-a", Some(dir.path().join("wt-a"))).unwrap();
+    let (path_b, _) = add(&test_repo.repo, "feature-b", Some(dir.path().join("wt-b"))).unwrap();
+    std::fs::write(path_b.join("notes.txt"), "keep me").unwrap();
+
+    test_repo.delete_branch("feature-a");
+    test_repo.delete_branch("feature-b");
+
+    let removed = prune(&test_repo.repo).unwrap();
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].branch, "feature-a");
+    assert!(!path_a.exists());
+    assert!(path_b.join("notes.txt").exists());
+    assert!(find(&test_repo.repo, "feature-b").is_some());
+}
+
+#[test]
+fn prune_forgets_deleted_directories() {
+    let (test_repo, dir) = setup();
+    let (path, _) = add(&test_repo.repo, "feature-a", Some(dir.path().join("wt-a"))).unwrap();
+    std::fs::remove_dir_all(&path).unwrap();
+
+    assert!(prune(&test_repo.repo).unwrap().is_empty());
+    assert!(find(&test_repo.repo, "feature-a").is_none());
+    assert!(status_map(&test_repo.repo).is_empty());
+}
+
+#[test]
+fn rename_follows_the_branch() {
+    let (test_repo, dir) = setup();
+    add(&test_repo.repo, "feature-a", Some(dir.path().join("wt-a"))).unwrap();
+
+    rename_branch(&test_repo.repo, "feature-a", "feature-x").unwrap();
+
+    assert!(find(&test_repo.repo, "feature-a").is_none());
+    assert!(find(&test_repo.repo, "feature-x").is_some());
+}
+
+#[test]
+fn default_path_sits_next_to_the_repository() {
+    let (test_repo, _dir) = setup();
+    let workdir = test_repo.workdir().canonicalize().unwrap();
+
+    let path = default_path(&workdir, "user/feature-a").unwrap();
+
+    assert_eq!(path.parent(), workdir.parent());
+    let name = workdir.file_name().unwrap().to_string_lossy();
+    assert_eq!(
+        path.file_name().unwrap().to_string_lossy(),
+        format!("{}.user-feature-a", name)
+    );
+}